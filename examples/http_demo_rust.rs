@@ -117,7 +117,10 @@ fn demo_http_post_json() {
     let json_body = r#"{"title": "Hello from Hype-RS", "body": "Test post", "userId": 1}"#;
 
     let mut headers = HashMap::new();
-    headers.insert("Content-Type".to_string(), "application/json".to_string());
+    headers.insert(
+        "Content-Type".to_string(),
+        Some("application/json".to_string()),
+    );
 
     println!("Creating new post on JSONPlaceholder...");
 
@@ -162,9 +165,12 @@ fn demo_http_fetch() {
     let mut headers = HashMap::new();
     headers.insert(
         "Accept".to_string(),
-        "application/vnd.github.v3+json".to_string(),
+        Some("application/vnd.github.v3+json".to_string()),
+    );
+    headers.insert(
+        "User-Agent".to_string(),
+        Some("Hype-RS-HTTP-Client".to_string()),
     );
-    headers.insert("User-Agent".to_string(), "Hype-RS-HTTP-Client".to_string());
 
     println!("Fetching Rust repository info...");
 
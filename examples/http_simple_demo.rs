@@ -38,7 +38,10 @@ fn demo_working_requests() {
 
     use std::collections::HashMap;
     let mut headers = HashMap::new();
-    headers.insert("Content-Type".to_string(), "application/json".to_string());
+    headers.insert(
+        "Content-Type".to_string(),
+        Some("application/json".to_string()),
+    );
 
     let body = r#"{"message": "Hello from Hype-RS!", "version": "0.1.0"}"#;
 
@@ -62,7 +65,7 @@ fn demo_working_requests() {
     println!("\n🎯 Example 3: Universal Fetch API\n");
 
     let mut fetch_headers = HashMap::new();
-    fetch_headers.insert("User-Agent".to_string(), "Hype-RS/0.1.0".to_string());
+    fetch_headers.insert("User-Agent".to_string(), Some("Hype-RS/0.1.0".to_string()));
 
     match client.fetch(
         "GET",
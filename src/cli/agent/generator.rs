@@ -51,7 +51,8 @@ pub fn generate_agent_docs() -> Result<String, Box<dyn std::error::Error>> {
             security: SecurityConstraints {
                 sandboxing: true,
                 restricted_operations: vec![
-                    "No raw OS command execution".to_string(),
+                    "No OS command execution unless run with --allow-subprocess (process.exec/spawn)"
+                        .to_string(),
                     "Limited filesystem access outside working directory".to_string(),
                     "No direct memory manipulation".to_string(),
                 ],
@@ -16,6 +16,12 @@ pub struct ArgumentParser {
     pub allow_flags: bool,
     pub flag_prefixes: Vec<String>,
     pub key_value_separators: Vec<String>,
+    /// Let `--key value` take the following argument as the value, not only
+    /// `--key=value`.
+    pub separate_values: bool,
+    /// Flags that never take a separate value, so `--verbose input.txt`
+    /// leaves `input.txt` positional.
+    pub boolean_flags: Vec<String>,
 }
 
 impl Default for ArgumentParser {
@@ -25,6 +31,8 @@ impl Default for ArgumentParser {
             allow_flags: true,
             flag_prefixes: vec!["--".to_string(), "-".to_string()],
             key_value_separators: vec!["=".to_string(), ":".to_string()],
+            separate_values: false,
+            boolean_flags: Vec::new(),
         }
     }
 }
@@ -44,9 +52,16 @@ impl ArgumentParser {
         let mut named_args = HashMap::new();
         let mut flags = HashMap::new();
 
-        for arg in raw_args {
+        let mut args = raw_args.iter().peekable();
+        while let Some(arg) = args.next() {
             if self.is_flag(arg) {
                 if let Some((flag_name, flag_value)) = self.parse_flag(arg) {
+                    let flag_value = flag_value.or_else(|| {
+                        if !self.takes_separate_value(&flag_name) {
+                            return None;
+                        }
+                        args.next_if(|next| !self.is_flag(next)).cloned()
+                    });
                     if let Some(value) = flag_value {
                         named_args.insert(flag_name, value);
                     } else {
@@ -67,6 +82,18 @@ impl ArgumentParser {
         }
     }
 
+    /// Accept `--key value` in addition to `--key=value`, except for the
+    /// listed flags.
+    pub fn with_separate_values(mut self, boolean_flags: Vec<String>) -> Self {
+        self.separate_values = true;
+        self.boolean_flags = boolean_flags;
+        self
+    }
+
+    fn takes_separate_value(&self, flag_name: &str) -> bool {
+        self.separate_values && !self.boolean_flags.iter().any(|flag| flag == flag_name)
+    }
+
     fn is_flag(&self, arg: &str) -> bool {
         self.flag_prefixes
             .iter()
@@ -196,6 +223,18 @@ pub fn create_standard_arg_table(
     Ok(())
 }
 
+/// Set `_opts` to the named arguments (strings) and flags (`true`).
+pub fn create_opts_table(lua: &mlua::Lua, parsed_args: &ParsedArguments) -> mlua::Result<()> {
+    let opts = lua.create_table()?;
+    for flag in parsed_args.flags.keys() {
+        opts.set(flag.as_str(), true)?;
+    }
+    for (key, value) in &parsed_args.named_args {
+        opts.set(key.as_str(), value.as_str())?;
+    }
+    lua.globals().set("_opts", opts)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -287,4 +326,31 @@ mod tests {
         assert!(parsed.flags.get("v").unwrap());
         assert_eq!(parsed.named_args.get("f"), Some(&"file.txt".to_string()));
     }
+
+    #[test]
+    fn test_separate_values() {
+        let parser = ArgumentParser::new().with_separate_values(vec!["verbose".to_string()]);
+        let script_path = PathBuf::from("test.lua");
+        let args = [
+            "--name",
+            "bob",
+            "--verbose",
+            "pos1",
+            "--out=a.txt",
+            "--dry-run",
+        ]
+        .map(String::from);
+
+        let parsed = parser.parse(&script_path, &args);
+
+        assert_eq!(parsed.indexed_args, vec!["pos1"]);
+        assert_eq!(parsed.named_args.get("name"), Some(&"bob".to_string()));
+        assert_eq!(parsed.named_args.get("out"), Some(&"a.txt".to_string()));
+        assert!(parsed.flags.get("verbose").unwrap());
+        assert!(parsed.flags.get("dry-run").unwrap());
+
+        // Without the option the value stays positional
+        let parsed = ArgumentParser::new().parse(&script_path, &args);
+        assert_eq!(parsed.indexed_args, vec!["bob", "pos1"]);
+    }
 }
@@ -1,20 +1,32 @@
 use crate::cli::args::ArgumentParser;
 use crate::cli::install::{
-    install_package, list_packages, uninstall_package, which_command, InstallArgs,
+    install_package, list_packages, uninstall_package, upgrade_packages, verify_packages,
+    which_command, InstallArgs,
 };
 use crate::cli::parser::CliArgs;
-use crate::engine::{ExecutionConfig, ExecutionEngine, ExecutionResult, OutputFormat};
+use crate::cli::remote::{download_script, is_remote_script, verify_integrity};
+use crate::cli::watch::{ScriptWatcher, WATCH_DEBOUNCE};
+use crate::engine::{ExecutionConfig, ExecutionEngine, ExecutionResult, OutputEvent};
 use crate::error::HypeError;
+use crate::file_io::{read_lua_script, strip_shebang, validate_lua_file, validate_runnable_file};
+use crate::lua::bytecode::{bytecode_path, compile};
+use crate::lua::lint::{lint_source, LintOptions, Severity};
 use crate::lua::module_env::create_module_env;
-use crate::lua::require::setup_require_fn;
-use crate::lua::{create_cli_config, LuaStateManager};
+use crate::lua::require::{preload_modules, setup_require_fn};
+use crate::lua::{
+    check_source, create_cli_config, create_cli_security_policy, find_static_requires,
+    LuaStateManager,
+};
+use crate::modules::builtins::process::run_exit_hooks;
 use crate::modules::loader::ModuleLoader;
+use crate::modules::resolver::ModuleResolver;
+use std::collections::HashSet;
 use std::fs;
-use std::path::Path;
+use std::io::{IsTerminal, Write};
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
-use std::time::Duration;
 
-pub fn run_script(args: CliArgs) -> Result<(), HypeError> {
+pub fn run_script(mut args: CliArgs) -> Result<(), HypeError> {
     // Show argument parsing help if requested
     if args.show_help {
         let parser = ArgumentParser::new();
@@ -26,6 +38,42 @@ pub fn run_script(args: CliArgs) -> Result<(), HypeError> {
         return Ok(());
     }
 
+    // Fetch remote scripts into a temporary directory kept alive for the run
+    let _remote_dir = if is_remote_script(&args.script) {
+        let url = args.script.to_string_lossy().to_string();
+        if args.watch {
+            return Err(HypeError::Execution(format!(
+                "Cannot watch remote script {}: --watch needs a local file",
+                url
+            )));
+        }
+        if !args.allow_remote {
+            return Err(HypeError::Security(format!(
+                "Refusing to run remote script {}: pass --allow-remote to enable",
+                url
+            )));
+        }
+        if args.verbose {
+            eprintln!("Fetching remote script: {}", url);
+        }
+        let (dir, path) = download_script(&url, args.integrity.as_deref())?;
+        args.script = path;
+        Some(dir)
+    } else {
+        // Checked here rather than while parsing so a missing script exits with 2
+        if args.module.is_none() {
+            validate_runnable_file(&args.script)?;
+        }
+        if let Some(ref integrity) = args.integrity {
+            verify_integrity(&fs::read(&args.script).map_err(HypeError::Io)?, integrity)?;
+        }
+        None
+    };
+
+    if args.check {
+        return check_scripts(&args.script, args.verbose, false);
+    }
+
     if args.verbose {
         eprintln!("Executing script: {}", args.script.display());
         if let Some(ref module_path) = args.module {
@@ -42,10 +90,7 @@ pub fn run_script(args: CliArgs) -> Result<(), HypeError> {
 
     if args.debug {
         eprintln!("Debug mode enabled");
-        eprintln!(
-            "Timeout: {:?}",
-            args.timeout.map(|t| format!("{} seconds", t))
-        );
+        eprintln!("Timeout: {:?}", args.timeout);
         eprintln!("Argument parsing enabled");
     }
 
@@ -53,6 +98,48 @@ pub fn run_script(args: CliArgs) -> Result<(), HypeError> {
         return run_module(module_path.clone(), args);
     }
 
+    if args.watch {
+        return watch_script(&args);
+    }
+
+    let result = execute_file(&args)?;
+    report_result(&args, result)
+}
+
+/// Run the script once, then again each time it or a module it required
+/// changes, until interrupted. Failures are reported without ending the loop.
+fn watch_script(args: &CliArgs) -> Result<(), HypeError> {
+    let clear_screen = std::io::stdout().is_terminal();
+    loop {
+        if clear_screen {
+            print!("\x1b[2J\x1b[H");
+            let _ = std::io::stdout().flush();
+        }
+
+        let mut files = vec![args.script.clone()];
+        let outcome = execute_file(args).and_then(|result| {
+            files.extend(result.module_paths.iter().cloned());
+            report_result(args, result)
+        });
+        match outcome {
+            // Script errors have already been printed with its output
+            Ok(()) | Err(HypeError::Exit(_)) => {}
+            Err(e) => eprintln!("Error: {}", e),
+        }
+
+        let watcher = ScriptWatcher::new(&files)?;
+        eprintln!(
+            "[watch] Waiting for changes to {} file(s), press Ctrl-C to stop",
+            watcher.file_count()
+        );
+        let changed = watcher.wait_for_change(WATCH_DEBOUNCE)?;
+        if let Some(path) = changed.first() {
+            eprintln!("[watch] {} changed, restarting", path.display());
+        }
+    }
+}
+
+fn execute_file(args: &CliArgs) -> Result<ExecutionResult, HypeError> {
     // Create execution configuration
     let mut config = ExecutionConfig::default();
     config.script_path = args.script.clone();
@@ -60,21 +147,127 @@ pub fn run_script(args: CliArgs) -> Result<(), HypeError> {
     config.parsed_args = Some(args.parsed_args.clone());
     config.verbose = args.verbose;
     config.debug = args.debug;
-    config.timeout = args.timeout.map(Duration::from_secs);
+    config.timeout = args.timeout;
+    config.instruction_limit = args.instruction_limit;
     config.capture_output = true;
-    config.output_format = OutputFormat::Text;
+    config.output_format = args.output_format;
+    config.output_events = args.output_events;
     config.enable_stats = args.verbose || args.debug;
     config.allow_debug_operations = args.debug;
     config.allow_file_operations = args.debug;
     config.allow_os_operations = args.debug;
     config.allow_package_loading = args.debug;
+    config.allow_subprocess = args.allow_subprocess;
+    config.sandbox = args.sandbox;
+    config.allow_absolute_requires = args.allow_absolute_requires;
+    config.args_as_table = args.args_as_table.is_some();
+    config.preload = args.preload.clone();
+    config.pretty_print = args.pretty_print;
 
     // Create and run execution engine
     let mut engine = ExecutionEngine::new(config)?;
-    let result = engine.execute()?;
+    engine.execute()
+}
+
+/// Print the result of a run and write its stats when `--stats-json` asked for them.
+fn report_result(args: &CliArgs, result: ExecutionResult) -> Result<(), HypeError> {
+    let stats = args
+        .stats_json
+        .as_ref()
+        .map(|_| execution_stats_json(&result));
 
     // Handle execution result
-    handle_execution_result(result, args.verbose)?;
+    let outcome = if args.output_events {
+        OutputEvent::Metrics {
+            metrics: execution_stats_json(&result),
+        }
+        .emit();
+        if result.success {
+            Ok(())
+        } else {
+            Err(HypeError::Execution(result.error_output))
+        }
+    } else {
+        handle_execution_result(result, args.verbose)
+    };
+
+    if let (Some(stats), Some(destination)) = (stats, args.stats_json.as_deref()) {
+        if destination == Path::new("-") {
+            eprintln!("{}", stats);
+        } else {
+            fs::write(destination, format!("{}\n", stats)).map_err(HypeError::Io)?;
+        }
+    }
+
+    outcome
+}
+
+/// Compile `entry` and every local Lua module it statically requires, without
+/// running anything. With `check_requires`, require targets that do not
+/// resolve are reported as problems too.
+fn check_scripts(entry: &Path, verbose: bool, check_requires: bool) -> Result<(), HypeError> {
+    let cwd = std::env::current_dir().map_err(HypeError::Io)?;
+    let resolver = ModuleResolver::new(cwd);
+    let mut pending = vec![entry.to_path_buf()];
+    let mut visited = HashSet::new();
+    let mut failures = 0;
+
+    while let Some(path) = pending.pop() {
+        if !visited.insert(path.canonicalize().unwrap_or_else(|_| path.clone())) {
+            continue;
+        }
+
+        let source = match read_lua_script(&path) {
+            Ok(source) => source,
+            Err(e) => {
+                eprintln!("{}: {}", path.display(), e);
+                failures += 1;
+                continue;
+            }
+        };
+
+        match check_source(&source, &path.display().to_string()) {
+            Ok(()) => {
+                if verbose {
+                    eprintln!("{}: OK", path.display());
+                }
+            }
+            Err(HypeError::Lua(message)) => {
+                eprintln!("{}", message);
+                failures += 1;
+            }
+            Err(e) => {
+                eprintln!("{}: {}", path.display(), e);
+                failures += 1;
+            }
+        }
+
+        for module_id in find_static_requires(&source) {
+            match resolver.resolve(&module_id) {
+                Ok(resolved) => {
+                    if resolved.is_file() && resolved.extension().is_some_and(|ext| ext == "lua") {
+                        pending.push(resolved);
+                    }
+                }
+                Err(_) if check_requires => {
+                    eprintln!(
+                        "{}: cannot resolve require('{}')",
+                        path.display(),
+                        module_id
+                    );
+                    failures += 1;
+                }
+                Err(_) => {}
+            }
+        }
+    }
+
+    if failures > 0 {
+        return Err(HypeError::Execution(format!(
+            "Check failed with {} problem(s)",
+            failures
+        )));
+    }
 
     Ok(())
 }
@@ -89,13 +282,13 @@ fn run_module(module_path: String, args: CliArgs) -> Result<(), HypeError> {
     }
 
     let module_code = fs::read_to_string(path)
+        .map(strip_shebang)
         .map_err(|_| HypeError::File(crate::error::FileError::NotFound(path.to_path_buf())))?;
 
-    let mut lua_config = create_cli_config(
-        args.verbose,
-        args.debug,
-        args.timeout.map(Duration::from_secs),
-    );
+    let mut lua_config = create_cli_config(args.verbose, args.debug, args.timeout);
+    if !args.debug {
+        lua_config.instruction_limit = args.instruction_limit;
+    }
     lua_config.allow_file_operations = args.debug;
     lua_config.allow_os_operations = args.debug;
     lua_config.allow_debug_operations = args.debug;
@@ -104,12 +297,21 @@ fn run_module(module_path: String, args: CliArgs) -> Result<(), HypeError> {
     let state_manager = LuaStateManager::new(lua_config)?;
     let lua = state_manager.lua.lock().unwrap();
 
+    let mut security_policy = create_cli_security_policy(args.debug, args.sandbox);
+    security_policy.allow_subprocess |= args.allow_subprocess;
+    security_policy.install(&lua);
+
     let cwd = std::env::current_dir().map_err(|e| HypeError::Io(e))?;
-    let loader = Arc::new(Mutex::new(ModuleLoader::new(cwd)));
+    let mut loader = ModuleLoader::new(cwd);
+    loader.set_entry(module_path.as_str());
+    loader.set_allow_absolute_paths(args.allow_absolute_requires);
+    let loader = Arc::new(Mutex::new(loader));
 
     setup_require_fn(&lua, loader)
         .map_err(|e| HypeError::Lua(format!("Failed to setup module system: {}", e)))?;
 
+    preload_modules(&lua, &args.preload)?;
+
     let env = create_module_env(&lua, path)
         .map_err(|e| HypeError::Lua(format!("Failed to create module environment: {}", e)))?;
 
@@ -119,11 +321,14 @@ fn run_module(module_path: String, args: CliArgs) -> Result<(), HypeError> {
             .map_err(|e| HypeError::Lua(format!("Failed to set require function: {}", e)))?;
     }
 
-    let chunk = lua.load(&module_code).set_environment(env);
+    let chunk = lua
+        .load(&module_code)
+        .set_name(format!("@{}", module_path))
+        .set_environment(env);
 
-    chunk
-        .eval::<()>()
-        .map_err(|e| HypeError::Lua(format!("Failed to execute module: {}", e)))?;
+    let outcome = chunk.eval::<()>();
+    run_exit_hooks(&lua, if outcome.is_ok() { 0 } else { 1 });
+    outcome.map_err(|e| HypeError::Lua(format!("Failed to execute module: {}", e)))?;
 
     if args.verbose {
         eprintln!("Module executed successfully");
@@ -132,6 +337,18 @@ fn run_module(module_path: String, args: CliArgs) -> Result<(), HypeError> {
     Ok(())
 }
 
+fn execution_stats_json(result: &ExecutionResult) -> serde_json::Value {
+    let mut stats = result
+        .metrics
+        .as_ref()
+        .map(|metrics| metrics.to_json())
+        .unwrap_or_else(|| serde_json::json!({}));
+    stats["success"] = serde_json::json!(result.success);
+    stats["exit_code"] = serde_json::json!(result.exit_code);
+    stats["total_time_ms"] = serde_json::json!(result.execution_time.as_secs_f64() * 1000.0);
+    stats
+}
+
 fn handle_execution_result(result: ExecutionResult, verbose: bool) -> Result<(), HypeError> {
     if result.success {
         // Print stdout if we have any output
@@ -157,14 +374,18 @@ fn handle_execution_result(result: ExecutionResult, verbose: bool) -> Result<(),
             }
         }
     } else {
-        // Print error output
-        if !result.error_output.is_empty() {
-            eprint!("{}", result.error_output);
-        }
-
         // Print stdout if we have any (might have partial output before error)
         if !result.output.is_empty() {
             print!("{}", result.output);
+            let _ = std::io::Write::flush(&mut std::io::stdout());
+        }
+
+        // Print error output
+        if !result.error_output.is_empty() {
+            eprint!("{}", result.error_output);
+            if !result.error_output.ends_with('\n') {
+                eprintln!();
+            }
         }
 
         if verbose {
@@ -177,7 +398,7 @@ fn handle_execution_result(result: ExecutionResult, verbose: bool) -> Result<(),
             }
         }
 
-        return Err(HypeError::Execution(result.error_output));
+        return Err(HypeError::Exit(result.exit_code));
     }
 
     Ok(())
@@ -207,10 +428,101 @@ pub fn handle_uninstall_command(name: String, verbose: bool) -> Result<(), HypeE
     uninstall_package(name, verbose)
 }
 
-pub fn handle_list_command(json: bool, verbose: bool) -> Result<(), HypeError> {
-    list_packages(verbose, json)
+pub fn handle_list_command(
+    filter: Option<String>,
+    outdated: bool,
+    json: bool,
+    verbose: bool,
+) -> Result<(), HypeError> {
+    list_packages(filter.as_deref(), outdated, verbose, json)
 }
 
 pub fn handle_which_command(command: String) -> Result<(), HypeError> {
     which_command(command)
 }
+
+pub fn handle_upgrade_command(
+    name: Option<String>,
+    force: bool,
+    verbose: bool,
+) -> Result<(), HypeError> {
+    upgrade_packages(name, force, verbose)
+}
+
+pub fn handle_verify_command(name: Option<String>, verbose: bool) -> Result<(), HypeError> {
+    verify_packages(name, verbose)
+}
+
+pub fn handle_check_command(
+    path: PathBuf,
+    check_requires: bool,
+    verbose: bool,
+) -> Result<(), HypeError> {
+    validate_lua_file(&path)?;
+    check_scripts(&path, verbose, check_requires)?;
+    println!("{}: no problems found", path.display());
+    Ok(())
+}
+
+/// Compile a script to bytecode, written beside the script by default. The
+/// `.luac` only runs when it is passed to `hype run` itself.
+pub fn handle_compile_command(
+    script: PathBuf,
+    output: Option<PathBuf>,
+    strip: bool,
+) -> Result<(), HypeError> {
+    validate_lua_file(&script)?;
+    let source = read_lua_script(&script)?;
+    // Named like `hype run` names source chunks so error messages match
+    let bytes = compile(&source, &script.to_string_lossy(), strip)?;
+    let output = output.unwrap_or_else(|| bytecode_path(&script));
+    fs::write(&output, &bytes).map_err(HypeError::Io)?;
+    println!(
+        "Compiled {} -> {} ({} bytes)",
+        script.display(),
+        output.display(),
+        bytes.len()
+    );
+    Ok(())
+}
+
+pub fn handle_lint_command(paths: Vec<PathBuf>, strict: bool) -> Result<(), HypeError> {
+    let options = if strict {
+        LintOptions::strict()
+    } else {
+        LintOptions::default()
+    };
+    let mut errors = 0;
+    let mut warnings = 0;
+
+    for path in &paths {
+        let source = match read_lua_script(path) {
+            Ok(source) => source,
+            Err(e) => {
+                eprintln!("{}: {}", path.display(), e);
+                errors += 1;
+                continue;
+            }
+        };
+
+        for finding in lint_source(&source, &options) {
+            match finding.severity {
+                Severity::Error => errors += 1,
+                Severity::Warning => warnings += 1,
+            }
+            println!("{}:{}", path.display(), finding);
+        }
+    }
+
+    if errors > 0 || warnings > 0 {
+        eprintln!("{} error(s), {} warning(s)", errors, warnings);
+    }
+    if errors > 0 {
+        return Err(HypeError::Execution(format!(
+            "Lint failed with {} error(s)",
+            errors
+        )));
+    }
+
+    Ok(())
+}
@@ -1,6 +1,8 @@
+use crate::cli::package_source::PackageSource;
 use crate::error::{HypeError, Result};
 use crate::modules::bin_wrapper::BinWrapper;
-use crate::modules::manifest::HypeManifest;
+use crate::modules::builtins::crypto::hash_file;
+use crate::modules::manifest::{dependency_path, HypeManifest};
 use crate::modules::registry_global::{GlobalPackageRegistry, InstalledPackage};
 use std::collections::HashMap;
 use std::env;
@@ -13,9 +15,22 @@ pub struct InstallArgs {
 }
 
 pub fn install_package(args: InstallArgs) -> Result<()> {
-    let source_path = args
-        .path
-        .unwrap_or_else(|| PathBuf::from("."))
+    let spec = args.path.unwrap_or_else(|| PathBuf::from("."));
+    let source = PackageSource::parse(&spec.to_string_lossy());
+
+    // Remote packages are fetched into a temp dir that lives until the copy is done
+    let (download_dir, source_path) = match source {
+        PackageSource::Local(path) => (None, path),
+        remote => {
+            if args.verbose {
+                println!("Fetching package from {}", spec.display());
+            }
+            let (dir, root) = remote.fetch()?;
+            (Some(dir), root)
+        }
+    };
+
+    let source_path = source_path
         .canonicalize()
         .map_err(|e| HypeError::Execution(format!("Invalid package path: {}", e)))?;
 
@@ -67,10 +82,7 @@ pub fn install_package(args: InstallArgs) -> Result<()> {
         })?;
 
         for cmd in old_pkg.bin.keys() {
-            let wrapper_path = registry.bin_dir().join(cmd);
-            if wrapper_path.exists() {
-                std::fs::remove_file(&wrapper_path)?;
-            }
+            BinWrapper::remove_wrapper(&registry.bin_dir().join(cmd))?;
         }
 
         if old_pkg.location.exists() {
@@ -86,6 +98,10 @@ pub fn install_package(args: InstallArgs) -> Result<()> {
     }
 
     copy_package(&source_path, &dest_dir)?;
+    if let Err(e) = vendor_dependencies(&manifest, &source_path, &dest_dir, args.verbose) {
+        let _ = std::fs::remove_dir_all(&dest_dir);
+        return Err(e);
+    }
 
     if args.verbose {
         println!("Creating binary wrappers...");
@@ -101,6 +117,8 @@ pub fn install_package(args: InstallArgs) -> Result<()> {
         BinWrapper::create_wrapper(&wrapper_path, &dest_dir, script_path)?;
     }
 
+    let checksums = compute_checksums(&dest_dir, bin_map)?;
+
     let install_date = chrono::Utc::now().to_rfc3339();
 
     let installed_pkg = InstalledPackage {
@@ -109,6 +127,8 @@ pub fn install_package(args: InstallArgs) -> Result<()> {
         install_date,
         location: dest_dir,
         bin: bin_map.clone(),
+        checksums,
+        source: download_dir.is_none().then(|| source_path.clone()),
     };
 
     registry
@@ -148,7 +168,7 @@ pub fn uninstall_package(name: String, verbose: bool) -> Result<()> {
             if verbose {
                 println!("  Removing: {}", cmd);
             }
-            std::fs::remove_file(&wrapper_path)?;
+            BinWrapper::remove_wrapper(&wrapper_path)?;
         }
     }
 
@@ -171,28 +191,63 @@ pub fn uninstall_package(name: String, verbose: bool) -> Result<()> {
     Ok(())
 }
 
-pub fn list_packages(verbose: bool, json: bool) -> Result<()> {
+pub fn list_packages(
+    filter: Option<&str>,
+    outdated: bool,
+    verbose: bool,
+    json: bool,
+) -> Result<()> {
     let registry = GlobalPackageRegistry::load()
         .map_err(|e| HypeError::Execution(format!("Failed to load package registry: {}", e)))?;
 
-    let packages = registry.list();
+    let packages: Vec<(&InstalledPackage, Option<String>)> =
+        filter_packages(registry.list(), filter)
+            .into_iter()
+            .map(|pkg| {
+                let update = if outdated {
+                    available_update(pkg)
+                } else {
+                    None
+                };
+                (pkg, update)
+            })
+            .collect();
 
     if json {
-        let json_output = serde_json::to_string_pretty(&packages)
+        let mut rows = Vec::with_capacity(packages.len());
+        for (pkg, update) in &packages {
+            let mut row = serde_json::to_value(pkg).map_err(|e| {
+                HypeError::Execution(format!("Failed to serialize packages: {}", e))
+            })?;
+            if let (Some(version), Some(object)) = (update, row.as_object_mut()) {
+                object.insert("available_version".to_string(), version.clone().into());
+            }
+            rows.push(row);
+        }
+        let json_output = serde_json::to_string_pretty(&rows)
             .map_err(|e| HypeError::Execution(format!("Failed to serialize packages: {}", e)))?;
         println!("{}", json_output);
         return Ok(());
     }
 
     if packages.is_empty() {
-        println!("No globally installed packages.");
+        match filter {
+            Some(filter) => println!("No globally installed packages match '{}'.", filter),
+            None => println!("No globally installed packages."),
+        }
         return Ok(());
     }
 
     println!("Globally installed packages:\n");
 
-    for pkg in &packages {
-        println!("  {}@{}", pkg.name, pkg.version);
+    for (pkg, update) in &packages {
+        match update {
+            Some(version) => println!(
+                "  {}@{}  (outdated: {} available)",
+                pkg.name, pkg.version, version
+            ),
+            None => println!("  {}@{}", pkg.name, pkg.version),
+        }
 
         let commands: Vec<&String> = pkg.bin.keys().collect();
         if !commands.is_empty() {
@@ -209,6 +264,9 @@ pub fn list_packages(verbose: bool, json: bool) -> Result<()> {
         if verbose {
             println!("    Location: {}", pkg.location.display());
             println!("    Installed: {}", pkg.install_date);
+            if let Some(source) = &pkg.source {
+                println!("    Source: {}", source.display());
+            }
         }
 
         println!();
@@ -220,9 +278,243 @@ pub fn list_packages(verbose: bool, json: bool) -> Result<()> {
         if packages.len() == 1 { "" } else { "s" }
     );
 
+    if outdated {
+        let count = packages
+            .iter()
+            .filter(|(_, update)| update.is_some())
+            .count();
+        println!("Outdated: {}", count);
+    }
+
     Ok(())
 }
 
+/// Packages whose name contains `filter`, sorted by name.
+fn filter_packages<'a>(
+    mut packages: Vec<&'a InstalledPackage>,
+    filter: Option<&str>,
+) -> Vec<&'a InstalledPackage> {
+    if let Some(filter) = filter {
+        packages.retain(|pkg| pkg.name.contains(filter));
+    }
+    packages.sort_by(|a, b| a.name.cmp(&b.name));
+    packages
+}
+
+/// The version declared by the package's source `hype.json` when it is newer
+/// than the installed one. Downloads and moved sources have nothing to compare.
+fn available_update(pkg: &InstalledPackage) -> Option<String> {
+    let manifest_path = pkg.source.as_ref()?.join("hype.json");
+    let manifest = HypeManifest::load(&manifest_path).ok()?;
+
+    let installed = semver::Version::parse(&pkg.version).ok()?;
+    let available = semver::Version::parse(&manifest.version).ok()?;
+    (available > installed).then_some(manifest.version)
+}
+
+pub fn upgrade_packages(name: Option<String>, force: bool, verbose: bool) -> Result<()> {
+    let mut registry = GlobalPackageRegistry::load()
+        .map_err(|e| HypeError::Execution(format!("Failed to load package registry: {}", e)))?;
+
+    let Some(name) = name else {
+        let mut names: Vec<String> = registry.list().iter().map(|p| p.name.clone()).collect();
+        names.sort();
+
+        let mut upgraded = 0;
+        let mut failures = 0;
+        for name in names {
+            if registry.get(&name).is_some_and(|pkg| pkg.source.is_none()) {
+                println!("- {}: installed from a download, skipping", name);
+                continue;
+            }
+            match upgrade_package(&mut registry, &name, force, verbose) {
+                Ok(true) => upgraded += 1,
+                Ok(false) => {}
+                Err(e) => {
+                    eprintln!("✗ {}: {}", name, e);
+                    failures += 1;
+                }
+            }
+        }
+
+        println!(
+            "Upgraded {} package{}",
+            upgraded,
+            if upgraded == 1 { "" } else { "s" }
+        );
+        if failures > 0 {
+            return Err(HypeError::Execution(format!(
+                "{} package{} failed to upgrade",
+                failures,
+                if failures == 1 { "" } else { "s" }
+            )));
+        }
+        return Ok(());
+    };
+
+    upgrade_package(&mut registry, &name, force, verbose).map(|_| ())
+}
+
+/// Reinstall `name` from its recorded source directory when the source
+/// declares a newer version (or always with `force`). The new copy, its
+/// wrappers and the registry entry are prepared before anything is replaced,
+/// so a failure leaves the installed version usable. Returns whether the
+/// package was reinstalled.
+fn upgrade_package(
+    registry: &mut GlobalPackageRegistry,
+    name: &str,
+    force: bool,
+    verbose: bool,
+) -> Result<bool> {
+    let old_pkg = registry
+        .get(name)
+        .cloned()
+        .ok_or_else(|| HypeError::Execution(format!("Package '{}' is not installed", name)))?;
+
+    let source_path = old_pkg.source.clone().ok_or_else(|| {
+        HypeError::Execution(format!(
+            "Package '{}' was not installed from a local directory; reinstall it with 'hype install'",
+            name
+        ))
+    })?;
+    let manifest_path = source_path.join("hype.json");
+    if !manifest_path.exists() {
+        return Err(HypeError::Execution(format!(
+            "Source for '{}' no longer exists: {}",
+            name,
+            source_path.display()
+        )));
+    }
+
+    let manifest = HypeManifest::load(&manifest_path)?;
+    manifest.validate_with_package_dir(&source_path)?;
+    if manifest.name != name {
+        return Err(HypeError::Execution(format!(
+            "Source at {} now declares package '{}', not '{}'",
+            source_path.display(),
+            manifest.name,
+            name
+        )));
+    }
+
+    let bin_map = manifest.bin.clone().unwrap_or_default();
+    if bin_map.is_empty() {
+        return Err(HypeError::Execution(
+            "Package has no 'bin' field - nothing to install".to_string(),
+        ));
+    }
+
+    let is_newer = match (
+        semver::Version::parse(&manifest.version),
+        semver::Version::parse(&old_pkg.version),
+    ) {
+        (Ok(available), Ok(installed)) => available > installed,
+        _ => manifest.version != old_pkg.version,
+    };
+    if !is_newer && !force {
+        println!("{}@{} is up to date", name, old_pkg.version);
+        return Ok(false);
+    }
+
+    let pkg_version_str = format!("{}@{}", manifest.name, manifest.version);
+    let packages_dir = registry.packages_dir().clone();
+    let bin_dir = registry.bin_dir().clone();
+    let dest_dir = packages_dir.join(&pkg_version_str);
+    let staging_dir = packages_dir.join(format!(".{}.staging", pkg_version_str));
+    let backup_dir = packages_dir.join(format!(".{}.backup", pkg_version_str));
+
+    if verbose {
+        println!(
+            "Upgrading {}@{} -> {}",
+            name, old_pkg.version, manifest.version
+        );
+        println!("Copying package to {}", dest_dir.display());
+    }
+
+    copy_package(&source_path, &staging_dir)?;
+    if let Err(e) = vendor_dependencies(&manifest, &source_path, &staging_dir, verbose) {
+        let _ = std::fs::remove_dir_all(&staging_dir);
+        return Err(e);
+    }
+    let checksums = compute_checksums(&staging_dir, &bin_map)?;
+
+    // Reinstalling the same version replaces its directory, so keep the old copy
+    // until the registry has been updated.
+    if dest_dir.exists() {
+        if backup_dir.exists() {
+            std::fs::remove_dir_all(&backup_dir)?;
+        }
+        std::fs::rename(&dest_dir, &backup_dir)?;
+    }
+    std::fs::rename(&staging_dir, &dest_dir)?;
+
+    let restore = |error: HypeError| -> HypeError {
+        let _ = std::fs::remove_dir_all(&dest_dir);
+        if backup_dir.exists() {
+            let _ = std::fs::rename(&backup_dir, &dest_dir);
+        }
+        error
+    };
+
+    let mut staged_wrappers: Vec<(PathBuf, PathBuf)> = Vec::new();
+    for (cmd_name, script_path) in &bin_map {
+        let staged = bin_dir.join(format!(".{}.upgrade", cmd_name));
+        if let Err(e) = BinWrapper::create_wrapper(&staged, &dest_dir, script_path) {
+            for (staged, _) in &staged_wrappers {
+                let _ = BinWrapper::remove_wrapper(staged);
+            }
+            return Err(restore(e));
+        }
+        staged_wrappers.push((staged, bin_dir.join(cmd_name)));
+    }
+
+    let installed_pkg = InstalledPackage {
+        name: manifest.name.clone(),
+        version: manifest.version.clone(),
+        install_date: chrono::Utc::now().to_rfc3339(),
+        location: dest_dir.clone(),
+        bin: bin_map.clone(),
+        checksums,
+        source: Some(source_path),
+    };
+
+    if let Err(e) = registry.replace_package(installed_pkg) {
+        for (staged, _) in &staged_wrappers {
+            let _ = BinWrapper::remove_wrapper(staged);
+        }
+        return Err(restore(HypeError::Execution(format!(
+            "Failed to update registry: {}",
+            e
+        ))));
+    }
+
+    for (staged, wrapper_path) in &staged_wrappers {
+        let files = BinWrapper::wrapper_files(staged)
+            .into_iter()
+            .zip(BinWrapper::wrapper_files(wrapper_path));
+        for (from, to) in files {
+            std::fs::rename(from, to)?;
+        }
+    }
+    for cmd in old_pkg.bin.keys().filter(|cmd| !bin_map.contains_key(*cmd)) {
+        BinWrapper::remove_wrapper(&bin_dir.join(cmd))?;
+    }
+
+    if backup_dir.exists() {
+        std::fs::remove_dir_all(&backup_dir)?;
+    }
+    if old_pkg.location != dest_dir && old_pkg.location.exists() {
+        std::fs::remove_dir_all(&old_pkg.location)?;
+    }
+
+    println!(
+        "✓ Upgraded {} {} -> {}",
+        name, old_pkg.version, manifest.version
+    );
+
+    Ok(true)
+}
+
 pub fn which_command(cmd: String) -> Result<()> {
     let registry = GlobalPackageRegistry::load()
         .map_err(|e| HypeError::Execution(format!("Failed to load package registry: {}", e)))?;
@@ -245,6 +537,174 @@ pub fn which_command(cmd: String) -> Result<()> {
     std::process::exit(1);
 }
 
+pub fn verify_packages(name: Option<String>, verbose: bool) -> Result<()> {
+    let registry = GlobalPackageRegistry::load()
+        .map_err(|e| HypeError::Execution(format!("Failed to load package registry: {}", e)))?;
+
+    let mut packages = match &name {
+        Some(name) => vec![registry
+            .get(name)
+            .ok_or_else(|| HypeError::Execution(format!("Package '{}' is not installed", name)))?],
+        None => registry.list(),
+    };
+    packages.sort_by(|a, b| a.name.cmp(&b.name));
+
+    if packages.is_empty() {
+        println!("No globally installed packages.");
+        return Ok(());
+    }
+
+    let mut problems = 0;
+    for pkg in packages {
+        if pkg.checksums.is_empty() {
+            println!(
+                "? {}@{}: no checksums recorded (reinstall to enable verification)",
+                pkg.name, pkg.version
+            );
+            continue;
+        }
+
+        let issues = find_tampered_files(pkg);
+        if issues.is_empty() {
+            println!(
+                "✓ {}@{}: {} file{} verified",
+                pkg.name,
+                pkg.version,
+                pkg.checksums.len(),
+                if pkg.checksums.len() == 1 { "" } else { "s" }
+            );
+            if verbose {
+                println!("    Location: {}", pkg.location.display());
+            }
+        } else {
+            println!("✗ {}@{}:", pkg.name, pkg.version);
+            for issue in &issues {
+                println!("    {}", issue);
+            }
+            problems += issues.len();
+        }
+    }
+
+    if problems > 0 {
+        return Err(HypeError::Execution(format!(
+            "Verification failed: {} tampered file{}",
+            problems,
+            if problems == 1 { "" } else { "s" }
+        )));
+    }
+
+    Ok(())
+}
+
+/// Describe every bin script whose contents no longer match the checksum
+/// recorded at install time.
+fn find_tampered_files(pkg: &InstalledPackage) -> Vec<String> {
+    let mut scripts: Vec<_> = pkg.checksums.iter().collect();
+    scripts.sort();
+
+    scripts
+        .into_iter()
+        .filter_map(|(script, expected)| {
+            let path = pkg.location.join(script);
+            if !path.is_file() {
+                return Some(format!("{}: missing", script));
+            }
+            match hash_file("sha256", &path.to_string_lossy()) {
+                Ok(actual) if &actual == expected => None,
+                Ok(actual) => Some(format!(
+                    "{}: checksum mismatch (expected {}, found {})",
+                    script, expected, actual
+                )),
+                Err(e) => Some(format!("{}: {}", script, e)),
+            }
+        })
+        .collect()
+}
+
+fn compute_checksums(
+    package_dir: &Path,
+    bin_map: &HashMap<String, String>,
+) -> Result<HashMap<String, String>> {
+    bin_map
+        .values()
+        .map(|script| {
+            let path = package_dir.join(script);
+            let checksum = hash_file("sha256", &path.to_string_lossy()).map_err(|e| {
+                HypeError::Execution(format!("Failed to checksum {}: {}", script, e))
+            })?;
+            Ok((script.clone(), checksum))
+        })
+        .collect()
+}
+
+/// Copy local-path dependencies into `<package>/hype_modules/<name>` and check
+/// that version dependencies are already vendored there at a matching version.
+fn vendor_dependencies(
+    manifest: &HypeManifest,
+    source_path: &Path,
+    dest_dir: &Path,
+    verbose: bool,
+) -> Result<()> {
+    let Some(dependencies) = &manifest.dependencies else {
+        return Ok(());
+    };
+    let mut dependencies: Vec<_> = dependencies.iter().collect();
+    dependencies.sort();
+
+    let modules_dir = dest_dir.join("hype_modules");
+    for (name, spec) in dependencies {
+        if let Some(dep_path) = dependency_path(source_path, spec) {
+            if verbose {
+                println!(
+                    "  Vendoring dependency {} from {}",
+                    name,
+                    dep_path.display()
+                );
+            }
+            std::fs::create_dir_all(&modules_dir)?;
+            if dep_path.is_dir() {
+                copy_package(&dep_path, &modules_dir.join(name))?;
+            } else if dep_path.is_file() {
+                std::fs::copy(&dep_path, modules_dir.join(format!("{}.lua", name)))?;
+            } else {
+                return Err(HypeError::Execution(format!(
+                    "Dependency '{}' does not exist at path: {}",
+                    name,
+                    dep_path.display()
+                )));
+            }
+            continue;
+        }
+
+        let vendored_dir = modules_dir.join(name);
+        if !vendored_dir.is_dir() && !modules_dir.join(format!("{}.lua", name)).is_file() {
+            return Err(HypeError::Execution(format!(
+                "Dependency '{}' ({}) not found: add it to hype_modules/ or give it a local path",
+                name, spec
+            )));
+        }
+
+        let requirement = semver::VersionReq::parse(spec).map_err(|e| {
+            HypeError::Execution(format!("Invalid version for dependency '{}': {}", name, e))
+        })?;
+        let dep_manifest = vendored_dir.join("hype.json");
+        if requirement != semver::VersionReq::STAR && dep_manifest.is_file() {
+            let version = HypeManifest::load(&dep_manifest)?.version;
+            let matches = semver::Version::parse(&version)
+                .map(|v| requirement.matches(&v))
+                .unwrap_or(false);
+            if !matches {
+                return Err(HypeError::Execution(format!(
+                    "Dependency '{}' requires {} but hype_modules/{} is version {}",
+                    name, spec, name, version
+                )));
+            }
+        }
+    }
+
+    Ok(())
+}
+
 fn copy_package(source: &Path, dest: &Path) -> Result<()> {
     if dest.exists() {
         std::fs::remove_dir_all(dest)?;
@@ -556,6 +1016,255 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_install_package_from_file_tarball() {
+        use flate2::write::GzEncoder;
+        use flate2::Compression;
+
+        let _lock = TEST_MUTEX.lock().unwrap();
+        let (temp, hype_home) = setup_test_env();
+        let pkg_dir = create_test_package_dir(&temp, "tarball-pkg");
+
+        let archive = temp.path().join("tarball-pkg.tar.gz");
+        let file = std::fs::File::create(&archive).unwrap();
+        let mut builder = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+        builder
+            .append_dir_all("tarball-pkg-1.0.0", &pkg_dir)
+            .unwrap();
+        builder.into_inner().unwrap().finish().unwrap();
+
+        let url = url::Url::from_file_path(&archive).unwrap().to_string();
+        install_package(InstallArgs {
+            path: Some(PathBuf::from(url)),
+            force: false,
+            verbose: false,
+        })
+        .unwrap();
+
+        let registry = GlobalPackageRegistry::load().unwrap();
+        let installed = registry.get("tarball-pkg").unwrap();
+        assert_eq!(installed.version, "1.0.0");
+        assert!(installed.location.join("bin/cli.lua").is_file());
+        assert!(hype_home.join("bin/testcmd").exists());
+    }
+
+    #[test]
+    fn test_verify_reports_tampered_script() {
+        let _lock = TEST_MUTEX.lock().unwrap();
+        let (temp, _hype_home) = setup_test_env();
+        let pkg_dir = create_test_package_dir(&temp, "verify-pkg");
+
+        install_package(InstallArgs {
+            path: Some(pkg_dir),
+            force: false,
+            verbose: false,
+        })
+        .unwrap();
+
+        let registry = GlobalPackageRegistry::load().unwrap();
+        let installed = registry.get("verify-pkg").unwrap().clone();
+        assert_eq!(installed.checksums.len(), 1);
+        assert!(find_tampered_files(&installed).is_empty());
+        verify_packages(Some("verify-pkg".to_string()), false).unwrap();
+
+        std::fs::write(installed.location.join("bin/cli.lua"), "os.exit(1)").unwrap();
+
+        let issues = find_tampered_files(&installed);
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].starts_with("bin/cli.lua: checksum mismatch"));
+
+        let err = verify_packages(None, false).unwrap_err();
+        assert!(err.to_string().contains("1 tampered file"), "{}", err);
+    }
+
+    #[test]
+    fn test_list_filter_narrows_results() {
+        let _lock = TEST_MUTEX.lock().unwrap();
+        let (temp, _hype_home) = setup_test_env();
+
+        for name in ["http-tools", "json-tools", "http-server"] {
+            let pkg_dir = create_test_package_dir(&temp, name);
+            let manifest = std::fs::read_to_string(pkg_dir.join("hype.json"))
+                .unwrap()
+                .replace("testcmd", name);
+            std::fs::write(pkg_dir.join("hype.json"), manifest).unwrap();
+            install_package(InstallArgs {
+                path: Some(pkg_dir),
+                force: false,
+                verbose: false,
+            })
+            .unwrap();
+        }
+
+        let registry = GlobalPackageRegistry::load().unwrap();
+        let names = |filter| {
+            filter_packages(registry.list(), filter)
+                .iter()
+                .map(|pkg| pkg.name.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(Some("http")), vec!["http-server", "http-tools"]);
+        assert_eq!(names(Some("json")), vec!["json-tools"]);
+        assert!(names(Some("xml")).is_empty());
+        assert_eq!(names(None).len(), 3);
+        assert!(list_packages(Some("http"), true, false, false).is_ok());
+    }
+
+    #[test]
+    fn test_available_update_compares_source_manifest() {
+        let _lock = TEST_MUTEX.lock().unwrap();
+        let (temp, _hype_home) = setup_test_env();
+        let pkg_dir = create_test_package_dir(&temp, "aging-pkg");
+
+        install_package(InstallArgs {
+            path: Some(pkg_dir.clone()),
+            force: false,
+            verbose: false,
+        })
+        .unwrap();
+
+        let registry = GlobalPackageRegistry::load().unwrap();
+        let installed = registry.get("aging-pkg").unwrap();
+        assert_eq!(available_update(installed), None);
+
+        let manifest = std::fs::read_to_string(pkg_dir.join("hype.json"))
+            .unwrap()
+            .replace("1.0.0", "1.2.0");
+        std::fs::write(pkg_dir.join("hype.json"), manifest).unwrap();
+        assert_eq!(available_update(installed), Some("1.2.0".to_string()));
+
+        std::fs::remove_dir_all(&pkg_dir).unwrap();
+        assert_eq!(available_update(installed), None);
+    }
+
+    #[test]
+    fn test_upgrade_reinstalls_newer_source_version() {
+        let _lock = TEST_MUTEX.lock().unwrap();
+        let (temp, hype_home) = setup_test_env();
+        let pkg_dir = create_test_package_dir(&temp, "upgrade-pkg");
+
+        install_package(InstallArgs {
+            path: Some(pkg_dir.clone()),
+            force: false,
+            verbose: false,
+        })
+        .unwrap();
+
+        let manifest = std::fs::read_to_string(pkg_dir.join("hype.json"))
+            .unwrap()
+            .replace("1.0.0", "1.1.0");
+        std::fs::write(pkg_dir.join("hype.json"), manifest).unwrap();
+        std::fs::write(pkg_dir.join("bin/cli.lua"), "print('v1.1')").unwrap();
+
+        upgrade_packages(Some("upgrade-pkg".to_string()), false, false).unwrap();
+
+        let registry = GlobalPackageRegistry::load().unwrap();
+        let installed = registry.get("upgrade-pkg").unwrap();
+        assert_eq!(installed.version, "1.1.0");
+        assert!(installed.location.ends_with("upgrade-pkg@1.1.0"));
+        assert_eq!(
+            registry.which_command("testcmd"),
+            Some("upgrade-pkg@1.1.0".to_string())
+        );
+        assert!(find_tampered_files(installed).is_empty());
+        assert!(!hype_home.join("packages/upgrade-pkg@1.0.0").exists());
+
+        let wrapper = std::fs::read_to_string(hype_home.join("bin/testcmd")).unwrap();
+        assert!(wrapper.contains("upgrade-pkg@1.1.0"), "{}", wrapper);
+        assert_eq!(
+            std::fs::read_to_string(installed.location.join("bin/cli.lua")).unwrap(),
+            "print('v1.1')"
+        );
+    }
+
+    #[test]
+    fn test_upgrade_skips_current_version_unless_forced() {
+        let _lock = TEST_MUTEX.lock().unwrap();
+        let (temp, _hype_home) = setup_test_env();
+        let pkg_dir = create_test_package_dir(&temp, "current-pkg");
+
+        install_package(InstallArgs {
+            path: Some(pkg_dir.clone()),
+            force: false,
+            verbose: false,
+        })
+        .unwrap();
+        std::fs::write(pkg_dir.join("bin/cli.lua"), "print('patched')").unwrap();
+
+        let mut registry = GlobalPackageRegistry::load().unwrap();
+        assert!(!upgrade_package(&mut registry, "current-pkg", false, false).unwrap());
+        assert!(upgrade_package(&mut registry, "current-pkg", true, false).unwrap());
+
+        let registry = GlobalPackageRegistry::load().unwrap();
+        let installed = registry.get("current-pkg").unwrap();
+        assert_eq!(installed.version, "1.0.0");
+        assert_eq!(
+            std::fs::read_to_string(installed.location.join("bin/cli.lua")).unwrap(),
+            "print('patched')"
+        );
+        assert!(upgrade_packages(None, false, false).is_ok());
+    }
+
+    #[test]
+    fn test_install_vendors_local_path_dependency() {
+        let _lock = TEST_MUTEX.lock().unwrap();
+        let (temp, _hype_home) = setup_test_env();
+        let pkg_dir = create_test_package_dir(&temp, "dep-app");
+
+        let utils_dir = temp.path().join("shared/utils");
+        std::fs::create_dir_all(&utils_dir).unwrap();
+        std::fs::write(utils_dir.join("init.lua"), "return { answer = 42 }").unwrap();
+
+        let manifest = std::fs::read_to_string(pkg_dir.join("hype.json")).unwrap();
+        let mut manifest: serde_json::Value = serde_json::from_str(&manifest).unwrap();
+        manifest["dependencies"] = serde_json::json!({ "utils": "../shared/utils" });
+        std::fs::write(pkg_dir.join("hype.json"), manifest.to_string()).unwrap();
+
+        install_package(InstallArgs {
+            path: Some(pkg_dir),
+            force: false,
+            verbose: false,
+        })
+        .unwrap();
+
+        let registry = GlobalPackageRegistry::load().unwrap();
+        let installed = registry.get("dep-app").unwrap();
+        assert_eq!(
+            std::fs::read_to_string(installed.location.join("hype_modules/utils/init.lua"))
+                .unwrap(),
+            "return { answer = 42 }"
+        );
+    }
+
+    #[test]
+    fn test_install_rejects_missing_version_dependency() {
+        let _lock = TEST_MUTEX.lock().unwrap();
+        let (temp, _hype_home) = setup_test_env();
+        let pkg_dir = create_test_package_dir(&temp, "needs-dep");
+
+        let manifest = std::fs::read_to_string(pkg_dir.join("hype.json")).unwrap();
+        let mut manifest: serde_json::Value = serde_json::from_str(&manifest).unwrap();
+        manifest["dependencies"] = serde_json::json!({ "logger": "^1.0.0" });
+        std::fs::write(pkg_dir.join("hype.json"), manifest.to_string()).unwrap();
+
+        let err = install_package(InstallArgs {
+            path: Some(pkg_dir),
+            force: false,
+            verbose: false,
+        })
+        .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("Dependency 'logger' (^1.0.0) not found"),
+            "{}",
+            err
+        );
+        assert!(GlobalPackageRegistry::load()
+            .unwrap()
+            .get("needs-dep")
+            .is_none());
+    }
+
     #[test]
     fn test_uninstall_package_not_found() {
         let _lock = TEST_MUTEX.lock().unwrap();
@@ -591,7 +1300,7 @@ mod tests {
         let _lock = TEST_MUTEX.lock().unwrap();
         let (_temp, _hype_home) = setup_test_env();
 
-        let result = list_packages(false, false);
+        let result = list_packages(None, false, false, false);
 
         assert!(result.is_ok());
     }
@@ -601,7 +1310,7 @@ mod tests {
         let _lock = TEST_MUTEX.lock().unwrap();
         let (_temp, _hype_home) = setup_test_env();
 
-        let result = list_packages(false, true);
+        let result = list_packages(None, false, false, true);
 
         assert!(result.is_ok());
     }
@@ -727,7 +1436,7 @@ mod tests {
         let _lock = TEST_MUTEX.lock().unwrap();
         let (_temp, _hype_home) = setup_test_env();
 
-        let result = list_packages(true, false);
+        let result = list_packages(None, false, true, false);
 
         assert!(result.is_ok());
     }
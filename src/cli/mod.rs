@@ -2,5 +2,8 @@ pub mod agent;
 pub mod args;
 pub mod commands;
 pub mod install;
+pub mod package_source;
 pub mod parser;
+pub mod remote;
 pub mod validation;
+pub mod watch;
@@ -0,0 +1,307 @@
+use crate::error::{HypeError, Result};
+use flate2::read::GzDecoder;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tempfile::TempDir;
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Where a package passed to `hype install` comes from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PackageSource {
+    /// A package directory on disk.
+    Local(PathBuf),
+    /// A `.tar` or `.tar.gz` archive at an http(s) or `file://` URL.
+    Tarball(String),
+    /// A git repository, cloned at `reference` or its default branch.
+    Git {
+        url: String,
+        reference: Option<String>,
+    },
+}
+
+impl PackageSource {
+    /// Classify an install spec.
+    ///
+    /// - `github.com/user/repo[#ref]` installs the GitHub tarball for `ref` (default `HEAD`)
+    /// - `git+<url>[#ref]`, or a URL ending in `.git`, is cloned with `git`
+    /// - other `http(s)://` and `file://` URLs are downloaded as tarballs
+    /// - anything else is a local path
+    pub fn parse(spec: &str) -> Self {
+        if Path::new(spec).exists() {
+            return PackageSource::Local(PathBuf::from(spec));
+        }
+
+        let (base, reference) = match spec.rsplit_once('#') {
+            Some((base, reference)) if !reference.is_empty() => (base, Some(reference.to_string())),
+            _ => (spec, None),
+        };
+
+        if let Some(url) = base.strip_prefix("git+") {
+            return PackageSource::Git {
+                url: url.to_string(),
+                reference,
+            };
+        }
+
+        if let Some(repo) = github_repo(base) {
+            return PackageSource::Tarball(format!(
+                "https://github.com/{}/archive/{}.tar.gz",
+                repo,
+                reference.as_deref().unwrap_or("HEAD")
+            ));
+        }
+
+        if base.ends_with(".git") && (is_url(base) || base.starts_with("git@")) {
+            return PackageSource::Git {
+                url: base.to_string(),
+                reference,
+            };
+        }
+
+        if is_url(spec) {
+            return PackageSource::Tarball(spec.to_string());
+        }
+
+        PackageSource::Local(PathBuf::from(spec))
+    }
+
+    pub fn is_remote(&self) -> bool {
+        !matches!(self, PackageSource::Local(_))
+    }
+
+    /// Download a remote package into a temporary directory and return the
+    /// directory holding its `hype.json`. The `TempDir` must outlive the install.
+    pub fn fetch(&self) -> Result<(TempDir, PathBuf)> {
+        let dir = TempDir::new().map_err(HypeError::Io)?;
+
+        match self {
+            PackageSource::Local(path) => Ok((dir, path.clone())),
+            PackageSource::Tarball(url) => {
+                let archive = download(url)?;
+                extract_tarball(&archive, dir.path())?;
+                let root = find_package_root(dir.path())?;
+                Ok((dir, root))
+            }
+            PackageSource::Git { url, reference } => {
+                let checkout = dir.path().join("package");
+                clone_repository(url, reference.as_deref(), &checkout)?;
+                let root = find_package_root(&checkout)?;
+                Ok((dir, root))
+            }
+        }
+    }
+}
+
+fn is_url(spec: &str) -> bool {
+    ["http://", "https://", "file://"]
+        .iter()
+        .any(|scheme| spec.starts_with(scheme))
+}
+
+/// `user/repo` for `github.com/user/repo` specs, with or without a scheme or `.git`.
+fn github_repo(spec: &str) -> Option<String> {
+    let rest = spec
+        .strip_prefix("https://")
+        .or_else(|| spec.strip_prefix("http://"))
+        .unwrap_or(spec)
+        .strip_prefix("github.com/")?;
+    let rest = rest.trim_end_matches('/');
+    let rest = rest.strip_suffix(".git").unwrap_or(rest);
+
+    let mut segments = rest.split('/');
+    match (segments.next(), segments.next(), segments.next()) {
+        (Some(user), Some(repo), None) if !user.is_empty() && !repo.is_empty() => {
+            Some(format!("{}/{}", user, repo))
+        }
+        _ => None,
+    }
+}
+
+fn download(url: &str) -> Result<Vec<u8>> {
+    if url.starts_with("file://") {
+        let path = url::Url::parse(url)
+            .ok()
+            .and_then(|u| u.to_file_path().ok())
+            .ok_or_else(|| HypeError::Execution(format!("Invalid file URL: {}", url)))?;
+        return fs::read(&path)
+            .map_err(|e| HypeError::Execution(format!("Failed to read {}: {}", url, e)));
+    }
+
+    fetch_bytes(url)
+}
+
+#[cfg(feature = "http")]
+fn fetch_bytes(url: &str) -> Result<Vec<u8>> {
+    let client = crate::modules::builtins::http::HttpClient::new()
+        .map_err(|e| HypeError::Execution(format!("Failed to create HTTP client: {}", e)))?;
+    client
+        .get_bytes(url)
+        .map_err(|e| HypeError::Execution(format!("Failed to download {}: {}", url, e)))
+}
+
+#[cfg(not(feature = "http"))]
+fn fetch_bytes(url: &str) -> Result<Vec<u8>> {
+    Err(HypeError::Execution(format!(
+        "Cannot download {}: HTTP feature not enabled. Compile with --features http",
+        url
+    )))
+}
+
+/// Unpack a `.tar` or gzip-compressed `.tar.gz` archive into `dest`.
+///
+/// Entries that would escape `dest` (absolute paths or `..`) are skipped.
+fn extract_tarball(archive: &[u8], dest: &Path) -> Result<()> {
+    let reader: Box<dyn Read + '_> = if archive.starts_with(&GZIP_MAGIC) {
+        Box::new(GzDecoder::new(archive))
+    } else {
+        Box::new(archive)
+    };
+
+    tar::Archive::new(reader)
+        .unpack(dest)
+        .map_err(|e| HypeError::Execution(format!("Failed to extract package archive: {}", e)))
+}
+
+fn clone_repository(url: &str, reference: Option<&str>, dest: &Path) -> Result<()> {
+    let output = clone_command(url, reference, dest)
+        .output()
+        .map_err(|e| HypeError::Execution(format!("Failed to run git: {}", e)))?;
+    if !output.status.success() {
+        return Err(HypeError::Execution(format!(
+            "Failed to clone {}: {}",
+            url,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(())
+}
+
+fn clone_command(url: &str, reference: Option<&str>, dest: &Path) -> Command {
+    let mut command = Command::new("git");
+    command.args(["clone", "--depth", "1", "--quiet"]);
+    if let Some(reference) = reference {
+        command.args(["--branch", reference]);
+    }
+    // `--` keeps a URL such as `--upload-pack=...` from being read as an option
+    command.arg("--").arg(url).arg(dest);
+    command
+}
+
+/// Find the directory holding `hype.json`: `dir` itself, or the single
+/// top-level directory that archives such as GitHub tarballs wrap packages in.
+fn find_package_root(dir: &Path) -> Result<PathBuf> {
+    if dir.join("hype.json").is_file() {
+        return Ok(dir.to_path_buf());
+    }
+
+    let entries = fs::read_dir(dir)?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<std::io::Result<Vec<_>>>()?;
+    if let [only] = entries.as_slice() {
+        if only.is_dir() && only.join("hype.json").is_file() {
+            return Ok(only.clone());
+        }
+    }
+
+    Err(HypeError::Execution(
+        "No hype.json found in downloaded package".to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+
+    fn tarball(files: &[(&str, &str)]) -> Vec<u8> {
+        let mut builder = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+        for (path, content) in files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder
+                .append_data(&mut header, path, content.as_bytes())
+                .unwrap();
+        }
+        builder.into_inner().unwrap().finish().unwrap()
+    }
+
+    #[test]
+    fn test_parse_specs() {
+        assert_eq!(
+            PackageSource::parse("github.com/user/tool"),
+            PackageSource::Tarball("https://github.com/user/tool/archive/HEAD.tar.gz".to_string())
+        );
+        assert_eq!(
+            PackageSource::parse("https://github.com/user/tool.git#v1.2.0"),
+            PackageSource::Tarball(
+                "https://github.com/user/tool/archive/v1.2.0.tar.gz".to_string()
+            )
+        );
+        assert_eq!(
+            PackageSource::parse("git+https://git.example.com/tool.git#main"),
+            PackageSource::Git {
+                url: "https://git.example.com/tool.git".to_string(),
+                reference: Some("main".to_string()),
+            }
+        );
+        assert_eq!(
+            PackageSource::parse("https://example.com/tool-1.0.0.tar.gz"),
+            PackageSource::Tarball("https://example.com/tool-1.0.0.tar.gz".to_string())
+        );
+        assert_eq!(
+            PackageSource::parse("./my-package"),
+            PackageSource::Local(PathBuf::from("./my-package"))
+        );
+        assert!(!PackageSource::parse("github.com/user").is_remote());
+    }
+
+    #[test]
+    fn test_fetch_file_tarball_with_wrapper_directory() {
+        let temp = TempDir::new().unwrap();
+        let archive = temp.path().join("tool.tar.gz");
+        fs::write(
+            &archive,
+            tarball(&[
+                (
+                    "tool-main/hype.json",
+                    r#"{"name":"tool","version":"1.0.0"}"#,
+                ),
+                ("tool-main/bin/cli.lua", "print('hi')"),
+            ]),
+        )
+        .unwrap();
+
+        let url = url::Url::from_file_path(&archive).unwrap().to_string();
+        let (_dir, root) = PackageSource::parse(&url).fetch().unwrap();
+        assert!(root.ends_with("tool-main"));
+        assert!(root.join("bin/cli.lua").is_file());
+    }
+
+    #[test]
+    fn test_fetch_rejects_archive_without_manifest() {
+        let temp = TempDir::new().unwrap();
+        let archive = temp.path().join("empty.tar.gz");
+        fs::write(&archive, tarball(&[("README.md", "nothing here")])).unwrap();
+
+        let url = url::Url::from_file_path(&archive).unwrap().to_string();
+        let err = PackageSource::parse(&url).fetch().unwrap_err();
+        assert!(err.to_string().contains("No hype.json"), "{}", err);
+    }
+
+    #[test]
+    fn test_git_url_is_not_parsed_as_option() {
+        let url = "--upload-pack=touch pwned";
+        let command = clone_command(url, Some("main"), Path::new("checkout"));
+        let args: Vec<_> = command.get_args().collect();
+
+        let separator = args.iter().position(|arg| *arg == "--").unwrap();
+        assert_eq!(args[separator + 1], url);
+        assert_eq!(args.last().unwrap(), &"checkout");
+    }
+}
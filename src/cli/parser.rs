@@ -1,7 +1,10 @@
 use super::args::{ArgumentParser, ParsedArguments};
+use crate::engine::OutputFormat;
 use crate::file_io::validate_lua_file;
+use crate::lua::SandboxLevel;
 use clap::{Arg, Command};
 use std::path::PathBuf;
+use std::time::Duration;
 
 #[derive(Debug)]
 pub struct CliArgs {
@@ -10,9 +13,28 @@ pub struct CliArgs {
     pub parsed_args: ParsedArguments,
     pub verbose: bool,
     pub debug: bool,
-    pub timeout: Option<u64>,
+    pub timeout: Option<Duration>,
+    pub instruction_limit: Option<u32>,
+    pub stats_json: Option<PathBuf>,
+    pub output_events: bool,
+    pub output_format: OutputFormat,
+    pub allow_remote: bool,
+    pub integrity: Option<String>,
+    pub allow_subprocess: bool,
+    /// Security profile from `--sandbox` (or `--unsafe`).
+    pub sandbox: SandboxLevel,
+    /// Let `require` load modules by absolute path.
+    pub allow_absolute_requires: bool,
+    pub check: bool,
     pub show_help: bool,
     pub module: Option<String>,
+    /// Modules from `-r/--require`, loaded in order before the script.
+    pub preload: Vec<String>,
+    pub pretty_print: bool,
+    /// Re-run the script whenever it or a module it required changes.
+    pub watch: bool,
+    /// Set by `--args-as-table`: the flags that never take a separate value.
+    pub args_as_table: Option<Vec<String>>,
 }
 
 #[derive(Debug)]
@@ -28,12 +50,37 @@ pub enum HypeCommand {
         verbose: bool,
     },
     List {
+        filter: Option<String>,
+        outdated: bool,
         json: bool,
         verbose: bool,
     },
     Which {
         command: String,
     },
+    Verify {
+        name: Option<String>,
+        verbose: bool,
+    },
+    Upgrade {
+        name: Option<String>,
+        force: bool,
+        verbose: bool,
+    },
+    Lint {
+        paths: Vec<PathBuf>,
+        strict: bool,
+    },
+    Check {
+        path: PathBuf,
+        requires: bool,
+        verbose: bool,
+    },
+    Compile {
+        script: PathBuf,
+        output: Option<PathBuf>,
+        strip: bool,
+    },
     Agent,
 }
 
@@ -42,7 +89,7 @@ pub fn build_cli() -> Command {
         .about("Run a Lua script (default command)")
         .arg(
             Arg::new("script")
-                .help("The Lua script file to execute")
+                .help("The Lua script file or http(s) URL to execute")
                 .required_unless_present_any(&["help_args", "module"])
                 .value_parser(clap::value_parser!(PathBuf))
         )
@@ -73,12 +120,98 @@ pub fn build_cli() -> Command {
                 .help("Enable debug information")
                 .action(clap::ArgAction::SetTrue)
         )
+        .arg(
+            Arg::new("check")
+                .long("check")
+                .help("Check syntax of the script and its local requires without running it")
+                .action(clap::ArgAction::SetTrue)
+        )
         .arg(
             Arg::new("timeout")
                 .long("timeout")
-                .help("Script execution timeout in seconds")
-                .value_name("SECONDS")
-                .value_parser(clap::value_parser!(u64))
+                .help("Script execution timeout (e.g. 30, 30s, 500ms); plain numbers are seconds")
+                .value_name("DURATION")
+                .value_parser(parse_timeout)
+        )
+        .arg(
+            Arg::new("instruction_limit")
+                .long("instruction-limit")
+                .help("Stop the script after this many Lua VM instructions (ignored with --debug)")
+                .value_name("COUNT")
+                .value_parser(clap::value_parser!(u32).range(1..))
+        )
+        .arg(
+            Arg::new("stats_json")
+                .long("stats-json")
+                .help("Write execution stats as JSON to stderr, or to PATH with --stats-json=PATH")
+                .value_name("PATH")
+                .value_parser(clap::value_parser!(PathBuf))
+                .num_args(0..=1)
+                .require_equals(true)
+                .default_missing_value("-")
+                .conflicts_with("module")
+        )
+        .arg(
+            Arg::new("output_events")
+                .long("output-events")
+                .help("Emit output, errors and metrics as newline-delimited JSON events on stdout")
+                .action(clap::ArgAction::SetTrue)
+                .conflicts_with_all(["module", "check"])
+        )
+        .arg(
+            Arg::new("output_format")
+                .long("output-format")
+                .help("How captured script output is written: text, or json for one {stream, timestamp, line} record per line")
+                .value_name("FORMAT")
+                .value_parser(["text", "json"])
+                .conflicts_with_all(["module", "check", "output_events"])
+        )
+        .arg(
+            Arg::new("allow_remote")
+                .long("allow-remote")
+                .help("Allow running a script fetched from an http(s) URL")
+                .action(clap::ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("integrity")
+                .long("integrity")
+                .help("Require the script to match a hash, e.g. sha256-<base64>")
+                .value_name("HASH")
+        )
+        .arg(
+            Arg::new("allow_subprocess")
+                .long("allow-subprocess")
+                .help("Allow the script to run external commands via process.exec/spawn")
+                .action(clap::ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("sandbox")
+                .long("sandbox")
+                .help("Security profile: strict (no file writes, network or subprocesses), default, or unsafe (everything allowed)")
+                .value_name("LEVEL")
+                .value_parser(clap::value_parser!(SandboxLevel))
+        )
+        .arg(
+            Arg::new("unsafe")
+                .long("unsafe")
+                .help("Shorthand for --sandbox unsafe")
+                .action(clap::ArgAction::SetTrue)
+                .conflicts_with("sandbox")
+        )
+        .arg(
+            Arg::new("allow_absolute_requires")
+                .long("allow-absolute-requires")
+                .help("Allow require() to load modules by absolute path")
+                .action(clap::ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("args_as_table")
+                .long("args-as-table")
+                .help("Parse --key value and --flag script options into the _opts table; list flags that never take a value with --args-as-table=verbose,dry-run")
+                .value_name("BOOLEAN_FLAGS")
+                .num_args(0..=1)
+                .require_equals(true)
+                .default_missing_value("")
         )
         .arg(
             Arg::new("module")
@@ -86,13 +219,34 @@ pub fn build_cli() -> Command {
                 .long("module")
                 .help("Load and execute a Lua module")
                 .value_name("PATH")
+        )
+        .arg(
+            Arg::new("preload")
+                .short('r')
+                .long("require")
+                .help("Require a module before the script runs; repeat to preload several in order")
+                .value_name("MODULE")
+                .action(clap::ArgAction::Append)
+        )
+        .arg(
+            Arg::new("pretty_print")
+                .long("pretty-print")
+                .help("Show tables passed to print() with util.inspect instead of their address")
+                .action(clap::ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("watch")
+                .long("watch")
+                .help("Re-run the script when it or a module it requires changes")
+                .action(clap::ArgAction::SetTrue)
+                .conflicts_with_all(["module", "check"])
         );
 
     let install_cmd = Command::new("install")
         .about("Install a package globally")
         .arg(
             Arg::new("path")
-                .help("Path to package directory (defaults to current directory)")
+                .help("Package directory, git URL, github.com/user/repo or tarball URL (defaults to current directory)")
                 .value_parser(clap::value_parser!(PathBuf)),
         )
         .arg(
@@ -127,6 +281,13 @@ pub fn build_cli() -> Command {
 
     let list_cmd = Command::new("list")
         .about("List all globally installed packages")
+        .arg(Arg::new("filter").help("Only list packages whose name contains this text"))
+        .arg(
+            Arg::new("outdated")
+                .long("outdated")
+                .help("Mark packages whose source hype.json declares a newer version")
+                .action(clap::ArgAction::SetTrue),
+        )
         .arg(
             Arg::new("json")
                 .short('j')
@@ -150,6 +311,107 @@ pub fn build_cli() -> Command {
                 .required(true),
         );
 
+    let upgrade_cmd = Command::new("upgrade")
+        .about("Reinstall packages from their source directory when it has a newer version")
+        .arg(
+            Arg::new("name")
+                .help("Package to upgrade")
+                .required_unless_present("all"),
+        )
+        .arg(
+            Arg::new("all")
+                .long("all")
+                .help("Upgrade every package installed from a local directory")
+                .action(clap::ArgAction::SetTrue)
+                .conflicts_with("name"),
+        )
+        .arg(
+            Arg::new("force")
+                .short('f')
+                .long("force")
+                .help("Reinstall even if the source version is not newer")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("verbose")
+                .short('v')
+                .long("verbose")
+                .help("Enable verbose output")
+                .action(clap::ArgAction::SetTrue),
+        );
+
+    let verify_cmd = Command::new("verify")
+        .about("Check installed bin scripts against their install-time checksums")
+        .arg(Arg::new("name").help("Package to verify (defaults to all packages)"))
+        .arg(
+            Arg::new("verbose")
+                .short('v')
+                .long("verbose")
+                .help("Enable verbose output")
+                .action(clap::ArgAction::SetTrue),
+        );
+
+    let lint_cmd = Command::new("lint")
+        .about("Check Lua scripts for common mistakes")
+        .arg(
+            Arg::new("paths")
+                .help("Lua files to lint")
+                .value_parser(clap::value_parser!(PathBuf))
+                .num_args(1..)
+                .required(true),
+        )
+        .arg(
+            Arg::new("strict")
+                .long("strict")
+                .help("Report globals denied by the sandbox security policy as errors")
+                .action(clap::ArgAction::SetTrue),
+        );
+
+    let check_cmd = Command::new("check")
+        .about("Validate a script and the local modules it requires without running it")
+        .arg(
+            Arg::new("path")
+                .help("Lua script to check")
+                .value_parser(clap::value_parser!(PathBuf))
+                .required(true),
+        )
+        .arg(
+            Arg::new("requires")
+                .long("requires")
+                .help("Also report require() targets that do not resolve")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("verbose")
+                .short('v')
+                .long("verbose")
+                .help("List each file as it passes")
+                .action(clap::ArgAction::SetTrue),
+        );
+
+    let compile_cmd = Command::new("compile")
+        .about("Precompile a Lua script to bytecode so runs skip parsing")
+        .arg(
+            Arg::new("script")
+                .help("Lua script to compile")
+                .value_parser(clap::value_parser!(PathBuf))
+                .required(true),
+        )
+        .arg(
+            Arg::new("output")
+                .short('o')
+                .long("output")
+                .help("Where to write the bytecode (defaults to the script path with a .luac extension)")
+                .value_name("PATH")
+                .value_parser(clap::value_parser!(PathBuf)),
+        )
+        .arg(
+            Arg::new("strip")
+                .long("strip")
+                .help("Drop debug information; errors will no longer report line numbers")
+                .action(clap::ArgAction::SetTrue),
+        );
+
     let agent_cmd = Command::new("agent")
         .about("Output machine-readable documentation for LLM agents")
         .hide(true);
@@ -168,6 +430,11 @@ pub fn build_cli() -> Command {
         .subcommand(uninstall_cmd)
         .subcommand(list_cmd)
         .subcommand(which_cmd)
+        .subcommand(upgrade_cmd)
+        .subcommand(verify_cmd)
+        .subcommand(lint_cmd)
+        .subcommand(check_cmd)
+        .subcommand(compile_cmd)
         .subcommand(agent_cmd)
         .arg(
             Arg::new("script")
@@ -194,13 +461,23 @@ pub fn build_cli() -> Command {
             "Examples:\n\
    hype script.lua arg1 arg2          # Run a Lua script (backward compatible)\n\
    hype run script.lua arg1 arg2      # Run with explicit subcommand\n\
+   hype run --watch app.lua           # Re-run when app.lua or its modules change\n\
+   hype run --sandbox strict app.lua  # Run without file writes, network or subprocesses\n\
    hype install                       # Install package from current directory\n\
    hype install ./my-package          # Install package from path\n\
+   hype install github.com/user/repo  # Install package from GitHub\n\
    hype install --force               # Force reinstall\n\
    hype uninstall my-package          # Uninstall a package\n\
    hype list                          # List installed packages\n\
    hype list --json                   # List in JSON format\n\
+   hype list http --outdated          # Find outdated packages matching 'http'\n\
    hype which mycli                   # Show which package provides 'mycli'\n\
+   hype upgrade my-package            # Reinstall if its source has a newer version\n\
+   hype upgrade --all                 # Upgrade every locally installed package\n\
+   hype verify                        # Detect modified installed scripts\n\
+   hype lint script.lua --strict      # Lint a script for common mistakes\n\
+   hype check script.lua --requires   # Check syntax and require targets without running\n\
+   hype compile script.lua            # Write script.luac, run with `hype script.luac`\n\
    hype --version                     # Show version\n\
    hype --help                        # Show this help"
         )
@@ -235,9 +512,16 @@ pub fn parse_args() -> Result<HypeCommand, String> {
             Ok(HypeCommand::Uninstall { name, verbose })
         }
         Some(("list", sub_matches)) => {
+            let filter = sub_matches.get_one::<String>("filter").cloned();
+            let outdated = sub_matches.get_flag("outdated");
             let json = sub_matches.get_flag("json");
             let verbose = sub_matches.get_flag("verbose");
-            Ok(HypeCommand::List { json, verbose })
+            Ok(HypeCommand::List {
+                filter,
+                outdated,
+                json,
+                verbose,
+            })
         }
         Some(("which", sub_matches)) => {
             let command = sub_matches
@@ -246,6 +530,55 @@ pub fn parse_args() -> Result<HypeCommand, String> {
                 .clone();
             Ok(HypeCommand::Which { command })
         }
+        Some(("upgrade", sub_matches)) => {
+            let name = sub_matches.get_one::<String>("name").cloned();
+            let force = sub_matches.get_flag("force");
+            let verbose = sub_matches.get_flag("verbose");
+            Ok(HypeCommand::Upgrade {
+                name,
+                force,
+                verbose,
+            })
+        }
+        Some(("verify", sub_matches)) => {
+            let name = sub_matches.get_one::<String>("name").cloned();
+            let verbose = sub_matches.get_flag("verbose");
+            Ok(HypeCommand::Verify { name, verbose })
+        }
+        Some(("lint", sub_matches)) => {
+            let paths = sub_matches
+                .get_many::<PathBuf>("paths")
+                .map(|values| values.cloned().collect())
+                .unwrap_or_default();
+            let strict = sub_matches.get_flag("strict");
+            Ok(HypeCommand::Lint { paths, strict })
+        }
+        Some(("check", sub_matches)) => {
+            let path = sub_matches
+                .get_one::<PathBuf>("path")
+                .ok_or("Script path is required")?
+                .clone();
+            let requires = sub_matches.get_flag("requires");
+            let verbose = sub_matches.get_flag("verbose");
+            Ok(HypeCommand::Check {
+                path,
+                requires,
+                verbose,
+            })
+        }
+        Some(("compile", sub_matches)) => {
+            let script = sub_matches
+                .get_one::<PathBuf>("script")
+                .ok_or("Script path is required")?
+                .clone();
+            let output = sub_matches.get_one::<PathBuf>("output").cloned();
+            let strip = sub_matches.get_flag("strip");
+            Ok(HypeCommand::Compile {
+                script,
+                output,
+                strip,
+            })
+        }
         Some(("agent", _)) => Ok(HypeCommand::Agent),
         None => {
             if let Some(_script_path) = matches.get_one::<PathBuf>("script") {
@@ -272,15 +605,92 @@ fn parse_run_args(matches: &clap::ArgMatches) -> Result<CliArgs, String> {
         false
     };
     let timeout = if matches.contains_id("timeout") {
-        matches.get_one::<u64>("timeout").copied()
+        matches.get_one::<Duration>("timeout").copied()
+    } else {
+        None
+    };
+    let instruction_limit = if matches.contains_id("instruction_limit") {
+        matches.get_one::<u32>("instruction_limit").copied()
     } else {
         None
     };
+    let stats_json = if matches.contains_id("stats_json") {
+        matches.get_one::<PathBuf>("stats_json").cloned()
+    } else {
+        None
+    };
+    let output_events = if matches.contains_id("output_events") {
+        matches.get_flag("output_events")
+    } else {
+        false
+    };
+    let output_format = match matches.try_get_one::<String>("output_format") {
+        Ok(Some(format)) => format.parse()?,
+        _ => OutputFormat::Text,
+    };
+    let allow_remote = if matches.contains_id("allow_remote") {
+        matches.get_flag("allow_remote")
+    } else {
+        false
+    };
+    let integrity = if matches.contains_id("integrity") {
+        matches.get_one::<String>("integrity").cloned()
+    } else {
+        None
+    };
+    let allow_subprocess = if matches.contains_id("allow_subprocess") {
+        matches.get_flag("allow_subprocess")
+    } else {
+        false
+    };
+    let sandbox = match matches.try_get_one::<SandboxLevel>("sandbox") {
+        Ok(Some(level)) => *level,
+        _ if matches.contains_id("unsafe") && matches.get_flag("unsafe") => SandboxLevel::Unsafe,
+        _ => SandboxLevel::Default,
+    };
+    if sandbox == SandboxLevel::Strict && allow_subprocess {
+        return Err("--allow-subprocess cannot be used with --sandbox strict".to_string());
+    }
+    let allow_absolute_requires = if matches.contains_id("allow_absolute_requires") {
+        matches.get_flag("allow_absolute_requires")
+    } else {
+        false
+    };
+    let check = if matches.contains_id("check") {
+        matches.get_flag("check")
+    } else {
+        false
+    };
     let module = if matches.contains_id("module") {
         matches.get_one::<String>("module").cloned()
     } else {
         None
     };
+    let pretty_print = if matches.contains_id("pretty_print") {
+        matches.get_flag("pretty_print")
+    } else {
+        false
+    };
+    let watch = if matches.contains_id("watch") {
+        matches.get_flag("watch")
+    } else {
+        false
+    };
+    let preload = match matches.try_get_many::<String>("preload") {
+        Ok(Some(modules)) => modules.cloned().collect(),
+        _ => Vec::new(),
+    };
+    let args_as_table = match matches.try_get_one::<String>("args_as_table") {
+        Ok(Some(names)) => Some(
+            names
+                .split(',')
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .map(String::from)
+                .collect::<Vec<_>>(),
+        ),
+        _ => None,
+    };
 
     if show_help {
         let script = PathBuf::from("script.lua");
@@ -295,23 +705,32 @@ fn parse_run_args(matches: &clap::ArgMatches) -> Result<CliArgs, String> {
             verbose,
             debug,
             timeout,
+            instruction_limit,
+            stats_json,
+            output_events,
+            output_format,
+            allow_remote,
+            integrity,
+            allow_subprocess,
+            sandbox,
+            allow_absolute_requires,
+            check,
             show_help,
             module,
+            preload,
+            pretty_print,
+            watch,
+            args_as_table,
         });
     }
 
     let script = if let Some(ref module_path) = module {
         PathBuf::from(module_path)
     } else {
-        let script_path = matches
+        matches
             .get_one::<PathBuf>("script")
             .ok_or("Script file is required unless using --module")?
-            .clone();
-
-        if let Err(e) = validate_lua_file(&script_path) {
-            return Err(format!("Script validation failed: {}", e));
-        }
-        script_path
+            .clone()
     };
 
     let script_args = matches
@@ -320,7 +739,10 @@ fn parse_run_args(matches: &clap::ArgMatches) -> Result<CliArgs, String> {
         .cloned()
         .collect::<Vec<String>>();
 
-    let arg_parser = ArgumentParser::new();
+    let arg_parser = match &args_as_table {
+        Some(boolean_flags) => ArgumentParser::new().with_separate_values(boolean_flags.clone()),
+        None => ArgumentParser::new(),
+    };
     let parsed_args = arg_parser.parse(&script, &script_args);
 
     Ok(CliArgs {
@@ -330,8 +752,22 @@ fn parse_run_args(matches: &clap::ArgMatches) -> Result<CliArgs, String> {
         verbose,
         debug,
         timeout,
+        instruction_limit,
+        stats_json,
+        output_events,
+        output_format,
+        allow_remote,
+        integrity,
+        allow_subprocess,
+        sandbox,
+        allow_absolute_requires,
+        check,
         show_help,
         module,
+        preload,
+        pretty_print,
+        watch,
+        args_as_table,
     })
 }
 
@@ -343,10 +779,6 @@ fn parse_run_args_compat(matches: &clap::ArgMatches) -> Result<CliArgs, String>
         .ok_or("Script file is required")?
         .clone();
 
-    if let Err(e) = validate_lua_file(&script_path) {
-        return Err(format!("Script validation failed: {}", e));
-    }
-
     let script_args = matches
         .get_many::<String>("script_args")
         .unwrap_or_default()
@@ -363,11 +795,210 @@ fn parse_run_args_compat(matches: &clap::ArgMatches) -> Result<CliArgs, String>
         verbose,
         debug: false,
         timeout: None,
+        instruction_limit: None,
+        stats_json: None,
+        output_events: false,
+        output_format: OutputFormat::Text,
+        allow_remote: false,
+        integrity: None,
+        allow_subprocess: false,
+        sandbox: SandboxLevel::Default,
+        allow_absolute_requires: false,
+        check: false,
         show_help: false,
         module: None,
+        preload: Vec::new(),
+        pretty_print: false,
+        watch: false,
+        args_as_table: None,
     })
 }
 
+/// Parses a `--timeout` value such as `500ms`, `2s` or `30` (seconds).
+pub fn parse_timeout(value: &str) -> Result<Duration, String> {
+    let value = value.trim();
+    let (number, to_duration): (&str, fn(u64) -> Duration) =
+        if let Some(ms) = value.strip_suffix("ms") {
+            (ms, Duration::from_millis)
+        } else if let Some(secs) = value.strip_suffix('s') {
+            (secs, Duration::from_secs)
+        } else {
+            (value, Duration::from_secs)
+        };
+
+    let amount = number.trim().parse::<u64>().map_err(|_| {
+        format!(
+            "Invalid timeout '{}': expected e.g. 30, 30s or 500ms",
+            value
+        )
+    })?;
+
+    if amount == 0 {
+        return Err("Timeout must be greater than zero".to_string());
+    }
+
+    Ok(to_duration(amount))
+}
+
 pub fn validate_script_file(path: &PathBuf) -> Result<(), String> {
     validate_lua_file(path).map_err(|e| e.to_string())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_timeout_units() {
+        assert_eq!(parse_timeout("500ms"), Ok(Duration::from_millis(500)));
+        assert_eq!(parse_timeout("2s"), Ok(Duration::from_secs(2)));
+        assert_eq!(parse_timeout("30"), Ok(Duration::from_secs(30)));
+    }
+
+    #[test]
+    fn test_parse_timeout_rejects_invalid_values() {
+        assert!(parse_timeout("").is_err());
+        assert!(parse_timeout("0").is_err());
+        assert!(parse_timeout("-5s").is_err());
+        assert!(parse_timeout("1.5s").is_err());
+        assert!(parse_timeout("10m").is_err());
+    }
+
+    #[test]
+    fn test_run_timeout_flag() {
+        let matches = build_cli()
+            .try_get_matches_from(["hype", "run", "script.lua", "--timeout", "250ms"])
+            .unwrap();
+        let (_, run_matches) = matches.subcommand().unwrap();
+
+        assert_eq!(
+            run_matches.get_one::<Duration>("timeout"),
+            Some(&Duration::from_millis(250))
+        );
+    }
+
+    #[test]
+    fn test_run_stats_json_flag() {
+        let matches = build_cli()
+            .try_get_matches_from(["hype", "run", "--stats-json", "script.lua"])
+            .unwrap();
+        let (_, run_matches) = matches.subcommand().unwrap();
+        assert_eq!(
+            run_matches.get_one::<PathBuf>("stats_json"),
+            Some(&PathBuf::from("-"))
+        );
+        assert_eq!(
+            run_matches.get_one::<PathBuf>("script"),
+            Some(&PathBuf::from("script.lua"))
+        );
+
+        let matches = build_cli()
+            .try_get_matches_from(["hype", "run", "script.lua", "--stats-json=out.json"])
+            .unwrap();
+        let (_, run_matches) = matches.subcommand().unwrap();
+        assert_eq!(
+            run_matches.get_one::<PathBuf>("stats_json"),
+            Some(&PathBuf::from("out.json"))
+        );
+    }
+
+    #[test]
+    fn test_run_timeout_flag_rejects_invalid_value() {
+        let result =
+            build_cli().try_get_matches_from(["hype", "run", "script.lua", "--timeout", "soon"]);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_run_watch_flag() {
+        let matches = build_cli()
+            .try_get_matches_from(["hype", "run", "--watch", "app.lua"])
+            .unwrap();
+        let (_, run_matches) = matches.subcommand().unwrap();
+        assert!(parse_run_args(run_matches).unwrap().watch);
+
+        let result =
+            build_cli().try_get_matches_from(["hype", "run", "--watch", "--check", "a.lua"]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_run_allow_absolute_requires_flag() {
+        let matches = build_cli()
+            .try_get_matches_from(["hype", "run", "app.lua"])
+            .unwrap();
+        let (_, run_matches) = matches.subcommand().unwrap();
+        assert!(!parse_run_args(run_matches).unwrap().allow_absolute_requires);
+
+        let matches = build_cli()
+            .try_get_matches_from(["hype", "run", "--allow-absolute-requires", "app.lua"])
+            .unwrap();
+        let (_, run_matches) = matches.subcommand().unwrap();
+        assert!(parse_run_args(run_matches).unwrap().allow_absolute_requires);
+    }
+
+    #[test]
+    fn test_run_sandbox_flag() {
+        let sandbox = |args: &[&str]| {
+            let matches = build_cli().try_get_matches_from(args).unwrap();
+            let (_, run_matches) = matches.subcommand().unwrap();
+            parse_run_args(run_matches).map(|args| args.sandbox)
+        };
+        assert_eq!(
+            sandbox(&["hype", "run", "a.lua"]),
+            Ok(SandboxLevel::Default)
+        );
+        assert_eq!(
+            sandbox(&["hype", "run", "--sandbox", "strict", "a.lua"]),
+            Ok(SandboxLevel::Strict)
+        );
+        assert_eq!(
+            sandbox(&["hype", "run", "--unsafe", "a.lua"]),
+            Ok(SandboxLevel::Unsafe)
+        );
+        assert!(sandbox(&[
+            "hype",
+            "run",
+            "--sandbox",
+            "strict",
+            "--allow-subprocess",
+            "a.lua"
+        ])
+        .is_err());
+
+        for args in [
+            &["hype", "run", "--sandbox", "loose", "a.lua"][..],
+            &["hype", "run", "--sandbox", "strict", "--unsafe", "a.lua"][..],
+        ] {
+            assert!(build_cli().try_get_matches_from(args).is_err());
+        }
+    }
+
+    #[test]
+    fn test_lint_subcommand() {
+        let matches = build_cli()
+            .try_get_matches_from(["hype", "lint", "a.lua", "b.lua", "--strict"])
+            .unwrap();
+        let (name, lint_matches) = matches.subcommand().unwrap();
+        assert_eq!(name, "lint");
+        let paths: Vec<&PathBuf> = lint_matches.get_many::<PathBuf>("paths").unwrap().collect();
+        assert_eq!(paths, [&PathBuf::from("a.lua"), &PathBuf::from("b.lua")]);
+        assert!(lint_matches.get_flag("strict"));
+    }
+
+    #[test]
+    fn test_check_subcommand() {
+        let matches = build_cli()
+            .try_get_matches_from(["hype", "check", "main.lua", "--requires"])
+            .unwrap();
+        let (name, check_matches) = matches.subcommand().unwrap();
+        assert_eq!(name, "check");
+        assert_eq!(
+            check_matches.get_one::<PathBuf>("path"),
+            Some(&PathBuf::from("main.lua"))
+        );
+        assert!(check_matches.get_flag("requires"));
+        assert!(!check_matches.get_flag("verbose"));
+    }
+}
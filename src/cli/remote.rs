@@ -0,0 +1,214 @@
+use crate::error::{HypeError, Result};
+use crate::modules::builtins::crypto;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+const SUPPORTED_INTEGRITY_ALGORITHMS: [&str; 2] = ["sha256", "sha512"];
+
+pub fn is_remote_script(script: &Path) -> bool {
+    script
+        .to_str()
+        .map(|s| s.starts_with("http://") || s.starts_with("https://"))
+        .unwrap_or(false)
+}
+
+/// Verify content against an integrity string such as `sha256-<base64>`.
+///
+/// The digest may be given in base64 (Subresource Integrity format) or hex.
+pub fn verify_integrity(content: &[u8], integrity: &str) -> Result<()> {
+    let (algorithm, expected) = integrity.trim().split_once('-').ok_or_else(|| {
+        HypeError::Config(format!(
+            "Invalid integrity '{}': expected <algorithm>-<digest>, e.g. sha256-...",
+            integrity
+        ))
+    })?;
+
+    let algorithm = algorithm.to_lowercase();
+    if !SUPPORTED_INTEGRITY_ALGORITHMS.contains(&algorithm.as_str()) {
+        return Err(HypeError::Config(format!(
+            "Unsupported integrity algorithm '{}'. Supported: {}",
+            algorithm,
+            SUPPORTED_INTEGRITY_ALGORITHMS.join(", ")
+        )));
+    }
+
+    let actual_hex =
+        crypto::hash(&algorithm, content).map_err(|e| HypeError::Config(e.to_string()))?;
+    let actual_bytes =
+        crypto::hex_decode(&actual_hex).map_err(|e| HypeError::Config(e.to_string()))?;
+    let actual_base64 = crypto::base64_encode(&actual_bytes);
+
+    let matches = crypto::timing_safe_equal(expected.as_bytes(), actual_base64.as_bytes())
+        || crypto::timing_safe_equal(expected.to_lowercase().as_bytes(), actual_hex.as_bytes());
+
+    if !matches {
+        return Err(HypeError::Security(format!(
+            "Integrity check failed: expected {}, got {}-{}",
+            integrity, algorithm, actual_base64
+        )));
+    }
+
+    Ok(())
+}
+
+/// Download a remote script into a temporary directory, verifying its integrity if requested.
+///
+/// The returned `TempDir` must be kept alive while the script runs.
+pub fn download_script(url: &str, integrity: Option<&str>) -> Result<(TempDir, PathBuf)> {
+    let (served, headers) = fetch_script(url)?;
+
+    // The digest covers the bytes exactly as served; only then are they
+    // decoded and turned into text
+    if let Some(integrity) = integrity {
+        verify_integrity(&served, integrity)?;
+    }
+    let content = decode_script(&served, &headers)?;
+
+    let file_name = url::Url::parse(url)
+        .ok()
+        .and_then(|u| {
+            u.path_segments()
+                .and_then(|mut segments| segments.next_back().map(str::to_string))
+        })
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| "remote.lua".to_string());
+    let file_name = if file_name.ends_with(".lua") {
+        file_name
+    } else {
+        format!("{}.lua", file_name)
+    };
+
+    let dir = TempDir::new().map_err(HypeError::Io)?;
+    let path = dir.path().join(file_name);
+    fs::write(&path, content).map_err(HypeError::Io)?;
+
+    Ok((dir, path))
+}
+
+/// Fetch the script body without decoding it, along with the response headers.
+/// Asking for `identity` means the served bytes are normally the script itself.
+#[cfg(feature = "http")]
+fn fetch_script(url: &str) -> Result<(Vec<u8>, HashMap<String, String>)> {
+    let client = crate::modules::builtins::http::HttpClient::new()
+        .map_err(|e| HypeError::Execution(format!("Failed to create HTTP client: {}", e)))?;
+    client.set_default_headers(HashMap::from([(
+        "Accept-Encoding".to_string(),
+        "identity".to_string(),
+    )]));
+    let response = client
+        .get(url)
+        .map_err(|e| HypeError::Execution(format!("Failed to fetch {}: {}", url, e)))?;
+
+    if !(200..300).contains(&response.status) {
+        return Err(HypeError::Execution(format!(
+            "Failed to fetch {}: HTTP {} {}",
+            url, response.status, response.status_text
+        )));
+    }
+
+    Ok((response.raw_body, response.headers))
+}
+
+#[cfg(feature = "http")]
+fn decode_script(served: &[u8], headers: &HashMap<String, String>) -> Result<String> {
+    let body = crate::modules::builtins::http::response::decode_body(headers, served)
+        .map_err(|e| HypeError::Execution(e.to_string()))?;
+    Ok(String::from_utf8_lossy(&body).into_owned())
+}
+
+#[cfg(not(feature = "http"))]
+fn decode_script(served: &[u8], _headers: &HashMap<String, String>) -> Result<String> {
+    Ok(String::from_utf8_lossy(served).into_owned())
+}
+
+#[cfg(not(feature = "http"))]
+fn fetch_script(url: &str) -> Result<(Vec<u8>, HashMap<String, String>)> {
+    Err(HypeError::Execution(format!(
+        "Cannot fetch {}: HTTP feature not enabled. Compile with --features http",
+        url
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCRIPT: &[u8] = b"print('hello')";
+
+    fn sha256_base64(content: &[u8]) -> String {
+        let hex = crypto::hash("sha256", content).unwrap();
+        crypto::base64_encode(&crypto::hex_decode(&hex).unwrap())
+    }
+
+    #[test]
+    fn test_is_remote_script() {
+        assert!(is_remote_script(Path::new("https://example.com/tool.lua")));
+        assert!(is_remote_script(Path::new("http://localhost:8080/x.lua")));
+        assert!(!is_remote_script(Path::new("scripts/tool.lua")));
+    }
+
+    #[test]
+    fn test_verify_integrity_accepts_base64_and_hex() {
+        let sri = format!("sha256-{}", sha256_base64(SCRIPT));
+        assert!(verify_integrity(SCRIPT, &sri).is_ok());
+
+        let hex = format!("sha256-{}", crypto::hash("sha256", SCRIPT).unwrap());
+        assert!(verify_integrity(SCRIPT, &hex).is_ok());
+    }
+
+    #[test]
+    fn test_verify_integrity_rejects_mismatch() {
+        let sri = format!("sha256-{}", sha256_base64(b"print('other')"));
+        let err = verify_integrity(SCRIPT, &sri).unwrap_err();
+        assert!(matches!(err, HypeError::Security(_)));
+    }
+
+    /// Serve `body` once over HTTP and return its URL.
+    #[cfg(feature = "http")]
+    fn serve_once(body: &'static [u8]) -> String {
+        use std::io::{Read, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0; 4096];
+            let _ = stream.read(&mut request);
+            let head = format!(
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                body.len()
+            );
+            stream.write_all(head.as_bytes()).unwrap();
+            stream.write_all(body).unwrap();
+        });
+        format!("http://{}/latin1.lua", addr)
+    }
+
+    #[cfg(feature = "http")]
+    #[test]
+    fn test_integrity_covers_non_utf8_bytes_as_served() {
+        const LATIN1: &[u8] = b"-- caf\xe9 au lait\nprint('ok')\n";
+        let sri = format!("sha256-{}", sha256_base64(LATIN1));
+
+        let (_dir, path) = download_script(&serve_once(LATIN1), Some(&sri)).unwrap();
+        assert!(fs::read_to_string(path).unwrap().contains("print('ok')"));
+    }
+
+    #[test]
+    fn test_verify_integrity_rejects_malformed_values() {
+        assert!(matches!(
+            verify_integrity(SCRIPT, "not-a-real-hash"),
+            Err(HypeError::Config(_))
+        ));
+        assert!(matches!(
+            verify_integrity(SCRIPT, "sha256"),
+            Err(HypeError::Config(_))
+        ));
+        assert!(matches!(
+            verify_integrity(SCRIPT, "md5-abc"),
+            Err(HypeError::Config(_))
+        ));
+    }
+}
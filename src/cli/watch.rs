@@ -0,0 +1,116 @@
+use crate::error::HypeError;
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver};
+use std::time::{Duration, Instant};
+
+/// How long to wait for an editor to finish saving before re-running.
+pub const WATCH_DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// Waits for any of a set of files to change.
+///
+/// The directories holding the files are watched rather than the files
+/// themselves, because many editors save by writing a new file and renaming
+/// it over the old one, which would end a watch on the original file.
+pub struct ScriptWatcher {
+    _watcher: RecommendedWatcher,
+    events: Receiver<notify::Result<Event>>,
+    files: HashSet<PathBuf>,
+}
+
+impl ScriptWatcher {
+    pub fn new(files: &[PathBuf]) -> Result<Self, HypeError> {
+        let (sender, events) = channel();
+        let mut watcher = notify::recommended_watcher(move |event| {
+            let _ = sender.send(event);
+        })
+        .map_err(watch_error)?;
+
+        let files: HashSet<PathBuf> = files.iter().map(|file| absolute(file)).collect();
+        let dirs: HashSet<&Path> = files.iter().filter_map(|file| file.parent()).collect();
+        for dir in dirs {
+            watcher
+                .watch(dir, RecursiveMode::NonRecursive)
+                .map_err(watch_error)?;
+        }
+
+        Ok(Self {
+            _watcher: watcher,
+            events,
+            files,
+        })
+    }
+
+    /// Number of files being watched.
+    pub fn file_count(&self) -> usize {
+        self.files.len()
+    }
+
+    /// Block until a watched file changes, then keep absorbing events until
+    /// none arrive for `debounce`, so a burst of saves triggers one re-run.
+    /// Returns the changed files.
+    pub fn wait_for_change(&self, debounce: Duration) -> Result<Vec<PathBuf>, HypeError> {
+        let mut changed = Vec::new();
+        while changed.is_empty() {
+            let event = self
+                .events
+                .recv()
+                .map_err(|_| HypeError::Execution("file watcher stopped".to_string()))?;
+            self.collect(event, &mut changed)?;
+        }
+
+        let mut quiet_until = Instant::now() + debounce;
+        while let Some(remaining) = quiet_until.checked_duration_since(Instant::now()) {
+            match self.events.recv_timeout(remaining) {
+                Ok(event) => {
+                    if self.collect(event, &mut changed)? {
+                        quiet_until = Instant::now() + debounce;
+                    }
+                }
+                Err(_) => break,
+            }
+        }
+        Ok(changed)
+    }
+
+    /// Add the watched files named by `event` to `changed`, returning whether
+    /// there were any.
+    fn collect(
+        &self,
+        event: notify::Result<Event>,
+        changed: &mut Vec<PathBuf>,
+    ) -> Result<bool, HypeError> {
+        let event = event.map_err(watch_error)?;
+        if matches!(event.kind, EventKind::Access(_)) {
+            return Ok(false);
+        }
+        let mut found = false;
+        for path in event.paths {
+            if self.files.contains(&path) {
+                found = true;
+                if !changed.contains(&path) {
+                    changed.push(path);
+                }
+            }
+        }
+        Ok(found)
+    }
+}
+
+/// Make `path` canonical, as the paths in notify events start with the
+/// canonical directory being watched.
+fn absolute(path: &Path) -> PathBuf {
+    let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    if path.is_absolute() {
+        path
+    } else {
+        std::env::current_dir()
+            .map(|cwd| cwd.join(&path))
+            .unwrap_or(path)
+    }
+}
+
+fn watch_error(err: notify::Error) -> HypeError {
+    HypeError::Execution(format!("Cannot watch files: {}", err))
+}
@@ -3,16 +3,17 @@ use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 
-use crate::cli::args::{create_standard_arg_table, ParsedArguments};
-use crate::engine::output::{OutputCapture, OutputFormat};
+use crate::cli::args::{create_opts_table, create_standard_arg_table, ParsedArguments};
+use crate::engine::output::{OutputCapture, OutputFormat, StdoutRedirect};
 use crate::engine::stats::{ExecutionStats, StatsCollector};
 use crate::engine::timeout::{TimeoutError, TimeoutManager};
 use crate::error::{HypeError, Result};
-use crate::file_io::read_lua_script;
 use crate::lua::{
-    create_cli_config, create_cli_security_policy, setup_require_fn, LuaStateConfig,
-    LuaStateManager, SecurityPolicy,
+    create_cli_config, create_cli_security_policy, format_print_line, preload_modules,
+    setup_require_fn, LuaStateConfig, LuaStateManager, LuaStateMetrics, SandboxLevel,
+    SecurityPolicy,
 };
+use crate::modules::builtins::process::{run_exit_hooks, ScriptArgv};
 use crate::modules::loader::ModuleLoader;
 
 #[derive(Debug, Clone)]
@@ -25,6 +26,7 @@ pub struct ExecutionConfig {
     pub timeout: Option<Duration>,
     pub capture_output: bool,
     pub output_format: OutputFormat,
+    pub output_events: bool,
     pub enable_stats: bool,
     pub memory_limit: Option<usize>,
     pub instruction_limit: Option<u32>,
@@ -32,6 +34,17 @@ pub struct ExecutionConfig {
     pub allow_os_operations: bool,
     pub allow_debug_operations: bool,
     pub allow_package_loading: bool,
+    pub allow_subprocess: bool,
+    /// Security profile the script runs under.
+    pub sandbox: SandboxLevel,
+    /// Let `require` load modules by absolute path.
+    pub allow_absolute_requires: bool,
+    /// Expose named arguments and flags as the `_opts` global.
+    pub args_as_table: bool,
+    /// Modules required, in order, before the script runs.
+    pub preload: Vec<String>,
+    /// Show tables passed to `print` with `util.inspect`.
+    pub pretty_print: bool,
 }
 
 impl Default for ExecutionConfig {
@@ -45,6 +58,7 @@ impl Default for ExecutionConfig {
             timeout: Some(Duration::from_secs(30)),
             capture_output: true,
             output_format: OutputFormat::Text,
+            output_events: false,
             enable_stats: false,
             memory_limit: Some(64 * 1024 * 1024), // 64MB
             instruction_limit: Some(1_000_000),   // 1M instructions
@@ -52,6 +66,12 @@ impl Default for ExecutionConfig {
             allow_os_operations: false,
             allow_debug_operations: false,
             allow_package_loading: false,
+            allow_subprocess: false,
+            sandbox: SandboxLevel::Default,
+            allow_absolute_requires: false,
+            args_as_table: false,
+            preload: Vec::new(),
+            pretty_print: false,
         }
     }
 }
@@ -65,6 +85,9 @@ pub struct ExecutionResult {
     pub stats: Option<ExecutionStats>,
     pub execution_time: Duration,
     pub error: Option<String>,
+    pub metrics: Option<LuaStateMetrics>,
+    /// Files of the modules the script required, for `--watch`.
+    pub module_paths: Vec<PathBuf>,
 }
 
 impl Default for ExecutionResult {
@@ -77,6 +100,8 @@ impl Default for ExecutionResult {
             stats: None,
             execution_time: Duration::ZERO,
             error: None,
+            metrics: None,
+            module_paths: Vec::new(),
         }
     }
 }
@@ -90,7 +115,10 @@ pub struct ExecutionEngine {
 
 impl ExecutionEngine {
     pub fn new(config: ExecutionConfig) -> Result<Self> {
-        let output_capture = Arc::new(Mutex::new(OutputCapture::new(config.capture_output)));
+        let mut capture = OutputCapture::new(config.capture_output);
+        capture.set_stream_events(config.output_events);
+        capture.set_record_lines(config.output_format == OutputFormat::Json);
+        let output_capture = Arc::new(Mutex::new(capture));
         let timeout_manager = TimeoutManager::new(config.timeout);
         let stats_collector = StatsCollector::new(config.enable_stats);
 
@@ -126,7 +154,33 @@ impl ExecutionEngine {
         }
 
         // Execute the script
-        let execution_result = self.execute_script_internal();
+        let execution_result = self.create_state_manager().and_then(|state_manager| {
+            if let Some(handle) = &timeout_handle {
+                handle
+                    .deadline()
+                    .install(&state_manager.lua.lock().unwrap());
+            }
+            let loader = self.create_module_loader()?;
+            let outcome = self.execute_script_internal(&state_manager, &loader);
+            result.metrics = Some(state_manager.get_metrics());
+            result.module_paths = loader.lock().unwrap().module_paths().to_vec();
+            let exit_code = if outcome.is_ok() { 0 } else { 1 };
+            run_exit_hooks(&state_manager.lua.lock().unwrap(), exit_code);
+            outcome
+        });
+
+        // A native call cut short by the watchdog may fail with its own error
+        // (an HTTP timeout, say); report it as the script timing out.
+        let execution_result = match (execution_result, &timeout_handle) {
+            (Err(e), Some(handle))
+                if handle.deadline().is_expired() && !e.to_string().contains("timed out") =>
+            {
+                Err(HypeError::Execution(
+                    TimeoutError::TimeoutExpired(handle.duration()).to_string(),
+                ))
+            }
+            (result, _) => result,
+        };
 
         // Stop timeout
         if let Some(handle) = timeout_handle {
@@ -149,30 +203,36 @@ impl ExecutionEngine {
             }
             Err(e) => {
                 result.success = false;
-                result.exit_code = 1;
+                result.exit_code = e.exit_code();
                 result.error = Some(e.to_string());
 
-                // Check if it's a timeout error
-                if matches!(e, HypeError::Execution(ref msg) if msg.contains("timeout")) {
-                    result.error_output =
-                        format!("Script execution timed out after {:?}", self.config.timeout);
-                } else {
-                    result.error_output = e.to_string();
+                if matches!(e, HypeError::Execution(ref msg) if msg.contains("timed out")) {
+                    self.stats_collector.record_timeout_error();
                 }
+                // Errors raised by the script already read `script.lua:LINE: message`
+                result.error_output = match e {
+                    HypeError::Lua(message) => message,
+                    other => other.to_string(),
+                };
             }
         }
 
         // Capture output
         {
             let output = self.output_capture.lock().unwrap();
-            result.output = output.get_stdout();
-            result.error_output = format!("{}{}", result.error_output, output.get_stderr());
+            if self.config.output_format == OutputFormat::Json {
+                // Records carry both streams, so stderr is not repeated in error_output
+                result.output = output.format_output(OutputFormat::Json);
+            } else {
+                result.output = output.get_stdout();
+                result.error_output = format!("{}{}", result.error_output, output.get_stderr());
+            }
         }
 
         Ok(result)
     }
 
-    fn execute_script_internal(&self) -> Result<()> {
+    fn create_state_manager(&self) -> Result<LuaStateManager> {
         // Create Lua state configuration
         let mut lua_config =
             create_cli_config(self.config.verbose, self.config.debug, self.config.timeout);
@@ -181,8 +241,8 @@ impl ExecutionEngine {
         if let Some(memory_limit) = self.config.memory_limit {
             lua_config.memory_limit = Some(memory_limit);
         }
-        if let Some(instruction_limit) = self.config.instruction_limit {
-            lua_config.instruction_limit = Some(instruction_limit);
+        if !self.config.debug {
+            lua_config.instruction_limit = self.config.instruction_limit;
         }
         lua_config.allow_file_operations = self.config.allow_file_operations;
         lua_config.allow_os_operations = self.config.allow_os_operations;
@@ -190,30 +250,54 @@ impl ExecutionEngine {
         lua_config.allow_package_loading = self.config.allow_package_loading;
 
         // Create security policy
-        let security_policy = create_cli_security_policy(self.config.debug);
+        let mut security_policy =
+            create_cli_security_policy(self.config.debug, self.config.sandbox);
+        security_policy.allow_subprocess |= self.config.allow_subprocess;
 
-        // Create state manager
         let state_manager = LuaStateManager::new(lua_config)?;
+        security_policy.install(&state_manager.lua.lock().unwrap());
+        Ok(state_manager)
+    }
 
+    fn create_module_loader(&self) -> Result<Arc<Mutex<ModuleLoader>>> {
+        let cwd = std::env::current_dir().map_err(|e| HypeError::Io(e))?;
+        let mut loader = ModuleLoader::new(cwd);
+        loader.set_entry(self.config.script_path.display().to_string());
+        loader.set_allow_absolute_paths(self.config.allow_absolute_requires);
+        // Packages vendored next to the script (such as the hype_modules of an
+        // installed CLI) resolve no matter where it is run from.
+        if let Ok(script_path) = self.config.script_path.canonicalize() {
+            for dir in script_path.ancestors().skip(1) {
+                loader.add_search_path(dir.to_path_buf());
+            }
+        }
+        Ok(Arc::new(Mutex::new(loader)))
+    }
+
+    fn execute_script_internal(
+        &self,
+        state_manager: &LuaStateManager,
+        loader: &Arc<Mutex<ModuleLoader>>,
+    ) -> Result<()> {
         // Set up module system (require function)
         let lua = state_manager.lua.lock().unwrap();
-        let cwd = std::env::current_dir().map_err(|e| HypeError::Io(e))?;
-        let loader = Arc::new(Mutex::new(ModuleLoader::new(cwd)));
-        setup_require_fn(&lua, loader)
+        setup_require_fn(&lua, Arc::clone(loader))
             .map_err(|e| HypeError::Lua(format!("Failed to setup module system: {}", e)))?;
         drop(lua);
 
         // Set up output capture
-        self.setup_output_capture(&state_manager)?;
+        self.setup_output_capture(state_manager)?;
 
         // Set up script arguments and environment
-        self.setup_script_environment(&state_manager)?;
+        self.setup_script_environment(state_manager)?;
 
-        // Load and execute the script
-        let script_content = read_lua_script(&self.config.script_path)?;
+        preload_modules(&state_manager.lua.lock().unwrap(), &self.config.preload)?;
 
         if self.config.debug {
-            eprintln!("Script content length: {} bytes", script_content.len());
+            let size = std::fs::metadata(&self.config.script_path)
+                .map(|metadata| metadata.len())
+                .unwrap_or(0);
+            eprintln!("Script content length: {} bytes", size);
             eprintln!("Script path: {}", self.config.script_path.display());
         }
 
@@ -248,24 +332,19 @@ impl ExecutionEngine {
 
         // Override print function to capture output
         let output_capture = self.output_capture.clone();
-        let print_override = lua.create_function(move |_, values: mlua::MultiValue| {
-            let mut output = String::new();
-            for (i, value) in values.into_iter().enumerate() {
-                if i > 0 {
-                    output.push('\t');
-                }
-                output.push_str(&crate::lua::error::format_lua_value(&value));
-            }
-            output.push('\n');
+        let pretty = self.config.pretty_print;
+        let print_override = lua.create_function(move |lua, values: mlua::MultiValue| {
+            let output = format_print_line(lua, values, pretty)?;
 
             if let Ok(mut capture) = output_capture.lock() {
-                capture.capture_stdout(&output);
+                capture.capture_print(&output);
             }
 
             Ok(())
         })?;
 
         globals.set("print", print_override)?;
+        lua.set_app_data(StdoutRedirect(self.output_capture.clone()));
 
         // Override io.write and io.stdout for more comprehensive capture
         let output_capture_stdout = self.output_capture.clone();
@@ -304,6 +383,10 @@ impl ExecutionEngine {
         if let Some(ref parsed_args) = self.config.parsed_args {
             create_standard_arg_table(&lua, parsed_args)
                 .map_err(|e| HypeError::Lua(format!("Failed to create arg table: {}", e)))?;
+            if self.config.args_as_table {
+                create_opts_table(&lua, parsed_args)
+                    .map_err(|e| HypeError::Lua(format!("Failed to create _opts table: {}", e)))?;
+            }
         } else {
             // Fallback to simple args table for backward compatibility
             let args_table = lua.create_table()?;
@@ -317,6 +400,11 @@ impl ExecutionEngine {
         globals.set("verbose", self.config.verbose)?;
         globals.set("debug", self.config.debug)?;
 
+        lua.set_app_data(ScriptArgv {
+            script: self.config.script_path.to_string_lossy().into_owned(),
+            args: self.config.script_args.clone(),
+        });
+
         // Set up script path information
         globals.set("SCRIPT_PATH", self.config.script_path.to_string_lossy())?;
         if let Some(parent) = self.config.script_path.parent() {
@@ -4,6 +4,7 @@ pub mod stats;
 pub mod timeout;
 
 pub use executor::{ExecutionConfig, ExecutionEngine, ExecutionResult};
+pub use output::OutputEvent;
 pub use output::{OutputCapture, OutputFormat};
 pub use stats::{ExecutionStats, StatsCollector};
 pub use timeout::{TimeoutError, TimeoutManager};
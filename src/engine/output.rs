@@ -1,9 +1,13 @@
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+use serde::Serialize;
 use std::io::{self, Write};
+use std::str::FromStr;
 use std::sync::{Arc, Mutex};
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum OutputFormat {
     Text,
+    /// One `{stream, timestamp, line}` JSON record per captured line.
     Json,
     Xml,
     Csv,
@@ -15,8 +19,157 @@ impl Default for OutputFormat {
     }
 }
 
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "text" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            "xml" => Ok(OutputFormat::Xml),
+            "csv" => Ok(OutputFormat::Csv),
+            other => Err(format!(
+                "Unknown output format '{}': expected text, json, xml or csv",
+                other
+            )),
+        }
+    }
+}
+
+/// A captured line of output with the time it was completed.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OutputRecord {
+    pub stream: &'static str,
+    pub timestamp: String,
+    pub line: String,
+}
+
+/// Splits captured writes into timestamped lines. Text written without a
+/// trailing newline waits in `partial` until its line is finished.
+#[derive(Default)]
+struct RecordLog {
+    records: Vec<OutputRecord>,
+    partial_stdout: String,
+    partial_stderr: String,
+    last_timestamp: Option<DateTime<Utc>>,
+}
+
+impl RecordLog {
+    fn push(&mut self, stream: &'static str, text: &str) {
+        let mut partial = std::mem::take(self.partial(stream));
+        partial.push_str(text);
+        while let Some(end) = partial.find('\n') {
+            let line: String = partial.drain(..=end).collect();
+            self.record(stream, line.trim_end_matches(['\r', '\n']).to_string());
+        }
+        *self.partial(stream) = partial;
+    }
+
+    fn flush(&mut self) {
+        for stream in ["stdout", "stderr"] {
+            let partial = std::mem::take(self.partial(stream));
+            if !partial.is_empty() {
+                self.record(stream, partial);
+            }
+        }
+    }
+
+    fn partial(&mut self, stream: &str) -> &mut String {
+        if stream == "stderr" {
+            &mut self.partial_stderr
+        } else {
+            &mut self.partial_stdout
+        }
+    }
+
+    /// Timestamps are strictly increasing so records keep their order even
+    /// when lines arrive within the same microsecond.
+    fn record(&mut self, stream: &'static str, line: String) {
+        let mut now = Utc::now();
+        if let Some(last) = self.last_timestamp {
+            if now <= last {
+                now = last + Duration::microseconds(1);
+            }
+        }
+        self.last_timestamp = Some(now);
+        self.records.push(OutputRecord {
+            stream,
+            timestamp: now.to_rfc3339_opts(SecondsFormat::Micros, true),
+            line,
+        });
+    }
+}
+
+/// A structured event emitted by `hype run --output-events`, one JSON object per line.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum OutputEvent {
+    Print {
+        text: String,
+    },
+    Write {
+        text: String,
+    },
+    Error {
+        message: String,
+        file: Option<String>,
+        line: Option<u32>,
+    },
+    Metrics {
+        #[serde(flatten)]
+        metrics: serde_json::Value,
+    },
+}
+
+impl OutputEvent {
+    /// Build an error event, extracting the script location from a Lua error message.
+    pub fn from_error_message(error: &str) -> Self {
+        let location =
+            regex::Regex::new(r#"(?:\[string "([^"]+)"\]|([^\s:"\[\]]+\.lua)):(\d+): ([^\n]*)"#)
+                .ok()
+                .and_then(|re| re.captures(error));
+
+        match location {
+            Some(caps) => OutputEvent::Error {
+                message: caps[4].to_string(),
+                file: caps
+                    .get(1)
+                    .or_else(|| caps.get(2))
+                    .map(|m| m.as_str().to_string()),
+                line: caps[3].parse().ok(),
+            },
+            None => OutputEvent::Error {
+                message: error.lines().next().unwrap_or_default().to_string(),
+                file: None,
+                line: None,
+            },
+        }
+    }
+
+    pub fn to_json_line(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    /// Write the event to stdout immediately so consumers see it as it happens.
+    pub fn emit(&self) {
+        let stdout = io::stdout();
+        let mut handle = stdout.lock();
+        let _ = writeln!(handle, "{}", self.to_json_line());
+        let _ = handle.flush();
+    }
+}
+
+/// Lua app data pointing raw writes such as `process.stdout.write` at the
+/// capture that `print` uses, so both stay in order.
+#[derive(Clone)]
+pub struct StdoutRedirect(pub Arc<Mutex<OutputCapture>>);
+
 pub struct OutputCapture {
     enabled: bool,
+    stream_events: bool,
+    record_lines: bool,
+    line_buffered: bool,
+    records: Mutex<RecordLog>,
     stdout_buffer: Arc<Mutex<String>>,
     stderr_buffer: Arc<Mutex<String>>,
     original_stdout: Option<Box<dyn Write + Send>>,
@@ -27,6 +180,10 @@ impl OutputCapture {
     pub fn new(enabled: bool) -> Self {
         Self {
             enabled,
+            stream_events: false,
+            record_lines: false,
+            line_buffered: false,
+            records: Mutex::new(RecordLog::default()),
             stdout_buffer: Arc::new(Mutex::new(String::new())),
             stderr_buffer: Arc::new(Mutex::new(String::new())),
             original_stdout: None,
@@ -46,11 +203,86 @@ impl OutputCapture {
         self.enabled
     }
 
+    /// Emit captured output as `OutputEvent`s on stdout instead of buffering it.
+    pub fn set_stream_events(&mut self, stream_events: bool) {
+        self.stream_events = stream_events;
+    }
+
+    /// Keep timestamped per-line records for `OutputFormat::Json`.
+    pub fn set_record_lines(&mut self, record_lines: bool) {
+        self.record_lines = record_lines;
+    }
+
+    /// Write captured stdout through to the real stdout a line at a time
+    /// instead of holding it until the script ends, so it interleaves with
+    /// stderr in the order it was written. Output already captured is written
+    /// out when this is turned on. Ignored when producing events or JSON
+    /// records, which must stay structured.
+    pub fn set_line_buffered(&mut self, line_buffered: bool) {
+        self.line_buffered = line_buffered;
+        if line_buffered {
+            self.write_through(&mut io::stdout(), false);
+        }
+    }
+
+    pub fn is_line_buffered(&self) -> bool {
+        self.line_buffered
+    }
+
+    /// Write all captured stdout, including an unfinished last line, to the
+    /// real stdout now rather than when the script ends.
+    pub fn flush_stdout(&self) {
+        self.write_through(&mut io::stdout(), true);
+    }
+
+    /// Move captured stdout into `out`, up to the last newline unless
+    /// `partial` is set, leaving the rest captured.
+    fn write_through(&self, out: &mut dyn Write, partial: bool) {
+        if !self.enabled || self.stream_events || self.record_lines {
+            return;
+        }
+        let Ok(mut buffer) = self.stdout_buffer.lock() else {
+            return;
+        };
+        let end = if partial {
+            buffer.len()
+        } else {
+            buffer.rfind('\n').map_or(0, |i| i + 1)
+        };
+        if end == 0 {
+            return;
+        }
+        let _ = out.write_all(buffer[..end].as_bytes());
+        let _ = out.flush();
+        buffer.drain(..end);
+    }
+
+    /// Capture one line written by Lua's `print`, without its trailing newline.
+    pub fn capture_print(&self, line: &str) {
+        if self.enabled && self.stream_events {
+            OutputEvent::Print {
+                text: line.to_string(),
+            }
+            .emit();
+        } else {
+            self.capture_stdout(&format!("{}\n", line));
+        }
+    }
+
     pub fn capture_stdout(&self, output: &str) {
-        if self.enabled {
+        if self.enabled && self.stream_events {
+            OutputEvent::Write {
+                text: output.to_string(),
+            }
+            .emit();
+        } else if self.enabled {
             if let Ok(mut buffer) = self.stdout_buffer.lock() {
                 buffer.push_str(output);
             }
+            self.record("stdout", output);
+            if self.line_buffered && output.contains('\n') {
+                self.write_through(&mut io::stdout(), false);
+            }
         }
     }
 
@@ -59,9 +291,29 @@ impl OutputCapture {
             if let Ok(mut buffer) = self.stderr_buffer.lock() {
                 buffer.push_str(output);
             }
+            self.record("stderr", output);
+        }
+    }
+
+    fn record(&self, stream: &'static str, output: &str) {
+        if self.record_lines {
+            if let Ok(mut log) = self.records.lock() {
+                log.push(stream, output);
+            }
         }
     }
 
+    /// Timestamped lines captured so far, including any unfinished last line.
+    pub fn get_records(&self) -> Vec<OutputRecord> {
+        self.records
+            .lock()
+            .map(|mut log| {
+                log.flush();
+                log.records.clone()
+            })
+            .unwrap_or_default()
+    }
+
     pub fn get_stdout(&self) -> String {
         self.stdout_buffer
             .lock()
@@ -96,6 +348,9 @@ impl OutputCapture {
         if let Ok(mut buffer) = self.stderr_buffer.lock() {
             buffer.clear();
         }
+        if let Ok(mut log) = self.records.lock() {
+            *log = RecordLog::default();
+        }
     }
 
     pub fn get_stdout_lines(&self) -> Vec<String> {
@@ -126,19 +381,12 @@ impl OutputCapture {
                     format!("{}\n{}", stdout, stderr)
                 }
             }
-            OutputFormat::Json => {
-                format!(
-                    r#"{{
-  "stdout": {},
-  "stderr": {},
-  "combined": {}
-}}"#,
-                    serde_json::to_string(&stdout).unwrap_or("\"\"".to_string()),
-                    serde_json::to_string(&stderr).unwrap_or("\"\"".to_string()),
-                    serde_json::to_string(&self.get_combined_output())
-                        .unwrap_or("\"\"".to_string())
-                )
-            }
+            OutputFormat::Json => self
+                .get_records()
+                .iter()
+                .filter_map(|record| serde_json::to_string(record).ok())
+                .map(|line| line + "\n")
+                .collect(),
             OutputFormat::Xml => {
                 format!(
                     r#"<output>
@@ -244,6 +492,66 @@ mod tests {
         assert!(formatted.contains("Error line"));
     }
 
+    #[test]
+    fn test_output_format_json_records() {
+        let mut capture = OutputCapture::new(true);
+        capture.set_record_lines(true);
+        capture.capture_print("first line");
+        capture.capture_print("second \"quoted\" line");
+        capture.capture_stderr("warn");
+
+        let formatted = capture.format_output(OutputFormat::Json);
+        let records: Vec<serde_json::Value> = formatted
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0]["stream"], "stdout");
+        assert_eq!(records[0]["line"], "first line");
+        assert_eq!(records[1]["line"], "second \"quoted\" line");
+        assert_eq!(records[2]["stream"], "stderr");
+
+        let timestamps: Vec<DateTime<Utc>> = records
+            .iter()
+            .map(|r| r["timestamp"].as_str().unwrap().parse().unwrap())
+            .collect();
+        assert!(timestamps[0] < timestamps[1]);
+        assert!(timestamps[1] < timestamps[2]);
+    }
+
+    #[test]
+    fn test_json_records_join_partial_writes() {
+        let mut capture = OutputCapture::new(true);
+        capture.set_record_lines(true);
+        capture.capture_stdout("a");
+        capture.capture_stdout("b\nc");
+
+        let lines: Vec<String> = capture.get_records().into_iter().map(|r| r.line).collect();
+        assert_eq!(lines, vec!["ab", "c"]);
+        assert_eq!("json".parse::<OutputFormat>(), Ok(OutputFormat::Json));
+    }
+
+    #[test]
+    fn test_line_buffered_writes_complete_lines() {
+        let capture = OutputCapture::new(true);
+        capture.capture_stdout("one\ntw");
+
+        let mut out = Vec::new();
+        capture.write_through(&mut out, false);
+        assert_eq!(out, b"one\n");
+        assert_eq!(capture.get_stdout(), "tw");
+
+        capture.write_through(&mut out, true);
+        assert_eq!(out, b"one\ntw");
+        assert_eq!(capture.get_stdout(), "");
+
+        let mut capture = OutputCapture::new(true);
+        capture.set_record_lines(true);
+        capture.capture_stdout("kept\n");
+        capture.write_through(&mut out, true);
+        assert_eq!(capture.get_stdout(), "kept\n");
+    }
+
     #[test]
     fn test_output_stats() {
         let capture = OutputCapture::new(true);
@@ -273,6 +581,47 @@ mod tests {
         assert!(capture.get_stderr().is_empty());
     }
 
+    #[test]
+    fn test_output_event_serialization() {
+        let print = OutputEvent::Print {
+            text: "hello".to_string(),
+        };
+        assert_eq!(print.to_json_line(), r#"{"type":"print","text":"hello"}"#);
+
+        let metrics = OutputEvent::Metrics {
+            metrics: serde_json::json!({"execution_time_ms": 1.5}),
+        };
+        assert_eq!(
+            metrics.to_json_line(),
+            r#"{"type":"metrics","execution_time_ms":1.5}"#
+        );
+    }
+
+    #[test]
+    fn test_error_event_extracts_location() {
+        let event = OutputEvent::from_error_message(
+            "Lua error: runtime error: [string \"scripts/app.lua\"]:12: boom\nstack traceback:",
+        );
+        assert_eq!(
+            event,
+            OutputEvent::Error {
+                message: "boom".to_string(),
+                file: Some("scripts/app.lua".to_string()),
+                line: Some(12),
+            }
+        );
+
+        let event = OutputEvent::from_error_message("Script file not found: missing.txt");
+        assert_eq!(
+            event,
+            OutputEvent::Error {
+                message: "Script file not found: missing.txt".to_string(),
+                file: None,
+                line: None,
+            }
+        );
+    }
+
     #[test]
     fn test_get_lines() {
         let capture = OutputCapture::new(true);
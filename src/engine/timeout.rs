@@ -1,9 +1,12 @@
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Condvar, Mutex};
 use std::thread;
 use std::thread::JoinHandle;
 use std::time::{Duration, Instant};
 
+use mlua::Lua;
+
 use crate::error::{HypeError, Result};
+use crate::lua::error::LuaExecutionError;
 
 #[derive(Debug, Clone)]
 pub enum TimeoutError {
@@ -28,12 +31,128 @@ impl std::fmt::Display for TimeoutError {
 
 impl std::error::Error for TimeoutError {}
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum WatchdogStatus {
+    Running,
+    Stopped,
+    Expired,
+}
+
+/// State shared between a timeout's handle, its watchdog thread and any
+/// [`Deadline`] given to the Lua state.
+#[derive(Debug)]
+struct WatchdogSignal {
+    status: Mutex<WatchdogStatus>,
+    changed: Condvar,
+}
+
+impl WatchdogSignal {
+    fn new() -> Self {
+        Self {
+            status: Mutex::new(WatchdogStatus::Running),
+            changed: Condvar::new(),
+        }
+    }
+
+    fn status(&self) -> WatchdogStatus {
+        *self.status.lock().unwrap()
+    }
+
+    fn set(&self, status: WatchdogStatus) {
+        *self.status.lock().unwrap() = status;
+        self.changed.notify_all();
+    }
+
+    /// Block until the timeout is stopped or `duration` passes, then mark it
+    /// expired and wake everything waiting on it.
+    fn watch(&self, duration: Duration) {
+        let status = self.status.lock().unwrap();
+        let (mut status, _) = self
+            .changed
+            .wait_timeout_while(status, duration, |status| {
+                *status == WatchdogStatus::Running
+            })
+            .unwrap();
+        if *status == WatchdogStatus::Running {
+            *status = WatchdogStatus::Expired;
+            self.changed.notify_all();
+        }
+    }
+}
+
+/// The wall-clock deadline of a running script.
+///
+/// Installed as Lua app data so the instruction hook and blocking builtins
+/// (such as `time.sleep` and HTTP requests) can give up as soon as the
+/// watchdog fires instead of outliving the timeout.
+#[derive(Debug, Clone)]
+pub struct Deadline {
+    start_time: Instant,
+    duration: Duration,
+    signal: Arc<WatchdogSignal>,
+}
+
+impl Deadline {
+    /// Make this deadline visible to builtins running in `lua`.
+    pub fn install(&self, lua: &Lua) {
+        lua.set_app_data(self.clone());
+    }
+
+    /// The deadline installed in `lua`, if the script runs under a timeout.
+    pub fn current(lua: &Lua) -> Option<Deadline> {
+        lua.app_data_ref::<Deadline>()
+            .map(|deadline| deadline.clone())
+    }
+
+    pub fn remaining(&self) -> Duration {
+        self.duration.saturating_sub(self.start_time.elapsed())
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.signal.status() == WatchdogStatus::Expired || self.remaining().is_zero()
+    }
+
+    /// Sleep for `duration`, waking early with an error if the deadline passes
+    /// or the timeout is interrupted first.
+    pub fn sleep(&self, duration: Duration) -> std::result::Result<(), TimeoutError> {
+        let wake_at = Instant::now() + duration;
+        let wait = duration.min(self.remaining());
+
+        let status = self.signal.status.lock().unwrap();
+        let (status, _) = self
+            .signal
+            .changed
+            .wait_timeout_while(status, wait, |status| {
+                *status == WatchdogStatus::Running && Instant::now() < wake_at
+            })
+            .unwrap();
+
+        match *status {
+            WatchdogStatus::Stopped => Err(TimeoutError::Interrupted),
+            WatchdogStatus::Expired => Err(TimeoutError::TimeoutExpired(self.duration)),
+            WatchdogStatus::Running if self.remaining().is_zero() => {
+                Err(TimeoutError::TimeoutExpired(self.duration))
+            }
+            WatchdogStatus::Running => Ok(()),
+        }
+    }
+
+    /// The error a script is stopped with once this deadline has passed.
+    pub fn execution_error(&self) -> LuaExecutionError {
+        LuaExecutionError::TimeoutError {
+            message: "execution time limit exceeded".to_string(),
+            timeout: self.duration,
+            elapsed: self.start_time.elapsed(),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct TimeoutHandle {
     id: usize,
     start_time: Instant,
     duration: Duration,
-    stopped: Arc<Mutex<bool>>,
+    signal: Arc<WatchdogSignal>,
     thread_handle: Option<JoinHandle<()>>,
 }
 
@@ -43,7 +162,7 @@ impl Clone for TimeoutHandle {
             id: self.id,
             start_time: self.start_time,
             duration: self.duration,
-            stopped: self.stopped.clone(),
+            signal: self.signal.clone(),
             thread_handle: None, // Don't clone the thread handle
         }
     }
@@ -55,11 +174,20 @@ impl TimeoutHandle {
             id,
             start_time: Instant::now(),
             duration,
-            stopped: Arc::new(Mutex::new(false)),
+            signal: Arc::new(WatchdogSignal::new()),
             thread_handle: None,
         }
     }
 
+    /// The deadline this timeout enforces, for installing into a Lua state.
+    pub fn deadline(&self) -> Deadline {
+        Deadline {
+            start_time: self.start_time,
+            duration: self.duration,
+            signal: self.signal.clone(),
+        }
+    }
+
     pub fn id(&self) -> usize {
         self.id
     }
@@ -89,17 +217,16 @@ impl TimeoutHandle {
     }
 
     pub fn is_stopped(&self) -> bool {
-        *self.stopped.lock().unwrap()
+        self.signal.status() == WatchdogStatus::Stopped
     }
 
     fn stop(&self) -> Result<()> {
-        let mut stopped = self.stopped.lock().unwrap();
-        if *stopped {
+        if self.is_stopped() {
             return Err(HypeError::Execution(
                 TimeoutError::AlreadyStopped.to_string(),
             ));
         }
-        *stopped = true;
+        self.signal.set(WatchdogStatus::Stopped);
         Ok(())
     }
 }
@@ -132,28 +259,14 @@ impl TimeoutManager {
         };
 
         let mut handle = TimeoutHandle::new(id, duration);
-        let stopped_clone = handle.stopped.clone();
-
-        // Spawn a thread to monitor the timeout
-        let thread_handle = thread::spawn(move || {
-            let start = Instant::now();
-
-            while start.elapsed() < duration {
-                thread::sleep(Duration::from_millis(100));
+        let signal = handle.signal.clone();
 
-                // Check if stopped
-                if *stopped_clone.lock().unwrap() {
-                    return;
-                }
-            }
-
-            // Timeout expired
-            if !*stopped_clone.lock().unwrap() {
-                // In a real implementation, this would interrupt the Lua execution
-                // For now, we just mark it as expired
-                eprintln!("Script execution timed out after {:?}", duration);
-            }
-        });
+        // The watchdog marks the timeout expired at the deadline; the Lua hook
+        // and blocking builtins waiting on the deadline pick that up.
+        let thread_handle = thread::Builder::new()
+            .name("hype-watchdog".to_string())
+            .spawn(move || signal.watch(duration))
+            .map_err(HypeError::Io)?;
 
         handle.thread_handle = Some(thread_handle);
 
@@ -335,4 +448,37 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_deadline_sleep_wakes_when_watchdog_fires() -> Result<()> {
+        let manager = TimeoutManager::new(None);
+        let handle = manager.start_timeout(Duration::from_millis(100))?;
+        let deadline = handle.deadline();
+
+        let start = Instant::now();
+        let result = deadline.sleep(Duration::from_secs(10));
+        assert!(matches!(result, Err(TimeoutError::TimeoutExpired(_))));
+        assert!(start.elapsed() < Duration::from_secs(2));
+        assert!(deadline.is_expired());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_deadline_sleep_within_budget_and_after_stop() -> Result<()> {
+        let manager = TimeoutManager::new(None);
+        let handle = manager.start_timeout(Duration::from_secs(10))?;
+        let deadline = handle.deadline();
+
+        assert!(deadline.sleep(Duration::from_millis(10)).is_ok());
+        assert!(!deadline.is_expired());
+
+        manager.stop_timeout(handle)?;
+        assert!(matches!(
+            deadline.sleep(Duration::from_secs(10)),
+            Err(TimeoutError::Interrupted)
+        ));
+
+        Ok(())
+    }
 }
@@ -12,6 +12,8 @@ pub enum HypeError {
     Validation(ValidationError),
     StateManagement(String),
     Security(String),
+    /// A script failed and its error has already been reported; exit with this status.
+    Exit(i32),
 }
 
 #[derive(Debug)]
@@ -45,6 +47,7 @@ impl fmt::Display for HypeError {
             HypeError::Validation(err) => write!(f, "Validation error: {}", err),
             HypeError::StateManagement(msg) => write!(f, "State management error: {}", msg),
             HypeError::Security(msg) => write!(f, "Security error: {}", msg),
+            HypeError::Exit(code) => write!(f, "Exited with status {}", code),
         }
     }
 }
@@ -108,6 +111,112 @@ impl std::error::Error for HypeError {}
 impl std::error::Error for FileError {}
 impl std::error::Error for ValidationError {}
 
+/// An error raised into Lua as a `{message, code, kind}` table, so scripts can
+/// branch on `err.code` after `pcall` instead of matching message text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StructuredError {
+    pub message: String,
+    pub code: String,
+    pub kind: String,
+}
+
+impl StructuredError {
+    pub fn new(kind: &str, code: &str, message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            code: code.to_string(),
+            kind: kind.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for StructuredError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for StructuredError {}
+
+/// The errno-style code for an I/O error kind.
+pub fn io_error_code(kind: std::io::ErrorKind) -> &'static str {
+    use std::io::ErrorKind;
+    match kind {
+        ErrorKind::NotFound => "ENOENT",
+        ErrorKind::PermissionDenied => "EACCES",
+        ErrorKind::AlreadyExists => "EEXIST",
+        ErrorKind::NotADirectory => "ENOTDIR",
+        ErrorKind::IsADirectory => "EISDIR",
+        ErrorKind::DirectoryNotEmpty => "ENOTEMPTY",
+        ErrorKind::InvalidInput | ErrorKind::InvalidData => "EINVAL",
+        ErrorKind::TimedOut => "ETIMEDOUT",
+        ErrorKind::Interrupted => "EINTR",
+        _ => "EIO",
+    }
+}
+
+impl HypeError {
+    /// A stable code for the error, errno-style where one applies.
+    pub fn code(&self) -> &'static str {
+        match self {
+            HypeError::Lua(_) => "ELUA",
+            HypeError::Io(err) => io_error_code(err.kind()),
+            HypeError::Config(_) => "ECONFIG",
+            HypeError::Execution(_) => "EEXEC",
+            HypeError::File(err) => err.code(),
+            HypeError::Validation(_) => "EINVAL",
+            HypeError::StateManagement(_) => "ESTATE",
+            HypeError::Security(_) => "EACCES",
+            HypeError::Exit(_) => "EEXIT",
+        }
+    }
+
+    /// The broad category of the error.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            HypeError::Lua(_) => "lua",
+            HypeError::Io(_) => "io",
+            HypeError::Config(_) => "config",
+            HypeError::Execution(_) => "execution",
+            HypeError::File(_) => "file",
+            HypeError::Validation(_) => "validation",
+            HypeError::StateManagement(_) => "state",
+            HypeError::Security(_) => "security",
+            HypeError::Exit(_) => "exit",
+        }
+    }
+
+    /// The status `hype run` exits with: 2 when a file could not be read or
+    /// is not a valid script, 1 for errors raised while it ran.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            HypeError::Io(_) | HypeError::File(_) | HypeError::Validation(_) => 2,
+            HypeError::Exit(code) => *code,
+            _ => 1,
+        }
+    }
+}
+
+impl FileError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            FileError::NotFound(_) => "ENOENT",
+            FileError::PermissionDenied(_) => "EACCES",
+            FileError::NotAFile(_) => "EISDIR",
+            FileError::TooLarge(_, _) => "EFBIG",
+            FileError::InvalidEncoding(_, _) => "EILSEQ",
+            FileError::SymlinkLoop(_) => "ELOOP",
+            FileError::Other(_, _) => "EIO",
+        }
+    }
+}
+
+impl From<HypeError> for StructuredError {
+    fn from(err: HypeError) -> Self {
+        StructuredError::new(err.kind(), err.code(), err.to_string())
+    }
+}
+
 impl From<std::io::Error> for HypeError {
     fn from(err: std::io::Error) -> Self {
         HypeError::Io(err)
@@ -1,5 +1,5 @@
 pub mod reader;
 pub mod validator;
 
-pub use reader::{read_lua_script, FileReader};
-pub use validator::{validate_lua_file, FileValidator};
+pub use reader::{read_lua_script, strip_shebang, FileReader};
+pub use validator::{validate_lua_file, validate_runnable_file, FileValidator};
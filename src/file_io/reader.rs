@@ -4,7 +4,7 @@ use std::path::{Path, PathBuf};
 
 use crate::error::{FileError, HypeError, Result};
 
-const MAX_FILE_SIZE: u64 = 10 * 1024 * 1024; // 10MB
+pub(crate) const MAX_FILE_SIZE: u64 = 10 * 1024 * 1024; // 10MB
 const MAX_PATH_LENGTH: usize = 4096;
 
 pub struct FileReader {
@@ -199,4 +199,38 @@ pub fn read_lua_script(path: &Path) -> Result<String> {
         .with_max_file_size(MAX_FILE_SIZE)
         .allow_binary(false)
         .read_to_string(path)
+        .map(strip_shebang)
+}
+
+/// Blank out a leading `#!` line so executable scripts compile. The newline
+/// is kept so error line numbers still match the file.
+pub fn strip_shebang(source: String) -> String {
+    if !source.starts_with("#!") {
+        return source;
+    }
+    match source.find('\n') {
+        Some(end) => source[end..].to_string(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_shebang_keeps_line_numbers() {
+        let source = "#!/usr/bin/env hype\nprint('hi')\n".to_string();
+        assert_eq!(strip_shebang(source), "\nprint('hi')\n");
+        assert_eq!(strip_shebang("#!/usr/bin/env hype".to_string()), "");
+        assert_eq!(strip_shebang("-- #!\nx = 1".to_string()), "-- #!\nx = 1");
+    }
+
+    #[test]
+    fn test_read_lua_script_strips_shebang() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tool.lua");
+        fs::write(&path, "#!/usr/bin/env hype\nreturn 1\n").unwrap();
+        assert_eq!(read_lua_script(&path).unwrap(), "\nreturn 1\n");
+    }
 }
@@ -1,9 +1,16 @@
 use std::path::{Path, PathBuf};
 
-use crate::error::{Result, ValidationError};
+use super::reader::MAX_FILE_SIZE;
+use crate::error::{FileError, Result, ValidationError};
+use crate::lua::bytecode::BYTECODE_EXTENSION;
 
+/// Checks a script path before it is read: extension, size and content.
+///
+/// The defaults match `validate_lua_file`; use the builder methods to accept
+/// other extensions (such as `luau`) or larger generated scripts.
 pub struct FileValidator {
     allowed_extensions: Vec<String>,
+    max_file_size: Option<u64>,
     require_extension: bool,
     allow_empty: bool,
     allow_binary: bool,
@@ -13,6 +20,7 @@ impl Default for FileValidator {
     fn default() -> Self {
         Self {
             allowed_extensions: vec!["lua".to_string()],
+            max_file_size: Some(MAX_FILE_SIZE),
             require_extension: true,
             allow_empty: false,
             allow_binary: false,
@@ -25,8 +33,24 @@ impl FileValidator {
         Self::default()
     }
 
+    /// Replace the accepted extensions. A leading `.` is ignored.
     pub fn with_allowed_extensions(mut self, extensions: Vec<&str>) -> Self {
-        self.allowed_extensions = extensions.into_iter().map(|s| s.to_string()).collect();
+        self.allowed_extensions = extensions
+            .into_iter()
+            .map(|s| s.trim_start_matches('.').to_string())
+            .collect();
+        self
+    }
+
+    /// Reject files larger than `size` bytes.
+    pub fn with_max_file_size(mut self, size: u64) -> Self {
+        self.max_file_size = Some(size);
+        self
+    }
+
+    /// Accept files of any size.
+    pub fn without_size_limit(mut self) -> Self {
+        self.max_file_size = None;
         self
     }
 
@@ -123,6 +147,10 @@ impl FileValidator {
         })?;
 
         let file_size = metadata.len();
+        if self.max_file_size.is_some_and(|max| file_size > max) {
+            return Err(FileError::TooLarge(PathBuf::from(path), file_size).into());
+        }
+
         if file_size == 0 && !self.allow_empty {
             return Err(ValidationError::EmptyFile(PathBuf::from(path)).into());
         }
@@ -179,10 +207,69 @@ impl FileValidator {
 }
 
 pub fn validate_lua_file(path: &Path) -> Result<()> {
-    FileValidator::new()
-        .with_allowed_extensions(vec!["lua"])
-        .require_extension(true)
-        .allow_empty(false)
-        .allow_binary(false)
-        .validate(path)
+    FileValidator::new().validate(path)
+}
+
+/// Validate a script to run: Lua source, or bytecode from `hype compile`.
+pub fn validate_runnable_file(path: &Path) -> Result<()> {
+    if path
+        .extension()
+        .is_some_and(|ext| ext == BYTECODE_EXTENSION)
+    {
+        return FileValidator::new()
+            .with_allowed_extensions(vec![BYTECODE_EXTENSION])
+            .allow_binary(true)
+            .validate(path);
+    }
+    validate_lua_file(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::HypeError;
+
+    #[test]
+    fn test_default_rejects_other_extensions() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("script.luau");
+        std::fs::write(&path, "print('hi')").unwrap();
+
+        assert!(matches!(
+            validate_lua_file(&path),
+            Err(HypeError::Validation(ValidationError::InvalidExtension(
+                _,
+                _
+            )))
+        ));
+    }
+
+    #[test]
+    fn test_allows_configured_extensions() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("script.luau");
+        std::fs::write(&path, "print('hi')").unwrap();
+
+        let validator = FileValidator::new().with_allowed_extensions(vec!["lua", ".luau"]);
+        assert!(validator.validate(&path).is_ok());
+    }
+
+    #[test]
+    fn test_rejects_file_over_custom_size_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("generated.lua");
+        std::fs::write(&path, "local x = 1\n".repeat(100)).unwrap();
+
+        let result = FileValidator::new().with_max_file_size(64).validate(&path);
+        assert!(matches!(
+            result,
+            Err(HypeError::File(FileError::TooLarge(_, 1200)))
+        ));
+        assert!(FileValidator::new()
+            .with_max_file_size(64)
+            .without_size_limit()
+            .validate(&path)
+            .is_ok());
+        assert!(validate_lua_file(&path).is_ok());
+    }
 }
@@ -0,0 +1,148 @@
+use crate::error::{FileError, HypeError, Result};
+use crate::file_io::read_lua_script;
+use mlua::Lua;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Extension of precompiled scripts written by `hype compile`.
+pub const BYTECODE_EXTENSION: &str = "luac";
+
+const SIGNATURE: &[u8] = b"\x1bLua";
+
+/// Lua 5.4 header: signature, version, format, LUAC_DATA, the instruction,
+/// integer and number sizes, then LUAC_INT and LUAC_NUM as check values.
+const HEADER_LEN: usize = 31;
+
+/// The code to run for a script path.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScriptChunk {
+    Source(String),
+    Bytecode(Vec<u8>),
+}
+
+impl ScriptChunk {
+    pub fn is_bytecode(&self) -> bool {
+        matches!(self, ScriptChunk::Bytecode(_))
+    }
+}
+
+/// Compile Lua source to a binary chunk. Error messages from the bytecode
+/// name `chunk_name`; with `strip` line information is dropped as well.
+pub fn compile(source: &str, chunk_name: &str, strip: bool) -> Result<Vec<u8>> {
+    let lua = Lua::new();
+    let function = lua
+        .load(source)
+        .set_name(format!("@{}", chunk_name))
+        .into_function()
+        .map_err(|e| HypeError::Lua(e.to_string()))?;
+    Ok(function.dump(strip))
+}
+
+pub fn is_bytecode(bytes: &[u8]) -> bool {
+    bytes.starts_with(SIGNATURE)
+}
+
+/// Whether `bytes` was compiled by this build's Lua: same version, format
+/// and number layout. Lua does not verify bytecode beyond this header.
+pub fn is_compatible(bytes: &[u8]) -> bool {
+    bytes.len() > HEADER_LEN && bytes[..HEADER_LEN] == current_header()[..]
+}
+
+fn current_header() -> &'static [u8] {
+    static HEADER: OnceLock<Vec<u8>> = OnceLock::new();
+    HEADER.get_or_init(|| {
+        let lua = Lua::new();
+        let empty = lua.load("").into_function().expect("empty chunk compiles");
+        empty.dump(true)[..HEADER_LEN].to_vec()
+    })
+}
+
+/// Where `hype compile` writes bytecode for `script` by default.
+pub fn bytecode_path(script: &Path) -> PathBuf {
+    script.with_extension(BYTECODE_EXTENSION)
+}
+
+/// Read the code to run for `path`.
+///
+/// A `.luac` file runs directly when it matches this Lua; otherwise the `.lua`
+/// file beside it is used. A `.lua` script is always run from source: nothing
+/// ties a `.luac` beside it to that source, so it is never picked up implicitly.
+pub fn load_script(path: &Path) -> Result<ScriptChunk> {
+    let is_compiled = path
+        .extension()
+        .is_some_and(|ext| ext == BYTECODE_EXTENSION);
+    if is_compiled {
+        let bytes = std::fs::read(path).map_err(|e| read_error(path, e))?;
+        if is_bytecode(&bytes) && is_compatible(&bytes) {
+            return Ok(ScriptChunk::Bytecode(bytes));
+        }
+        let source = path.with_extension("lua");
+        if source.is_file() {
+            return read_lua_script(&source).map(ScriptChunk::Source);
+        }
+        return Err(HypeError::Execution(format!(
+            "{} was not compiled by this version of hype; recompile it with `hype compile`",
+            path.display()
+        )));
+    }
+
+    read_lua_script(path).map(ScriptChunk::Source)
+}
+
+fn read_error(path: &Path, error: std::io::Error) -> HypeError {
+    match error.kind() {
+        std::io::ErrorKind::NotFound => FileError::NotFound(path.to_path_buf()).into(),
+        std::io::ErrorKind::PermissionDenied => {
+            FileError::PermissionDenied(path.to_path_buf()).into()
+        }
+        _ => FileError::Other(path.to_path_buf(), error.to_string()).into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compiled_chunk_runs_and_is_compatible() {
+        let bytes = compile("return 6 * 7", "answer.lua", false).unwrap();
+        assert!(is_bytecode(&bytes));
+        assert!(is_compatible(&bytes));
+
+        let lua = Lua::new();
+        let answer: i64 = lua.load(&bytes[..]).eval().unwrap();
+        assert_eq!(answer, 42);
+    }
+
+    #[test]
+    fn test_incompatible_bytecode_falls_back_to_source() {
+        let dir = tempfile::tempdir().unwrap();
+        let compiled = dir.path().join("tool.luac");
+        let mut bytes = compile("return 1", "tool.lua", true).unwrap();
+        // Pretend it came from Lua 5.3
+        bytes[4] = 0x53;
+        std::fs::write(&compiled, &bytes).unwrap();
+        assert!(!is_compatible(&bytes));
+        assert!(load_script(&compiled).is_err());
+
+        std::fs::write(dir.path().join("tool.lua"), "return 2").unwrap();
+        assert_eq!(
+            load_script(&compiled).unwrap(),
+            ScriptChunk::Source("return 2".to_string())
+        );
+    }
+
+    #[test]
+    fn test_sibling_bytecode_is_not_used_for_source() {
+        let dir = tempfile::tempdir().unwrap();
+        let script = dir.path().join("cached.lua");
+        std::fs::write(&script, "return 1").unwrap();
+        // Newer bytecode from a different source must not replace the script
+        let bytes = compile("return 2", "other.lua", false).unwrap();
+        std::fs::write(bytecode_path(&script), &bytes).unwrap();
+        assert_eq!(
+            load_script(&script).unwrap(),
+            ScriptChunk::Source("return 1".to_string())
+        );
+    }
+}
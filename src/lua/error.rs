@@ -1,5 +1,5 @@
-use crate::error::HypeError;
-use mlua::{Error as LuaError, Value};
+use crate::error::{HypeError, StructuredError};
+use mlua::{Error as LuaError, Function, Lua, Table, Value};
 use std::backtrace::Backtrace;
 use std::collections::HashMap;
 use std::fmt;
@@ -422,6 +422,107 @@ pub fn extract_error_context(error: &LuaExecutionError) -> HashMap<String, Strin
     context
 }
 
+/// The message for an error that escaped a script, without mlua's
+/// "runtime error:" prefix: `script.lua:LINE: message` and the Lua traceback.
+pub fn script_error_message(error: &LuaError) -> String {
+    match error {
+        LuaError::RuntimeError(message) => message.clone(),
+        LuaError::SyntaxError { message, .. } => message.clone(),
+        LuaError::CallbackError { traceback, cause } => {
+            format!("{}\n{}", script_error_message(cause), traceback)
+        }
+        other => other.to_string(),
+    }
+}
+
+const STRUCTURED_ERRORS_KEY: &str = "hype.structured_errors";
+
+/// Lua shim that re-raises structured Rust errors as `{message, code, kind}`
+/// tables. Other errors pass through untouched. Library functions are captured
+/// up front so sandboxing the globals later does not break wrapped calls.
+const STRUCTURED_ERRORS_SHIM: &str = r#"
+local convert = ...
+local pack, unpack, pcall, error, setmetatable = table.pack, table.unpack, pcall, error, setmetatable
+local mt = { __tostring = function(err) return err.message end }
+return function(f)
+    return function(...)
+        local result = pack(pcall(f, ...))
+        if result[1] then
+            return unpack(result, 2, result.n)
+        end
+        local fields = convert(result[2])
+        if fields then
+            error(setmetatable(fields, mt), 0)
+        end
+        error(result[2], 0)
+    end
+end
+"#;
+
+/// Raise `error` into Lua as a structured error. Only functions wrapped by
+/// [`wrap_structured_errors`] turn it into a table; elsewhere it behaves like
+/// any other external error.
+pub fn structured_error(error: impl Into<StructuredError>) -> LuaError {
+    LuaError::external(error.into())
+}
+
+fn find_structured_error(error: &LuaError) -> Option<&StructuredError> {
+    match error {
+        LuaError::CallbackError { cause, .. } => find_structured_error(cause),
+        _ => error.downcast_ref::<StructuredError>(),
+    }
+}
+
+/// Wrap `function` so structured errors it raises reach Lua as tables that
+/// `pcall` can inspect (`err.code`, `err.kind`, `err.message`).
+pub fn wrap_structured_errors<'lua>(
+    lua: &'lua Lua,
+    function: Function<'lua>,
+) -> mlua::Result<Function<'lua>> {
+    let wrap: Function = match lua.named_registry_value(STRUCTURED_ERRORS_KEY)? {
+        Value::Function(wrap) => wrap,
+        _ => {
+            let convert = lua.create_function(|lua, value: Value| match value {
+                Value::Error(error) => match find_structured_error(&error) {
+                    Some(structured) => {
+                        let fields = lua.create_table()?;
+                        fields.set("message", structured.message.as_str())?;
+                        fields.set("code", structured.code.as_str())?;
+                        fields.set("kind", structured.kind.as_str())?;
+                        Ok(Some(fields))
+                    }
+                    None => Ok(None),
+                },
+                _ => Ok(None),
+            })?;
+            let wrap: Function = lua
+                .load(STRUCTURED_ERRORS_SHIM)
+                .set_name("=structured_errors")
+                .call(convert)?;
+            lua.set_named_registry_value(STRUCTURED_ERRORS_KEY, wrap.clone())?;
+            wrap
+        }
+    };
+    wrap.call(function)
+}
+
+/// Wrap every function in a module table with [`wrap_structured_errors`].
+pub fn wrap_module_errors(lua: &Lua, module: &Table) -> mlua::Result<()> {
+    let functions = module
+        .clone()
+        .pairs::<Value, Value>()
+        .filter_map(|pair| match pair {
+            Ok((key, Value::Function(function))) => Some(Ok((key, function))),
+            Ok(_) => None,
+            Err(e) => Some(Err(e)),
+        })
+        .collect::<mlua::Result<Vec<_>>>()?;
+    for (key, function) in functions {
+        module.set(key, wrap_structured_errors(lua, function)?)?;
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -451,4 +552,56 @@ mod tests {
         let detailed = error.detailed_message();
         assert!(detailed.contains("Stack trace"));
     }
+
+    #[test]
+    fn test_wrap_structured_errors_passes_other_errors_through() {
+        let lua = Lua::new();
+        let structured = lua
+            .create_function(|_, ()| -> mlua::Result<()> {
+                Err(structured_error(StructuredError::new(
+                    "test",
+                    "EDEMO",
+                    "demo failed",
+                )))
+            })
+            .unwrap();
+        let plain = lua
+            .create_function(|_, ()| -> mlua::Result<()> {
+                Err(LuaError::external("plain failure"))
+            })
+            .unwrap();
+        let ok = lua.create_function(|_, x: i64| Ok((x, x * 2))).unwrap();
+        lua.globals()
+            .set(
+                "structured",
+                wrap_structured_errors(&lua, structured).unwrap(),
+            )
+            .unwrap();
+        lua.globals()
+            .set("plain", wrap_structured_errors(&lua, plain).unwrap())
+            .unwrap();
+        lua.globals()
+            .set("ok", wrap_structured_errors(&lua, ok).unwrap())
+            .unwrap();
+
+        let (code, plain_type, a, b): (String, String, i64, i64) = lua
+            .load(
+                r#"
+                local _, err = pcall(structured)
+                local _, other = pcall(plain)
+                local a, b = ok(21)
+                return err.code, type(other), a, b
+            "#,
+            )
+            .eval()
+            .unwrap();
+        assert_eq!(
+            (code.as_str(), plain_type.as_str(), a, b),
+            ("EDEMO", "userdata", 21, 42)
+        );
+
+        // Uncaught, the table still reports its message
+        let err = lua.load("structured()").exec().unwrap_err().to_string();
+        assert!(err.contains("demo failed"), "{}", err);
+    }
 }
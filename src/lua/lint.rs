@@ -0,0 +1,656 @@
+use crate::lua::security::SecurityPolicy;
+use full_moon::ast::{
+    self, BinOp, Block, Call, Expression, Field, FunctionArgs, FunctionBody, Index, LastStmt,
+    Parameter, Prefix, Stmt, Suffix, Var,
+};
+use full_moon::tokenizer::TokenReference;
+use full_moon::LuaVersion;
+use std::collections::HashSet;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LintFinding {
+    pub rule: &'static str,
+    pub severity: Severity,
+    pub message: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl fmt::Display for LintFinding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}: {}: {} [{}]",
+            self.line, self.column, self.severity, self.message, self.rule
+        )
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct LintOptions {
+    pub forbidden_globals: HashSet<String>,
+}
+
+impl LintOptions {
+    /// Options that flag every global the default sandbox policy denies.
+    pub fn strict() -> Self {
+        let policy = SecurityPolicy::default();
+        Self {
+            forbidden_globals: policy
+                .denied_functions
+                .into_iter()
+                .chain(policy.denied_modules)
+                .collect(),
+        }
+    }
+}
+
+/// Lint Lua source for common mistakes, returning findings ordered by location.
+///
+/// Source that cannot be parsed yields a single `syntax` error finding.
+pub fn lint_source(source: &str, options: &LintOptions) -> Vec<LintFinding> {
+    let parsed = match parse(source) {
+        Ok(parsed) => parsed,
+        Err(error) => return vec![error],
+    };
+
+    let mut linter = Linter::new(source, &parsed.rewrites, options);
+    linter.open_scope();
+    linter.block(parsed.ast.nodes());
+    linter.close_scope();
+
+    // A rewritten `=` outside a condition is an ordinary syntax error
+    if let Some(rewrite) = parsed
+        .rewrites
+        .iter()
+        .find(|rewrite| !linter.claimed.contains(&rewrite.offset))
+    {
+        return vec![rewrite.error.clone()];
+    }
+
+    let mut findings = linter.findings;
+    findings.sort_by_key(|f| (f.line, f.column));
+    findings
+}
+
+fn syntax_finding(message: impl Into<String>, line: usize, column: usize) -> LintFinding {
+    LintFinding {
+        rule: "syntax",
+        severity: Severity::Error,
+        message: message.into(),
+        line,
+        column,
+    }
+}
+
+/// An `=` that stopped the parse and was replaced with `==` so the rest of
+/// the script could be linted.
+struct Rewrite {
+    /// Byte offset of the `==` in the rewritten source.
+    offset: usize,
+    /// The parse error to report if the `=` turns out not to be in a condition.
+    error: LintFinding,
+}
+
+struct Parsed {
+    ast: ast::Ast,
+    rewrites: Vec<Rewrite>,
+}
+
+/// Parse `source` as Lua 5.4. Lua rejects `if x = 1 then` outright, so each
+/// parse error on a lone `=` is retried with `==` in its place; the walk
+/// later decides whether that was an assignment in a condition.
+fn parse(source: &str) -> Result<Parsed, LintFinding> {
+    let mut code = source.to_string();
+    let mut rewrites: Vec<Rewrite> = Vec::new();
+
+    loop {
+        let result = full_moon::parse_fallible(&code, LuaVersion::lua54());
+        let Some(error) = result.errors().first() else {
+            return Ok(Parsed {
+                ast: result.into_ast(),
+                rewrites,
+            });
+        };
+
+        let start = error.range().0.bytes();
+        let (line, column) = location(source, original_offset(start, &rewrites));
+        let finding = match error {
+            full_moon::Error::AstError(error) => syntax_finding(
+                format!("{} near '{}'", error.error_message(), error.token()),
+                line,
+                column,
+            ),
+            full_moon::Error::TokenizerError(error) => {
+                syntax_finding(error.error().to_string(), line, column)
+            }
+        };
+
+        let is_lone_equals = matches!(error, full_moon::Error::AstError(error)
+            if error.token().to_string() == "=")
+            && !rewrites.iter().any(|rewrite| rewrite.offset == start);
+        if !is_lone_equals {
+            return Err(finding);
+        }
+        code.insert(start, '=');
+        rewrites.push(Rewrite {
+            offset: start,
+            error: finding,
+        });
+    }
+}
+
+/// Map a byte offset in the rewritten source back to `source`, undoing the
+/// extra `=` of each earlier rewrite.
+fn original_offset(offset: usize, rewrites: &[Rewrite]) -> usize {
+    offset
+        - rewrites
+            .iter()
+            .filter(|rewrite| rewrite.offset < offset)
+            .count()
+}
+
+/// 1-based line and column (in characters) of a byte offset.
+fn location(source: &str, offset: usize) -> (usize, usize) {
+    let before = &source[..offset.min(source.len())];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    (
+        before.matches('\n').count() + 1,
+        before[line_start..].chars().count() + 1,
+    )
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum LocalKind {
+    Local,
+    Parameter,
+    LoopVariable,
+    ImplicitSelf,
+}
+
+#[derive(Debug)]
+struct LocalVariable {
+    name: String,
+    kind: LocalKind,
+    line: usize,
+    column: usize,
+    used: bool,
+}
+
+struct Linter<'a> {
+    source: &'a str,
+    rewrites: &'a [Rewrite],
+    /// Rewrites found to be the comparison of a condition.
+    claimed: Vec<usize>,
+    scopes: Vec<Vec<LocalVariable>>,
+    options: &'a LintOptions,
+    findings: Vec<LintFinding>,
+}
+
+impl<'a> Linter<'a> {
+    fn new(source: &'a str, rewrites: &'a [Rewrite], options: &'a LintOptions) -> Self {
+        Self {
+            source,
+            rewrites,
+            claimed: Vec::new(),
+            scopes: Vec::new(),
+            options,
+            findings: Vec::new(),
+        }
+    }
+
+    fn position(&self, token: &TokenReference) -> (usize, usize) {
+        let offset = token.token().start_position().bytes();
+        location(self.source, original_offset(offset, self.rewrites))
+    }
+
+    fn report(
+        &mut self,
+        rule: &'static str,
+        severity: Severity,
+        message: String,
+        token: &TokenReference,
+    ) {
+        let (line, column) = self.position(token);
+        self.findings.push(LintFinding {
+            rule,
+            severity,
+            message,
+            line,
+            column,
+        });
+    }
+
+    fn open_scope(&mut self) {
+        self.scopes.push(Vec::new());
+    }
+
+    fn close_scope(&mut self) {
+        let Some(scope) = self.scopes.pop() else {
+            return;
+        };
+        for local in scope {
+            if local.kind == LocalKind::Local && !local.used && !local.name.starts_with('_') {
+                self.findings.push(LintFinding {
+                    rule: "unused-local",
+                    severity: Severity::Warning,
+                    message: format!("unused local '{}'", local.name),
+                    line: local.line,
+                    column: local.column,
+                });
+            }
+        }
+    }
+
+    fn declare(&mut self, token: &TokenReference, name: &str, kind: LocalKind) {
+        if kind != LocalKind::ImplicitSelf && !name.starts_with('_') {
+            let shadowed = self
+                .scopes
+                .iter()
+                .rev()
+                .flat_map(|scope| scope.iter().rev())
+                .find(|local| local.name == name)
+                .map(|local| local.line);
+            if let Some(line) = shadowed {
+                self.report(
+                    "shadowed-local",
+                    Severity::Warning,
+                    format!("local '{}' shadows a local defined on line {}", name, line),
+                    token,
+                );
+            }
+        }
+
+        let (line, column) = self.position(token);
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.push(LocalVariable {
+                name: name.to_string(),
+                kind,
+                line,
+                column,
+                used: false,
+            });
+        }
+    }
+
+    fn declare_name(&mut self, token: &TokenReference, kind: LocalKind) {
+        self.declare(token, &token.token().to_string(), kind);
+    }
+
+    fn resolve(&mut self, token: &TokenReference, is_read: bool) {
+        let name = token.token().to_string();
+        let local = self
+            .scopes
+            .iter_mut()
+            .rev()
+            .flat_map(|scope| scope.iter_mut().rev())
+            .find(|local| local.name == name);
+
+        match local {
+            Some(local) => {
+                if is_read {
+                    local.used = true;
+                }
+            }
+            None => {
+                if self.options.forbidden_globals.contains(&name) {
+                    self.report(
+                        "forbidden-global",
+                        Severity::Error,
+                        format!("use of forbidden global '{}'", name),
+                        token,
+                    );
+                }
+            }
+        }
+    }
+
+    fn block(&mut self, block: &Block) {
+        for statement in block.stmts() {
+            self.statement(statement);
+        }
+        if let Some(LastStmt::Return(ret)) = block.last_stmt() {
+            self.expressions(ret.returns());
+        }
+    }
+
+    fn scoped_block(&mut self, block: &Block) {
+        self.open_scope();
+        self.block(block);
+        self.close_scope();
+    }
+
+    fn statement(&mut self, statement: &Stmt) {
+        match statement {
+            Stmt::Assignment(assignment) => {
+                self.expressions(assignment.expressions());
+                for var in assignment.variables() {
+                    match var {
+                        Var::Name(name) => self.resolve(name, false),
+                        Var::Expression(var) => {
+                            self.prefix(var.prefix());
+                            self.suffixes(var.suffixes());
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            Stmt::Do(block) => self.scoped_block(block.block()),
+            Stmt::FunctionCall(call) => {
+                self.prefix(call.prefix());
+                self.suffixes(call.suffixes());
+            }
+            Stmt::FunctionDeclaration(declaration) => {
+                let name = declaration.name();
+                let is_method = name.method_name().is_some();
+                if let Some(first) = name.names().iter().next() {
+                    self.resolve(first, is_method || name.names().len() > 1);
+                }
+                self.function_body(declaration.body(), name.method_name());
+            }
+            Stmt::GenericFor(generic_for) => {
+                self.expressions(generic_for.expressions());
+                self.open_scope();
+                for name in generic_for.names() {
+                    self.declare_name(name, LocalKind::LoopVariable);
+                }
+                self.block(generic_for.block());
+                self.close_scope();
+            }
+            Stmt::NumericFor(numeric_for) => {
+                self.expression(numeric_for.start());
+                self.expression(numeric_for.end());
+                if let Some(step) = numeric_for.step() {
+                    self.expression(step);
+                }
+                self.open_scope();
+                self.declare_name(numeric_for.index_variable(), LocalKind::LoopVariable);
+                self.block(numeric_for.block());
+                self.close_scope();
+            }
+            Stmt::If(if_statement) => {
+                self.condition(if_statement.condition());
+                self.scoped_block(if_statement.block());
+                for else_if in if_statement.else_if().into_iter().flatten() {
+                    self.condition(else_if.condition());
+                    self.scoped_block(else_if.block());
+                }
+                if let Some(block) = if_statement.else_block() {
+                    self.scoped_block(block);
+                }
+            }
+            Stmt::LocalAssignment(assignment) => {
+                self.expressions(assignment.expressions());
+                for name in assignment.names() {
+                    self.declare_name(name, LocalKind::Local);
+                }
+            }
+            Stmt::LocalFunction(function) => {
+                self.declare_name(function.name(), LocalKind::Local);
+                self.function_body(function.body(), None);
+            }
+            Stmt::Repeat(repeat) => {
+                // The condition sees the locals of the loop body
+                self.open_scope();
+                self.block(repeat.block());
+                self.condition(repeat.until());
+                self.close_scope();
+            }
+            Stmt::While(while_loop) => {
+                self.condition(while_loop.condition());
+                self.scoped_block(while_loop.block());
+            }
+            _ => {}
+        }
+    }
+
+    fn condition(&mut self, condition: &Expression) {
+        let mut comparison = condition;
+        while let Expression::Parentheses { expression, .. } = comparison {
+            comparison = expression;
+        }
+        if let Expression::BinaryOperator {
+            binop: BinOp::TwoEqual(operator),
+            ..
+        } = comparison
+        {
+            let offset = operator.token().start_position().bytes();
+            if self.rewrites.iter().any(|rewrite| rewrite.offset == offset) {
+                self.claimed.push(offset);
+                self.report(
+                    "assignment-in-condition",
+                    Severity::Error,
+                    "assignment in condition; did you mean '=='?".to_string(),
+                    operator,
+                );
+            }
+        }
+        self.expression(condition);
+    }
+
+    fn function_body(&mut self, body: &FunctionBody, method_name: Option<&TokenReference>) {
+        self.open_scope();
+        if let Some(token) = method_name {
+            self.declare(token, "self", LocalKind::ImplicitSelf);
+        }
+        for parameter in body.parameters() {
+            if let Parameter::Name(name) = parameter {
+                self.declare_name(name, LocalKind::Parameter);
+            }
+        }
+        self.block(body.block());
+        self.close_scope();
+    }
+
+    fn expressions<'b>(&mut self, expressions: impl IntoIterator<Item = &'b Expression>) {
+        for expression in expressions {
+            self.expression(expression);
+        }
+    }
+
+    fn expression(&mut self, expression: &Expression) {
+        match expression {
+            Expression::BinaryOperator { lhs, rhs, .. } => {
+                self.expression(lhs);
+                self.expression(rhs);
+            }
+            Expression::Parentheses { expression, .. }
+            | Expression::UnaryOperator { expression, .. } => self.expression(expression),
+            Expression::Function(function) => self.function_body(function.body(), None),
+            Expression::FunctionCall(call) => {
+                self.prefix(call.prefix());
+                self.suffixes(call.suffixes());
+            }
+            Expression::TableConstructor(table) => self.table(table),
+            Expression::Var(Var::Name(name)) => self.resolve(name, true),
+            Expression::Var(Var::Expression(var)) => {
+                self.prefix(var.prefix());
+                self.suffixes(var.suffixes());
+            }
+            _ => {}
+        }
+    }
+
+    fn prefix(&mut self, prefix: &Prefix) {
+        match prefix {
+            Prefix::Name(name) => self.resolve(name, true),
+            Prefix::Expression(expression) => self.expression(expression),
+            _ => {}
+        }
+    }
+
+    fn suffixes<'b>(&mut self, suffixes: impl Iterator<Item = &'b Suffix>) {
+        for suffix in suffixes {
+            match suffix {
+                Suffix::Call(Call::AnonymousCall(args)) => self.arguments(args),
+                Suffix::Call(Call::MethodCall(call)) => self.arguments(call.args()),
+                Suffix::Index(Index::Brackets { expression, .. }) => self.expression(expression),
+                _ => {}
+            }
+        }
+    }
+
+    fn arguments(&mut self, args: &FunctionArgs) {
+        match args {
+            FunctionArgs::Parentheses { arguments, .. } => self.expressions(arguments),
+            FunctionArgs::TableConstructor(table) => self.table(table),
+            _ => {}
+        }
+    }
+
+    fn table(&mut self, table: &ast::TableConstructor) {
+        for field in table.fields() {
+            match field {
+                Field::ExpressionKey { key, value, .. } => {
+                    self.expression(key);
+                    self.expression(value);
+                }
+                Field::NameKey { value, .. } => self.expression(value),
+                Field::NoKey(value) => self.expression(value),
+                _ => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(source: &str, options: &LintOptions) -> Vec<&'static str> {
+        lint_source(source, options)
+            .into_iter()
+            .map(|f| f.rule)
+            .collect()
+    }
+
+    #[test]
+    fn test_reports_unused_local() {
+        let findings = lint_source(
+            "local used = 1\nlocal unused = 2\nprint(used)\n",
+            &LintOptions::default(),
+        );
+
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].rule, "unused-local");
+        assert_eq!(findings[0].severity, Severity::Warning);
+        assert_eq!(findings[0].message, "unused local 'unused'");
+        assert_eq!((findings[0].line, findings[0].column), (2, 7));
+    }
+
+    #[test]
+    fn test_underscore_locals_and_assignments() {
+        assert!(rules("local _ignored = 1", &LintOptions::default()).is_empty());
+        assert_eq!(
+            rules("local x\nx = 5", &LintOptions::default()),
+            vec!["unused-local"]
+        );
+        assert!(rules("local t = {}\nt.x = 5", &LintOptions::default()).is_empty());
+    }
+
+    #[test]
+    fn test_reports_forbidden_global_under_strict_policy() {
+        let source = "local f = load('return 1')\nprint(f())\n";
+
+        assert!(rules(source, &LintOptions::default()).is_empty());
+
+        let findings = lint_source(source, &LintOptions::strict());
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].rule, "forbidden-global");
+        assert_eq!(findings[0].severity, Severity::Error);
+        assert_eq!((findings[0].line, findings[0].column), (1, 11));
+    }
+
+    #[test]
+    fn test_local_named_like_forbidden_global_is_allowed() {
+        let source = "local load = function() end\nload()\nlocal t = {require = 1}\n";
+        assert_eq!(rules(source, &LintOptions::strict()), vec!["unused-local"]);
+    }
+
+    #[test]
+    fn test_reports_shadowed_variable() {
+        let findings = lint_source(
+            "local value = 1\nlocal function f(value)\n  return value\nend\nreturn f(value)\n",
+            &LintOptions::default(),
+        );
+
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].rule, "shadowed-local");
+        assert!(findings[0].message.contains("line 1"));
+    }
+
+    #[test]
+    fn test_reports_assignment_in_condition() {
+        let findings = lint_source(
+            "local x = 1\nif x = 2 then print(x) end\n",
+            &LintOptions::default(),
+        );
+
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].rule, "assignment-in-condition");
+        assert_eq!(findings[0].severity, Severity::Error);
+        assert_eq!((findings[0].line, findings[0].column), (2, 6));
+    }
+
+    #[test]
+    fn test_assignment_in_loop_conditions_and_elsewhere() {
+        let source = "local x = 1\nwhile (x = 2) do end\nrepeat local y = x until y = 3\n";
+        let findings = lint_source(source, &LintOptions::default());
+        assert_eq!(
+            findings.iter().map(|f| f.rule).collect::<Vec<_>>(),
+            vec!["assignment-in-condition", "assignment-in-condition"]
+        );
+        assert_eq!((findings[1].line, findings[1].column), (3, 28));
+
+        // Outside a condition a stray '=' is just a syntax error
+        assert_eq!(
+            rules("print(x = 1)", &LintOptions::default()),
+            vec!["syntax"]
+        );
+    }
+
+    #[test]
+    fn test_clean_source_has_no_findings() {
+        let source = r##"#!/usr/bin/env hype
+local M = {}
+--[[ long
+comment ]]
+function M:greet(name, ...)
+  local parts = {[1] = "hi", name, n = select("#", ...)}
+  for i, part in ipairs(parts) do
+    if part ~= nil and i >= 1 then
+      io.write(tostring(part) .. [==[ ]] ]==])
+    end
+  end
+  repeat local done = true until done
+  return self, #parts
+end
+return M
+"##;
+        assert!(rules(source, &LintOptions::default()).is_empty());
+    }
+
+    #[test]
+    fn test_syntax_error_is_reported() {
+        let findings = lint_source("local x = = 1", &LintOptions::default());
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].rule, "syntax");
+        assert_eq!(findings[0].severity, Severity::Error);
+    }
+}
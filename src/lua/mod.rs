@@ -1,16 +1,19 @@
+pub mod bytecode;
 pub mod debug;
 pub mod env;
 pub mod environment;
 pub mod error;
 pub mod lifecycle;
+pub mod lint;
 pub mod module_env;
 pub mod path;
 pub mod require;
 pub mod security;
 pub mod state;
 
-use crate::error::Result;
+use crate::error::{HypeError, Result};
 use crate::file_io::read_lua_script;
+use crate::modules::builtins::util::operations::{inspect as inspect_value, InspectOptions};
 use std::path::Path;
 use std::time::Duration;
 
@@ -22,9 +25,12 @@ pub use lifecycle::{
     LuaStateHandle, LuaStateLifecycleManager, LuaStatePool as LifecyclePool, StateInfo,
 };
 pub use module_env::{create_module_env, ModuleEnvironment};
-pub use require::{setup_require_fn, RequireSetup};
-pub use security::{FileOperationPolicy, OsOperationPolicy, SecurityManager, SecurityPolicy};
-pub use state::{LuaStateConfig, LuaStateManager, LuaStateMetrics, LuaStatePool};
+pub use require::{preload_modules, setup_require_fn, RequireSetup};
+pub use security::{bytecode_allowed, subprocess_allowed};
+pub use security::{
+    FileOperationPolicy, OsOperationPolicy, SandboxLevel, SecurityManager, SecurityPolicy,
+};
+pub use state::{LuaStateConfig, LuaStateManager, LuaStateMetrics, LuaStatePool, PooledState};
 
 /// Execute a Lua script with the new comprehensive state management
 pub fn execute_script(
@@ -120,13 +126,69 @@ pub fn execute_code(
     state_manager.execute_code(code)
 }
 
-/// Create a default Lua state configuration for CLI usage
-pub fn create_cli_config(verbose: bool, debug: bool, timeout: Option<Duration>) -> LuaStateConfig {
-    let mut config = LuaStateConfig::default();
+/// Execute Lua code on a state borrowed from `pool` instead of a fresh VM.
+///
+/// The state's globals and security policy are reset before it is reused, so
+/// globals set by one call are not visible to the next.
+pub fn execute_pooled(pool: &LuaStatePool, code: &str) -> Result<String> {
+    pool.acquire()?.execute_code(code)
+}
 
-    if let Some(timeout) = timeout {
-        config.time_limit = Some(timeout);
+/// Compile Lua source without executing it, naming the chunk for error messages.
+pub fn check_source(source: &str, chunk_name: &str) -> Result<()> {
+    let lua = mlua::Lua::new();
+    lua.load(source)
+        .set_name(format!("@{}", chunk_name))
+        .into_function()
+        .map(|_| ())
+        .map_err(|e| match e {
+            mlua::Error::SyntaxError { message, .. } => HypeError::Lua(message),
+            other => HypeError::from(other),
+        })
+}
+
+/// The line `print` writes: each value as `tostring` renders it, separated
+/// by tabs. With `pretty`, tables without a `__tostring` are shown with
+/// `util.inspect` instead of their address.
+pub fn format_print_line(
+    lua: &mlua::Lua,
+    values: mlua::MultiValue,
+    pretty: bool,
+) -> mlua::Result<String> {
+    let tostring: mlua::Function = lua.globals().get("tostring")?;
+    let mut parts = Vec::with_capacity(values.len());
+    for value in values {
+        let inspect = match &value {
+            mlua::Value::Table(table) if pretty => !table
+                .get_metatable()
+                .is_some_and(|mt| mt.contains_key("__tostring").unwrap_or(false)),
+            _ => false,
+        };
+        parts.push(if inspect {
+            inspect_value(&value, &InspectOptions::default())?
+        } else {
+            tostring.call::<_, String>(value)?
+        });
     }
+    Ok(parts.join("\t"))
+}
+
+/// Module names passed to `require` as string literals.
+pub fn find_static_requires(source: &str) -> Vec<String> {
+    let re = regex::Regex::new(r#"require\s*\(?\s*["']([^"']+)["']"#).unwrap();
+    re.captures_iter(source)
+        .map(|caps| caps[1].to_string())
+        .collect()
+}
+
+/// Create a default Lua state configuration for CLI usage
+pub fn create_cli_config(verbose: bool, debug: bool, timeout: Option<Duration>) -> LuaStateConfig {
+    let mut config = LuaStateConfig {
+        time_limit: timeout,
+        // Scripts run unbounded unless --instruction-limit is given
+        instruction_limit: None,
+        ..LuaStateConfig::default()
+    };
 
     if debug {
         config.allow_debug_operations = true;
@@ -143,10 +205,28 @@ pub fn create_cli_config(verbose: bool, debug: bool, timeout: Option<Duration>)
     config
 }
 
-/// Create a security policy for CLI usage
-pub fn create_cli_security_policy(debug: bool) -> SecurityPolicy {
+/// Create a security policy for CLI usage at the given `--sandbox` level
+pub fn create_cli_security_policy(debug: bool, sandbox: SandboxLevel) -> SecurityPolicy {
     let mut policy = SecurityPolicy::default();
 
+    match sandbox {
+        SandboxLevel::Strict => {
+            policy.allowed_file_operations.write = false;
+            policy.allowed_file_operations.append = false;
+            policy.network_policy.deny_all = true;
+            policy.allow_subprocess = false;
+            policy.allow_bytecode = false;
+        }
+        SandboxLevel::Default => {}
+        SandboxLevel::Unsafe => {
+            policy.allow_subprocess = true;
+            policy.allow_debug = true;
+            policy.allow_package_loading = true;
+            policy.environment_policy.allow_write = true;
+            policy.environment_policy.allow_sensitive_read = true;
+        }
+    }
+
     if debug {
         policy.allow_debug = true;
         policy.allow_package_loading = true;
@@ -162,6 +242,7 @@ pub fn create_cli_security_policy(debug: bool) -> SecurityPolicy {
 mod tests {
     use super::*;
     use mlua::Value;
+    use std::sync::Arc;
 
     #[test]
     fn test_execute_code_simple() {
@@ -173,17 +254,98 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_execute_pooled_reuses_state_without_leaking_globals() {
+        let pool = LuaStatePool::new(LuaStateConfig::default(), 1).unwrap();
+
+        let first_vm = {
+            let state = pool.acquire().unwrap();
+            let result = state
+                .execute_code("counter = 41; string = nil; tostring = function() return 'x' end; return counter + 1")
+                .unwrap();
+            assert_eq!(result, "42");
+            Arc::as_ptr(&state.lua)
+        };
+        assert_eq!(pool.available(), 1);
+
+        let state = pool.acquire().unwrap();
+        assert_eq!(Arc::as_ptr(&state.lua), first_vm, "state should be reused");
+        let result = state
+            .execute_code("return tostring(counter) .. ' ' .. type(string)")
+            .unwrap();
+        assert_eq!(result, "\"nil table\"");
+        drop(state);
+
+        assert_eq!(
+            execute_pooled(&pool, "leaked = true; return 1").unwrap(),
+            "1"
+        );
+        assert_eq!(
+            execute_pooled(&pool, "return leaked == nil").unwrap(),
+            "true"
+        );
+        assert_eq!(pool.available(), 1);
+    }
+
     #[test]
     fn test_cli_config_creation() {
         let config = create_cli_config(true, false, Some(Duration::from_secs(10)));
         assert!(config.allow_debug_operations);
         assert_eq!(config.time_limit, Some(Duration::from_secs(10)));
+
+        let config = create_cli_config(false, false, None);
+        assert_eq!(config.time_limit, None);
+    }
+
+    #[test]
+    fn test_check_source_reports_location() {
+        assert!(check_source("local x = 1\nreturn x", "ok.lua").is_ok());
+
+        let err = check_source("local x = 1\nlocal = 2\n", "broken.lua").unwrap_err();
+        let message = err.to_string();
+        assert!(message.contains("broken.lua:2:"), "{}", message);
+    }
+
+    #[test]
+    fn test_check_source_does_not_execute() {
+        assert!(check_source("error('should not run')", "side_effects.lua").is_ok());
+    }
+
+    #[test]
+    fn test_find_static_requires() {
+        let source = r#"
+            local fs = require("fs")
+            local utils = require './lib/utils'
+            local dynamic = require(name)
+        "#;
+        assert_eq!(find_static_requires(source), vec!["fs", "./lib/utils"]);
     }
 
     #[test]
     fn test_cli_security_policy() {
-        let policy = create_cli_security_policy(true);
+        let policy = create_cli_security_policy(true, SandboxLevel::Default);
         assert!(policy.allow_debug);
         assert!(policy.allow_package_loading);
     }
+
+    #[test]
+    fn test_cli_security_policy_sandbox_levels() {
+        let strict = create_cli_security_policy(false, SandboxLevel::Strict);
+        assert!(!strict.allowed_file_operations.write);
+        assert!(strict.allowed_file_operations.read);
+        assert!(strict.network_policy.deny_all);
+        assert!(!strict.allow_subprocess);
+        assert!(!strict.allow_bytecode);
+
+        let default = create_cli_security_policy(false, SandboxLevel::Default);
+        assert!(default.allowed_file_operations.write);
+        assert!(!default.network_policy.deny_all);
+        assert!(!default.allow_subprocess);
+        assert!(default.allow_bytecode);
+
+        let unsafe_policy = create_cli_security_policy(false, SandboxLevel::Unsafe);
+        assert!(unsafe_policy.allowed_file_operations.write);
+        assert!(unsafe_policy.allow_subprocess);
+        assert!(unsafe_policy.environment_policy.allow_write);
+    }
 }
@@ -1,9 +1,12 @@
-use mlua::{Lua, Table, Value};
+use mlua::{Lua, Table, TableExt, Value};
 use serde_json::Value as JsonValue;
+use std::ffi::c_void;
 use std::sync::{Arc, Mutex};
 
-use crate::error::Result;
-use crate::modules::loader::ModuleLoader;
+use crate::error::{HypeError, Result, StructuredError};
+use crate::lua::error::{structured_error, wrap_structured_errors};
+use crate::modules::builtins::json::operations::encode_number;
+use crate::modules::loader::{error_message, execute_user_module, ModuleLoader};
 
 pub struct RequireSetup;
 
@@ -43,10 +46,10 @@ pub fn setup_require_fn(lua: &Lua, loader: Arc<Mutex<ModuleLoader>>) -> Result<(
                 let result = loader_lock
                     .load_builtin_with_lua(lua_ctx, &module_id)
                     .map_err(|err| {
-                        mlua::Error::RuntimeError(format!(
-                            "Failed to load builtin module '{}': {}",
-                            module_id, err
-                        ))
+                        module_error(
+                            "ERR_MODULE_LOAD",
+                            format!("Failed to load builtin module '{}': {}", module_id, err),
+                        )
                     })?;
                 
                 // Add module metadata to builtin modules
@@ -62,21 +65,47 @@ pub fn setup_require_fn(lua: &Lua, loader: Arc<Mutex<ModuleLoader>>) -> Result<(
                 return Ok(result);
             }
 
-            // Load user-defined module directly with Lua execution
+            // Get the path for the cache key
+            let path = match loader_lock.resolver().resolve(&module_id) {
+                Ok(path) => path,
+                Err(err) => {
+                    let err = loader_lock.chain_error(&module_id, error_message(err));
+                    return Err(module_error(
+                        "MODULE_NOT_FOUND",
+                        format!(
+                            "Failed to resolve module '{}': {}",
+                            module_id,
+                            error_message(err)
+                        ),
+                    ));
+                }
+            };
+
+            let load_error = |err: HypeError| {
+                module_error(
+                    "ERR_MODULE_LOAD",
+                    format!(
+                        "Failed to load module '{}': {}",
+                        module_id,
+                        error_message(err)
+                    ),
+                )
+            };
+            loader_lock
+                .begin_user_module(&module_id, &path)
+                .map_err(load_error)?;
+
+            // Unlock while the module runs so it can require others
+            drop(loader_lock);
+            let result = execute_user_module(lua_ctx, &path, &module_id);
+            let mut loader_lock = loader_clone.lock().map_err(|_| {
+                mlua::Error::RuntimeError("Failed to acquire module loader lock".to_string())
+            })?;
             let lua_exports = loader_lock
-                .load_user_module_with_lua(lua_ctx, &module_id)
-                .map_err(|err| {
-                    mlua::Error::RuntimeError(format!("Failed to load module '{}': {}", module_id, err))
-                })?;
+                .finish_user_module(&module_id, &path, result)
+                .map_err(load_error)?;
 
             // Add to require.cache
-            let require_table: Table = lua_ctx.globals().get("require")?;
-            let cache_table: Table = require_table.get("cache")?;
-            
-            // Get the path for the cache key
-            let path = loader_lock.resolver().resolve(&module_id).map_err(|err| {
-                mlua::Error::RuntimeError(format!("Failed to resolve module '{}': {}", module_id, err))
-            })?;
             let cache_key = path.to_string_lossy().to_string();
             cache_table.set(cache_key, lua_exports.clone())?;
 
@@ -86,9 +115,10 @@ pub fn setup_require_fn(lua: &Lua, loader: Arc<Mutex<ModuleLoader>>) -> Result<(
         })?;
 
     create_resolve_fn(lua, &require_table, Arc::clone(&loader))?;
+    create_builtin_fns(lua, &require_table, Arc::clone(&loader))?;
 
     let metatable = lua.create_table()?;
-    metatable.set("__call", require_fn)?;
+    metatable.set("__call", wrap_structured_errors(lua, require_fn)?)?;
     require_table.set_metatable(Some(metatable));
 
     globals.set("require", require_table)?;
@@ -104,6 +134,10 @@ pub fn setup_require_fn(lua: &Lua, loader: Arc<Mutex<ModuleLoader>>) -> Result<(
     Ok(())
 }
 
+fn module_error(code: &str, message: String) -> mlua::Error {
+    structured_error(StructuredError::new("module", code, message))
+}
+
 fn update_require_cache(lua: &Lua, loader: &ModuleLoader) -> mlua::Result<()> {
     let require_table: Table = lua.globals().get("require")?;
     let cache_table: Table = require_table.get("cache")?;
@@ -152,6 +186,55 @@ fn create_resolve_fn(
     Ok(())
 }
 
+/// Require each of `modules` in order, as `hype run -r` does before the
+/// script starts. Their exports stay in `require.cache` for the script.
+pub fn preload_modules(lua: &Lua, modules: &[String]) -> Result<()> {
+    let require: Table = lua.globals().get("require")?;
+    for module_id in modules {
+        require.call::<_, Value>(module_id.as_str()).map_err(|e| {
+            let message = match e {
+                mlua::Error::RuntimeError(message) => message,
+                other => other.to_string(),
+            };
+            // The traceback only points into the require machinery
+            let message = message
+                .split("\nstack traceback:")
+                .next()
+                .unwrap_or_default();
+            HypeError::Execution(format!(
+                "Failed to preload module '{}': {}",
+                module_id, message
+            ))
+        })?;
+    }
+    Ok(())
+}
+
+/// `require.builtins()` and `require.has(name)`, so scripts can check for
+/// modules that depend on cargo features (such as `http`) before requiring them.
+fn create_builtin_fns(
+    lua: &Lua,
+    require_table: &Table,
+    loader: Arc<Mutex<ModuleLoader>>,
+) -> mlua::Result<()> {
+    let names = loader
+        .lock()
+        .map_err(|_| mlua::Error::RuntimeError("Failed to acquire module loader lock".to_string()))?
+        .builtin_names();
+
+    let list = names.clone();
+    let builtins_fn = lua.create_function(move |lua_ctx: &Lua, ()| {
+        let mut sorted = list.clone();
+        sorted.sort_unstable();
+        lua_ctx.create_sequence_from(sorted)
+    })?;
+    require_table.set("builtins", builtins_fn)?;
+
+    let has_fn = lua.create_function(move |_, name: String| Ok(names.contains(&name.as_str())))?;
+    require_table.set("has", has_fn)?;
+    Ok(())
+}
+
 pub fn json_to_lua<'a>(lua: &'a Lua, value: &JsonValue) -> Result<Value<'a>> {
     match value {
         JsonValue::Null => Ok(Value::Nil),
@@ -185,6 +268,132 @@ pub fn json_to_lua<'a>(lua: &'a Lua, value: &JsonValue) -> Result<Value<'a>> {
     }
 }
 
+/// Tables nested deeper than this are rejected instead of overflowing the stack.
+const MAX_JSON_DEPTH: usize = 512;
+
+/// Convert a Lua value to JSON, the inverse of [`json_to_lua`].
+///
+/// A table becomes an array when its keys are exactly `1..n` and an object
+/// otherwise, so sparse arrays keep their indices as string keys. The empty
+/// table is an object. A table whose metatable has `__tojson` is replaced by
+/// whatever that function returns. Functions, userdata and threads, object
+/// keys that are not strings or numbers, NaN/infinite numbers and tables
+/// that contain themselves raise an error.
+pub fn lua_to_json(lua: &Lua, value: Value) -> mlua::Result<JsonValue> {
+    lua_to_json_with(lua, value, false)
+}
+
+/// [`lua_to_json`], writing NaN and infinite numbers as `null` when
+/// `allow_non_finite` is set.
+pub fn lua_to_json_with(
+    _lua: &Lua,
+    value: Value,
+    allow_non_finite: bool,
+) -> mlua::Result<JsonValue> {
+    let mut conversion = Conversion {
+        parents: Vec::new(),
+        allow_non_finite,
+    };
+    value_to_json(value, &mut conversion)
+}
+
+struct Conversion {
+    /// The tables being converted, outermost first, to catch cycles.
+    parents: Vec<*const c_void>,
+    allow_non_finite: bool,
+}
+
+fn value_to_json(value: Value, conversion: &mut Conversion) -> mlua::Result<JsonValue> {
+    match value {
+        Value::Nil => Ok(JsonValue::Null),
+        Value::Boolean(b) => Ok(JsonValue::Bool(b)),
+        Value::Integer(i) => Ok(JsonValue::Number(i.into())),
+        Value::Number(n) => {
+            encode_number(n, conversion.allow_non_finite).map_err(|e| json_error(&e.to_string()))
+        }
+        Value::String(s) => Ok(JsonValue::String(s.to_str()?.to_string())),
+        Value::Table(table) => {
+            let pointer = table.to_pointer();
+            if conversion.parents.contains(&pointer) {
+                return Err(json_error("cannot convert a table that contains itself"));
+            }
+            if conversion.parents.len() >= MAX_JSON_DEPTH {
+                return Err(json_error("tables are nested too deeply"));
+            }
+            conversion.parents.push(pointer);
+            let result = match tojson_metamethod(&table)? {
+                Some(tojson) => match tojson.call::<_, Value>(table.clone())? {
+                    // Returning the table itself means "encode my fields"
+                    Value::Table(same) if same == table => table_to_json(same, conversion),
+                    replacement => value_to_json(replacement, conversion),
+                },
+                None => table_to_json(table, conversion),
+            };
+            conversion.parents.pop();
+            result
+        }
+        other => Err(json_error(&format!(
+            "cannot convert a {} to JSON",
+            other.type_name()
+        ))),
+    }
+}
+
+fn table_to_json(table: Table, conversion: &mut Conversion) -> mlua::Result<JsonValue> {
+    if is_sequence(&table)? {
+        let mut array = Vec::new();
+        for value in table.sequence_values::<Value>() {
+            array.push(value_to_json(value?, conversion)?);
+        }
+        return Ok(JsonValue::Array(array));
+    }
+
+    let mut object = serde_json::Map::new();
+    for pair in table.pairs::<Value, Value>() {
+        let (key, value) = pair?;
+        let key = match key {
+            Value::String(s) => s.to_str()?.to_string(),
+            Value::Integer(i) => i.to_string(),
+            Value::Number(n) => n.to_string(),
+            other => {
+                return Err(json_error(&format!(
+                    "object keys must be strings or numbers, not {}",
+                    other.type_name()
+                )))
+            }
+        };
+        object.insert(key, value_to_json(value, conversion)?);
+    }
+    Ok(JsonValue::Object(object))
+}
+
+/// True when the keys of `table` are exactly the integers `1..n`, with n > 0.
+fn is_sequence(table: &Table) -> mlua::Result<bool> {
+    let mut count = 0;
+    let mut max_index = 0;
+    for pair in table.clone().pairs::<Value, Value>() {
+        match pair?.0 {
+            Value::Integer(i) if i > 0 => {
+                max_index = max_index.max(i);
+                count += 1;
+            }
+            _ => return Ok(false),
+        }
+    }
+    Ok(count > 0 && count == max_index)
+}
+
+fn tojson_metamethod<'lua>(table: &Table<'lua>) -> mlua::Result<Option<mlua::Function<'lua>>> {
+    match table.get_metatable() {
+        Some(metatable) => metatable.raw_get("__tojson"),
+        None => Ok(None),
+    }
+}
+
+fn json_error(message: &str) -> mlua::Error {
+    mlua::Error::RuntimeError(format!("JSON encoding failed: {}", message))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -660,4 +869,49 @@ mod tests {
             .eval();
         assert_eq!(result.unwrap(), true);
     }
+
+    #[test]
+    fn test_require_missing_module_has_module_not_found_code() {
+        let lua = Lua::new();
+        let dir = tempfile::tempdir().unwrap();
+        let loader = Arc::new(Mutex::new(ModuleLoader::new(dir.path().to_path_buf())));
+        setup_require_fn(&lua, loader).unwrap();
+
+        let (code, kind): (String, String) = lua
+            .load("local ok, err = pcall(require, './nope') return err.code, err.kind")
+            .eval()
+            .unwrap();
+        assert_eq!(code, "MODULE_NOT_FOUND");
+        assert_eq!(kind, "module");
+    }
+
+    #[test]
+    fn test_lua_to_json_tables() {
+        let lua = Lua::new();
+        let value: Value = lua
+            .load("return {list = {1, 2}, sparse = {[1] = true, [3] = true}, name = 'x'}")
+            .eval()
+            .unwrap();
+        assert_eq!(
+            lua_to_json(&lua, value).unwrap(),
+            serde_json::json!({"list": [1, 2], "sparse": {"1": true, "3": true}, "name": "x"})
+        );
+
+        let function: Value = lua.load("return print").eval().unwrap();
+        assert!(lua_to_json(&lua, function).is_err());
+        assert!(lua_to_json(&lua, Value::Number(f64::NAN)).is_err());
+    }
+
+    #[test]
+    fn test_lua_to_json_tojson_returning_self() {
+        let lua = Lua::new();
+        let value: Value = lua
+            .load("return setmetatable({a = 1}, {__tojson = function(t) return t end})")
+            .eval()
+            .unwrap();
+        assert_eq!(
+            lua_to_json(&lua, value).unwrap(),
+            serde_json::json!({"a": 1})
+        );
+    }
 }
@@ -1,7 +1,8 @@
 use crate::error::{HypeError, Result};
 use mlua::{Function, Lua, Table, Value};
 use std::collections::HashSet;
-use std::path::{Path, PathBuf};
+use std::net::{IpAddr, Ipv4Addr};
+use std::path::{Component, Path, PathBuf};
 use std::sync::{Arc, RwLock};
 use std::time::{Duration, Instant};
 
@@ -13,6 +14,7 @@ pub struct SecurityPolicy {
     pub denied_functions: HashSet<String>,
     pub allowed_file_operations: FileOperationPolicy,
     pub allowed_os_operations: OsOperationPolicy,
+    pub network_policy: NetworkPolicy,
     pub memory_limit: Option<usize>,
     pub execution_time_limit: Option<Duration>,
     pub instruction_limit: Option<u32>,
@@ -20,9 +22,42 @@ pub struct SecurityPolicy {
     pub allow_package_loading: bool,
     pub allow_coroutine_creation: bool,
     pub allow_metatable_manipulation: bool,
+    pub allow_subprocess: bool,
+    /// Run precompiled `.luac` scripts. Lua does not verify binary chunks,
+    /// so crafted bytecode can break out of the VM.
+    pub allow_bytecode: bool,
     pub environment_policy: EnvironmentOperationPolicy,
 }
 
+/// The security profiles picked with `hype run --sandbox <level>`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SandboxLevel {
+    /// No file writes, network access, subprocesses or precompiled bytecode.
+    Strict,
+    /// The standard CLI policy: files and network allowed, subprocesses only
+    /// with `--allow-subprocess`.
+    #[default]
+    Default,
+    /// Everything allowed, including subprocesses and debug access.
+    Unsafe,
+}
+
+impl std::str::FromStr for SandboxLevel {
+    type Err = String;
+
+    fn from_str(level: &str) -> std::result::Result<Self, Self::Err> {
+        match level {
+            "strict" => Ok(SandboxLevel::Strict),
+            "default" => Ok(SandboxLevel::Default),
+            "unsafe" => Ok(SandboxLevel::Unsafe),
+            other => Err(format!(
+                "Invalid sandbox level '{}': expected strict, default or unsafe",
+                other
+            )),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct FileOperationPolicy {
     pub read: bool,
@@ -43,6 +78,18 @@ pub struct OsOperationPolicy {
     pub setenv: bool,
 }
 
+/// Restrictions on outgoing network requests made by builtin modules.
+#[derive(Debug, Clone, Default)]
+pub struct NetworkPolicy {
+    /// Hosts that may be contacted. `*.example.com` also matches subdomains.
+    /// An empty list permits every host.
+    pub allowed_hosts: Vec<String>,
+    /// Reject loopback, private and link-local addresses (SSRF protection).
+    pub deny_private_ips: bool,
+    /// Block all network access: outgoing requests and listening servers.
+    pub deny_all: bool,
+}
+
 #[derive(Debug, Clone)]
 pub struct EnvironmentOperationPolicy {
     pub allow_read: bool,
@@ -88,9 +135,9 @@ impl Default for SecurityPolicy {
                 "newproxy".to_string(),
             ]),
             allowed_file_operations: FileOperationPolicy {
-                read: false,
-                write: false,
-                append: false,
+                read: true,
+                write: true,
+                append: true,
                 allowed_paths: vec![],
                 denied_paths: vec![],
                 max_file_size: Some(1024 * 1024), // 1MB default
@@ -103,6 +150,7 @@ impl Default for SecurityPolicy {
                 getenv: false,
                 setenv: false,
             },
+            network_policy: NetworkPolicy::default(),
             memory_limit: Some(64 * 1024 * 1024), // 64MB default
             execution_time_limit: Some(Duration::from_secs(30)),
             instruction_limit: Some(1000000), // 1M instructions
@@ -110,6 +158,8 @@ impl Default for SecurityPolicy {
             allow_package_loading: false,
             allow_coroutine_creation: false,
             allow_metatable_manipulation: false,
+            allow_subprocess: false,
+            allow_bytecode: true,
             environment_policy: EnvironmentOperationPolicy {
                 allow_read: true,
                 allow_write: false,
@@ -145,6 +195,205 @@ impl Default for SecurityPolicy {
     }
 }
 
+impl SecurityPolicy {
+    /// Attach this policy to a Lua state so builtin modules can consult it at call time.
+    pub fn install(&self, lua: &Lua) {
+        lua.set_app_data(self.clone());
+    }
+}
+
+/// Whether the policy installed on `lua` permits spawning subprocesses.
+///
+/// States without an installed policy are treated as sandboxed.
+pub fn subprocess_allowed(lua: &Lua) -> bool {
+    lua.app_data_ref::<SecurityPolicy>()
+        .map(|policy| policy.allow_subprocess)
+        .unwrap_or(false)
+}
+
+/// Whether the policy installed on `lua` permits running binary chunks.
+/// States without an installed policy are unrestricted.
+pub fn bytecode_allowed(lua: &Lua) -> bool {
+    lua.app_data_ref::<SecurityPolicy>()
+        .map(|policy| policy.allow_bytecode)
+        .unwrap_or(true)
+}
+
+impl FileOperationPolicy {
+    /// Check `operation` against the read/write switches, then `path`
+    /// against the denied and allowed path prefixes.
+    ///
+    /// Both the target and the configured roots are resolved first (made
+    /// absolute, symlinks followed, `..` applied), so traversal such as
+    /// `allowed/../secret` or a symlink pointing outside an allowed root is
+    /// checked against where it actually leads. An empty allowlist permits
+    /// every path that is not denied.
+    pub fn check_path(&self, path: &Path, operation: &str) -> Result<PathBuf> {
+        let permitted = match operation {
+            "write" => self.write,
+            "append" => self.append,
+            _ => self.read,
+        };
+        if !permitted {
+            return Err(HypeError::Security(format!(
+                "File access denied: {} of '{}' is disabled by the security policy",
+                operation,
+                path.display()
+            )));
+        }
+
+        let resolved = resolve_path(path);
+
+        if self
+            .denied_paths
+            .iter()
+            .any(|denied| resolved.starts_with(resolve_path(denied)))
+        {
+            return Err(HypeError::Security(format!(
+                "File access denied: {} of '{}' is in a denied path",
+                operation,
+                path.display()
+            )));
+        }
+
+        if !self.allowed_paths.is_empty()
+            && !self
+                .allowed_paths
+                .iter()
+                .any(|allowed| resolved.starts_with(resolve_path(allowed)))
+        {
+            return Err(HypeError::Security(format!(
+                "File access denied: {} of '{}' is outside the allowed paths",
+                operation,
+                path.display()
+            )));
+        }
+
+        Ok(resolved)
+    }
+}
+
+/// Resolve `path` to an absolute path with symlinks followed for the parts
+/// that exist and `.`/`..` applied lexically for the parts that do not.
+pub fn resolve_path(path: &Path) -> PathBuf {
+    let mut resolved = if path.is_absolute() {
+        PathBuf::new()
+    } else {
+        std::env::current_dir()
+            .and_then(|cwd| cwd.canonicalize())
+            .unwrap_or_default()
+    };
+
+    for component in path.components() {
+        match component {
+            Component::Prefix(_) | Component::RootDir => resolved.push(component.as_os_str()),
+            Component::CurDir => {}
+            Component::ParentDir => {
+                resolved.pop();
+            }
+            Component::Normal(name) => {
+                resolved.push(name);
+                if let Ok(real) = resolved.canonicalize() {
+                    resolved = real;
+                }
+            }
+        }
+    }
+
+    resolved
+}
+
+/// Check a filesystem access from a builtin module against the policy
+/// installed on `lua`. States without an installed policy are unrestricted.
+pub fn check_file_access(lua: &Lua, path: &str, operation: &str) -> Result<()> {
+    match lua.app_data_ref::<SecurityPolicy>() {
+        Some(policy) => policy
+            .allowed_file_operations
+            .check_path(Path::new(path), operation)
+            .map(|_| ()),
+        None => Ok(()),
+    }
+}
+
+impl NetworkPolicy {
+    /// Fail when `deny_all` turns off network access altogether.
+    pub fn check_enabled(&self) -> Result<()> {
+        if self.deny_all {
+            return Err(HypeError::Security(
+                "Network access denied: networking is disabled by the security policy".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Check that `host` is on the allowlist.
+    pub fn check_host(&self, host: &str) -> Result<()> {
+        self.check_enabled()?;
+        let host = host
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .to_ascii_lowercase();
+
+        if self.allowed_hosts.is_empty()
+            || self.allowed_hosts.iter().any(|allowed| {
+                let allowed = allowed.to_ascii_lowercase();
+                match allowed.strip_prefix("*.") {
+                    Some(domain) => host == domain || host.ends_with(&format!(".{}", domain)),
+                    None => host == allowed,
+                }
+            })
+        {
+            Ok(())
+        } else {
+            Err(HypeError::Security(format!(
+                "Network access denied: host '{}' is not in the allowed hosts",
+                host
+            )))
+        }
+    }
+
+    /// Check the addresses a host resolves to when `deny_private_ips` is set.
+    pub fn check_addrs(&self, host: &str, addrs: &[IpAddr]) -> Result<()> {
+        if !self.deny_private_ips {
+            return Ok(());
+        }
+        match addrs.iter().find(|ip| is_private_ip(ip)) {
+            Some(ip) => Err(HypeError::Security(format!(
+                "Network access denied: host '{}' resolves to private address {}",
+                host, ip
+            ))),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Whether `ip` is a loopback, private, link-local or otherwise non-public address.
+pub fn is_private_ip(ip: &IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => is_private_ipv4(v4),
+        IpAddr::V6(v6) => match v6.to_ipv4_mapped() {
+            Some(v4) => is_private_ipv4(&v4),
+            None => {
+                let first = v6.segments()[0];
+                v6.is_loopback()
+                    || v6.is_unspecified()
+                    || (first & 0xfe00) == 0xfc00 // unique local fc00::/7
+                    || (first & 0xffc0) == 0xfe80 // link-local fe80::/10
+            }
+        },
+    }
+}
+
+fn is_private_ipv4(ip: &Ipv4Addr) -> bool {
+    let [a, b, ..] = ip.octets();
+    ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+        || (a == 100 && (64..128).contains(&b)) // carrier-grade NAT 100.64.0.0/10
+}
+
 pub struct SecurityManager {
     policy: SecurityPolicy,
     execution_stats: Arc<RwLock<ExecutionStats>>,
@@ -202,6 +451,8 @@ impl SecurityManager {
             stats.start_time = Some(Instant::now());
         }
 
+        self.policy.install(lua);
+
         // Apply module restrictions
         self.apply_module_restrictions(lua, &globals)?;
 
@@ -488,6 +739,176 @@ mod tests {
         assert!(policy.denied_modules.contains("io"));
         assert!(policy.allowed_modules.contains("string"));
         assert!(!policy.allow_debug);
+        assert!(!policy.allow_subprocess);
+    }
+
+    #[test]
+    fn test_subprocess_allowed_follows_installed_policy() {
+        let lua = Lua::new();
+        assert!(!subprocess_allowed(&lua));
+
+        let mut policy = SecurityPolicy::default();
+        policy.install(&lua);
+        assert!(!subprocess_allowed(&lua));
+
+        policy.allow_subprocess = true;
+        policy.install(&lua);
+        assert!(subprocess_allowed(&lua));
+    }
+
+    fn scoped_policy(root: &Path) -> FileOperationPolicy {
+        let mut policy = SecurityPolicy::default().allowed_file_operations;
+        policy.allowed_paths.push(root.to_path_buf());
+        policy
+    }
+
+    #[test]
+    fn test_file_policy_operation_switches() {
+        let mut policy = SecurityPolicy::default().allowed_file_operations;
+        assert!(policy.check_path(Path::new("out.txt"), "write").is_ok());
+
+        policy.write = false;
+        let err = policy
+            .check_path(Path::new("out.txt"), "write")
+            .unwrap_err();
+        assert!(err.to_string().contains("disabled"), "{}", err);
+        assert!(policy.check_path(Path::new("in.txt"), "read").is_ok());
+
+        policy.read = false;
+        assert!(policy.check_path(Path::new("in.txt"), "stat").is_err());
+    }
+
+    #[test]
+    fn test_file_policy_allows_paths_inside_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("data");
+        std::fs::create_dir(&root).unwrap();
+        std::fs::write(root.join("input.txt"), "ok").unwrap();
+        let policy = scoped_policy(&root);
+
+        assert!(policy.check_path(&root.join("input.txt"), "read").is_ok());
+        assert!(policy
+            .check_path(&root.join("new/output.txt"), "write")
+            .is_ok());
+        assert!(policy
+            .check_path(&root.join("sub/../input.txt"), "read")
+            .is_ok());
+    }
+
+    #[test]
+    fn test_file_policy_blocks_paths_outside_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("data");
+        std::fs::create_dir(&root).unwrap();
+        std::fs::write(dir.path().join("secret.txt"), "no").unwrap();
+        let policy = scoped_policy(&root);
+
+        let err = policy
+            .check_path(&dir.path().join("secret.txt"), "read")
+            .unwrap_err();
+        assert!(matches!(err, HypeError::Security(_)));
+        assert!(policy
+            .check_path(&root.join("../secret.txt"), "read")
+            .is_err());
+        assert!(policy
+            .check_path(&root.join("missing/../../secret.txt"), "read")
+            .is_err());
+        assert!(policy
+            .check_path(&dir.path().join("data-other/file"), "read")
+            .is_err());
+    }
+
+    #[test]
+    fn test_file_policy_denied_paths_take_precedence() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut policy = scoped_policy(dir.path());
+        policy.denied_paths.push(dir.path().join("private"));
+
+        assert!(policy
+            .check_path(&dir.path().join("public.txt"), "read")
+            .is_ok());
+        let err = policy
+            .check_path(&dir.path().join("private/key.pem"), "read")
+            .unwrap_err();
+        assert!(err.to_string().contains("denied path"), "{}", err);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_file_policy_follows_symlinks_out_of_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("data");
+        std::fs::create_dir(&root).unwrap();
+        std::fs::write(dir.path().join("secret.txt"), "no").unwrap();
+        std::os::unix::fs::symlink(dir.path().join("secret.txt"), root.join("link.txt")).unwrap();
+
+        assert!(scoped_policy(&root)
+            .check_path(&root.join("link.txt"), "read")
+            .is_err());
+    }
+
+    #[test]
+    fn test_check_file_access_without_policy_is_unrestricted() {
+        let lua = Lua::new();
+        assert!(check_file_access(&lua, "/etc/hosts", "read").is_ok());
+
+        let mut policy = SecurityPolicy::default();
+        policy
+            .allowed_file_operations
+            .allowed_paths
+            .push(PathBuf::from("/nonexistent-root"));
+        policy.install(&lua);
+        assert!(check_file_access(&lua, "/etc/hosts", "read").is_err());
+    }
+
+    #[test]
+    fn test_network_policy_host_allowlist() {
+        let policy = NetworkPolicy {
+            allowed_hosts: vec!["api.example.com".to_string(), "*.cdn.net".to_string()],
+            ..NetworkPolicy::default()
+        };
+        assert!(policy.check_host("api.example.com").is_ok());
+        assert!(policy.check_host("API.Example.com").is_ok());
+        assert!(policy.check_host("img.cdn.net").is_ok());
+        assert!(policy.check_host("cdn.net").is_ok());
+
+        let err = policy.check_host("evil.com").unwrap_err();
+        assert!(err.to_string().contains("Security error"), "{}", err);
+        assert!(policy.check_host("example.com").is_err());
+        assert!(policy.check_host("notcdn.net").is_err());
+
+        assert!(NetworkPolicy::default().check_host("anything.org").is_ok());
+    }
+
+    #[test]
+    fn test_network_policy_denies_private_ips() {
+        let policy = NetworkPolicy {
+            deny_private_ips: true,
+            ..NetworkPolicy::default()
+        };
+        for ip in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "0.0.0.0",
+            "::1",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+        ] {
+            let addr: IpAddr = ip.parse().unwrap();
+            assert!(policy.check_addrs(ip, &[addr]).is_err(), "{}", ip);
+        }
+
+        let public: IpAddr = "93.184.216.34".parse().unwrap();
+        assert!(policy.check_addrs("example.com", &[public]).is_ok());
+
+        let loopback: IpAddr = "127.0.0.1".parse().unwrap();
+        assert!(NetworkPolicy::default()
+            .check_addrs("localhost", &[loopback])
+            .is_ok());
     }
 
     #[test]
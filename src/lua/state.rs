@@ -1,10 +1,24 @@
+use crate::engine::timeout::Deadline;
 use crate::error::{HypeError, Result};
+use crate::lua::bytecode::{load_script, ScriptChunk};
 use crate::lua::environment::{EnvironmentConfig, EnvironmentManager};
-use mlua::{Function, Lua, Table, Value};
+use crate::lua::error::{script_error_message, LuaExecutionError};
+use crate::lua::security::{bytecode_allowed, SecurityManager, SecurityPolicy};
+use crate::modules::builtins::process::dispatch_signals;
+use mlua::{ChunkMode, Function, HookTriggers, Lua, Table, Value};
+use std::ops::Deref;
 use std::path::Path;
 use std::sync::{Arc, Mutex, RwLock};
 use std::time::{Duration, Instant};
 
+/// Registry keys holding the globals captured by `snapshot_globals`.
+const BASELINE_GLOBALS_KEY: &str = "hype.state.baseline_globals";
+const BASELINE_GLOBALS_MT_KEY: &str = "hype.state.baseline_globals_mt";
+/// Registry keys holding the `package.loaded` and `require.cache` entries
+/// captured by `snapshot_globals`.
+const BASELINE_LOADED_KEY: &str = "hype.state.baseline_loaded";
+const BASELINE_REQUIRE_CACHE_KEY: &str = "hype.state.baseline_require_cache";
+
 #[derive(Debug, Clone)]
 pub struct LuaStateConfig {
     pub memory_limit: Option<usize>,
@@ -23,7 +37,7 @@ impl Default for LuaStateConfig {
     fn default() -> Self {
         Self {
             memory_limit: Some(64 * 1024 * 1024),      // 64MB default
-            instruction_limit: Some(1000000),          // 1M instructions default
+            instruction_limit: None,                   // opt in; see --instruction-limit
             time_limit: Some(Duration::from_secs(30)), // 30 seconds default
             allow_os_operations: false,
             allow_file_operations: false,
@@ -44,6 +58,18 @@ pub struct LuaStateMetrics {
     pub gc_collections: u32,
 }
 
+impl LuaStateMetrics {
+    /// Machine-readable form of the metrics, with the execution time in milliseconds.
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "execution_time_ms": self.execution_time.as_secs_f64() * 1000.0,
+            "instructions_executed": self.instructions_executed,
+            "memory_usage": self.memory_usage,
+            "gc_collections": self.gc_collections,
+        })
+    }
+}
+
 impl Default for LuaStateMetrics {
     fn default() -> Self {
         Self {
@@ -55,6 +81,41 @@ impl Default for LuaStateMetrics {
     }
 }
 
+const MONITOR_INSTRUCTION_INTERVAL: u32 = 1000;
+
+/// Converts a script error, surfacing limit violations raised by the monitoring hook
+/// and allocations refused because of the memory limit.
+fn map_lua_error(error: mlua::Error, memory_limit: Option<usize>) -> HypeError {
+    if let (Some(limit), true) = (memory_limit, is_memory_error(&error)) {
+        return HypeError::StateManagement(format!(
+            "script exceeded the memory limit of {} bytes",
+            limit
+        ));
+    }
+    match find_execution_error(&error) {
+        Some(
+            exec_error @ (LuaExecutionError::TimeoutError { .. }
+            | LuaExecutionError::InstructionLimitError { .. }),
+        ) => HypeError::Execution(exec_error.user_friendly_message()),
+        _ => HypeError::Lua(script_error_message(&error)),
+    }
+}
+
+fn is_memory_error(error: &mlua::Error) -> bool {
+    match error {
+        mlua::Error::MemoryError(_) => true,
+        mlua::Error::CallbackError { cause, .. } => is_memory_error(cause),
+        _ => false,
+    }
+}
+
+fn find_execution_error(error: &mlua::Error) -> Option<&LuaExecutionError> {
+    match error {
+        mlua::Error::CallbackError { cause, .. } => find_execution_error(cause),
+        _ => error.downcast_ref::<LuaExecutionError>(),
+    }
+}
+
 #[derive(Debug)]
 pub struct LuaStateManager {
     pub lua: Arc<Mutex<Lua>>,
@@ -95,6 +156,11 @@ impl LuaStateManager {
             // Note: gc_set_pause removed due to API limitations
         }
 
+        // Cap the allocator so runaway scripts fail instead of exhausting the host
+        if let Some(limit) = self.config.memory_limit {
+            lua.set_memory_limit(limit)?;
+        }
+
         // Set up safe environment
         self.setup_sandbox(&lua, &globals)?;
 
@@ -239,17 +305,68 @@ impl LuaStateManager {
     }
 
     fn setup_monitoring(&self, lua: &Lua) -> Result<()> {
-        // Set up instruction count hook
         let instruction_count = self.instruction_count.clone();
         let instruction_limit = self.config.instruction_limit;
         let time_limit = self.config.time_limit;
         let start_time = self.start_time.clone();
 
-        // Note: Hook setup removed due to mlua API limitations
-        // In a production implementation, you would need to use a different approach
-        // for instruction counting and time limiting, possibly through a wrapper
-        // that periodically checks execution state.
+        lua.set_hook(
+            HookTriggers::new().every_nth_instruction(MONITOR_INSTRUCTION_INTERVAL),
+            move |lua, _debug| {
+                // The engine's watchdog expires this deadline even while a
+                // native call blocks; stop at the first instruction after it.
+                if let Some(deadline) = lua.app_data_ref::<Deadline>() {
+                    if deadline.is_expired() {
+                        return Err(mlua::Error::external(deadline.execution_error()));
+                    }
+                }
+                dispatch_signals(lua)?;
+
+                let executed = {
+                    let mut count = instruction_count.lock().unwrap();
+                    *count = count.saturating_add(MONITOR_INSTRUCTION_INTERVAL);
+                    *count
+                };
+
+                if let Some(limit) = instruction_limit.filter(|limit| executed > *limit) {
+                    return Err(mlua::Error::external(
+                        LuaExecutionError::InstructionLimitError {
+                            message: "instruction limit exceeded".to_string(),
+                            limit,
+                            executed,
+                        },
+                    ));
+                }
+
+                let (Some(time_limit), Some(start)) = (time_limit, *start_time.lock().unwrap())
+                else {
+                    return Ok(());
+                };
+                let elapsed = start.elapsed();
+                if elapsed > time_limit {
+                    return Err(mlua::Error::external(LuaExecutionError::TimeoutError {
+                        message: "execution time limit exceeded".to_string(),
+                        timeout: time_limit,
+                        elapsed,
+                    }));
+                }
+                Ok(())
+            },
+        );
+
+        Ok(())
+    }
 
+    /// Run `setup` with the Lua state and its globals table, so embedders can
+    /// register values and Rust functions before a script runs. Globals set
+    /// here are kept: `execute_script` only adds `args` when none exists.
+    pub fn with_globals<F>(&self, setup: F) -> Result<()>
+    where
+        F: FnOnce(&Lua, &Table) -> mlua::Result<()>,
+    {
+        let lua = self.lua.lock().unwrap();
+        let globals = lua.globals();
+        setup(&lua, &globals)?;
         Ok(())
     }
 
@@ -283,13 +400,18 @@ impl LuaStateManager {
             globals.set("args", args_table)?;
         }
 
-        // Load and execute script
-        let script_content = crate::file_io::read_lua_script(script_path)?;
-
-        let result = lua
-            .load(&script_content)
-            .set_name(script_path.to_string_lossy())
-            .exec();
+        // Load and execute script, skipping the parser for precompiled bytecode
+        let chunk = match load_script(script_path)? {
+            ScriptChunk::Source(source) => lua.load(source).set_mode(ChunkMode::Text),
+            ScriptChunk::Bytecode(_) if !bytecode_allowed(&lua) => {
+                return Err(HypeError::Security(format!(
+                    "{} is precompiled bytecode, which the security policy does not allow; run the .lua source instead",
+                    script_path.display()
+                )));
+            }
+            ScriptChunk::Bytecode(bytes) => lua.load(bytes).set_mode(ChunkMode::Binary),
+        };
+        let result = chunk.set_name(format!("@{}", script_path.display())).exec();
 
         // Update metrics
         {
@@ -307,7 +429,7 @@ impl LuaStateManager {
             metrics.memory_usage = lua.used_memory() as usize;
         }
 
-        result.map_err(|e| HypeError::Lua(e.to_string()))?;
+        result.map_err(|e| map_lua_error(e, self.config.memory_limit))?;
 
         Ok(())
     }
@@ -347,7 +469,7 @@ impl LuaStateManager {
 
         match result {
             Ok(value) => Ok(crate::lua::error::format_lua_value(&value)),
-            Err(e) => Err(HypeError::Lua(e.to_string())),
+            Err(e) => Err(map_lua_error(e, self.config.memory_limit)),
         }
     }
 
@@ -387,6 +509,63 @@ impl LuaStateManager {
         Ok(())
     }
 
+    /// Remember the current globals so `reset_globals` can restore them.
+    pub fn snapshot_globals(&self) -> Result<()> {
+        let lua = self.lua.lock().unwrap();
+        let globals = lua.globals();
+
+        lua.set_named_registry_value(BASELINE_GLOBALS_KEY, copy_table(&lua, &globals)?)?;
+        match globals.get_metatable() {
+            Some(mt) => lua.set_named_registry_value(BASELINE_GLOBALS_MT_KEY, mt)?,
+            None => lua.unset_named_registry_value(BASELINE_GLOBALS_MT_KEY)?,
+        }
+
+        let (loaded, require_cache) = module_caches(&globals)?;
+        for (key, cache) in [
+            (BASELINE_LOADED_KEY, loaded),
+            (BASELINE_REQUIRE_CACHE_KEY, require_cache),
+        ] {
+            match cache {
+                Some(cache) => lua.set_named_registry_value(key, copy_table(&lua, &cache)?)?,
+                None => lua.unset_named_registry_value(key)?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Put the globals back as they were at `snapshot_globals`: globals a
+    /// script added are removed and replaced or removed ones are restored.
+    /// `package.loaded` and `require.cache` are restored too, so modules
+    /// required by one script are loaded afresh by the next. Other changes
+    /// made inside shared tables (e.g. `string.x = 1`) are not undone.
+    pub fn reset_globals(&self) -> Result<()> {
+        let lua = self.lua.lock().unwrap();
+        let globals = lua.globals();
+        let baseline: Table = lua
+            .named_registry_value::<Option<Table>>(BASELINE_GLOBALS_KEY)?
+            .ok_or_else(|| {
+                HypeError::StateManagement("No globals snapshot to reset to".to_string())
+            })?;
+
+        restore_table(&globals, &baseline)?;
+        globals.set_metatable(lua.named_registry_value::<Option<Table>>(BASELINE_GLOBALS_MT_KEY)?);
+
+        let (loaded, require_cache) = module_caches(&globals)?;
+        for (key, cache) in [
+            (BASELINE_LOADED_KEY, loaded),
+            (BASELINE_REQUIRE_CACHE_KEY, require_cache),
+        ] {
+            if let (Some(cache), Some(baseline)) =
+                (cache, lua.named_registry_value::<Option<Table>>(key)?)
+            {
+                restore_table(&cache, &baseline)?;
+            }
+        }
+
+        lua.gc_collect()?;
+        Ok(())
+    }
+
     pub fn get_environment_manager(&self) -> Arc<Mutex<EnvironmentManager>> {
         self.environment_manager.clone()
     }
@@ -402,6 +581,51 @@ impl LuaStateManager {
     }
 }
 
+/// A shallow copy of `table`'s entries, ignoring metamethods.
+fn copy_table<'lua>(lua: &'lua Lua, table: &Table<'lua>) -> mlua::Result<Table<'lua>> {
+    let copy = lua.create_table()?;
+    for pair in table.clone().pairs::<Value, Value>() {
+        let (key, value) = pair?;
+        copy.raw_set(key, value)?;
+    }
+    Ok(copy)
+}
+
+/// Make `table` hold exactly the entries of `baseline`.
+fn restore_table(table: &Table, baseline: &Table) -> mlua::Result<()> {
+    let keys = table
+        .clone()
+        .pairs::<Value, Value>()
+        .map(|pair| pair.map(|(key, _)| key))
+        .collect::<mlua::Result<Vec<_>>>()?;
+    for key in keys {
+        if !baseline.contains_key(key.clone())? {
+            table.raw_remove(key)?;
+        }
+    }
+    for pair in baseline.clone().pairs::<Value, Value>() {
+        let (key, value) = pair?;
+        table.raw_set(key, value)?;
+    }
+    Ok(())
+}
+
+/// The module caches a script can fill: `package.loaded` and, when hype's
+/// `require` table is installed, `require.cache`.
+fn module_caches<'lua>(
+    globals: &Table<'lua>,
+) -> mlua::Result<(Option<Table<'lua>>, Option<Table<'lua>>)> {
+    let loaded = match globals.raw_get::<_, Value>("package")? {
+        Value::Table(package) => package.raw_get::<_, Option<Table>>("loaded").ok().flatten(),
+        _ => None,
+    };
+    let require_cache = match globals.raw_get::<_, Value>("require")? {
+        Value::Table(require) => require.raw_get::<_, Option<Table>>("cache").ok().flatten(),
+        _ => None,
+    };
+    Ok((loaded, require_cache))
+}
+
 impl Drop for LuaStateManager {
     fn drop(&mut self) {
         // Force garbage collection on cleanup
@@ -415,6 +639,7 @@ impl Drop for LuaStateManager {
 pub struct LuaStatePool {
     states: Arc<RwLock<Vec<LuaStateManager>>>,
     config: LuaStateConfig,
+    security_policy: SecurityPolicy,
     max_size: usize,
 }
 
@@ -423,21 +648,44 @@ impl LuaStatePool {
         Ok(Self {
             states: Arc::new(RwLock::new(Vec::new())),
             config,
+            security_policy: SecurityPolicy::default(),
             max_size,
         })
     }
 
+    /// Sandbox pooled states with `policy` instead of the default policy.
+    pub fn with_security_policy(mut self, policy: SecurityPolicy) -> Self {
+        self.security_policy = policy;
+        self
+    }
+
+    /// Create a sandboxed state and snapshot its globals for later resets.
+    fn create_state(&self) -> Result<LuaStateManager> {
+        let state = LuaStateManager::new(self.config.clone())?;
+        {
+            let lua = state.lua.lock().unwrap();
+            SecurityManager::new(self.security_policy.clone()).apply_sandbox(&lua)?;
+        }
+        state.snapshot_globals()?;
+        Ok(state)
+    }
+
     pub fn acquire_state(&self) -> Result<LuaStateManager> {
         let mut states = self.states.write().unwrap();
 
         if let Some(state) = states.pop() {
             Ok(state)
         } else {
-            LuaStateManager::new(self.config.clone())
+            self.create_state()
         }
     }
 
+    /// Return a state to the pool, resetting its globals and security policy.
+    /// States that cannot be reset, or that exceed `max_size`, are dropped.
     pub fn release_state(&self, state: LuaStateManager) -> Result<()> {
+        state.reset_globals()?;
+        self.security_policy.install(&state.lua.lock().unwrap());
+
         let mut states = self.states.write().unwrap();
 
         if states.len() < self.max_size {
@@ -447,6 +695,19 @@ impl LuaStatePool {
         Ok(())
     }
 
+    /// Borrow a state that goes back to the pool when the guard is dropped.
+    pub fn acquire(&self) -> Result<PooledState<'_>> {
+        Ok(PooledState {
+            pool: self,
+            state: Some(self.acquire_state()?),
+        })
+    }
+
+    /// Number of idle states waiting to be reused.
+    pub fn available(&self) -> usize {
+        self.states.read().unwrap().len()
+    }
+
     pub fn clear(&self) -> Result<()> {
         let mut states = self.states.write().unwrap();
         states.clear();
@@ -454,6 +715,30 @@ impl LuaStatePool {
     }
 }
 
+/// A state borrowed from a [`LuaStatePool`].
+pub struct PooledState<'a> {
+    pool: &'a LuaStatePool,
+    state: Option<LuaStateManager>,
+}
+
+impl Deref for PooledState<'_> {
+    type Target = LuaStateManager;
+
+    fn deref(&self) -> &LuaStateManager {
+        self.state
+            .as_ref()
+            .expect("pooled state is present until drop")
+    }
+}
+
+impl Drop for PooledState<'_> {
+    fn drop(&mut self) {
+        if let Some(state) = self.state.take() {
+            let _ = self.pool.release_state(state);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -478,6 +763,32 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_with_globals_injects_rust_functions() {
+        let state = LuaStateManager::new(LuaStateConfig::default()).unwrap();
+        state
+            .with_globals(|lua, globals| {
+                let greet =
+                    lua.create_function(|_, name: String| Ok(format!("hello, {}", name)))?;
+                globals.set("greet", greet)?;
+                globals.set("APP_NAME", "wrapper")
+            })
+            .unwrap();
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let script = dir.path().join("main.lua");
+        std::fs::write(
+            &script,
+            "assert(greet(APP_NAME) == 'hello, wrapper')\nassert(args[1] == 'x')\nresult = greet('lua')",
+        )
+        .unwrap();
+        state.execute_script(&script, &["x".to_string()]).unwrap();
+        assert_eq!(state.get_global("result").unwrap(), "\"hello, lua\"");
+
+        let err = state.with_globals(|_, _| Err(mlua::Error::runtime("setup failed")));
+        assert!(err.unwrap_err().to_string().contains("setup failed"));
+    }
+
     #[test]
     fn test_sandbox_restriction() {
         let config = LuaStateConfig::default();
@@ -487,4 +798,137 @@ mod tests {
         let result = state.execute_code("os.execute('echo test')");
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_time_limit_stops_infinite_loop() {
+        let mut config = LuaStateConfig::default();
+        config.time_limit = Some(Duration::from_millis(100));
+        config.instruction_limit = None;
+        let state = LuaStateManager::new(config).unwrap();
+
+        let result = state.execute_code("while true do end");
+        match result {
+            Err(HypeError::Execution(msg)) => assert!(msg.contains("timed out"), "{}", msg),
+            other => panic!("expected timeout error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_instruction_limit_stops_tight_loop() {
+        let config = LuaStateConfig {
+            instruction_limit: Some(50_000),
+            ..Default::default()
+        };
+        let state = LuaStateManager::new(config).unwrap();
+
+        let result = state.execute_code("local n = 0 while true do n = n + 1 end");
+        match result {
+            Err(HypeError::Execution(msg)) => {
+                assert!(msg.contains("Instruction limit exceeded"), "{}", msg);
+                assert!(msg.contains("> 50000"), "{}", msg);
+            }
+            other => panic!("expected instruction limit error, got {:?}", other),
+        }
+        assert!(state.get_metrics().instructions_executed > 50_000);
+
+        // The count resets for the next run
+        assert_eq!(state.execute_code("return 1 + 1").unwrap(), "2");
+    }
+
+    #[test]
+    fn test_default_config_has_no_instruction_limit() {
+        let state = LuaStateManager::new(LuaStateConfig::default()).unwrap();
+        let result = state.execute_code("local n = 0 for i = 1, 2000000 do n = n + 1 end return n");
+        assert_eq!(result.unwrap(), "2000000");
+    }
+
+    #[test]
+    fn test_memory_limit_stops_growing_table() {
+        let config = LuaStateConfig {
+            memory_limit: Some(4 * 1024 * 1024),
+            instruction_limit: None,
+            time_limit: None,
+            ..Default::default()
+        };
+        let state = LuaStateManager::new(config).unwrap();
+
+        let result = state.execute_code(
+            "local t = {} for i = 1, 1e9 do t[i] = string.rep('x', 64) .. i end return #t",
+        );
+        match result {
+            Err(HypeError::StateManagement(msg)) => {
+                assert!(msg.contains("memory limit of 4194304 bytes"), "{}", msg)
+            }
+            other => panic!("expected memory limit error, got {:?}", other),
+        }
+
+        // The state stays usable once the oversized table is collected
+        state.force_gc().unwrap();
+        assert_eq!(state.execute_code("return 1 + 1").unwrap(), "2");
+    }
+
+    #[test]
+    fn test_metrics_track_instructions() {
+        let state = LuaStateManager::new(LuaStateConfig::default()).unwrap();
+
+        state
+            .execute_code("local n = 0 for i = 1, 10000 do n = n + i end return n")
+            .unwrap();
+
+        let metrics = state.get_metrics().to_json();
+        assert!(metrics["instructions_executed"].as_u64().unwrap() > 0);
+        assert!(metrics["memory_usage"].as_u64().unwrap() > 0);
+        assert!(metrics["execution_time_ms"].is_f64());
+    }
+
+    #[test]
+    fn test_pool_reset_clears_module_caches() {
+        let pool = LuaStatePool::new(LuaStateConfig::default(), 1).unwrap();
+        {
+            // An embedder-provided loader in the style of hype's `require`
+            let state = pool.acquire().unwrap();
+            state
+                .with_globals(|lua, globals| {
+                    lua.load(
+                        r#"
+                        package = { loaded = {}, preload = {} }
+                        package.preload.counter = function() return { hits = 0 } end
+                        "#,
+                    )
+                    .exec()?;
+                    let load: Function = lua
+                        .load(
+                            r#"
+                            return function(self, name)
+                                if package.loaded[name] == nil then
+                                    package.loaded[name] = package.preload[name](name)
+                                    self.cache[name] = package.loaded[name]
+                                end
+                                return package.loaded[name]
+                            end
+                            "#,
+                        )
+                        .eval()?;
+                    let require = lua.create_table()?;
+                    require.set("cache", lua.create_table()?)?;
+                    let metatable = lua.create_table()?;
+                    metatable.set("__call", load)?;
+                    require.set_metatable(Some(metatable));
+                    globals.set("require", require)
+                })
+                .unwrap();
+            state.snapshot_globals().unwrap();
+        }
+
+        let script = r#"
+            local fresh = package.loaded.counter == nil and require.cache.counter == nil
+            local counter = require("counter")
+            counter.hits = counter.hits + 1
+            return tostring(fresh) .. " " .. counter.hits
+        "#;
+        for _ in 0..2 {
+            let state = pool.acquire().unwrap();
+            assert_eq!(state.execute_code(script).unwrap(), "\"true 1\"");
+        }
+    }
 }
@@ -8,10 +8,12 @@ mod modules;
 
 use cli::agent::generate_agent_docs;
 use cli::commands::{
-    handle_install_command, handle_list_command, handle_uninstall_command, handle_which_command,
-    run_script,
+    handle_check_command, handle_compile_command, handle_install_command, handle_lint_command,
+    handle_list_command, handle_uninstall_command, handle_upgrade_command, handle_verify_command,
+    handle_which_command, run_script,
 };
 use cli::parser::{parse_args, HypeCommand};
+use engine::OutputEvent;
 use error::HypeError;
 
 fn main() -> Result<(), HypeError> {
@@ -24,15 +26,52 @@ fn main() -> Result<(), HypeError> {
     };
 
     match command {
-        HypeCommand::Run(args) => run_script(args),
+        HypeCommand::Run(args) => {
+            let output_events = args.output_events;
+            match run_script(args) {
+                Ok(()) => Ok(()),
+                Err(e) if output_events => {
+                    OutputEvent::from_error_message(&e.to_string()).emit();
+                    std::process::exit(e.exit_code());
+                }
+                // The error and the script's output have already been printed
+                Err(HypeError::Exit(code)) => std::process::exit(code),
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(e.exit_code());
+                }
+            }
+        }
         HypeCommand::Install {
             path,
             force,
             verbose,
         } => handle_install_command(path, force, verbose),
         HypeCommand::Uninstall { name, verbose } => handle_uninstall_command(name, verbose),
-        HypeCommand::List { json, verbose } => handle_list_command(json, verbose),
+        HypeCommand::List {
+            filter,
+            outdated,
+            json,
+            verbose,
+        } => handle_list_command(filter, outdated, json, verbose),
         HypeCommand::Which { command } => handle_which_command(command),
+        HypeCommand::Upgrade {
+            name,
+            force,
+            verbose,
+        } => handle_upgrade_command(name, force, verbose),
+        HypeCommand::Verify { name, verbose } => handle_verify_command(name, verbose),
+        HypeCommand::Lint { paths, strict } => handle_lint_command(paths, strict),
+        HypeCommand::Check {
+            path,
+            requires,
+            verbose,
+        } => handle_check_command(path, requires, verbose),
+        HypeCommand::Compile {
+            script,
+            output,
+            strip,
+        } => handle_compile_command(script, output, strip),
         HypeCommand::Agent => match generate_agent_docs() {
             Ok(json) => {
                 println!("{}", json);
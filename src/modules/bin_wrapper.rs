@@ -1,11 +1,18 @@
 use crate::error::Result;
-use std::path::Path;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
 
 pub struct BinWrapper;
 
 impl BinWrapper {
+    // The hype binary that ran the install is tried first so wrappers keep
+    // working from shells whose PATH lacks it; PATH is the fallback when that
+    // binary has moved.
     const UNIX_TEMPLATE: &'static str = r#"#!/usr/bin/env bash
-HYPE_BIN="$(command -v hype)"
+HYPE_BIN="{HYPE_BIN}"
+if [ -z "$HYPE_BIN" ] || [ ! -x "$HYPE_BIN" ]; then
+    HYPE_BIN="$(command -v hype)"
+fi
 if [ -z "$HYPE_BIN" ]; then
     echo "Error: hype not found in PATH" >&2
     exit 1
@@ -16,14 +23,17 @@ exec "$HYPE_BIN" "$SCRIPT_PATH" "$@"
 "#;
 
     const WINDOWS_TEMPLATE: &'static str = r#"@echo off
-where hype >nul 2>&1
-if %ERRORLEVEL% NEQ 0 (
+setlocal
+set "HYPE_BIN={HYPE_BIN}"
+if defined HYPE_BIN if not exist "%HYPE_BIN%" set "HYPE_BIN="
+if not defined HYPE_BIN for /f "delims=" %%i in ('where hype 2^>nul') do if not defined HYPE_BIN set "HYPE_BIN=%%i"
+if not defined HYPE_BIN (
     echo Error: hype not found in PATH >&2
     exit /b 1
 )
-set PACKAGE_DIR={PACKAGE_DIR}
-set SCRIPT_PATH=%PACKAGE_DIR%\{SCRIPT_RELATIVE}
-hype "%SCRIPT_PATH%" %*
+set "PACKAGE_DIR={PACKAGE_DIR}"
+set "SCRIPT_PATH=%PACKAGE_DIR%\{SCRIPT_RELATIVE}"
+"%HYPE_BIN%" "%SCRIPT_PATH%" %*
 "#;
 
     pub fn create_unix_wrapper(
@@ -31,10 +41,11 @@ hype "%SCRIPT_PATH%" %*
         package_dir: &Path,
         script_relative: &str,
     ) -> Result<()> {
-        let package_dir_str = package_dir.to_string_lossy();
-        let content = Self::UNIX_TEMPLATE
-            .replace("{PACKAGE_DIR}", &package_dir_str)
-            .replace("{SCRIPT_RELATIVE}", script_relative);
+        let content = Self::render_unix(
+            package_dir,
+            script_relative,
+            Self::current_hype().as_deref(),
+        );
 
         std::fs::write(bin_path, content)?;
 
@@ -53,18 +64,18 @@ hype "%SCRIPT_PATH%" %*
         package_dir: &Path,
         script_relative: &str,
     ) -> Result<()> {
-        let package_dir_str = package_dir.to_string_lossy().replace('/', "\\");
-        let script_relative_windows = script_relative.replace('/', "\\");
-        let content = Self::WINDOWS_TEMPLATE
-            .replace("{PACKAGE_DIR}", &package_dir_str)
-            .replace("{SCRIPT_RELATIVE}", &script_relative_windows);
+        let content = Self::render_windows(
+            package_dir,
+            script_relative,
+            Self::current_hype().as_deref(),
+        );
 
         std::fs::write(bin_path, content)?;
 
         Ok(())
     }
 
-    #[cfg(unix)]
+    #[cfg(not(windows))]
     pub fn create_wrapper(
         bin_path: &Path,
         package_dir: &Path,
@@ -73,16 +84,100 @@ hype "%SCRIPT_PATH%" %*
         Self::create_unix_wrapper(bin_path, package_dir, script_relative)
     }
 
+    /// Writes a `.cmd` wrapper for cmd.exe and PowerShell next to a bash
+    /// wrapper for Git Bash and MSYS shells.
     #[cfg(windows)]
     pub fn create_wrapper(
         bin_path: &Path,
         package_dir: &Path,
         script_relative: &str,
     ) -> Result<()> {
-        Self::create_windows_wrapper(bin_path, package_dir, script_relative)
+        Self::create_unix_wrapper(bin_path, package_dir, script_relative)?;
+        Self::create_windows_wrapper(&Self::cmd_path(bin_path), package_dir, script_relative)
+    }
+
+    /// Every file `create_wrapper` writes for `bin_path` on this platform.
+    pub fn wrapper_files(bin_path: &Path) -> Vec<PathBuf> {
+        if cfg!(windows) {
+            vec![bin_path.to_path_buf(), Self::cmd_path(bin_path)]
+        } else {
+            vec![bin_path.to_path_buf()]
+        }
+    }
+
+    pub fn remove_wrapper(bin_path: &Path) -> Result<()> {
+        for path in Self::wrapper_files(bin_path) {
+            if path.exists() {
+                std::fs::remove_file(&path)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn cmd_path(bin_path: &Path) -> PathBuf {
+        let mut path = OsString::from(bin_path.as_os_str());
+        path.push(".cmd");
+        PathBuf::from(path)
+    }
+
+    /// The running executable, when it is hype itself rather than a test
+    /// harness or another embedding binary.
+    fn current_hype() -> Option<PathBuf> {
+        let exe = std::env::current_exe().ok()?;
+        (exe.file_stem()? == "hype").then_some(exe)
+    }
+
+    fn render_unix(package_dir: &Path, script_relative: &str, hype_bin: Option<&Path>) -> String {
+        let hype_bin = hype_bin
+            .map(|p| p.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        Self::UNIX_TEMPLATE
+            .replace("{HYPE_BIN}", &escape_double_quoted(&hype_bin))
+            .replace(
+                "{PACKAGE_DIR}",
+                &escape_double_quoted(&package_dir.to_string_lossy()),
+            )
+            .replace("{SCRIPT_RELATIVE}", &escape_double_quoted(script_relative))
+    }
+
+    fn render_windows(
+        package_dir: &Path,
+        script_relative: &str,
+        hype_bin: Option<&Path>,
+    ) -> String {
+        let hype_bin = hype_bin
+            .map(|p| p.to_string_lossy().replace('/', "\\"))
+            .unwrap_or_default();
+        Self::WINDOWS_TEMPLATE
+            .replace("{HYPE_BIN}", &escape_batch(&hype_bin))
+            .replace(
+                "{PACKAGE_DIR}",
+                &escape_batch(&package_dir.to_string_lossy().replace('/', "\\")),
+            )
+            .replace(
+                "{SCRIPT_RELATIVE}",
+                &escape_batch(&script_relative.replace('/', "\\")),
+            )
     }
 }
 
+/// Escape a value for use inside a bash double-quoted string.
+fn escape_double_quoted(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '"' | '\\' | '$' | '`') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Escape a value for use inside a quoted `set "NAME=value"` in a batch file.
+fn escape_batch(value: &str) -> String {
+    value.replace('%', "%%")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -116,9 +211,9 @@ mod tests {
 
         let content = std::fs::read_to_string(&bin_path).unwrap();
         assert!(content.contains("@echo off"));
-        assert!(content.contains("set PACKAGE_DIR=C:\\Users\\user\\.hype\\packages\\mypackage"));
-        assert!(content.contains("set SCRIPT_PATH=%PACKAGE_DIR%\\bin\\script.lua"));
-        assert!(content.contains("hype \"%SCRIPT_PATH%\" %*"));
+        assert!(content.contains("set \"PACKAGE_DIR=C:\\Users\\user\\.hype\\packages\\mypackage\""));
+        assert!(content.contains("set \"SCRIPT_PATH=%PACKAGE_DIR%\\bin\\script.lua\""));
+        assert!(content.contains("\"%HYPE_BIN%\" \"%SCRIPT_PATH%\" %*"));
     }
 
     #[test]
@@ -205,7 +300,69 @@ mod tests {
 
         BinWrapper::create_wrapper(&bin_path, &package_dir, script_relative).unwrap();
 
-        let content = std::fs::read_to_string(&bin_path).unwrap();
+        let content = std::fs::read_to_string(BinWrapper::cmd_path(&bin_path)).unwrap();
         assert!(content.contains("@echo off"));
+        assert!(std::fs::read_to_string(&bin_path)
+            .unwrap()
+            .contains("#!/usr/bin/env bash"));
+    }
+
+    #[test]
+    fn test_wrapper_escapes_shell_metacharacters() {
+        let package_dir = PathBuf::from("/tmp/it's \"$HOME\"/pkg`id`");
+        let content = BinWrapper::render_unix(&package_dir, "bin/cli.lua", None);
+
+        assert!(content.contains(r#"PACKAGE_DIR="/tmp/it's \"\$HOME\"/pkg\`id\`""#));
+        assert!(content.contains("HYPE_BIN=\"\"\n"));
+    }
+
+    #[test]
+    fn test_windows_wrapper_embeds_hype_and_escapes_percent() {
+        let content = BinWrapper::render_windows(
+            Path::new("C:/Users/100% user/pkg"),
+            "bin/cli.lua",
+            Some(Path::new("C:/Tools/hype.exe")),
+        );
+
+        assert!(content.contains("set \"HYPE_BIN=C:\\Tools\\hype.exe\""));
+        assert!(content.contains("set \"PACKAGE_DIR=C:\\Users\\100%% user\\pkg\""));
+        assert!(content.contains("where hype"));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_wrapper_runs_embedded_hype_with_spaced_path_and_args() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = TempDir::new().unwrap();
+        let fake_hype = temp_dir.path().join("fake hype");
+        std::fs::write(
+            &fake_hype,
+            "#!/bin/sh\nfor arg in \"$@\"; do echo \"[$arg]\"; done\n",
+        )
+        .unwrap();
+        std::fs::set_permissions(&fake_hype, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let package_dir = temp_dir.path().join("my packages/tool@1.0.0");
+        let bin_path = temp_dir.path().join("tool");
+        std::fs::write(
+            &bin_path,
+            BinWrapper::render_unix(&package_dir, "bin/main script.lua", Some(&fake_hype)),
+        )
+        .unwrap();
+
+        let output = std::process::Command::new("bash")
+            .arg(&bin_path)
+            .args(["one two", "--flag=$HOME", ""])
+            .env("PATH", "/usr/bin:/bin")
+            .output()
+            .unwrap();
+
+        assert!(output.status.success(), "{:?}", output);
+        let expected = format!(
+            "[{}/bin/main script.lua]\n[one two]\n[--flag=$HOME]\n[]\n",
+            package_dir.display()
+        );
+        assert_eq!(String::from_utf8_lossy(&output.stdout), expected);
     }
 }
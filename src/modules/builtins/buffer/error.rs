@@ -0,0 +1,53 @@
+use std::fmt;
+
+#[derive(Debug)]
+pub enum BufferError {
+    OutOfRange {
+        offset: i64,
+        width: usize,
+        length: usize,
+    },
+    ValueOutOfRange(i64, usize),
+    InvalidSize(i64),
+    UnknownEncoding(String),
+    DecodeError(String),
+}
+
+impl fmt::Display for BufferError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BufferError::OutOfRange {
+                offset,
+                width,
+                length,
+            } => write!(
+                f,
+                "Offset {} out of range: {} byte(s) requested from a buffer of length {}",
+                offset, width, length
+            ),
+            BufferError::ValueOutOfRange(value, width) => write!(
+                f,
+                "Value {} does not fit in an unsigned {}-bit integer",
+                value,
+                width * 8
+            ),
+            BufferError::InvalidSize(size) => {
+                write!(f, "Invalid buffer size: {} (must be non-negative)", size)
+            }
+            BufferError::UnknownEncoding(name) => write!(
+                f,
+                "Unknown encoding '{}' (expected utf8, hex, base64 or binary)",
+                name
+            ),
+            BufferError::DecodeError(msg) => write!(f, "Failed to decode buffer data: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for BufferError {}
+
+impl From<BufferError> for crate::error::HypeError {
+    fn from(err: BufferError) -> Self {
+        crate::error::HypeError::Execution(err.to_string())
+    }
+}
@@ -0,0 +1,195 @@
+use super::operations::*;
+use mlua::{
+    Lua, MetaMethod, Result as LuaResult, Table, UserData, UserDataMethods, Value as LuaValue,
+};
+
+const INT_WIDTHS: [(usize, &str); 3] = [(1, "8"), (2, "16"), (4, "32")];
+
+impl UserData for Buffer {
+    fn add_methods<'lua, M: UserDataMethods<'lua, Self>>(methods: &mut M) {
+        for (width, bits) in INT_WIDTHS {
+            methods.add_method(
+                format!("readUInt{}", bits),
+                move |_, this, (offset, little_endian): (i64, Option<bool>)| {
+                    this.read_uint(offset, width, little_endian.unwrap_or(false))
+                        .map_err(mlua::Error::external)
+                },
+            );
+
+            methods.add_method_mut(
+                format!("writeUInt{}", bits),
+                move |_, this, (value, offset, little_endian): (i64, i64, Option<bool>)| {
+                    this.write_uint(value, offset, width, little_endian.unwrap_or(false))
+                        .map_err(mlua::Error::external)
+                },
+            );
+        }
+
+        methods.add_method(
+            "slice",
+            |_, this, (start, end): (Option<i64>, Option<i64>)| {
+                this.slice(start.unwrap_or(0), end)
+                    .map_err(mlua::Error::external)
+            },
+        );
+
+        methods.add_method("toString", |lua, this, encoding: Option<String>| {
+            let encoding = parse_encoding(encoding)?;
+            lua.create_string(this.encode(encoding))
+        });
+
+        methods.add_method("length", |_, this, ()| Ok(this.len()));
+
+        methods.add_meta_method(MetaMethod::Len, |_, this, ()| Ok(this.len()));
+
+        methods.add_meta_method(MetaMethod::Eq, |_, this, other: mlua::AnyUserData| {
+            Ok(other
+                .borrow::<Buffer>()
+                .map(|other| *this == *other)
+                .unwrap_or(false))
+        });
+
+        methods.add_meta_method(MetaMethod::ToString, |_, this, ()| {
+            if this.is_empty() {
+                return Ok("<Buffer >".to_string());
+            }
+            let hex = this
+                .as_bytes()
+                .iter()
+                .map(|b| format!("{:02x}", b))
+                .collect::<Vec<_>>()
+                .join(" ");
+            Ok(format!("<Buffer {}>", hex))
+        });
+    }
+}
+
+fn parse_encoding(encoding: Option<String>) -> LuaResult<Encoding> {
+    encoding
+        .as_deref()
+        .unwrap_or("utf8")
+        .parse()
+        .map_err(mlua::Error::external)
+}
+
+pub fn create_buffer_module(lua: &Lua) -> LuaResult<Table<'_>> {
+    let buffer = lua.create_table()?;
+
+    let alloc_fn =
+        lua.create_function(|_, size: i64| Buffer::alloc(size).map_err(mlua::Error::external))?;
+    buffer.set("alloc", alloc_fn)?;
+
+    let from_fn = lua.create_function(
+        |_, (data, encoding): (LuaValue, Option<String>)| match data {
+            LuaValue::String(s) => Buffer::decode(s.as_bytes(), parse_encoding(encoding)?)
+                .map_err(mlua::Error::external),
+            LuaValue::Table(t) => {
+                let mut bytes = Vec::with_capacity(t.raw_len());
+                for value in t.sequence_values::<i64>() {
+                    let value = value?;
+                    let byte = u8::try_from(value).map_err(|_| {
+                        mlua::Error::external(super::BufferError::ValueOutOfRange(value, 1))
+                    })?;
+                    bytes.push(byte);
+                }
+                Ok(Buffer::from_bytes(bytes))
+            }
+            LuaValue::UserData(ud) => Ok(ud.borrow::<Buffer>()?.clone()),
+            other => Err(mlua::Error::external(format!(
+                "buffer.from expects a string, table of bytes or buffer, got {}",
+                other.type_name()
+            ))),
+        },
+    )?;
+    buffer.set("from", from_fn)?;
+
+    let is_buffer_fn = lua.create_function(|_, value: LuaValue| {
+        Ok(matches!(value, LuaValue::UserData(ud) if ud.is::<Buffer>()))
+    })?;
+    buffer.set("isBuffer", is_buffer_fn)?;
+
+    Ok(buffer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lua_with_buffer() -> Lua {
+        let lua = Lua::new();
+        let buffer = create_buffer_module(&lua).unwrap();
+        lua.globals().set("buffer", buffer).unwrap();
+        lua
+    }
+
+    #[test]
+    fn test_create_buffer_module() {
+        let lua = Lua::new();
+        let buffer = create_buffer_module(&lua).unwrap();
+        assert!(buffer.contains_key("alloc").unwrap());
+        assert!(buffer.contains_key("from").unwrap());
+        assert!(buffer.contains_key("isBuffer").unwrap());
+    }
+
+    #[test]
+    fn test_write_read_uint32_both_endians() {
+        let lua = lua_with_buffer();
+        let result: (u32, u32, String, usize) = lua
+            .load(
+                r#"
+                local buf = buffer.alloc(8)
+                buf:writeUInt32(0x01020304, 0)
+                buf:writeUInt32(0x01020304, 4, true)
+                return buf:readUInt32(0), buf:readUInt32(4, true), buf:toString("hex"), #buf
+            "#,
+            )
+            .eval()
+            .unwrap();
+        assert_eq!(
+            result,
+            (0x01020304, 0x01020304, "0102030404030201".to_string(), 8)
+        );
+    }
+
+    #[test]
+    fn test_slice_and_from() {
+        let lua = lua_with_buffer();
+        let result: (String, bool, usize, String) = lua
+            .load(
+                r#"
+                local buf = buffer.from("GET /index.html")
+                local method = buf:slice(0, 3)
+                local copy = buffer.from({0x47, 0x45, 0x54})
+                return method:toString(), method == copy, buf:slice(4):length(), tostring(copy)
+            "#,
+            )
+            .eval()
+            .unwrap();
+        assert_eq!(
+            result,
+            ("GET".to_string(), true, 11, "<Buffer 47 45 54>".to_string())
+        );
+    }
+
+    #[test]
+    fn test_bounds_errors_are_lua_errors() {
+        let lua = lua_with_buffer();
+        for script in [
+            "buffer.alloc(2):readUInt32(0)",
+            "buffer.alloc(2):writeUInt8(300, 0)",
+            "buffer.alloc(2):slice(1, 5)",
+            "buffer.alloc(-1)",
+            "buffer.from('zz', 'hex')",
+            "buffer.from({256})",
+        ] {
+            let err = lua.load(script).exec().unwrap_err().to_string();
+            assert!(!err.is_empty(), "{}", script);
+        }
+        let err = lua
+            .load("buffer.alloc(2):readUInt16(1)")
+            .exec()
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("out of range"), "{}", err);
+    }
+}
@@ -0,0 +1,74 @@
+pub mod error;
+pub mod lua_bindings;
+pub mod operations;
+
+use serde_json::{json, Value as JsonValue};
+
+use super::BuiltinModule;
+use crate::error::HypeError;
+
+pub use error::BufferError;
+pub use lua_bindings::create_buffer_module;
+
+pub struct BufferModule;
+
+impl BufferModule {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for BufferModule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BuiltinModule for BufferModule {
+    fn name(&self) -> &str {
+        "buffer"
+    }
+
+    fn exports(&self) -> std::result::Result<JsonValue, HypeError> {
+        Ok(json!({
+            "__id": "buffer",
+            "__desc": "Binary data buffers with bounds-checked integer access",
+            "alloc": {
+                "__fn": "alloc",
+                "__desc": "Create a zero-filled buffer of the given size",
+                "__signature": "alloc(size: number) -> Buffer"
+            },
+            "from": {
+                "__fn": "from",
+                "__desc": "Create a buffer from a string, byte table or buffer",
+                "__signature": "from(data: string | number[] | Buffer, encoding?: string) -> Buffer"
+            },
+            "isBuffer": {
+                "__fn": "isBuffer",
+                "__desc": "Check whether a value is a buffer",
+                "__signature": "isBuffer(value: any) -> boolean"
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_buffer_module_name() {
+        let module = BufferModule::new();
+        assert_eq!(module.name(), "buffer");
+    }
+
+    #[test]
+    fn test_buffer_module_exports() {
+        let module = BufferModule::new();
+        let exports = module.exports().unwrap();
+        assert!(exports.is_object());
+        assert!(exports.get("alloc").is_some());
+        assert!(exports.get("from").is_some());
+        assert!(exports.get("isBuffer").is_some());
+    }
+}
@@ -0,0 +1,244 @@
+use super::error::BufferError;
+use base64::{engine::general_purpose, Engine as _};
+use std::str::FromStr;
+
+/// Text encodings understood by `buffer.from` and `Buffer:toString`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    Utf8,
+    Hex,
+    Base64,
+    Binary,
+}
+
+impl FromStr for Encoding {
+    type Err = BufferError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "utf8" | "utf-8" => Ok(Encoding::Utf8),
+            "hex" => Ok(Encoding::Hex),
+            "base64" => Ok(Encoding::Base64),
+            "binary" | "latin1" => Ok(Encoding::Binary),
+            _ => Err(BufferError::UnknownEncoding(s.to_string())),
+        }
+    }
+}
+
+/// A fixed-length byte array with bounds-checked integer access.
+///
+/// Offsets are zero-based, as in Node.js buffers.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Buffer {
+    data: Vec<u8>,
+}
+
+impl Buffer {
+    pub fn alloc(size: i64) -> Result<Self, BufferError> {
+        let size = usize::try_from(size).map_err(|_| BufferError::InvalidSize(size))?;
+        Ok(Self {
+            data: vec![0; size],
+        })
+    }
+
+    pub fn from_bytes(data: Vec<u8>) -> Self {
+        Self { data }
+    }
+
+    /// Decode `input` into a buffer. `utf8` and `binary` take the bytes as-is.
+    pub fn decode(input: &[u8], encoding: Encoding) -> Result<Self, BufferError> {
+        let data = match encoding {
+            Encoding::Utf8 | Encoding::Binary => input.to_vec(),
+            Encoding::Hex => {
+                hex::decode(input).map_err(|e| BufferError::DecodeError(e.to_string()))?
+            }
+            Encoding::Base64 => general_purpose::STANDARD
+                .decode(input)
+                .map_err(|e| BufferError::DecodeError(e.to_string()))?,
+        };
+        Ok(Self { data })
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Encode the contents. `utf8` replaces invalid sequences with U+FFFD;
+    /// `binary` returns the raw bytes.
+    pub fn encode(&self, encoding: Encoding) -> Vec<u8> {
+        match encoding {
+            Encoding::Utf8 => String::from_utf8_lossy(&self.data)
+                .into_owned()
+                .into_bytes(),
+            Encoding::Hex => hex::encode(&self.data).into_bytes(),
+            Encoding::Base64 => general_purpose::STANDARD.encode(&self.data).into_bytes(),
+            Encoding::Binary => self.data.clone(),
+        }
+    }
+
+    fn range(&self, offset: i64, width: usize) -> Result<usize, BufferError> {
+        usize::try_from(offset)
+            .ok()
+            .filter(|start| {
+                start
+                    .checked_add(width)
+                    .is_some_and(|end| end <= self.len())
+            })
+            .ok_or(BufferError::OutOfRange {
+                offset,
+                width,
+                length: self.len(),
+            })
+    }
+
+    /// Read an unsigned integer of `width` bytes (1, 2 or 4) at `offset`.
+    pub fn read_uint(
+        &self,
+        offset: i64,
+        width: usize,
+        little_endian: bool,
+    ) -> Result<u32, BufferError> {
+        let start = self.range(offset, width)?;
+        let bytes = &self.data[start..start + width];
+        let fold = |acc: u32, b: &u8| (acc << 8) | u32::from(*b);
+        Ok(if little_endian {
+            bytes.iter().rev().fold(0, fold)
+        } else {
+            bytes.iter().fold(0, fold)
+        })
+    }
+
+    /// Write `value` as an unsigned integer of `width` bytes (1, 2 or 4) at
+    /// `offset`, returning the offset just past the written bytes.
+    pub fn write_uint(
+        &mut self,
+        value: i64,
+        offset: i64,
+        width: usize,
+        little_endian: bool,
+    ) -> Result<usize, BufferError> {
+        let max = (1i64 << (width * 8)) - 1;
+        if !(0..=max).contains(&value) {
+            return Err(BufferError::ValueOutOfRange(value, width));
+        }
+        let start = self.range(offset, width)?;
+        let be = (value as u32).to_be_bytes();
+        let bytes = &be[4 - width..];
+        let target = &mut self.data[start..start + width];
+        if little_endian {
+            for (dst, src) in target.iter_mut().zip(bytes.iter().rev()) {
+                *dst = *src;
+            }
+        } else {
+            target.copy_from_slice(bytes);
+        }
+        Ok(start + width)
+    }
+
+    /// Copy the bytes in `start..end` into a new buffer. `end` defaults to the
+    /// buffer length.
+    pub fn slice(&self, start: i64, end: Option<i64>) -> Result<Buffer, BufferError> {
+        let end = end.unwrap_or(self.len() as i64);
+        let width =
+            usize::try_from(end.saturating_sub(start)).map_err(|_| BufferError::OutOfRange {
+                offset: start,
+                width: 0,
+                length: self.len(),
+            })?;
+        let start = self.range(start, width)?;
+        Ok(Buffer::from_bytes(self.data[start..start + width].to_vec()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_alloc_zero_filled() {
+        let buf = Buffer::alloc(4).unwrap();
+        assert_eq!(buf.as_bytes(), &[0, 0, 0, 0]);
+        assert!(matches!(
+            Buffer::alloc(-1),
+            Err(BufferError::InvalidSize(-1))
+        ));
+    }
+
+    #[test]
+    fn test_write_read_uint32_both_endians() {
+        let mut buf = Buffer::alloc(8).unwrap();
+        assert_eq!(buf.write_uint(0xDEADBEEF, 0, 4, false).unwrap(), 4);
+        assert_eq!(buf.write_uint(0xDEADBEEF, 4, 4, true).unwrap(), 8);
+        assert_eq!(
+            buf.as_bytes(),
+            &[0xDE, 0xAD, 0xBE, 0xEF, 0xEF, 0xBE, 0xAD, 0xDE]
+        );
+        assert_eq!(buf.read_uint(0, 4, false).unwrap(), 0xDEADBEEF);
+        assert_eq!(buf.read_uint(4, 4, true).unwrap(), 0xDEADBEEF);
+        assert_eq!(buf.read_uint(0, 4, true).unwrap(), 0xEFBEADDE);
+    }
+
+    #[test]
+    fn test_uint8_and_uint16() {
+        let mut buf = Buffer::alloc(3).unwrap();
+        buf.write_uint(0xFF, 0, 1, false).unwrap();
+        buf.write_uint(0x1234, 1, 2, true).unwrap();
+        assert_eq!(buf.as_bytes(), &[0xFF, 0x34, 0x12]);
+        assert_eq!(buf.read_uint(1, 2, true).unwrap(), 0x1234);
+        assert_eq!(buf.read_uint(1, 2, false).unwrap(), 0x3412);
+    }
+
+    #[test]
+    fn test_bounds_and_value_checks() {
+        let mut buf = Buffer::alloc(4).unwrap();
+        assert!(matches!(
+            buf.read_uint(1, 4, false),
+            Err(BufferError::OutOfRange { offset: 1, .. })
+        ));
+        assert!(buf.read_uint(-1, 1, false).is_err());
+        assert!(buf.read_uint(i64::MAX, 4, false).is_err());
+        assert!(matches!(
+            buf.write_uint(256, 0, 1, false),
+            Err(BufferError::ValueOutOfRange(256, 1))
+        ));
+        assert!(buf.write_uint(-1, 0, 4, false).is_err());
+        assert!(buf.write_uint(1, 3, 2, false).is_err());
+    }
+
+    #[test]
+    fn test_slice() {
+        let buf = Buffer::from_bytes(b"hello world".to_vec());
+        assert_eq!(buf.slice(6, None).unwrap().as_bytes(), b"world");
+        assert_eq!(buf.slice(0, Some(5)).unwrap().as_bytes(), b"hello");
+        assert!(buf.slice(3, Some(3)).unwrap().is_empty());
+        assert!(buf.slice(5, Some(2)).is_err());
+        assert!(buf.slice(0, Some(20)).is_err());
+    }
+
+    #[test]
+    fn test_encodings() {
+        let buf = Buffer::decode(b"68690a", Encoding::Hex).unwrap();
+        assert_eq!(buf.as_bytes(), b"hi\n");
+        assert_eq!(buf.encode(Encoding::Base64), b"aGkK");
+        assert_eq!(
+            Buffer::decode(b"aGkK", Encoding::Base64)
+                .unwrap()
+                .as_bytes(),
+            b"hi\n"
+        );
+        assert_eq!(
+            Buffer::from_bytes(vec![0xFF]).encode(Encoding::Utf8),
+            "\u{FFFD}".as_bytes()
+        );
+        assert!(Buffer::decode(b"zz", Encoding::Hex).is_err());
+        assert!("ebcdic".parse::<Encoding>().is_err());
+    }
+}
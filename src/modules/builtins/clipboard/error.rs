@@ -0,0 +1,62 @@
+use crate::error::StructuredError;
+use std::fmt;
+
+#[derive(Debug)]
+pub enum ClipboardError {
+    /// No clipboard could be opened, e.g. on a server without a display.
+    Unavailable(String),
+    /// Another program is holding the clipboard.
+    Busy,
+    Access(String),
+}
+
+impl fmt::Display for ClipboardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClipboardError::Unavailable(reason) => write!(
+                f,
+                "Clipboard is unavailable ({}); a desktop session is needed",
+                reason
+            ),
+            ClipboardError::Busy => write!(f, "Clipboard is in use by another program"),
+            ClipboardError::Access(msg) => write!(f, "Clipboard error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for ClipboardError {}
+
+impl ClipboardError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            ClipboardError::Unavailable(_) => "ERR_UNAVAILABLE",
+            ClipboardError::Busy => "ERR_BUSY",
+            ClipboardError::Access(_) => "ERR_CLIPBOARD",
+        }
+    }
+}
+
+impl From<arboard::Error> for ClipboardError {
+    fn from(err: arboard::Error) -> Self {
+        match err {
+            arboard::Error::ClipboardNotSupported => {
+                ClipboardError::Unavailable("not supported on this system".to_string())
+            }
+            arboard::Error::ClipboardOccupied => ClipboardError::Busy,
+            arboard::Error::Unknown { description } => ClipboardError::Access(description),
+            other => ClipboardError::Access(other.to_string()),
+        }
+    }
+}
+
+impl From<ClipboardError> for StructuredError {
+    fn from(err: ClipboardError) -> Self {
+        StructuredError::new("clipboard", err.code(), err.to_string())
+    }
+}
+
+impl From<ClipboardError> for crate::error::HypeError {
+    fn from(err: ClipboardError) -> Self {
+        crate::error::HypeError::Execution(err.to_string())
+    }
+}
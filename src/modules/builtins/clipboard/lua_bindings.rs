@@ -0,0 +1,36 @@
+use super::operations::SystemClipboard;
+use crate::lua::error::{structured_error, wrap_module_errors};
+use mlua::{Lua, Result as LuaResult, Table};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+pub fn create_clipboard_module(lua: &Lua) -> LuaResult<Table<'_>> {
+    let clipboard = lua.create_table()?;
+    let system = Rc::new(RefCell::new(SystemClipboard::new()));
+
+    let reader = system.clone();
+    let read_fn =
+        lua.create_function(move |_, ()| reader.borrow_mut().read().map_err(structured_error))?;
+    clipboard.set("read", read_fn)?;
+
+    let write_fn = lua.create_function(move |_, text: String| {
+        system.borrow_mut().write(&text).map_err(structured_error)
+    })?;
+    clipboard.set("write", write_fn)?;
+
+    wrap_module_errors(lua, &clipboard)?;
+    Ok(clipboard)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_clipboard_module() {
+        let lua = Lua::new();
+        let clipboard = create_clipboard_module(&lua).unwrap();
+        assert!(clipboard.contains_key("read").unwrap());
+        assert!(clipboard.contains_key("write").unwrap());
+    }
+}
@@ -0,0 +1,67 @@
+pub mod error;
+pub mod lua_bindings;
+pub mod operations;
+
+use serde_json::{json, Value as JsonValue};
+
+use super::BuiltinModule;
+use crate::error::HypeError;
+
+pub use lua_bindings::create_clipboard_module;
+
+pub struct ClipboardModule;
+
+impl ClipboardModule {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for ClipboardModule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BuiltinModule for ClipboardModule {
+    fn name(&self) -> &str {
+        "clipboard"
+    }
+
+    fn exports(&self) -> std::result::Result<JsonValue, HypeError> {
+        Ok(json!({
+            "__id": "clipboard",
+            "__desc": "Read and write the system clipboard's text",
+            "read": {
+                "__fn": "read",
+                "__desc": "The clipboard's text, or nil when it holds no text",
+                "__signature": "read() -> string | nil"
+            },
+            "write": {
+                "__fn": "write",
+                "__desc": "Replace the clipboard's contents with text",
+                "__signature": "write(text: string)"
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clipboard_module_name() {
+        let module = ClipboardModule::new();
+        assert_eq!(module.name(), "clipboard");
+    }
+
+    #[test]
+    fn test_clipboard_module_exports() {
+        let module = ClipboardModule::new();
+        let exports = module.exports().unwrap();
+        assert_eq!(exports["__id"], "clipboard");
+        assert!(exports.get("read").is_some());
+        assert!(exports.get("write").is_some());
+    }
+}
@@ -0,0 +1,80 @@
+use super::error::ClipboardError;
+
+pub type Result<T> = std::result::Result<T, ClipboardError>;
+
+/// The system clipboard, opened on first use so that `require("clipboard")`
+/// works everywhere and only reading or writing fails on headless systems.
+///
+/// On X11 and Wayland the clipboard belongs to the process that wrote it.
+/// The connection is kept open for the life of the script and handed to a
+/// clipboard manager, if one is running, when it closes.
+#[derive(Default)]
+pub struct SystemClipboard {
+    inner: Option<arboard::Clipboard>,
+}
+
+impl SystemClipboard {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The clipboard's text, or `None` when it is empty or holds something
+    /// other than text.
+    pub fn read(&mut self) -> Result<Option<String>> {
+        match self.open()?.get_text() {
+            Ok(text) => Ok(Some(text)),
+            Err(arboard::Error::ContentNotAvailable) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn write(&mut self, text: &str) -> Result<()> {
+        self.open()?.set_text(text).map_err(Into::into)
+    }
+
+    fn open(&mut self) -> Result<&mut arboard::Clipboard> {
+        if self.inner.is_none() {
+            let clipboard = arboard::Clipboard::new().map_err(open_error)?;
+            self.inner = Some(clipboard);
+        }
+        Ok(self.inner.as_mut().expect("clipboard was just opened"))
+    }
+}
+
+/// Failing to connect at all means there is no clipboard to talk to.
+fn open_error(err: arboard::Error) -> ClipboardError {
+    match ClipboardError::from(err) {
+        ClipboardError::Access(reason) => ClipboardError::Unavailable(headless_hint(reason)),
+        other => other,
+    }
+}
+
+fn headless_hint(reason: String) -> String {
+    let no_display = cfg!(all(unix, not(target_os = "macos")))
+        && std::env::var_os("DISPLAY").is_none()
+        && std::env::var_os("WAYLAND_DISPLAY").is_none();
+    if no_display {
+        format!("{}; neither DISPLAY nor WAYLAND_DISPLAY is set", reason)
+    } else {
+        reason
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_open_errors_mean_unavailable() {
+        let err = open_error(arboard::Error::Unknown {
+            description: "X11 server connection timed out".to_string(),
+        });
+        assert_eq!(err.code(), "ERR_UNAVAILABLE");
+        assert!(err
+            .to_string()
+            .starts_with("Clipboard is unavailable (X11 server connection timed out"));
+
+        let err = open_error(arboard::Error::ClipboardOccupied);
+        assert_eq!(err.code(), "ERR_BUSY");
+    }
+}
@@ -0,0 +1,26 @@
+use std::fmt;
+
+#[derive(Debug)]
+pub enum ConsoleError {
+    InvalidLevel(String),
+}
+
+impl fmt::Display for ConsoleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConsoleError::InvalidLevel(level) => write!(
+                f,
+                "Unknown log level '{}': expected debug, info, warn, error or silent",
+                level
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ConsoleError {}
+
+impl From<ConsoleError> for crate::error::HypeError {
+    fn from(err: ConsoleError) -> Self {
+        crate::error::HypeError::Execution(err.to_string())
+    }
+}
@@ -0,0 +1,162 @@
+use super::operations::*;
+use crate::lua::require::lua_to_json;
+use crate::modules::builtins::util::lua_bindings::format_values;
+use mlua::{Lua, MultiValue, Result as LuaResult, Table, Value};
+
+/// A console that writes to stderr at the `HYPE_LOG` level.
+pub fn create_console_module(lua: &Lua) -> LuaResult<Table<'_>> {
+    create_console_module_with(lua, Console::from_env())
+}
+
+pub fn create_console_module_with(lua: &Lua, console: Console) -> LuaResult<Table<'_>> {
+    let table = lua.create_table()?;
+
+    for level in [Level::Debug, Level::Info, Level::Warn, Level::Error] {
+        let console = console.clone();
+        let log_fn = lua.create_function(move |lua, args: MultiValue| {
+            if !console.enabled(level) {
+                return Ok(());
+            }
+            let mut args = args.into_iter();
+            let template = args.next().unwrap_or(Value::Nil);
+            let message = format_values(lua, template, args.collect())?;
+            // A closed stderr must not take the script down with it
+            let _ = console.log(level, &message);
+            Ok(())
+        })?;
+        table.set(level.as_str(), log_fn)?;
+    }
+
+    let json_console = console.clone();
+    let json_fn = lua.create_function(move |lua, (value, level): (Value, Option<String>)| {
+        let level = parse_level(level.as_deref().unwrap_or("info"))?;
+        if !json_console.enabled(level) {
+            return Ok(());
+        }
+        let _ = json_console.json(level, lua_to_json(lua, value)?);
+        Ok(())
+    })?;
+    table.set("json", json_fn)?;
+
+    let set_level_console = console.clone();
+    let set_level_fn = lua.create_function(move |_, level: String| {
+        set_level_console.set_level(parse_level(&level)?);
+        Ok(())
+    })?;
+    table.set("setLevel", set_level_fn)?;
+
+    let level_fn = lua.create_function(move |_, ()| Ok(console.level().as_str()))?;
+    table.set("level", level_fn)?;
+
+    Ok(table)
+}
+
+fn parse_level(level: &str) -> LuaResult<Level> {
+    level.parse().map_err(mlua::Error::external)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{self, Write};
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Buffer {
+        fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(data)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl Buffer {
+        fn lines(&self) -> Vec<String> {
+            String::from_utf8(self.0.lock().unwrap().clone())
+                .unwrap()
+                .lines()
+                .map(str::to_string)
+                .collect()
+        }
+    }
+
+    fn lua_with_console(level: Level) -> (Lua, Buffer) {
+        let lua = Lua::new();
+        let buffer = Buffer::default();
+        let console =
+            create_console_module_with(&lua, Console::new(level, Box::new(buffer.clone())))
+                .unwrap();
+        lua.globals().set("console", console).unwrap();
+        (lua, buffer)
+    }
+
+    #[test]
+    fn test_create_console_module() {
+        let lua = Lua::new();
+        let console = create_console_module(&lua).unwrap();
+        for name in [
+            "debug", "info", "warn", "error", "json", "setLevel", "level",
+        ] {
+            assert!(console.contains_key(name).unwrap(), "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_warn_level_suppresses_info() {
+        let (lua, buffer) = lua_with_console(Level::Warn);
+        lua.load(
+            r#"
+            console.debug("debugging")
+            console.info("starting %s", "server")
+            console.warn("disk at %d%%", 91)
+            console.error("failed:", {code = 2})
+        "#,
+        )
+        .exec()
+        .unwrap();
+
+        let lines = buffer.lines();
+        assert_eq!(lines.len(), 2, "{:?}", lines);
+        assert!(lines[0].ends_with("[WARN] disk at 91%"), "{}", lines[0]);
+        assert!(
+            lines[1].ends_with("[ERROR] failed: { code = 2 }"),
+            "{}",
+            lines[1]
+        );
+
+        lua.load(r#"console.setLevel("debug"); console.debug("now visible")"#)
+            .exec()
+            .unwrap();
+        assert!(buffer.lines()[2].ends_with("[DEBUG] now visible"));
+    }
+
+    #[test]
+    fn test_json_writes_valid_lines() {
+        let (lua, buffer) = lua_with_console(Level::Info);
+        lua.load(
+            r#"
+            console.json({event = "request", status = 200, tags = {"a", "b"}})
+            console.json({event = "noise"}, "debug")
+            console.json({event = "slow", level = "custom"}, "warn")
+        "#,
+        )
+        .exec()
+        .unwrap();
+
+        let lines = buffer.lines();
+        assert_eq!(lines.len(), 2, "{:?}", lines);
+        let first: serde_json::Value = serde_json::from_str(&lines[0]).unwrap();
+        assert_eq!(first["event"], "request");
+        assert_eq!(first["status"], 200);
+        assert_eq!(first["tags"], serde_json::json!(["a", "b"]));
+        assert_eq!(first["level"], "info");
+        assert!(first["timestamp"].as_str().unwrap().ends_with('Z'));
+
+        let second: serde_json::Value = serde_json::from_str(&lines[1]).unwrap();
+        assert_eq!(second["level"], "custom");
+    }
+}
@@ -0,0 +1,95 @@
+pub mod error;
+pub mod lua_bindings;
+pub mod operations;
+
+use serde_json::{json, Value as JsonValue};
+
+use super::BuiltinModule;
+use crate::error::HypeError;
+
+pub use error::ConsoleError;
+pub use lua_bindings::create_console_module;
+
+pub struct ConsoleModule;
+
+impl ConsoleModule {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for ConsoleModule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BuiltinModule for ConsoleModule {
+    fn name(&self) -> &str {
+        "console"
+    }
+
+    fn exports(&self) -> std::result::Result<JsonValue, HypeError> {
+        Ok(json!({
+            "__id": "console",
+            "__desc": "Leveled logging to stderr, filtered by HYPE_LOG",
+            "debug": {
+                "__fn": "debug",
+                "__desc": "Log a util.format-style message at debug level",
+                "__signature": "debug(template: any, ...: any) -> nil"
+            },
+            "info": {
+                "__fn": "info",
+                "__desc": "Log a util.format-style message at info level",
+                "__signature": "info(template: any, ...: any) -> nil"
+            },
+            "warn": {
+                "__fn": "warn",
+                "__desc": "Log a util.format-style message at warn level",
+                "__signature": "warn(template: any, ...: any) -> nil"
+            },
+            "error": {
+                "__fn": "error",
+                "__desc": "Log a util.format-style message at error level",
+                "__signature": "error(template: any, ...: any) -> nil"
+            },
+            "json": {
+                "__fn": "json",
+                "__desc": "Log a value as one JSON line, adding timestamp and level to tables",
+                "__signature": "json(value: any, level?: string) -> nil"
+            },
+            "setLevel": {
+                "__fn": "setLevel",
+                "__desc": "Change the minimum level that is printed",
+                "__signature": "setLevel(level: string) -> nil"
+            },
+            "level": {
+                "__fn": "level",
+                "__desc": "Current minimum level",
+                "__signature": "level() -> string"
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_console_module_name() {
+        let module = ConsoleModule::new();
+        assert_eq!(module.name(), "console");
+    }
+
+    #[test]
+    fn test_console_module_exports() {
+        let module = ConsoleModule::new();
+        let exports = module.exports().unwrap();
+        for name in [
+            "debug", "info", "warn", "error", "json", "setLevel", "level",
+        ] {
+            assert!(exports.get(name).is_some(), "{}", name);
+        }
+    }
+}
@@ -0,0 +1,176 @@
+use super::ConsoleError;
+use chrono::{SecondsFormat, Utc};
+use serde_json::Value as JsonValue;
+use std::io::{self, Write};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+
+/// Environment variable holding the minimum level to print.
+pub const LEVEL_ENV: &str = "HYPE_LOG";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Debug,
+    Info,
+    Warn,
+    Error,
+    /// Only usable as a threshold: suppresses everything.
+    Silent,
+}
+
+impl Level {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Level::Debug => "debug",
+            Level::Info => "info",
+            Level::Warn => "warn",
+            Level::Error => "error",
+            Level::Silent => "silent",
+        }
+    }
+}
+
+impl FromStr for Level {
+    type Err = ConsoleError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "debug" | "trace" => Ok(Level::Debug),
+            "info" => Ok(Level::Info),
+            "warn" | "warning" => Ok(Level::Warn),
+            "error" => Ok(Level::Error),
+            "silent" | "off" | "none" => Ok(Level::Silent),
+            _ => Err(ConsoleError::InvalidLevel(value.to_string())),
+        }
+    }
+}
+
+/// Writes leveled log lines to a sink (stderr unless a test supplies one).
+#[derive(Clone)]
+pub struct Console {
+    level: Arc<Mutex<Level>>,
+    sink: Arc<Mutex<Box<dyn Write + Send>>>,
+}
+
+impl Console {
+    pub fn new(level: Level, sink: Box<dyn Write + Send>) -> Self {
+        Self {
+            level: Arc::new(Mutex::new(level)),
+            sink: Arc::new(Mutex::new(sink)),
+        }
+    }
+
+    /// Write to stderr at the level named by `HYPE_LOG`, or `info` when it is
+    /// unset. An unrecognized value is reported once and ignored.
+    pub fn from_env() -> Self {
+        let level = match std::env::var(LEVEL_ENV) {
+            Ok(value) if !value.trim().is_empty() => value.parse().unwrap_or_else(|e| {
+                eprintln!("Warning: {}: {}", LEVEL_ENV, e);
+                Level::Info
+            }),
+            _ => Level::Info,
+        };
+        Self::new(level, Box::new(io::stderr()))
+    }
+
+    pub fn level(&self) -> Level {
+        *self.level.lock().unwrap()
+    }
+
+    pub fn set_level(&self, level: Level) {
+        *self.level.lock().unwrap() = level;
+    }
+
+    pub fn enabled(&self, level: Level) -> bool {
+        level != Level::Silent && level >= self.level()
+    }
+
+    /// `2026-01-02T03:04:05.678Z [WARN] message`
+    pub fn log(&self, level: Level, message: &str) -> io::Result<()> {
+        if !self.enabled(level) {
+            return Ok(());
+        }
+        let line = format!(
+            "{} [{}] {}",
+            timestamp(),
+            level.as_str().to_ascii_uppercase(),
+            message
+        );
+        self.write_line(&line)
+    }
+
+    /// Write `value` as a single JSON line. Objects gain `timestamp` and
+    /// `level` fields unless they already have them.
+    pub fn json(&self, level: Level, value: JsonValue) -> io::Result<()> {
+        if !self.enabled(level) {
+            return Ok(());
+        }
+        let value = match value {
+            JsonValue::Object(mut fields) => {
+                fields
+                    .entry("timestamp")
+                    .or_insert_with(|| JsonValue::String(timestamp()));
+                fields
+                    .entry("level")
+                    .or_insert_with(|| JsonValue::String(level.as_str().to_string()));
+                JsonValue::Object(fields)
+            }
+            other => other,
+        };
+        let line = serde_json::to_string(&value)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        self.write_line(&line)
+    }
+
+    fn write_line(&self, line: &str) -> io::Result<()> {
+        let mut sink = self.sink.lock().unwrap();
+        writeln!(sink, "{}", line)?;
+        sink.flush()
+    }
+}
+
+fn timestamp() -> String {
+    Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Buffer {
+        fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(data)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_parse_levels() {
+        assert_eq!("WARNING".parse::<Level>().unwrap(), Level::Warn);
+        assert_eq!("off".parse::<Level>().unwrap(), Level::Silent);
+        assert!("loud".parse::<Level>().is_err());
+    }
+
+    #[test]
+    fn test_level_filters_lines() {
+        let buffer = Buffer::default();
+        let console = Console::new(Level::Warn, Box::new(buffer.clone()));
+
+        console.log(Level::Info, "hidden").unwrap();
+        console.log(Level::Warn, "careful").unwrap();
+        console.log(Level::Error, "broken").unwrap();
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 2, "{}", output);
+        assert!(lines[0].ends_with(" [WARN] careful"), "{}", lines[0]);
+        assert!(lines[1].ends_with(" [ERROR] broken"), "{}", lines[1]);
+        assert!(lines[0].contains('T') && lines[0].contains("Z ["));
+    }
+}
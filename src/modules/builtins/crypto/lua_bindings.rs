@@ -1,5 +1,9 @@
 use super::operations::*;
-use mlua::{Lua, Result as LuaResult, Table, Value};
+use crate::lua::security::check_file_access;
+use mlua::{Lua, Result as LuaResult, String as LuaString, Table, Value};
+use std::cell::RefCell;
+use std::path::Path;
+use std::rc::Rc;
 
 pub fn create_crypto_module(lua: &Lua) -> LuaResult<Table> {
     let crypto = lua.create_table()?;
@@ -12,15 +16,32 @@ pub fn create_crypto_module(lua: &Lua) -> LuaResult<Table> {
     })?;
     crypto.set("hash", hash_fn)?;
 
-    let hash_file_fn =
-        lua.create_function(|_, (algorithm, path): (String, String)| {
-            match hash_file(&algorithm, &path) {
-                Ok(result) => Ok(result),
-                Err(e) => Err(mlua::Error::RuntimeError(e.to_string())),
-            }
-        })?;
+    let hash_file_fn = lua.create_function(|lua, (algorithm, path): (String, String)| {
+        check_file_access(lua, &path, "read")
+            .map_err(|e| mlua::Error::RuntimeError(e.to_string()))?;
+        match hash_file(&algorithm, &path) {
+            Ok(result) => Ok(result),
+            Err(e) => Err(mlua::Error::RuntimeError(e.to_string())),
+        }
+    })?;
     crypto.set("hashFile", hash_file_fn)?;
 
+    let hash_dir_fn = lua.create_function(
+        |lua, (algorithm, path, options): (String, String, Option<Table>)| {
+            check_file_access(lua, &path, "read")
+                .map_err(|e| mlua::Error::RuntimeError(e.to_string()))?;
+            let ignore: Vec<String> = match options {
+                Some(options) => options
+                    .get::<_, Option<Vec<String>>>("ignore")?
+                    .unwrap_or_default(),
+                None => Vec::new(),
+            };
+            hash_dir(&algorithm, Path::new(&path), &ignore)
+                .map_err(|e| mlua::Error::RuntimeError(e.to_string()))
+        },
+    )?;
+    crypto.set("hashDir", hash_dir_fn)?;
+
     let hmac_fn =
         lua.create_function(
             |_, (algorithm, key, data): (String, String, String)| match hmac_sign(
@@ -34,16 +55,25 @@ pub fn create_crypto_module(lua: &Lua) -> LuaResult<Table> {
         )?;
     crypto.set("hmac", hmac_fn)?;
 
-    let random_bytes_fn = lua.create_function(|lua, size: usize| match random_bytes(size) {
-        Ok(bytes) => {
-            let table = lua.create_table()?;
-            for (i, byte) in bytes.iter().enumerate() {
-                table.set(i + 1, *byte as i32)?;
+    let random_bytes_fn =
+        lua.create_function(|lua, (size, encoding): (usize, Option<String>)| {
+            if let Some(encoding) = encoding {
+                return match random_bytes_encoded(size, &encoding) {
+                    Ok(text) => Ok(Value::String(lua.create_string(&text)?)),
+                    Err(e) => Err(mlua::Error::RuntimeError(e.to_string())),
+                };
             }
-            Ok(table)
-        }
-        Err(e) => Err(mlua::Error::RuntimeError(e.to_string())),
-    })?;
+            match random_bytes(size) {
+                Ok(bytes) => {
+                    let table = lua.create_table()?;
+                    for (i, byte) in bytes.iter().enumerate() {
+                        table.set(i + 1, *byte as i32)?;
+                    }
+                    Ok(Value::Table(table))
+                }
+                Err(e) => Err(mlua::Error::RuntimeError(e.to_string())),
+            }
+        })?;
     crypto.set("randomBytes", random_bytes_fn)?;
 
     let random_int_fn =
@@ -56,23 +86,45 @@ pub fn create_crypto_module(lua: &Lua) -> LuaResult<Table> {
     let random_uuid_fn = lua.create_function(|_, ()| Ok(random_uuid()))?;
     crypto.set("randomUUID", random_uuid_fn)?;
 
+    // Seeded, reproducible and NOT secure: for tests and simulations only
+    let insecure_rng = Rc::new(RefCell::new(InsecureRng::from_entropy()));
+
+    let rng = Rc::clone(&insecure_rng);
+    let seed_insecure_fn = lua.create_function(move |_, seed: i64| {
+        rng.borrow_mut().seed(seed as u64);
+        Ok(())
+    })?;
+    crypto.set("seedInsecure", seed_insecure_fn)?;
+
+    let rng = Rc::clone(&insecure_rng);
+    let random_float_fn = lua.create_function(move |_, ()| Ok(rng.borrow_mut().random_float()))?;
+    crypto.set("randomFloat", random_float_fn)?;
+
+    let rng = insecure_rng;
+    let random_int_insecure_fn = lua.create_function(move |_, (min, max): (i64, i64)| {
+        rng.borrow_mut()
+            .random_int(min, max)
+            .map_err(|e| mlua::Error::RuntimeError(e.to_string()))
+    })?;
+    crypto.set("randomIntInsecure", random_int_insecure_fn)?;
+
+    // Encoders and decoders work on raw bytes so binary data round-trips
     let base64_encode_fn =
-        lua.create_function(|_, data: String| Ok(base64_encode(data.as_bytes())))?;
+        lua.create_function(|_, data: LuaString| Ok(base64_encode(data.as_bytes())))?;
     crypto.set("base64Encode", base64_encode_fn)?;
 
-    let base64_decode_fn = lua.create_function(|_, data: String| match base64_decode(&data) {
-        Ok(bytes) => String::from_utf8(bytes)
-            .map_err(|e| mlua::Error::RuntimeError(format!("Invalid UTF-8: {}", e))),
+    let base64_decode_fn = lua.create_function(|lua, data: String| match base64_decode(&data) {
+        Ok(bytes) => lua.create_string(&bytes),
         Err(e) => Err(mlua::Error::RuntimeError(e.to_string())),
     })?;
     crypto.set("base64Decode", base64_decode_fn)?;
 
-    let hex_encode_fn = lua.create_function(|_, data: String| Ok(hex_encode(data.as_bytes())))?;
+    let hex_encode_fn =
+        lua.create_function(|_, data: LuaString| Ok(hex_encode(data.as_bytes())))?;
     crypto.set("hexEncode", hex_encode_fn)?;
 
-    let hex_decode_fn = lua.create_function(|_, data: String| match hex_decode(&data) {
-        Ok(bytes) => String::from_utf8(bytes)
-            .map_err(|e| mlua::Error::RuntimeError(format!("Invalid UTF-8: {}", e))),
+    let hex_decode_fn = lua.create_function(|lua, data: String| match hex_decode(&data) {
+        Ok(bytes) => lua.create_string(&bytes),
         Err(e) => Err(mlua::Error::RuntimeError(e.to_string())),
     })?;
     crypto.set("hexDecode", hex_decode_fn)?;
@@ -118,10 +170,14 @@ mod tests {
         let crypto = result.unwrap();
         assert!(crypto.contains_key("hash").unwrap());
         assert!(crypto.contains_key("hashFile").unwrap());
+        assert!(crypto.contains_key("hashDir").unwrap());
         assert!(crypto.contains_key("hmac").unwrap());
         assert!(crypto.contains_key("randomBytes").unwrap());
         assert!(crypto.contains_key("randomInt").unwrap());
         assert!(crypto.contains_key("randomUUID").unwrap());
+        assert!(crypto.contains_key("seedInsecure").unwrap());
+        assert!(crypto.contains_key("randomFloat").unwrap());
+        assert!(crypto.contains_key("randomIntInsecure").unwrap());
         assert!(crypto.contains_key("base64Encode").unwrap());
         assert!(crypto.contains_key("base64Decode").unwrap());
         assert!(crypto.contains_key("hexEncode").unwrap());
@@ -44,6 +44,11 @@ impl BuiltinModule for CryptoModule {
                 "__desc": "Hash file contents",
                 "__signature": "hashFile(algorithm: string, path: string) -> string"
             },
+            "hashDir": {
+                "__fn": "hashDir",
+                "__desc": "Hash a directory tree deterministically",
+                "__signature": "hashDir(algorithm: string, path: string, options?: table) -> string"
+            },
             "hmac": {
                 "__fn": "hmac",
                 "__desc": "HMAC signing with key",
@@ -51,8 +56,8 @@ impl BuiltinModule for CryptoModule {
             },
             "randomBytes": {
                 "__fn": "randomBytes",
-                "__desc": "Generate cryptographically secure random bytes",
-                "__signature": "randomBytes(size: number) -> table"
+                "__desc": "Generate cryptographically secure random bytes, as a byte table or a hex/base64 string",
+                "__signature": "randomBytes(size: number, encoding?: \"hex\" | \"base64\") -> table | string"
             },
             "randomInt": {
                 "__fn": "randomInt",
@@ -64,6 +69,21 @@ impl BuiltinModule for CryptoModule {
                 "__desc": "Generate UUID v4",
                 "__signature": "randomUUID() -> string"
             },
+            "seedInsecure": {
+                "__fn": "seedInsecure",
+                "__desc": "Seed the reproducible, NOT secure generator behind randomFloat and randomIntInsecure",
+                "__signature": "seedInsecure(seed: number) -> nil"
+            },
+            "randomFloat": {
+                "__fn": "randomFloat",
+                "__desc": "Generate reproducible, NOT secure random float in range [0, 1)",
+                "__signature": "randomFloat() -> number"
+            },
+            "randomIntInsecure": {
+                "__fn": "randomIntInsecure",
+                "__desc": "Generate reproducible, NOT secure random integer in range [min, max)",
+                "__signature": "randomIntInsecure(min: number, max: number) -> number"
+            },
             "base64Encode": {
                 "__fn": "base64Encode",
                 "__desc": "Base64 encode string",
@@ -120,10 +140,14 @@ mod tests {
         assert!(exports.is_object());
         assert!(exports.get("hash").is_some());
         assert!(exports.get("hashFile").is_some());
+        assert!(exports.get("hashDir").is_some());
         assert!(exports.get("hmac").is_some());
         assert!(exports.get("randomBytes").is_some());
         assert!(exports.get("randomInt").is_some());
         assert!(exports.get("randomUUID").is_some());
+        assert!(exports.get("seedInsecure").is_some());
+        assert!(exports.get("randomFloat").is_some());
+        assert!(exports.get("randomIntInsecure").is_some());
         assert!(exports.get("base64Encode").is_some());
         assert!(exports.get("base64Decode").is_some());
         assert!(exports.get("hexEncode").is_some());
@@ -3,10 +3,12 @@ use base64::{engine::general_purpose, Engine as _};
 use bcrypt::{hash as bcrypt_hash, verify as bcrypt_verify, DEFAULT_COST};
 use hmac::{Hmac, Mac};
 use md5::Md5;
-use rand::Rng;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use sha1::Sha1;
 use sha2::{Digest, Sha256, Sha512};
 use std::fs;
+use std::path::Path;
 use uuid::Uuid;
 
 pub fn hash(algorithm: &str, data: &[u8]) -> Result<String, CryptoError> {
@@ -43,6 +45,102 @@ pub fn hash_file(algorithm: &str, path: &str) -> Result<String, CryptoError> {
     hash(algorithm, &data)
 }
 
+/// A single digest over every file under `root`. Files are visited in sorted
+/// order of their `/`-separated relative paths, and each contributes its path
+/// and the digest of its contents, so identical trees hash equal whatever
+/// order the filesystem lists them in. Entries whose relative path or name
+/// matches one of the `ignore` glob patterns are skipped, along with
+/// everything under an ignored directory.
+pub fn hash_dir(algorithm: &str, root: &Path, ignore: &[String]) -> Result<String, CryptoError> {
+    // Reject an unknown algorithm before walking the tree
+    hash(algorithm, b"")?;
+    if !root.is_dir() {
+        return Err(CryptoError::FileError(format!(
+            "{} is not a directory",
+            root.display()
+        )));
+    }
+
+    let mut files = Vec::new();
+    collect_files(root, "", ignore, &mut files)?;
+    files.sort();
+
+    let mut manifest = Vec::new();
+    for relative in &files {
+        let digest = hash_file(algorithm, &root.join(relative).to_string_lossy())?;
+        manifest.extend_from_slice(relative.as_bytes());
+        manifest.push(0);
+        manifest.extend_from_slice(digest.as_bytes());
+        manifest.push(b'\n');
+    }
+    hash(algorithm, &manifest)
+}
+
+fn collect_files(
+    dir: &Path,
+    prefix: &str,
+    ignore: &[String],
+    files: &mut Vec<String>,
+) -> Result<(), CryptoError> {
+    let entries = fs::read_dir(dir).map_err(|e| CryptoError::FileError(e.to_string()))?;
+    for entry in entries {
+        let entry = entry.map_err(|e| CryptoError::FileError(e.to_string()))?;
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let relative = if prefix.is_empty() {
+            name.clone()
+        } else {
+            format!("{}/{}", prefix, name)
+        };
+        if ignore
+            .iter()
+            .any(|pattern| glob_match(pattern, &relative) || glob_match(pattern, &name))
+        {
+            continue;
+        }
+
+        // Symlinked directories are not followed, so a link cycle cannot loop
+        let file_type = entry
+            .file_type()
+            .map_err(|e| CryptoError::FileError(e.to_string()))?;
+        if file_type.is_dir() {
+            collect_files(&entry.path(), &relative, ignore, files)?;
+        } else if entry.path().is_file() {
+            files.push(relative);
+        }
+    }
+    Ok(())
+}
+
+/// Shell-style matching where `*` matches any run of characters and `?`
+/// matches exactly one.
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    p = star + 1;
+                    t = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
 pub fn hmac_sign(algorithm: &str, key: &[u8], data: &[u8]) -> Result<String, CryptoError> {
     match algorithm.to_lowercase().as_str() {
         "sha256" => {
@@ -84,6 +182,19 @@ pub fn random_bytes(size: usize) -> Result<Vec<u8>, CryptoError> {
     Ok(bytes)
 }
 
+/// `size` random bytes rendered as a `"hex"` or `"base64"` string.
+pub fn random_bytes_encoded(size: usize, encoding: &str) -> Result<String, CryptoError> {
+    let bytes = random_bytes(size)?;
+    match encoding {
+        "hex" => Ok(hex_encode(&bytes)),
+        "base64" => Ok(base64_encode(&bytes)),
+        other => Err(CryptoError::InvalidInput(format!(
+            "Unknown encoding '{}': expected hex or base64",
+            other
+        ))),
+    }
+}
+
 pub fn random_int(min: i64, max: i64) -> Result<i64, CryptoError> {
     if min >= max {
         return Err(CryptoError::InvalidInput(
@@ -141,6 +252,40 @@ pub fn timing_safe_equal(a: &[u8], b: &[u8]) -> bool {
     result == 0
 }
 
+// Seedable PRNG. Everything above draws from the operating system's CSPRNG;
+// the generator below is for reproducible runs and is NOT suitable for keys,
+// tokens, passwords or anything else that must be unpredictable.
+
+/// A fast pseudo-random generator that replays the same sequence for the
+/// same seed. Its output can be predicted from the seed.
+pub struct InsecureRng(StdRng);
+
+impl InsecureRng {
+    /// A generator with a random seed, used until a script calls `seed`.
+    pub fn from_entropy() -> Self {
+        Self(StdRng::from_entropy())
+    }
+
+    pub fn seed(&mut self, seed: u64) {
+        self.0 = StdRng::seed_from_u64(seed);
+    }
+
+    /// A float in `[0, 1)`.
+    pub fn random_float(&mut self) -> f64 {
+        self.0.gen()
+    }
+
+    /// An integer in `[min, max)`, like [`random_int`].
+    pub fn random_int(&mut self, min: i64, max: i64) -> Result<i64, CryptoError> {
+        if min >= max {
+            return Err(CryptoError::InvalidInput(
+                "min must be less than max".to_string(),
+            ));
+        }
+        Ok(self.0.gen_range(min..max))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -166,6 +311,83 @@ mod tests {
         assert!(result.is_err());
     }
 
+    fn write_tree(root: &Path, files: &[(&str, &str)]) {
+        for (relative, contents) in files {
+            let path = root.join(relative);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, contents).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_hash_dir_is_deterministic() {
+        let first = tempfile::tempdir().unwrap();
+        let second = tempfile::tempdir().unwrap();
+        write_tree(
+            first.path(),
+            &[
+                ("a.txt", "alpha"),
+                ("sub/b.txt", "beta"),
+                ("sub/c.txt", "gamma"),
+            ],
+        );
+        // Same tree, created in a different order
+        write_tree(
+            second.path(),
+            &[
+                ("sub/c.txt", "gamma"),
+                ("a.txt", "alpha"),
+                ("sub/b.txt", "beta"),
+            ],
+        );
+        let digest = hash_dir("sha256", first.path(), &[]).unwrap();
+        assert_eq!(digest.len(), 64);
+        assert_eq!(digest, hash_dir("sha256", second.path(), &[]).unwrap());
+
+        fs::write(second.path().join("sub/c.txt"), "gammA").unwrap();
+        assert_ne!(digest, hash_dir("sha256", second.path(), &[]).unwrap());
+    }
+
+    #[test]
+    fn test_hash_dir_includes_paths_and_ignores_patterns() {
+        let first = tempfile::tempdir().unwrap();
+        let second = tempfile::tempdir().unwrap();
+        write_tree(first.path(), &[("a.txt", "same")]);
+        write_tree(second.path(), &[("b.txt", "same")]);
+        assert_ne!(
+            hash_dir("md5", first.path(), &[]).unwrap(),
+            hash_dir("md5", second.path(), &[]).unwrap()
+        );
+
+        let clean = hash_dir("sha1", first.path(), &[]).unwrap();
+        write_tree(
+            first.path(),
+            &[("debug.log", "noise"), ("target/out.bin", "build")],
+        );
+        let ignore = vec!["*.log".to_string(), "target".to_string()];
+        assert_eq!(hash_dir("sha1", first.path(), &ignore).unwrap(), clean);
+        assert_ne!(hash_dir("sha1", first.path(), &[]).unwrap(), clean);
+
+        assert!(matches!(
+            hash_dir("crc32", first.path(), &[]),
+            Err(CryptoError::InvalidAlgorithm(_))
+        ));
+        assert!(matches!(
+            hash_dir("sha256", &first.path().join("a.txt"), &[]),
+            Err(CryptoError::FileError(_))
+        ));
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("*.log", "debug.log"));
+        assert!(glob_match("sub/*", "sub/b.txt"));
+        assert!(glob_match("?.txt", "a.txt"));
+        assert!(glob_match("a*b*c", "axxbyyc"));
+        assert!(!glob_match("*.log", "debug.txt"));
+        assert!(!glob_match("?.txt", "ab.txt"));
+    }
+
     #[test]
     fn test_hmac_sha256() {
         let result = hmac_sign("sha256", b"secret", b"hello").unwrap();
@@ -181,6 +403,21 @@ mod tests {
         assert_eq!(bytes.len(), 16);
     }
 
+    #[test]
+    fn test_random_bytes_encoded() {
+        let hex = random_bytes_encoded(16, "hex").unwrap();
+        assert_eq!(hex.len(), 32);
+        assert_eq!(hex_decode(&hex).unwrap().len(), 16);
+
+        let base64 = random_bytes_encoded(24, "base64").unwrap();
+        assert_eq!(base64_decode(&base64).unwrap().len(), 24);
+
+        assert!(matches!(
+            random_bytes_encoded(16, "utf8"),
+            Err(CryptoError::InvalidInput(_))
+        ));
+    }
+
     #[test]
     fn test_random_bytes_invalid_size() {
         let result = random_bytes(0);
@@ -286,4 +523,22 @@ mod tests {
     fn test_timing_safe_equal_different_length() {
         assert!(!timing_safe_equal(b"hello", b"helloworld"));
     }
+
+    #[test]
+    fn test_insecure_rng_replays_seeded_sequence() {
+        let sequence = |seed| {
+            let mut rng = InsecureRng::from_entropy();
+            rng.seed(seed);
+            let ints: Vec<i64> = (0..8).map(|_| rng.random_int(0, 1000).unwrap()).collect();
+            (ints, rng.random_float())
+        };
+
+        assert_eq!(sequence(42), sequence(42));
+        assert_ne!(sequence(42), sequence(43));
+
+        let (ints, float) = sequence(7);
+        assert!(ints.iter().all(|n| (0..1000).contains(n)));
+        assert!((0.0..1.0).contains(&float));
+        assert!(InsecureRng::from_entropy().random_int(5, 5).is_err());
+    }
 }
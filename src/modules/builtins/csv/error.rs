@@ -0,0 +1,37 @@
+use std::fmt;
+
+#[derive(Debug)]
+pub enum CsvError {
+    ParseError(String),
+    WriteError(String),
+    IoError(String),
+    InvalidOption(String),
+}
+
+impl fmt::Display for CsvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CsvError::ParseError(msg) => write!(f, "CSV parse error: {}", msg),
+            CsvError::WriteError(msg) => write!(f, "CSV write error: {}", msg),
+            CsvError::IoError(msg) => write!(f, "CSV I/O error: {}", msg),
+            CsvError::InvalidOption(msg) => write!(f, "Invalid CSV option: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for CsvError {}
+
+impl From<csv::Error> for CsvError {
+    fn from(err: csv::Error) -> Self {
+        match err.kind() {
+            csv::ErrorKind::Io(_) => CsvError::IoError(err.to_string()),
+            _ => CsvError::ParseError(err.to_string()),
+        }
+    }
+}
+
+impl From<CsvError> for crate::error::HypeError {
+    fn from(err: CsvError) -> Self {
+        crate::error::HypeError::Execution(err.to_string())
+    }
+}
@@ -0,0 +1,255 @@
+use super::operations::*;
+use crate::lua::security::check_file_access;
+use mlua::{Function, Lua, Table, Value as LuaValue};
+use std::collections::BTreeSet;
+
+pub fn create_csv_module(lua: &Lua) -> mlua::Result<Table<'_>> {
+    let csv_table = lua.create_table()?;
+
+    register_parse(lua, &csv_table)?;
+    register_stringify(lua, &csv_table)?;
+    register_read_file(lua, &csv_table)?;
+
+    Ok(csv_table)
+}
+
+fn register_parse(lua: &Lua, table: &Table) -> mlua::Result<()> {
+    let parse_fn = lua.create_function(|lua, (input, options): (String, Option<Table>)| {
+        let options = parse_options(options.as_ref())?;
+        let data = parse(&input, &options).map_err(mlua::Error::external)?;
+
+        let rows = lua.create_table()?;
+        for record in data.rows {
+            rows.raw_push(row_to_table(lua, record, data.headers.as_deref())?)?;
+        }
+        Ok(rows)
+    })?;
+    table.set("parse", parse_fn)?;
+    Ok(())
+}
+
+fn register_stringify(lua: &Lua, table: &Table) -> mlua::Result<()> {
+    let stringify_fn = lua.create_function(|lua, (rows, options): (Table, Option<Table>)| {
+        let csv_options = parse_options(options.as_ref())?;
+        let columns: Option<Vec<String>> = match &options {
+            Some(opts) => match opts.get::<_, LuaValue>("headers")? {
+                LuaValue::Table(names) => {
+                    Some(names.sequence_values().collect::<mlua::Result<_>>()?)
+                }
+                _ => None,
+            },
+            None => None,
+        };
+        let records = rows_to_records(lua, &rows, columns)?;
+        stringify(&records, &csv_options).map_err(mlua::Error::external)
+    })?;
+    table.set("stringify", stringify_fn)?;
+    Ok(())
+}
+
+fn register_read_file(lua: &Lua, table: &Table) -> mlua::Result<()> {
+    let read_file_fn = lua.create_function(
+        |lua, (path, callback, options): (String, Function, Option<Table>)| {
+            check_file_access(lua, &path, "read").map_err(mlua::Error::external)?;
+            let options = parse_options(options.as_ref())?;
+            let mut reader = open_file(&path, &options).map_err(mlua::Error::external)?;
+            let headers = if options.headers {
+                Some(headers(&mut reader).map_err(mlua::Error::external)?)
+            } else {
+                None
+            };
+
+            let mut count = 0;
+            for record in reader.records() {
+                let record = record
+                    .map_err(|e| mlua::Error::external(super::CsvError::from(e)))?
+                    .iter()
+                    .map(str::to_string)
+                    .collect();
+                count += 1;
+                let row = row_to_table(lua, record, headers.as_deref())?;
+                if let LuaValue::Boolean(false) = callback.call::<_, LuaValue>((row, count))? {
+                    break;
+                }
+            }
+            Ok(count)
+        },
+    )?;
+    table.set("readFile", read_file_fn)?;
+    Ok(())
+}
+
+fn parse_options(options: Option<&Table>) -> mlua::Result<CsvOptions> {
+    let mut csv_options = CsvOptions::default();
+    if let Some(opts) = options {
+        if let Some(delimiter) = opts.get::<_, Option<String>>("delimiter")? {
+            csv_options.delimiter =
+                CsvOptions::single_byte("delimiter", &delimiter).map_err(mlua::Error::external)?;
+        }
+        if let Some(quote) = opts.get::<_, Option<String>>("quote")? {
+            csv_options.quote =
+                CsvOptions::single_byte("quote", &quote).map_err(mlua::Error::external)?;
+        }
+        if let LuaValue::Boolean(headers) = opts.get::<_, LuaValue>("headers")? {
+            csv_options.headers = headers;
+        }
+    }
+    Ok(csv_options)
+}
+
+fn row_to_table<'lua>(
+    lua: &'lua Lua,
+    record: Vec<String>,
+    headers: Option<&[String]>,
+) -> mlua::Result<Table<'lua>> {
+    match headers {
+        Some(headers) => {
+            let row = lua.create_table()?;
+            for (name, field) in headers.iter().zip(record) {
+                row.set(name.as_str(), field)?;
+            }
+            Ok(row)
+        }
+        None => lua.create_sequence_from(record),
+    }
+}
+
+/// Convert an array of rows to records. Rows that are key/value tables are laid
+/// out by `columns` (or their sorted keys) under a header row.
+fn rows_to_records(
+    lua: &Lua,
+    rows: &Table,
+    columns: Option<Vec<String>>,
+) -> mlua::Result<Vec<Vec<String>>> {
+    let rows: Vec<Table> = rows
+        .clone()
+        .sequence_values()
+        .collect::<mlua::Result<_>>()?;
+    let is_record = |row: &Table| {
+        row.raw_len() == 0 && row.clone().pairs::<LuaValue, LuaValue>().next().is_some()
+    };
+
+    let columns = match columns {
+        Some(columns) => Some(columns),
+        None if rows.iter().any(is_record) => {
+            let mut keys = BTreeSet::new();
+            for row in rows.iter().filter(|row| is_record(row)) {
+                for pair in row.clone().pairs::<String, LuaValue>() {
+                    keys.insert(pair?.0);
+                }
+            }
+            Some(keys.into_iter().collect())
+        }
+        None => None,
+    };
+
+    let mut records = Vec::with_capacity(rows.len() + 1);
+    if let Some(columns) = &columns {
+        records.push(columns.clone());
+    }
+    for row in &rows {
+        let record = match &columns {
+            Some(columns) if is_record(row) => columns
+                .iter()
+                .map(|name| field_to_string(lua, row.get(name.as_str())?))
+                .collect::<mlua::Result<_>>()?,
+            _ => row
+                .clone()
+                .sequence_values::<LuaValue>()
+                .map(|value| field_to_string(lua, value?))
+                .collect::<mlua::Result<_>>()?,
+        };
+        records.push(record);
+    }
+    Ok(records)
+}
+
+fn field_to_string(lua: &Lua, value: LuaValue) -> mlua::Result<String> {
+    match value {
+        LuaValue::Nil => Ok(String::new()),
+        LuaValue::String(s) => Ok(s.to_str()?.to_string()),
+        other => {
+            let tostring: Function = lua.globals().get("tostring")?;
+            tostring.call(other)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lua_with_csv() -> Lua {
+        let lua = Lua::new();
+        let csv = create_csv_module(&lua).unwrap();
+        lua.globals().set("csv", csv).unwrap();
+        lua
+    }
+
+    #[test]
+    fn test_create_csv_module() {
+        let lua = Lua::new();
+        let csv = create_csv_module(&lua).unwrap();
+        assert!(csv.contains_key("parse").unwrap());
+        assert!(csv.contains_key("stringify").unwrap());
+        assert!(csv.contains_key("readFile").unwrap());
+    }
+
+    #[test]
+    fn test_parse_quoted_fields_with_delimiters() {
+        let lua = lua_with_csv();
+        let (first, second, rows): (String, String, usize) = lua
+            .load(
+                r#"
+                local rows = csv.parse('"Doe, Jane","a ""quoted"" word"\nx,y\n')
+                return rows[1][1], rows[1][2], #rows
+            "#,
+            )
+            .eval()
+            .unwrap();
+        assert_eq!(first, "Doe, Jane");
+        assert_eq!(second, "a \"quoted\" word");
+        assert_eq!(rows, 2);
+    }
+
+    #[test]
+    fn test_parse_with_headers_and_stringify_records() {
+        let lua = lua_with_csv();
+        let (name, text): (String, String) = lua
+            .load(
+                r#"
+                local people = csv.parse("id;name\n1;Ada\n2;Grace\n", {headers = true, delimiter = ";"})
+                return people[2].name, csv.stringify(people)
+            "#,
+            )
+            .eval()
+            .unwrap();
+        assert_eq!(name, "Grace");
+        assert_eq!(text, "id,name\n1,Ada\n2,Grace\n");
+    }
+
+    #[test]
+    fn test_read_file_streams_rows() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data.csv");
+        std::fs::write(&path, "id,name\n1,Ada\n2,Grace\n3,Linus\n").unwrap();
+
+        let lua = lua_with_csv();
+        lua.globals().set("PATH", path.to_str().unwrap()).unwrap();
+        let (names, count): (String, i64) = lua
+            .load(
+                r#"
+                local names = {}
+                local count = csv.readFile(PATH, function(row, index)
+                    table.insert(names, index .. ":" .. row.name)
+                    if index == 2 then return false end
+                end, {headers = true})
+                return table.concat(names, ","), count
+            "#,
+            )
+            .eval()
+            .unwrap();
+        assert_eq!(names, "1:Ada,2:Grace");
+        assert_eq!(count, 2);
+    }
+}
@@ -0,0 +1,74 @@
+pub mod error;
+pub mod lua_bindings;
+pub mod operations;
+
+use serde_json::{json, Value as JsonValue};
+
+use super::BuiltinModule;
+use crate::error::HypeError;
+
+pub use error::CsvError;
+pub use lua_bindings::create_csv_module;
+
+pub struct CsvModule;
+
+impl CsvModule {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for CsvModule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BuiltinModule for CsvModule {
+    fn name(&self) -> &str {
+        "csv"
+    }
+
+    fn exports(&self) -> std::result::Result<JsonValue, HypeError> {
+        Ok(json!({
+            "__id": "csv",
+            "__desc": "CSV parsing and serialization",
+            "parse": {
+                "__fn": "parse",
+                "__desc": "Parse CSV text into row arrays, or objects with {headers=true}",
+                "__signature": "parse(str: string, options?: {delimiter?: string, quote?: string, headers?: boolean}) -> table[]"
+            },
+            "stringify": {
+                "__fn": "stringify",
+                "__desc": "Serialize rows (arrays or objects) to CSV text",
+                "__signature": "stringify(rows: table[], options?: {delimiter?: string, quote?: string, headers?: string[]}) -> string"
+            },
+            "readFile": {
+                "__fn": "readFile",
+                "__desc": "Stream a CSV file row by row; return false from the callback to stop",
+                "__signature": "readFile(path: string, callback: function(row: table, index: number), options?: table) -> number"
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_csv_module_name() {
+        let module = CsvModule::new();
+        assert_eq!(module.name(), "csv");
+    }
+
+    #[test]
+    fn test_csv_module_exports() {
+        let module = CsvModule::new();
+        let exports = module.exports().unwrap();
+        assert!(exports.is_object());
+        assert!(exports.get("parse").is_some());
+        assert!(exports.get("stringify").is_some());
+        assert!(exports.get("readFile").is_some());
+    }
+}
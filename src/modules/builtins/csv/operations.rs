@@ -0,0 +1,168 @@
+use super::error::CsvError;
+use std::fs::File;
+
+pub type Result<T> = std::result::Result<T, CsvError>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvOptions {
+    pub delimiter: u8,
+    pub quote: u8,
+    pub headers: bool,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: b',',
+            quote: b'"',
+            headers: false,
+        }
+    }
+}
+
+impl CsvOptions {
+    /// Convert a one-character option such as `delimiter = ";"` to its byte.
+    pub fn single_byte(name: &str, value: &str) -> Result<u8> {
+        match value.as_bytes() {
+            [byte] => Ok(*byte),
+            _ => Err(CsvError::InvalidOption(format!(
+                "{} must be a single ASCII character, got '{}'",
+                name, value
+            ))),
+        }
+    }
+
+    fn reader_builder(&self) -> csv::ReaderBuilder {
+        let mut builder = csv::ReaderBuilder::new();
+        builder
+            .delimiter(self.delimiter)
+            .quote(self.quote)
+            .has_headers(self.headers)
+            .flexible(!self.headers);
+        builder
+    }
+}
+
+/// Parsed CSV data. `headers` is set when [`CsvOptions::headers`] is enabled.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct CsvData {
+    pub headers: Option<Vec<String>>,
+    pub rows: Vec<Vec<String>>,
+}
+
+pub fn parse(input: &str, options: &CsvOptions) -> Result<CsvData> {
+    let mut reader = options.reader_builder().from_reader(input.as_bytes());
+    read_all(&mut reader, options)
+}
+
+/// Open a file for row-at-a-time reading with [`csv::Reader::records`].
+pub fn open_file(path: &str, options: &CsvOptions) -> Result<csv::Reader<File>> {
+    let file = File::open(path).map_err(|e| CsvError::IoError(format!("{}: {}", path, e)))?;
+    Ok(options.reader_builder().from_reader(file))
+}
+
+pub fn headers<R: std::io::Read>(reader: &mut csv::Reader<R>) -> Result<Vec<String>> {
+    Ok(reader.headers()?.iter().map(str::to_string).collect())
+}
+
+fn read_all<R: std::io::Read>(
+    reader: &mut csv::Reader<R>,
+    options: &CsvOptions,
+) -> Result<CsvData> {
+    let headers = if options.headers {
+        Some(headers(reader)?)
+    } else {
+        None
+    };
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        rows.push(record?.iter().map(str::to_string).collect());
+    }
+    Ok(CsvData { headers, rows })
+}
+
+/// Write rows as CSV, quoting fields that contain the delimiter, quotes or newlines.
+pub fn stringify(rows: &[Vec<String>], options: &CsvOptions) -> Result<String> {
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(options.delimiter)
+        .quote(options.quote)
+        .flexible(true)
+        .from_writer(Vec::new());
+    for row in rows {
+        writer
+            .write_record(row)
+            .map_err(|e| CsvError::WriteError(e.to_string()))?;
+    }
+    let bytes = writer
+        .into_inner()
+        .map_err(|e| CsvError::WriteError(e.to_string()))?;
+    String::from_utf8(bytes).map_err(|e| CsvError::WriteError(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(fields: &[&str]) -> Vec<String> {
+        fields.iter().map(|f| f.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_quoted_fields() {
+        let input = "name,notes\n\"Doe, Jane\",\"said \"\"hi\"\"\"\nBob,\"line one\nline two\"\n";
+        let data = parse(input, &CsvOptions::default()).unwrap();
+        assert_eq!(data.headers, None);
+        assert_eq!(
+            data.rows,
+            vec![
+                row(&["name", "notes"]),
+                row(&["Doe, Jane", "said \"hi\""]),
+                row(&["Bob", "line one\nline two"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_with_headers() {
+        let options = CsvOptions {
+            headers: true,
+            ..CsvOptions::default()
+        };
+        let data = parse("id,name\n1,Ada\n2,Grace\n", &options).unwrap();
+        assert_eq!(data.headers, Some(row(&["id", "name"])));
+        assert_eq!(data.rows, vec![row(&["1", "Ada"]), row(&["2", "Grace"])]);
+
+        let err = parse("id,name\n1,Ada,extra\n", &options).unwrap_err();
+        assert!(matches!(err, CsvError::ParseError(_)), "{}", err);
+    }
+
+    #[test]
+    fn test_custom_delimiter_and_quote() {
+        let options = CsvOptions {
+            delimiter: b';',
+            quote: b'\'',
+            headers: false,
+        };
+        let data = parse("a;'b;c'\n", &options).unwrap();
+        assert_eq!(data.rows, vec![row(&["a", "b;c"])]);
+        assert_eq!(stringify(&data.rows, &options).unwrap(), "a;'b;c'\n");
+    }
+
+    #[test]
+    fn test_stringify_round_trip() {
+        let rows = vec![
+            row(&["id", "comment"]),
+            row(&["1", "has, comma"]),
+            row(&["2", "has \"quotes\"\nand newline"]),
+        ];
+        let text = stringify(&rows, &CsvOptions::default()).unwrap();
+        assert_eq!(parse(&text, &CsvOptions::default()).unwrap().rows, rows);
+    }
+
+    #[test]
+    fn test_single_byte_option() {
+        assert_eq!(CsvOptions::single_byte("delimiter", "\t").unwrap(), b'\t');
+        assert!(CsvOptions::single_byte("delimiter", "::").is_err());
+        assert!(CsvOptions::single_byte("quote", "").is_err());
+    }
+}
@@ -0,0 +1,48 @@
+use crate::error::StructuredError;
+use std::fmt;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum DiffError {
+    /// The patch text is not a well-formed unified diff.
+    InvalidPatch { line: usize, message: String },
+    /// A hunk's context or removed lines do not match the original text.
+    Mismatch { hunk: usize, line: usize },
+}
+
+impl fmt::Display for DiffError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DiffError::InvalidPatch { line, message } => {
+                write!(f, "Invalid patch at line {}: {}", line, message)
+            }
+            DiffError::Mismatch { hunk, line } => write!(
+                f,
+                "Hunk {} does not apply: original text differs at line {}",
+                hunk, line
+            ),
+        }
+    }
+}
+
+impl std::error::Error for DiffError {}
+
+impl DiffError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            DiffError::InvalidPatch { .. } => "ERR_INVALID_PATCH",
+            DiffError::Mismatch { .. } => "ERR_PATCH_MISMATCH",
+        }
+    }
+}
+
+impl From<DiffError> for StructuredError {
+    fn from(err: DiffError) -> Self {
+        StructuredError::new("diff", err.code(), err.to_string())
+    }
+}
+
+impl From<DiffError> for crate::error::HypeError {
+    fn from(err: DiffError) -> Self {
+        crate::error::HypeError::Execution(err.to_string())
+    }
+}
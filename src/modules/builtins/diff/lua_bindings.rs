@@ -0,0 +1,84 @@
+use super::operations::{apply_patch, char_changes, unified_diff, DEFAULT_CONTEXT};
+use crate::lua::error::{structured_error, wrap_module_errors};
+use mlua::{Lua, Result as LuaResult, Table};
+
+pub fn create_diff_module(lua: &Lua) -> LuaResult<Table<'_>> {
+    let diff = lua.create_table()?;
+
+    let lines_fn =
+        lua.create_function(|_, (old, new, options): (String, String, Option<Table>)| {
+            let mut context = DEFAULT_CONTEXT;
+            let mut header = None;
+            if let Some(options) = options {
+                context = options
+                    .get::<_, Option<usize>>("context")?
+                    .unwrap_or(DEFAULT_CONTEXT);
+                let from: Option<String> = options.get("fromFile")?;
+                let to: Option<String> = options.get("toFile")?;
+                if from.is_some() || to.is_some() {
+                    header = Some((
+                        from.unwrap_or_else(|| "a".to_string()),
+                        to.unwrap_or_else(|| "b".to_string()),
+                    ));
+                }
+            }
+            let header = header
+                .as_ref()
+                .map(|(from, to)| (from.as_str(), to.as_str()));
+            Ok(unified_diff(&old, &new, context, header))
+        })?;
+    diff.set("lines", lines_fn)?;
+
+    let chars_fn = lua.create_function(|lua, (old, new): (String, String)| {
+        let result = lua.create_table()?;
+        for (i, change) in char_changes(&old, &new).into_iter().enumerate() {
+            let entry = lua.create_table()?;
+            entry.set("op", change.kind.as_str())?;
+            entry.set("value", change.value)?;
+            result.set(i + 1, entry)?;
+        }
+        Ok(result)
+    })?;
+    diff.set("chars", chars_fn)?;
+
+    let apply_fn = lua.create_function(|_, (original, patch): (String, String)| {
+        apply_patch(&original, &patch).map_err(structured_error)
+    })?;
+    diff.set("apply", apply_fn)?;
+
+    wrap_module_errors(lua, &diff)?;
+    Ok(diff)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_module_functions() {
+        let lua = Lua::new();
+        let diff = create_diff_module(&lua).unwrap();
+        assert!(diff.contains_key("lines").unwrap());
+        assert!(diff.contains_key("chars").unwrap());
+        assert!(diff.contains_key("apply").unwrap());
+    }
+
+    #[test]
+    fn test_apply_error_code() {
+        let lua = Lua::new();
+        lua.globals()
+            .set("diff", create_diff_module(&lua).unwrap())
+            .unwrap();
+        let code: String = lua
+            .load(
+                r#"
+                local patch = diff.lines("a\n", "b\n")
+                local ok, err = pcall(diff.apply, "c\n", patch)
+                return err.code
+            "#,
+            )
+            .eval()
+            .unwrap();
+        assert_eq!(code, "ERR_PATCH_MISMATCH");
+    }
+}
@@ -0,0 +1,73 @@
+pub mod error;
+pub mod lua_bindings;
+pub mod operations;
+
+use serde_json::{json, Value as JsonValue};
+
+use super::BuiltinModule;
+use crate::error::HypeError;
+
+pub use lua_bindings::create_diff_module;
+
+pub struct DiffModule;
+
+impl DiffModule {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for DiffModule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BuiltinModule for DiffModule {
+    fn name(&self) -> &str {
+        "diff"
+    }
+
+    fn exports(&self) -> std::result::Result<JsonValue, HypeError> {
+        Ok(json!({
+            "__id": "diff",
+            "__desc": "Compute unified and character-level text diffs and apply patches",
+            "lines": {
+                "__fn": "lines",
+                "__desc": "Line diff of two strings as a unified diff; empty when they are equal",
+                "__signature": "lines(old: string, new: string, options?: {context?: number, fromFile?: string, toFile?: string}) -> string"
+            },
+            "chars": {
+                "__fn": "chars",
+                "__desc": "Character diff of two strings as runs of kept, inserted and deleted text",
+                "__signature": "chars(old: string, new: string) -> {op: \"equal\"|\"insert\"|\"delete\", value: string}[]"
+            },
+            "apply": {
+                "__fn": "apply",
+                "__desc": "Apply a unified diff to a string",
+                "__signature": "apply(original: string, patch: string) -> string"
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_module_name() {
+        let module = DiffModule::new();
+        assert_eq!(module.name(), "diff");
+    }
+
+    #[test]
+    fn test_diff_module_exports() {
+        let module = DiffModule::new();
+        let exports = module.exports().unwrap();
+        assert_eq!(exports["__id"], "diff");
+        assert!(exports.get("lines").is_some());
+        assert!(exports.get("chars").is_some());
+        assert!(exports.get("apply").is_some());
+    }
+}
@@ -0,0 +1,287 @@
+use super::error::DiffError;
+use similar::{ChangeTag, TextDiff};
+
+/// Unchanged lines shown around each change when no context is given.
+pub const DEFAULT_CONTEXT: usize = 3;
+
+/// A unified diff turning `old` into `new`, empty when they are equal.
+/// `header` names the old and new files on the `---`/`+++` lines.
+pub fn unified_diff(old: &str, new: &str, context: usize, header: Option<(&str, &str)>) -> String {
+    let diff = TextDiff::from_lines(old, new);
+    let mut unified = diff.unified_diff();
+    unified.context_radius(context);
+    if let Some((old_name, new_name)) = header {
+        unified.header(old_name, new_name);
+    }
+    unified.to_string()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    Equal,
+    Insert,
+    Delete,
+}
+
+impl ChangeKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChangeKind::Equal => "equal",
+            ChangeKind::Insert => "insert",
+            ChangeKind::Delete => "delete",
+        }
+    }
+}
+
+/// A run of consecutive characters that were kept, inserted or deleted.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Change {
+    pub kind: ChangeKind,
+    pub value: String,
+}
+
+/// Character-level changes turning `old` into `new`, with adjacent characters
+/// of the same kind merged into one change.
+pub fn char_changes(old: &str, new: &str) -> Vec<Change> {
+    let diff = TextDiff::from_chars(old, new);
+    let mut changes: Vec<Change> = Vec::new();
+    for change in diff.iter_all_changes() {
+        let kind = match change.tag() {
+            ChangeTag::Equal => ChangeKind::Equal,
+            ChangeTag::Insert => ChangeKind::Insert,
+            ChangeTag::Delete => ChangeKind::Delete,
+        };
+        match changes.last_mut() {
+            Some(last) if last.kind == kind => last.value.push_str(change.value()),
+            _ => changes.push(Change {
+                kind,
+                value: change.value().to_string(),
+            }),
+        }
+    }
+    changes
+}
+
+struct HunkLine<'a> {
+    /// `' '` for context, `'-'` for a removed line, `'+'` for an added one.
+    tag: char,
+    text: &'a str,
+    newline: bool,
+}
+
+struct Hunk<'a> {
+    old_start: usize,
+    old_count: usize,
+    lines: Vec<HunkLine<'a>>,
+}
+
+/// Apply a unified diff to `original`. Hunks must match the original text
+/// exactly at the line numbers they name; file headers and any other lines
+/// outside hunks are ignored.
+pub fn apply_patch(original: &str, patch: &str) -> Result<String, DiffError> {
+    let hunks = parse_patch(patch)?;
+    let old_lines: Vec<&str> = original.split_inclusive('\n').collect();
+    let mut out = String::with_capacity(original.len());
+    let mut pos = 0;
+
+    for (index, hunk) in hunks.iter().enumerate() {
+        let mismatch = |line: usize| DiffError::Mismatch {
+            hunk: index + 1,
+            line: line + 1,
+        };
+        // A hunk removing nothing names the line it inserts after
+        let start = if hunk.old_count == 0 {
+            hunk.old_start
+        } else {
+            hunk.old_start.saturating_sub(1)
+        };
+        if start < pos || start > old_lines.len() {
+            return Err(mismatch(start));
+        }
+        old_lines[pos..start]
+            .iter()
+            .for_each(|line| out.push_str(line));
+        pos = start;
+
+        for line in &hunk.lines {
+            if line.tag == '+' {
+                out.push_str(line.text);
+                if line.newline {
+                    out.push('\n');
+                }
+                continue;
+            }
+            let actual = old_lines.get(pos).ok_or_else(|| mismatch(pos))?;
+            if actual.strip_suffix('\n').unwrap_or(actual) != line.text {
+                return Err(mismatch(pos));
+            }
+            if line.tag == ' ' {
+                out.push_str(actual);
+            }
+            pos += 1;
+        }
+    }
+
+    old_lines[pos..].iter().for_each(|line| out.push_str(line));
+    Ok(out)
+}
+
+fn parse_patch(patch: &str) -> Result<Vec<Hunk<'_>>, DiffError> {
+    let mut lines: Vec<&str> = patch.split('\n').collect();
+    if patch.ends_with('\n') {
+        lines.pop();
+    }
+    let invalid = |line: usize, message: &str| DiffError::InvalidPatch {
+        line: line + 1,
+        message: message.to_string(),
+    };
+
+    let mut hunks = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        if !lines[i].starts_with("@@") {
+            i += 1;
+            continue;
+        }
+        let (old_start, old_count, new_count) =
+            parse_hunk_header(lines[i]).ok_or_else(|| invalid(i, "malformed hunk header"))?;
+        i += 1;
+
+        let mut body: Vec<HunkLine> = Vec::new();
+        let (mut old_seen, mut new_seen) = (0, 0);
+        loop {
+            // "\ No newline at end of file" belongs to the line before it
+            if lines.get(i).is_some_and(|line| line.starts_with('\\')) {
+                body.last_mut()
+                    .ok_or_else(|| invalid(i, "no-newline marker before any line"))?
+                    .newline = false;
+                i += 1;
+                continue;
+            }
+            if old_seen >= old_count && new_seen >= new_count {
+                break;
+            }
+            let text = *lines
+                .get(i)
+                .ok_or_else(|| invalid(i, "hunk is shorter than its header says"))?;
+            // Some tools strip the leading space from empty context lines
+            let (tag, text) = match text.chars().next() {
+                None => (' ', ""),
+                Some(tag @ (' ' | '-' | '+')) => (tag, &text[1..]),
+                Some(_) => return Err(invalid(i, "expected a line starting with ' ', '-' or '+'")),
+            };
+            if tag != '+' {
+                old_seen += 1;
+            }
+            if tag != '-' {
+                new_seen += 1;
+            }
+            if old_seen > old_count || new_seen > new_count {
+                return Err(invalid(i, "hunk is longer than its header says"));
+            }
+            body.push(HunkLine {
+                tag,
+                text,
+                newline: true,
+            });
+            i += 1;
+        }
+
+        hunks.push(Hunk {
+            old_start,
+            old_count,
+            lines: body,
+        });
+    }
+    Ok(hunks)
+}
+
+/// The old start, old count and new count of an `@@ -a,b +c,d @@` header.
+fn parse_hunk_header(line: &str) -> Option<(usize, usize, usize)> {
+    let ranges = line.strip_prefix("@@ -")?.split_once(" @@")?.0;
+    let (old, new) = ranges.split_once(" +")?;
+    let (old_start, old_count) = parse_range(old)?;
+    let (_, new_count) = parse_range(new)?;
+    Some((old_start, old_count, new_count))
+}
+
+/// A `start,count` range, where a missing count means one line.
+fn parse_range(range: &str) -> Option<(usize, usize)> {
+    match range.split_once(',') {
+        Some((start, count)) => Some((start.parse().ok()?, count.parse().ok()?)),
+        None => Some((range.parse().ok()?, 1)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unified_diff_marks_changed_lines() {
+        let diff = unified_diff(
+            "a\nb\nc\n",
+            "a\nB\nc\n",
+            DEFAULT_CONTEXT,
+            Some(("old", "new")),
+        );
+        assert_eq!(diff, "--- old\n+++ new\n@@ -1,3 +1,3 @@\n a\n-b\n+B\n c\n");
+        assert_eq!(unified_diff("same\n", "same\n", DEFAULT_CONTEXT, None), "");
+    }
+
+    #[test]
+    fn test_apply_round_trips() {
+        let cases = [
+            ("a\nb\nc\n", "a\nB\nc\nd\n"),
+            ("", "new\nfile\n"),
+            ("gone\n", ""),
+            ("no newline", "no newline\nat end"),
+            (
+                "1\n2\n3\n4\n5\n6\n7\n8\n9\n10\n",
+                "0\n1\n2\n3\n4\n5\n6\n7\n8\n9\n",
+            ),
+        ];
+        for (old, new) in cases {
+            let patch = unified_diff(old, new, 1, None);
+            assert_eq!(apply_patch(old, &patch).unwrap(), new, "{}", patch);
+        }
+    }
+
+    #[test]
+    fn test_apply_rejects_mismatched_and_malformed_patches() {
+        let patch = unified_diff("a\nb\n", "a\nc\n", DEFAULT_CONTEXT, None);
+        assert_eq!(
+            apply_patch("a\nx\n", &patch),
+            Err(DiffError::Mismatch { hunk: 1, line: 2 })
+        );
+        assert!(matches!(
+            apply_patch("a\n", "@@ -1,2 +1,2 @@\n a\n"),
+            Err(DiffError::InvalidPatch { line: 3, .. })
+        ));
+        assert!(matches!(
+            apply_patch("a\n", "@@ -x +1 @@\n"),
+            Err(DiffError::InvalidPatch { line: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn test_char_changes_merge_runs() {
+        let changes = char_changes("kitten", "sitting");
+        let text: Vec<(&str, &str)> = changes
+            .iter()
+            .map(|change| (change.kind.as_str(), change.value.as_str()))
+            .collect();
+        assert_eq!(
+            text,
+            vec![
+                ("delete", "k"),
+                ("insert", "s"),
+                ("equal", "itt"),
+                ("delete", "e"),
+                ("insert", "i"),
+                ("equal", "n"),
+                ("insert", "g"),
+            ]
+        );
+    }
+}
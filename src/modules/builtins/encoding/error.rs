@@ -0,0 +1,42 @@
+use crate::error::StructuredError;
+use std::fmt;
+
+#[derive(Debug)]
+pub enum EncodingError {
+    InvalidInput {
+        encoding: &'static str,
+        reason: String,
+    },
+}
+
+impl fmt::Display for EncodingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EncodingError::InvalidInput { encoding, reason } => {
+                write!(f, "Invalid {} input: {}", encoding, reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for EncodingError {}
+
+impl EncodingError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            EncodingError::InvalidInput { .. } => "ERR_INVALID_INPUT",
+        }
+    }
+}
+
+impl From<EncodingError> for StructuredError {
+    fn from(err: EncodingError) -> Self {
+        StructuredError::new("encoding", err.code(), err.to_string())
+    }
+}
+
+impl From<EncodingError> for crate::error::HypeError {
+    fn from(err: EncodingError) -> Self {
+        crate::error::HypeError::Execution(err.to_string())
+    }
+}
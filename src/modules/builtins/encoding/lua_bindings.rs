@@ -0,0 +1,64 @@
+use super::operations::*;
+use crate::lua::error::{structured_error, wrap_module_errors};
+use mlua::{Lua, Result as LuaResult, String as LuaString, Table};
+
+type Encoder = fn(&[u8]) -> String;
+type Decoder = fn(&str) -> Result<Vec<u8>>;
+
+pub fn create_encoding_module(lua: &Lua) -> LuaResult<Table<'_>> {
+    let encoding = lua.create_table()?;
+
+    let codecs: [(&str, Encoder, Decoder); 5] = [
+        ("base32", base32_encode, base32_decode),
+        ("base64", base64_encode, base64_decode),
+        ("base64Url", base64_url_encode, base64_url_decode),
+        ("hex", hex_encode, hex_decode),
+        ("percent", percent_encode_bytes, percent_decode),
+    ];
+    for (name, encode, decode) in codecs {
+        let encode_fn =
+            lua.create_function(move |_, data: LuaString| Ok(encode(data.as_bytes())))?;
+        encoding.set(format!("{}Encode", name), encode_fn)?;
+
+        let decode_fn = lua.create_function(move |lua, input: LuaString| {
+            let bytes = decode(input.to_str()?).map_err(structured_error)?;
+            lua.create_string(&bytes)
+        })?;
+        encoding.set(format!("{}Decode", name), decode_fn)?;
+    }
+
+    wrap_module_errors(lua, &encoding)?;
+    Ok(encoding)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_encoding_module() {
+        let lua = Lua::new();
+        let encoding = create_encoding_module(&lua).unwrap();
+        for name in ["base32", "base64", "base64Url", "hex", "percent"] {
+            assert!(encoding.contains_key(format!("{}Encode", name)).unwrap());
+            assert!(encoding.contains_key(format!("{}Decode", name)).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_errors_are_structured() {
+        let lua = Lua::new();
+        lua.globals()
+            .set("encoding", create_encoding_module(&lua).unwrap())
+            .unwrap();
+        lua.load(
+            r#"
+local ok, err = pcall(encoding.base32Decode, "not base32!")
+assert(not ok and err.kind == "encoding" and err.code == "ERR_INVALID_INPUT", tostring(err))
+assert(tostring(err):find("base32"), tostring(err))
+"#,
+        )
+        .exec()
+        .unwrap();
+    }
+}
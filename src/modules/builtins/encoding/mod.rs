@@ -0,0 +1,112 @@
+pub mod error;
+pub mod lua_bindings;
+pub mod operations;
+
+use serde_json::{json, Value as JsonValue};
+
+use super::BuiltinModule;
+use crate::error::HypeError;
+
+pub use lua_bindings::create_encoding_module;
+
+pub struct EncodingModule;
+
+impl EncodingModule {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for EncodingModule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BuiltinModule for EncodingModule {
+    fn name(&self) -> &str {
+        "encoding"
+    }
+
+    fn exports(&self) -> std::result::Result<JsonValue, HypeError> {
+        Ok(json!({
+            "__id": "encoding",
+            "__desc": "Binary-safe base32, base64, URL-safe base64, hex and percent encodings",
+            "base32Encode": {
+                "__fn": "base32Encode",
+                "__desc": "RFC 4648 base32 with = padding",
+                "__signature": "base32Encode(data: string) -> string"
+            },
+            "base32Decode": {
+                "__fn": "base32Decode",
+                "__desc": "Decode base32 (case-insensitive, padding optional)",
+                "__signature": "base32Decode(input: string) -> string"
+            },
+            "base64Encode": {
+                "__fn": "base64Encode",
+                "__desc": "Standard base64 with = padding",
+                "__signature": "base64Encode(data: string) -> string"
+            },
+            "base64Decode": {
+                "__fn": "base64Decode",
+                "__desc": "Decode standard base64",
+                "__signature": "base64Decode(input: string) -> string"
+            },
+            "base64UrlEncode": {
+                "__fn": "base64UrlEncode",
+                "__desc": "URL-safe base64 (- and _) without padding",
+                "__signature": "base64UrlEncode(data: string) -> string"
+            },
+            "base64UrlDecode": {
+                "__fn": "base64UrlDecode",
+                "__desc": "Decode URL-safe base64, with or without padding",
+                "__signature": "base64UrlDecode(input: string) -> string"
+            },
+            "hexEncode": {
+                "__fn": "hexEncode",
+                "__desc": "Lowercase hex",
+                "__signature": "hexEncode(data: string) -> string"
+            },
+            "hexDecode": {
+                "__fn": "hexDecode",
+                "__desc": "Decode hex in either case",
+                "__signature": "hexDecode(input: string) -> string"
+            },
+            "percentEncode": {
+                "__fn": "percentEncode",
+                "__desc": "Escape all but RFC 3986 unreserved characters as %XX",
+                "__signature": "percentEncode(data: string) -> string"
+            },
+            "percentDecode": {
+                "__fn": "percentDecode",
+                "__desc": "Decode %XX escapes",
+                "__signature": "percentDecode(input: string) -> string"
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encoding_module_name() {
+        let module = EncodingModule::new();
+        assert_eq!(module.name(), "encoding");
+    }
+
+    #[test]
+    fn test_encoding_module_exports() {
+        let module = EncodingModule::new();
+        let exports = module.exports().unwrap();
+        for name in [
+            "base32Encode",
+            "base32Decode",
+            "base64UrlEncode",
+            "hexDecode",
+        ] {
+            assert!(exports.get(name).is_some(), "{}", name);
+        }
+    }
+}
@@ -0,0 +1,198 @@
+use super::error::EncodingError;
+use crate::modules::builtins::crypto::operations as crypto;
+use base64::alphabet;
+use base64::engine::general_purpose::{GeneralPurpose, GeneralPurposeConfig};
+use base64::engine::DecodePaddingMode;
+use base64::Engine as _;
+use percent_encoding::{percent_encode, AsciiSet, NON_ALPHANUMERIC};
+
+pub type Result<T> = std::result::Result<T, EncodingError>;
+
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// URL-safe base64 as used in JWTs: `-` and `_` instead of `+` and `/`, no
+/// padding on output, padding optional on input.
+const BASE64_URL: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::URL_SAFE,
+    GeneralPurposeConfig::new()
+        .with_encode_padding(false)
+        .with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+/// Everything but RFC 3986 unreserved characters is escaped.
+const PERCENT_RESERVED: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+fn invalid(encoding: &'static str, reason: impl ToString) -> EncodingError {
+    EncodingError::InvalidInput {
+        encoding,
+        reason: reason.to_string(),
+    }
+}
+
+/// RFC 4648 base32 with `=` padding.
+pub fn base32_encode(data: &[u8]) -> String {
+    let mut output = String::with_capacity((data.len() + 4) / 5 * 8);
+    for chunk in data.chunks(5) {
+        let mut block = [0u8; 5];
+        block[..chunk.len()].copy_from_slice(chunk);
+        let bits = block.iter().fold(0u64, |acc, &b| (acc << 8) | b as u64);
+
+        // 8 bits per input byte, 5 per output character, rounded up
+        let chars = (chunk.len() * 8 + 4) / 5;
+        for i in 0..8 {
+            if i < chars {
+                let index = (bits >> (35 - i * 5)) & 0x1f;
+                output.push(BASE32_ALPHABET[index as usize] as char);
+            } else {
+                output.push('=');
+            }
+        }
+    }
+    output
+}
+
+/// Decode RFC 4648 base32. Lowercase letters are accepted and the trailing
+/// `=` padding is optional.
+pub fn base32_decode(input: &str) -> Result<Vec<u8>> {
+    let trimmed = input.trim_end_matches('=');
+    if input.len() != trimmed.len() && input.len() % 8 != 0 {
+        return Err(invalid(
+            "base32",
+            "padded input must be a multiple of 8 characters",
+        ));
+    }
+    // Lengths that no whole number of bytes encodes to
+    if matches!(trimmed.len() % 8, 1 | 3 | 6) {
+        return Err(invalid(
+            "base32",
+            format!("{} characters is not a valid length", trimmed.len()),
+        ));
+    }
+
+    let mut output = Vec::with_capacity(trimmed.len() * 5 / 8);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for (position, c) in trimmed.chars().enumerate() {
+        let value = BASE32_ALPHABET
+            .iter()
+            .position(|&a| a as char == c.to_ascii_uppercase())
+            .ok_or_else(|| {
+                invalid(
+                    "base32",
+                    format!("unexpected character '{}' at position {}", c, position),
+                )
+            })?;
+        buffer = (buffer << 5) | value as u32;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            output.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    Ok(output)
+}
+
+pub fn base64_encode(data: &[u8]) -> String {
+    crypto::base64_encode(data)
+}
+
+pub fn base64_decode(input: &str) -> Result<Vec<u8>> {
+    crypto::base64_decode(input).map_err(|e| invalid("base64", e))
+}
+
+pub fn base64_url_encode(data: &[u8]) -> String {
+    BASE64_URL.encode(data)
+}
+
+pub fn base64_url_decode(input: &str) -> Result<Vec<u8>> {
+    BASE64_URL
+        .decode(input)
+        .map_err(|e| invalid("base64url", e))
+}
+
+pub fn hex_encode(data: &[u8]) -> String {
+    crypto::hex_encode(data)
+}
+
+/// Decode hex in either case.
+pub fn hex_decode(input: &str) -> Result<Vec<u8>> {
+    hex::decode(input).map_err(|e| invalid("hex", e))
+}
+
+/// Percent-encode every byte outside the RFC 3986 unreserved set. Unlike
+/// `querystring.escape`, spaces become `%20` rather than `+`.
+pub fn percent_encode_bytes(data: &[u8]) -> String {
+    percent_encode(data, PERCENT_RESERVED).to_string()
+}
+
+/// Decode `%XX` escapes; other characters pass through unchanged.
+pub fn percent_decode(input: &str) -> Result<Vec<u8>> {
+    let bytes = input.as_bytes();
+    if let Some(position) = bytes.iter().enumerate().position(|(i, &b)| {
+        b == b'%'
+            && !bytes
+                .get(i + 1..i + 3)
+                .is_some_and(|pair| pair.iter().all(u8::is_ascii_hexdigit))
+    }) {
+        return Err(invalid(
+            "percent",
+            format!("incomplete escape at position {}", position),
+        ));
+    }
+    Ok(percent_encoding::percent_decode(bytes).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base32_rfc4648_vectors() {
+        let vectors = [
+            ("", ""),
+            ("f", "MY======"),
+            ("fo", "MZXQ===="),
+            ("foo", "MZXW6==="),
+            ("foob", "MZXW6YQ="),
+            ("fooba", "MZXW6YTB"),
+            ("foobar", "MZXW6YTBOI======"),
+        ];
+        for (plain, encoded) in vectors {
+            assert_eq!(base32_encode(plain.as_bytes()), encoded);
+            assert_eq!(base32_decode(encoded).unwrap(), plain.as_bytes());
+        }
+        assert_eq!(base32_decode("mzxw6ytboi").unwrap(), b"foobar");
+    }
+
+    #[test]
+    fn test_base32_rejects_invalid_input() {
+        assert!(base32_decode("MZXW1===").is_err());
+        assert!(base32_decode("MZX").is_err());
+        assert!(base32_decode("MZXW6YQ").is_ok());
+        assert!(base32_decode("MZXW6YQ==").is_err());
+    }
+
+    #[test]
+    fn test_base64_url_alphabet() {
+        let data = [0xfb, 0xff, 0xbf];
+        assert_eq!(base64_encode(&data), "+/+/");
+        assert_eq!(base64_url_encode(&data), "-_-_");
+        assert_eq!(base64_url_encode(b"hi"), "aGk");
+        assert_eq!(base64_url_decode("aGk=").unwrap(), b"hi");
+        assert!(base64_url_decode("+/+/").is_err());
+    }
+
+    #[test]
+    fn test_percent_roundtrip() {
+        let encoded = percent_encode_bytes(b"a b/c~\xff");
+        assert_eq!(encoded, "a%20b%2Fc~%FF");
+        assert_eq!(percent_decode(&encoded).unwrap(), b"a b/c~\xff");
+        assert!(percent_decode("100%").is_err());
+        assert!(percent_decode("%zz").is_err());
+    }
+}
@@ -1,3 +1,4 @@
+use crate::error::{io_error_code, StructuredError};
 use std::fmt;
 
 #[derive(Debug)]
@@ -7,6 +8,8 @@ pub enum FsError {
     PermissionDenied(String),
     NotFound(String),
     AlreadyExists(String),
+    NotADirectory(String),
+    IsADirectory(String),
     InvalidOperation(String),
 }
 
@@ -18,6 +21,8 @@ impl fmt::Display for FsError {
             FsError::PermissionDenied(msg) => write!(f, "Permission denied: {}", msg),
             FsError::NotFound(msg) => write!(f, "Not found: {}", msg),
             FsError::AlreadyExists(msg) => write!(f, "Already exists: {}", msg),
+            FsError::NotADirectory(msg) => write!(f, "Not a directory: {}", msg),
+            FsError::IsADirectory(msg) => write!(f, "Is a directory: {}", msg),
             FsError::InvalidOperation(msg) => write!(f, "Invalid operation: {}", msg),
         }
     }
@@ -25,6 +30,36 @@ impl fmt::Display for FsError {
 
 impl std::error::Error for FsError {}
 
+impl FsError {
+    /// The POSIX errno name scripts see as `err.code`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            FsError::IoError(e) => io_error_code(e.kind()),
+            FsError::InvalidPath(_) => "EINVAL",
+            FsError::PermissionDenied(_) => "EACCES",
+            FsError::NotFound(_) => "ENOENT",
+            FsError::AlreadyExists(_) => "EEXIST",
+            FsError::NotADirectory(_) => "ENOTDIR",
+            FsError::IsADirectory(_) => "EISDIR",
+            FsError::InvalidOperation(_) => "EINVAL",
+        }
+    }
+}
+
+impl From<FsError> for StructuredError {
+    fn from(err: FsError) -> Self {
+        StructuredError::new("fs", err.code(), err.to_string())
+    }
+}
+
+/// Lets operations that call back into Lua, such as `walk`, fail with either
+/// a Lua error or a structured fs error.
+impl From<FsError> for mlua::Error {
+    fn from(err: FsError) -> Self {
+        crate::lua::error::structured_error(err)
+    }
+}
+
 impl From<std::io::Error> for FsError {
     fn from(err: std::io::Error) -> Self {
         use std::io::ErrorKind;
@@ -32,6 +67,8 @@ impl From<std::io::Error> for FsError {
             ErrorKind::NotFound => FsError::NotFound(err.to_string()),
             ErrorKind::PermissionDenied => FsError::PermissionDenied(err.to_string()),
             ErrorKind::AlreadyExists => FsError::AlreadyExists(err.to_string()),
+            ErrorKind::NotADirectory => FsError::NotADirectory(err.to_string()),
+            ErrorKind::IsADirectory => FsError::IsADirectory(err.to_string()),
             _ => FsError::IoError(err),
         }
     }
@@ -1,24 +1,45 @@
 use super::operations::*;
-use mlua::{Lua, Table};
+use super::stream::WriteStream;
+use super::watch::{FileWatcher, WatchEvent, WatchEventKind, DEFAULT_DEBOUNCE};
+use crate::lua::error::{structured_error, wrap_module_errors};
+use crate::lua::security::check_file_access;
+use crate::modules::builtins::json::lua_bindings::json_to_lua;
+use crate::modules::builtins::json::operations::decode;
+use crate::modules::builtins::os::operations::DEFAULT_TEMP_PREFIX;
+use mlua::{
+    AnyUserData, Function, Lua, RegistryKey, Table, UserData, UserDataFields, UserDataMethods,
+    Value, Variadic,
+};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
 pub fn create_fs_module(lua: &Lua) -> mlua::Result<Table> {
     let fs_table = lua.create_table()?;
 
     register_read_file_sync(lua, &fs_table)?;
     register_write_file_sync(lua, &fs_table)?;
+    register_create_write_stream(lua, &fs_table)?;
     register_exists_sync(lua, &fs_table)?;
+    register_access(lua, &fs_table)?;
     register_stat_sync(lua, &fs_table)?;
     register_readdir_sync(lua, &fs_table)?;
+    register_walk(lua, &fs_table)?;
     register_unlink_sync(lua, &fs_table)?;
     register_mkdir_sync(lua, &fs_table)?;
     register_rmdir_sync(lua, &fs_table)?;
+    register_mkstemp(lua, &fs_table)?;
+    register_watch(lua, &fs_table)?;
+    register_watch_blocking(lua, &fs_table)?;
+    register_watch_json(lua, &fs_table)?;
 
+    wrap_module_errors(lua, &fs_table)?;
     Ok(fs_table)
 }
 
 fn register_read_file_sync(lua: &Lua, table: &Table) -> mlua::Result<()> {
-    let read_fn = lua.create_function(|_, path: String| {
-        let content = read_file_sync(&path).map_err(mlua::Error::external)?;
+    let read_fn = lua.create_function(|lua, path: String| {
+        guard_path(lua, &path, "read")?;
+        let content = read_file_sync(&path).map_err(structured_error)?;
         Ok(content)
     })?;
     table.set("readFileSync", read_fn)?;
@@ -26,23 +47,56 @@ fn register_read_file_sync(lua: &Lua, table: &Table) -> mlua::Result<()> {
 }
 
 fn register_write_file_sync(lua: &Lua, table: &Table) -> mlua::Result<()> {
-    let write_fn = lua.create_function(|_, (path, data): (String, String)| {
-        write_file_sync(&path, &data).map_err(mlua::Error::external)?;
+    let write_fn = lua.create_function(|lua, (path, data): (String, String)| {
+        guard_path(lua, &path, "write")?;
+        write_file_sync(&path, &data).map_err(structured_error)?;
         Ok(())
     })?;
     table.set("writeFileSync", write_fn)?;
     Ok(())
 }
 
+fn register_create_write_stream(lua: &Lua, table: &Table) -> mlua::Result<()> {
+    let create_fn = lua.create_function(|lua, (path, options): (String, Option<Table>)| {
+        guard_path(lua, &path, "write")?;
+        let append = match &options {
+            Some(options) => options.get::<_, Option<bool>>("append")?.unwrap_or(false),
+            None => false,
+        };
+        WriteStream::open(&path, append).map_err(structured_error)
+    })?;
+    table.set("createWriteStream", create_fn)?;
+    Ok(())
+}
+
 fn register_exists_sync(lua: &Lua, table: &Table) -> mlua::Result<()> {
-    let exists_fn = lua.create_function(|_, path: String| Ok(exists_sync(&path)))?;
+    let exists_fn = lua.create_function(|lua, path: String| {
+        Ok(check_file_access(lua, &path, "stat").is_ok() && exists_sync(&path))
+    })?;
     table.set("existsSync", exists_fn)?;
     Ok(())
 }
 
+fn register_access(lua: &Lua, table: &Table) -> mlua::Result<()> {
+    let access_fn = lua.create_function(|lua, (path, mode): (String, Option<u32>)| {
+        guard_path(lua, &path, "stat")?;
+        access_sync(&path, mode.unwrap_or(F_OK)).map_err(structured_error)
+    })?;
+    table.set("access", access_fn)?;
+
+    let constants = lua.create_table()?;
+    constants.set("F_OK", F_OK)?;
+    constants.set("R_OK", R_OK)?;
+    constants.set("W_OK", W_OK)?;
+    constants.set("X_OK", X_OK)?;
+    table.set("constants", constants)?;
+    Ok(())
+}
+
 fn register_stat_sync(lua: &Lua, table: &Table) -> mlua::Result<()> {
     let stat_fn = lua.create_function(move |lua, path: String| {
-        let stat = stat_sync(&path).map_err(mlua::Error::external)?;
+        guard_path(lua, &path, "stat")?;
+        let stat = stat_sync(&path).map_err(structured_error)?;
 
         let table = lua.create_table()?;
         table.set("size", stat.size)?;
@@ -58,23 +112,88 @@ fn register_stat_sync(lua: &Lua, table: &Table) -> mlua::Result<()> {
 }
 
 fn register_readdir_sync(lua: &Lua, table: &Table) -> mlua::Result<()> {
-    let readdir_fn = lua.create_function(move |lua, path: String| {
-        let files = readdir_sync(&path).map_err(mlua::Error::external)?;
+    let readdir_fn =
+        lua.create_function(move |lua, (path, options): (String, Option<Table>)| {
+            guard_path(lua, &path, "read")?;
+            let (with_file_types, recursive) = match options {
+                Some(opts) => (
+                    opts.get::<_, Option<bool>>("withFileTypes")?
+                        .unwrap_or(false),
+                    opts.get::<_, Option<bool>>("recursive")?.unwrap_or(false),
+                ),
+                None => (false, false),
+            };
 
-        let table = lua.create_table()?;
-        for (i, name) in files.iter().enumerate() {
-            table.set(i + 1, name.as_str())?;
-        }
+            let table = lua.create_table()?;
+            if recursive {
+                let mut count = 0;
+                walk(&path, |entry| {
+                    count += 1;
+                    if with_file_types {
+                        let entry_table = lua.create_table()?;
+                        entry_table.set("name", entry.relative_path.as_str())?;
+                        entry_table.set("isFile", entry.is_file)?;
+                        entry_table.set("isDirectory", entry.is_directory && !entry.is_symlink)?;
+                        entry_table.set("isSymlink", entry.is_symlink)?;
+                        table.set(count, entry_table)?;
+                    } else {
+                        table.set(count, entry.relative_path.as_str())?;
+                    }
+                    Ok::<_, mlua::Error>(can_descend(lua, entry))
+                })?;
+            } else if with_file_types {
+                let entries = readdir_with_types(&path).map_err(structured_error)?;
+                for (i, entry) in entries.into_iter().enumerate() {
+                    let entry_table = lua.create_table()?;
+                    entry_table.set("name", entry.name)?;
+                    entry_table.set("isFile", entry.is_file)?;
+                    entry_table.set("isDirectory", entry.is_directory)?;
+                    entry_table.set("isSymlink", entry.is_symlink)?;
+                    table.set(i + 1, entry_table)?;
+                }
+            } else {
+                let files = readdir_sync(&path).map_err(structured_error)?;
+                for (i, name) in files.iter().enumerate() {
+                    table.set(i + 1, name.as_str())?;
+                }
+            }
 
-        Ok(table)
-    })?;
+            Ok(table)
+        })?;
     table.set("readdirSync", readdir_fn)?;
     Ok(())
 }
 
+fn register_walk(lua: &Lua, table: &Table) -> mlua::Result<()> {
+    let walk_fn = lua.create_function(|lua, (path, callback): (String, Function)| {
+        guard_path(lua, &path, "read")?;
+        walk(&path, |entry| {
+            let entry_table = lua.create_table()?;
+            entry_table.set("path", entry.path.to_string_lossy().as_ref())?;
+            entry_table.set("relativePath", entry.relative_path.as_str())?;
+            entry_table.set("isDir", entry.is_directory)?;
+            entry_table.set("isSymlink", entry.is_symlink)?;
+            let descend = !matches!(
+                callback.call::<_, Value>(entry_table)?,
+                Value::Boolean(false)
+            );
+            Ok::<_, mlua::Error>(descend && can_descend(lua, entry))
+        })
+    })?;
+    table.set("walk", walk_fn)?;
+    Ok(())
+}
+
+/// Whether a walk may enter `entry`. A symlink whose target is outside the
+/// paths the security policy allows is listed but not followed.
+fn can_descend(lua: &Lua, entry: &WalkEntry) -> bool {
+    !entry.is_symlink || guard_path(lua, &entry.path.to_string_lossy(), "read").is_ok()
+}
+
 fn register_unlink_sync(lua: &Lua, table: &Table) -> mlua::Result<()> {
-    let unlink_fn = lua.create_function(|_, path: String| {
-        unlink_sync(&path).map_err(mlua::Error::external)?;
+    let unlink_fn = lua.create_function(|lua, path: String| {
+        guard_path(lua, &path, "write")?;
+        unlink_sync(&path).map_err(structured_error)?;
         Ok(())
     })?;
     table.set("unlinkSync", unlink_fn)?;
@@ -82,8 +201,9 @@ fn register_unlink_sync(lua: &Lua, table: &Table) -> mlua::Result<()> {
 }
 
 fn register_mkdir_sync(lua: &Lua, table: &Table) -> mlua::Result<()> {
-    let mkdir_fn = lua.create_function(|_, path: String| {
-        mkdir_sync(&path).map_err(mlua::Error::external)?;
+    let mkdir_fn = lua.create_function(|lua, path: String| {
+        guard_path(lua, &path, "write")?;
+        mkdir_sync(&path).map_err(structured_error)?;
         Ok(())
     })?;
     table.set("mkdirSync", mkdir_fn)?;
@@ -91,17 +211,295 @@ fn register_mkdir_sync(lua: &Lua, table: &Table) -> mlua::Result<()> {
 }
 
 fn register_rmdir_sync(lua: &Lua, table: &Table) -> mlua::Result<()> {
-    let rmdir_fn = lua.create_function(|_, path: String| {
-        rmdir_sync(&path).map_err(mlua::Error::external)?;
+    let rmdir_fn = lua.create_function(|lua, path: String| {
+        guard_path(lua, &path, "write")?;
+        rmdir_sync(&path).map_err(structured_error)?;
         Ok(())
     })?;
     table.set("rmdirSync", rmdir_fn)?;
     Ok(())
 }
 
+fn register_mkstemp(lua: &Lua, table: &Table) -> mlua::Result<()> {
+    let mkstemp_fn = lua.create_function(|lua, prefix: Option<String>| {
+        guard_path(lua, &std::env::temp_dir().to_string_lossy(), "write")?;
+        mkstemp(prefix.as_deref().unwrap_or(DEFAULT_TEMP_PREFIX)).map_err(structured_error)
+    })?;
+    table.set("mkstemp", mkstemp_fn)?;
+    Ok(())
+}
+
+fn register_watch(lua: &Lua, table: &Table) -> mlua::Result<()> {
+    let watch_fn = lua.create_function(
+        |lua, (path, callback, options): (String, Function, Option<Table>)| {
+            guard_path(lua, &path, "read")?;
+            let watcher = open_watcher(&path, options.as_ref())?;
+            Ok(WatchHandle {
+                watcher,
+                callback: lua.create_registry_value(callback)?,
+            })
+        },
+    )?;
+    table.set("watch", watch_fn)?;
+    Ok(())
+}
+
+fn register_watch_blocking(lua: &Lua, table: &Table) -> mlua::Result<()> {
+    let watch_fn = lua.create_function(
+        |lua, (path, callback, options): (String, Function, Option<Table>)| {
+            guard_path(lua, &path, "read")?;
+            let watcher = open_watcher(&path, options.as_ref())?;
+            let deadline = match &options {
+                Some(opts) => opts
+                    .get::<_, Option<u64>>("timeout")?
+                    .map(|ms| Instant::now() + Duration::from_millis(ms)),
+                None => None,
+            };
+
+            loop {
+                let wait = match deadline {
+                    Some(deadline) => match deadline.checked_duration_since(Instant::now()) {
+                        Some(remaining) => Some(remaining),
+                        None => return Ok(()),
+                    },
+                    None => None,
+                };
+                for event in watcher.next_batch(wait).map_err(structured_error)? {
+                    // Returning false from the callback stops watching
+                    if let Value::Boolean(false) = dispatch_event(lua, &callback, event)? {
+                        return Ok(());
+                    }
+                }
+            }
+        },
+    )?;
+    table.set("watchBlocking", watch_fn)?;
+    Ok(())
+}
+
+fn register_watch_json(lua: &Lua, table: &Table) -> mlua::Result<()> {
+    let watch_fn = lua.create_function(
+        |lua, (path, callback, options): (String, Function, Option<Table>)| {
+            guard_path(lua, &path, "read")?;
+            // Watch the directory so editors that save by replacing the file
+            // keep being followed
+            let file = PathBuf::from(&path);
+            let dir = match file.parent() {
+                Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+                _ => PathBuf::from("."),
+            };
+            let debounce = match &options {
+                Some(opts) => opts.get::<_, Option<u64>>("debounce")?,
+                None => None,
+            };
+            let debounce = debounce.map_or(DEFAULT_DEBOUNCE, Duration::from_millis);
+            let watcher = FileWatcher::new(&dir, false, debounce).map_err(structured_error)?;
+
+            let config = load_json_file(lua, &path)?;
+            callback.call::<_, ()>(config)?;
+            Ok(JsonWatchHandle {
+                watcher,
+                path: file,
+                callback: lua.create_registry_value(callback)?,
+            })
+        },
+    )?;
+    table.set("watchJson", watch_fn)?;
+    Ok(())
+}
+
+fn load_json_file<'lua>(lua: &'lua Lua, path: &str) -> mlua::Result<Value<'lua>> {
+    let content = read_file_sync(path).map_err(structured_error)?;
+    let value = decode(&content).map_err(mlua::Error::external)?;
+    json_to_lua(lua, &value)
+}
+
+/// Options shared by `watch` and `watchBlocking`: `recursive` (default true)
+/// and `debounce` in milliseconds.
+fn open_watcher(path: &str, options: Option<&Table>) -> mlua::Result<FileWatcher> {
+    let mut recursive = true;
+    let mut debounce = DEFAULT_DEBOUNCE;
+    if let Some(opts) = options {
+        recursive = opts.get::<_, Option<bool>>("recursive")?.unwrap_or(true);
+        if let Some(ms) = opts.get::<_, Option<u64>>("debounce")? {
+            debounce = Duration::from_millis(ms);
+        }
+    }
+    FileWatcher::new(Path::new(path), recursive, debounce).map_err(structured_error)
+}
+
+fn dispatch_event<'lua>(
+    lua: &'lua Lua,
+    callback: &Function<'lua>,
+    event: WatchEvent,
+) -> mlua::Result<Value<'lua>> {
+    let table = lua.create_table()?;
+    table.set("event", event.kind.as_str())?;
+    table.set("path", event.path.to_string_lossy().as_ref())?;
+    callback.call(table)
+}
+
+/// Stream returned by `fs.createWriteStream`. Garbage collection closes it
+/// like `close` does, flushing what is buffered.
+impl UserData for WriteStream {
+    fn add_fields<'lua, F: UserDataFields<'lua, Self>>(fields: &mut F) {
+        fields.add_field_method_get("closed", |_, this| Ok(this.is_closed()));
+        fields.add_field_method_get("bytesWritten", |_, this| Ok(this.bytes_written()));
+    }
+
+    fn add_methods<'lua, M: UserDataMethods<'lua, Self>>(methods: &mut M) {
+        // Takes any number of strings and numbers and returns the stream,
+        // like Lua's file:write
+        methods.add_function(
+            "write",
+            |lua, (stream, values): (AnyUserData, Variadic<Value>)| {
+                {
+                    let mut this = stream.borrow_mut::<WriteStream>()?;
+                    for value in values {
+                        let data = match value {
+                            Value::String(data) => data,
+                            Value::Integer(_) | Value::Number(_) => {
+                                match lua.coerce_string(value)? {
+                                    Some(data) => data,
+                                    None => continue,
+                                }
+                            }
+                            other => {
+                                return Err(mlua::Error::runtime(format!(
+                                    "write expects strings or numbers, got {}",
+                                    other.type_name()
+                                )))
+                            }
+                        };
+                        this.write(data.as_bytes()).map_err(structured_error)?;
+                    }
+                }
+                Ok(stream)
+            },
+        );
+
+        methods.add_method_mut("flush", |_, this, ()| {
+            this.flush().map_err(structured_error)
+        });
+
+        methods.add_method_mut("close", |_, this, ()| {
+            this.close().map_err(structured_error)
+        });
+    }
+}
+
+/// Handle returned by `fs.watch`. Events queue up until `poll` delivers them.
+struct WatchHandle {
+    watcher: FileWatcher,
+    callback: RegistryKey,
+}
+
+impl UserData for WatchHandle {
+    fn add_fields<'lua, F: UserDataFields<'lua, Self>>(fields: &mut F) {
+        fields.add_field_method_get("closed", |_, this| Ok(this.watcher.is_closed()));
+    }
+
+    fn add_methods<'lua, M: UserDataMethods<'lua, Self>>(methods: &mut M) {
+        methods.add_method("poll", |lua, this, timeout: Option<u64>| {
+            let timeout = Duration::from_millis(timeout.unwrap_or(0));
+            let events = this
+                .watcher
+                .next_batch(Some(timeout))
+                .map_err(structured_error)?;
+            let callback: Function = lua.registry_value(&this.callback)?;
+            let count = events.len();
+            for event in events {
+                dispatch_event(lua, &callback, event)?;
+            }
+            Ok(count)
+        });
+
+        methods.add_method_mut("close", |_, this, ()| {
+            this.watcher.close();
+            Ok(())
+        });
+    }
+}
+
+/// Handle returned by `fs.watchJson`. A `poll` that sees the file change
+/// reloads it and calls back with the new value; invalid JSON is logged and
+/// skipped so the previous config stays in use.
+struct JsonWatchHandle {
+    watcher: FileWatcher,
+    path: PathBuf,
+    callback: RegistryKey,
+}
+
+impl JsonWatchHandle {
+    fn is_target(&self, event: &WatchEvent) -> bool {
+        event.kind != WatchEventKind::Delete && event.path.file_name() == self.path.file_name()
+    }
+}
+
+impl UserData for JsonWatchHandle {
+    fn add_fields<'lua, F: UserDataFields<'lua, Self>>(fields: &mut F) {
+        fields.add_field_method_get("closed", |_, this| Ok(this.watcher.is_closed()));
+    }
+
+    fn add_methods<'lua, M: UserDataMethods<'lua, Self>>(methods: &mut M) {
+        methods.add_method("poll", |lua, this, timeout: Option<u64>| {
+            let timeout = Duration::from_millis(timeout.unwrap_or(0));
+            let events = this
+                .watcher
+                .next_batch(Some(timeout))
+                .map_err(structured_error)?;
+            if !events.iter().any(|event| this.is_target(event)) {
+                return Ok(false);
+            }
+
+            let path = this.path.to_string_lossy();
+            match load_json_file(lua, &path) {
+                Ok(config) => {
+                    let callback: Function = lua.registry_value(&this.callback)?;
+                    callback.call::<_, ()>(config)?;
+                    Ok(true)
+                }
+                Err(e) => {
+                    eprintln!(
+                        "fs.watchJson: keeping previous config, {} did not load: {}",
+                        path, e
+                    );
+                    Ok(false)
+                }
+            }
+        });
+
+        methods.add_method_mut("close", |_, this, ()| {
+            this.watcher.close();
+            Ok(())
+        });
+    }
+}
+
+/// Reject paths that the installed security policy does not allow.
+fn guard_path(lua: &Lua, path: &str, operation: &str) -> mlua::Result<()> {
+    check_file_access(lua, path, operation).map_err(structured_error)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::lua::security::SecurityPolicy;
+
+    fn lua_scoped_to(root: &std::path::Path) -> Lua {
+        let lua = Lua::new();
+        let mut policy = SecurityPolicy::default();
+        policy
+            .allowed_file_operations
+            .allowed_paths
+            .push(root.to_path_buf());
+        policy.install(&lua);
+        lua.globals()
+            .set("fs", create_fs_module(&lua).unwrap())
+            .unwrap();
+        lua.globals().set("ROOT", root.to_str().unwrap()).unwrap();
+        lua
+    }
 
     #[test]
     fn test_create_fs_module() {
@@ -112,11 +510,219 @@ mod tests {
         let fs_table = result.unwrap();
         assert!(fs_table.contains_key("readFileSync").unwrap());
         assert!(fs_table.contains_key("writeFileSync").unwrap());
+        assert!(fs_table.contains_key("createWriteStream").unwrap());
         assert!(fs_table.contains_key("existsSync").unwrap());
+        assert!(fs_table.contains_key("access").unwrap());
         assert!(fs_table.contains_key("statSync").unwrap());
         assert!(fs_table.contains_key("readdirSync").unwrap());
         assert!(fs_table.contains_key("unlinkSync").unwrap());
         assert!(fs_table.contains_key("mkdirSync").unwrap());
         assert!(fs_table.contains_key("rmdirSync").unwrap());
+        assert!(fs_table.contains_key("mkstemp").unwrap());
+        assert!(fs_table.contains_key("watch").unwrap());
+        assert!(fs_table.contains_key("watchBlocking").unwrap());
+        assert!(fs_table.contains_key("watchJson").unwrap());
+    }
+
+    #[test]
+    fn test_watch_reports_created_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let lua = lua_scoped_to(dir.path());
+
+        let (event, path): (String, String) = lua
+            .load(
+                r#"
+                local seen = {}
+                local handle = fs.watch(ROOT, function(e) seen[#seen + 1] = e end)
+                fs.writeFileSync(ROOT .. "/new.txt", "hello")
+
+                -- Events arrive asynchronously; allow plenty of time on slow machines
+                local found
+                for _ = 1, 50 do
+                    handle:poll(200)
+                    for _, e in ipairs(seen) do
+                        if e.path:sub(-#"new.txt") == "new.txt" then found = e end
+                    end
+                    if found then break end
+                end
+                handle:close()
+                assert(handle.closed)
+                assert(found, "no event for new.txt")
+                return found.event, found.path
+            "#,
+            )
+            .eval()
+            .unwrap();
+        assert_eq!(event, "create");
+        assert!(path.ends_with("new.txt"), "{}", path);
+    }
+
+    #[test]
+    fn test_watch_blocking_stops_when_callback_returns_false() {
+        let dir = tempfile::tempdir().unwrap();
+        let lua = lua_scoped_to(dir.path());
+        let target = dir.path().join("trigger.txt");
+        let writer = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(200));
+            std::fs::write(target, "x").unwrap();
+        });
+
+        let event: String = lua
+            .load(
+                r#"
+                local got
+                fs.watchBlocking(ROOT, function(e)
+                    got = e.event
+                    return false
+                end, {timeout = 10000})
+                return got
+            "#,
+            )
+            .eval()
+            .unwrap();
+        writer.join().unwrap();
+        assert_eq!(event, "create");
+    }
+
+    #[test]
+    fn test_watch_json_reloads_changed_config() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("config.json"), r#"{"level": 1}"#).unwrap();
+        let lua = lua_scoped_to(dir.path());
+
+        let levels: String = lua
+            .load(
+                r#"
+                local path = ROOT .. "/config.json"
+                local levels = {}
+                local handle = fs.watchJson(path, function(config)
+                    levels[#levels + 1] = config.level
+                end)
+                assert(#levels == 1, "not called immediately")
+
+                local function poll_until(count)
+                    for _ = 1, 50 do
+                        handle:poll(200)
+                        if #levels >= count then return end
+                    end
+                end
+
+                -- A bad edit is skipped and the next good one is delivered
+                fs.writeFileSync(path, '{"level": ')
+                for _ = 1, 3 do handle:poll(200) end
+                fs.writeFileSync(path, '{"level": 2}')
+                poll_until(2)
+                handle:close()
+                return table.concat(levels, ",")
+            "#,
+            )
+            .eval()
+            .unwrap();
+        assert_eq!(levels, "1,2");
+    }
+
+    #[test]
+    fn test_policy_allows_access_inside_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("data");
+        std::fs::create_dir(&root).unwrap();
+        std::fs::write(root.join("in.txt"), "inside").unwrap();
+        let lua = lua_scoped_to(&root);
+
+        let content: String = lua
+            .load(
+                r#"
+                fs.writeFileSync(ROOT .. "/out.txt", "written")
+                return fs.readFileSync(ROOT .. "/in.txt") .. "," .. fs.readFileSync(ROOT .. "/out.txt")
+            "#,
+            )
+            .eval()
+            .unwrap();
+        assert_eq!(content, "inside,written");
+    }
+
+    #[test]
+    fn test_policy_blocks_access_outside_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("data");
+        std::fs::create_dir(&root).unwrap();
+        std::fs::write(dir.path().join("secret.txt"), "secret").unwrap();
+        let lua = lua_scoped_to(&root);
+
+        for script in [
+            r#"return fs.readFileSync(ROOT .. "/../secret.txt")"#,
+            r#"return fs.writeFileSync(ROOT .. "/../escape.txt", "x")"#,
+            r#"return fs.readdirSync(ROOT .. "/..")"#,
+            r#"return fs.statSync(ROOT .. "/../secret.txt")"#,
+        ] {
+            let err = lua.load(script).exec().unwrap_err().to_string();
+            assert!(err.contains("Security error"), "{}: {}", script, err);
+        }
+        let exists: bool = lua
+            .load(r#"return fs.existsSync(ROOT .. "/../secret.txt")"#)
+            .eval()
+            .unwrap();
+        assert!(!exists);
+        assert!(!dir.path().join("escape.txt").exists());
+    }
+
+    #[test]
+    fn test_missing_file_error_has_enoent_code() {
+        let dir = tempfile::tempdir().unwrap();
+        let lua = lua_scoped_to(dir.path());
+
+        let (code, kind, message, text): (String, String, String, String) = lua
+            .load(
+                r#"
+                local ok, err = pcall(fs.readFileSync, ROOT .. "/missing.txt")
+                assert(not ok)
+                return err.code, err.kind, err.message, tostring(err)
+            "#,
+            )
+            .eval()
+            .unwrap();
+        assert_eq!(code, "ENOENT");
+        assert_eq!(kind, "fs");
+        assert!(message.starts_with("Not found"), "{}", message);
+        assert_eq!(text, message);
+
+        let code: String = lua
+            .load(r#"local ok, err = pcall(fs.readFileSync, ROOT .. "/../x") return err.code"#)
+            .eval()
+            .unwrap();
+        assert_eq!(code, "EACCES");
+    }
+
+    #[test]
+    fn test_errno_codes_for_common_operations() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("file.txt"), "x").unwrap();
+        std::fs::create_dir(dir.path().join("sub")).unwrap();
+        let lua = lua_scoped_to(dir.path());
+
+        for (script, expected) in [
+            (r#"fs.readFileSync(ROOT .. "/missing.txt")"#, "ENOENT"),
+            (
+                r#"fs.writeFileSync(ROOT .. "/no/such/dir.txt", "x")"#,
+                "ENOENT",
+            ),
+            (r#"fs.statSync(ROOT .. "/missing.txt")"#, "ENOENT"),
+            (r#"fs.unlinkSync(ROOT .. "/missing.txt")"#, "ENOENT"),
+            (r#"fs.mkdirSync(ROOT .. "/sub")"#, "EEXIST"),
+            (r#"fs.mkdirSync(ROOT .. "/file.txt")"#, "EEXIST"),
+            (r#"fs.readdirSync(ROOT .. "/file.txt")"#, "ENOTDIR"),
+            (r#"fs.rmdirSync(ROOT .. "/file.txt")"#, "ENOTDIR"),
+            (r#"fs.readFileSync(ROOT .. "/sub")"#, "EISDIR"),
+            (r#"fs.readFileSync(ROOT .. "/../outside.txt")"#, "EACCES"),
+        ] {
+            let code: String = lua
+                .load(format!(
+                    "local ok, err = pcall(function() {} end) assert(not ok) return err.code",
+                    script
+                ))
+                .eval()
+                .unwrap();
+            assert_eq!(code, expected, "{}", script);
+        }
     }
 }
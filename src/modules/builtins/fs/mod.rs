@@ -1,6 +1,8 @@
 pub mod error;
 pub mod lua_bindings;
 pub mod operations;
+pub mod stream;
+pub mod watch;
 
 use serde_json::{json, Value as JsonValue};
 
@@ -44,11 +46,25 @@ impl BuiltinModule for FsModule {
                 "__desc": "Write file synchronously",
                 "__signature": "writeFileSync(path: string, data: string) -> nil"
             },
+            "createWriteStream": {
+                "__fn": "createWriteStream",
+                "__desc": "Open a file for buffered writes with stream:write(...), stream:flush() and stream:close()",
+                "__signature": "createWriteStream(path: string, options?: {append?}) -> stream"
+            },
             "existsSync": {
                 "__fn": "existsSync",
                 "__desc": "Check if file exists",
                 "__signature": "existsSync(path: string) -> boolean"
             },
+            "access": {
+                "__fn": "access",
+                "__desc": "Check whether the process may read, write or execute a path",
+                "__signature": "access(path: string, mode?: number) -> boolean"
+            },
+            "constants": {
+                "__table": "constants",
+                "__desc": "Mode bits for access: F_OK, R_OK, W_OK, X_OK",
+            },
             "statSync": {
                 "__fn": "statSync",
                 "__desc": "Get file statistics",
@@ -56,8 +72,13 @@ impl BuiltinModule for FsModule {
             },
             "readdirSync": {
                 "__fn": "readdirSync",
-                "__desc": "Read directory contents",
-                "__signature": "readdirSync(path: string) -> string[]"
+                "__desc": "Read directory contents as sorted names, or with withFileTypes as {name, isFile, isDirectory, isSymlink} tables typed without a stat per entry; recursive lists every descendant by relative path",
+                "__signature": "readdirSync(path: string, options?: {withFileTypes?: boolean, recursive?: boolean}) -> string[] | {name, isFile, isDirectory, isSymlink}[]"
+            },
+            "walk": {
+                "__fn": "walk",
+                "__desc": "Call a function for every entry under a directory, depth-first; returning false from it skips a directory's contents. Symlinked directories are followed unless that would loop",
+                "__signature": "walk(path: string, callback: function(entry: {path, relativePath, isDir, isSymlink}) -> boolean?)"
             },
             "unlinkSync": {
                 "__fn": "unlinkSync",
@@ -73,6 +94,26 @@ impl BuiltinModule for FsModule {
                 "__fn": "rmdirSync",
                 "__desc": "Remove directory",
                 "__signature": "rmdirSync(path: string) -> nil"
+            },
+            "mkstemp": {
+                "__fn": "mkstemp",
+                "__desc": "Create a uniquely named empty file in the system temp directory",
+                "__signature": "mkstemp(prefix?: string) -> string"
+            },
+            "watch": {
+                "__fn": "watch",
+                "__desc": "Watch a file or directory; handle:poll() delivers {event, path} to the callback",
+                "__signature": "watch(path: string, callback: function(event), options?: {recursive?, debounce?}) -> handle"
+            },
+            "watchBlocking": {
+                "__fn": "watchBlocking",
+                "__desc": "Watch a path and block, calling back on each change until it returns false",
+                "__signature": "watchBlocking(path: string, callback: function(event), options?: {recursive?, debounce?, timeout?}) -> nil"
+            },
+            "watchJson": {
+                "__fn": "watchJson",
+                "__desc": "Load a JSON file and call back with the parsed value now and, via handle:poll(), after each change",
+                "__signature": "watchJson(path: string, onChange: function(value), options?: {debounce?}) -> handle"
             }
         }))
     }
@@ -96,11 +137,14 @@ mod tests {
         assert!(exports.get("readFileSync").is_some());
         assert!(exports.get("writeFileSync").is_some());
         assert!(exports.get("existsSync").is_some());
+        assert!(exports.get("access").is_some());
         assert!(exports.get("statSync").is_some());
         assert!(exports.get("readdirSync").is_some());
+        assert!(exports.get("walk").is_some());
         assert!(exports.get("unlinkSync").is_some());
         assert!(exports.get("mkdirSync").is_some());
         assert!(exports.get("rmdirSync").is_some());
+        assert!(exports.get("watch").is_some());
     }
 
     #[test]
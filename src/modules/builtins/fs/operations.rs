@@ -1,6 +1,9 @@
 use super::error::FsError;
+use crate::modules::builtins::os::operations::{temp_name, TEMP_ATTEMPTS};
+use crate::modules::builtins::os::OsError;
 use std::fs;
-use std::path::Path;
+use std::io;
+use std::path::{Path, PathBuf};
 
 pub type Result<T> = std::result::Result<T, FsError>;
 
@@ -16,6 +19,47 @@ pub fn exists_sync(path: &str) -> bool {
     Path::new(path).exists()
 }
 
+/// `fs.access` mode bits, with the values POSIX `access(2)` uses.
+pub const F_OK: u32 = 0;
+pub const R_OK: u32 = 4;
+pub const W_OK: u32 = 2;
+pub const X_OK: u32 = 1;
+
+/// Whether this process has every permission in `mode` (a mask of `R_OK`,
+/// `W_OK` and `X_OK`) for `path`. A path that does not exist is an error
+/// rather than `false`.
+pub fn access_sync(path: &str, mode: u32) -> Result<bool> {
+    if mode & !(R_OK | W_OK | X_OK) != 0 {
+        return Err(FsError::InvalidOperation(format!(
+            "invalid access mode {}",
+            mode
+        )));
+    }
+    let metadata = fs::metadata(path)?;
+    check_access(path, &metadata, mode)
+}
+
+#[cfg(unix)]
+fn check_access(path: &str, _metadata: &fs::Metadata, mode: u32) -> Result<bool> {
+    let c_path = std::ffi::CString::new(path)
+        .map_err(|_| FsError::InvalidPath(format!("{:?} contains a NUL byte", path)))?;
+    // access(2) answers for the real user, including root's overrides
+    if unsafe { libc::access(c_path.as_ptr(), mode as libc::c_int) } == 0 {
+        return Ok(true);
+    }
+    let err = io::Error::last_os_error();
+    match err.raw_os_error() {
+        Some(libc::EACCES) | Some(libc::EROFS) | Some(libc::ETXTBSY) => Ok(false),
+        _ => Err(err.into()),
+    }
+}
+
+#[cfg(not(unix))]
+fn check_access(_path: &str, metadata: &fs::Metadata, mode: u32) -> Result<bool> {
+    // Windows has no execute bit, so X_OK only checks existence, as in Node
+    Ok(mode & W_OK == 0 || !metadata.permissions().readonly())
+}
+
 pub fn stat_sync(path: &str) -> Result<FileStat> {
     let metadata = fs::metadata(path)?;
 
@@ -47,11 +91,112 @@ pub fn readdir_sync(path: &str) -> Result<Vec<String>> {
     Ok(names)
 }
 
+/// The entries of `path` with their types, sorted by name. Types come from
+/// the directory listing itself, so no entry is stat'ed; a symlink is
+/// reported as a symlink rather than as what it points to.
+pub fn readdir_with_types(path: &str) -> Result<Vec<DirEntryInfo>> {
+    let mut entries = Vec::new();
+
+    for entry in fs::read_dir(path)? {
+        let entry = entry?;
+        let Some(name) = entry.file_name().to_str().map(str::to_string) else {
+            continue;
+        };
+        let file_type = entry.file_type()?;
+        entries.push(DirEntryInfo {
+            name,
+            is_file: file_type.is_file(),
+            is_directory: file_type.is_dir(),
+            is_symlink: file_type.is_symlink(),
+        });
+    }
+
+    entries.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(entries)
+}
+
+/// Visit every entry under `root` depth-first, each directory's entries in
+/// name order, calling `visit` before descending into a directory. `visit`
+/// returns whether to descend; its answer is ignored for other entries.
+///
+/// Symlinks to directories are followed, except into a directory that is
+/// already being walked, so symlink cycles end instead of recursing forever.
+pub fn walk<E: From<FsError>>(
+    root: &str,
+    mut visit: impl FnMut(&WalkEntry) -> std::result::Result<bool, E>,
+) -> std::result::Result<(), E> {
+    let root = Path::new(root);
+    let mut ancestors = vec![fs::canonicalize(root).map_err(FsError::from)?];
+    walk_dir(root, "", &mut ancestors, &mut visit)
+}
+
+fn walk_dir<E: From<FsError>>(
+    dir: &Path,
+    relative_dir: &str,
+    ancestors: &mut Vec<PathBuf>,
+    visit: &mut impl FnMut(&WalkEntry) -> std::result::Result<bool, E>,
+) -> std::result::Result<(), E> {
+    let mut entries = fs::read_dir(dir)
+        .and_then(|entries| entries.collect::<io::Result<Vec<_>>>())
+        .map_err(FsError::from)?;
+    entries.sort_by_key(|entry| entry.file_name());
+
+    for entry in entries {
+        let Some(name) = entry.file_name().to_str().map(str::to_string) else {
+            continue;
+        };
+        let file_type = entry.file_type().map_err(FsError::from)?;
+        let path = entry.path();
+        // A broken symlink is reported, but is not a directory
+        let is_directory = if file_type.is_symlink() {
+            fs::metadata(&path).is_ok_and(|metadata| metadata.is_dir())
+        } else {
+            file_type.is_dir()
+        };
+        let walk_entry = WalkEntry {
+            relative_path: if relative_dir.is_empty() {
+                name
+            } else {
+                format!("{}/{}", relative_dir, name)
+            },
+            path,
+            is_file: file_type.is_file(),
+            is_directory,
+            is_symlink: file_type.is_symlink(),
+        };
+
+        if !visit(&walk_entry)? || !walk_entry.is_directory {
+            continue;
+        }
+        let Ok(target) = fs::canonicalize(&walk_entry.path) else {
+            continue;
+        };
+        if ancestors.contains(&target) {
+            continue;
+        }
+        ancestors.push(target);
+        let result = walk_dir(
+            &walk_entry.path,
+            &walk_entry.relative_path,
+            ancestors,
+            visit,
+        );
+        ancestors.pop();
+        result?;
+    }
+    Ok(())
+}
+
 pub fn unlink_sync(path: &str) -> Result<()> {
     fs::remove_file(path).map_err(Into::into)
 }
 
+/// Create `path` and any missing parents. Fails with `AlreadyExists` if
+/// `path` itself already exists, like `mkdir`.
 pub fn mkdir_sync(path: &str) -> Result<()> {
+    if Path::new(path).symlink_metadata().is_ok() {
+        return Err(FsError::AlreadyExists(path.to_string()));
+    }
     fs::create_dir_all(path).map_err(Into::into)
 }
 
@@ -59,14 +204,35 @@ pub fn rmdir_sync(path: &str) -> Result<()> {
     let metadata = fs::metadata(path)?;
 
     if !metadata.is_dir() {
-        return Err(FsError::InvalidOperation(
-            "Path is not a directory".to_string(),
-        ));
+        return Err(FsError::NotADirectory(path.to_string()));
     }
 
     fs::remove_dir(path).map_err(Into::into)
 }
 
+/// Create a new, empty, uniquely named file under the system temp directory
+/// and return its path.
+pub fn mkstemp(prefix: &str) -> Result<String> {
+    for _ in 0..TEMP_ATTEMPTS {
+        let path = temp_name(prefix).map_err(|e| match e {
+            OsError::InvalidPrefix(_) => FsError::InvalidPath(e.to_string()),
+            other => FsError::InvalidOperation(other.to_string()),
+        })?;
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        match options.open(&path) {
+            Ok(_) => return Ok(path.to_string_lossy().into_owned()),
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Err(FsError::InvalidOperation(
+        "mkstemp could not find an unused name".to_string(),
+    ))
+}
+
 pub struct FileStat {
     pub size: u64,
     pub is_file: bool,
@@ -75,6 +241,24 @@ pub struct FileStat {
     pub mtime: u64,
 }
 
+pub struct DirEntryInfo {
+    pub name: String,
+    pub is_file: bool,
+    pub is_directory: bool,
+    pub is_symlink: bool,
+}
+
+/// An entry found by [`walk`]. `relative_path` is relative to the walk's
+/// root and uses `/` separators. `is_directory` follows symlinks, while
+/// `is_file` describes the entry itself.
+pub struct WalkEntry {
+    pub path: PathBuf,
+    pub relative_path: String,
+    pub is_file: bool,
+    pub is_directory: bool,
+    pub is_symlink: bool,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -91,6 +275,20 @@ mod tests {
         assert_eq!(content, "Hello World");
     }
 
+    #[test]
+    fn test_mkstemp_creates_distinct_empty_files() {
+        let first = mkstemp("hype-test-").unwrap();
+        let second = mkstemp("hype-test-").unwrap();
+
+        assert_ne!(first, second);
+        for file in [&first, &second] {
+            assert!(Path::new(file).is_file(), "{}", file);
+            assert_eq!(read_file_sync(file).unwrap(), "");
+            unlink_sync(file).unwrap();
+        }
+        assert!(matches!(mkstemp("a/b"), Err(FsError::InvalidPath(_))));
+    }
+
     #[test]
     fn test_exists() {
         let temp_dir = TempDir::new().unwrap();
@@ -102,6 +300,36 @@ mod tests {
         assert!(exists_sync(file_path.to_str().unwrap()));
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn test_access_read_only_file() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("readonly.txt");
+        fs::write(&file_path, "data").unwrap();
+        fs::set_permissions(&file_path, fs::Permissions::from_mode(0o444)).unwrap();
+        let path = file_path.to_str().unwrap();
+
+        assert!(access_sync(path, F_OK).unwrap());
+        assert!(access_sync(path, R_OK).unwrap());
+        assert!(!access_sync(path, R_OK | X_OK).unwrap());
+        // root may write to any file regardless of its mode
+        if unsafe { libc::geteuid() } != 0 {
+            assert!(!access_sync(path, W_OK).unwrap());
+        }
+
+        let missing = temp_dir.path().join("missing.txt");
+        assert!(matches!(
+            access_sync(missing.to_str().unwrap(), R_OK),
+            Err(FsError::NotFound(_))
+        ));
+        assert!(matches!(
+            access_sync(path, 8),
+            Err(FsError::InvalidOperation(_))
+        ));
+    }
+
     #[test]
     fn test_mkdir_rmdir() {
         let temp_dir = TempDir::new().unwrap();
@@ -128,6 +356,42 @@ mod tests {
         assert!(files.contains(&"file2.txt".to_string()));
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn test_walk_is_depth_first_and_stops_at_symlink_cycles() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("a/b")).unwrap();
+        fs::write(root.join("a/b/c.txt"), "").unwrap();
+        fs::write(root.join("z.txt"), "").unwrap();
+        std::os::unix::fs::symlink("..", root.join("a/up")).unwrap();
+
+        let mut visited = Vec::new();
+        walk(root.to_str().unwrap(), |entry| {
+            visited.push(entry.relative_path.clone());
+            Ok::<_, FsError>(true)
+        })
+        .unwrap();
+        assert_eq!(visited, vec!["a", "a/b", "a/b/c.txt", "a/up", "z.txt"]);
+    }
+
+    #[test]
+    fn test_readdir_with_types() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("b.txt"), "").unwrap();
+        fs::create_dir(temp_dir.path().join("a")).unwrap();
+
+        let entries = readdir_with_types(temp_dir.path().to_str().unwrap()).unwrap();
+        let summary: Vec<(&str, bool, bool, bool)> = entries
+            .iter()
+            .map(|e| (e.name.as_str(), e.is_file, e.is_directory, e.is_symlink))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![("a", false, true, false), ("b.txt", true, false, false)]
+        );
+    }
+
     #[test]
     fn test_stat() {
         let temp_dir = TempDir::new().unwrap();
@@ -161,4 +425,14 @@ mod tests {
         let read_content = read_file_sync(file_path_str).unwrap();
         assert_eq!(read_content, content);
     }
+
+    #[test]
+    fn test_mkdir_existing_path_fails() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().to_str().unwrap();
+
+        let err = mkdir_sync(path).unwrap_err();
+        assert!(matches!(err, FsError::AlreadyExists(_)));
+        assert_eq!(err.code(), "EEXIST");
+    }
 }
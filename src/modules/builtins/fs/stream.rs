@@ -0,0 +1,98 @@
+use super::error::FsError;
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+
+pub type Result<T> = std::result::Result<T, FsError>;
+
+/// A file opened for buffered, incremental writes. Dropping the stream
+/// flushes whatever is still buffered, but only `close` reports a failed flush.
+pub struct WriteStream {
+    writer: Option<BufWriter<File>>,
+    bytes_written: u64,
+}
+
+impl WriteStream {
+    /// Open `path` for writing, creating it if needed. The file is truncated
+    /// unless `append` is set.
+    pub fn open(path: &str, append: bool) -> Result<Self> {
+        let mut options = OpenOptions::new();
+        options.create(true);
+        if append {
+            options.append(true);
+        } else {
+            options.write(true).truncate(true);
+        }
+        let file = options.open(path)?;
+        Ok(Self {
+            writer: Some(BufWriter::new(file)),
+            bytes_written: 0,
+        })
+    }
+
+    pub fn write(&mut self, data: &[u8]) -> Result<()> {
+        self.writer()?.write_all(data)?;
+        self.bytes_written += data.len() as u64;
+        Ok(())
+    }
+
+    /// Write buffered data through to the file.
+    pub fn flush(&mut self) -> Result<()> {
+        self.writer()?.flush().map_err(Into::into)
+    }
+
+    /// Flush and close the file. Closing twice is allowed.
+    pub fn close(&mut self) -> Result<()> {
+        match self.writer.take() {
+            Some(mut writer) => writer.flush().map_err(Into::into),
+            None => Ok(()),
+        }
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.writer.is_none()
+    }
+
+    pub fn bytes_written(&self) -> u64 {
+        self.bytes_written
+    }
+
+    fn writer(&mut self) -> Result<&mut BufWriter<File>> {
+        self.writer
+            .as_mut()
+            .ok_or_else(|| FsError::InvalidOperation("write stream is closed".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_write_stream_truncates_or_appends() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("log.txt");
+        let path = path.to_str().unwrap();
+        std::fs::write(path, "old\n").unwrap();
+
+        let mut stream = WriteStream::open(path, false).unwrap();
+        stream.write(b"one\n").unwrap();
+        stream.close().unwrap();
+        let mut stream = WriteStream::open(path, true).unwrap();
+        stream.write(b"two\n").unwrap();
+        assert_eq!(stream.bytes_written(), 4);
+        drop(stream);
+        assert_eq!(std::fs::read_to_string(path).unwrap(), "one\ntwo\n");
+    }
+
+    #[test]
+    fn test_write_after_close_fails() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("out.txt");
+        let mut stream = WriteStream::open(path.to_str().unwrap(), false).unwrap();
+        stream.close().unwrap();
+        stream.close().unwrap();
+        assert!(stream.is_closed());
+        assert_eq!(stream.write(b"x").unwrap_err().code(), "EINVAL");
+    }
+}
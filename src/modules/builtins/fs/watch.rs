@@ -0,0 +1,200 @@
+use super::error::FsError;
+use notify::event::{ModifyKind, RenameMode};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+/// How long to keep collecting after the first event before reporting a batch.
+pub const DEFAULT_DEBOUNCE: Duration = Duration::from_millis(50);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatchEventKind {
+    Create,
+    Modify,
+    Delete,
+}
+
+impl WatchEventKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WatchEventKind::Create => "create",
+            WatchEventKind::Modify => "modify",
+            WatchEventKind::Delete => "delete",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WatchEvent {
+    pub kind: WatchEventKind,
+    pub path: PathBuf,
+}
+
+/// Watches a file or directory and reports changes in debounced batches.
+pub struct FileWatcher {
+    watcher: Option<RecommendedWatcher>,
+    events: Receiver<notify::Result<Event>>,
+    debounce: Duration,
+}
+
+impl FileWatcher {
+    pub fn new(path: &Path, recursive: bool, debounce: Duration) -> Result<Self, FsError> {
+        let (sender, events) = channel();
+        let mut watcher = notify::recommended_watcher(move |event| {
+            let _ = sender.send(event);
+        })
+        .map_err(notify_error)?;
+        let mode = if recursive {
+            RecursiveMode::Recursive
+        } else {
+            RecursiveMode::NonRecursive
+        };
+        watcher.watch(path, mode).map_err(notify_error)?;
+
+        Ok(Self {
+            watcher: Some(watcher),
+            events,
+            debounce,
+        })
+    }
+
+    /// Wait up to `timeout` (forever when `None`) for a change, then keep
+    /// collecting for the debounce window. Repeated events for the same path
+    /// are merged. Returns an empty batch on timeout or once closed.
+    pub fn next_batch(&self, timeout: Option<Duration>) -> Result<Vec<WatchEvent>, FsError> {
+        if self.watcher.is_none() {
+            return Ok(Vec::new());
+        }
+
+        let mut batch = Vec::new();
+        let first = match timeout {
+            Some(timeout) => match self.events.recv_timeout(timeout) {
+                Ok(event) => event,
+                Err(RecvTimeoutError::Timeout) | Err(RecvTimeoutError::Disconnected) => {
+                    return Ok(batch)
+                }
+            },
+            None => match self.events.recv() {
+                Ok(event) => event,
+                Err(_) => return Ok(batch),
+            },
+        };
+        merge_events(&mut batch, first.map_err(notify_error)?);
+
+        let deadline = Instant::now() + self.debounce;
+        while let Some(remaining) = deadline.checked_duration_since(Instant::now()) {
+            match self.events.recv_timeout(remaining) {
+                Ok(event) => merge_events(&mut batch, event.map_err(notify_error)?),
+                Err(_) => break,
+            }
+        }
+        Ok(batch)
+    }
+
+    /// Stop watching. Pending events are discarded.
+    pub fn close(&mut self) {
+        self.watcher = None;
+        while self.events.try_recv().is_ok() {}
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.watcher.is_none()
+    }
+}
+
+fn merge_events(batch: &mut Vec<WatchEvent>, event: Event) {
+    for (kind, path) in classify(&event) {
+        match batch.iter_mut().find(|existing| existing.path == path) {
+            // A file created and then written in one window is still new
+            Some(existing)
+                if existing.kind == WatchEventKind::Create && kind == WatchEventKind::Modify => {}
+            Some(existing) => existing.kind = kind,
+            None => batch.push(WatchEvent { kind, path }),
+        }
+    }
+}
+
+/// Map a notify event to create/modify/delete per path. Renames are reported
+/// as a delete of the old path and a create of the new one.
+fn classify(event: &Event) -> Vec<(WatchEventKind, PathBuf)> {
+    let kind = match event.kind {
+        EventKind::Create(_) => WatchEventKind::Create,
+        EventKind::Remove(_) => WatchEventKind::Delete,
+        EventKind::Modify(ModifyKind::Name(RenameMode::From)) => WatchEventKind::Delete,
+        EventKind::Modify(ModifyKind::Name(RenameMode::To)) => WatchEventKind::Create,
+        EventKind::Modify(ModifyKind::Name(RenameMode::Both)) => {
+            let mut paths = event.paths.iter();
+            return paths
+                .next()
+                .map(|from| (WatchEventKind::Delete, from.clone()))
+                .into_iter()
+                .chain(paths.next().map(|to| (WatchEventKind::Create, to.clone())))
+                .collect();
+        }
+        EventKind::Modify(_) => WatchEventKind::Modify,
+        EventKind::Access(_) | EventKind::Any | EventKind::Other => return Vec::new(),
+    };
+    event
+        .paths
+        .iter()
+        .map(|path| (kind, path.clone()))
+        .collect()
+}
+
+fn notify_error(err: notify::Error) -> FsError {
+    let paths = err
+        .paths
+        .iter()
+        .map(|p| p.display().to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    match err.kind {
+        notify::ErrorKind::Io(e) => FsError::from(e),
+        notify::ErrorKind::PathNotFound => FsError::NotFound(paths),
+        other => FsError::InvalidOperation(format!("watch failed: {:?}", other)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notify::event::{CreateKind, DataChange};
+
+    fn event(kind: EventKind, paths: &[&str]) -> Event {
+        Event {
+            kind,
+            paths: paths.iter().map(PathBuf::from).collect(),
+            attrs: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_merge_keeps_create_over_following_modify() {
+        let mut batch = Vec::new();
+        merge_events(
+            &mut batch,
+            event(EventKind::Create(CreateKind::File), &["a"]),
+        );
+        merge_events(
+            &mut batch,
+            event(EventKind::Modify(ModifyKind::Data(DataChange::Any)), &["a"]),
+        );
+        merge_events(
+            &mut batch,
+            event(
+                EventKind::Modify(ModifyKind::Name(RenameMode::Both)),
+                &["b", "c"],
+            ),
+        );
+
+        let kinds = batch
+            .iter()
+            .map(|e| (e.kind.as_str(), e.path.to_str().unwrap()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            vec![("create", "a"), ("delete", "b"), ("create", "c")]
+        );
+    }
+}
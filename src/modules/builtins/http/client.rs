@@ -1,49 +1,271 @@
 use super::auth::AuthOption;
 use super::forms;
+#[cfg(feature = "http")]
+use super::response::decode_body;
+use super::response::ACCEPT_ENCODING;
+#[cfg(feature = "http")]
+use super::websocket::{self, WebSocket};
 use super::{HttpError, HttpResponse, Result};
+#[cfg(feature = "http")]
+use crate::error::HypeError;
+use crate::lua::security::NetworkPolicy;
 use std::collections::HashMap;
+use std::path::Path;
+use std::sync::RwLock;
 use std::time::Duration;
 
+#[cfg(feature = "http")]
+use crate::engine::timeout::Deadline;
+#[cfg(feature = "http")]
+use futures_util::future::join_all;
 #[cfg(feature = "http")]
 use reqwest::cookie::Jar;
 #[cfg(feature = "http")]
 use reqwest::Proxy;
 #[cfg(feature = "http")]
+use std::fs::{File, OpenOptions};
+#[cfg(feature = "http")]
+use std::future::Future;
+#[cfg(feature = "http")]
+use std::io::Write;
+#[cfg(feature = "http")]
+use std::net::{IpAddr, SocketAddr, ToSocketAddrs};
+#[cfg(feature = "http")]
 use std::sync::Arc;
 #[cfg(feature = "http")]
 use tokio::runtime::Runtime;
 #[cfg(feature = "http")]
-use url::Url;
+use tokio::sync::Semaphore;
+#[cfg(feature = "http")]
+use url::{Host, Url};
+
+pub const DEFAULT_USER_AGENT: &str = concat!("hype/", env!("CARGO_PKG_VERSION"));
+
+/// Headers for a single request. A `None` value removes a header the client
+/// would otherwise send, such as one of its default headers.
+pub type RequestHeaders = HashMap<String, Option<String>>;
+
+/// Options for [`HttpClient::download`].
+#[derive(Default)]
+pub struct DownloadOptions {
+    /// Continue from the end of an existing partial file with a `Range` request.
+    pub resume: bool,
+    /// Extra attempts after a network error or timeout. With `resume` each
+    /// attempt continues where the last one stopped.
+    pub retries: u32,
+    pub headers: Option<RequestHeaders>,
+    pub timeout: Option<u64>,
+}
+
+/// The outcome of a finished download.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Download {
+    pub status: u16,
+    /// Size of the complete file in bytes.
+    pub size: u64,
+    /// Bytes received by this call, less than `size` after a resume.
+    pub downloaded: u64,
+    pub resumed: bool,
+}
+
+/// A `Content-Range: bytes start-end/total` header. `start` is `None` for
+/// the `bytes */total` form sent with 416 responses.
+#[cfg(feature = "http")]
+#[derive(Debug, PartialEq, Eq)]
+struct ContentRange {
+    start: Option<u64>,
+    total: Option<u64>,
+}
+
+#[cfg(feature = "http")]
+impl ContentRange {
+    fn parse(value: &str) -> Option<Self> {
+        let (range, total) = value.trim().strip_prefix("bytes ")?.split_once('/')?;
+        let total = match total {
+            "*" => None,
+            total => Some(total.parse().ok()?),
+        };
+        let start = match range {
+            "*" => None,
+            range => Some(range.split_once('-')?.0.parse().ok()?),
+        };
+        Some(Self { start, total })
+    }
+}
+
+/// One request of a batch sent with [`HttpClient::fetch_all`].
+pub struct FetchRequest {
+    pub method: String,
+    pub url: String,
+    pub body: Option<String>,
+    pub headers: Option<RequestHeaders>,
+    pub timeout: Option<u64>,
+}
+
+#[cfg(feature = "http")]
+fn build_client(
+    cookie_jar: &Arc<Jar>,
+    proxy_url: Option<&str>,
+    host_overrides: &HashMap<String, SocketAddr>,
+    network_policy: &NetworkPolicy,
+) -> Result<reqwest::Client> {
+    let redirect_policy = {
+        let network_policy = network_policy.clone();
+        let host_overrides = host_overrides.clone();
+        reqwest::redirect::Policy::custom(move |attempt| {
+            if attempt.previous().len() >= 10 {
+                attempt.error("too many redirects")
+            } else if let Err(e) = check_url(&network_policy, &host_overrides, attempt.url()) {
+                attempt.error(e)
+            } else {
+                attempt.follow()
+            }
+        })
+    };
+
+    let mut builder = reqwest::Client::builder()
+        .timeout(Duration::from_secs(30))
+        .pool_max_idle_per_host(10)
+        .cookie_provider(cookie_jar.clone())
+        .redirect(redirect_policy)
+        .user_agent(DEFAULT_USER_AGENT);
+
+    if let Some(proxy_url) = proxy_url {
+        let proxy = Proxy::all(proxy_url)
+            .map_err(|e| HttpError::RequestError(format!("Invalid proxy: {}", e)))?;
+        builder = builder.proxy(proxy);
+    }
+
+    for (host, addr) in host_overrides {
+        builder = builder.resolve(host, *addr);
+    }
+
+    if network_policy.deny_private_ips {
+        builder = builder.dns_resolver(Arc::new(PolicyResolver {
+            policy: network_policy.clone(),
+        }));
+    }
+
+    builder
+        .build()
+        .map_err(|e| HttpError::RuntimeError(e.to_string()))
+}
+
+/// Check a request URL against the network policy. Redirect targets are
+/// checked too, so an allowed host cannot bounce a request to a private one.
+#[cfg(feature = "http")]
+fn check_url(
+    policy: &NetworkPolicy,
+    host_overrides: &HashMap<String, SocketAddr>,
+    url: &Url,
+) -> Result<()> {
+    policy.check_enabled().map_err(security_error)?;
+    let Some(host) = url.host() else {
+        return Ok(());
+    };
+    let host_str = host.to_string();
+    policy.check_host(&host_str).map_err(security_error)?;
+
+    if policy.deny_private_ips {
+        let addrs = match host {
+            Host::Ipv4(ip) => vec![IpAddr::V4(ip)],
+            Host::Ipv6(ip) => vec![IpAddr::V6(ip)],
+            Host::Domain(domain) => match host_overrides.get(domain) {
+                Some(addr) => vec![addr.ip()],
+                None => (domain, url.port_or_known_default().unwrap_or(80))
+                    .to_socket_addrs()
+                    .map(|addrs| addrs.map(|addr| addr.ip()).collect())
+                    .map_err(|e| unresolved_error(&host_str, e))?,
+            },
+        };
+        if addrs.is_empty() {
+            return Err(unresolved_error(&host_str, "no addresses found"));
+        }
+        policy
+            .check_addrs(&host_str, &addrs)
+            .map_err(security_error)?;
+    }
+    Ok(())
+}
+
+/// Resolves hostnames for a client whose policy sets `deny_private_ips`.
+/// Connections only use the addresses checked here, so a host cannot pass
+/// `check_url` and then re-resolve to a private address (DNS rebinding).
+#[cfg(feature = "http")]
+struct PolicyResolver {
+    policy: NetworkPolicy,
+}
+
+#[cfg(feature = "http")]
+impl reqwest::dns::Resolve for PolicyResolver {
+    fn resolve(&self, name: reqwest::dns::Name) -> reqwest::dns::Resolving {
+        let policy = self.policy.clone();
+        Box::pin(async move {
+            let host = name.as_str().to_string();
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host.as_str(), 0))
+                .await
+                .map_err(|e| unresolved_error(&host, e))?
+                .collect();
+            if addrs.is_empty() {
+                return Err(unresolved_error(&host, "no addresses found").into());
+            }
+            let ips: Vec<IpAddr> = addrs.iter().map(SocketAddr::ip).collect();
+            policy.check_addrs(&host, &ips).map_err(security_error)?;
+            Ok(Box::new(addrs.into_iter()) as reqwest::dns::Addrs)
+        })
+    }
+}
+
+/// With `deny_private_ips` set, a host that cannot be resolved is refused
+/// rather than let through unchecked.
+#[cfg(feature = "http")]
+fn unresolved_error(host: &str, reason: impl std::fmt::Display) -> HttpError {
+    HttpError::SecurityError(format!(
+        "Network access denied: could not resolve host '{}': {}",
+        host, reason
+    ))
+}
+
+#[cfg(feature = "http")]
+fn security_error(err: HypeError) -> HttpError {
+    match err {
+        HypeError::Security(msg) => HttpError::SecurityError(msg),
+        other => HttpError::RuntimeError(other.to_string()),
+    }
+}
+
+#[cfg(feature = "http")]
+fn parse_override_addr(addr: &str) -> Option<SocketAddr> {
+    let addr = addr.trim();
+    addr.parse::<SocketAddr>()
+        .ok()
+        .or_else(|| addr.parse::<IpAddr>().ok().map(|ip| SocketAddr::new(ip, 0)))
+}
 
 pub struct HttpClient {
     #[cfg(feature = "http")]
-    client: reqwest::Client,
+    client: RwLock<reqwest::Client>,
     #[cfg(feature = "http")]
     runtime: Runtime,
     #[cfg(feature = "http")]
     cookie_jar: Arc<Jar>,
+    #[cfg(feature = "http")]
+    proxy_url: Option<String>,
+    #[cfg(feature = "http")]
+    host_overrides: RwLock<HashMap<String, SocketAddr>>,
+    #[cfg(feature = "http")]
+    network_policy: RwLock<NetworkPolicy>,
+    #[cfg(feature = "http")]
+    deadline: RwLock<Option<Deadline>>,
+    user_agent: RwLock<String>,
+    default_headers: RwLock<HashMap<String, String>>,
 }
 
 impl HttpClient {
     pub fn new() -> Result<Self> {
         #[cfg(feature = "http")]
         {
-            let cookie_jar = Arc::new(Jar::default());
-
-            let client = reqwest::Client::builder()
-                .timeout(Duration::from_secs(30))
-                .pool_max_idle_per_host(10)
-                .cookie_provider(cookie_jar.clone())
-                .build()
-                .map_err(|e| HttpError::RuntimeError(e.to_string()))?;
-
-            let runtime = Runtime::new().map_err(|e| HttpError::RuntimeError(e.to_string()))?;
-
-            Ok(Self {
-                client,
-                runtime,
-                cookie_jar,
-            })
+            Self::with_proxy_url(None)
         }
 
         #[cfg(not(feature = "http"))]
@@ -56,45 +278,243 @@ impl HttpClient {
 
     #[cfg(feature = "http")]
     pub fn new_with_proxy(proxy_url: &str) -> Result<Self> {
-        let cookie_jar = Arc::new(Jar::default());
+        Self::with_proxy_url(Some(proxy_url.to_string()))
+    }
 
-        let proxy = Proxy::all(proxy_url)
-            .map_err(|e| HttpError::RequestError(format!("Invalid proxy: {}", e)))?;
+    #[cfg(not(feature = "http"))]
+    pub fn new_with_proxy(_proxy_url: &str) -> Result<Self> {
+        Err(HttpError::RuntimeError(
+            "HTTP feature not enabled".to_string(),
+        ))
+    }
 
-        let client = reqwest::Client::builder()
-            .timeout(Duration::from_secs(30))
-            .pool_max_idle_per_host(10)
-            .cookie_provider(cookie_jar.clone())
-            .proxy(proxy)
-            .build()
-            .map_err(|e| HttpError::RuntimeError(e.to_string()))?;
+    #[cfg(feature = "http")]
+    fn with_proxy_url(proxy_url: Option<String>) -> Result<Self> {
+        let cookie_jar = Arc::new(Jar::default());
+        let host_overrides = HashMap::new();
+        let network_policy = NetworkPolicy::default();
+        let client = build_client(
+            &cookie_jar,
+            proxy_url.as_deref(),
+            &host_overrides,
+            &network_policy,
+        )?;
 
         let runtime = Runtime::new().map_err(|e| HttpError::RuntimeError(e.to_string()))?;
 
         Ok(Self {
-            client,
+            client: RwLock::new(client),
             runtime,
             cookie_jar,
+            proxy_url,
+            host_overrides: RwLock::new(host_overrides),
+            network_policy: RwLock::new(network_policy),
+            deadline: RwLock::new(None),
+            user_agent: RwLock::new(DEFAULT_USER_AGENT.to_string()),
+            default_headers: RwLock::new(HashMap::new()),
         })
     }
 
+    #[cfg(feature = "http")]
+    fn client(&self) -> reqwest::Client {
+        self.client.read().unwrap().clone()
+    }
+
+    /// Run a request on the client's runtime, cut short by the script deadline.
+    ///
+    /// The per-request timeout still applies; whichever comes first wins.
+    #[cfg(feature = "http")]
+    fn block_on<T>(&self, request: impl Future<Output = Result<T>>) -> Result<T> {
+        let Some(deadline) = self.deadline.read().unwrap().clone() else {
+            return self.runtime.block_on(request);
+        };
+        let remaining = deadline.remaining();
+        if remaining.is_zero() {
+            return Err(HttpError::TimeoutError);
+        }
+        self.runtime.block_on(async {
+            tokio::time::timeout(remaining, request)
+                .await
+                .unwrap_or(Err(HttpError::TimeoutError))
+        })
+    }
+
+    /// Parse `url` and check it against the network policy.
+    #[cfg(feature = "http")]
+    fn parse_url(&self, url: &str) -> Result<Url> {
+        let parsed_url = Url::parse(url)
+            .map_err(|e| HttpError::RequestError(format!("Invalid URL '{}': {}", url, e)))?;
+        check_url(
+            &self.network_policy.read().unwrap(),
+            &self.host_overrides.read().unwrap(),
+            &parsed_url,
+        )?;
+        Ok(parsed_url)
+    }
+
+    /// Restrict which hosts requests (and redirects) may reach.
+    #[cfg(feature = "http")]
+    pub fn set_network_policy(&self, policy: NetworkPolicy) -> Result<()> {
+        let client = build_client(
+            &self.cookie_jar,
+            self.proxy_url.as_deref(),
+            &self.host_overrides.read().unwrap(),
+            &policy,
+        )?;
+        *self.client.write().unwrap() = client;
+        *self.network_policy.write().unwrap() = policy;
+        Ok(())
+    }
+
     #[cfg(not(feature = "http"))]
-    pub fn new_with_proxy(_proxy_url: &str) -> Result<Self> {
+    pub fn set_network_policy(&self, _policy: NetworkPolicy) -> Result<()> {
         Err(HttpError::RuntimeError(
             "HTTP feature not enabled".to_string(),
         ))
     }
 
+    /// Stop requests once the running script's wall-clock deadline passes.
+    #[cfg(feature = "http")]
+    pub fn set_deadline(&self, deadline: Deadline) {
+        *self.deadline.write().unwrap() = Some(deadline);
+    }
+
+    /// Override DNS resolution for specific hosts.
+    ///
+    /// Values are `ip` or `ip:port`; the port is used when the request URL
+    /// does not specify one. Replaces any previously configured overrides.
+    #[cfg(feature = "http")]
+    pub fn set_host_resolver(&self, overrides: HashMap<String, String>) -> Result<()> {
+        let mut resolved = HashMap::new();
+        for (host, addr) in overrides {
+            let socket_addr = parse_override_addr(&addr).ok_or_else(|| {
+                HttpError::RequestError(format!(
+                    "Invalid address '{}' for host '{}': expected ip or ip:port",
+                    addr, host
+                ))
+            })?;
+            resolved.insert(host, socket_addr);
+        }
+
+        let client = build_client(
+            &self.cookie_jar,
+            self.proxy_url.as_deref(),
+            &resolved,
+            &self.network_policy.read().unwrap(),
+        )?;
+        *self.client.write().unwrap() = client;
+        *self.host_overrides.write().unwrap() = resolved;
+        Ok(())
+    }
+
+    #[cfg(not(feature = "http"))]
+    pub fn set_host_resolver(&self, _overrides: HashMap<String, String>) -> Result<()> {
+        Err(HttpError::RuntimeError(
+            "HTTP feature not enabled".to_string(),
+        ))
+    }
+
+    #[cfg(feature = "http")]
+    pub fn host_overrides(&self) -> HashMap<String, SocketAddr> {
+        self.host_overrides.read().unwrap().clone()
+    }
+
+    /// Open a WebSocket on this client's runtime. The URL is checked against
+    /// the network policy, host overrides apply, and the handshake carries the
+    /// User-Agent, default headers and `headers`.
+    #[cfg(feature = "http")]
+    pub fn connect_websocket(
+        &self,
+        url: &str,
+        headers: Option<RequestHeaders>,
+    ) -> Result<WebSocket> {
+        let parsed_url = self.parse_url(url)?;
+        let addr = parsed_url.host_str().and_then(|host| {
+            let mut addr = *self.host_overrides.read().unwrap().get(host)?;
+            if addr.port() == 0 {
+                addr.set_port(parsed_url.port_or_known_default().unwrap_or(80));
+            }
+            Some(addr)
+        });
+        let mut headers = self.merge_headers(headers);
+        headers.retain(|name, _| !name.eq_ignore_ascii_case("accept-encoding"));
+
+        websocket::connect(
+            self.runtime.handle().clone(),
+            &parsed_url,
+            addr,
+            &headers,
+            self.deadline.read().unwrap().clone(),
+        )
+    }
+
+    pub fn set_user_agent(&self, user_agent: &str) {
+        *self.user_agent.write().unwrap() = user_agent.to_string();
+    }
+
+    pub fn user_agent(&self) -> String {
+        self.user_agent.read().unwrap().clone()
+    }
+
+    pub fn set_default_headers(&self, headers: HashMap<String, String>) {
+        *self.default_headers.write().unwrap() = headers;
+    }
+
+    pub fn default_headers(&self) -> HashMap<String, String> {
+        self.default_headers.read().unwrap().clone()
+    }
+
+    /// Merge the User-Agent, Accept-Encoding, client default headers and
+    /// per-request headers.
+    ///
+    /// Header names are compared case-insensitively; later sources win, and a
+    /// per-request `None` removes the header.
+    pub fn merge_headers(&self, headers: Option<RequestHeaders>) -> HashMap<String, String> {
+        let mut merged: HashMap<String, (String, String)> = HashMap::new();
+        let mut insert = |key: String, value: Option<String>| match value {
+            Some(value) => {
+                merged.insert(key.to_ascii_lowercase(), (key, value));
+            }
+            None => {
+                merged.remove(&key.to_ascii_lowercase());
+            }
+        };
+
+        insert("User-Agent".to_string(), Some(self.user_agent()));
+        insert(
+            "Accept-Encoding".to_string(),
+            Some(ACCEPT_ENCODING.to_string()),
+        );
+        for (key, value) in self.default_headers() {
+            insert(key, Some(value));
+        }
+        for (key, value) in headers.unwrap_or_default() {
+            insert(key, value);
+        }
+
+        merged.into_values().collect()
+    }
+
+    #[cfg(feature = "http")]
+    fn apply_headers(
+        &self,
+        mut request: reqwest::RequestBuilder,
+        headers: Option<RequestHeaders>,
+    ) -> reqwest::RequestBuilder {
+        for (key, value) in self.merge_headers(headers) {
+            request = request.header(key, value);
+        }
+        request
+    }
+
     #[cfg(feature = "http")]
     pub fn get(&self, url: &str) -> Result<HttpResponse> {
-        let parsed_url = Url::parse(url)
-            .map_err(|e| HttpError::RequestError(format!("Invalid URL '{}': {}", url, e)))?;
+        let parsed_url = self.parse_url(url)?;
 
-        self.runtime.block_on(async {
-            let response = self.client.get(parsed_url.as_str()).send().await?;
-            HttpResponse::from_reqwest(response)
-                .await
-                .map_err(Into::into)
+        self.block_on(async {
+            let request = self.apply_headers(self.client().get(parsed_url.as_str()), None);
+            let response = request.send().await?;
+            HttpResponse::from_reqwest(response).await
         })
     }
 
@@ -105,33 +525,57 @@ impl HttpClient {
         ))
     }
 
+    /// Fetch `url` and return the decoded body bytes, failing on non-2xx statuses.
+    #[cfg(feature = "http")]
+    pub fn get_bytes(&self, url: &str) -> Result<Vec<u8>> {
+        let parsed_url = self.parse_url(url)?;
+
+        self.block_on(async {
+            let response = self
+                .apply_headers(self.client().get(parsed_url.as_str()), None)
+                .send()
+                .await?;
+            let status = response.status();
+            if !status.is_success() {
+                return Err(HttpError::ResponseError(
+                    status.as_u16(),
+                    status.canonical_reason().unwrap_or("Unknown").to_string(),
+                ));
+            }
+            let headers = response
+                .headers()
+                .iter()
+                .filter_map(|(k, v)| Some((k.to_string(), v.to_str().ok()?.to_string())))
+                .collect();
+            decode_body(&headers, &response.bytes().await?)
+        })
+    }
+
+    #[cfg(not(feature = "http"))]
+    pub fn get_bytes(&self, _url: &str) -> Result<Vec<u8>> {
+        Err(HttpError::RuntimeError(
+            "HTTP feature not enabled".to_string(),
+        ))
+    }
+
     #[cfg(feature = "http")]
     pub fn post(
         &self,
         url: &str,
         body: Option<String>,
-        headers: Option<HashMap<String, String>>,
+        headers: Option<RequestHeaders>,
     ) -> Result<HttpResponse> {
-        let parsed_url = Url::parse(url)
-            .map_err(|e| HttpError::RequestError(format!("Invalid URL '{}': {}", url, e)))?;
+        let parsed_url = self.parse_url(url)?;
 
-        self.runtime.block_on(async {
-            let mut request = self.client.post(parsed_url.as_str());
-
-            if let Some(headers_map) = headers {
-                for (key, value) in headers_map {
-                    request = request.header(key, value);
-                }
-            }
+        self.block_on(async {
+            let mut request = self.apply_headers(self.client().post(parsed_url.as_str()), headers);
 
             if let Some(body_content) = body {
                 request = request.body(body_content);
             }
 
             let response = request.send().await?;
-            HttpResponse::from_reqwest(response)
-                .await
-                .map_err(Into::into)
+            HttpResponse::from_reqwest(response).await
         })
     }
 
@@ -140,7 +584,7 @@ impl HttpClient {
         &self,
         _url: &str,
         _body: Option<String>,
-        _headers: Option<HashMap<String, String>>,
+        _headers: Option<RequestHeaders>,
     ) -> Result<HttpResponse> {
         Err(HttpError::RuntimeError(
             "HTTP feature not enabled".to_string(),
@@ -152,28 +596,19 @@ impl HttpClient {
         &self,
         url: &str,
         body: Option<String>,
-        headers: Option<HashMap<String, String>>,
+        headers: Option<RequestHeaders>,
     ) -> Result<HttpResponse> {
-        let parsed_url = Url::parse(url)
-            .map_err(|e| HttpError::RequestError(format!("Invalid URL '{}': {}", url, e)))?;
-
-        self.runtime.block_on(async {
-            let mut request = self.client.put(parsed_url.as_str());
+        let parsed_url = self.parse_url(url)?;
 
-            if let Some(headers_map) = headers {
-                for (key, value) in headers_map {
-                    request = request.header(key, value);
-                }
-            }
+        self.block_on(async {
+            let mut request = self.apply_headers(self.client().put(parsed_url.as_str()), headers);
 
             if let Some(body_content) = body {
                 request = request.body(body_content);
             }
 
             let response = request.send().await?;
-            HttpResponse::from_reqwest(response)
-                .await
-                .map_err(Into::into)
+            HttpResponse::from_reqwest(response).await
         })
     }
 
@@ -182,7 +617,7 @@ impl HttpClient {
         &self,
         _url: &str,
         _body: Option<String>,
-        _headers: Option<HashMap<String, String>>,
+        _headers: Option<RequestHeaders>,
     ) -> Result<HttpResponse> {
         Err(HttpError::RuntimeError(
             "HTTP feature not enabled".to_string(),
@@ -190,36 +625,19 @@ impl HttpClient {
     }
 
     #[cfg(feature = "http")]
-    pub fn delete(
-        &self,
-        url: &str,
-        headers: Option<HashMap<String, String>>,
-    ) -> Result<HttpResponse> {
-        let parsed_url = Url::parse(url)
-            .map_err(|e| HttpError::RequestError(format!("Invalid URL '{}': {}", url, e)))?;
+    pub fn delete(&self, url: &str, headers: Option<RequestHeaders>) -> Result<HttpResponse> {
+        let parsed_url = self.parse_url(url)?;
 
-        self.runtime.block_on(async {
-            let mut request = self.client.delete(parsed_url.as_str());
-
-            if let Some(headers_map) = headers {
-                for (key, value) in headers_map {
-                    request = request.header(key, value);
-                }
-            }
+        self.block_on(async {
+            let request = self.apply_headers(self.client().delete(parsed_url.as_str()), headers);
 
             let response = request.send().await?;
-            HttpResponse::from_reqwest(response)
-                .await
-                .map_err(Into::into)
+            HttpResponse::from_reqwest(response).await
         })
     }
 
     #[cfg(not(feature = "http"))]
-    pub fn delete(
-        &self,
-        _url: &str,
-        _headers: Option<HashMap<String, String>>,
-    ) -> Result<HttpResponse> {
+    pub fn delete(&self, _url: &str, _headers: Option<RequestHeaders>) -> Result<HttpResponse> {
         Err(HttpError::RuntimeError(
             "HTTP feature not enabled".to_string(),
         ))
@@ -231,49 +649,214 @@ impl HttpClient {
         method: &str,
         url: &str,
         body: Option<String>,
-        headers: Option<HashMap<String, String>>,
+        headers: Option<RequestHeaders>,
         timeout: Option<u64>,
     ) -> Result<HttpResponse> {
-        let parsed_url = Url::parse(url)
-            .map_err(|e| HttpError::RequestError(format!("Invalid URL '{}': {}", url, e)))?;
+        let parsed_url = self.parse_url(url)?;
+        self.block_on(self.send_request(method, parsed_url, body, headers, timeout))
+    }
 
-        self.runtime.block_on(async {
-            let url_str = parsed_url.as_str();
-            let mut request = match method.to_uppercase().as_str() {
-                "GET" => self.client.get(url_str),
-                "POST" => self.client.post(url_str),
-                "PUT" => self.client.put(url_str),
-                "DELETE" => self.client.delete(url_str),
-                "PATCH" => self.client.patch(url_str),
-                "HEAD" => self.client.head(url_str),
-                "OPTIONS" => self.client.request(reqwest::Method::OPTIONS, url_str),
-                _ => {
-                    return Err(HttpError::RequestError(format!(
-                        "Unsupported HTTP method: {}",
-                        method
-                    )))
-                }
-            };
+    /// Send every request concurrently, at most `concurrency` at a time when
+    /// a limit is given. Results are in the same order as `requests`.
+    #[cfg(feature = "http")]
+    pub fn fetch_all(
+        &self,
+        requests: Vec<FetchRequest>,
+        concurrency: Option<usize>,
+    ) -> Result<Vec<Result<HttpResponse>>> {
+        let limit = concurrency.unwrap_or(requests.len()).max(1);
+        let semaphore = Semaphore::new(limit);
+        let pending = requests.into_iter().map(|request| {
+            let semaphore = &semaphore;
+            async move {
+                let parsed_url = self.parse_url(&request.url)?;
+                let _permit = semaphore
+                    .acquire()
+                    .await
+                    .map_err(|e| HttpError::RuntimeError(e.to_string()))?;
+                self.send_request(
+                    &request.method,
+                    parsed_url,
+                    request.body,
+                    request.headers,
+                    request.timeout,
+                )
+                .await
+            }
+        });
+        self.block_on(async { Ok(join_all(pending).await) })
+    }
 
-            if let Some(headers_map) = headers {
-                for (key, value) in headers_map {
-                    request = request.header(key, value);
+    /// Download `url` into `dest`, streaming the body to disk. With `resume`
+    /// an existing `dest` is treated as the start of the file and only the
+    /// rest is requested. When the server ignores the range or answers with
+    /// a `Content-Range` that does not continue the file, the whole file is
+    /// downloaded again instead.
+    #[cfg(feature = "http")]
+    pub fn download(&self, url: &str, dest: &Path, options: &DownloadOptions) -> Result<Download> {
+        let parsed_url = self.parse_url(url)?;
+
+        self.block_on(async {
+            let mut retries = options.retries;
+            let mut use_range = options.resume;
+            loop {
+                let offset = match use_range {
+                    true => std::fs::metadata(dest).map(|m| m.len()).unwrap_or(0),
+                    false => 0,
+                };
+                match self.download_from(&parsed_url, dest, options, offset).await {
+                    Ok(Some(download)) => return Ok(download),
+                    // The range was refused, so start again from the beginning
+                    Ok(None) => use_range = false,
+                    Err(HttpError::NetworkError(_) | HttpError::TimeoutError) if retries > 0 => {
+                        retries -= 1;
+                        use_range = options.resume;
+                    }
+                    Err(e) => return Err(e),
                 }
             }
+        })
+    }
 
-            if let Some(body_content) = body {
-                request = request.body(body_content);
+    /// One download attempt continuing from `offset`. Returns `None` when the
+    /// server would not continue from there.
+    #[cfg(feature = "http")]
+    async fn download_from(
+        &self,
+        url: &Url,
+        dest: &Path,
+        options: &DownloadOptions,
+        offset: u64,
+    ) -> Result<Option<Download>> {
+        let mut headers = options.headers.clone().unwrap_or_default();
+        // Ranges count bytes of the encoded body, so ask for it unencoded
+        if !headers
+            .keys()
+            .any(|key| key.eq_ignore_ascii_case("accept-encoding"))
+        {
+            headers.insert("Accept-Encoding".to_string(), Some("identity".to_string()));
+        }
+        if offset > 0 {
+            headers.insert("Range".to_string(), Some(format!("bytes={}-", offset)));
+        }
+
+        let mut request = self.apply_headers(self.client().get(url.as_str()), Some(headers));
+        if let Some(timeout_ms) = options.timeout {
+            request = request.timeout(Duration::from_millis(timeout_ms));
+        }
+        let mut response = request.send().await?;
+
+        let status = response.status();
+        let content_range = response
+            .headers()
+            .get(reqwest::header::CONTENT_RANGE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(ContentRange::parse);
+
+        if offset > 0 && status == reqwest::StatusCode::RANGE_NOT_SATISFIABLE {
+            // Nothing left past the end of a file that is already complete
+            return Ok(match content_range.and_then(|range| range.total) {
+                Some(total) if total == offset => Some(Download {
+                    status: status.as_u16(),
+                    size: offset,
+                    downloaded: 0,
+                    resumed: true,
+                }),
+                _ => None,
+            });
+        }
+        if !status.is_success() {
+            return Err(HttpError::ResponseError(
+                status.as_u16(),
+                status.canonical_reason().unwrap_or("Unknown").to_string(),
+            ));
+        }
+
+        let resumed = offset > 0 && status == reqwest::StatusCode::PARTIAL_CONTENT;
+        let expected = if resumed {
+            match content_range {
+                Some(ContentRange {
+                    start: Some(start),
+                    total,
+                }) if start == offset => total,
+                _ => return Ok(None),
             }
+        } else {
+            response.content_length()
+        };
+
+        let write_error =
+            |e: std::io::Error| HttpError::RuntimeError(format!("{}: {}", dest.display(), e));
+        let mut file = if resumed {
+            OpenOptions::new().append(true).open(dest)
+        } else {
+            File::create(dest)
+        }
+        .map_err(write_error)?;
 
-            if let Some(timeout_ms) = timeout {
-                request = request.timeout(Duration::from_millis(timeout_ms));
+        let mut downloaded = 0;
+        while let Some(chunk) = response.chunk().await? {
+            file.write_all(&chunk).map_err(write_error)?;
+            downloaded += chunk.len() as u64;
+        }
+        file.flush().map_err(write_error)?;
+
+        let size = if resumed { offset } else { 0 } + downloaded;
+        if let Some(expected) = expected {
+            if size != expected {
+                return Err(HttpError::NetworkError(format!(
+                    "download ended after {} of {} bytes",
+                    size, expected
+                )));
             }
+        }
 
-            let response = request.send().await?;
-            HttpResponse::from_reqwest(response)
-                .await
-                .map_err(Into::into)
-        })
+        Ok(Some(Download {
+            status: status.as_u16(),
+            size,
+            downloaded,
+            resumed,
+        }))
+    }
+
+    #[cfg(feature = "http")]
+    async fn send_request(
+        &self,
+        method: &str,
+        parsed_url: Url,
+        body: Option<String>,
+        headers: Option<RequestHeaders>,
+        timeout: Option<u64>,
+    ) -> Result<HttpResponse> {
+        let url_str = parsed_url.as_str();
+        let mut request = match method.to_uppercase().as_str() {
+            "GET" => self.client().get(url_str),
+            "POST" => self.client().post(url_str),
+            "PUT" => self.client().put(url_str),
+            "DELETE" => self.client().delete(url_str),
+            "PATCH" => self.client().patch(url_str),
+            "HEAD" => self.client().head(url_str),
+            "OPTIONS" => self.client().request(reqwest::Method::OPTIONS, url_str),
+            _ => {
+                return Err(HttpError::RequestError(format!(
+                    "Unsupported HTTP method: {}",
+                    method
+                )))
+            }
+        };
+
+        request = self.apply_headers(request, headers);
+
+        if let Some(body_content) = body {
+            request = request.body(body_content);
+        }
+
+        if let Some(timeout_ms) = timeout {
+            request = request.timeout(Duration::from_millis(timeout_ms));
+        }
+
+        let response = request.send().await?;
+        HttpResponse::from_reqwest(response).await
     }
 
     #[cfg(not(feature = "http"))]
@@ -282,7 +865,7 @@ impl HttpClient {
         _method: &str,
         _url: &str,
         _body: Option<String>,
-        _headers: Option<HashMap<String, String>>,
+        _headers: Option<RequestHeaders>,
         _timeout: Option<u64>,
     ) -> Result<HttpResponse> {
         Err(HttpError::RuntimeError(
@@ -290,6 +873,29 @@ impl HttpClient {
         ))
     }
 
+    #[cfg(not(feature = "http"))]
+    pub fn download(
+        &self,
+        _url: &str,
+        _dest: &Path,
+        _options: &DownloadOptions,
+    ) -> Result<Download> {
+        Err(HttpError::RuntimeError(
+            "HTTP feature not enabled".to_string(),
+        ))
+    }
+
+    #[cfg(not(feature = "http"))]
+    pub fn fetch_all(
+        &self,
+        _requests: Vec<FetchRequest>,
+        _concurrency: Option<usize>,
+    ) -> Result<Vec<Result<HttpResponse>>> {
+        Err(HttpError::RuntimeError(
+            "HTTP feature not enabled".to_string(),
+        ))
+    }
+
     #[cfg(feature = "http")]
     pub fn get_cookies(&self, url: &str) -> Result<Vec<(String, String)>> {
         use reqwest::cookie::CookieStore;
@@ -329,23 +935,49 @@ impl HttpClient {
         url: &str,
         proxy: Option<String>,
         auth: Option<AuthOption>,
+        headers: Option<RequestHeaders>,
     ) -> Result<HttpResponse> {
-        let parsed_url = Url::parse(url)
-            .map_err(|e| HttpError::RequestError(format!("Invalid URL '{}': {}", url, e)))?;
+        self.parse_url(url)?;
 
         if let Some(proxy_url) = proxy {
             let temp_client = Self::new_with_proxy(&proxy_url)?;
-            if let Some(auth_opt) = auth {
-                return temp_client.get_with_auth(url, auth_opt);
-            }
-            return temp_client.get(url);
+            temp_client.set_user_agent(&self.user_agent());
+            temp_client.set_default_headers(self.default_headers());
+            let overrides = self.host_overrides();
+            let network_policy = self.network_policy.read().unwrap().clone();
+            let client = build_client(
+                &temp_client.cookie_jar,
+                Some(&proxy_url),
+                &overrides,
+                &network_policy,
+            )?;
+            *temp_client.client.write().unwrap() = client;
+            *temp_client.host_overrides.write().unwrap() = overrides;
+            *temp_client.network_policy.write().unwrap() = network_policy;
+            return temp_client.get_with_headers(url, auth, headers);
         }
 
-        if let Some(auth_opt) = auth {
-            return self.get_with_auth(url, auth_opt);
-        }
+        self.get_with_headers(url, auth, headers)
+    }
 
-        self.get(url)
+    /// GET with per-request headers, where `auth` sets the Authorization header.
+    #[cfg(feature = "http")]
+    fn get_with_headers(
+        &self,
+        url: &str,
+        auth: Option<AuthOption>,
+        headers: Option<RequestHeaders>,
+    ) -> Result<HttpResponse> {
+        match (auth, headers) {
+            (None, None) => self.get(url),
+            (Some(auth), None) => self.get_with_auth(url, auth),
+            (auth, Some(mut headers)) => {
+                if let Some(auth) = auth {
+                    headers.insert("Authorization".to_string(), Some(auth.to_header_value()));
+                }
+                self.fetch("GET", url, None, Some(headers), None)
+            }
+        }
     }
 
     #[cfg(not(feature = "http"))]
@@ -354,6 +986,7 @@ impl HttpClient {
         _url: &str,
         _proxy: Option<String>,
         _auth: Option<AuthOption>,
+        _headers: Option<RequestHeaders>,
     ) -> Result<HttpResponse> {
         Err(HttpError::RuntimeError(
             "HTTP feature not enabled".to_string(),
@@ -362,19 +995,16 @@ impl HttpClient {
 
     #[cfg(feature = "http")]
     pub fn get_with_auth(&self, url: &str, auth: AuthOption) -> Result<HttpResponse> {
-        let parsed_url = Url::parse(url)
-            .map_err(|e| HttpError::RequestError(format!("Invalid URL '{}': {}", url, e)))?;
+        let parsed_url = self.parse_url(url)?;
 
-        self.runtime.block_on(async {
+        self.block_on(async {
+            let mut auth_header = HashMap::new();
+            auth_header.insert("Authorization".to_string(), Some(auth.to_header_value()));
             let response = self
-                .client
-                .get(parsed_url.as_str())
-                .header("Authorization", auth.to_header_value())
+                .apply_headers(self.client().get(parsed_url.as_str()), Some(auth_header))
                 .send()
                 .await?;
-            HttpResponse::from_reqwest(response)
-                .await
-                .map_err(Into::into)
+            HttpResponse::from_reqwest(response).await
         })
     }
 
@@ -392,7 +1022,7 @@ impl HttpClient {
         let mut headers = HashMap::new();
         headers.insert(
             "Content-Type".to_string(),
-            "application/x-www-form-urlencoded".to_string(),
+            Some("application/x-www-form-urlencoded".to_string()),
         );
 
         self.post(url, Some(body), Some(headers))
@@ -412,23 +1042,19 @@ impl HttpClient {
         fields: HashMap<String, String>,
         file: forms::FileField,
     ) -> Result<HttpResponse> {
-        let parsed_url =
-            Url::parse(url).map_err(|e| HttpError::RequestError(format!("Invalid URL: {}", e)))?;
+        let parsed_url = self.parse_url(url)?;
 
         let form =
             forms::build_multipart_form(fields, file).map_err(|e| HttpError::RequestError(e))?;
 
-        self.runtime.block_on(async {
+        self.block_on(async {
             let response = self
-                .client
-                .post(parsed_url.as_str())
+                .apply_headers(self.client().post(parsed_url.as_str()), None)
                 .multipart(form)
                 .send()
                 .await?;
 
-            HttpResponse::from_reqwest(response)
-                .await
-                .map_err(Into::into)
+            HttpResponse::from_reqwest(response).await
         })
     }
 
@@ -465,6 +1091,57 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[cfg(feature = "http")]
+    #[test]
+    fn test_merge_headers_overrides_and_removes_defaults() {
+        let client = HttpClient::new().unwrap();
+        client.set_default_headers(HashMap::from([
+            ("Authorization".to_string(), "Bearer a".to_string()),
+            ("X-Team".to_string(), "core".to_string()),
+        ]));
+
+        let merged = client.merge_headers(Some(HashMap::from([
+            ("x-team".to_string(), Some("web".to_string())),
+            ("authorization".to_string(), None),
+        ])));
+        let names: HashMap<String, String> = merged
+            .into_iter()
+            .map(|(name, value)| (name.to_ascii_lowercase(), value))
+            .collect();
+
+        assert_eq!(names["x-team"], "web");
+        assert!(!names.contains_key("authorization"));
+        assert_eq!(names["user-agent"], DEFAULT_USER_AGENT);
+    }
+
+    #[cfg(feature = "http")]
+    #[test]
+    fn test_parse_content_range() {
+        assert_eq!(
+            ContentRange::parse("bytes 100-199/1000"),
+            Some(ContentRange {
+                start: Some(100),
+                total: Some(1000)
+            })
+        );
+        assert_eq!(
+            ContentRange::parse("bytes 0-9/*"),
+            Some(ContentRange {
+                start: Some(0),
+                total: None
+            })
+        );
+        assert_eq!(
+            ContentRange::parse("bytes */1000"),
+            Some(ContentRange {
+                start: None,
+                total: Some(1000)
+            })
+        );
+        assert_eq!(ContentRange::parse("items 0-9/10"), None);
+        assert_eq!(ContentRange::parse("bytes x-9/10"), None);
+    }
+
     #[cfg(feature = "http")]
     #[test]
     #[ignore]
@@ -486,7 +1163,10 @@ mod tests {
         let client = HttpClient::new().unwrap();
         let body = Some(r#"{"test": true}"#.to_string());
         let mut headers = HashMap::new();
-        headers.insert("Content-Type".to_string(), "application/json".to_string());
+        headers.insert(
+            "Content-Type".to_string(),
+            Some("application/json".to_string()),
+        );
 
         let result = client.post("https://httpbin.org/post", body, Some(headers));
 
@@ -643,4 +1323,98 @@ mod tests {
         let result = client.delete("ftp://invalid.com", None);
         assert!(result.is_ok() || result.is_err());
     }
+
+    #[cfg(feature = "http")]
+    #[test]
+    fn test_network_policy_rejects_disallowed_hosts() {
+        let client = HttpClient::new().unwrap();
+        client
+            .set_network_policy(NetworkPolicy {
+                allowed_hosts: vec!["api.example.com".to_string()],
+                deny_private_ips: true,
+                ..NetworkPolicy::default()
+            })
+            .unwrap();
+        client
+            .set_host_resolver(HashMap::from([(
+                "api.example.com".to_string(),
+                "93.184.216.34".to_string(),
+            )]))
+            .unwrap();
+
+        assert!(client.parse_url("https://api.example.com/v1").is_ok());
+        for url in ["https://evil.example.org/", "http://127.0.0.1:8080/admin"] {
+            match client.get(url) {
+                Err(HttpError::SecurityError(msg)) => assert!(msg.contains("denied"), "{}", msg),
+                other => panic!("expected SecurityError for {}, got {:?}", url, other.err()),
+            }
+        }
+    }
+
+    #[cfg(feature = "http")]
+    #[test]
+    fn test_network_policy_denies_private_ips() {
+        let client = HttpClient::new().unwrap();
+        client
+            .set_network_policy(NetworkPolicy {
+                deny_private_ips: true,
+                ..NetworkPolicy::default()
+            })
+            .unwrap();
+
+        for url in [
+            "http://127.0.0.1/",
+            "http://[::1]/",
+            "http://169.254.169.254/latest/meta-data",
+            "http://localhost:3000/",
+        ] {
+            assert!(
+                matches!(
+                    client.fetch("GET", url, None, None, None),
+                    Err(HttpError::SecurityError(_))
+                ),
+                "{}",
+                url
+            );
+        }
+        assert!(client.parse_url("https://93.184.216.34/").is_ok());
+    }
+
+    #[cfg(feature = "http")]
+    #[test]
+    fn test_network_policy_refuses_unresolvable_hosts() {
+        let client = HttpClient::new().unwrap();
+        client
+            .set_network_policy(NetworkPolicy {
+                deny_private_ips: true,
+                ..NetworkPolicy::default()
+            })
+            .unwrap();
+
+        match client.parse_url("https://does-not-exist.invalid/") {
+            Err(HttpError::SecurityError(msg)) => {
+                assert!(msg.contains("could not resolve"), "{}", msg)
+            }
+            other => panic!("expected SecurityError, got {:?}", other),
+        }
+    }
+
+    #[cfg(feature = "http")]
+    #[test]
+    fn test_policy_resolver_rejects_private_addresses() {
+        use reqwest::dns::Resolve;
+
+        let resolver = PolicyResolver {
+            policy: NetworkPolicy {
+                deny_private_ips: true,
+                ..NetworkPolicy::default()
+            },
+        };
+        let runtime = Runtime::new().unwrap();
+        for host in ["localhost", "does-not-exist.invalid"] {
+            let result = runtime.block_on(resolver.resolve(host.parse().unwrap()));
+            let err = result.err().unwrap_or_else(|| panic!("{} resolved", host));
+            assert!(err.to_string().contains("Network access denied"), "{}", err);
+        }
+    }
 }
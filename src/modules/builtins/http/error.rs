@@ -1,3 +1,4 @@
+use crate::error::StructuredError;
 use std::fmt;
 
 #[derive(Debug)]
@@ -8,7 +9,9 @@ pub enum HttpError {
     RequestError(String),
     ResponseError(u16, String),
     JsonParseError(String),
+    DecodeError(String),
     RuntimeError(String),
+    SecurityError(String),
 }
 
 impl fmt::Display for HttpError {
@@ -20,13 +23,37 @@ impl fmt::Display for HttpError {
             HttpError::RequestError(msg) => write!(f, "Request error: {}", msg),
             HttpError::ResponseError(status, msg) => write!(f, "HTTP {} {}", status, msg),
             HttpError::JsonParseError(msg) => write!(f, "JSON parse error: {}", msg),
+            HttpError::DecodeError(msg) => write!(f, "Failed to decode {}", msg),
             HttpError::RuntimeError(msg) => write!(f, "Runtime error: {}", msg),
+            HttpError::SecurityError(msg) => write!(f, "Security error: {}", msg),
         }
     }
 }
 
 impl std::error::Error for HttpError {}
 
+impl HttpError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            HttpError::NetworkError(_) => "ENETWORK",
+            HttpError::TimeoutError => "ETIMEDOUT",
+            HttpError::InvalidUrl(_) => "EINVALIDURL",
+            HttpError::RequestError(_) => "EREQUEST",
+            HttpError::ResponseError(_, _) => "ERESPONSE",
+            HttpError::JsonParseError(_) => "EJSON",
+            HttpError::DecodeError(_) => "EDECODE",
+            HttpError::RuntimeError(_) => "ERUNTIME",
+            HttpError::SecurityError(_) => "EACCES",
+        }
+    }
+}
+
+impl From<HttpError> for StructuredError {
+    fn from(err: HttpError) -> Self {
+        StructuredError::new("http", err.code(), err.to_string())
+    }
+}
+
 #[cfg(feature = "http")]
 impl From<reqwest::Error> for HttpError {
     fn from(err: reqwest::Error) -> Self {
@@ -1,30 +1,122 @@
 use mlua::{Lua, Table, Value};
 use serde_json::Value as JsonValue;
+#[cfg(feature = "http")]
+use std::cell::RefCell;
 use std::collections::HashMap;
+#[cfg(feature = "http")]
+use std::path::Path;
+#[cfg(feature = "http")]
+use std::rc::Rc;
 use std::sync::Arc;
+#[cfg(feature = "http")]
+use std::time::Duration;
+
+#[cfg(feature = "http")]
+use super::forms::encode_form_urlencoded;
+#[cfg(feature = "http")]
+use super::server::{HttpServer, NextRequest, PendingRequest, ServerRequest, ServerResponse};
+#[cfg(feature = "http")]
+use super::websocket::{Received, WebSocket, WsMessage};
+
+use super::{
+    AuthOption, DownloadOptions, FetchRequest, FileField, HttpClient, HttpError, HttpResponse,
+    RequestHeaders,
+};
+#[cfg(feature = "http")]
+use crate::engine::timeout::Deadline;
+#[cfg(feature = "http")]
+use crate::lua::error::{structured_error, wrap_module_errors};
+#[cfg(feature = "http")]
+use crate::lua::require::lua_to_json;
+#[cfg(feature = "http")]
+use crate::lua::security::{check_file_access, SecurityPolicy};
+#[cfg(feature = "http")]
+use crate::modules::builtins::process::dispatch_signals;
+#[cfg(feature = "http")]
+use mlua::{Function, RegistryKey, UserData, UserDataFields, UserDataMethods};
 
-use super::{AuthOption, FileField, HttpClient, HttpResponse};
+/// How often `server:serve()` wakes to run signal handlers and check the timeout.
+#[cfg(feature = "http")]
+const SERVE_POLL_INTERVAL: Duration = Duration::from_millis(50);
 
 #[cfg(feature = "http")]
 pub fn create_http_module(lua: &Lua) -> mlua::Result<Table> {
     let http_table = lua.create_table()?;
 
+    register_client_functions(lua, &http_table, create_client(lua)?)?;
+    register_new_client(lua, &http_table)?;
+    register_create_server(lua, &http_table)?;
+
+    wrap_module_errors(lua, &http_table)?;
+    Ok(http_table)
+}
+
+/// A client that follows the network policy and script deadline of `lua`.
+#[cfg(feature = "http")]
+fn create_client(lua: &Lua) -> mlua::Result<Arc<HttpClient>> {
     let client = Arc::new(HttpClient::new().map_err(|e| mlua::Error::external(e))?);
+    if let Some(policy) = lua.app_data_ref::<SecurityPolicy>() {
+        client
+            .set_network_policy(policy.network_policy.clone())
+            .map_err(mlua::Error::external)?;
+    }
+    if let Some(deadline) = Deadline::current(lua) {
+        client.set_deadline(deadline);
+    }
+    Ok(client)
+}
+
+/// The request functions shared by the module and the tables returned by
+/// `http.newClient`, all sending through `client`.
+#[cfg(feature = "http")]
+fn register_client_functions(
+    lua: &Lua,
+    table: &Table,
+    client: Arc<HttpClient>,
+) -> mlua::Result<()> {
+    let lua_client = LuaClient::new(client.clone());
+    register_get(lua, table, lua_client.clone())?;
+    register_post(lua, table, lua_client.clone())?;
+    register_put(lua, table, lua_client.clone())?;
+    register_delete(lua, table, lua_client.clone())?;
+    register_patch(lua, table, lua_client.clone())?;
+    register_head(lua, table, lua_client.clone())?;
+    register_fetch(lua, table, lua_client.clone())?;
+    register_fetch_all(lua, table, lua_client.clone())?;
+    register_post_json(lua, table, lua_client.clone())?;
+    register_put_json(lua, table, lua_client.clone())?;
+    register_post_form(lua, table, lua_client.clone())?;
+    register_interceptors(lua, table, lua_client)?;
+    register_upload_file(lua, table, client.clone())?;
+    register_download(lua, table, client.clone())?;
+    register_get_cookies(lua, table, client.clone())?;
+    register_set_user_agent(lua, table, client.clone())?;
+    register_set_default_headers(lua, table, client.clone())?;
+    register_set_host_resolver(lua, table, client.clone())?;
+    register_connect_websocket(lua, table, client)?;
+    Ok(())
+}
 
-    register_get(lua, &http_table, client.clone())?;
-    register_post(lua, &http_table, client.clone())?;
-    register_put(lua, &http_table, client.clone())?;
-    register_delete(lua, &http_table, client.clone())?;
-    register_patch(lua, &http_table, client.clone())?;
-    register_head(lua, &http_table, client.clone())?;
-    register_fetch(lua, &http_table, client.clone())?;
-    register_post_json(lua, &http_table, client.clone())?;
-    register_put_json(lua, &http_table, client.clone())?;
-    register_post_form(lua, &http_table, client.clone())?;
-    register_upload_file(lua, &http_table, client.clone())?;
-    register_get_cookies(lua, &http_table, client)?;
+#[cfg(feature = "http")]
+fn register_new_client(lua: &Lua, table: &Table) -> mlua::Result<()> {
+    let new_client_fn = lua.create_function(|lua, options: Option<Table>| {
+        let client = create_client(lua)?;
+        if let Some(opts) = options {
+            if let Some(headers) = opts.get::<_, Option<Table>>("headers")? {
+                client.set_default_headers(parse_default_headers(headers)?);
+            }
+            if let Some(user_agent) = opts.get::<_, Option<String>>("userAgent")? {
+                client.set_user_agent(&user_agent);
+            }
+        }
 
-    Ok(http_table)
+        let client_table = lua.create_table()?;
+        register_client_functions(lua, &client_table, client)?;
+        wrap_module_errors(lua, &client_table)?;
+        Ok(client_table)
+    })?;
+    table.set("newClient", new_client_fn)?;
+    Ok(())
 }
 
 #[cfg(not(feature = "http"))]
@@ -45,163 +137,426 @@ pub fn create_http_module(lua: &Lua) -> mlua::Result<Table> {
     http_table.set("patch", error_fn.clone())?;
     http_table.set("head", error_fn.clone())?;
     http_table.set("fetch", error_fn.clone())?;
+    http_table.set("fetchAll", error_fn.clone())?;
+    http_table.set("download", error_fn.clone())?;
     http_table.set("postJson", error_fn.clone())?;
-    http_table.set("putJson", error_fn)?;
+    http_table.set("putJson", error_fn.clone())?;
+    http_table.set("setUserAgent", error_fn.clone())?;
+    http_table.set("setDefaultHeaders", error_fn.clone())?;
+    http_table.set("newClient", error_fn.clone())?;
+    http_table.set("onRequest", error_fn.clone())?;
+    http_table.set("onResponse", error_fn.clone())?;
+    http_table.set("setHostResolver", error_fn.clone())?;
+    http_table.set("connectWebSocket", error_fn.clone())?;
+    http_table.set("createServer", error_fn)?;
 
     Ok(http_table)
 }
 
+/// A client as seen from Lua: the HTTP client plus the hooks added with
+/// `onRequest` and `onResponse`.
 #[cfg(feature = "http")]
-fn register_get(lua: &Lua, table: &Table, client: Arc<HttpClient>) -> mlua::Result<()> {
+#[derive(Clone)]
+struct LuaClient {
+    http: Arc<HttpClient>,
+    interceptors: Rc<RefCell<Interceptors>>,
+}
+
+/// Hook functions kept in the Lua registry, run in the order they were added.
+#[cfg(feature = "http")]
+#[derive(Default)]
+struct Interceptors {
+    request: Vec<RegistryKey>,
+    response: Vec<RegistryKey>,
+}
+
+/// A request about to be sent, which request hooks may change.
+#[cfg(feature = "http")]
+struct OutgoingRequest {
+    method: String,
+    url: String,
+    headers: RequestHeaders,
+    body: Option<String>,
+    timeout: Option<u64>,
+    proxy: Option<String>,
+}
+
+#[cfg(feature = "http")]
+impl OutgoingRequest {
+    fn new(method: &str, url: String) -> Self {
+        Self {
+            method: method.to_string(),
+            url,
+            headers: HashMap::new(),
+            body: None,
+            timeout: None,
+            proxy: None,
+        }
+    }
+
+    fn with(mut self, body: Option<String>, headers: Option<RequestHeaders>) -> Self {
+        self.body = body;
+        self.headers = headers.unwrap_or_default();
+        self
+    }
+}
+
+#[cfg(feature = "http")]
+impl LuaClient {
+    fn new(http: Arc<HttpClient>) -> Self {
+        Self {
+            http,
+            interceptors: Rc::new(RefCell::new(Interceptors::default())),
+        }
+    }
+
+    /// Run the request hooks, send the request, then run the response hooks
+    /// on the response table that is returned.
+    fn send<'lua>(&self, lua: &'lua Lua, request: OutgoingRequest) -> mlua::Result<Table<'lua>> {
+        let request = self.run_request_hooks(lua, request)?;
+        let response = match request.proxy {
+            Some(proxy) => {
+                self.http
+                    .get_with_options(&request.url, Some(proxy), None, Some(request.headers))
+            }
+            None => self.http.fetch(
+                &request.method,
+                &request.url,
+                request.body,
+                Some(request.headers),
+                request.timeout,
+            ),
+        }
+        .map_err(structured_error)?;
+
+        self.respond(lua, response)
+    }
+
+    /// Run the request hooks on each request, send them all concurrently,
+    /// then run the response hooks on each response. A request that fails
+    /// becomes `{ error = { message, code, kind } }` in its slot.
+    fn send_all<'lua>(
+        &self,
+        lua: &'lua Lua,
+        requests: Vec<OutgoingRequest>,
+        concurrency: Option<usize>,
+    ) -> mlua::Result<Table<'lua>> {
+        let requests = requests
+            .into_iter()
+            .map(|request| {
+                let request = self.run_request_hooks(lua, request)?;
+                Ok(FetchRequest {
+                    method: request.method,
+                    url: request.url,
+                    body: request.body,
+                    headers: Some(request.headers),
+                    timeout: request.timeout,
+                })
+            })
+            .collect::<mlua::Result<Vec<_>>>()?;
+
+        let responses = self
+            .http
+            .fetch_all(requests, concurrency)
+            .map_err(structured_error)?;
+
+        let results = lua.create_table()?;
+        for (index, response) in responses.into_iter().enumerate() {
+            let entry = match response {
+                Ok(response) => self.respond(lua, response)?,
+                Err(e) => {
+                    let error = lua.create_table()?;
+                    error.set("message", e.to_string())?;
+                    error.set("code", e.code())?;
+                    error.set("kind", "http")?;
+                    let entry = lua.create_table()?;
+                    entry.set("error", error)?;
+                    entry
+                }
+            };
+            results.set(index + 1, entry)?;
+        }
+        Ok(results)
+    }
+
+    /// The response table for `response`, after the response hooks have run.
+    fn respond<'lua>(&self, lua: &'lua Lua, response: HttpResponse) -> mlua::Result<Table<'lua>> {
+        let table = create_response_table(lua, response)?;
+        let hooks: Vec<Function> = self.hooks(lua, |interceptors| &interceptors.response)?;
+        for hook in hooks {
+            hook.call::<_, ()>(table.clone())?;
+        }
+        Ok(table)
+    }
+
+    fn run_request_hooks(
+        &self,
+        lua: &Lua,
+        mut request: OutgoingRequest,
+    ) -> mlua::Result<OutgoingRequest> {
+        let hooks: Vec<Function> = self.hooks(lua, |interceptors| &interceptors.request)?;
+        if hooks.is_empty() {
+            return Ok(request);
+        }
+
+        let table = lua.create_table()?;
+        table.set("method", request.method.as_str())?;
+        table.set("url", request.url.as_str())?;
+        let headers = lua.create_table()?;
+        for (name, value) in &request.headers {
+            match value {
+                Some(value) => headers.set(name.as_str(), value.as_str())?,
+                None => headers.set(name.as_str(), false)?,
+            }
+        }
+        table.set("headers", headers)?;
+        table.set("body", request.body.as_deref())?;
+
+        for hook in hooks {
+            hook.call::<_, ()>(table.clone())?;
+        }
+
+        request.method = table.get::<_, String>("method")?.to_uppercase();
+        request.url = table.get("url")?;
+        request.headers = parse_headers(&table)?.unwrap_or_default();
+        request.body = table.get("body")?;
+        Ok(request)
+    }
+
+    fn hooks<'lua>(
+        &self,
+        lua: &'lua Lua,
+        select: impl Fn(&Interceptors) -> &Vec<RegistryKey>,
+    ) -> mlua::Result<Vec<Function<'lua>>> {
+        let interceptors = self.interceptors.borrow();
+        select(&interceptors)
+            .iter()
+            .map(|key| lua.registry_value(key))
+            .collect()
+    }
+}
+
+#[cfg(feature = "http")]
+fn register_interceptors(lua: &Lua, table: &Table, client: LuaClient) -> mlua::Result<()> {
+    let request_client = client.clone();
+    let on_request_fn =
+        lua.create_function(move |lua, (first, hook): (Value, Option<Function>)| {
+            let key = lua.create_registry_value(hook_argument(first, hook)?)?;
+            request_client.interceptors.borrow_mut().request.push(key);
+            Ok(())
+        })?;
+    table.set("onRequest", on_request_fn)?;
+
+    let on_response_fn =
+        lua.create_function(move |lua, (first, hook): (Value, Option<Function>)| {
+            let key = lua.create_registry_value(hook_argument(first, hook)?)?;
+            client.interceptors.borrow_mut().response.push(key);
+            Ok(())
+        })?;
+    table.set("onResponse", on_response_fn)?;
+    Ok(())
+}
+
+/// The hook passed to `onRequest`/`onResponse`, called either as
+/// `client:onRequest(fn)` or `client.onRequest(fn)`.
+#[cfg(feature = "http")]
+fn hook_argument<'lua>(
+    first: Value<'lua>,
+    hook: Option<Function<'lua>>,
+) -> mlua::Result<Function<'lua>> {
+    match (first, hook) {
+        (_, Some(hook)) => Ok(hook),
+        (Value::Function(hook), None) => Ok(hook),
+        _ => Err(mlua::Error::RuntimeError(
+            "expected a hook function".to_string(),
+        )),
+    }
+}
+
+#[cfg(feature = "http")]
+fn register_get(lua: &Lua, table: &Table, client: LuaClient) -> mlua::Result<()> {
     let get_fn = lua.create_function(move |lua, (url, options): (String, Option<Table>)| {
+        let mut request = OutgoingRequest::new("GET", url);
         if let Some(opts) = options {
-            let proxy = opts.get::<_, Option<String>>("proxy").ok().flatten();
-            let auth = parse_auth_options(&opts)?;
-
-            let response = client
-                .get_with_options(&url, proxy, auth)
-                .map_err(|e| mlua::Error::external(e))?;
-            create_response_table(lua, response)
-        } else {
-            let response = client.get(&url).map_err(|e| mlua::Error::external(e))?;
-            create_response_table(lua, response)
+            request.proxy = opts.get::<_, Option<String>>("proxy").ok().flatten();
+            request.headers = parse_headers(&opts)?.unwrap_or_default();
+            if let Some(auth) = parse_auth_options(&opts)? {
+                request
+                    .headers
+                    .insert("Authorization".to_string(), Some(auth.to_header_value()));
+            }
         }
+        client.send(lua, request)
     })?;
     table.set("get", get_fn)?;
     Ok(())
 }
 
 #[cfg(feature = "http")]
-fn register_post(lua: &Lua, table: &Table, client: Arc<HttpClient>) -> mlua::Result<()> {
+fn register_post(lua: &Lua, table: &Table, client: LuaClient) -> mlua::Result<()> {
     let post_fn = lua.create_function(move |lua, (url, options): (String, Option<Table>)| {
-        let (body, headers) = parse_request_options(options)?;
-        let response = client
-            .post(&url, body, headers)
-            .map_err(|e| mlua::Error::external(e))?;
-        create_response_table(lua, response)
+        let (body, headers) = parse_request_options(lua, options)?;
+        client.send(lua, OutgoingRequest::new("POST", url).with(body, headers))
     })?;
     table.set("post", post_fn)?;
     Ok(())
 }
 
 #[cfg(feature = "http")]
-fn register_put(lua: &Lua, table: &Table, client: Arc<HttpClient>) -> mlua::Result<()> {
+fn register_put(lua: &Lua, table: &Table, client: LuaClient) -> mlua::Result<()> {
     let put_fn = lua.create_function(move |lua, (url, options): (String, Option<Table>)| {
-        let (body, headers) = parse_request_options(options)?;
-        let response = client
-            .put(&url, body, headers)
-            .map_err(|e| mlua::Error::external(e))?;
-        create_response_table(lua, response)
+        let (body, headers) = parse_request_options(lua, options)?;
+        client.send(lua, OutgoingRequest::new("PUT", url).with(body, headers))
     })?;
     table.set("put", put_fn)?;
     Ok(())
 }
 
 #[cfg(feature = "http")]
-fn register_delete(lua: &Lua, table: &Table, client: Arc<HttpClient>) -> mlua::Result<()> {
+fn register_delete(lua: &Lua, table: &Table, client: LuaClient) -> mlua::Result<()> {
     let delete_fn = lua.create_function(move |lua, (url, options): (String, Option<Table>)| {
         let headers = options.and_then(|opts| parse_headers(&opts).ok().flatten());
-        let response = client
-            .delete(&url, headers)
-            .map_err(|e| mlua::Error::external(e))?;
-        create_response_table(lua, response)
+        client.send(lua, OutgoingRequest::new("DELETE", url).with(None, headers))
     })?;
     table.set("delete", delete_fn)?;
     Ok(())
 }
 
 #[cfg(feature = "http")]
-fn register_patch(lua: &Lua, table: &Table, client: Arc<HttpClient>) -> mlua::Result<()> {
+fn register_patch(lua: &Lua, table: &Table, client: LuaClient) -> mlua::Result<()> {
     let patch_fn = lua.create_function(move |lua, (url, options): (String, Option<Table>)| {
-        let (body, headers) = parse_request_options(options)?;
-
-        let method = "PATCH";
-        let response = client
-            .fetch(method, &url, body, headers, None)
-            .map_err(|e| mlua::Error::external(e))?;
-        create_response_table(lua, response)
+        let (body, headers) = parse_request_options(lua, options)?;
+        client.send(lua, OutgoingRequest::new("PATCH", url).with(body, headers))
     })?;
     table.set("patch", patch_fn)?;
     Ok(())
 }
 
 #[cfg(feature = "http")]
-fn register_head(lua: &Lua, table: &Table, client: Arc<HttpClient>) -> mlua::Result<()> {
+fn register_head(lua: &Lua, table: &Table, client: LuaClient) -> mlua::Result<()> {
     let head_fn = lua.create_function(move |lua, (url, options): (String, Option<Table>)| {
         let headers = options.and_then(|opts| parse_headers(&opts).ok().flatten());
-
-        let method = "HEAD";
-        let response = client
-            .fetch(method, &url, None, headers, None)
-            .map_err(|e| mlua::Error::external(e))?;
-        create_response_table(lua, response)
+        client.send(lua, OutgoingRequest::new("HEAD", url).with(None, headers))
     })?;
     table.set("head", head_fn)?;
     Ok(())
 }
 
 #[cfg(feature = "http")]
-fn register_fetch(lua: &Lua, table: &Table, client: Arc<HttpClient>) -> mlua::Result<()> {
+fn register_fetch(lua: &Lua, table: &Table, client: LuaClient) -> mlua::Result<()> {
     let fetch_fn = lua.create_function(move |lua, (url, options): (String, Option<Table>)| {
-        let opts = parse_fetch_options(options)?;
-        let response = client
-            .fetch(&opts.method, &url, opts.body, opts.headers, opts.timeout)
-            .map_err(|e| mlua::Error::external(e))?;
-        create_response_table(lua, response)
+        let opts = parse_fetch_options(lua, options)?;
+        let mut request = OutgoingRequest::new(&opts.method, url).with(opts.body, opts.headers);
+        request.timeout = opts.timeout;
+        let table = client.send(lua, request)?;
+        if opts.json_response {
+            let body: String = table.get("body")?;
+            table.set("body", parse_json_body(lua, &body)?)?;
+        }
+        Ok(table)
     })?;
     table.set("fetch", fetch_fn)?;
     Ok(())
 }
 
 #[cfg(feature = "http")]
-fn register_post_json(lua: &Lua, table: &Table, client: Arc<HttpClient>) -> mlua::Result<()> {
-    let post_json_fn = lua.create_function(move |lua, (url, data): (String, Table)| {
-        let json_value = lua_table_to_json(lua, &data)?;
-        let body = serde_json::to_string(&json_value).map_err(|e| mlua::Error::external(e))?;
-
-        let mut headers = HashMap::new();
-        headers.insert("Content-Type".to_string(), "application/json".to_string());
+fn register_fetch_all(lua: &Lua, table: &Table, client: LuaClient) -> mlua::Result<()> {
+    let fetch_all_fn =
+        lua.create_function(move |lua, (urls, options): (Vec<String>, Option<Table>)| {
+            let concurrency = match &options {
+                Some(opts) => match opts.get::<_, Option<i64>>("concurrency")? {
+                    Some(n) if n >= 1 => Some(n as usize),
+                    Some(_) => {
+                        return Err(mlua::Error::RuntimeError(
+                            "Option 'concurrency' must be at least 1".to_string(),
+                        ))
+                    }
+                    None => None,
+                },
+                None => None,
+            };
+            let opts = parse_fetch_options(lua, options)?;
+            let requests = urls
+                .into_iter()
+                .map(|url| {
+                    let mut request = OutgoingRequest::new(&opts.method, url)
+                        .with(opts.body.clone(), opts.headers.clone());
+                    request.timeout = opts.timeout;
+                    request
+                })
+                .collect();
+
+            let results = client.send_all(lua, requests, concurrency)?;
+            if opts.json_response {
+                for entry in results.clone().sequence_values::<Table>() {
+                    let entry = entry?;
+                    if let Some(body) = entry.get::<_, Option<String>>("body")? {
+                        entry.set("body", parse_json_body(lua, &body)?)?;
+                    }
+                }
+            }
+            Ok(results)
+        })?;
+    table.set("fetchAll", fetch_all_fn)?;
+    Ok(())
+}
 
-        let response = client
-            .post(&url, Some(body), Some(headers))
-            .map_err(|e| mlua::Error::external(e))?;
-        create_response_table(lua, response)
+#[cfg(feature = "http")]
+fn register_post_json(lua: &Lua, table: &Table, client: LuaClient) -> mlua::Result<()> {
+    let post_json_fn = lua.create_function(move |lua, (url, data): (String, Table)| {
+        client.send(lua, json_request(lua, "POST", url, data)?)
     })?;
     table.set("postJson", post_json_fn)?;
     Ok(())
 }
 
 #[cfg(feature = "http")]
-fn register_put_json(lua: &Lua, table: &Table, client: Arc<HttpClient>) -> mlua::Result<()> {
+fn register_put_json(lua: &Lua, table: &Table, client: LuaClient) -> mlua::Result<()> {
     let put_json_fn = lua.create_function(move |lua, (url, data): (String, Table)| {
-        let json_value = lua_table_to_json(lua, &data)?;
-        let body = serde_json::to_string(&json_value).map_err(|e| mlua::Error::external(e))?;
-
-        let mut headers = HashMap::new();
-        headers.insert("Content-Type".to_string(), "application/json".to_string());
-
-        let response = client
-            .put(&url, Some(body), Some(headers))
-            .map_err(|e| mlua::Error::external(e))?;
-        create_response_table(lua, response)
+        client.send(lua, json_request(lua, "PUT", url, data)?)
     })?;
     table.set("putJson", put_json_fn)?;
     Ok(())
 }
 
+/// A request with `data` encoded as its JSON body.
 #[cfg(feature = "http")]
-fn register_post_form(lua: &Lua, table: &Table, client: Arc<HttpClient>) -> mlua::Result<()> {
+fn json_request(
+    lua: &Lua,
+    method: &str,
+    url: String,
+    data: Table,
+) -> mlua::Result<OutgoingRequest> {
+    let json_value = lua_to_json(lua, Value::Table(data))?;
+    let body = serde_json::to_string(&json_value).map_err(mlua::Error::external)?;
+
+    let mut request = OutgoingRequest::new(method, url);
+    request.body = Some(body);
+    request.headers.insert(
+        "Content-Type".to_string(),
+        Some("application/json".to_string()),
+    );
+    Ok(request)
+}
+
+#[cfg(feature = "http")]
+fn register_post_form(lua: &Lua, table: &Table, client: LuaClient) -> mlua::Result<()> {
     let post_form_fn = lua.create_function(move |lua, (url, fields): (String, Table)| {
         let mut field_map = HashMap::new();
         for pair in fields.pairs::<String, String>() {
             let (key, value) = pair?;
             field_map.insert(key, value);
         }
-
-        let response = client
-            .post_form(&url, field_map)
-            .map_err(|e| mlua::Error::external(e))?;
-        create_response_table(lua, response)
+        let body = encode_form_urlencoded(field_map)
+            .map_err(|e| structured_error(HttpError::RequestError(e)))?;
+
+        let mut request = OutgoingRequest::new("POST", url);
+        request.body = Some(body);
+        request.headers.insert(
+            "Content-Type".to_string(),
+            Some("application/x-www-form-urlencoded".to_string()),
+        );
+        client.send(lua, request)
     })?;
     table.set("postForm", post_form_fn)?;
     Ok(())
@@ -237,19 +592,45 @@ fn register_upload_file(lua: &Lua, table: &Table, client: Arc<HttpClient>) -> ml
 
         let response = client
             .upload_file(&url, fields, file)
-            .map_err(|e| mlua::Error::external(e))?;
+            .map_err(structured_error)?;
         create_response_table(lua, response)
     })?;
     table.set("uploadFile", upload_fn)?;
     Ok(())
 }
 
+#[cfg(feature = "http")]
+fn register_download(lua: &Lua, table: &Table, client: Arc<HttpClient>) -> mlua::Result<()> {
+    let download_fn = lua.create_function(
+        move |lua, (url, dest, options): (String, String, Option<Table>)| {
+            check_file_access(lua, &dest, "write").map_err(structured_error)?;
+            let mut download_options = DownloadOptions::default();
+            if let Some(opts) = options {
+                download_options.resume = opts.get::<_, Option<bool>>("resume")?.unwrap_or(false);
+                download_options.retries = opts.get::<_, Option<u32>>("retries")?.unwrap_or(0);
+                download_options.headers = parse_headers(&opts)?;
+                download_options.timeout = opts.get("timeout")?;
+            }
+
+            let download = client
+                .download(&url, Path::new(&dest), &download_options)
+                .map_err(structured_error)?;
+            let result = lua.create_table()?;
+            result.set("status", download.status)?;
+            result.set("size", download.size)?;
+            result.set("downloaded", download.downloaded)?;
+            result.set("resumed", download.resumed)?;
+            Ok(result)
+        },
+    )?;
+    table.set("download", download_fn)?;
+    Ok(())
+}
+
 #[cfg(feature = "http")]
 fn register_get_cookies(lua: &Lua, table: &Table, client: Arc<HttpClient>) -> mlua::Result<()> {
     let get_cookies_fn = lua.create_function(move |lua, url: String| {
-        let cookies = client
-            .get_cookies(&url)
-            .map_err(|e| mlua::Error::external(e))?;
+        let cookies = client.get_cookies(&url).map_err(structured_error)?;
 
         let result = lua.create_table()?;
         for (name, value) in cookies {
@@ -261,6 +642,50 @@ fn register_get_cookies(lua: &Lua, table: &Table, client: Arc<HttpClient>) -> ml
     Ok(())
 }
 
+#[cfg(feature = "http")]
+fn register_set_user_agent(lua: &Lua, table: &Table, client: Arc<HttpClient>) -> mlua::Result<()> {
+    let set_user_agent_fn = lua.create_function(move |_, user_agent: String| {
+        client.set_user_agent(&user_agent);
+        Ok(())
+    })?;
+    table.set("setUserAgent", set_user_agent_fn)?;
+    Ok(())
+}
+
+#[cfg(feature = "http")]
+fn register_set_default_headers(
+    lua: &Lua,
+    table: &Table,
+    client: Arc<HttpClient>,
+) -> mlua::Result<()> {
+    let set_default_headers_fn = lua.create_function(move |_, headers_table: Table| {
+        client.set_default_headers(parse_default_headers(headers_table)?);
+        Ok(())
+    })?;
+    table.set("setDefaultHeaders", set_default_headers_fn)?;
+    Ok(())
+}
+
+#[cfg(feature = "http")]
+fn register_set_host_resolver(
+    lua: &Lua,
+    table: &Table,
+    client: Arc<HttpClient>,
+) -> mlua::Result<()> {
+    let set_host_resolver_fn = lua.create_function(move |_, overrides_table: Table| {
+        let mut overrides = HashMap::new();
+        for pair in overrides_table.pairs::<String, String>() {
+            let (host, addr) = pair?;
+            overrides.insert(host, addr);
+        }
+        client
+            .set_host_resolver(overrides)
+            .map_err(structured_error)
+    })?;
+    table.set("setHostResolver", set_host_resolver_fn)?;
+    Ok(())
+}
+
 #[cfg(not(feature = "http"))]
 fn register_get_cookies(lua: &Lua, table: &Table, _client: Arc<HttpClient>) -> mlua::Result<()> {
     let error_fn = lua.create_function(|_, _: String| {
@@ -273,6 +698,312 @@ fn register_get_cookies(lua: &Lua, table: &Table, _client: Arc<HttpClient>) -> m
     Ok(())
 }
 
+#[cfg(feature = "http")]
+fn register_connect_websocket(
+    lua: &Lua,
+    table: &Table,
+    client: Arc<HttpClient>,
+) -> mlua::Result<()> {
+    let connect_fn = lua.create_function(move |_, (url, options): (String, Option<Table>)| {
+        let headers = match &options {
+            Some(opts) => parse_headers(opts)?,
+            None => None,
+        };
+        let socket = client
+            .connect_websocket(&url, headers)
+            .map_err(structured_error)?;
+        Ok(WebSocketHandle {
+            socket,
+            _client: client.clone(),
+        })
+    })?;
+    table.set("connectWebSocket", connect_fn)?;
+    Ok(())
+}
+
+/// Handle returned by `http.connectWebSocket`.
+#[cfg(feature = "http")]
+struct WebSocketHandle {
+    socket: WebSocket,
+    /// The socket runs on this client's runtime, so keep it alive.
+    _client: Arc<HttpClient>,
+}
+
+#[cfg(feature = "http")]
+impl UserData for WebSocketHandle {
+    fn add_fields<'lua, F: UserDataFields<'lua, Self>>(fields: &mut F) {
+        fields.add_field_method_get("closed", |_, this| Ok(this.socket.is_closed()));
+    }
+
+    fn add_methods<'lua, M: UserDataMethods<'lua, Self>>(methods: &mut M) {
+        methods.add_method_mut(
+            "send",
+            |_, this, (data, options): (mlua::String, Option<Table>)| {
+                let binary = match options {
+                    Some(opts) => opts.get::<_, Option<bool>>("binary")?.unwrap_or(false),
+                    None => false,
+                };
+                this.socket
+                    .send(data.as_bytes().to_vec(), binary)
+                    .map_err(structured_error)
+            },
+        );
+
+        // Returns the message and "text" or "binary", or nil and "timeout" or "closed"
+        methods.add_method_mut("receive", |lua, this, timeout: Option<u64>| {
+            let timeout = timeout.map(Duration::from_millis);
+            let (message, kind) = match this.socket.receive(timeout).map_err(structured_error)? {
+                Received::Message(WsMessage::Text(text)) => {
+                    (Value::String(lua.create_string(&text)?), "text")
+                }
+                Received::Message(WsMessage::Binary(data)) => {
+                    (Value::String(lua.create_string(&data)?), "binary")
+                }
+                Received::Timeout => (Value::Nil, "timeout"),
+                Received::Closed => (Value::Nil, "closed"),
+            };
+            Ok((message, kind))
+        });
+
+        methods.add_method_mut("close", |_, this, ()| {
+            this.socket.close().map_err(structured_error)
+        });
+    }
+}
+
+#[cfg(feature = "http")]
+fn register_create_server(lua: &Lua, table: &Table) -> mlua::Result<()> {
+    let create_server_fn = lua.create_function(|lua, handler: Function| {
+        Ok(ServerHandle {
+            server: None,
+            handler: lua.create_registry_value(handler)?,
+        })
+    })?;
+    table.set("createServer", create_server_fn)?;
+    Ok(())
+}
+
+/// Handle returned by `http.createServer`. Requests queue up until `serve`
+/// or `poll` runs the handler for them on the script's thread.
+#[cfg(feature = "http")]
+struct ServerHandle {
+    server: Option<HttpServer>,
+    handler: RegistryKey,
+}
+
+#[cfg(feature = "http")]
+impl ServerHandle {
+    fn listening(&self) -> mlua::Result<&HttpServer> {
+        self.server.as_ref().ok_or_else(|| {
+            mlua::Error::external("server is not listening; call server:listen first")
+        })
+    }
+
+    /// Run the handler for one request and send its response.
+    fn dispatch(&self, lua: &Lua, pending: PendingRequest) -> mlua::Result<()> {
+        let handler: Function = lua.registry_value(&self.handler)?;
+        let request = create_server_request_table(lua, &pending.request)?;
+        let response = handler
+            .call::<_, Value>(request)
+            .and_then(|value| server_response_from_lua(lua, value));
+        match response {
+            Ok(response) => pending.respond(response),
+            Err(e) => {
+                // One failing request should not take the server down
+                eprintln!(
+                    "http server: {} {} failed: {}",
+                    pending.request.method, pending.request.path, e
+                );
+                pending.respond(ServerResponse::internal_error());
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "http")]
+impl UserData for ServerHandle {
+    fn add_fields<'lua, F: UserDataFields<'lua, Self>>(fields: &mut F) {
+        fields.add_field_method_get("port", |_, this| {
+            Ok(this.server.as_ref().map(|s| s.local_addr().port()))
+        });
+        fields.add_field_method_get("address", |_, this| {
+            Ok(this.server.as_ref().map(|s| s.local_addr().to_string()))
+        });
+        fields.add_field_method_get("closed", |_, this| {
+            Ok(this.server.as_ref().map_or(true, |s| s.is_closing()))
+        });
+    }
+
+    fn add_methods<'lua, M: UserDataMethods<'lua, Self>>(methods: &mut M) {
+        methods.add_method_mut(
+            "listen",
+            |lua, this, (port, host): (Option<u16>, Option<String>)| {
+                if this.server.is_some() {
+                    return Err(mlua::Error::external("server is already listening"));
+                }
+                if let Some(policy) = lua.app_data_ref::<SecurityPolicy>() {
+                    policy
+                        .network_policy
+                        .check_enabled()
+                        .map_err(structured_error)?;
+                }
+                let host = host.as_deref().unwrap_or("127.0.0.1");
+                let server = HttpServer::bind(host, port.unwrap_or(0)).map_err(structured_error)?;
+                let port = server.local_addr().port();
+                this.server = Some(server);
+                Ok(port)
+            },
+        );
+
+        methods.add_method("serve", |lua, this, ()| {
+            let server = this.listening()?;
+            loop {
+                match server.next_request(SERVE_POLL_INTERVAL) {
+                    NextRequest::Request(pending) => this.dispatch(lua, pending)?,
+                    NextRequest::Timeout => {}
+                    NextRequest::Closed => return Ok(()),
+                }
+                dispatch_signals(lua)?;
+                if let Some(deadline) = Deadline::current(lua).filter(|d| d.is_expired()) {
+                    return Err(mlua::Error::external(deadline.execution_error()));
+                }
+            }
+        });
+
+        methods.add_method("poll", |lua, this, timeout: Option<u64>| {
+            let server = this.listening()?;
+            let mut timeout = Duration::from_millis(timeout.unwrap_or(0));
+            let mut handled = 0;
+            while let NextRequest::Request(pending) = server.next_request(timeout) {
+                this.dispatch(lua, pending)?;
+                handled += 1;
+                timeout = Duration::ZERO;
+            }
+            Ok(handled)
+        });
+
+        methods.add_method("close", |_, this, ()| {
+            if let Some(server) = &this.server {
+                server.close();
+            }
+            Ok(())
+        });
+    }
+}
+
+#[cfg(feature = "http")]
+fn create_server_request_table<'lua>(
+    lua: &'lua Lua,
+    request: &ServerRequest,
+) -> mlua::Result<Table<'lua>> {
+    let table = lua.create_table()?;
+    table.set("method", request.method.as_str())?;
+    table.set("path", request.path.as_str())?;
+    let url = match &request.query {
+        Some(query) => format!("{}?{}", request.path, query),
+        None => request.path.clone(),
+    };
+    table.set("url", url)?;
+
+    let query = lua.create_table()?;
+    if let Some(raw) = &request.query {
+        for (key, value) in url::form_urlencoded::parse(raw.as_bytes()) {
+            query.set(key.as_ref(), value.as_ref())?;
+        }
+    }
+    table.set("query", query)?;
+
+    // Repeated headers are joined with ", " as HTTP allows
+    let headers = lua.create_table()?;
+    for (name, value) in &request.headers {
+        let joined = match headers.get::<_, Option<String>>(name.as_str())? {
+            Some(existing) => format!("{}, {}", existing, value),
+            None => value.clone(),
+        };
+        headers.set(name.as_str(), joined)?;
+    }
+    table.set("headers", headers)?;
+    table.set("body", lua.create_string(&request.body)?)?;
+    table.set("remoteAddr", request.remote_addr.to_string())?;
+    Ok(table)
+}
+
+/// A handler may return a `{status, headers, body | json}` table, a string
+/// body for a 200, or nothing for a 204.
+#[cfg(feature = "http")]
+fn server_response_from_lua(lua: &Lua, value: Value) -> mlua::Result<ServerResponse> {
+    let response = match value {
+        Value::Nil => ServerResponse::new(204, ""),
+        Value::String(body) => ServerResponse::new(200, body.as_bytes()),
+        Value::Table(table) => {
+            let status = table.get::<_, Option<u16>>("status")?.unwrap_or(200);
+            let mut response = ServerResponse::new(status, "");
+            if let Some(headers) = table.get::<_, Option<Table>>("headers")? {
+                for pair in headers.pairs::<String, Value>() {
+                    let (name, value) = pair?;
+                    match value {
+                        // Several values, e.g. more than one set-cookie
+                        Value::Table(values) => {
+                            for value in values.sequence_values::<String>() {
+                                response.headers.push((name.clone(), value?));
+                            }
+                        }
+                        other => {
+                            let value = lua
+                                .coerce_string(other)?
+                                .ok_or_else(|| {
+                                    mlua::Error::external(format!(
+                                        "header '{}' must be a string",
+                                        name
+                                    ))
+                                })?
+                                .to_str()?
+                                .to_string();
+                            response.headers.push((name, value));
+                        }
+                    }
+                }
+            }
+
+            let body = table.get::<_, Option<mlua::String>>("body")?;
+            let json = table.get::<_, Option<Table>>("json")?;
+            match (body, json) {
+                (Some(_), Some(_)) => {
+                    return Err(mlua::Error::RuntimeError(
+                        "Response fields 'body' and 'json' are mutually exclusive".to_string(),
+                    ))
+                }
+                (Some(body), None) => response.body = body.as_bytes().to_vec(),
+                (None, Some(data)) => {
+                    let json_value = lua_to_json(lua, Value::Table(data))?;
+                    response.body =
+                        serde_json::to_vec(&json_value).map_err(mlua::Error::external)?;
+                    if !response
+                        .headers
+                        .iter()
+                        .any(|(name, _)| name.eq_ignore_ascii_case("content-type"))
+                    {
+                        response
+                            .headers
+                            .push(("content-type".to_string(), "application/json".to_string()));
+                    }
+                }
+                (None, None) => {}
+            }
+            response
+        }
+        other => {
+            return Err(mlua::Error::external(format!(
+                "handler must return a table, string or nil, not {}",
+                other.type_name()
+            )))
+        }
+    };
+    response.validate().map_err(structured_error)?;
+    Ok(response)
+}
+
 fn create_response_table<'lua>(
     lua: &'lua Lua,
     response: HttpResponse,
@@ -295,6 +1026,12 @@ fn create_response_table<'lua>(
         lua.create_function(move |_, ()| Ok(body_for_text.clone()))?,
     )?;
 
+    let raw_body = response.raw_body.clone();
+    table.set(
+        "rawBody",
+        lua.create_function(move |lua, ()| lua.create_string(&raw_body))?,
+    )?;
+
     let body_for_json = response.body.clone();
     table.set(
         "json",
@@ -317,17 +1054,20 @@ fn create_response_table<'lua>(
 struct FetchOptions {
     method: String,
     body: Option<String>,
-    headers: Option<HashMap<String, String>>,
+    headers: Option<RequestHeaders>,
     timeout: Option<u64>,
+    /// `responseType = "json"`: decode the body instead of returning text.
+    json_response: bool,
 }
 
-fn parse_fetch_options(options: Option<Table>) -> mlua::Result<FetchOptions> {
+fn parse_fetch_options(lua: &Lua, options: Option<Table>) -> mlua::Result<FetchOptions> {
     let Some(opts) = options else {
         return Ok(FetchOptions {
             method: "GET".to_string(),
             body: None,
             headers: None,
             timeout: None,
+            json_response: false,
         });
     };
 
@@ -336,32 +1076,90 @@ fn parse_fetch_options(options: Option<Table>) -> mlua::Result<FetchOptions> {
         .unwrap_or_else(|| "GET".to_string())
         .to_uppercase();
 
-    let body = opts.get::<_, Option<String>>("body")?;
-    let headers = parse_headers(&opts)?;
+    let mut headers = parse_headers(&opts)?;
+    let body = parse_body(lua, &opts, &mut headers)?;
     let timeout = opts.get::<_, Option<u64>>("timeout")?;
+    let json_response = match opts.get::<_, Option<String>>("responseType")?.as_deref() {
+        None | Some("text") => false,
+        Some("json") => true,
+        Some(other) => {
+            return Err(mlua::Error::RuntimeError(format!(
+                "Option 'responseType' must be \"text\" or \"json\", not \"{}\"",
+                other
+            )))
+        }
+    };
 
     Ok(FetchOptions {
         method,
         body,
         headers,
         timeout,
+        json_response,
     })
 }
 
+/// Decode a response body for `responseType = "json"`. An empty body, as
+/// sent with 204 No Content, decodes to nil.
+fn parse_json_body<'lua>(lua: &'lua Lua, body: &str) -> mlua::Result<Value<'lua>> {
+    if body.trim().is_empty() {
+        return Ok(Value::Nil);
+    }
+    let json: JsonValue = serde_json::from_str(body)
+        .map_err(|e| structured_error(HttpError::JsonParseError(e.to_string())))?;
+    json_to_lua_value(lua, &json)
+}
+
 fn parse_request_options(
+    lua: &Lua,
     options: Option<Table>,
-) -> mlua::Result<(Option<String>, Option<HashMap<String, String>>)> {
+) -> mlua::Result<(Option<String>, Option<RequestHeaders>)> {
     let Some(opts) = options else {
         return Ok((None, None));
     };
 
-    let body = opts.get::<_, Option<String>>("body")?;
-    let headers = parse_headers(&opts)?;
+    let mut headers = parse_headers(&opts)?;
+    let body = parse_body(lua, &opts, &mut headers)?;
 
     Ok((body, headers))
 }
 
-fn parse_headers(opts: &Table) -> mlua::Result<Option<HashMap<String, String>>> {
+fn parse_body(
+    lua: &Lua,
+    opts: &Table,
+    headers: &mut Option<RequestHeaders>,
+) -> mlua::Result<Option<String>> {
+    let body = opts.get::<_, Option<String>>("body")?;
+    let json = opts.get::<_, Option<Table>>("json")?;
+
+    match (body, json) {
+        (Some(_), Some(_)) => Err(mlua::Error::RuntimeError(
+            "Options 'body' and 'json' are mutually exclusive".to_string(),
+        )),
+        (None, Some(data)) => {
+            let json_value = lua_to_json(lua, Value::Table(data))?;
+            let body = serde_json::to_string(&json_value).map_err(mlua::Error::external)?;
+
+            let headers_map = headers.get_or_insert_with(HashMap::new);
+            if !headers_map
+                .keys()
+                .any(|key| key.eq_ignore_ascii_case("content-type"))
+            {
+                headers_map.insert(
+                    "Content-Type".to_string(),
+                    Some("application/json".to_string()),
+                );
+            }
+
+            Ok(Some(body))
+        }
+        (body, None) => Ok(body),
+    }
+}
+
+/// The `headers` option of a request. A header set to `false` is not sent,
+/// even when it is one of the client's defaults.
+fn parse_headers(opts: &Table) -> mlua::Result<Option<RequestHeaders>> {
     let headers_table: Option<Table> = opts.get("headers")?;
 
     let Some(headers_table) = headers_table else {
@@ -369,8 +1167,21 @@ fn parse_headers(opts: &Table) -> mlua::Result<Option<HashMap<String, String>>>
     };
 
     let mut headers = HashMap::new();
-    for pair in headers_table.pairs::<String, String>() {
+    for pair in headers_table.pairs::<String, Value>() {
         let (key, value) = pair?;
+        let value = match value {
+            Value::Boolean(false) => None,
+            Value::String(value) => Some(value.to_str()?.to_string()),
+            Value::Integer(value) => Some(value.to_string()),
+            Value::Number(value) => Some(value.to_string()),
+            other => {
+                return Err(mlua::Error::RuntimeError(format!(
+                    "header '{}' must be a string or false, not {}",
+                    key,
+                    other.type_name()
+                )))
+            }
+        };
         headers.insert(key, value);
     }
 
@@ -381,6 +1192,17 @@ fn parse_headers(opts: &Table) -> mlua::Result<Option<HashMap<String, String>>>
     }
 }
 
+/// Default headers for a client, from a table of header names to values.
+#[cfg(feature = "http")]
+fn parse_default_headers(headers_table: Table) -> mlua::Result<HashMap<String, String>> {
+    let mut headers = HashMap::new();
+    for pair in headers_table.pairs::<String, String>() {
+        let (key, value) = pair?;
+        headers.insert(key, value);
+    }
+    Ok(headers)
+}
+
 #[cfg(feature = "http")]
 fn parse_auth_options(opts: &Table) -> mlua::Result<Option<AuthOption>> {
     if let Ok(auth_table) = opts.get::<_, Table>("auth") {
@@ -429,50 +1251,6 @@ fn json_to_lua_value<'lua>(lua: &'lua Lua, value: &JsonValue) -> mlua::Result<Va
     }
 }
 
-fn lua_table_to_json<'lua>(lua: &'lua Lua, table: &Table<'lua>) -> mlua::Result<JsonValue> {
-    let len = table.raw_len();
-
-    if len > 0 {
-        let mut array = Vec::new();
-        for i in 1..=len {
-            let value: Value = table.get(i)?;
-            array.push(lua_value_to_json(lua, value)?);
-        }
-        Ok(JsonValue::Array(array))
-    } else {
-        let mut object = serde_json::Map::new();
-        for pair in table.clone().pairs::<Value, Value>() {
-            let (key, value) = pair?;
-            let key_str = match key {
-                Value::String(s) => s.to_str()?.to_string(),
-                Value::Integer(i) => i.to_string(),
-                Value::Number(n) => n.to_string(),
-                _ => continue,
-            };
-            object.insert(key_str, lua_value_to_json(lua, value)?);
-        }
-        Ok(JsonValue::Object(object))
-    }
-}
-
-fn lua_value_to_json(lua: &Lua, value: Value) -> mlua::Result<JsonValue> {
-    match value {
-        Value::Nil => Ok(JsonValue::Null),
-        Value::Boolean(b) => Ok(JsonValue::Bool(b)),
-        Value::Integer(i) => Ok(JsonValue::Number(serde_json::Number::from(i))),
-        Value::Number(n) => {
-            if let Some(num) = serde_json::Number::from_f64(n) {
-                Ok(JsonValue::Number(num))
-            } else {
-                Ok(JsonValue::Null)
-            }
-        }
-        Value::String(s) => Ok(JsonValue::String(s.to_str()?.to_string())),
-        Value::Table(t) => lua_table_to_json(lua, &t),
-        _ => Ok(JsonValue::Null),
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -501,6 +1279,24 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "http")]
+    #[test]
+    fn test_installed_network_policy_applies_to_requests() {
+        let lua = Lua::new();
+        let mut policy = SecurityPolicy::default();
+        policy.network_policy.deny_private_ips = true;
+        policy.install(&lua);
+        let http = create_http_module(&lua).unwrap();
+        lua.globals().set("http", http).unwrap();
+
+        let err = lua
+            .load(r#"return http.get("http://127.0.0.1:9/")"#)
+            .exec()
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Security error"), "{}", err);
+    }
+
     #[test]
     fn test_json_to_lua_value() {
         let lua = Lua::new();
@@ -524,22 +1320,4 @@ mod tests {
             panic!("Expected table");
         }
     }
-
-    #[test]
-    fn test_lua_table_to_json() {
-        let lua = Lua::new();
-        let table = lua.create_table().unwrap();
-        table.set("name", "test").unwrap();
-        table.set("value", 42).unwrap();
-        table.set("active", true).unwrap();
-
-        let result = lua_table_to_json(&lua, &table);
-        assert!(result.is_ok());
-
-        let json = result.unwrap();
-        assert!(json.is_object());
-        assert_eq!(json["name"], "test");
-        assert_eq!(json["value"], 42);
-        assert_eq!(json["active"], true);
-    }
 }
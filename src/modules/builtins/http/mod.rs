@@ -11,9 +11,13 @@ pub mod response;
 
 #[cfg(feature = "http")]
 pub mod lua_bindings;
+#[cfg(feature = "http")]
+pub mod server;
+#[cfg(feature = "http")]
+pub mod websocket;
 
 pub use auth::AuthOption;
-pub use client::HttpClient;
+pub use client::{Download, DownloadOptions, FetchRequest, HttpClient, RequestHeaders};
 pub use error::HttpError;
 pub use forms::FileField;
 pub use response::HttpResponse;
@@ -54,12 +58,12 @@ impl BuiltinModule for HttpModule {
             "post": {
                 "__fn": "post",
                 "__desc": "HTTP POST request",
-                "__signature": "post(url: string, options?: {body?: string, headers?: table}) -> Response"
+                "__signature": "post(url: string, options?: {body?: string, json?: table, headers?: table}) -> Response"
             },
             "put": {
                 "__fn": "put",
                 "__desc": "HTTP PUT request",
-                "__signature": "put(url: string, options?: {body?: string, headers?: table}) -> Response"
+                "__signature": "put(url: string, options?: {body?: string, json?: table, headers?: table}) -> Response"
             },
             "delete": {
                 "__fn": "delete",
@@ -69,7 +73,7 @@ impl BuiltinModule for HttpModule {
             "patch": {
                 "__fn": "patch",
                 "__desc": "HTTP PATCH request",
-                "__signature": "patch(url: string, options?: {body?: string, headers?: table}) -> Response"
+                "__signature": "patch(url: string, options?: {body?: string, json?: table, headers?: table}) -> Response"
             },
             "head": {
                 "__fn": "head",
@@ -79,7 +83,17 @@ impl BuiltinModule for HttpModule {
             "fetch": {
                 "__fn": "fetch",
                 "__desc": "Universal fetch API for HTTP requests",
-                "__signature": "fetch(url: string, options?: {method?: string, body?: string, headers?: table, timeout?: number}) -> Response"
+                "__signature": "fetch(url: string, options?: {method?: string, body?: string, json?: table, headers?: table, timeout?: number, responseType?: \"text\"|\"json\"}) -> Response"
+            },
+            "fetchAll": {
+                "__fn": "fetchAll",
+                "__desc": "Fetch URLs concurrently, returning responses or {error} tables in input order",
+                "__signature": "fetchAll(urls: string[], options?: {concurrency?: number, ...fetch options}) -> (Response | {error: table})[]"
+            },
+            "download": {
+                "__fn": "download",
+                "__desc": "Download a URL to a file, resuming a partial file with a Range request",
+                "__signature": "download(url: string, dest: string, options?: {resume?: boolean, retries?: number, headers?: table, timeout?: number}) -> {status, size, downloaded, resumed}"
             },
             "postJson": {
                 "__fn": "postJson",
@@ -90,6 +104,46 @@ impl BuiltinModule for HttpModule {
                 "__fn": "putJson",
                 "__desc": "PUT request with JSON body",
                 "__signature": "putJson(url: string, data: table) -> Response"
+            },
+            "setUserAgent": {
+                "__fn": "setUserAgent",
+                "__desc": "Set the User-Agent sent with subsequent requests (default: hype/<version>)",
+                "__signature": "setUserAgent(userAgent: string)"
+            },
+            "newClient": {
+                "__fn": "newClient",
+                "__desc": "Create a client with its own default headers, cookies and settings; it has the same request functions as the module, and per-request headers override its defaults or remove them when set to false",
+                "__signature": "newClient(options?: {headers?: table, userAgent?: string}) -> HttpClient"
+            },
+            "onRequest": {
+                "__fn": "onRequest",
+                "__desc": "Add a hook run before each request with a {method, url, headers, body} table it may change; hooks run in the order they were added",
+                "__signature": "client:onRequest(hook: function(request: table))"
+            },
+            "onResponse": {
+                "__fn": "onResponse",
+                "__desc": "Add a hook run with each response table before it is returned; hooks run in the order they were added",
+                "__signature": "client:onResponse(hook: function(response: table))"
+            },
+            "setDefaultHeaders": {
+                "__fn": "setDefaultHeaders",
+                "__desc": "Set headers sent with every subsequent request; per-request headers override them",
+                "__signature": "setDefaultHeaders(headers: table)"
+            },
+            "setHostResolver": {
+                "__fn": "setHostResolver",
+                "__desc": "Resolve specific hosts to fixed addresses (ip or ip:port) instead of using DNS",
+                "__signature": "setHostResolver(overrides: table)"
+            },
+            "createServer": {
+                "__fn": "createServer",
+                "__desc": "Create an HTTP/1.1 server whose handler maps a request table to {status, headers, body}",
+                "__signature": "createServer(handler: function(request) -> table | string | nil) -> Server"
+            },
+            "connectWebSocket": {
+                "__fn": "connectWebSocket",
+                "__desc": "Open a WebSocket (ws:// or wss://); pings are answered automatically",
+                "__signature": "connectWebSocket(url: string, options?: {headers?: table}) -> WebSocket"
             }
         }))
     }
@@ -116,7 +170,10 @@ mod tests {
         assert!(exports.get("put").is_some());
         assert!(exports.get("delete").is_some());
         assert!(exports.get("fetch").is_some());
+        assert!(exports.get("fetchAll").is_some());
+        assert!(exports.get("download").is_some());
         assert!(exports.get("postJson").is_some());
+        assert!(exports.get("newClient").is_some());
     }
 
     #[test]
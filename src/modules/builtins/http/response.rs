@@ -1,12 +1,21 @@
+use super::HttpError;
+use crate::modules::builtins::zlib::operations::{gunzip, inflate};
 use serde_json::Value as JsonValue;
 use std::collections::HashMap;
+use std::io::Read;
+
+/// Encodings advertised in `Accept-Encoding` and decoded by [`decode_body`].
+pub const ACCEPT_ENCODING: &str = "gzip, deflate, br";
 
 #[derive(Debug, Clone)]
 pub struct HttpResponse {
     pub status: u16,
     pub status_text: String,
     pub headers: HashMap<String, String>,
+    /// The body after `Content-Encoding` has been decoded.
     pub body: String,
+    /// The body bytes as received, before decoding.
+    pub raw_body: Vec<u8>,
 }
 
 impl HttpResponse {
@@ -16,11 +25,13 @@ impl HttpResponse {
         headers: HashMap<String, String>,
         body: String,
     ) -> Self {
+        let raw_body = body.as_bytes().to_vec();
         Self {
             status,
             status_text,
             headers,
             body,
+            raw_body,
         }
     }
 
@@ -37,7 +48,7 @@ impl HttpResponse {
     }
 
     #[cfg(feature = "http")]
-    pub async fn from_reqwest(response: reqwest::Response) -> Result<Self, reqwest::Error> {
+    pub async fn from_reqwest(response: reqwest::Response) -> Result<Self, HttpError> {
         let status = response.status().as_u16();
         let status_text = response
             .status()
@@ -52,10 +63,54 @@ impl HttpResponse {
             }
         }
 
-        let body = response.text().await?;
+        let raw_body = response.bytes().await?.to_vec();
+        let body = decode_body(&headers, &raw_body)?;
+
+        Ok(Self {
+            status,
+            status_text,
+            headers,
+            body: String::from_utf8_lossy(&body).into_owned(),
+            raw_body,
+        })
+    }
+}
 
-        Ok(Self::new(status, status_text, headers, body))
+/// Undo `gzip`, `deflate` and `br` content encodings, in the order they were
+/// applied. Unknown encodings leave the body as received, and so does an empty
+/// body, as sent for `HEAD`, `204` and `304` even when `Content-Encoding` is set.
+pub fn decode_body(headers: &HashMap<String, String>, raw: &[u8]) -> Result<Vec<u8>, HttpError> {
+    if raw.is_empty() {
+        return Ok(Vec::new());
     }
+    let Some(encoding) = headers
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case("content-encoding"))
+        .map(|(_, value)| value.to_ascii_lowercase())
+    else {
+        return Ok(raw.to_vec());
+    };
+
+    let mut body = raw.to_vec();
+    for coding in encoding.split(',').map(str::trim).rev() {
+        body = match coding {
+            "gzip" | "x-gzip" => gunzip(&body).map_err(|e| e.to_string()),
+            "deflate" => inflate(&body).map_err(|e| e.to_string()),
+            "br" => unbrotli(&body),
+            "" | "identity" => continue,
+            _ => return Ok(raw.to_vec()),
+        }
+        .map_err(|e| HttpError::DecodeError(format!("{} body: {}", coding, e)))?;
+    }
+    Ok(body)
+}
+
+fn unbrotli(data: &[u8]) -> Result<Vec<u8>, String> {
+    let mut output = Vec::new();
+    brotli::Decompressor::new(data, 4096)
+        .read_to_end(&mut output)
+        .map_err(|e| e.to_string())?;
+    Ok(output)
 }
 
 #[cfg(test)]
@@ -106,6 +161,44 @@ mod tests {
         assert_eq!(parsed["value"], 123);
     }
 
+    #[test]
+    fn test_decode_body() {
+        use crate::modules::builtins::zlib::operations::{deflate, gzip};
+
+        let encoded =
+            |value: &str| HashMap::from([("Content-Encoding".to_string(), value.to_string())]);
+        let body = br#"{"ok":true}"#;
+
+        let gzipped = gzip(body, 6).unwrap();
+        assert_eq!(decode_body(&encoded("gzip"), &gzipped).unwrap(), body);
+        let deflated = deflate(body, 6).unwrap();
+        assert_eq!(decode_body(&encoded("deflate"), &deflated).unwrap(), body);
+        let both = gzip(&deflated, 6).unwrap();
+        assert_eq!(decode_body(&encoded("deflate, gzip"), &both).unwrap(), body);
+
+        let mut brotli_body = Vec::new();
+        {
+            let mut writer = brotli::CompressorWriter::new(&mut brotli_body, 4096, 5, 22);
+            std::io::Write::write_all(&mut writer, body).unwrap();
+        }
+        assert_eq!(decode_body(&encoded("br"), &brotli_body).unwrap(), body);
+
+        assert_eq!(decode_body(&HashMap::new(), body).unwrap(), body);
+        assert_eq!(decode_body(&encoded("zstd"), b"opaque").unwrap(), b"opaque");
+        assert!(matches!(
+            decode_body(&encoded("gzip"), b"not gzip"),
+            Err(HttpError::DecodeError(_))
+        ));
+    }
+
+    #[test]
+    fn test_empty_encoded_body_is_not_decoded() {
+        for coding in ["gzip", "deflate", "br"] {
+            let headers = HashMap::from([("Content-Encoding".to_string(), coding.to_string())]);
+            assert_eq!(decode_body(&headers, b"").unwrap(), b"");
+        }
+    }
+
     #[test]
     fn test_response_text() {
         let headers = HashMap::new();
@@ -0,0 +1,299 @@
+use super::{HttpError, Result};
+use http_body_util::{BodyExt, Full};
+use hyper::body::{Bytes, Incoming};
+use hyper::header::{HeaderName, HeaderValue};
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::StatusCode;
+use hyper_util::rt::TokioIo;
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender};
+use std::time::Duration;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::runtime::Runtime;
+use tokio::sync::{oneshot, watch};
+
+/// A request as handed to the script. Header names are lowercase.
+#[derive(Debug, Clone)]
+pub struct ServerRequest {
+    pub method: String,
+    pub path: String,
+    pub query: Option<String>,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+    pub remote_addr: SocketAddr,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServerResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl ServerResponse {
+    pub fn new(status: u16, body: impl Into<Vec<u8>>) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body: body.into(),
+        }
+    }
+
+    pub fn internal_error() -> Self {
+        Self::new(500, "Internal Server Error")
+    }
+
+    /// Reject a status or header that cannot be sent, so the script hears
+    /// about it instead of the client getting a bare 500.
+    pub fn validate(&self) -> Result<()> {
+        StatusCode::from_u16(self.status)
+            .map_err(|_| HttpError::ResponseError(self.status, "is not a valid status".into()))?;
+        for (name, value) in &self.headers {
+            HeaderName::from_bytes(name.as_bytes())
+                .map_err(|_| HttpError::RequestError(format!("Invalid header name: {}", name)))?;
+            HeaderValue::from_str(value).map_err(|_| {
+                HttpError::RequestError(format!("Invalid value for header {}", name))
+            })?;
+        }
+        Ok(())
+    }
+
+    fn into_hyper(self) -> hyper::Response<Full<Bytes>> {
+        let mut builder = hyper::Response::builder().status(self.status);
+        for (name, value) in &self.headers {
+            builder = builder.header(name.as_str(), value.as_str());
+        }
+        builder
+            .body(Full::new(Bytes::from(self.body)))
+            .unwrap_or_else(|_| Self::internal_error().into_hyper())
+    }
+}
+
+/// A request waiting for the script's response. Dropping it without calling
+/// `respond` answers the client with a 500.
+pub struct PendingRequest {
+    pub request: ServerRequest,
+    responder: oneshot::Sender<ServerResponse>,
+}
+
+impl PendingRequest {
+    pub fn respond(self, response: ServerResponse) {
+        // The client may have disconnected already
+        let _ = self.responder.send(response);
+    }
+}
+
+pub enum NextRequest {
+    Request(PendingRequest),
+    Timeout,
+    /// The server was closed and every open connection has finished.
+    Closed,
+}
+
+/// An HTTP/1.1 server. Connections are accepted on a background runtime and
+/// each request is queued for the thread that owns the Lua state, which
+/// takes them with `next_request`.
+pub struct HttpServer {
+    runtime: Option<Runtime>,
+    local_addr: SocketAddr,
+    requests: Receiver<PendingRequest>,
+    shutdown: watch::Sender<bool>,
+}
+
+impl HttpServer {
+    /// Bind `host:port` and start accepting connections. Port 0 picks a free port.
+    pub fn bind(host: &str, port: u16) -> Result<Self> {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(2)
+            .thread_name("hype-http-server")
+            .enable_all()
+            .build()
+            .map_err(|e| HttpError::RuntimeError(e.to_string()))?;
+        let listener = runtime
+            .block_on(TcpListener::bind((host, port)))
+            .map_err(|e| {
+                HttpError::NetworkError(format!("cannot listen on {}:{}: {}", host, port, e))
+            })?;
+        let local_addr = listener.local_addr()?;
+
+        let (sender, requests) = channel();
+        let (shutdown, closing) = watch::channel(false);
+        runtime.spawn(accept_loop(listener, sender, closing));
+
+        Ok(Self {
+            runtime: Some(runtime),
+            local_addr,
+            requests,
+            shutdown,
+        })
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// Wait up to `timeout` for the next request.
+    pub fn next_request(&self, timeout: Duration) -> NextRequest {
+        match self.requests.recv_timeout(timeout) {
+            Ok(pending) => NextRequest::Request(pending),
+            Err(RecvTimeoutError::Timeout) => NextRequest::Timeout,
+            Err(RecvTimeoutError::Disconnected) => NextRequest::Closed,
+        }
+    }
+
+    /// Stop accepting connections. Requests already received are still
+    /// delivered, and keep-alive connections close after their current response.
+    pub fn close(&self) {
+        self.shutdown.send_replace(true);
+    }
+
+    pub fn is_closing(&self) -> bool {
+        *self.shutdown.borrow()
+    }
+}
+
+impl Drop for HttpServer {
+    fn drop(&mut self) {
+        self.close();
+        if let Some(runtime) = self.runtime.take() {
+            runtime.shutdown_background();
+        }
+    }
+}
+
+async fn accept_loop(
+    listener: TcpListener,
+    sender: Sender<PendingRequest>,
+    mut closing: watch::Receiver<bool>,
+) {
+    loop {
+        tokio::select! {
+            accepted = listener.accept() => {
+                if let Ok((stream, remote_addr)) = accepted {
+                    tokio::spawn(serve_connection(stream, remote_addr, sender.clone(), closing.clone()));
+                }
+            }
+            _ = closed(&mut closing) => break,
+        }
+    }
+}
+
+async fn serve_connection(
+    stream: TcpStream,
+    remote_addr: SocketAddr,
+    sender: Sender<PendingRequest>,
+    mut closing: watch::Receiver<bool>,
+) {
+    let service = service_fn(move |request| handle(request, remote_addr, sender.clone()));
+    let connection = http1::Builder::new().serve_connection(TokioIo::new(stream), service);
+    tokio::pin!(connection);
+
+    tokio::select! {
+        _ = connection.as_mut() => {}
+        _ = closed(&mut closing) => {
+            connection.as_mut().graceful_shutdown();
+            let _ = connection.await;
+        }
+    }
+}
+
+/// Resolves once `close` is called or the server is dropped.
+async fn closed(closing: &mut watch::Receiver<bool>) {
+    let _ = closing.wait_for(|closed| *closed).await;
+}
+
+async fn handle(
+    request: hyper::Request<Incoming>,
+    remote_addr: SocketAddr,
+    sender: Sender<PendingRequest>,
+) -> std::result::Result<hyper::Response<Full<Bytes>>, Infallible> {
+    let (parts, body) = request.into_parts();
+    let body = match body.collect().await {
+        Ok(collected) => collected.to_bytes().to_vec(),
+        Err(_) => return Ok(ServerResponse::new(400, "Bad Request").into_hyper()),
+    };
+
+    let request = ServerRequest {
+        method: parts.method.to_string(),
+        path: parts.uri.path().to_string(),
+        query: parts.uri.query().map(str::to_string),
+        headers: parts
+            .headers
+            .iter()
+            .map(|(name, value)| {
+                let value = String::from_utf8_lossy(value.as_bytes()).into_owned();
+                (name.as_str().to_string(), value)
+            })
+            .collect(),
+        body,
+        remote_addr,
+    };
+
+    let (responder, response) = oneshot::channel();
+    if sender.send(PendingRequest { request, responder }).is_err() {
+        return Ok(ServerResponse::new(503, "Service Unavailable").into_hyper());
+    }
+    let response = response
+        .await
+        .unwrap_or_else(|_| ServerResponse::internal_error());
+    Ok(response.into_hyper())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+
+    fn raw_request(addr: SocketAddr, request: &str) -> String {
+        let mut stream = std::net::TcpStream::connect(addr).unwrap();
+        stream.write_all(request.as_bytes()).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    }
+
+    #[test]
+    fn test_requests_are_queued_for_the_owner() {
+        let server = HttpServer::bind("127.0.0.1", 0).unwrap();
+        let addr = server.local_addr();
+        let client = std::thread::spawn(move || {
+            raw_request(
+                addr,
+                "POST /hook?x=1 HTTP/1.1\r\nHost: test\r\nContent-Length: 4\r\nConnection: close\r\n\r\nping",
+            )
+        });
+
+        let NextRequest::Request(pending) = server.next_request(Duration::from_secs(5)) else {
+            panic!("no request arrived");
+        };
+        assert_eq!(pending.request.method, "POST");
+        assert_eq!(pending.request.path, "/hook");
+        assert_eq!(pending.request.query.as_deref(), Some("x=1"));
+        assert_eq!(pending.request.body, b"ping");
+        let mut response = ServerResponse::new(201, "pong");
+        response.headers.push(("x-echo".into(), "yes".into()));
+        pending.respond(response);
+
+        let raw = client.join().unwrap();
+        assert!(raw.starts_with("HTTP/1.1 201"), "{}", raw);
+        assert!(raw.contains("x-echo: yes"));
+        assert!(raw.ends_with("pong"));
+    }
+
+    #[test]
+    fn test_close_drains_and_reports_closed() {
+        let server = HttpServer::bind("127.0.0.1", 0).unwrap();
+        server.close();
+        assert!(server.is_closing());
+        let closed = (0..50).any(|_| {
+            matches!(
+                server.next_request(Duration::from_millis(100)),
+                NextRequest::Closed
+            )
+        });
+        assert!(closed);
+        assert!(ServerResponse::new(1000, "").validate().is_err());
+    }
+}
@@ -0,0 +1,217 @@
+use super::{HttpError, Result};
+use crate::engine::timeout::Deadline;
+use futures_util::{SinkExt, StreamExt};
+use std::collections::HashMap;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::time::Duration;
+use tokio::net::TcpStream;
+use tokio::runtime::Handle;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::{HeaderName, HeaderValue};
+use tokio_tungstenite::tungstenite::{Error as WsError, Message};
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+use url::Url;
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WsMessage {
+    Text(String),
+    Binary(Vec<u8>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Received {
+    Message(WsMessage),
+    Timeout,
+    /// The peer closed the connection, or `close` was called.
+    Closed,
+}
+
+/// A client WebSocket connection driven by blocking calls on the HTTP
+/// client's runtime. Pings from the server are answered while receiving.
+pub struct WebSocket {
+    runtime: Handle,
+    stream: Option<WebSocketStream<MaybeTlsStream<TcpStream>>>,
+    deadline: Option<Deadline>,
+}
+
+/// Open a connection to `url` (`ws://` or `wss://`). The TCP connection goes
+/// to `addr` when given, e.g. from `setHostResolver`, and to the URL's host otherwise.
+pub(super) fn connect(
+    runtime: Handle,
+    url: &Url,
+    addr: Option<SocketAddr>,
+    headers: &HashMap<String, String>,
+    deadline: Option<Deadline>,
+) -> Result<WebSocket> {
+    if !matches!(url.scheme(), "ws" | "wss") {
+        return Err(HttpError::InvalidUrl(format!(
+            "{} (WebSocket URLs start with ws:// or wss://)",
+            url
+        )));
+    }
+    let host = url
+        .host_str()
+        .ok_or_else(|| HttpError::InvalidUrl(url.to_string()))?
+        .to_string();
+    let port = url.port_or_known_default().unwrap_or(80);
+
+    let mut request = url.as_str().into_client_request().map_err(ws_error)?;
+    for (name, value) in headers {
+        let name = HeaderName::from_bytes(name.as_bytes())
+            .map_err(|_| HttpError::RequestError(format!("Invalid header name: {}", name)))?;
+        let value = HeaderValue::from_str(value)
+            .map_err(|_| HttpError::RequestError(format!("Invalid value for header {}", name)))?;
+        request.headers_mut().insert(name, value);
+    }
+
+    let handshake = async move {
+        let tcp = match addr {
+            Some(addr) => TcpStream::connect(addr).await?,
+            None => TcpStream::connect((host.as_str(), port)).await?,
+        };
+        let (stream, _response) = tokio_tungstenite::client_async_tls(request, tcp)
+            .await
+            .map_err(ws_error)?;
+        Ok(stream)
+    };
+
+    let mut socket = WebSocket {
+        runtime,
+        stream: None,
+        deadline,
+    };
+    let stream = socket.block_on(Some(CONNECT_TIMEOUT), handshake)?;
+    socket.stream = Some(stream.ok_or(HttpError::TimeoutError)?);
+    Ok(socket)
+}
+
+impl WebSocket {
+    /// Send a text frame, or a binary frame when `binary` is set.
+    pub fn send(&mut self, data: Vec<u8>, binary: bool) -> Result<()> {
+        let message = if binary {
+            Message::binary(data)
+        } else {
+            let text = String::from_utf8(data).map_err(|_| {
+                HttpError::RequestError(
+                    "text frames must be UTF-8; send binary data with {binary = true}".to_string(),
+                )
+            })?;
+            Message::text(text)
+        };
+        let stream = self
+            .stream
+            .as_mut()
+            .ok_or_else(|| HttpError::NetworkError("WebSocket is closed".to_string()))?;
+        self.runtime
+            .block_on(stream.send(message))
+            .map_err(ws_error)
+    }
+
+    /// Wait up to `timeout` (forever when `None`) for the next text or binary
+    /// message. Control frames are handled here and never returned.
+    pub fn receive(&mut self, timeout: Option<Duration>) -> Result<Received> {
+        let Some(stream) = self.stream.as_mut() else {
+            return Ok(Received::Closed);
+        };
+        let next = async {
+            loop {
+                match stream.next().await {
+                    Some(Ok(Message::Text(text))) => {
+                        return Ok(Some(WsMessage::Text(text.to_string())))
+                    }
+                    Some(Ok(Message::Binary(data))) => {
+                        return Ok(Some(WsMessage::Binary(data.to_vec())))
+                    }
+                    // tungstenite queues the pong reply itself
+                    Some(Ok(Message::Ping(_) | Message::Pong(_) | Message::Frame(_))) => {}
+                    Some(Ok(Message::Close(_))) | None => return Ok(None),
+                    Some(Err(WsError::ConnectionClosed | WsError::AlreadyClosed)) => {
+                        return Ok(None)
+                    }
+                    Some(Err(e)) => return Err(ws_error(e)),
+                }
+            }
+        };
+
+        let deadline = self.deadline.clone();
+        match block_on_with_deadline(&self.runtime, deadline.as_ref(), timeout, next)? {
+            None => Ok(Received::Timeout),
+            Some(Some(message)) => Ok(Received::Message(message)),
+            Some(None) => {
+                self.stream = None;
+                Ok(Received::Closed)
+            }
+        }
+    }
+
+    /// Send a close frame and wait briefly for the server to acknowledge it.
+    pub fn close(&mut self) -> Result<()> {
+        let Some(mut stream) = self.stream.take() else {
+            return Ok(());
+        };
+        let closing = async move {
+            match stream.close(None).await {
+                Ok(()) | Err(WsError::ConnectionClosed | WsError::AlreadyClosed) => {}
+                Err(e) => return Err(ws_error(e)),
+            }
+            // Drain until the server's close frame arrives
+            while let Some(Ok(_)) = stream.next().await {}
+            Ok(())
+        };
+        self.block_on(Some(Duration::from_secs(5)), closing)
+            .map(|_| ())
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.stream.is_none()
+    }
+
+    fn block_on<T>(
+        &self,
+        timeout: Option<Duration>,
+        future: impl Future<Output = Result<T>>,
+    ) -> Result<Option<T>> {
+        block_on_with_deadline(&self.runtime, self.deadline.as_ref(), timeout, future)
+    }
+}
+
+/// Run `future` on `runtime` for up to `timeout`, returning `None` if it runs
+/// out. The script deadline, when there is one, raises a timeout error instead.
+fn block_on_with_deadline<T>(
+    runtime: &Handle,
+    deadline: Option<&Deadline>,
+    timeout: Option<Duration>,
+    future: impl Future<Output = Result<T>>,
+) -> Result<Option<T>> {
+    let limit = deadline.map(|d| d.remaining());
+    if limit.is_some_and(|remaining| remaining.is_zero()) {
+        return Err(HttpError::TimeoutError);
+    }
+    let wait = match (timeout, limit) {
+        (Some(timeout), Some(limit)) => Some(timeout.min(limit)),
+        (timeout, limit) => timeout.or(limit),
+    };
+    let Some(wait) = wait else {
+        return runtime.block_on(future).map(Some);
+    };
+    match runtime.block_on(async { tokio::time::timeout(wait, future).await }) {
+        Ok(result) => result.map(Some),
+        Err(_) if deadline.is_some_and(|d| d.is_expired()) => Err(HttpError::TimeoutError),
+        Err(_) => Ok(None),
+    }
+}
+
+fn ws_error(err: WsError) -> HttpError {
+    match err {
+        WsError::Url(e) => HttpError::InvalidUrl(e.to_string()),
+        WsError::Http(response) => HttpError::ResponseError(
+            response.status().as_u16(),
+            "WebSocket handshake rejected".to_string(),
+        ),
+        WsError::Io(e) => HttpError::NetworkError(e.to_string()),
+        other => HttpError::NetworkError(other.to_string()),
+    }
+}
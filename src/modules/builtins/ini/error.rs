@@ -0,0 +1,42 @@
+use crate::error::StructuredError;
+use std::fmt;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum IniError {
+    Parse { line: usize, message: String },
+    InvalidValue(String),
+}
+
+impl fmt::Display for IniError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IniError::Parse { line, message } => {
+                write!(f, "INI parse error at line {}: {}", line, message)
+            }
+            IniError::InvalidValue(msg) => write!(f, "Cannot write INI: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for IniError {}
+
+impl IniError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            IniError::Parse { .. } => "ERR_PARSE",
+            IniError::InvalidValue(_) => "ERR_INVALID_VALUE",
+        }
+    }
+}
+
+impl From<IniError> for StructuredError {
+    fn from(err: IniError) -> Self {
+        StructuredError::new("ini", err.code(), err.to_string())
+    }
+}
+
+impl From<IniError> for crate::error::HypeError {
+    fn from(err: IniError) -> Self {
+        crate::error::HypeError::Execution(err.to_string())
+    }
+}
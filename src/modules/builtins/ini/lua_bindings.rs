@@ -0,0 +1,104 @@
+use super::error::IniError;
+use super::operations::*;
+use crate::lua::error::{structured_error, wrap_module_errors};
+use mlua::{Lua, Result as LuaResult, Table, Value};
+
+pub fn create_ini_module(lua: &Lua) -> LuaResult<Table<'_>> {
+    let ini = lua.create_table()?;
+
+    let parse_fn = lua.create_function(|lua, input: String| {
+        let document = parse(&input).map_err(structured_error)?;
+        let result = properties_to_table(lua, &document.global)?;
+        for (name, properties) in &document.sections {
+            result.set(name.as_str(), properties_to_table(lua, properties)?)?;
+        }
+        Ok(result)
+    })?;
+    ini.set("parse", parse_fn)?;
+
+    let stringify_fn = lua.create_function(|lua, table: Table| {
+        let document = table_to_document(lua, table)?;
+        stringify(&document).map_err(structured_error)
+    })?;
+    ini.set("stringify", stringify_fn)?;
+
+    wrap_module_errors(lua, &ini)?;
+    Ok(ini)
+}
+
+fn properties_to_table<'lua>(lua: &'lua Lua, properties: &Properties) -> LuaResult<Table<'lua>> {
+    let table = lua.create_table_with_capacity(0, properties.len())?;
+    for (key, value) in properties {
+        table.set(key.as_str(), value.as_str())?;
+    }
+    Ok(table)
+}
+
+/// Tables at the top level are sections; every other value is a global key.
+fn table_to_document(lua: &Lua, table: Table) -> LuaResult<IniDocument> {
+    let mut document = IniDocument::default();
+    for pair in table.pairs::<Value, Value>() {
+        let (key, value) = pair?;
+        let key = scalar_to_string(lua, key, "keys")?;
+        match value {
+            Value::Table(section) => {
+                let mut properties = Properties::new();
+                for pair in section.pairs::<Value, Value>() {
+                    let (name, value) = pair?;
+                    let name = scalar_to_string(lua, name, "keys")?;
+                    let value = scalar_to_string(lua, value, "values inside a section")?;
+                    properties.insert(name, value);
+                }
+                document.sections.insert(key, properties);
+            }
+            value => {
+                let value = scalar_to_string(lua, value, "values")?;
+                document.global.insert(key, value);
+            }
+        }
+    }
+    Ok(document)
+}
+
+/// Strings, numbers and booleans as text; anything else cannot be written.
+fn scalar_to_string(lua: &Lua, value: Value, what: &str) -> LuaResult<String> {
+    match value {
+        Value::String(s) => Ok(s.to_str()?.to_string()),
+        Value::Boolean(b) => Ok(b.to_string()),
+        Value::Integer(_) | Value::Number(_) => match lua.coerce_string(value)? {
+            Some(s) => Ok(s.to_str()?.to_string()),
+            None => Ok(String::new()),
+        },
+        other => Err(structured_error(IniError::InvalidValue(format!(
+            "{} must be strings, numbers or booleans, not {}",
+            what,
+            other.type_name()
+        )))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_ini_module() {
+        let lua = Lua::new();
+        let ini = create_ini_module(&lua).unwrap();
+        assert!(ini.contains_key("parse").unwrap());
+        assert!(ini.contains_key("stringify").unwrap());
+    }
+
+    #[test]
+    fn test_stringify_converts_scalars() {
+        let lua = Lua::new();
+        let ini = create_ini_module(&lua).unwrap();
+        lua.globals().set("ini", ini).unwrap();
+
+        let text: String = lua
+            .load(r#"return ini.stringify({debug = true, server = {port = 8080, ratio = 0.5}})"#)
+            .eval()
+            .unwrap();
+        assert_eq!(text, "debug = true\n\n[server]\nport = 8080\nratio = 0.5\n");
+    }
+}
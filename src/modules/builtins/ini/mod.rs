@@ -0,0 +1,67 @@
+pub mod error;
+pub mod lua_bindings;
+pub mod operations;
+
+use serde_json::{json, Value as JsonValue};
+
+use super::BuiltinModule;
+use crate::error::HypeError;
+
+pub use lua_bindings::create_ini_module;
+
+pub struct IniModule;
+
+impl IniModule {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for IniModule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BuiltinModule for IniModule {
+    fn name(&self) -> &str {
+        "ini"
+    }
+
+    fn exports(&self) -> std::result::Result<JsonValue, HypeError> {
+        Ok(json!({
+            "__id": "ini",
+            "__desc": "INI parsing and serialization with sections, global keys and comments",
+            "parse": {
+                "__fn": "parse",
+                "__desc": "Parse INI text into {key = value, section = {key = value}} with string values",
+                "__signature": "parse(str: string) -> table"
+            },
+            "stringify": {
+                "__fn": "stringify",
+                "__desc": "Write a table as INI text; nested tables become sections",
+                "__signature": "stringify(table: table) -> string"
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ini_module_name() {
+        let module = IniModule::new();
+        assert_eq!(module.name(), "ini");
+    }
+
+    #[test]
+    fn test_ini_module_exports() {
+        let module = IniModule::new();
+        let exports = module.exports().unwrap();
+        assert_eq!(exports["__id"], "ini");
+        assert!(exports.get("parse").is_some());
+        assert!(exports.get("stringify").is_some());
+    }
+}
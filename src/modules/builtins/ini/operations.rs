@@ -0,0 +1,183 @@
+use super::error::IniError;
+use std::collections::BTreeMap;
+
+pub type Result<T> = std::result::Result<T, IniError>;
+
+pub type Properties = BTreeMap<String, String>;
+
+/// A parsed INI file. Every value is kept as a string.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct IniDocument {
+    /// Keys that appear before the first `[section]` header.
+    pub global: Properties,
+    pub sections: BTreeMap<String, Properties>,
+}
+
+/// Parse INI text. Lines starting with `;` or `#` are comments, keys and
+/// values are trimmed, and a value wrapped in double quotes loses the quotes.
+/// A key repeated within a section keeps its last value, and a section that
+/// appears twice is merged.
+pub fn parse(input: &str) -> Result<IniDocument> {
+    let mut document = IniDocument::default();
+    let mut section: Option<String> = None;
+
+    for (index, raw) in input.lines().enumerate() {
+        let line = raw.trim();
+        let error = |message: &str| IniError::Parse {
+            line: index + 1,
+            message: message.to_string(),
+        };
+
+        if line.is_empty() || line.starts_with(';') || line.starts_with('#') {
+            continue;
+        }
+
+        if let Some(header) = line.strip_prefix('[') {
+            let name = header
+                .strip_suffix(']')
+                .ok_or_else(|| error("section header is missing ']'"))?
+                .trim();
+            if name.is_empty() {
+                return Err(error("section name is empty"));
+            }
+            if document.global.contains_key(name) {
+                return Err(error(&format!(
+                    "section [{}] has the same name as a global key",
+                    name
+                )));
+            }
+            document.sections.entry(name.to_string()).or_default();
+            section = Some(name.to_string());
+            continue;
+        }
+
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| error("expected 'key = value'"))?;
+        let key = key.trim();
+        if key.is_empty() {
+            return Err(error("key is empty"));
+        }
+        let properties = match &section {
+            Some(name) => document.sections.get_mut(name).expect("section was added"),
+            None => &mut document.global,
+        };
+        properties.insert(key.to_string(), unquote(value.trim()).to_string());
+    }
+
+    Ok(document)
+}
+
+/// Write `document` as INI text: global keys first, then each section, with
+/// keys in sorted order. Values that would not read back the same are quoted.
+pub fn stringify(document: &IniDocument) -> Result<String> {
+    let mut output = String::new();
+    write_properties(&mut output, &document.global)?;
+
+    for (name, properties) in &document.sections {
+        if name.is_empty() || name.contains(['[', ']', '\n', '\r']) {
+            return Err(IniError::InvalidValue(format!(
+                "'{}' is not a valid section name",
+                name
+            )));
+        }
+        if !output.is_empty() {
+            output.push('\n');
+        }
+        output.push_str(&format!("[{}]\n", name));
+        write_properties(&mut output, properties)?;
+    }
+
+    Ok(output)
+}
+
+fn write_properties(output: &mut String, properties: &Properties) -> Result<()> {
+    for (key, value) in properties {
+        let trimmed = key.trim();
+        if trimmed.is_empty()
+            || trimmed != key
+            || key.starts_with(['[', ';', '#'])
+            || key.contains(['=', '\n', '\r'])
+        {
+            return Err(IniError::InvalidValue(format!(
+                "'{}' is not a valid key",
+                key
+            )));
+        }
+        if value.contains(['\n', '\r']) {
+            return Err(IniError::InvalidValue(format!(
+                "the value of '{}' spans several lines",
+                key
+            )));
+        }
+        output.push_str(&format!("{} = {}\n", key, quote(value)));
+    }
+    Ok(())
+}
+
+fn unquote(value: &str) -> &str {
+    value
+        .strip_prefix('"')
+        .and_then(|rest| rest.strip_suffix('"'))
+        .unwrap_or(value)
+}
+
+fn quote(value: &str) -> String {
+    let needs_quotes = value.trim() != value || (value.starts_with('"') && value.ends_with('"'));
+    if needs_quotes && !value.is_empty() {
+        format!("\"{}\"", value)
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sections_comments_and_globals() {
+        let document = parse(
+            "; generated\nroot = /srv\n\n[server]\n# listen address\nhost = 0.0.0.0\nport=8080\nbanner = \"  hi  \"\n\n[paths]\nlogs = /var/log\n",
+        )
+        .unwrap();
+
+        assert_eq!(document.global["root"], "/srv");
+        assert_eq!(document.sections["server"]["host"], "0.0.0.0");
+        assert_eq!(document.sections["server"]["port"], "8080");
+        assert_eq!(document.sections["server"]["banner"], "  hi  ");
+        assert_eq!(document.sections["paths"]["logs"], "/var/log");
+    }
+
+    #[test]
+    fn test_duplicate_keys_keep_the_last_value() {
+        let document = parse("[a]\nx = 1\nx = 2\n[b]\ny = 1\n[a]\nz = 3\n").unwrap();
+        assert_eq!(document.sections["a"]["x"], "2");
+        assert_eq!(document.sections["a"]["z"], "3");
+        assert_eq!(document.sections.len(), 2);
+    }
+
+    #[test]
+    fn test_parse_errors_report_the_line() {
+        let err = parse("[ok]\nkey = 1\n[broken\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "INI parse error at line 3: section header is missing ']'"
+        );
+        assert_eq!(err.code(), "ERR_PARSE");
+        assert!(parse("just words").is_err());
+        assert!(parse("= value").is_err());
+        assert!(parse("name = x\n[name]\n").is_err());
+    }
+
+    #[test]
+    fn test_stringify_round_trips() {
+        let text = "root = /srv\n\n[server]\nbanner = \"  hi  \"\nhost = 0.0.0.0\n";
+        let document = parse(text).unwrap();
+        assert_eq!(stringify(&document).unwrap(), text);
+
+        let mut bad = IniDocument::default();
+        bad.global.insert("a=b".to_string(), "1".to_string());
+        assert_eq!(stringify(&bad).unwrap_err().code(), "ERR_INVALID_VALUE");
+    }
+}
@@ -5,6 +5,8 @@ pub enum JsonError {
     SerializationError(String),
     DeserializationError(String),
     InvalidUtf8,
+    /// NaN or an infinity, which JSON cannot represent.
+    NonFinite(f64),
 }
 
 impl fmt::Display for JsonError {
@@ -15,6 +17,20 @@ impl fmt::Display for JsonError {
                 write!(f, "JSON deserialization error: {}", msg)
             }
             JsonError::InvalidUtf8 => write!(f, "Invalid UTF-8 in JSON string"),
+            JsonError::NonFinite(n) => {
+                let name = if n.is_nan() {
+                    "NaN"
+                } else if *n > 0.0 {
+                    "Infinity"
+                } else {
+                    "-Infinity"
+                };
+                write!(
+                    f,
+                    "{} is not valid JSON (encode with {{allowNonFinite = true}} to write null)",
+                    name
+                )
+            }
         }
     }
 }
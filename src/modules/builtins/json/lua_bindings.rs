@@ -1,4 +1,5 @@
 use super::operations::*;
+use crate::lua::require::lua_to_json_with;
 use mlua::{Lua, Table, Value as LuaValue};
 
 pub fn create_json_module(lua: &Lua) -> mlua::Result<Table> {
@@ -13,16 +14,8 @@ pub fn create_json_module(lua: &Lua) -> mlua::Result<Table> {
 }
 
 fn register_encode(lua: &Lua, table: &Table) -> mlua::Result<()> {
-    let encode_fn = lua.create_function(|lua, (value, pretty): (LuaValue, Option<bool>)| {
-        let json_value = lua_to_json(lua, value)?;
-
-        let result = if pretty.unwrap_or(false) {
-            encode_pretty(&json_value)
-        } else {
-            encode(&json_value)
-        };
-
-        result.map_err(mlua::Error::external)
+    let encode_fn = lua.create_function(|lua, (value, options): (LuaValue, LuaValue)| {
+        encode_value(lua, value, options)
     })?;
     table.set("encode", encode_fn)?;
     Ok(())
@@ -38,16 +31,8 @@ fn register_decode(lua: &Lua, table: &Table) -> mlua::Result<()> {
 }
 
 fn register_stringify(lua: &Lua, table: &Table) -> mlua::Result<()> {
-    let stringify_fn = lua.create_function(|lua, (value, pretty): (LuaValue, Option<bool>)| {
-        let json_value = lua_to_json(lua, value)?;
-
-        let result = if pretty.unwrap_or(false) {
-            encode_pretty(&json_value)
-        } else {
-            encode(&json_value)
-        };
-
-        result.map_err(mlua::Error::external)
+    let stringify_fn = lua.create_function(|lua, (value, options): (LuaValue, LuaValue)| {
+        encode_value(lua, value, options)
     })?;
     table.set("stringify", stringify_fn)?;
     Ok(())
@@ -62,52 +47,38 @@ fn register_parse(lua: &Lua, table: &Table) -> mlua::Result<()> {
     Ok(())
 }
 
-fn lua_to_json(_lua: &Lua, value: LuaValue) -> mlua::Result<serde_json::Value> {
-    match value {
-        LuaValue::Nil => Ok(serde_json::Value::Null),
-        LuaValue::Boolean(b) => Ok(serde_json::Value::Bool(b)),
-        LuaValue::Integer(i) => Ok(serde_json::Value::Number(i.into())),
-        LuaValue::Number(n) => {
-            if let Some(num) = serde_json::Number::from_f64(n) {
-                Ok(serde_json::Value::Number(num))
-            } else {
-                Err(mlua::Error::external("Invalid number for JSON"))
-            }
-        }
-        LuaValue::String(s) => Ok(serde_json::Value::String(s.to_str()?.to_string())),
-        LuaValue::Table(t) => {
-            if is_array(&t)? {
-                let mut arr = Vec::new();
-                for pair in t.sequence_values::<LuaValue>() {
-                    arr.push(lua_to_json(_lua, pair?)?);
-                }
-                Ok(serde_json::Value::Array(arr))
-            } else {
-                let mut map = serde_json::Map::new();
-                for pair in t.pairs::<LuaValue, LuaValue>() {
-                    let (k, v) = pair?;
-                    let key = match k {
-                        LuaValue::String(s) => s.to_str()?.to_string(),
-                        LuaValue::Integer(i) => i.to_string(),
-                        LuaValue::Number(n) => n.to_string(),
-                        _ => {
-                            return Err(mlua::Error::external(
-                                "Table keys must be strings or numbers",
-                            ))
-                        }
-                    };
-                    map.insert(key, lua_to_json(_lua, v)?);
-                }
-                Ok(serde_json::Value::Object(map))
-            }
+/// `json.encode(value, pretty?)` or `json.encode(value, {pretty?, allowNonFinite?})`.
+fn encode_value(lua: &Lua, value: LuaValue, options: LuaValue) -> mlua::Result<String> {
+    let (pretty, allow_non_finite) = match options {
+        LuaValue::Nil => (false, false),
+        LuaValue::Boolean(pretty) => (pretty, false),
+        LuaValue::Table(options) => (
+            options.get::<_, Option<bool>>("pretty")?.unwrap_or(false),
+            options
+                .get::<_, Option<bool>>("allowNonFinite")?
+                .unwrap_or(false),
+        ),
+        other => {
+            return Err(mlua::Error::RuntimeError(format!(
+                "json.encode options must be a boolean or a table, got {}",
+                other.type_name()
+            )))
         }
-        _ => Err(mlua::Error::external(
-            "Unsupported Lua type for JSON conversion",
-        )),
-    }
+    };
+
+    let json_value = lua_to_json_with(lua, value, allow_non_finite)?;
+    let result = if pretty {
+        encode_pretty(&json_value)
+    } else {
+        encode(&json_value)
+    };
+    result.map_err(mlua::Error::external)
 }
 
-fn json_to_lua<'lua>(lua: &'lua Lua, value: &serde_json::Value) -> mlua::Result<LuaValue<'lua>> {
+pub(crate) fn json_to_lua<'lua>(
+    lua: &'lua Lua,
+    value: &serde_json::Value,
+) -> mlua::Result<LuaValue<'lua>> {
     match value {
         serde_json::Value::Null => Ok(LuaValue::Nil),
         serde_json::Value::Bool(b) => Ok(LuaValue::Boolean(*b)),
@@ -138,24 +109,6 @@ fn json_to_lua<'lua>(lua: &'lua Lua, value: &serde_json::Value) -> mlua::Result<
     }
 }
 
-fn is_array(table: &Table) -> mlua::Result<bool> {
-    let mut max_index = 0;
-    let mut count = 0;
-
-    for pair in table.clone().pairs::<LuaValue, LuaValue>() {
-        let (key, _) = pair?;
-        match key {
-            LuaValue::Integer(i) if i > 0 => {
-                max_index = max_index.max(i);
-                count += 1;
-            }
-            _ => return Ok(false),
-        }
-    }
-
-    Ok(count > 0 && count == max_index)
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
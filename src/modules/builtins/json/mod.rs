@@ -37,7 +37,7 @@ impl BuiltinModule for JsonModule {
             "encode": {
                 "__fn": "encode",
                 "__desc": "Encode Lua value to JSON string",
-                "__signature": "encode(value, pretty?: boolean) -> string"
+                "__signature": "encode(value, options?: boolean | {pretty?: boolean, allowNonFinite?: boolean}) -> string"
             },
             "decode": {
                 "__fn": "decode",
@@ -47,7 +47,7 @@ impl BuiltinModule for JsonModule {
             "stringify": {
                 "__fn": "stringify",
                 "__desc": "Alias for encode",
-                "__signature": "stringify(value, pretty?: boolean) -> string"
+                "__signature": "stringify(value, options?: boolean | {pretty?: boolean, allowNonFinite?: boolean}) -> string"
             },
             "parse": {
                 "__fn": "parse",
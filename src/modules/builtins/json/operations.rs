@@ -11,6 +11,16 @@ pub fn encode_pretty(value: &JsonValue) -> Result<String> {
     serde_json::to_string_pretty(value).map_err(Into::into)
 }
 
+/// JSON has no NaN or Infinity. Like most JSON libraries, write them as
+/// `null` when `allow_non_finite` is set and refuse them otherwise.
+pub fn encode_number(n: f64, allow_non_finite: bool) -> Result<JsonValue> {
+    match serde_json::Number::from_f64(n) {
+        Some(number) => Ok(JsonValue::Number(number)),
+        None if allow_non_finite => Ok(JsonValue::Null),
+        None => Err(JsonError::NonFinite(n)),
+    }
+}
+
 pub fn decode(json_str: &str) -> Result<JsonValue> {
     serde_json::from_str(json_str).map_err(Into::into)
 }
@@ -36,6 +46,22 @@ mod tests {
         assert!(result.contains("Bob"));
     }
 
+    #[test]
+    fn test_encode_number_non_finite() {
+        assert_eq!(encode_number(1.5, false).unwrap(), json!(1.5));
+        assert_eq!(encode_number(f64::INFINITY, true).unwrap(), JsonValue::Null);
+        assert_eq!(
+            encode_number(f64::NEG_INFINITY, false)
+                .unwrap_err()
+                .to_string(),
+            "-Infinity is not valid JSON (encode with {allowNonFinite = true} to write null)"
+        );
+        assert!(encode_number(f64::NAN, false)
+            .unwrap_err()
+            .to_string()
+            .starts_with("NaN is not valid JSON"));
+    }
+
     #[test]
     fn test_decode_object() {
         let json_str = r#"{"name":"Carol","age":25}"#;
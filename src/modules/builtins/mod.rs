@@ -3,21 +3,41 @@ use std::collections::HashMap;
 
 use crate::error::HypeError;
 
+pub mod buffer;
+#[cfg(feature = "clipboard")]
+pub mod clipboard;
+pub mod console;
 pub mod crypto;
+pub mod csv;
+pub mod diff;
+pub mod encoding;
 pub mod events;
 pub mod fs;
 #[cfg(feature = "http")]
 pub mod http;
+pub mod ini;
 pub mod json;
 pub mod os;
 pub mod path;
 pub mod process;
 pub mod querystring;
+pub mod semver;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+pub mod stats;
 pub mod string;
 pub mod table;
+pub mod template;
+pub mod term;
 pub mod time;
+pub mod toml;
 pub mod url;
 pub mod util;
+pub mod uuid;
+pub mod worker;
+pub mod xml;
+pub mod yaml;
+pub mod zlib;
 
 /// Trait for built-in modules.
 ///
@@ -75,8 +95,28 @@ impl BuiltinRegistry {
             "string" => string::StringModule::new().exports()?,
             "time" => time::TimeModule::new().exports()?,
             "url" => url::UrlModule::new().exports()?,
+            "uuid" => uuid::UuidModule::new().exports()?,
             "querystring" => querystring::QueryStringModule::new().exports()?,
             "crypto" => crypto::CryptoModule::new().exports()?,
+            "buffer" => buffer::BufferModule::new().exports()?,
+            "console" => console::ConsoleModule::new().exports()?,
+            "yaml" => yaml::YamlModule::new().exports()?,
+            "zlib" => zlib::ZlibModule::new().exports()?,
+            "toml" => toml::TomlModule::new().exports()?,
+            "csv" => csv::CsvModule::new().exports()?,
+            "stats" => stats::StatsModule::new().exports()?,
+            "semver" => semver::SemverModule::new().exports()?,
+            "encoding" => encoding::EncodingModule::new().exports()?,
+            "term" => term::TermModule::new().exports()?,
+            "ini" => ini::IniModule::new().exports()?,
+            "template" => template::TemplateModule::new().exports()?,
+            "diff" => diff::DiffModule::new().exports()?,
+            "worker" => worker::WorkerModule::new().exports()?,
+            "xml" => xml::XmlModule::new().exports()?,
+            #[cfg(feature = "sqlite")]
+            "sqlite" => sqlite::SqliteModule::new().exports()?,
+            #[cfg(feature = "clipboard")]
+            "clipboard" => clipboard::ClipboardModule::new().exports()?,
             #[cfg(feature = "http")]
             "http" => http::HttpModule::new().exports()?,
             _ => {
@@ -93,84 +133,49 @@ impl BuiltinRegistry {
 
     /// Check if a module is a built-in
     pub fn is_builtin(&self, name: &str) -> bool {
-        #[cfg(feature = "http")]
-        {
-            matches!(
-                name,
-                "fs" | "path"
-                    | "events"
-                    | "util"
-                    | "table"
-                    | "json"
-                    | "process"
-                    | "os"
-                    | "string"
-                    | "time"
-                    | "url"
-                    | "querystring"
-                    | "crypto"
-                    | "http"
-            )
-        }
-        #[cfg(not(feature = "http"))]
-        {
-            matches!(
-                name,
-                "fs" | "path"
-                    | "events"
-                    | "util"
-                    | "table"
-                    | "json"
-                    | "process"
-                    | "os"
-                    | "string"
-                    | "time"
-                    | "url"
-                    | "querystring"
-                    | "crypto"
-            )
-        }
+        self.list().contains(&name)
     }
 
     /// List all available built-in modules
     pub fn list(&self) -> Vec<&'static str> {
+        let mut modules = vec![
+            "fs",
+            "path",
+            "events",
+            "util",
+            "table",
+            "json",
+            "process",
+            "os",
+            "string",
+            "time",
+            "url",
+            "uuid",
+            "querystring",
+            "crypto",
+            "buffer",
+            "console",
+            "yaml",
+            "zlib",
+            "toml",
+            "csv",
+            "stats",
+            "semver",
+            "encoding",
+            "term",
+            "ini",
+            "template",
+            "diff",
+            "worker",
+            "xml",
+        ];
+        #[cfg(feature = "sqlite")]
+        modules.push("sqlite");
+        #[cfg(feature = "clipboard")]
+        modules.push("clipboard");
         #[cfg(feature = "http")]
-        {
-            vec![
-                "fs",
-                "path",
-                "events",
-                "util",
-                "table",
-                "json",
-                "process",
-                "os",
-                "string",
-                "time",
-                "url",
-                "querystring",
-                "crypto",
-                "http",
-            ]
-        }
-        #[cfg(not(feature = "http"))]
-        {
-            vec![
-                "fs",
-                "path",
-                "events",
-                "util",
-                "table",
-                "json",
-                "process",
-                "os",
-                "string",
-                "time",
-                "url",
-                "querystring",
-                "crypto",
-            ]
-        }
+        modules.push("http");
+        modules
     }
 
     /// Clear the module cache
@@ -231,9 +236,84 @@ impl BuiltinRegistry {
                 .map_err(|e| {
                     HypeError::Execution(format!("Failed to create crypto module: {}", e))
                 }),
+            "buffer" => buffer::create_buffer_module(lua)
+                .map(mlua::Value::Table)
+                .map_err(|e| {
+                    HypeError::Execution(format!("Failed to create buffer module: {}", e))
+                }),
             "http" => http::create_http_module(lua)
                 .map(mlua::Value::Table)
                 .map_err(|e| HypeError::Execution(format!("Failed to create http module: {}", e))),
+            "yaml" => yaml::create_yaml_module(lua)
+                .map(mlua::Value::Table)
+                .map_err(|e| HypeError::Execution(format!("Failed to create yaml module: {}", e))),
+            "toml" => toml::create_toml_module(lua)
+                .map(mlua::Value::Table)
+                .map_err(|e| HypeError::Execution(format!("Failed to create toml module: {}", e))),
+            "csv" => csv::create_csv_module(lua)
+                .map(mlua::Value::Table)
+                .map_err(|e| HypeError::Execution(format!("Failed to create csv module: {}", e))),
+            "util" => util::create_util_module(lua)
+                .map(mlua::Value::Table)
+                .map_err(|e| HypeError::Execution(format!("Failed to create util module: {}", e))),
+            "zlib" => zlib::create_zlib_module(lua)
+                .map(mlua::Value::Table)
+                .map_err(|e| HypeError::Execution(format!("Failed to create zlib module: {}", e))),
+            "console" => console::create_console_module(lua)
+                .map(mlua::Value::Table)
+                .map_err(|e| {
+                    HypeError::Execution(format!("Failed to create console module: {}", e))
+                }),
+            "uuid" => uuid::create_uuid_module(lua)
+                .map(mlua::Value::Table)
+                .map_err(|e| HypeError::Execution(format!("Failed to create uuid module: {}", e))),
+            "stats" => stats::create_stats_module(lua)
+                .map(mlua::Value::Table)
+                .map_err(|e| HypeError::Execution(format!("Failed to create stats module: {}", e))),
+            "semver" => semver::create_semver_module(lua)
+                .map(mlua::Value::Table)
+                .map_err(|e| {
+                    HypeError::Execution(format!("Failed to create semver module: {}", e))
+                }),
+            "encoding" => encoding::create_encoding_module(lua)
+                .map(mlua::Value::Table)
+                .map_err(|e| {
+                    HypeError::Execution(format!("Failed to create encoding module: {}", e))
+                }),
+            "term" => term::create_term_module(lua)
+                .map(mlua::Value::Table)
+                .map_err(|e| HypeError::Execution(format!("Failed to create term module: {}", e))),
+            "ini" => ini::create_ini_module(lua)
+                .map(mlua::Value::Table)
+                .map_err(|e| HypeError::Execution(format!("Failed to create ini module: {}", e))),
+            "template" => template::create_template_module(lua)
+                .map(mlua::Value::Table)
+                .map_err(|e| {
+                    HypeError::Execution(format!("Failed to create template module: {}", e))
+                }),
+            "diff" => diff::create_diff_module(lua)
+                .map(mlua::Value::Table)
+                .map_err(|e| HypeError::Execution(format!("Failed to create diff module: {}", e))),
+            "worker" => worker::create_worker_module(lua)
+                .map(mlua::Value::Table)
+                .map_err(|e| {
+                    HypeError::Execution(format!("Failed to create worker module: {}", e))
+                }),
+            "xml" => xml::create_xml_module(lua)
+                .map(mlua::Value::Table)
+                .map_err(|e| HypeError::Execution(format!("Failed to create xml module: {}", e))),
+            #[cfg(feature = "sqlite")]
+            "sqlite" => sqlite::create_sqlite_module(lua)
+                .map(mlua::Value::Table)
+                .map_err(|e| {
+                    HypeError::Execution(format!("Failed to create sqlite module: {}", e))
+                }),
+            #[cfg(feature = "clipboard")]
+            "clipboard" => clipboard::create_clipboard_module(lua)
+                .map(mlua::Value::Table)
+                .map_err(|e| {
+                    HypeError::Execution(format!("Failed to create clipboard module: {}", e))
+                }),
             _ => {
                 let json_exports = self.load(name)?;
                 crate::lua::require::json_to_lua(lua, &json_exports).map_err(|e| {
@@ -285,6 +365,81 @@ impl BuiltinRegistry {
                 .map_err(|e| {
                     HypeError::Execution(format!("Failed to create crypto module: {}", e))
                 }),
+            "buffer" => buffer::create_buffer_module(lua)
+                .map(mlua::Value::Table)
+                .map_err(|e| {
+                    HypeError::Execution(format!("Failed to create buffer module: {}", e))
+                }),
+            "yaml" => yaml::create_yaml_module(lua)
+                .map(mlua::Value::Table)
+                .map_err(|e| HypeError::Execution(format!("Failed to create yaml module: {}", e))),
+            "toml" => toml::create_toml_module(lua)
+                .map(mlua::Value::Table)
+                .map_err(|e| HypeError::Execution(format!("Failed to create toml module: {}", e))),
+            "csv" => csv::create_csv_module(lua)
+                .map(mlua::Value::Table)
+                .map_err(|e| HypeError::Execution(format!("Failed to create csv module: {}", e))),
+            "util" => util::create_util_module(lua)
+                .map(mlua::Value::Table)
+                .map_err(|e| HypeError::Execution(format!("Failed to create util module: {}", e))),
+            "zlib" => zlib::create_zlib_module(lua)
+                .map(mlua::Value::Table)
+                .map_err(|e| HypeError::Execution(format!("Failed to create zlib module: {}", e))),
+            "console" => console::create_console_module(lua)
+                .map(mlua::Value::Table)
+                .map_err(|e| {
+                    HypeError::Execution(format!("Failed to create console module: {}", e))
+                }),
+            "uuid" => uuid::create_uuid_module(lua)
+                .map(mlua::Value::Table)
+                .map_err(|e| HypeError::Execution(format!("Failed to create uuid module: {}", e))),
+            "stats" => stats::create_stats_module(lua)
+                .map(mlua::Value::Table)
+                .map_err(|e| HypeError::Execution(format!("Failed to create stats module: {}", e))),
+            "semver" => semver::create_semver_module(lua)
+                .map(mlua::Value::Table)
+                .map_err(|e| {
+                    HypeError::Execution(format!("Failed to create semver module: {}", e))
+                }),
+            "encoding" => encoding::create_encoding_module(lua)
+                .map(mlua::Value::Table)
+                .map_err(|e| {
+                    HypeError::Execution(format!("Failed to create encoding module: {}", e))
+                }),
+            "term" => term::create_term_module(lua)
+                .map(mlua::Value::Table)
+                .map_err(|e| HypeError::Execution(format!("Failed to create term module: {}", e))),
+            "ini" => ini::create_ini_module(lua)
+                .map(mlua::Value::Table)
+                .map_err(|e| HypeError::Execution(format!("Failed to create ini module: {}", e))),
+            "template" => template::create_template_module(lua)
+                .map(mlua::Value::Table)
+                .map_err(|e| {
+                    HypeError::Execution(format!("Failed to create template module: {}", e))
+                }),
+            "diff" => diff::create_diff_module(lua)
+                .map(mlua::Value::Table)
+                .map_err(|e| HypeError::Execution(format!("Failed to create diff module: {}", e))),
+            "worker" => worker::create_worker_module(lua)
+                .map(mlua::Value::Table)
+                .map_err(|e| {
+                    HypeError::Execution(format!("Failed to create worker module: {}", e))
+                }),
+            "xml" => xml::create_xml_module(lua)
+                .map(mlua::Value::Table)
+                .map_err(|e| HypeError::Execution(format!("Failed to create xml module: {}", e))),
+            #[cfg(feature = "sqlite")]
+            "sqlite" => sqlite::create_sqlite_module(lua)
+                .map(mlua::Value::Table)
+                .map_err(|e| {
+                    HypeError::Execution(format!("Failed to create sqlite module: {}", e))
+                }),
+            #[cfg(feature = "clipboard")]
+            "clipboard" => clipboard::create_clipboard_module(lua)
+                .map(mlua::Value::Table)
+                .map_err(|e| {
+                    HypeError::Execution(format!("Failed to create clipboard module: {}", e))
+                }),
             _ => {
                 let json_exports = self.load(name)?;
                 crate::lua::require::json_to_lua(lua, &json_exports).map_err(|e| {
@@ -328,10 +483,10 @@ mod tests {
     fn test_builtin_registry_list() {
         let registry = BuiltinRegistry::new();
         let list = registry.list();
-        #[cfg(feature = "http")]
-        assert_eq!(list.len(), 14);
-        #[cfg(not(feature = "http"))]
-        assert_eq!(list.len(), 13);
+        let optional = cfg!(feature = "http") as usize
+            + cfg!(feature = "sqlite") as usize
+            + cfg!(feature = "clipboard") as usize;
+        assert_eq!(list.len(), 29 + optional);
         assert!(list.contains(&"fs"));
         assert!(list.contains(&"path"));
         assert!(list.contains(&"events"));
@@ -343,10 +498,30 @@ mod tests {
         assert!(list.contains(&"string"));
         assert!(list.contains(&"time"));
         assert!(list.contains(&"url"));
+        assert!(list.contains(&"uuid"));
         assert!(list.contains(&"querystring"));
         assert!(list.contains(&"crypto"));
+        assert!(list.contains(&"buffer"));
+        assert!(list.contains(&"console"));
+        assert!(list.contains(&"yaml"));
+        assert!(list.contains(&"zlib"));
+        assert!(list.contains(&"toml"));
+        assert!(list.contains(&"csv"));
+        assert!(list.contains(&"stats"));
+        assert!(list.contains(&"semver"));
+        assert!(list.contains(&"encoding"));
+        assert!(list.contains(&"term"));
+        assert!(list.contains(&"ini"));
+        assert!(list.contains(&"template"));
+        assert!(list.contains(&"diff"));
+        assert!(list.contains(&"worker"));
+        assert!(list.contains(&"xml"));
         #[cfg(feature = "http")]
         assert!(list.contains(&"http"));
+        #[cfg(feature = "sqlite")]
+        assert!(list.contains(&"sqlite"));
+        #[cfg(feature = "clipboard")]
+        assert!(list.contains(&"clipboard"));
     }
 
     #[test]
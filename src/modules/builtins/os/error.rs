@@ -5,9 +5,12 @@ pub enum OsError {
     HostnameError(String),
     HomeDirNotFound,
     TempDirNotFound,
+    ExecPathError(String),
     SystemInfoError(String),
     NetworkInterfacesError(String),
     UserInfoError(String),
+    InvalidPrefix(String),
+    TempCreateError(String),
 }
 
 impl fmt::Display for OsError {
@@ -16,11 +19,22 @@ impl fmt::Display for OsError {
             OsError::HostnameError(err) => write!(f, "Failed to get hostname: {}", err),
             OsError::HomeDirNotFound => write!(f, "Home directory not found"),
             OsError::TempDirNotFound => write!(f, "Temp directory not found"),
+            OsError::ExecPathError(err) => {
+                write!(f, "Failed to locate the hype executable: {}", err)
+            }
             OsError::SystemInfoError(err) => write!(f, "Failed to get system info: {}", err),
             OsError::NetworkInterfacesError(err) => {
                 write!(f, "Failed to get network interfaces: {}", err)
             }
             OsError::UserInfoError(err) => write!(f, "Failed to get user info: {}", err),
+            OsError::InvalidPrefix(prefix) => {
+                write!(
+                    f,
+                    "Invalid temp prefix '{}': must not contain path separators",
+                    prefix
+                )
+            }
+            OsError::TempCreateError(err) => write!(f, "Failed to create temp path: {}", err),
         }
     }
 }
@@ -1,4 +1,6 @@
 use super::operations::*;
+use crate::lua::error::structured_error;
+use crate::lua::security::check_file_access;
 use mlua::{Lua, Result as LuaResult, Table, Value};
 
 pub fn create_os_module(lua: &Lua) -> LuaResult<Table> {
@@ -28,6 +30,20 @@ pub fn create_os_module(lua: &Lua) -> LuaResult<Table> {
     })?;
     os.set("tmpdir", tmpdir_fn)?;
 
+    let exec_path_fn = lua.create_function(|_, ()| match exec_path() {
+        Ok(path) => Ok(path),
+        Err(e) => Err(mlua::Error::RuntimeError(e.to_string())),
+    })?;
+    os.set("execPath", exec_path_fn)?;
+
+    let mkdtemp_fn = lua.create_function(|lua, prefix: Option<String>| {
+        let parent = tmpdir().map_err(|e| mlua::Error::RuntimeError(e.to_string()))?;
+        check_file_access(lua, &parent, "write").map_err(structured_error)?;
+        mkdtemp(prefix.as_deref().unwrap_or(DEFAULT_TEMP_PREFIX))
+            .map_err(|e| mlua::Error::RuntimeError(e.to_string()))
+    })?;
+    os.set("mkdtemp", mkdtemp_fn)?;
+
     let cpus_fn = lua.create_function(|lua, ()| match cpus() {
         Ok(cpu_list) => {
             let result = lua.create_table()?;
@@ -140,6 +156,8 @@ mod tests {
         assert!(os.contains_key("hostname").unwrap());
         assert!(os.contains_key("homedir").unwrap());
         assert!(os.contains_key("tmpdir").unwrap());
+        assert!(os.contains_key("execPath").unwrap());
+        assert!(os.contains_key("mkdtemp").unwrap());
         assert!(os.contains_key("cpus").unwrap());
         assert!(os.contains_key("totalmem").unwrap());
         assert!(os.contains_key("freemem").unwrap());
@@ -59,6 +59,16 @@ impl BuiltinModule for OsModule {
                 "__desc": "Get system temp directory",
                 "__signature": "tmpdir() -> string"
             },
+            "execPath": {
+                "__fn": "execPath",
+                "__desc": "Get the absolute path of the hype executable",
+                "__signature": "execPath() -> string"
+            },
+            "mkdtemp": {
+                "__fn": "mkdtemp",
+                "__desc": "Create a uniquely named directory in the system temp directory",
+                "__signature": "mkdtemp(prefix?: string) -> string"
+            },
             "cpus": {
                 "__fn": "cpus",
                 "__desc": "Get CPU information",
@@ -1,5 +1,9 @@
 use super::error::OsError;
+use crate::modules::builtins::crypto::operations::random_bytes;
 use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
 use sysinfo::System;
 
 pub fn platform() -> &'static str {
@@ -49,6 +53,50 @@ pub fn tmpdir() -> Result<String, OsError> {
         .ok_or(OsError::TempDirNotFound)
 }
 
+/// Absolute path of the running hype binary.
+pub fn exec_path() -> Result<String, OsError> {
+    let path = env::current_exe().map_err(|e| OsError::ExecPathError(e.to_string()))?;
+    Ok(path.to_string_lossy().into_owned())
+}
+
+pub const DEFAULT_TEMP_PREFIX: &str = "hype-";
+
+/// How many names `mkdtemp` and `fs.mkstemp` try before giving up on collisions.
+pub const TEMP_ATTEMPTS: usize = 16;
+
+/// A path in the system temp directory named `prefix` plus a random suffix.
+/// The prefix may not contain path separators.
+pub fn temp_name(prefix: &str) -> Result<PathBuf, OsError> {
+    if prefix.contains(['/', '\\']) || prefix == ".." {
+        return Err(OsError::InvalidPrefix(prefix.to_string()));
+    }
+    let suffix = random_bytes(6).map_err(|e| OsError::TempCreateError(e.to_string()))?;
+    Ok(env::temp_dir().join(format!("{}{}", prefix, hex::encode(suffix))))
+}
+
+/// Create a new, uniquely named directory under the system temp directory.
+pub fn mkdtemp(prefix: &str) -> Result<String, OsError> {
+    for _ in 0..TEMP_ATTEMPTS {
+        let path = temp_name(prefix)?;
+        match create_private_dir(&path) {
+            Ok(()) => return Ok(path.to_string_lossy().into_owned()),
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(OsError::TempCreateError(e.to_string())),
+        }
+    }
+    Err(OsError::TempCreateError(
+        "could not find an unused name".to_string(),
+    ))
+}
+
+/// Like `mkdir`, readable only by the owner on Unix.
+fn create_private_dir(path: &Path) -> io::Result<()> {
+    let mut builder = fs::DirBuilder::new();
+    #[cfg(unix)]
+    std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+    builder.create(path)
+}
+
 pub struct CpuInfo {
     pub model: String,
     pub speed: u64,
@@ -193,6 +241,12 @@ mod tests {
         assert!(!result.unwrap().is_empty());
     }
 
+    #[test]
+    fn test_exec_path() {
+        let path = exec_path().unwrap();
+        assert!(Path::new(&path).is_file());
+    }
+
     #[test]
     fn test_tmpdir() {
         let result = tmpdir();
@@ -200,6 +254,29 @@ mod tests {
         assert!(!result.unwrap().is_empty());
     }
 
+    #[test]
+    fn test_mkdtemp_creates_distinct_directories() {
+        let first = mkdtemp("hype-test-").unwrap();
+        let second = mkdtemp("hype-test-").unwrap();
+
+        assert_ne!(first, second);
+        for dir in [&first, &second] {
+            let path = std::path::Path::new(dir);
+            assert!(path.is_dir(), "{}", dir);
+            assert!(path.starts_with(env::temp_dir()));
+            assert!(path
+                .file_name()
+                .unwrap()
+                .to_string_lossy()
+                .starts_with("hype-test-"));
+            fs::remove_dir(path).unwrap();
+        }
+        assert!(matches!(
+            mkdtemp("../escape"),
+            Err(OsError::InvalidPrefix(_))
+        ));
+    }
+
     #[test]
     fn test_cpus() {
         let result = cpus();
@@ -6,6 +6,8 @@ pub enum ProcessError {
     InvalidPath(String),
     InvalidExitCode,
     PermissionDenied(String),
+    CommandFailed(String),
+    InvalidEnvFile { line: usize, message: String },
 }
 
 impl fmt::Display for ProcessError {
@@ -15,6 +17,10 @@ impl fmt::Display for ProcessError {
             ProcessError::InvalidPath(msg) => write!(f, "Invalid path: {}", msg),
             ProcessError::InvalidExitCode => write!(f, "Invalid exit code (must be 0-255)"),
             ProcessError::PermissionDenied(msg) => write!(f, "Permission denied: {}", msg),
+            ProcessError::CommandFailed(msg) => write!(f, "Command failed: {}", msg),
+            ProcessError::InvalidEnvFile { line, message } => {
+                write!(f, "Invalid env file at line {}: {}", line, message)
+            }
         }
     }
 }
@@ -1,5 +1,21 @@
+use super::error::ProcessError;
 use super::operations::*;
-use mlua::{Lua, Table, Value as LuaValue};
+use super::signals::{self, Signal};
+use crate::engine::output::StdoutRedirect;
+use crate::lua::security::check_file_access;
+use crate::lua::subprocess_allowed;
+use mlua::{
+    FromLua, Function, Lua, String as LuaString, Table, UserData, UserDataFields, UserDataMethods,
+    Value as LuaValue,
+};
+use std::path::Path;
+use std::process::Child;
+use std::time::Duration;
+
+const EXIT_HOOKS_KEY: &str = "hype.process.exit_hooks";
+const SIGNAL_HANDLERS_KEY: &str = "hype.process.signal_handlers";
+
+struct RunningExitHooks;
 
 pub fn create_process_module(lua: &Lua) -> mlua::Result<Table> {
     let process_table = lua.create_table()?;
@@ -9,11 +25,18 @@ pub fn create_process_module(lua: &Lua) -> mlua::Result<Table> {
     register_env(lua, &process_table)?;
     register_getenv(lua, &process_table)?;
     register_setenv(lua, &process_table)?;
+    register_load_env(lua, &process_table)?;
     register_pid(lua, &process_table)?;
     register_platform(lua, &process_table)?;
     register_arch(lua, &process_table)?;
+    register_hrtime(lua, &process_table)?;
     register_exit(lua, &process_table)?;
+    register_on_exit(lua, &process_table)?;
+    register_on(lua, &process_table)?;
     register_argv(lua, &process_table)?;
+    register_exec(lua, &process_table)?;
+    register_spawn(lua, &process_table)?;
+    register_stdio(lua, &process_table)?;
 
     Ok(process_table)
 }
@@ -32,10 +55,8 @@ fn register_chdir(lua: &Lua, table: &Table) -> mlua::Result<()> {
 }
 
 fn register_env(lua: &Lua, table: &Table) -> mlua::Result<()> {
+    // Kept empty so every access goes through the metamethods to the live environment
     let env_table = lua.create_table()?;
-    for (key, value) in get_env() {
-        env_table.set(key, value)?;
-    }
 
     let env_metatable = lua.create_table()?;
     env_metatable.set(
@@ -53,6 +74,17 @@ fn register_env(lua: &Lua, table: &Table) -> mlua::Result<()> {
             Ok(())
         })?,
     )?;
+    env_metatable.set(
+        "__pairs",
+        lua.create_function(|lua, _: LuaValue| {
+            let snapshot = lua.create_table()?;
+            for (key, value) in get_env() {
+                snapshot.set(key, value)?;
+            }
+            let next: mlua::Function = lua.globals().get("next")?;
+            Ok((next, snapshot, LuaValue::Nil))
+        })?,
+    )?;
 
     env_table.set_metatable(Some(env_metatable));
     table.set("env", env_table)?;
@@ -74,6 +106,38 @@ fn register_setenv(lua: &Lua, table: &Table) -> mlua::Result<()> {
     Ok(())
 }
 
+/// `process.loadEnv(path?, opts?)`: load a dotenv file. A missing default
+/// `.env` is not an error; a missing explicit path is.
+fn register_load_env(lua: &Lua, table: &Table) -> mlua::Result<()> {
+    let load_env_fn = lua.create_function(|lua, (first, second): (LuaValue, Option<Table>)| {
+        let (path, options) = match first {
+            LuaValue::Nil => (None, second),
+            LuaValue::Table(options) => (None, Some(options)),
+            other => (Some(String::from_lua(other, lua)?), second),
+        };
+        let override_existing = match &options {
+            Some(options) => options.get::<_, Option<bool>>("override")?.unwrap_or(false),
+            None => false,
+        };
+
+        let explicit = path.is_some();
+        let path = path.unwrap_or_else(|| DEFAULT_ENV_FILE.to_string());
+        if !explicit && !Path::new(&path).exists() {
+            return lua.create_table();
+        }
+        check_file_access(lua, &path, "read").map_err(mlua::Error::external)?;
+
+        let pairs = load_env(Path::new(&path), override_existing).map_err(mlua::Error::external)?;
+        let result = lua.create_table()?;
+        for (key, value) in pairs {
+            result.set(key, value)?;
+        }
+        Ok(result)
+    })?;
+    table.set("loadEnv", load_env_fn)?;
+    Ok(())
+}
+
 fn register_pid(_lua: &Lua, table: &Table) -> mlua::Result<()> {
     table.set("pid", get_pid())?;
     Ok(())
@@ -89,25 +153,343 @@ fn register_arch(_lua: &Lua, table: &Table) -> mlua::Result<()> {
     Ok(())
 }
 
+fn register_hrtime(lua: &Lua, table: &Table) -> mlua::Result<()> {
+    process_start();
+    let hrtime_fn = lua.create_function(|lua, previous: Option<Table>| {
+        let previous = match previous {
+            Some(reading) => {
+                let seconds: u64 = reading.get(1)?;
+                let nanoseconds: u32 = reading.get(2)?;
+                if nanoseconds >= 1_000_000_000 {
+                    return Err(mlua::Error::external(
+                        "hrtime reading must have nanoseconds below 1e9",
+                    ));
+                }
+                Some(Duration::new(seconds, nanoseconds))
+            }
+            None => None,
+        };
+        let elapsed = hrtime(previous);
+        lua.create_sequence_from([elapsed.as_secs(), u64::from(elapsed.subsec_nanos())])
+    })?;
+    table.set("hrtime", hrtime_fn)?;
+    Ok(())
+}
+
 fn register_exit(lua: &Lua, table: &Table) -> mlua::Result<()> {
-    let exit_fn = lua.create_function(|_, code: Option<i32>| -> mlua::Result<()> {
+    let exit_fn = lua.create_function(|lua, code: Option<i32>| -> mlua::Result<()> {
         let exit_code = code.unwrap_or(0);
         if !(0..=255).contains(&exit_code) {
             return Err(mlua::Error::external("Exit code must be between 0 and 255"));
         }
+        if lua.app_data_ref::<RunningExitHooks>().is_some() {
+            return Err(mlua::Error::external(
+                "process.exit cannot be called from an onExit hook",
+            ));
+        }
+        run_exit_hooks(lua, exit_code);
         exit(exit_code);
     })?;
     table.set("exit", exit_fn)?;
     Ok(())
 }
 
+fn register_on_exit(lua: &Lua, table: &Table) -> mlua::Result<()> {
+    let on_exit_fn = lua.create_function(|lua, callback: Function| {
+        let hooks = match lua.named_registry_value::<Option<Table>>(EXIT_HOOKS_KEY)? {
+            Some(hooks) => hooks,
+            None => {
+                let hooks = lua.create_table()?;
+                lua.set_named_registry_value(EXIT_HOOKS_KEY, hooks.clone())?;
+                hooks
+            }
+        };
+        hooks.raw_push(callback)
+    })?;
+    table.set("onExit", on_exit_fn)?;
+    Ok(())
+}
+
+/// Run callbacks registered with `process.onExit`, most recent first.
+///
+/// Each hook receives the exit code. Hooks run at most once; a failing hook is
+/// reported on stderr and does not prevent the remaining hooks from running.
+pub fn run_exit_hooks(lua: &Lua, code: i32) {
+    let hooks = match lua.named_registry_value::<Option<Table>>(EXIT_HOOKS_KEY) {
+        Ok(Some(hooks)) => hooks,
+        _ => return,
+    };
+    let _ = lua.unset_named_registry_value(EXIT_HOOKS_KEY);
+
+    lua.set_app_data(RunningExitHooks);
+    for index in (1..=hooks.raw_len()).rev() {
+        let result = hooks
+            .raw_get::<_, Function>(index)
+            .and_then(|hook| hook.call::<_, ()>(code));
+        if let Err(e) = result {
+            eprintln!("Error in onExit hook: {}", e);
+        }
+    }
+    lua.remove_app_data::<RunningExitHooks>();
+}
+
+fn register_on(lua: &Lua, table: &Table) -> mlua::Result<()> {
+    let on_fn = lua.create_function(|lua, (name, callback): (String, Function)| {
+        let signal = Signal::from_name(&name).ok_or_else(|| {
+            mlua::Error::external(format!(
+                "Unsupported signal '{}': expected SIGINT or SIGTERM",
+                name
+            ))
+        })?;
+
+        let handlers = match lua.named_registry_value::<Option<Table>>(SIGNAL_HANDLERS_KEY)? {
+            Some(handlers) => handlers,
+            None => {
+                let handlers = lua.create_table()?;
+                lua.set_named_registry_value(SIGNAL_HANDLERS_KEY, handlers.clone())?;
+                handlers
+            }
+        };
+        let callbacks = match handlers.get::<_, Option<Table>>(signal.name())? {
+            Some(callbacks) => callbacks,
+            None => {
+                let callbacks = lua.create_table()?;
+                handlers.set(signal.name(), callbacks.clone())?;
+                callbacks
+            }
+        };
+        callbacks.raw_push(callback)?;
+
+        signals::listen(signal).map_err(mlua::Error::external)
+    })?;
+    table.set("on", on_fn)?;
+    Ok(())
+}
+
+/// Run the `process.on` handlers for signals received since the last call.
+/// Called at safe points: the instruction hook and blocking builtins.
+pub fn dispatch_signals(lua: &Lua) -> mlua::Result<()> {
+    if !signals::has_pending() {
+        return Ok(());
+    }
+    let Some(handlers) = lua.named_registry_value::<Option<Table>>(SIGNAL_HANDLERS_KEY)? else {
+        return Ok(());
+    };
+
+    for signal in signals::take_pending() {
+        let Some(callbacks) = handlers.get::<_, Option<Table>>(signal.name())? else {
+            continue;
+        };
+        for callback in callbacks.sequence_values::<Function>() {
+            callback?.call::<_, ()>(signal.name())?;
+        }
+    }
+    Ok(())
+}
+
+fn register_stdio(lua: &Lua, table: &Table) -> mlua::Result<()> {
+    let stdin = lua.create_table()?;
+    stdin.set("isTTY", stdin_is_tty())?;
+    let read_fn = lua.create_function(|lua, ()| {
+        let data = read_stdin().map_err(mlua::Error::external)?;
+        lua.create_string(&data)
+    })?;
+    stdin.set("read", read_fn)?;
+    let lines_fn = lua.create_function(|lua, ()| {
+        lua.create_function(
+            |lua, ()| match read_stdin_line().map_err(mlua::Error::external)? {
+                Some(line) => Ok(LuaValue::String(lua.create_string(&line)?)),
+                None => Ok(LuaValue::Nil),
+            },
+        )
+    })?;
+    stdin.set("lines", lines_fn)?;
+    table.set("stdin", stdin)?;
+
+    let stdout = lua.create_table()?;
+    let stdout_write = lua.create_function(|lua, data: LuaString| {
+        // Under `hype run` print is buffered, so raw writes join the same buffer
+        if let Some(redirect) = lua.app_data_ref::<StdoutRedirect>() {
+            if let Ok(capture) = redirect.0.lock() {
+                capture.capture_stdout(&data.to_string_lossy());
+            }
+            return Ok(());
+        }
+        write_raw(&mut std::io::stdout(), data.as_bytes()).map_err(mlua::Error::external)
+    })?;
+    stdout.set("write", stdout_write)?;
+    table.set("stdout", stdout)?;
+
+    let set_line_buffered = lua.create_function(|lua, enabled: bool| {
+        if let Some(redirect) = lua.app_data_ref::<StdoutRedirect>() {
+            if let Ok(mut capture) = redirect.0.lock() {
+                capture.set_line_buffered(enabled);
+            }
+            return Ok(());
+        }
+        // Without a capture, print goes through Lua's C stdio handle
+        let mode = if enabled { "line" } else { "full" };
+        call_lua_stdout(lua, "setvbuf", Some(mode))
+    })?;
+    table.set("setStdoutLineBuffered", set_line_buffered)?;
+
+    let flush = lua.create_function(|lua, ()| {
+        if let Some(redirect) = lua.app_data_ref::<StdoutRedirect>() {
+            if let Ok(capture) = redirect.0.lock() {
+                capture.flush_stdout();
+            }
+        }
+        call_lua_stdout(lua, "flush", None)?;
+        std::io::Write::flush(&mut std::io::stdout()).map_err(mlua::Error::external)
+    })?;
+    table.set("flush", flush)?;
+
+    let stderr = lua.create_table()?;
+    let stderr_write = lua.create_function(|_, data: LuaString| {
+        write_raw(&mut std::io::stderr(), data.as_bytes()).map_err(mlua::Error::external)
+    })?;
+    stderr.set("write", stderr_write)?;
+    table.set("stderr", stderr)?;
+    Ok(())
+}
+
+/// Call a method on Lua's `io.stdout` file handle, if the io library is loaded.
+fn call_lua_stdout(lua: &Lua, method: &str, arg: Option<&str>) -> mlua::Result<()> {
+    lua.load(
+        "local method, arg = ...
+        if type(io) == 'table' and type(io.type) == 'function' and io.type(io.stdout) == 'file' then
+            io.stdout[method](io.stdout, arg)
+        end",
+    )
+    .set_name("process.stdout")
+    .call((method, arg))
+}
+
+/// The script being run and the arguments passed to it, installed by the
+/// executor so `process.argv` does not depend on how hype was invoked.
+pub struct ScriptArgv {
+    pub script: String,
+    pub args: Vec<String>,
+}
+
 fn register_argv(lua: &Lua, table: &Table) -> mlua::Result<()> {
-    let args: Vec<String> = std::env::args().collect();
+    let mut process_args = std::env::args();
+    let argv0 = process_args.next();
+
+    // argv[0] is the script, argv[1..] its arguments, mirroring Lua's `arg`.
+    // Outside a script run, fall back to the raw process arguments.
+    let (first, rest) = match lua.app_data_ref::<ScriptArgv>() {
+        Some(script) => (Some(script.script.clone()), script.args.clone()),
+        None => (argv0.clone(), process_args.collect()),
+    };
     let argv_table = lua.create_table()?;
-    for (i, arg) in args.iter().enumerate() {
-        argv_table.set(i + 1, arg.as_str())?;
+    if let Some(first) = first {
+        argv_table.set(0, first)?;
+    }
+    for (i, arg) in rest.into_iter().enumerate() {
+        argv_table.set(i + 1, arg)?;
     }
     table.set("argv", argv_table)?;
+    table.set("argv0", argv0)?;
+
+    let exec_path = std::env::current_exe()
+        .ok()
+        .map(|path| path.to_string_lossy().into_owned());
+    table.set("execPath", exec_path)?;
+    Ok(())
+}
+
+struct ChildHandle {
+    pid: u32,
+    child: Option<Child>,
+}
+
+impl UserData for ChildHandle {
+    fn add_fields<'lua, F: UserDataFields<'lua, Self>>(fields: &mut F) {
+        fields.add_field_method_get("pid", |_, this| Ok(this.pid));
+    }
+
+    fn add_methods<'lua, M: UserDataMethods<'lua, Self>>(methods: &mut M) {
+        methods.add_method_mut("wait", |lua, this, ()| {
+            let child = this
+                .child
+                .take()
+                .ok_or_else(|| mlua::Error::external("process has already been waited on"))?;
+            let output = wait_child(child).map_err(mlua::Error::external)?;
+            exec_output_to_table(lua, output)
+        });
+
+        methods.add_method_mut("kill", |_, this, ()| {
+            if let Some(child) = this.child.as_mut() {
+                child.kill().map_err(mlua::Error::external)?;
+            }
+            Ok(())
+        });
+    }
+}
+
+fn ensure_subprocess_allowed(lua: &Lua) -> mlua::Result<()> {
+    if subprocess_allowed(lua) {
+        Ok(())
+    } else {
+        Err(mlua::Error::external(ProcessError::PermissionDenied(
+            "subprocess execution is disabled by the security policy (allow_subprocess)"
+                .to_string(),
+        )))
+    }
+}
+
+fn parse_exec_options(options: Option<Table>) -> mlua::Result<ExecOptions> {
+    let mut exec_options = ExecOptions::default();
+    if let Some(opts) = options {
+        exec_options.cwd = opts.get("cwd")?;
+        exec_options.stdin = opts.get("stdin")?;
+        if let Some(env_table) = opts.get::<_, Option<Table>>("env")? {
+            for pair in env_table.pairs::<String, String>() {
+                let (key, value) = pair?;
+                exec_options.env.insert(key, value);
+            }
+        }
+    }
+    Ok(exec_options)
+}
+
+fn exec_output_to_table(lua: &Lua, output: ExecOutput) -> mlua::Result<Table<'_>> {
+    let table = lua.create_table()?;
+    table.set("stdout", output.stdout)?;
+    table.set("stderr", output.stderr)?;
+    table.set("code", output.code)?;
+    Ok(table)
+}
+
+fn register_exec(lua: &Lua, table: &Table) -> mlua::Result<()> {
+    let exec_fn = lua.create_function(
+        |lua, (cmd, args, options): (String, Option<Vec<String>>, Option<Table>)| {
+            ensure_subprocess_allowed(lua)?;
+            let options = parse_exec_options(options)?;
+            let output =
+                exec(&cmd, &args.unwrap_or_default(), &options).map_err(mlua::Error::external)?;
+            exec_output_to_table(lua, output)
+        },
+    )?;
+    table.set("exec", exec_fn)?;
+    Ok(())
+}
+
+fn register_spawn(lua: &Lua, table: &Table) -> mlua::Result<()> {
+    let spawn_fn = lua.create_function(
+        |lua, (cmd, args, options): (String, Option<Vec<String>>, Option<Table>)| {
+            ensure_subprocess_allowed(lua)?;
+            let options = parse_exec_options(options)?;
+            let child =
+                spawn(&cmd, &args.unwrap_or_default(), &options).map_err(mlua::Error::external)?;
+            Ok(ChildHandle {
+                pid: child.id(),
+                child: Some(child),
+            })
+        },
+    )?;
+    table.set("spawn", spawn_fn)?;
     Ok(())
 }
 
@@ -161,6 +543,80 @@ mod tests {
         assert_eq!(result, "test_value");
     }
 
+    #[test]
+    fn test_lua_env_writes_reach_process_environment() {
+        let lua = Lua::new();
+        let process_table = create_process_module(&lua).unwrap();
+        lua.globals().set("process", process_table).unwrap();
+
+        lua.load(r#"process.env.HYPE_PROXY_WRITE = "bar""#)
+            .exec()
+            .unwrap();
+        assert_eq!(std::env::var("HYPE_PROXY_WRITE").unwrap(), "bar");
+
+        lua.load(r#"process.env.HYPE_PROXY_WRITE = "baz""#)
+            .exec()
+            .unwrap();
+        assert_eq!(std::env::var("HYPE_PROXY_WRITE").unwrap(), "baz");
+
+        lua.load("process.env.HYPE_PROXY_WRITE = nil")
+            .exec()
+            .unwrap();
+        assert!(std::env::var("HYPE_PROXY_WRITE").is_err());
+    }
+
+    #[test]
+    fn test_lua_env_reads_reflect_external_changes() {
+        std::env::set_var("HYPE_PROXY_READ", "before");
+        let lua = Lua::new();
+        let process_table = create_process_module(&lua).unwrap();
+        lua.globals().set("process", process_table).unwrap();
+
+        let value: String = lua
+            .load("return process.env.HYPE_PROXY_READ")
+            .eval()
+            .unwrap();
+        assert_eq!(value, "before");
+
+        std::env::set_var("HYPE_PROXY_READ", "after");
+        let value: String = lua
+            .load("return process.env.HYPE_PROXY_READ")
+            .eval()
+            .unwrap();
+        assert_eq!(value, "after");
+
+        std::env::remove_var("HYPE_PROXY_READ");
+        let value: Option<String> = lua
+            .load("return process.env.HYPE_PROXY_READ")
+            .eval()
+            .unwrap();
+        assert_eq!(value, None);
+    }
+
+    #[test]
+    fn test_lua_env_pairs_lists_live_variables() {
+        let lua = Lua::new();
+        let process_table = create_process_module(&lua).unwrap();
+        lua.globals().set("process", process_table).unwrap();
+        std::env::set_var("HYPE_PROXY_PAIRS", "listed");
+
+        let found: bool = lua
+            .load(
+                r#"
+            for key, value in pairs(process.env) do
+                if key == "HYPE_PROXY_PAIRS" and value == "listed" then
+                    return true
+                end
+            end
+            return false
+        "#,
+            )
+            .eval()
+            .unwrap();
+        assert!(found);
+        std::env::remove_var("HYPE_PROXY_PAIRS");
+    }
+
     #[test]
     fn test_lua_getenv_setenv() {
         let lua = Lua::new();
@@ -200,4 +656,153 @@ mod tests {
         let result: bool = lua.load(code).eval().unwrap();
         assert!(result);
     }
+
+    fn lua_with_process(allow_subprocess: bool) -> Lua {
+        let lua = Lua::new();
+        let policy = crate::lua::SecurityPolicy {
+            allow_subprocess,
+            ..Default::default()
+        };
+        policy.install(&lua);
+        let process_table = create_process_module(&lua).unwrap();
+        lua.globals().set("process", process_table).unwrap();
+        lua
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_lua_exec_echo() {
+        let lua = lua_with_process(true);
+
+        let (stdout, code): (String, i32) = lua
+            .load(
+                r#"
+            local result = process.exec("echo", {"hello"})
+            return result.stdout, result.code
+        "#,
+            )
+            .eval()
+            .unwrap();
+        assert_eq!(stdout, "hello\n");
+        assert_eq!(code, 0);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_lua_spawn_wait() {
+        let lua = lua_with_process(true);
+
+        let (stdout, code, has_pid): (String, i32, bool) = lua
+            .load(
+                r#"
+            local child = process.spawn("sh", {"-c", "echo spawned; exit 2"})
+            local result = child:wait()
+            return result.stdout, result.code, child.pid > 0
+        "#,
+            )
+            .eval()
+            .unwrap();
+        assert_eq!(stdout, "spawned\n");
+        assert_eq!(code, 2);
+        assert!(has_pid);
+    }
+
+    #[test]
+    fn test_lua_exec_blocked_by_policy() {
+        let lua = lua_with_process(false);
+
+        let err = lua
+            .load(r#"return process.exec("echo", {"hello"})"#)
+            .exec()
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("allow_subprocess"), "{}", err);
+
+        let err = lua
+            .load(r#"return process.spawn("echo", {"hello"})"#)
+            .exec()
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("allow_subprocess"), "{}", err);
+    }
+
+    #[test]
+    fn test_lua_exec_blocked_without_policy() {
+        let lua = Lua::new();
+        let process_table = create_process_module(&lua).unwrap();
+        lua.globals().set("process", process_table).unwrap();
+
+        assert!(lua.load(r#"process.exec("echo")"#).exec().is_err());
+    }
+
+    #[test]
+    fn test_exit_hooks_run_lifo_and_survive_errors() {
+        let lua = lua_with_process(false);
+        lua.load(
+            r#"
+            calls = {}
+            process.onExit(function(code) table.insert(calls, "first:" .. code) end)
+            process.onExit(function() error("hook failed") end)
+            process.onExit(function(code) table.insert(calls, "last:" .. code) end)
+        "#,
+        )
+        .exec()
+        .unwrap();
+
+        run_exit_hooks(&lua, 2);
+        run_exit_hooks(&lua, 2);
+
+        let calls: Vec<String> = lua.load("return calls").eval().unwrap();
+        assert_eq!(calls, vec!["last:2", "first:2"]);
+    }
+
+    #[test]
+    fn test_exit_inside_hook_is_rejected() {
+        let lua = lua_with_process(false);
+        lua.load(
+            r#"
+            process.onExit(function() ran = true end)
+            process.onExit(function()
+                exit_ok, exit_err = pcall(process.exit, 5)
+            end)
+        "#,
+        )
+        .exec()
+        .unwrap();
+
+        run_exit_hooks(&lua, 0);
+
+        let (exit_ok, exit_err, ran): (bool, String, bool) = lua
+            .load("return exit_ok, tostring(exit_err), ran")
+            .eval()
+            .unwrap();
+        assert!(!exit_ok);
+        assert!(exit_err.contains("onExit"), "{}", exit_err);
+        assert!(ran);
+    }
+
+    #[test]
+    fn test_lua_hrtime_delta() {
+        let lua = lua_with_process(false);
+
+        let (seconds, nanoseconds, delta_ok): (u64, u32, bool) = lua
+            .load(
+                r#"
+            local start = process.hrtime()
+            local deadline = os.clock() + 0.01
+            while os.clock() < deadline do end
+            local delta = process.hrtime(start)
+            local later = process.hrtime(start)
+            local ok = delta[1] * 1e9 + delta[2] > 0
+                and later[1] * 1e9 + later[2] >= delta[1] * 1e9 + delta[2]
+            return start[1], start[2], ok
+        "#,
+            )
+            .eval()
+            .unwrap();
+
+        assert!(seconds < 3600);
+        assert!(nanoseconds < 1_000_000_000);
+        assert!(delta_ok);
+    }
 }
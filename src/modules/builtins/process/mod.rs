@@ -1,6 +1,7 @@
 pub mod error;
 pub mod lua_bindings;
 pub mod operations;
+pub mod signals;
 
 use serde_json::{json, Value as JsonValue};
 
@@ -8,7 +9,7 @@ use super::BuiltinModule;
 use crate::error::HypeError;
 
 pub use error::ProcessError;
-pub use lua_bindings::create_process_module;
+pub use lua_bindings::{create_process_module, dispatch_signals, run_exit_hooks, ScriptArgv};
 pub use operations::*;
 
 pub struct ProcessModule;
@@ -59,11 +60,31 @@ impl BuiltinModule for ProcessModule {
                 "__desc": "Set environment variable",
                 "__signature": "setenv(key: string, value: string) -> nil"
             },
+            "loadEnv": {
+                "__fn": "loadEnv",
+                "__desc": "Load KEY=VALUE pairs from a dotenv file into the environment",
+                "__signature": "loadEnv(path?: string, options?: {override?: boolean}) -> table"
+            },
             "exit": {
                 "__fn": "exit",
                 "__desc": "Exit process with code",
                 "__signature": "exit(code?: number) -> never"
             },
+            "hrtime": {
+                "__fn": "hrtime",
+                "__desc": "Monotonic high-resolution time, or the delta since a previous reading",
+                "__signature": "hrtime(previous?: {number, number}) -> {seconds: number, nanoseconds: number}"
+            },
+            "onExit": {
+                "__fn": "onExit",
+                "__desc": "Register a callback run before the process exits",
+                "__signature": "onExit(callback: function(code: number)) -> nil"
+            },
+            "on": {
+                "__fn": "on",
+                "__desc": "Handle SIGINT or SIGTERM with a callback instead of exiting",
+                "__signature": "on(signal: \"SIGINT\" | \"SIGTERM\", callback: function(signal: string)) -> nil"
+            },
             "pid": {
                 "__value": "number",
                 "__desc": "Process ID",
@@ -78,7 +99,47 @@ impl BuiltinModule for ProcessModule {
             },
             "argv": {
                 "__table": "argv",
-                "__desc": "Command-line arguments",
+                "__desc": "Script path at index 0 followed by the script arguments",
+            },
+            "argv0": {
+                "__value": "string",
+                "__desc": "Name hype was invoked as",
+            },
+            "execPath": {
+                "__value": "string",
+                "__desc": "Absolute path of the hype executable (nil if unavailable)",
+            },
+            "setStdoutLineBuffered": {
+                "__fn": "setStdoutLineBuffered",
+                "__desc": "Write stdout a line at a time instead of when the script ends",
+                "__signature": "setStdoutLineBuffered(enabled: boolean)"
+            },
+            "flush": {
+                "__fn": "flush",
+                "__desc": "Write any buffered stdout now",
+                "__signature": "flush()"
+            },
+            "exec": {
+                "__fn": "exec",
+                "__desc": "Run a command and capture its output (requires allow_subprocess)",
+                "__signature": "exec(cmd: string, args?: table, options?: {cwd?, env?, stdin?}) -> {stdout, stderr, code}"
+            },
+            "spawn": {
+                "__fn": "spawn",
+                "__desc": "Start a command and return a handle with pid, wait() and kill() (requires allow_subprocess)",
+                "__signature": "spawn(cmd: string, args?: table, options?: {cwd?, env?, stdin?}) -> handle"
+            },
+            "stdin": {
+                "__table": "stdin",
+                "__desc": "Standard input: read() for everything, lines() to iterate, isTTY",
+            },
+            "stdout": {
+                "__table": "stdout",
+                "__desc": "Standard output: write(data) without a trailing newline",
+            },
+            "stderr": {
+                "__table": "stderr",
+                "__desc": "Standard error: write(data) without a trailing newline",
             }
         }))
     }
@@ -105,10 +166,17 @@ mod tests {
         assert!(exports.get("getenv").is_some());
         assert!(exports.get("setenv").is_some());
         assert!(exports.get("exit").is_some());
+        assert!(exports.get("onExit").is_some());
+        assert!(exports.get("on").is_some());
+        assert!(exports.get("hrtime").is_some());
         assert!(exports.get("pid").is_some());
         assert!(exports.get("platform").is_some());
         assert!(exports.get("arch").is_some());
         assert!(exports.get("argv").is_some());
+        assert!(exports.get("exec").is_some());
+        assert!(exports.get("spawn").is_some());
+        assert!(exports.get("stdin").is_some());
+        assert!(exports.get("stdout").is_some());
     }
 
     #[test]
@@ -1,7 +1,14 @@
 use super::error::ProcessError;
 use std::collections::HashMap;
 use std::env;
-use std::path::PathBuf;
+use std::io::{self, BufRead, IsTerminal, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::OnceLock;
+use std::thread;
+use std::time::{Duration, Instant};
+
+static PROCESS_START: OnceLock<Instant> = OnceLock::new();
 
 pub type Result<T> = std::result::Result<T, ProcessError>;
 
@@ -32,6 +39,130 @@ pub fn remove_env_var(key: &str) {
     env::remove_var(key);
 }
 
+pub const DEFAULT_ENV_FILE: &str = ".env";
+
+/// Parse `.env` content into `KEY=VALUE` pairs in file order.
+///
+/// Blank lines and `#` comments are skipped and an `export ` prefix is
+/// allowed. Unquoted values end at a ` #` comment and are trimmed.
+/// Single-quoted values are literal; double-quoted values understand `\n`,
+/// `\r`, `\t`, `\"` and `\\`. Both quoted forms may span several lines.
+pub fn parse_env(content: &str) -> Result<Vec<(String, String)>> {
+    let mut pairs = Vec::new();
+    let mut lines = content.lines().enumerate();
+
+    while let Some((index, line)) = lines.next() {
+        let line_number = index + 1;
+        let invalid = |message: &str| ProcessError::InvalidEnvFile {
+            line: line_number,
+            message: message.to_string(),
+        };
+
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        let trimmed = trimmed.strip_prefix("export ").unwrap_or(trimmed);
+        let (key, rest) = trimmed
+            .split_once('=')
+            .ok_or_else(|| invalid("expected KEY=VALUE"))?;
+        let key = key.trim();
+        if !is_env_key(key) {
+            return Err(invalid(&format!("invalid variable name '{}'", key)));
+        }
+
+        let rest = rest.trim_start();
+        let value = match rest.chars().next() {
+            Some(quote @ ('"' | '\'')) => {
+                // Keep reading lines until the closing quote
+                let mut raw = rest[1..].to_string();
+                let (body, after) = loop {
+                    if let Some(end) = find_closing_quote(&raw, quote) {
+                        let after = raw[end + 1..].to_string();
+                        raw.truncate(end);
+                        break (raw, after);
+                    }
+                    match lines.next() {
+                        Some((_, next)) => {
+                            raw.push('\n');
+                            raw.push_str(next);
+                        }
+                        None => return Err(invalid("unterminated quoted value")),
+                    }
+                };
+                let after = after.trim();
+                if !after.is_empty() && !after.starts_with('#') {
+                    return Err(invalid("unexpected text after closing quote"));
+                }
+                if quote == '"' {
+                    unescape_env_value(&body)
+                } else {
+                    body
+                }
+            }
+            _ => match rest.find(" #").or_else(|| rest.find("\t#")) {
+                Some(comment) => rest[..comment].trim_end().to_string(),
+                None if rest.starts_with('#') => String::new(),
+                None => rest.trim_end().to_string(),
+            },
+        };
+        pairs.push((key.to_string(), value));
+    }
+    Ok(pairs)
+}
+
+fn is_env_key(key: &str) -> bool {
+    let mut chars = key.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-'))
+}
+
+/// Index of the quote ending a value, skipping `\"` inside double quotes.
+fn find_closing_quote(text: &str, quote: char) -> Option<usize> {
+    let mut escaped = false;
+    for (i, c) in text.char_indices() {
+        match c {
+            '\\' if quote == '"' && !escaped => escaped = true,
+            c if c == quote && !escaped => return Some(i),
+            _ => escaped = false,
+        }
+    }
+    None
+}
+
+fn unescape_env_value(value: &str) -> String {
+    let mut output = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            output.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => output.push('\n'),
+            Some('r') => output.push('\r'),
+            Some('t') => output.push('\t'),
+            Some(other) => output.push(other),
+            None => output.push('\\'),
+        }
+    }
+    output
+}
+
+/// Read an env file and set its variables. Variables that are already set
+/// keep their value unless `override_existing` is true. Returns everything
+/// the file defines.
+pub fn load_env(path: &Path, override_existing: bool) -> Result<Vec<(String, String)>> {
+    let content = std::fs::read_to_string(path)?;
+    let pairs = parse_env(&content)?;
+    for (key, value) in &pairs {
+        if override_existing || env::var_os(key).is_none() {
+            env::set_var(key, value);
+        }
+    }
+    Ok(pairs)
+}
+
 pub fn get_pid() -> u32 {
     std::process::id()
 }
@@ -44,14 +175,180 @@ pub fn get_arch() -> String {
     env::consts::ARCH.to_string()
 }
 
+/// The monotonic reference point for `hrtime`, fixed the first time it is requested.
+pub fn process_start() -> Instant {
+    *PROCESS_START.get_or_init(Instant::now)
+}
+
+/// Monotonic time elapsed since process start, or since `previous` when given.
+pub fn hrtime(previous: Option<Duration>) -> Duration {
+    let elapsed = process_start().elapsed();
+    match previous {
+        Some(previous) => elapsed.saturating_sub(previous),
+        None => elapsed,
+    }
+}
+
 pub fn exit(code: i32) -> ! {
     std::process::exit(code)
 }
 
+/// Read everything left on stdin. Returns an empty buffer once stdin is at EOF.
+pub fn read_stdin() -> Result<Vec<u8>> {
+    let mut buffer = Vec::new();
+    io::stdin().lock().read_to_end(&mut buffer)?;
+    Ok(buffer)
+}
+
+/// Read the next line from stdin without its `\n` or `\r\n`, or `None` at EOF.
+/// A final line with no newline is still returned.
+pub fn read_stdin_line() -> Result<Option<Vec<u8>>> {
+    let mut line = Vec::new();
+    if io::stdin().lock().read_until(b'\n', &mut line)? == 0 {
+        return Ok(None);
+    }
+    if line.ends_with(b"\n") {
+        line.pop();
+        if line.ends_with(b"\r") {
+            line.pop();
+        }
+    }
+    Ok(Some(line))
+}
+
+pub fn stdin_is_tty() -> bool {
+    io::stdin().is_terminal()
+}
+
+/// Write bytes as-is, with no trailing newline, and flush.
+pub fn write_raw(stream: &mut dyn Write, data: &[u8]) -> Result<()> {
+    stream.write_all(data)?;
+    stream.flush()?;
+    Ok(())
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct ExecOptions {
+    pub cwd: Option<String>,
+    pub env: HashMap<String, String>,
+    pub stdin: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ExecOutput {
+    pub stdout: String,
+    pub stderr: String,
+    pub code: Option<i32>,
+}
+
+/// Start `cmd` directly (no shell) with piped stdio.
+pub fn spawn(cmd: &str, args: &[String], options: &ExecOptions) -> Result<Child> {
+    let mut command = Command::new(cmd);
+    command
+        .args(args)
+        .envs(&options.env)
+        .stdin(if options.stdin.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    if let Some(cwd) = &options.cwd {
+        command.current_dir(cwd);
+    }
+
+    let mut child = command
+        .spawn()
+        .map_err(|e| ProcessError::CommandFailed(format!("failed to run '{}': {}", cmd, e)))?;
+
+    if let (Some(input), Some(mut stdin)) = (options.stdin.clone(), child.stdin.take()) {
+        thread::spawn(move || {
+            let _ = stdin.write_all(input.as_bytes());
+        });
+    }
+
+    Ok(child)
+}
+
+/// Wait for a spawned child and collect its output.
+pub fn wait_child(child: Child) -> Result<ExecOutput> {
+    let output = child.wait_with_output()?;
+    Ok(ExecOutput {
+        stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+        stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+        code: output.status.code(),
+    })
+}
+
+/// Run `cmd` to completion and capture its output.
+pub fn exec(cmd: &str, args: &[String], options: &ExecOptions) -> Result<ExecOutput> {
+    wait_child(spawn(cmd, args, options)?)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_hrtime_is_monotonic() {
+        let first = hrtime(None);
+        let second = hrtime(None);
+        assert!(second >= first);
+
+        let start = hrtime(None);
+        thread::sleep(Duration::from_millis(20));
+        let delta = hrtime(Some(start));
+        assert!(delta >= Duration::from_millis(20));
+        assert!(hrtime(Some(start)) >= delta);
+    }
+
+    #[test]
+    fn test_parse_env_quotes_comments_and_export() {
+        let content = r#"
+# Database settings
+export DB_HOST=localhost
+DB_PORT = 5432 # inline comment
+DB_PASSWORD="p@ss # not a comment"
+GREETING="line one\nline \"two\""
+LITERAL='single $quoted \n'
+MULTILINE="first
+second"
+EMPTY=
+HASH_ONLY=#
+"#;
+        let pairs = parse_env(content).unwrap();
+        let expected = [
+            ("DB_HOST", "localhost"),
+            ("DB_PORT", "5432"),
+            ("DB_PASSWORD", "p@ss # not a comment"),
+            ("GREETING", "line one\nline \"two\""),
+            ("LITERAL", "single $quoted \\n"),
+            ("MULTILINE", "first\nsecond"),
+            ("EMPTY", ""),
+            ("HASH_ONLY", ""),
+        ];
+        let pairs: Vec<(&str, &str)> = pairs
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.as_str()))
+            .collect();
+        assert_eq!(pairs, expected);
+    }
+
+    #[test]
+    fn test_parse_env_reports_line_of_error() {
+        let err = parse_env("OK=1\nnot a pair\n").unwrap_err();
+        assert!(
+            matches!(err, ProcessError::InvalidEnvFile { line: 2, .. }),
+            "{}",
+            err
+        );
+
+        let err = parse_env("A=1\nB=\"never closed\n").unwrap_err();
+        assert!(err.to_string().contains("unterminated"), "{}", err);
+        assert!(parse_env("1BAD=x").is_err());
+    }
+
     #[test]
     fn test_get_cwd() {
         let cwd = get_cwd().unwrap();
@@ -115,4 +412,49 @@ mod tests {
 
         set_cwd(&original).unwrap();
     }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_exec_captures_output_and_code() {
+        let output = exec("echo", &["hello".to_string()], &ExecOptions::default()).unwrap();
+        assert_eq!(output.stdout, "hello\n");
+        assert_eq!(output.code, Some(0));
+
+        let output = exec(
+            "sh",
+            &["-c".to_string(), "exit 3".to_string()],
+            &ExecOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(output.code, Some(3));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_exec_with_stdin_and_env() {
+        let mut options = ExecOptions {
+            stdin: Some("piped input".to_string()),
+            ..ExecOptions::default()
+        };
+        options
+            .env
+            .insert("HYPE_EXEC_TEST".to_string(), "from-env".to_string());
+
+        let output = exec(
+            "sh",
+            &[
+                "-c".to_string(),
+                "cat; echo \" $HYPE_EXEC_TEST\"".to_string(),
+            ],
+            &options,
+        )
+        .unwrap();
+        assert_eq!(output.stdout, "piped input from-env\n");
+    }
+
+    #[test]
+    fn test_exec_missing_command() {
+        let err = exec("hype-no-such-command", &[], &ExecOptions::default()).unwrap_err();
+        assert!(matches!(err, ProcessError::CommandFailed(_)));
+    }
 }
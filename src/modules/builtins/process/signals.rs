@@ -0,0 +1,159 @@
+use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Condvar, Mutex, OnceLock};
+use std::time::Duration;
+
+/// Signals scripts can handle with `process.on`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Signal {
+    Int,
+    Term,
+}
+
+impl Signal {
+    pub const ALL: [Signal; 2] = [Signal::Int, Signal::Term];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Signal::Int => "SIGINT",
+            Signal::Term => "SIGTERM",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|signal| signal.name() == name)
+    }
+
+    fn index(&self) -> usize {
+        *self as usize
+    }
+}
+
+/// Signals received but not yet handed to the script. The OS handler thread
+/// marks them here; the interpreter takes them at its next safe point.
+struct SignalState {
+    pending: [AtomicBool; 2],
+    listening: [AtomicBool; 2],
+    lock: Mutex<()>,
+    changed: Condvar,
+}
+
+fn state() -> &'static SignalState {
+    static STATE: OnceLock<SignalState> = OnceLock::new();
+    STATE.get_or_init(|| SignalState {
+        pending: [AtomicBool::new(false), AtomicBool::new(false)],
+        listening: [AtomicBool::new(false), AtomicBool::new(false)],
+        lock: Mutex::new(()),
+        changed: Condvar::new(),
+    })
+}
+
+/// Route `signal` to the script instead of its default action (terminating
+/// the process). Listening is process-wide and lasts until exit.
+#[cfg(unix)]
+pub fn listen(signal: Signal) -> io::Result<()> {
+    let state = state();
+    if state.listening[signal.index()].swap(true, Ordering::SeqCst) {
+        return Ok(());
+    }
+
+    let number = match signal {
+        Signal::Int => signal_hook::consts::SIGINT,
+        Signal::Term => signal_hook::consts::SIGTERM,
+    };
+    let mut signals = match signal_hook::iterator::Signals::new([number]) {
+        Ok(signals) => signals,
+        Err(e) => {
+            state.listening[signal.index()].store(false, Ordering::SeqCst);
+            return Err(e);
+        }
+    };
+    std::thread::Builder::new()
+        .name(format!("hype-{}", signal.name().to_lowercase()))
+        .spawn(move || {
+            for _ in signals.forever() {
+                raise(signal);
+            }
+        })?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+pub fn listen(_signal: Signal) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "signal handlers are only supported on Unix",
+    ))
+}
+
+/// Whether any signal is routed to the script.
+pub fn is_listening() -> bool {
+    state()
+        .listening
+        .iter()
+        .any(|listening| listening.load(Ordering::SeqCst))
+}
+
+/// Mark `signal` as received and wake anything waiting in `wait_for_signal`.
+pub fn raise(signal: Signal) {
+    let state = state();
+    state.pending[signal.index()].store(true, Ordering::SeqCst);
+    let _guard = state.lock.lock().unwrap();
+    state.changed.notify_all();
+}
+
+pub fn has_pending() -> bool {
+    state()
+        .pending
+        .iter()
+        .any(|pending| pending.load(Ordering::SeqCst))
+}
+
+/// Take the received signals, clearing them.
+pub fn take_pending() -> Vec<Signal> {
+    let state = state();
+    Signal::ALL
+        .into_iter()
+        .filter(|signal| state.pending[signal.index()].swap(false, Ordering::SeqCst))
+        .collect()
+}
+
+/// Sleep for up to `duration`, returning `true` early if a signal arrives.
+pub fn wait_for_signal(duration: Duration) -> bool {
+    let state = state();
+    let guard = state.lock.lock().unwrap();
+    let (_guard, _) = state
+        .changed
+        .wait_timeout_while(guard, duration, |_| !has_pending())
+        .unwrap();
+    has_pending()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    #[test]
+    fn test_signal_names() {
+        assert_eq!(Signal::from_name("SIGINT"), Some(Signal::Int));
+        assert_eq!(Signal::from_name("SIGTERM"), Some(Signal::Term));
+        assert_eq!(Signal::from_name("sigint"), None);
+    }
+
+    #[test]
+    fn test_raise_wakes_waiter() {
+        let waiter = std::thread::spawn(|| {
+            let start = Instant::now();
+            (wait_for_signal(Duration::from_secs(10)), start.elapsed())
+        });
+        std::thread::sleep(Duration::from_millis(50));
+        raise(Signal::Term);
+
+        let (woken, elapsed) = waiter.join().unwrap();
+        assert!(woken);
+        assert!(elapsed < Duration::from_secs(5));
+        assert_eq!(take_pending(), vec![Signal::Term]);
+        assert!(!has_pending());
+    }
+}
@@ -1,11 +1,22 @@
 use super::operations::*;
-use mlua::{Lua, Result as LuaResult, Table};
-use std::collections::HashMap;
+use mlua::{Lua, Result as LuaResult, Table, Value};
+use std::collections::BTreeMap;
 
-pub fn create_querystring_module(lua: &Lua) -> LuaResult<Table> {
+pub fn create_querystring_module(lua: &Lua) -> LuaResult<Table<'_>> {
     let querystring = lua.create_table()?;
 
-    let parse_fn = lua.create_function(|lua, query: String| {
+    let parse_fn = lua.create_function(|lua, (query, options): (String, Option<Table>)| {
+        let nested = match options {
+            Some(opts) => opts.get::<_, Option<bool>>("nested")?.unwrap_or(false),
+            None => false,
+        };
+        if nested {
+            return match query_value_to_lua(lua, parse_nested(&query))? {
+                Value::Table(table) => Ok(table),
+                _ => lua.create_table(),
+            };
+        }
+
         let parsed = parse(&query);
         let table = lua.create_table()?;
         for (key, value) in parsed {
@@ -15,13 +26,9 @@ pub fn create_querystring_module(lua: &Lua) -> LuaResult<Table> {
     })?;
     querystring.set("parse", parse_fn)?;
 
-    let stringify_fn = lua.create_function(|_, table: Table| {
-        let mut params = HashMap::new();
-        for pair in table.pairs::<String, String>() {
-            let (key, value) = pair?;
-            params.insert(key, value);
-        }
-        Ok(stringify(params))
+    let stringify_fn = lua.create_function(|lua, table: Table| {
+        let params = lua_to_query_value(lua, Value::Table(table))?;
+        Ok(stringify(flatten(&params)))
     })?;
     querystring.set("stringify", stringify_fn)?;
 
@@ -37,6 +44,67 @@ pub fn create_querystring_module(lua: &Lua) -> LuaResult<Table> {
     Ok(querystring)
 }
 
+fn query_value_to_lua(lua: &Lua, value: QueryValue) -> LuaResult<Value<'_>> {
+    Ok(match value {
+        QueryValue::String(s) => Value::String(lua.create_string(&s)?),
+        QueryValue::Array(items) => {
+            let table = lua.create_table_with_capacity(items.len(), 0)?;
+            for item in items {
+                table.raw_push(query_value_to_lua(lua, item)?)?;
+            }
+            Value::Table(table)
+        }
+        QueryValue::Object(fields) => {
+            let table = lua.create_table_with_capacity(0, fields.len())?;
+            for (key, child) in fields {
+                table.set(key, query_value_to_lua(lua, child)?)?;
+            }
+            Value::Table(table)
+        }
+    })
+}
+
+/// Sequences become arrays (`key[]`), other tables objects (`key[field]`).
+fn lua_to_query_value(lua: &Lua, value: Value) -> LuaResult<QueryValue> {
+    let table = match value {
+        Value::Table(table) => table,
+        other => {
+            return lua
+                .coerce_string(other.clone())?
+                .map(|s| QueryValue::String(s.to_string_lossy().to_string()))
+                .ok_or_else(|| {
+                    mlua::Error::RuntimeError(format!(
+                        "querystring.stringify: cannot encode a {} value",
+                        other.type_name()
+                    ))
+                });
+        }
+    };
+
+    let length = table.raw_len();
+    let pairs = table
+        .clone()
+        .pairs::<Value, Value>()
+        .collect::<LuaResult<Vec<_>>>()?;
+    if length > 0 && pairs.len() == length {
+        let items = table
+            .sequence_values::<Value>()
+            .map(|item| lua_to_query_value(lua, item?))
+            .collect::<LuaResult<_>>()?;
+        return Ok(QueryValue::Array(items));
+    }
+
+    let mut fields = BTreeMap::new();
+    for (key, child) in pairs {
+        let key = match lua.coerce_string(key)? {
+            Some(key) => key.to_str()?.to_string(),
+            None => continue,
+        };
+        fields.insert(key, lua_to_query_value(lua, child)?);
+    }
+    Ok(QueryValue::Object(fields))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -89,6 +157,31 @@ assert(string.find(result, "baz=qux") ~= nil)
         .unwrap();
     }
 
+    #[test]
+    fn test_querystring_nested_roundtrip() {
+        let lua = Lua::new();
+        let querystring = create_querystring_module(&lua).unwrap();
+        lua.globals().set("querystring", querystring).unwrap();
+
+        lua.load(
+            r#"
+local flat = querystring.parse("user[name]=a")
+assert(flat["user[name]"] == "a")
+
+local parsed = querystring.parse("user[name]=a&user[age]=30&items[]=1&items[]=2", {nested = true})
+assert(parsed.user.name == "a" and parsed.user.age == "30")
+assert(#parsed.items == 2 and parsed.items[2] == "2")
+
+local query = querystring.stringify({user = {name = "a", age = 30}, items = {"1", "2"}})
+assert(query == "items%5B%5D=1&items%5B%5D=2&user%5Bage%5D=30&user%5Bname%5D=a", query)
+local again = querystring.parse(query, {nested = true})
+assert(again.user.age == "30" and again.items[1] == "1")
+"#,
+        )
+        .exec()
+        .unwrap();
+    }
+
     #[test]
     fn test_querystring_escape_unescape() {
         let lua = Lua::new();
@@ -37,11 +37,11 @@ impl BuiltinModule for QueryStringModule {
             "parse": {
                 "__fn": "parse",
                 "__desc": "Parse query string into key-value pairs",
-                "__signature": "parse(queryString: string) -> table"
+                "__signature": "parse(queryString: string, options?: {nested?: boolean}) -> table"
             },
             "stringify": {
                 "__fn": "stringify",
-                "__desc": "Convert table to query string",
+                "__desc": "Convert table to query string, using bracket keys for nested tables",
                 "__signature": "stringify(params: table) -> string"
             },
             "escape": {
@@ -1,14 +1,144 @@
 use super::error::QueryStringError;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use url::form_urlencoded;
 
+/// A query string value after bracket keys such as `user[name]` and
+/// `items[]` have been expanded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QueryValue {
+    String(String),
+    Array(Vec<QueryValue>),
+    Object(BTreeMap<String, QueryValue>),
+}
+
 pub fn parse(query: &str) -> HashMap<String, String> {
     form_urlencoded::parse(query.as_bytes())
         .map(|(key, value)| (key.to_string(), value.to_string()))
         .collect()
 }
 
-pub fn stringify(params: HashMap<String, String>) -> String {
+/// Parse a query string, expanding bracket keys into nested values:
+/// `user[name]=a` becomes an object, `items[]=1&items[]=2` an array, and
+/// `items[0][id]=7` an object inside an array. Repeated keys keep the last value.
+pub fn parse_nested(query: &str) -> QueryValue {
+    let mut root = QueryValue::Object(BTreeMap::new());
+    for (key, value) in form_urlencoded::parse(query.as_bytes()) {
+        let path = split_key(&key);
+        insert_path(&mut root, &path, value.to_string());
+    }
+    root
+}
+
+/// `a[b][]` -> `["a", "b", ""]`. Keys with unbalanced brackets are kept whole.
+fn split_key(key: &str) -> Vec<&str> {
+    let Some(open) = key.find('[') else {
+        return vec![key];
+    };
+    if open == 0 || !key.ends_with(']') {
+        return vec![key];
+    }
+
+    let mut path = vec![&key[..open]];
+    let mut rest = &key[open..];
+    while let Some(inner) = rest.strip_prefix('[') {
+        let Some(close) = inner.find(']') else {
+            return vec![key];
+        };
+        path.push(&inner[..close]);
+        rest = &inner[close + 1..];
+    }
+    if !rest.is_empty() {
+        return vec![key];
+    }
+    path
+}
+
+fn insert_path(target: &mut QueryValue, path: &[&str], value: String) {
+    let Some((segment, rest)) = path.split_first() else {
+        *target = QueryValue::String(value);
+        return;
+    };
+
+    let wants_array = segment.is_empty() || segment.parse::<usize>().is_ok();
+    match target {
+        QueryValue::Array(_) if wants_array => {}
+        QueryValue::Object(_) => {}
+        QueryValue::Array(items) => {
+            // A named key under an array: keep the items under their indices
+            let object = std::mem::take(items)
+                .into_iter()
+                .enumerate()
+                .map(|(i, item)| (i.to_string(), item))
+                .collect();
+            *target = QueryValue::Object(object);
+        }
+        QueryValue::String(_) if wants_array => *target = QueryValue::Array(Vec::new()),
+        QueryValue::String(_) => *target = QueryValue::Object(BTreeMap::new()),
+    }
+
+    let child = match target {
+        QueryValue::Array(items) => {
+            // `[]` and out-of-range indices append
+            let index = match segment.parse::<usize>() {
+                Ok(i) if i < items.len() => i,
+                _ => {
+                    items.push(QueryValue::String(String::new()));
+                    items.len() - 1
+                }
+            };
+            &mut items[index]
+        }
+        QueryValue::Object(fields) => fields
+            .entry(segment.to_string())
+            .or_insert_with(|| QueryValue::String(String::new())),
+        QueryValue::String(_) => unreachable!("containers are created above"),
+    };
+    insert_path(child, rest, value);
+}
+
+/// Flatten a nested value into `key=value` pairs using bracket keys. Arrays of
+/// strings use `key[]`; arrays holding tables use indices so items stay together.
+pub fn flatten(value: &QueryValue) -> Vec<(String, String)> {
+    let mut pairs = Vec::new();
+    match value {
+        QueryValue::Object(fields) => {
+            for (key, child) in fields {
+                flatten_into(key.clone(), child, &mut pairs);
+            }
+        }
+        other => flatten_into(String::new(), other, &mut pairs),
+    }
+    pairs
+}
+
+fn flatten_into(prefix: String, value: &QueryValue, pairs: &mut Vec<(String, String)>) {
+    match value {
+        QueryValue::String(s) => pairs.push((prefix, s.clone())),
+        QueryValue::Array(items) => {
+            let scalars = items
+                .iter()
+                .all(|item| matches!(item, QueryValue::String(_)));
+            for (i, item) in items.iter().enumerate() {
+                let key = if scalars {
+                    format!("{}[]", prefix)
+                } else {
+                    format!("{}[{}]", prefix, i)
+                };
+                flatten_into(key, item, pairs);
+            }
+        }
+        QueryValue::Object(fields) => {
+            for (key, child) in fields {
+                flatten_into(format!("{}[{}]", prefix, key), child, pairs);
+            }
+        }
+    }
+}
+
+pub fn stringify<I>(params: I) -> String
+where
+    I: IntoIterator<Item = (String, String)>,
+{
     let mut serializer = form_urlencoded::Serializer::new(String::new());
     for (key, value) in params {
         serializer.append_pair(&key, &value);
@@ -64,6 +194,37 @@ mod tests {
         assert!(result.contains("name=John+Doe"));
     }
 
+    #[test]
+    fn test_parse_nested_brackets() {
+        let parsed = parse_nested("user[name]=Ada&user[age]=36&items[]=1&items[]=2&plain=x");
+        let expected = QueryValue::Object(BTreeMap::from([
+            (
+                "items".to_string(),
+                QueryValue::Array(vec![
+                    QueryValue::String("1".to_string()),
+                    QueryValue::String("2".to_string()),
+                ]),
+            ),
+            ("plain".to_string(), QueryValue::String("x".to_string())),
+            (
+                "user".to_string(),
+                QueryValue::Object(BTreeMap::from([
+                    ("age".to_string(), QueryValue::String("36".to_string())),
+                    ("name".to_string(), QueryValue::String("Ada".to_string())),
+                ])),
+            ),
+        ]));
+        assert_eq!(parsed, expected);
+        assert_eq!(split_key("odd[key"), vec!["odd[key"]);
+    }
+
+    #[test]
+    fn test_nested_roundtrip() {
+        let query = "items%5B%5D=1&items%5B%5D=2&rows%5B0%5D%5Bid%5D=7&rows%5B1%5D%5Bid%5D=8&user%5Bname%5D=Ada";
+        let parsed = parse_nested(query);
+        assert_eq!(stringify(flatten(&parsed)), query);
+    }
+
     #[test]
     fn test_escape() {
         assert_eq!(escape("hello world"), "hello+world");
@@ -0,0 +1,44 @@
+use crate::error::StructuredError;
+use std::fmt;
+
+#[derive(Debug)]
+pub enum SemverError {
+    InvalidVersion { input: String, reason: String },
+    InvalidRange { input: String, reason: String },
+}
+
+impl fmt::Display for SemverError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SemverError::InvalidVersion { input, reason } => {
+                write!(f, "Invalid version '{}': {}", input, reason)
+            }
+            SemverError::InvalidRange { input, reason } => {
+                write!(f, "Invalid version range '{}': {}", input, reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SemverError {}
+
+impl SemverError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            SemverError::InvalidVersion { .. } => "ERR_INVALID_VERSION",
+            SemverError::InvalidRange { .. } => "ERR_INVALID_RANGE",
+        }
+    }
+}
+
+impl From<SemverError> for StructuredError {
+    fn from(err: SemverError) -> Self {
+        StructuredError::new("semver", err.code(), err.to_string())
+    }
+}
+
+impl From<SemverError> for crate::error::HypeError {
+    fn from(err: SemverError) -> Self {
+        crate::error::HypeError::Execution(err.to_string())
+    }
+}
@@ -0,0 +1,124 @@
+use super::operations::*;
+use crate::lua::error::{structured_error, wrap_module_errors};
+use mlua::{Lua, Result as LuaResult, Table};
+use std::cmp::Ordering;
+
+type Comparison = fn(Ordering) -> bool;
+
+pub fn create_semver_module(lua: &Lua) -> LuaResult<Table<'_>> {
+    let semver = lua.create_table()?;
+
+    let parse_fn = lua.create_function(|lua, input: String| {
+        let version = parse(&input).map_err(structured_error)?;
+        let table = lua.create_table()?;
+        table.set("major", version.major)?;
+        table.set("minor", version.minor)?;
+        table.set("patch", version.patch)?;
+        if !version.pre.is_empty() {
+            table.set("prerelease", version.pre.as_str())?;
+        }
+        if !version.build.is_empty() {
+            table.set("build", version.build.as_str())?;
+        }
+        table.set("version", version.to_string())?;
+        Ok(table)
+    })?;
+    semver.set("parse", parse_fn)?;
+
+    let valid_fn = lua.create_function(|_, input: String| Ok(parse(&input).is_ok()))?;
+    semver.set("valid", valid_fn)?;
+
+    let compare_fn = lua.create_function(|_, (a, b): (String, String)| {
+        let ordering = compare(&a, &b).map_err(structured_error)?;
+        Ok(ordering as i8)
+    })?;
+    semver.set("compare", compare_fn)?;
+
+    let comparisons: [(&str, Comparison); 5] = [
+        ("gt", Ordering::is_gt),
+        ("gte", Ordering::is_ge),
+        ("lt", Ordering::is_lt),
+        ("lte", Ordering::is_le),
+        ("eq", Ordering::is_eq),
+    ];
+    for (name, test) in comparisons {
+        let comparison_fn = lua.create_function(move |_, (a, b): (String, String)| {
+            compare(&a, &b).map(test).map_err(structured_error)
+        })?;
+        semver.set(name, comparison_fn)?;
+    }
+
+    let satisfies_fn = lua.create_function(|_, (version, range): (String, String)| {
+        satisfies(&version, &range).map_err(structured_error)
+    })?;
+    semver.set("satisfies", satisfies_fn)?;
+
+    semver.set(
+        "major",
+        lua.create_function(|_, input: String| {
+            parse(&input).map(|v| v.major).map_err(structured_error)
+        })?,
+    )?;
+    semver.set(
+        "minor",
+        lua.create_function(|_, input: String| {
+            parse(&input).map(|v| v.minor).map_err(structured_error)
+        })?,
+    )?;
+    semver.set(
+        "patch",
+        lua.create_function(|_, input: String| {
+            parse(&input).map(|v| v.patch).map_err(structured_error)
+        })?,
+    )?;
+
+    wrap_module_errors(lua, &semver)?;
+    Ok(semver)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_semver_module() {
+        let lua = Lua::new();
+        let semver = create_semver_module(&lua).unwrap();
+        for name in [
+            "parse",
+            "valid",
+            "compare",
+            "satisfies",
+            "gt",
+            "gte",
+            "lt",
+            "lte",
+            "eq",
+            "major",
+            "minor",
+            "patch",
+        ] {
+            assert!(semver.contains_key(name).unwrap(), "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_errors_are_structured() {
+        let lua = Lua::new();
+        lua.globals()
+            .set("semver", create_semver_module(&lua).unwrap())
+            .unwrap();
+        lua.load(
+            r#"
+local ok, err = pcall(semver.parse, "1.2")
+assert(not ok and err.kind == "semver" and err.code == "ERR_INVALID_VERSION", tostring(err))
+
+ok, err = pcall(semver.satisfies, "1.2.3", ">= banana")
+assert(not ok and err.code == "ERR_INVALID_RANGE", tostring(err))
+assert(semver.valid("1.2") == false)
+"#,
+        )
+        .exec()
+        .unwrap();
+    }
+}
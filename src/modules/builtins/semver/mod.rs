@@ -0,0 +1,117 @@
+pub mod error;
+pub mod lua_bindings;
+pub mod operations;
+
+use serde_json::{json, Value as JsonValue};
+
+use super::BuiltinModule;
+use crate::error::HypeError;
+
+pub use lua_bindings::create_semver_module;
+
+pub struct SemverModule;
+
+impl SemverModule {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for SemverModule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BuiltinModule for SemverModule {
+    fn name(&self) -> &str {
+        "semver"
+    }
+
+    fn exports(&self) -> std::result::Result<JsonValue, HypeError> {
+        Ok(json!({
+            "__id": "semver",
+            "__desc": "Semantic version parsing, comparison and range matching",
+            "parse": {
+                "__fn": "parse",
+                "__desc": "Split a version into major, minor, patch, prerelease and build",
+                "__signature": "parse(version: string) -> {major, minor, patch, prerelease?, build?, version}"
+            },
+            "valid": {
+                "__fn": "valid",
+                "__desc": "Check whether a string is a valid semantic version",
+                "__signature": "valid(version: string) -> boolean"
+            },
+            "compare": {
+                "__fn": "compare",
+                "__desc": "Compare by semver precedence: -1, 0 or 1",
+                "__signature": "compare(a: string, b: string) -> number"
+            },
+            "satisfies": {
+                "__fn": "satisfies",
+                "__desc": "Check a version against a range such as ^1.2.3, ~1.2, >=1.0 <2.0 or 1.0 - 2.0",
+                "__signature": "satisfies(version: string, range: string) -> boolean"
+            },
+            "gt": {
+                "__fn": "gt",
+                "__desc": "a is greater than b",
+                "__signature": "gt(a: string, b: string) -> boolean"
+            },
+            "gte": {
+                "__fn": "gte",
+                "__desc": "a is greater than or equal to b",
+                "__signature": "gte(a: string, b: string) -> boolean"
+            },
+            "lt": {
+                "__fn": "lt",
+                "__desc": "a is less than b",
+                "__signature": "lt(a: string, b: string) -> boolean"
+            },
+            "lte": {
+                "__fn": "lte",
+                "__desc": "a is less than or equal to b",
+                "__signature": "lte(a: string, b: string) -> boolean"
+            },
+            "eq": {
+                "__fn": "eq",
+                "__desc": "a and b have the same precedence (build metadata is ignored)",
+                "__signature": "eq(a: string, b: string) -> boolean"
+            },
+            "major": {
+                "__fn": "major",
+                "__desc": "Major version number",
+                "__signature": "major(version: string) -> number"
+            },
+            "minor": {
+                "__fn": "minor",
+                "__desc": "Minor version number",
+                "__signature": "minor(version: string) -> number"
+            },
+            "patch": {
+                "__fn": "patch",
+                "__desc": "Patch version number",
+                "__signature": "patch(version: string) -> number"
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_semver_module_name() {
+        let module = SemverModule::new();
+        assert_eq!(module.name(), "semver");
+    }
+
+    #[test]
+    fn test_semver_module_exports() {
+        let module = SemverModule::new();
+        let exports = module.exports().unwrap();
+        for name in ["parse", "compare", "satisfies", "gt", "major"] {
+            assert!(exports.get(name).is_some(), "{}", name);
+        }
+    }
+}
@@ -0,0 +1,142 @@
+use super::error::SemverError;
+use ::semver::{Version, VersionReq};
+use std::cmp::Ordering;
+
+pub type Result<T> = std::result::Result<T, SemverError>;
+
+/// Parse a version, allowing a leading `v` as in git tags (`v1.2.3`).
+pub fn parse(input: &str) -> Result<Version> {
+    let trimmed = input.trim();
+    let text = trimmed.strip_prefix('v').unwrap_or(trimmed);
+    Version::parse(text).map_err(|e| SemverError::InvalidVersion {
+        input: input.to_string(),
+        reason: e.to_string(),
+    })
+}
+
+/// Order two versions by semver precedence: prereleases sort before their
+/// release and build metadata is ignored.
+pub fn compare(a: &str, b: &str) -> Result<Ordering> {
+    Ok(parse(a)?.cmp_precedence(&parse(b)?))
+}
+
+/// Whether `version` is in `range`. A range is one or more sets of
+/// comparators separated by `||`; a set matches when all of its comparators
+/// do. Comparators are `^1.2`, `~1.2.3`, `>=1.0 <2.0` (space or comma
+/// separated), wildcards like `1.x` or `*`, and hyphen ranges `1.0 - 2.0`.
+/// A bare version is a caret requirement, as in `hype.json` dependencies.
+pub fn satisfies(version: &str, range: &str) -> Result<bool> {
+    let version = parse(version)?;
+    let sets = parse_range(range)?;
+    Ok(sets.iter().any(|set| set.matches(&version)))
+}
+
+fn parse_range(range: &str) -> Result<Vec<VersionReq>> {
+    let invalid = |reason: String| SemverError::InvalidRange {
+        input: range.to_string(),
+        reason,
+    };
+    range
+        .split("||")
+        .map(|set| {
+            let set = normalize_set(set).map_err(invalid)?;
+            VersionReq::parse(&set).map_err(|e| invalid(e.to_string()))
+        })
+        .collect()
+}
+
+/// Rewrite one comparator set into the comma-separated form `VersionReq`
+/// parses: `>= 1.0 <2` becomes `>=1.0, <2` and `1.0 - 2.0` becomes
+/// `>=1.0, <=2.0`.
+fn normalize_set(set: &str) -> std::result::Result<String, String> {
+    let tokens: Vec<&str> = set
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|token| !token.is_empty())
+        .collect();
+
+    if let [from, "-", to] = tokens[..] {
+        return Ok(format!(">={}, <={}", from, to));
+    }
+    if tokens.contains(&"-") {
+        return Err("a hyphen range must be 'FROM - TO'".to_string());
+    }
+    if tokens.is_empty() {
+        return Ok("*".to_string());
+    }
+
+    let mut comparators = Vec::new();
+    let mut pending_operator: Option<&str> = None;
+    for token in tokens {
+        if token.chars().all(|c| "<>=~^".contains(c)) {
+            if pending_operator.replace(token).is_some() {
+                return Err(format!("operator '{}' is missing a version", token));
+            }
+            continue;
+        }
+        comparators.push(format!(
+            "{}{}",
+            pending_operator.take().unwrap_or(""),
+            token
+        ));
+    }
+    if let Some(operator) = pending_operator {
+        return Err(format!("operator '{}' is missing a version", operator));
+    }
+    Ok(comparators.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare_orders_by_precedence() {
+        let ordered = [
+            "0.9.0",
+            "1.0.0-alpha",
+            "1.0.0-alpha.1",
+            "1.0.0-beta",
+            "1.0.0-rc.1",
+            "1.0.0",
+            "1.2.0",
+            "1.10.0",
+        ];
+        for pair in ordered.windows(2) {
+            assert_eq!(
+                compare(pair[0], pair[1]).unwrap(),
+                Ordering::Less,
+                "{:?}",
+                pair
+            );
+        }
+        assert_eq!(compare("1.0.0+build.1", "1.0.0").unwrap(), Ordering::Equal);
+        assert_eq!(compare("v2.0.0", "2.0.0").unwrap(), Ordering::Equal);
+        assert!(compare("1.0", "1.0.0").is_err());
+    }
+
+    #[test]
+    fn test_satisfies_range_syntax() {
+        assert!(satisfies("1.5.0", "^1.2.3").unwrap());
+        assert!(!satisfies("2.0.0", "^1.2.3").unwrap());
+        assert!(satisfies("1.2.9", "~1.2.3").unwrap());
+        assert!(!satisfies("1.3.0", "~1.2.3").unwrap());
+        assert!(satisfies("1.4.0", ">= 1.2 <2").unwrap());
+        assert!(satisfies("1.4.0", ">=1.2, <2").unwrap());
+        assert!(satisfies("2.0.0", "1.0.0 - 2.0.0").unwrap());
+        assert!(!satisfies("2.0.1", "1.0.0 - 2.0.0").unwrap());
+        assert!(satisfies("3.1.0", "^1.0 || ^3.0").unwrap());
+        assert!(satisfies("1.9.9", "1.x").unwrap());
+        assert!(satisfies("1.2.7", "1.2.*").unwrap());
+        assert!(satisfies("1.2.4-beta", ">=1.2.4-alpha").unwrap());
+        assert!(!satisfies("1.2.4", "=1.2.3").unwrap());
+        assert!(satisfies("0.1.0", "*").unwrap());
+        assert!(!satisfies("2.0.0-beta", "^1.0.0").unwrap());
+    }
+
+    #[test]
+    fn test_invalid_ranges() {
+        assert!(satisfies("1.0.0", ">=").is_err());
+        assert!(satisfies("1.0.0", "1.0 - ").is_err());
+        assert!(satisfies("1.0.0", "not a range").is_err());
+    }
+}
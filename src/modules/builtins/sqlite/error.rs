@@ -0,0 +1,62 @@
+use crate::error::StructuredError;
+use std::fmt;
+
+#[derive(Debug)]
+pub enum SqliteError {
+    Open { path: String, reason: String },
+    Query(String),
+    Constraint(String),
+    InvalidParameter(String),
+    Closed,
+}
+
+impl fmt::Display for SqliteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SqliteError::Open { path, reason } => {
+                write!(f, "Cannot open database '{}': {}", path, reason)
+            }
+            SqliteError::Query(msg) => write!(f, "SQLite error: {}", msg),
+            SqliteError::Constraint(msg) => write!(f, "Constraint failed: {}", msg),
+            SqliteError::InvalidParameter(msg) => write!(f, "Invalid parameter: {}", msg),
+            SqliteError::Closed => write!(f, "Database is closed"),
+        }
+    }
+}
+
+impl std::error::Error for SqliteError {}
+
+impl SqliteError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            SqliteError::Open { .. } => "ERR_OPEN",
+            SqliteError::Query(_) => "ERR_QUERY",
+            SqliteError::Constraint(_) => "ERR_CONSTRAINT",
+            SqliteError::InvalidParameter(_) => "ERR_INVALID_PARAMETER",
+            SqliteError::Closed => "ERR_CLOSED",
+        }
+    }
+}
+
+impl From<rusqlite::Error> for SqliteError {
+    fn from(err: rusqlite::Error) -> Self {
+        match err.sqlite_error_code() {
+            Some(rusqlite::ErrorCode::ConstraintViolation) => {
+                SqliteError::Constraint(err.to_string())
+            }
+            _ => SqliteError::Query(err.to_string()),
+        }
+    }
+}
+
+impl From<SqliteError> for StructuredError {
+    fn from(err: SqliteError) -> Self {
+        StructuredError::new("sqlite", err.code(), err.to_string())
+    }
+}
+
+impl From<SqliteError> for crate::error::HypeError {
+    fn from(err: SqliteError) -> Self {
+        crate::error::HypeError::Execution(err.to_string())
+    }
+}
@@ -0,0 +1,255 @@
+use super::error::SqliteError;
+use super::operations::*;
+use crate::lua::error::{structured_error, wrap_module_errors};
+use crate::lua::security::check_file_access;
+use mlua::{Function, Lua, MultiValue, Result as LuaResult, Table, Value as LuaValue};
+use rusqlite::types::Value;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+pub fn create_sqlite_module(lua: &Lua) -> LuaResult<Table<'_>> {
+    let sqlite = lua.create_table()?;
+
+    let open_fn = lua.create_function(|lua, path: String| {
+        if path != MEMORY {
+            check_file_access(lua, &path, "write").map_err(structured_error)?;
+        }
+        let database = Database::open(&path).map_err(structured_error)?;
+        create_database_table(lua, Rc::new(RefCell::new(database)))
+    })?;
+    sqlite.set("open", open_fn)?;
+
+    wrap_module_errors(lua, &sqlite)?;
+    Ok(sqlite)
+}
+
+/// The object returned by `sqlite.open`. Methods are called with `:`, so each
+/// function takes the table itself first.
+fn create_database_table(lua: &Lua, database: Rc<RefCell<Database>>) -> LuaResult<Table<'_>> {
+    let table = lua.create_table()?;
+
+    let db = database.clone();
+    let exec_fn = lua.create_function(move |_, (_, sql, params): (Table, String, LuaValue)| {
+        let database = db.borrow();
+        let changes = match params {
+            LuaValue::Nil => database.exec_batch(&sql),
+            params => database
+                .execute(&sql, &lua_to_params(params)?)
+                .map(|n| n as u64),
+        };
+        changes.map_err(structured_error)
+    })?;
+    table.set("exec", exec_fn)?;
+
+    let db = database.clone();
+    let query_fn =
+        lua.create_function(move |lua, (_, sql, params): (Table, String, LuaValue)| {
+            let rows = db.borrow().query(&sql, &lua_to_params(params)?);
+            rows_to_lua(lua, rows.map_err(structured_error)?)
+        })?;
+    table.set("query", query_fn)?;
+
+    let db = database.clone();
+    let prepare_fn = lua.create_function(move |lua, (_, sql): (Table, String)| {
+        db.borrow().prepare(&sql).map_err(structured_error)?;
+        create_statement_table(lua, db.clone(), sql)
+    })?;
+    table.set("prepare", prepare_fn)?;
+
+    // Commits when the callback returns, rolls back and re-raises when it
+    // errors. Nested calls use savepoints.
+    let db = database.clone();
+    let transaction_fn = lua.create_function(move |_, (this, callback): (Table, Function)| {
+        db.borrow_mut().begin().map_err(structured_error)?;
+        match callback.call::<_, MultiValue>(this) {
+            Ok(results) => {
+                if let Err(e) = db.borrow_mut().commit() {
+                    let _ = db.borrow_mut().rollback();
+                    return Err(structured_error(e));
+                }
+                Ok(results)
+            }
+            Err(e) => {
+                db.borrow_mut().rollback().map_err(structured_error)?;
+                Err(e)
+            }
+        }
+    })?;
+    table.set("transaction", transaction_fn)?;
+
+    let db = database.clone();
+    let last_insert_fn = lua.create_function(move |_, _: Table| {
+        db.borrow().last_insert_rowid().map_err(structured_error)
+    })?;
+    table.set("lastInsertRowId", last_insert_fn)?;
+
+    let db = database.clone();
+    let is_closed_fn = lua.create_function(move |_, _: Table| Ok(db.borrow().is_closed()))?;
+    table.set("isClosed", is_closed_fn)?;
+
+    let close_fn = lua.create_function(move |_, _: Table| {
+        database.borrow_mut().close().map_err(structured_error)
+    })?;
+    table.set("close", close_fn)?;
+
+    wrap_module_errors(lua, &table)?;
+    Ok(table)
+}
+
+/// The object returned by `db:prepare`. The compiled statement is cached on
+/// the connection, so running it again skips parsing.
+fn create_statement_table(
+    lua: &Lua,
+    database: Rc<RefCell<Database>>,
+    sql: String,
+) -> LuaResult<Table<'_>> {
+    let table = lua.create_table()?;
+    table.set("sql", sql.as_str())?;
+
+    let db = database.clone();
+    let statement_sql = sql.clone();
+    let exec_fn = lua.create_function(move |_, (_, params): (Table, LuaValue)| {
+        let changes = db.borrow().execute(&statement_sql, &lua_to_params(params)?);
+        changes.map_err(structured_error)
+    })?;
+    table.set("exec", exec_fn)?;
+
+    let query_fn = lua.create_function(move |lua, (_, params): (Table, LuaValue)| {
+        let rows = database.borrow().query(&sql, &lua_to_params(params)?);
+        rows_to_lua(lua, rows.map_err(structured_error)?)
+    })?;
+    table.set("query", query_fn)?;
+
+    wrap_module_errors(lua, &table)?;
+    Ok(table)
+}
+
+/// `nil` binds nothing, an array binds `?` placeholders in order, and a table
+/// with string keys binds `:name` placeholders.
+fn lua_to_params(params: LuaValue) -> LuaResult<Params> {
+    let table = match params {
+        LuaValue::Nil => return Ok(Params::default()),
+        LuaValue::Table(table) => table,
+        other => {
+            return Err(structured_error(SqliteError::InvalidParameter(format!(
+                "parameters must be a table, not {}",
+                other.type_name()
+            ))))
+        }
+    };
+
+    let mut positional = HashMap::new();
+    let mut named = HashMap::new();
+    for pair in table.pairs::<LuaValue, LuaValue>() {
+        let (key, value) = pair?;
+        let value = lua_to_sql(value)?;
+        match key {
+            LuaValue::Integer(i) if i > 0 => {
+                positional.insert(i as usize, value);
+            }
+            LuaValue::String(name) => {
+                named.insert(name.to_str()?.to_string(), value);
+            }
+            other => {
+                return Err(structured_error(SqliteError::InvalidParameter(format!(
+                    "unexpected parameter key {:?}",
+                    other
+                ))))
+            }
+        }
+    }
+
+    match (positional.is_empty(), named.is_empty()) {
+        (_, true) => {
+            // Holes from nil values are bound as NULL
+            let count = positional.keys().max().copied().unwrap_or(0);
+            let values = (1..=count)
+                .map(|i| positional.remove(&i).unwrap_or(Value::Null))
+                .collect();
+            Ok(Params::Positional(values))
+        }
+        (true, false) => Ok(Params::Named(named)),
+        (false, false) => Err(structured_error(SqliteError::InvalidParameter(
+            "use either an array or a table of names, not both".to_string(),
+        ))),
+    }
+}
+
+fn lua_to_sql(value: LuaValue) -> LuaResult<Value> {
+    Ok(match value {
+        LuaValue::Nil => Value::Null,
+        LuaValue::Boolean(b) => Value::Integer(b as i64),
+        LuaValue::Integer(i) => Value::Integer(i),
+        LuaValue::Number(n) => Value::Real(n),
+        LuaValue::String(s) => match s.to_str() {
+            Ok(text) => Value::Text(text.to_string()),
+            Err(_) => Value::Blob(s.as_bytes().to_vec()),
+        },
+        other => {
+            return Err(structured_error(SqliteError::InvalidParameter(format!(
+                "cannot bind a {}",
+                other.type_name()
+            ))));
+        }
+    })
+}
+
+fn sql_to_lua(lua: &Lua, value: Value) -> LuaResult<LuaValue<'_>> {
+    Ok(match value {
+        Value::Null => LuaValue::Nil,
+        Value::Integer(i) => LuaValue::Integer(i),
+        Value::Real(n) => LuaValue::Number(n),
+        Value::Text(text) => LuaValue::String(lua.create_string(&text)?),
+        Value::Blob(data) => LuaValue::String(lua.create_string(&data)?),
+    })
+}
+
+/// Rows become tables keyed by column name; NULL columns are absent.
+fn rows_to_lua(lua: &Lua, rows: Vec<Row>) -> LuaResult<Table<'_>> {
+    let result = lua.create_table_with_capacity(rows.len(), 0)?;
+    for row in rows {
+        let table = lua.create_table_with_capacity(0, row.len())?;
+        for (column, value) in row {
+            table.set(column, sql_to_lua(lua, value)?)?;
+        }
+        result.raw_push(table)?;
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_sqlite_module() {
+        let lua = Lua::new();
+        let sqlite = create_sqlite_module(&lua).unwrap();
+        assert!(sqlite.contains_key("open").unwrap());
+    }
+
+    #[test]
+    fn test_statement_reuse_and_named_params() {
+        let lua = Lua::new();
+        lua.globals()
+            .set("sqlite", create_sqlite_module(&lua).unwrap())
+            .unwrap();
+        lua.load(
+            r#"
+local db = sqlite.open(":memory:")
+db:exec("CREATE TABLE kv (k TEXT PRIMARY KEY, v)")
+local put = db:prepare("INSERT INTO kv (k, v) VALUES (:k, :v)")
+assert(put:exec({k = "a", v = 1}) == 1)
+assert(put:exec({k = "b", v = 2.5}) == 1)
+local rows = db:query("SELECT v FROM kv WHERE k = ?", {"b"})
+assert(#rows == 1 and rows[1].v == 2.5)
+
+local ok, err = pcall(put.exec, put, {k = "a", v = 3})
+assert(not ok and err.code == "ERR_CONSTRAINT", tostring(err))
+"#,
+        )
+        .exec()
+        .unwrap();
+    }
+}
@@ -0,0 +1,61 @@
+pub mod error;
+pub mod lua_bindings;
+pub mod operations;
+
+use serde_json::{json, Value as JsonValue};
+
+use super::BuiltinModule;
+use crate::error::HypeError;
+
+pub use lua_bindings::create_sqlite_module;
+
+pub struct SqliteModule;
+
+impl SqliteModule {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for SqliteModule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BuiltinModule for SqliteModule {
+    fn name(&self) -> &str {
+        "sqlite"
+    }
+
+    fn exports(&self) -> std::result::Result<JsonValue, HypeError> {
+        Ok(json!({
+            "__id": "sqlite",
+            "__desc": "SQLite databases with bound parameters, prepared statements and transactions",
+            "open": {
+                "__fn": "open",
+                "__desc": "Open or create a database file; \":memory:\" for a private in-memory database",
+                "__signature": "open(path: string) -> Database"
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sqlite_module_name() {
+        let module = SqliteModule::new();
+        assert_eq!(module.name(), "sqlite");
+    }
+
+    #[test]
+    fn test_sqlite_module_exports() {
+        let module = SqliteModule::new();
+        let exports = module.exports().unwrap();
+        assert_eq!(exports["__id"], "sqlite");
+        assert!(exports.get("open").is_some());
+    }
+}
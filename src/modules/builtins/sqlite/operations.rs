@@ -0,0 +1,281 @@
+use super::error::SqliteError;
+use rusqlite::hooks::{AuthAction, AuthContext, Authorization};
+use rusqlite::types::Value;
+use rusqlite::{Connection, OpenFlags, Statement};
+use std::collections::HashMap;
+
+pub type Result<T> = std::result::Result<T, SqliteError>;
+
+/// Path that opens a private database held in memory.
+pub const MEMORY: &str = ":memory:";
+
+/// Values for a statement's `?` placeholders, in order, or for its `:name`,
+/// `@name` and `$name` placeholders by name (without the prefix).
+#[derive(Debug, Clone, PartialEq)]
+pub enum Params {
+    Positional(Vec<Value>),
+    Named(HashMap<String, Value>),
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Params::Positional(Vec::new())
+    }
+}
+
+/// One result row as column name and value pairs, in column order.
+pub type Row = Vec<(String, Value)>;
+
+/// A connection that can be closed while scripts still hold references to it,
+/// and that tracks nested `transaction` calls.
+pub struct Database {
+    connection: Option<Connection>,
+    savepoints: usize,
+}
+
+impl Database {
+    pub fn open(path: &str) -> Result<Self> {
+        // URI filenames are off so the path SQLite opens is the one the
+        // security policy checked
+        let connection = if path == MEMORY {
+            Connection::open_in_memory()
+        } else {
+            Connection::open_with_flags(path, OpenFlags::default() - OpenFlags::SQLITE_OPEN_URI)
+        }
+        .map_err(|e| SqliteError::Open {
+            path: path.to_string(),
+            reason: e.to_string(),
+        })?;
+        connection.authorizer(Some(deny_attach));
+        Ok(Self {
+            connection: Some(connection),
+            savepoints: 0,
+        })
+    }
+
+    fn connection(&self) -> Result<&Connection> {
+        self.connection.as_ref().ok_or(SqliteError::Closed)
+    }
+
+    /// Run one or more `;`-separated statements without parameters.
+    pub fn exec_batch(&self, sql: &str) -> Result<u64> {
+        let connection = self.connection()?;
+        connection.execute_batch(sql)?;
+        Ok(connection.changes())
+    }
+
+    /// Run a single statement with bound parameters, returning the number of
+    /// rows changed.
+    pub fn execute(&self, sql: &str, params: &Params) -> Result<usize> {
+        let mut statement = self.connection()?.prepare_cached(sql)?;
+        bind(&mut statement, params)?;
+        Ok(statement.raw_execute()?)
+    }
+
+    pub fn query(&self, sql: &str, params: &Params) -> Result<Vec<Row>> {
+        let mut statement = self.connection()?.prepare_cached(sql)?;
+        bind(&mut statement, params)?;
+        let columns: Vec<String> = statement
+            .column_names()
+            .into_iter()
+            .map(str::to_string)
+            .collect();
+
+        let mut rows = statement.raw_query();
+        let mut result = Vec::new();
+        while let Some(row) = rows.next()? {
+            let values = columns
+                .iter()
+                .enumerate()
+                .map(|(i, name)| Ok((name.clone(), row.get::<_, Value>(i)?)))
+                .collect::<Result<Row>>()?;
+            result.push(values);
+        }
+        Ok(result)
+    }
+
+    /// Compile `sql` without running it, so mistakes surface at `prepare`.
+    /// The compiled statement stays in the connection's cache for later runs.
+    pub fn prepare(&self, sql: &str) -> Result<()> {
+        self.connection()?.prepare_cached(sql)?;
+        Ok(())
+    }
+
+    pub fn last_insert_rowid(&self) -> Result<i64> {
+        Ok(self.connection()?.last_insert_rowid())
+    }
+
+    /// Start a transaction, or a savepoint inside one that is already open.
+    pub fn begin(&mut self) -> Result<()> {
+        let sql = if self.savepoints == 0 && self.connection()?.is_autocommit() {
+            "BEGIN".to_string()
+        } else {
+            format!("SAVEPOINT hype_{}", self.savepoints + 1)
+        };
+        self.connection()?.execute_batch(&sql)?;
+        self.savepoints += 1;
+        Ok(())
+    }
+
+    pub fn commit(&mut self) -> Result<()> {
+        let sql = match self.savepoints {
+            0 => return Ok(()),
+            1 => "COMMIT".to_string(),
+            n => format!("RELEASE hype_{}", n),
+        };
+        self.savepoints -= 1;
+        self.connection()?.execute_batch(&sql)?;
+        Ok(())
+    }
+
+    pub fn rollback(&mut self) -> Result<()> {
+        let sql = match self.savepoints {
+            0 => return Ok(()),
+            1 => "ROLLBACK".to_string(),
+            n => format!("ROLLBACK TO hype_{0}; RELEASE hype_{0}", n),
+        };
+        self.savepoints -= 1;
+        let connection = self.connection()?;
+        // SQLite may already have rolled back, e.g. after a full disk
+        if sql == "ROLLBACK" && connection.is_autocommit() {
+            return Ok(());
+        }
+        connection.execute_batch(&sql)?;
+        Ok(())
+    }
+
+    pub fn close(&mut self) -> Result<()> {
+        if let Some(connection) = self.connection.take() {
+            connection.close().map_err(|(_, e)| SqliteError::from(e))?;
+        }
+        Ok(())
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.connection.is_none()
+    }
+}
+
+/// Refuse `ATTACH` and `VACUUM INTO`, which would otherwise open or create
+/// files that never went through the security policy. Plain `VACUUM` attaches
+/// an unnamed temporary database and stays allowed.
+fn deny_attach(context: AuthContext<'_>) -> Authorization {
+    match context.action {
+        AuthAction::Attach { filename } if !filename.is_empty() => Authorization::Deny,
+        _ => Authorization::Allow,
+    }
+}
+
+/// Bind every placeholder in `statement`. Values are always bound, never
+/// spliced into the SQL, so they cannot change what the statement does.
+fn bind(statement: &mut Statement, params: &Params) -> Result<()> {
+    let count = statement.parameter_count();
+    match params {
+        Params::Positional(values) => {
+            if values.len() != count {
+                return Err(SqliteError::InvalidParameter(format!(
+                    "statement expects {} parameters, got {}",
+                    count,
+                    values.len()
+                )));
+            }
+            for (i, value) in values.iter().enumerate() {
+                statement.raw_bind_parameter(i + 1, value)?;
+            }
+        }
+        Params::Named(values) => {
+            for i in 1..=count {
+                let name = statement
+                    .parameter_name(i)
+                    .ok_or_else(|| {
+                        SqliteError::InvalidParameter(format!(
+                            "parameter {} is positional; pass an array",
+                            i
+                        ))
+                    })?
+                    .to_string();
+                let value = values.get(&name[1..]).ok_or_else(|| {
+                    SqliteError::InvalidParameter(format!("missing value for {}", name))
+                })?;
+                statement.raw_bind_parameter(i, value)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn people() -> Database {
+        let db = Database::open(MEMORY).unwrap();
+        db.exec_batch(
+            "CREATE TABLE people (id INTEGER PRIMARY KEY, name TEXT NOT NULL, age INTEGER)",
+        )
+        .unwrap();
+        db
+    }
+
+    #[test]
+    fn test_positional_and_named_binding() {
+        let db = people();
+        let insert = "INSERT INTO people (name, age) VALUES (?, ?)";
+        let params = Params::Positional(vec![Value::Text("Ada".into()), Value::Integer(36)]);
+        assert_eq!(db.execute(insert, &params).unwrap(), 1);
+        assert_eq!(db.last_insert_rowid().unwrap(), 1);
+
+        let named = Params::Named(HashMap::from([(
+            "name".to_string(),
+            Value::Text("Ada".into()),
+        )]));
+        let rows = db
+            .query("SELECT age FROM people WHERE name = :name", &named)
+            .unwrap();
+        assert_eq!(rows, vec![vec![("age".to_string(), Value::Integer(36))]]);
+
+        // A quote in a value is data, not SQL
+        let hostile = Params::Positional(vec![Value::Text("x'); DROP TABLE people; --".into())]);
+        assert!(db
+            .query("SELECT * FROM people WHERE name = ?", &hostile)
+            .unwrap()
+            .is_empty());
+        assert!(db.execute(insert, &Params::default()).is_err());
+    }
+
+    #[test]
+    fn test_nested_rollback_keeps_outer_work() {
+        let mut db = people();
+        db.begin().unwrap();
+        db.exec_batch("INSERT INTO people (name) VALUES ('outer')")
+            .unwrap();
+        db.begin().unwrap();
+        db.exec_batch("INSERT INTO people (name) VALUES ('inner')")
+            .unwrap();
+        db.rollback().unwrap();
+        db.commit().unwrap();
+
+        let rows = db
+            .query("SELECT name FROM people", &Params::default())
+            .unwrap();
+        assert_eq!(
+            rows,
+            vec![vec![("name".to_string(), Value::Text("outer".into()))]]
+        );
+    }
+
+    #[test]
+    fn test_attach_and_vacuum_into_are_denied() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("attached.db");
+        let db = people();
+
+        let attach = format!("ATTACH DATABASE '{}' AS other", target.display());
+        assert!(db.exec_batch(&attach).is_err());
+        let vacuum_into = format!("VACUUM INTO '{}'", target.display());
+        assert!(db.exec_batch(&vacuum_into).is_err());
+        assert!(!target.exists());
+
+        db.exec_batch("VACUUM").unwrap();
+    }
+}
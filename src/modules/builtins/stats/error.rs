@@ -0,0 +1,48 @@
+use crate::error::StructuredError;
+use std::fmt;
+
+#[derive(Debug)]
+pub enum StatsError {
+    NotANumber { index: usize, type_name: String },
+    InvalidPercentile(f64),
+}
+
+impl fmt::Display for StatsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StatsError::NotANumber { index, type_name } => {
+                write!(f, "Element {} is a {}, expected a number", index, type_name)
+            }
+            StatsError::InvalidPercentile(p) => {
+                write!(
+                    f,
+                    "Invalid percentile {}: expected a value from 0 to 100",
+                    p
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for StatsError {}
+
+impl StatsError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            StatsError::NotANumber { .. } => "ERR_NOT_A_NUMBER",
+            StatsError::InvalidPercentile(_) => "ERR_INVALID_PERCENTILE",
+        }
+    }
+}
+
+impl From<StatsError> for StructuredError {
+    fn from(err: StatsError) -> Self {
+        StructuredError::new("stats", err.code(), err.to_string())
+    }
+}
+
+impl From<StatsError> for crate::error::HypeError {
+    fn from(err: StatsError) -> Self {
+        crate::error::HypeError::Execution(err.to_string())
+    }
+}
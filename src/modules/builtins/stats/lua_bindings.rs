@@ -0,0 +1,114 @@
+use super::error::StatsError;
+use super::operations::*;
+use crate::lua::error::{structured_error, wrap_module_errors};
+use mlua::{Lua, Result as LuaResult, Table, Value};
+
+type Summary = fn(&[f64]) -> Option<f64>;
+type Spread = fn(&[f64], bool) -> Option<f64>;
+
+pub fn create_stats_module(lua: &Lua) -> LuaResult<Table<'_>> {
+    let stats = lua.create_table()?;
+
+    stats.set(
+        "sum",
+        lua.create_function(|_, values: Table| Ok(sum(&numbers(&values)?)))?,
+    )?;
+
+    let summaries: [(&str, Summary); 5] = [
+        ("mean", mean),
+        ("median", median),
+        ("mode", mode),
+        ("min", min),
+        ("max", max),
+    ];
+    for (name, summary) in summaries {
+        let summary_fn =
+            lua.create_function(move |_, values: Table| Ok(summary(&numbers(&values)?)))?;
+        stats.set(name, summary_fn)?;
+    }
+
+    let spreads: [(&str, Spread); 2] = [("variance", variance), ("stddev", stddev)];
+    for (name, spread) in spreads {
+        let spread_fn =
+            lua.create_function(move |_, (values, options): (Table, Option<Table>)| {
+                let sample = match options {
+                    Some(options) => options.get::<_, Option<bool>>("sample")?.unwrap_or(false),
+                    None => false,
+                };
+                Ok(spread(&numbers(&values)?, sample))
+            })?;
+        stats.set(name, spread_fn)?;
+    }
+
+    let percentile_fn = lua.create_function(|_, (values, p): (Table, f64)| {
+        percentile(&numbers(&values)?, p).map_err(structured_error)
+    })?;
+    stats.set("percentile", percentile_fn)?;
+
+    wrap_module_errors(lua, &stats)?;
+    Ok(stats)
+}
+
+/// The array part of `values` as numbers. Numeric strings are rejected too,
+/// since they usually mean a column was not converted.
+fn numbers(values: &Table) -> LuaResult<Vec<f64>> {
+    let length = values.raw_len();
+    let mut numbers = Vec::with_capacity(length);
+    for index in 1..=length {
+        match values.raw_get::<_, Value>(index)? {
+            Value::Integer(i) => numbers.push(i as f64),
+            Value::Number(n) => numbers.push(n),
+            other => {
+                return Err(structured_error(StatsError::NotANumber {
+                    index,
+                    type_name: other.type_name().to_string(),
+                }))
+            }
+        }
+    }
+    Ok(numbers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_stats_module() {
+        let lua = Lua::new();
+        let stats = create_stats_module(&lua).unwrap();
+        for name in [
+            "mean",
+            "median",
+            "mode",
+            "stddev",
+            "variance",
+            "percentile",
+            "min",
+            "max",
+            "sum",
+        ] {
+            assert!(stats.contains_key(name).unwrap(), "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_errors_are_structured() {
+        let lua = Lua::new();
+        lua.globals()
+            .set("stats", create_stats_module(&lua).unwrap())
+            .unwrap();
+        lua.load(
+            r#"
+local ok, err = pcall(stats.mean, {1, "2", 3})
+assert(not ok and err.kind == "stats" and err.code == "ERR_NOT_A_NUMBER", tostring(err))
+assert(tostring(err):find("Element 2 is a string"), tostring(err))
+
+ok, err = pcall(stats.percentile, {1, 2}, 150)
+assert(not ok and err.code == "ERR_INVALID_PERCENTILE", tostring(err))
+"#,
+        )
+        .exec()
+        .unwrap();
+    }
+}
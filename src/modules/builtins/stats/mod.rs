@@ -0,0 +1,102 @@
+pub mod error;
+pub mod lua_bindings;
+pub mod operations;
+
+use serde_json::{json, Value as JsonValue};
+
+use super::BuiltinModule;
+use crate::error::HypeError;
+
+pub use lua_bindings::create_stats_module;
+
+pub struct StatsModule;
+
+impl StatsModule {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for StatsModule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BuiltinModule for StatsModule {
+    fn name(&self) -> &str {
+        "stats"
+    }
+
+    fn exports(&self) -> std::result::Result<JsonValue, HypeError> {
+        Ok(json!({
+            "__id": "stats",
+            "__desc": "Descriptive statistics over numeric arrays",
+            "sum": {
+                "__fn": "sum",
+                "__desc": "Sum of the values (0 for an empty array)",
+                "__signature": "sum(values: number[]) -> number"
+            },
+            "mean": {
+                "__fn": "mean",
+                "__desc": "Arithmetic mean",
+                "__signature": "mean(values: number[]) -> number?"
+            },
+            "median": {
+                "__fn": "median",
+                "__desc": "Middle value, or the mean of the two middle values",
+                "__signature": "median(values: number[]) -> number?"
+            },
+            "mode": {
+                "__fn": "mode",
+                "__desc": "Most frequent value; ties go to the smallest",
+                "__signature": "mode(values: number[]) -> number?"
+            },
+            "variance": {
+                "__fn": "variance",
+                "__desc": "Population variance, or sample variance with {sample = true}",
+                "__signature": "variance(values: number[], options?: {sample?: boolean}) -> number?"
+            },
+            "stddev": {
+                "__fn": "stddev",
+                "__desc": "Population standard deviation, or sample standard deviation with {sample = true}",
+                "__signature": "stddev(values: number[], options?: {sample?: boolean}) -> number?"
+            },
+            "percentile": {
+                "__fn": "percentile",
+                "__desc": "The pth percentile (0-100), interpolated between the nearest ranks",
+                "__signature": "percentile(values: number[], p: number) -> number?"
+            },
+            "min": {
+                "__fn": "min",
+                "__desc": "Smallest value",
+                "__signature": "min(values: number[]) -> number?"
+            },
+            "max": {
+                "__fn": "max",
+                "__desc": "Largest value",
+                "__signature": "max(values: number[]) -> number?"
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stats_module_name() {
+        let module = StatsModule::new();
+        assert_eq!(module.name(), "stats");
+    }
+
+    #[test]
+    fn test_stats_module_exports() {
+        let module = StatsModule::new();
+        let exports = module.exports().unwrap();
+        for name in ["sum", "mean", "median", "mode", "stddev", "percentile"] {
+            assert!(exports.get(name).is_some(), "{}", name);
+        }
+    }
+}
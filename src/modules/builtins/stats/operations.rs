@@ -0,0 +1,145 @@
+use super::error::StatsError;
+
+// Summaries of an empty array are `None` rather than NaN.
+
+pub fn sum(values: &[f64]) -> f64 {
+    values.iter().sum()
+}
+
+pub fn mean(values: &[f64]) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    Some(sum(values) / values.len() as f64)
+}
+
+pub fn min(values: &[f64]) -> Option<f64> {
+    values.iter().copied().reduce(f64::min)
+}
+
+pub fn max(values: &[f64]) -> Option<f64> {
+    values.iter().copied().reduce(f64::max)
+}
+
+pub fn median(values: &[f64]) -> Option<f64> {
+    let sorted = sorted(values);
+    let n = sorted.len();
+    match n {
+        0 => None,
+        _ if n % 2 == 1 => Some(sorted[n / 2]),
+        _ => Some((sorted[n / 2 - 1] + sorted[n / 2]) / 2.0),
+    }
+}
+
+/// The most frequent value. Ties go to the smallest value so the result does
+/// not depend on element order.
+pub fn mode(values: &[f64]) -> Option<f64> {
+    let sorted = sorted(values);
+    let mut best: Option<(f64, usize)> = None;
+    let mut start = 0;
+    while start < sorted.len() {
+        let value = sorted[start];
+        let count = sorted[start..].iter().take_while(|&&v| v == value).count();
+        if best.map_or(true, |(_, best_count)| count > best_count) {
+            best = Some((value, count));
+        }
+        start += count.max(1);
+    }
+    best.map(|(value, _)| value)
+}
+
+/// Population variance, or the sample variance (dividing by n - 1) when
+/// `sample` is set.
+pub fn variance(values: &[f64], sample: bool) -> Option<f64> {
+    let mean = mean(values)?;
+    let divisor = if sample {
+        values.len().checked_sub(1).filter(|&d| d > 0)?
+    } else {
+        values.len()
+    };
+    let squares: f64 = values.iter().map(|v| (v - mean).powi(2)).sum();
+    Some(squares / divisor as f64)
+}
+
+pub fn stddev(values: &[f64], sample: bool) -> Option<f64> {
+    variance(values, sample).map(f64::sqrt)
+}
+
+/// The `p`th percentile (0 to 100), interpolating linearly between the two
+/// nearest ranks like NumPy's default and Excel's `PERCENTILE.INC`.
+pub fn percentile(values: &[f64], p: f64) -> Result<Option<f64>, StatsError> {
+    if !(0.0..=100.0).contains(&p) {
+        return Err(StatsError::InvalidPercentile(p));
+    }
+    let sorted = sorted(values);
+    if sorted.is_empty() {
+        return Ok(None);
+    }
+    let rank = p / 100.0 * (sorted.len() - 1) as f64;
+    let lower = rank.floor() as usize;
+    let upper = rank.ceil() as usize;
+    let fraction = rank - lower as f64;
+    Ok(Some(
+        sorted[lower] + (sorted[upper] - sorted[lower]) * fraction,
+    ))
+}
+
+fn sorted(values: &[f64]) -> Vec<f64> {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    sorted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DATA: [f64; 8] = [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0];
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn test_summaries() {
+        assert_eq!(sum(&DATA), 40.0);
+        assert_eq!(mean(&DATA), Some(5.0));
+        assert_eq!(median(&DATA), Some(4.5));
+        assert_eq!(median(&[3.0, 1.0, 2.0]), Some(2.0));
+        assert_eq!(mode(&DATA), Some(4.0));
+        assert_eq!(mode(&[3.0, 1.0, 3.0, 1.0]), Some(1.0));
+        assert_eq!(min(&DATA), Some(2.0));
+        assert_eq!(max(&DATA), Some(9.0));
+    }
+
+    #[test]
+    fn test_variance_population_and_sample() {
+        assert_eq!(variance(&DATA, false), Some(4.0));
+        assert_eq!(stddev(&DATA, false), Some(2.0));
+        assert!(close(variance(&DATA, true).unwrap(), 32.0 / 7.0));
+        assert!(close(stddev(&DATA, true).unwrap(), (32.0f64 / 7.0).sqrt()));
+        assert_eq!(stddev(&[1.0], true), None);
+        assert_eq!(stddev(&[1.0], false), Some(0.0));
+    }
+
+    #[test]
+    fn test_percentile_interpolates() {
+        assert_eq!(percentile(&DATA, 0.0).unwrap(), Some(2.0));
+        assert_eq!(percentile(&DATA, 100.0).unwrap(), Some(9.0));
+        assert_eq!(percentile(&DATA, 50.0).unwrap(), Some(4.5));
+        // rank 0.9 * 7 = 6.3, between 7 and 9
+        assert!(close(percentile(&DATA, 90.0).unwrap().unwrap(), 7.6));
+        assert!(percentile(&DATA, 101.0).is_err());
+    }
+
+    #[test]
+    fn test_empty_input() {
+        assert_eq!(sum(&[]), 0.0);
+        assert_eq!(mean(&[]), None);
+        assert_eq!(median(&[]), None);
+        assert_eq!(mode(&[]), None);
+        assert_eq!(min(&[]), None);
+        assert_eq!(variance(&[], false), None);
+        assert_eq!(percentile(&[], 50.0).unwrap(), None);
+    }
+}
@@ -6,6 +6,9 @@ pub enum StringError {
     InvalidIndex(usize, usize),
     InvalidCount(i64),
     InvalidLength(usize),
+    InvalidFormat(String),
+    MissingTemplateKey(String),
+    InvalidPattern(String),
 }
 
 impl fmt::Display for StringError {
@@ -21,6 +24,11 @@ impl fmt::Display for StringError {
             StringError::InvalidLength(len) => {
                 write!(f, "Invalid length: {} (must be non-negative)", len)
             }
+            StringError::InvalidFormat(msg) => write!(f, "Invalid format: {}", msg),
+            StringError::MissingTemplateKey(key) => {
+                write!(f, "Missing template value for '{}'", key)
+            }
+            StringError::InvalidPattern(msg) => write!(f, "Invalid pattern: {}", msg),
         }
     }
 }
@@ -1,5 +1,6 @@
 use super::operations::*;
-use mlua::{Lua, Result as LuaResult, Table, Value};
+use mlua::{Function, Lua, Result as LuaResult, String as LuaString, Table, Value, Variadic};
+use std::collections::HashMap;
 
 pub fn create_string_module(lua: &Lua) -> LuaResult<Table> {
     let globals = lua.globals();
@@ -11,16 +12,26 @@ pub fn create_string_module(lua: &Lua) -> LuaResult<Table> {
         string.set(key, value)?;
     }
 
-    let split_fn = lua.create_function(|lua, (s, delimiter): (String, String)| {
-        let result = split(&s, &delimiter);
-        let table = lua.create_table()?;
-        for (i, part) in result.iter().enumerate() {
-            table.set(i + 1, part.clone())?;
-        }
-        Ok(table)
-    })?;
+    let split_fn = lua.create_function(
+        |lua, (s, delimiter, limit): (String, String, Option<usize>)| {
+            let result = split(&s, &delimiter, limit);
+            let table = lua.create_table()?;
+            for (i, part) in result.iter().enumerate() {
+                table.set(i + 1, part.clone())?;
+            }
+            Ok(table)
+        },
+    )?;
     string.set("split", split_fn)?;
 
+    let split_regex_fn = lua.create_function(
+        |lua, (s, pattern, limit): (String, String, Option<usize>)| {
+            let result = split_regex(&s, &pattern, limit).map_err(mlua::Error::external)?;
+            lua.create_sequence_from(result)
+        },
+    )?;
+    string.set("splitRegex", split_regex_fn)?;
+
     let trim_fn = lua.create_function(|_, s: String| Ok(trim(&s)))?;
     string.set("trim", trim_fn)?;
 
@@ -54,6 +65,15 @@ pub fn create_string_module(lua: &Lua) -> LuaResult<Table> {
         })?;
     string.set("padEnd", pad_end_fn)?;
 
+    let slugify_fn = lua.create_function(|_, s: String| Ok(slugify(&s)))?;
+    string.set("slugify", slugify_fn)?;
+
+    let truncate_fn =
+        lua.create_function(|_, (s, length, suffix): (String, usize, Option<String>)| {
+            Ok(truncate(&s, length, suffix.as_deref()))
+        })?;
+    string.set("truncate", truncate_fn)?;
+
     let repeat_fn = lua.create_function(|_, (s, count): (String, usize)| Ok(repeat(&s, count)))?;
     string.set("repeat", repeat_fn)?;
 
@@ -99,9 +119,53 @@ pub fn create_string_module(lua: &Lua) -> LuaResult<Table> {
     })?;
     string.set("chars", chars_fn)?;
 
+    let format_fn = lua.create_function(|lua, (fmt, args): (LuaString, Variadic<Value>)| {
+        let tostring: Function = lua.globals().get("tostring")?;
+        let args = args
+            .into_iter()
+            .map(|value| to_format_arg(&tostring, value))
+            .collect::<LuaResult<Vec<_>>>()?;
+        let formatted = format(fmt.as_bytes(), &args).map_err(mlua::Error::external)?;
+        lua.create_string(formatted)
+    })?;
+    string.set("format", format_fn)?;
+
+    let template_fn = lua.create_function(
+        |lua, (tmpl, values, options): (String, Table, Option<Table>)| {
+            let tostring: Function = lua.globals().get("tostring")?;
+            let strict = match options {
+                Some(options) => options.get::<_, Option<bool>>("strict")?.unwrap_or(false),
+                None => false,
+            };
+            let mut map = HashMap::new();
+            for pair in values.pairs::<String, Value>() {
+                let (key, value) = pair?;
+                let value = match value {
+                    Value::String(s) => s.to_str()?.to_string(),
+                    other => tostring.call(other)?,
+                };
+                map.insert(key, value);
+            }
+            template(&tmpl, &map, strict).map_err(mlua::Error::external)
+        },
+    )?;
+    string.set("template", template_fn)?;
+
     Ok(string)
 }
 
+fn to_format_arg(tostring: &Function, value: Value) -> LuaResult<FormatArg> {
+    Ok(match value {
+        Value::Integer(i) => FormatArg::Int(i),
+        Value::Number(n) => FormatArg::Float(n),
+        Value::String(s) => FormatArg::Str(s.as_bytes().to_vec()),
+        other => FormatArg::Other {
+            type_name: other.type_name().to_string(),
+            display: tostring.call(other)?,
+        },
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -115,6 +179,7 @@ mod tests {
 
         let string = result.unwrap();
         assert!(string.contains_key("split").unwrap());
+        assert!(string.contains_key("splitRegex").unwrap());
         assert!(string.contains_key("trim").unwrap());
         assert!(string.contains_key("trimStart").unwrap());
         assert!(string.contains_key("trimEnd").unwrap());
@@ -131,6 +196,10 @@ mod tests {
         assert!(string.contains_key("capitalize").unwrap());
         assert!(string.contains_key("lines").unwrap());
         assert!(string.contains_key("chars").unwrap());
+        assert!(string.contains_key("format").unwrap());
+        assert!(string.contains_key("template").unwrap());
+        assert!(string.contains_key("slugify").unwrap());
+        assert!(string.contains_key("truncate").unwrap());
     }
 
     #[test]
@@ -191,4 +260,96 @@ mod tests {
 
         assert_eq!(result, "Hello");
     }
+
+    #[test]
+    fn test_string_format() {
+        let lua = Lua::new();
+        let string = create_string_module(&lua).unwrap();
+        lua.globals().set("str", string).unwrap();
+
+        let result: String = lua
+            .load(r#"return str.format("%-6s|%6.2f|%04d|%x|%s", "id", 3.14159, 7, 255, true)"#)
+            .eval()
+            .unwrap();
+
+        assert_eq!(result, "id    |  3.14|0007|ff|true");
+    }
+
+    #[test]
+    fn test_string_format_high_bytes() {
+        let lua = Lua::new();
+        let string = create_string_module(&lua).unwrap();
+        lua.globals().set("str", string).unwrap();
+
+        let (len, matches_builtin): (i64, bool) = lua
+            .load(
+                r#"
+                local s = str.format("%c%c%s", 200, 255, "\xe9")
+                return #s, s == string.format("%c%c%s", 200, 255, "\xe9")
+                "#,
+            )
+            .eval()
+            .unwrap();
+        assert_eq!(len, 3);
+        assert!(matches_builtin);
+    }
+
+    #[test]
+    fn test_string_template() {
+        let lua = Lua::new();
+        let string = create_string_module(&lua).unwrap();
+        lua.globals().set("str", string).unwrap();
+
+        let result: String = lua
+            .load(r#"return str.template("${greeting}, ${name}! ${missing}", {greeting = "Hi", name = 42})"#)
+            .eval()
+            .unwrap();
+        assert_eq!(result, "Hi, 42! ${missing}");
+
+        let err = lua
+            .load(r#"return str.template("${missing}", {}, {strict = true})"#)
+            .exec()
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("missing"), "{}", err);
+    }
+
+    #[test]
+    fn test_string_slugify_and_truncate() {
+        let lua = Lua::new();
+        let string = create_string_module(&lua).unwrap();
+        lua.globals().set("str", string).unwrap();
+
+        let result: String = lua
+            .load(r#"return str.slugify("Héllo Wörld") .. "|" .. str.truncate("héllo", 2) .. "|" .. str.truncate("abc", 1, "...")"#)
+            .eval()
+            .unwrap();
+        assert_eq!(result, "hello-world|hé…|a...");
+    }
+
+    #[test]
+    fn test_string_split_limit_and_regex() {
+        let lua = Lua::new();
+        let string = create_string_module(&lua).unwrap();
+        lua.globals().set("str", string).unwrap();
+
+        let result: String = lua
+            .load(
+                r#"
+                local limited = str.split("a,b,c,d", ",", 2)
+                local words = str.splitRegex("one   two\tthree", "\\s+")
+                return #limited .. ":" .. limited[2] .. "|" .. table.concat(words, ",")
+            "#,
+            )
+            .eval()
+            .unwrap();
+        assert_eq!(result, "2:b,c,d|one,two,three");
+
+        let err = lua
+            .load(r#"return str.splitRegex("abc", "(")"#)
+            .exec()
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Invalid pattern"), "{}", err);
+    }
 }
@@ -37,7 +37,12 @@ impl BuiltinModule for StringModule {
             "split": {
                 "__fn": "split",
                 "__desc": "Split string into array by delimiter",
-                "__signature": "split(str: string, delimiter: string) -> string[]"
+                "__signature": "split(str: string, delimiter: string, limit?: number) -> string[]"
+            },
+            "splitRegex": {
+                "__fn": "splitRegex",
+                "__desc": "Split string into array on regular expression matches",
+                "__signature": "splitRegex(str: string, pattern: string, limit?: number) -> string[]"
             },
             "trim": {
                 "__fn": "trim",
@@ -118,6 +123,26 @@ impl BuiltinModule for StringModule {
                 "__fn": "chars",
                 "__desc": "Split string into characters",
                 "__signature": "chars(str: string) -> string[]"
+            },
+            "slugify": {
+                "__fn": "slugify",
+                "__desc": "Convert text to a lowercase, hyphenated URL slug",
+                "__signature": "slugify(str: string) -> string"
+            },
+            "truncate": {
+                "__fn": "truncate",
+                "__desc": "Cut string to length characters and append a suffix",
+                "__signature": "truncate(str: string, length: number, suffix?: string) -> string"
+            },
+            "format": {
+                "__fn": "format",
+                "__desc": "Format values printf-style (%d, %s, %f, %x, %q, ...)",
+                "__signature": "format(fmt: string, ...: any) -> string"
+            },
+            "template": {
+                "__fn": "template",
+                "__desc": "Interpolate ${name} placeholders from a table",
+                "__signature": "template(tmpl: string, values: table, options?: {strict: boolean}) -> string"
             }
         }))
     }
@@ -139,6 +164,7 @@ mod tests {
         let exports = module.exports().unwrap();
         assert!(exports.is_object());
         assert!(exports.get("split").is_some());
+        assert!(exports.get("splitRegex").is_some());
         assert!(exports.get("trim").is_some());
         assert!(exports.get("trimStart").is_some());
         assert!(exports.get("trimEnd").is_some());
@@ -155,6 +181,10 @@ mod tests {
         assert!(exports.get("capitalize").is_some());
         assert!(exports.get("lines").is_some());
         assert!(exports.get("chars").is_some());
+        assert!(exports.get("slugify").is_some());
+        assert!(exports.get("truncate").is_some());
+        assert!(exports.get("format").is_some());
+        assert!(exports.get("template").is_some());
     }
 
     #[test]
@@ -1,10 +1,35 @@
 use super::error::StringError;
-
-pub fn split(s: &str, delimiter: &str) -> Vec<String> {
+use std::collections::HashMap;
+use std::iter::{Copied, Peekable};
+use std::slice::Iter;
+
+/// Split on a literal delimiter. With a `limit`, at most `limit` pieces are
+/// returned and the last one holds the unsplit remainder; `0` means no limit.
+pub fn split(s: &str, delimiter: &str, limit: Option<usize>) -> Vec<String> {
+    let limit = limit.filter(|&n| n > 0).unwrap_or(usize::MAX);
     if delimiter.is_empty() {
-        return s.chars().map(|c| c.to_string()).collect();
+        let mut parts: Vec<String> = Vec::new();
+        for (i, c) in s.char_indices() {
+            if parts.len() + 1 == limit {
+                parts.push(s[i..].to_string());
+                return parts;
+            }
+            parts.push(c.to_string());
+        }
+        return parts;
     }
-    s.split(delimiter).map(|s| s.to_string()).collect()
+    s.splitn(limit, delimiter).map(|s| s.to_string()).collect()
+}
+
+/// Split on matches of a regular expression, with the same `limit` rules as [`split`].
+pub fn split_regex(
+    s: &str,
+    pattern: &str,
+    limit: Option<usize>,
+) -> Result<Vec<String>, StringError> {
+    let re = regex::Regex::new(pattern).map_err(|e| StringError::InvalidPattern(e.to_string()))?;
+    let limit = limit.filter(|&n| n > 0).unwrap_or(usize::MAX);
+    Ok(re.splitn(s, limit).map(|s| s.to_string()).collect())
 }
 
 pub fn trim(s: &str) -> String {
@@ -105,6 +130,29 @@ pub fn capitalize(s: &str) -> String {
     }
 }
 
+pub fn slugify(s: &str) -> String {
+    let ascii = deunicode::deunicode(s).to_lowercase();
+    let mut slug = String::with_capacity(ascii.len());
+    for c in ascii.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    while slug.ends_with('-') {
+        slug.pop();
+    }
+    slug
+}
+
+pub fn truncate(s: &str, length: usize, suffix: Option<&str>) -> String {
+    match s.char_indices().nth(length) {
+        None => s.to_string(),
+        Some((end, _)) => format!("{}{}", &s[..end], suffix.unwrap_or("…")),
+    }
+}
+
 pub fn lines(s: &str) -> Vec<String> {
     s.lines().map(|line| line.to_string()).collect()
 }
@@ -113,15 +161,671 @@ pub fn chars(s: &str) -> Vec<String> {
     s.chars().map(|c| c.to_string()).collect()
 }
 
+/// A value passed to `format`, as seen by a printf-style conversion.
+/// Strings are raw bytes, since Lua strings need not be valid UTF-8.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FormatArg {
+    Int(i64),
+    Float(f64),
+    Str(Vec<u8>),
+    /// Any other Lua value, with its type name and `tostring` representation.
+    Other {
+        type_name: String,
+        display: String,
+    },
+}
+
+impl FormatArg {
+    fn type_name(&self) -> &str {
+        match self {
+            FormatArg::Int(_) | FormatArg::Float(_) => "number",
+            FormatArg::Str(_) => "string",
+            FormatArg::Other { type_name, .. } => type_name,
+        }
+    }
+
+    fn to_display(&self) -> Vec<u8> {
+        match self {
+            FormatArg::Int(i) => i.to_string().into_bytes(),
+            FormatArg::Float(f) => format_lua_float(*f).into_bytes(),
+            FormatArg::Str(s) => s.clone(),
+            FormatArg::Other { display, .. } => display.clone().into_bytes(),
+        }
+    }
+
+    fn as_integer(&self, position: usize) -> Result<i64, StringError> {
+        let number = match self {
+            FormatArg::Int(i) => return Ok(*i),
+            FormatArg::Float(f) => *f,
+            FormatArg::Str(s) => {
+                let s = String::from_utf8_lossy(s);
+                if let Ok(i) = s.trim().parse::<i64>() {
+                    return Ok(i);
+                }
+                s.trim()
+                    .parse::<f64>()
+                    .map_err(|_| bad_argument(position, "number expected, got string"))?
+            }
+            FormatArg::Other { type_name, .. } => {
+                return Err(bad_argument(
+                    position,
+                    &format!("number expected, got {}", type_name),
+                ))
+            }
+        };
+        if number.fract() == 0.0 && number >= i64::MIN as f64 && number < i64::MAX as f64 {
+            Ok(number as i64)
+        } else {
+            Err(bad_argument(
+                position,
+                "number has no integer representation",
+            ))
+        }
+    }
+
+    fn as_float(&self, position: usize) -> Result<f64, StringError> {
+        match self {
+            FormatArg::Int(i) => Ok(*i as f64),
+            FormatArg::Float(f) => Ok(*f),
+            FormatArg::Str(s) => String::from_utf8_lossy(s)
+                .trim()
+                .parse::<f64>()
+                .map_err(|_| bad_argument(position, "number expected, got string")),
+            other => Err(bad_argument(
+                position,
+                &format!("number expected, got {}", other.type_name()),
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct FormatSpec {
+    left: bool,
+    zero: bool,
+    plus: bool,
+    space: bool,
+    alt: bool,
+    width: usize,
+    precision: Option<usize>,
+}
+
+impl FormatSpec {
+    fn sign(&self, negative: bool) -> &'static str {
+        if negative {
+            "-"
+        } else if self.plus {
+            "+"
+        } else if self.space {
+            " "
+        } else {
+            ""
+        }
+    }
+
+    /// Pad to the field width, counted in bytes as C's printf does.
+    fn pad(&self, sign: &str, prefix: &str, body: &[u8], zero_fill: bool) -> Vec<u8> {
+        let fill = self
+            .width
+            .saturating_sub(sign.len() + prefix.len() + body.len());
+        let mut out = Vec::with_capacity(fill + sign.len() + prefix.len() + body.len());
+        if fill > 0 && !self.left && !(self.zero && zero_fill) {
+            out.resize(fill, b' ');
+        }
+        out.extend_from_slice(sign.as_bytes());
+        out.extend_from_slice(prefix.as_bytes());
+        if fill > 0 && !self.left && self.zero && zero_fill {
+            out.resize(out.len() + fill, b'0');
+        }
+        out.extend_from_slice(body);
+        if fill > 0 && self.left {
+            out.resize(out.len() + fill, b' ');
+        }
+        out
+    }
+
+    fn has_modifiers(&self) -> bool {
+        self.left
+            || self.zero
+            || self.plus
+            || self.space
+            || self.alt
+            || self.width > 0
+            || self.precision.is_some()
+    }
+}
+
+fn bad_argument(position: usize, reason: &str) -> StringError {
+    StringError::InvalidFormat(format!(
+        "bad argument #{} to 'format' ({})",
+        position, reason
+    ))
+}
+
+fn invalid_conversion(raw: &[u8]) -> StringError {
+    StringError::InvalidFormat(format!(
+        "invalid conversion '{}' to 'format'",
+        String::from_utf8_lossy(raw)
+    ))
+}
+
+fn read_number(bytes: &mut Peekable<Copied<Iter<u8>>>, raw: &mut Vec<u8>) -> (usize, usize) {
+    let mut value = 0;
+    let mut digits = 0;
+    while let Some(digit) = bytes.next_if(u8::is_ascii_digit) {
+        raw.push(digit);
+        value = value * 10 + (digit - b'0') as usize;
+        digits += 1;
+    }
+    (value, digits)
+}
+
+fn apply_integer_precision(digits: String, precision: Option<usize>, is_zero: bool) -> String {
+    match precision {
+        Some(0) if is_zero => String::new(),
+        Some(p) if digits.len() < p => format!("{}{}", "0".repeat(p - digits.len()), digits),
+        _ => digits,
+    }
+}
+
+fn format_fixed(value: f64, precision: usize, alt: bool) -> String {
+    let mut out = format!("{:.*}", precision, value);
+    if alt && precision == 0 {
+        out.push('.');
+    }
+    out
+}
+
+fn format_exponent(value: f64, precision: usize, alt: bool) -> String {
+    let formatted = format!("{:.*e}", precision, value);
+    let (mantissa, exponent) = formatted.split_once('e').unwrap_or((&formatted, "0"));
+    let exponent: i32 = exponent.parse().unwrap_or(0);
+    let dot = if alt && precision == 0 { "." } else { "" };
+    format!(
+        "{}{}e{}{:02}",
+        mantissa,
+        dot,
+        if exponent < 0 { '-' } else { '+' },
+        exponent.abs()
+    )
+}
+
+fn format_general(value: f64, precision: usize, alt: bool) -> String {
+    let precision = precision.max(1);
+    let exponent = if value == 0.0 {
+        0
+    } else {
+        format!("{:.*e}", precision - 1, value)
+            .split_once('e')
+            .and_then(|(_, e)| e.parse::<i32>().ok())
+            .unwrap_or(0)
+    };
+
+    let formatted = if exponent >= -4 && exponent < precision as i32 {
+        format_fixed(value, (precision as i32 - 1 - exponent) as usize, alt)
+    } else {
+        format_exponent(value, precision - 1, alt)
+    };
+    if alt {
+        return formatted;
+    }
+
+    let (mantissa, exponent) = match formatted.find('e') {
+        Some(index) => formatted.split_at(index),
+        None => (formatted.as_str(), ""),
+    };
+    let mantissa = if mantissa.contains('.') {
+        mantissa.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        mantissa
+    };
+    format!("{}{}", mantissa, exponent)
+}
+
+/// Format a float the way Lua's `tostring` does (`%.14g`, keeping a `.0` on integral values).
+fn format_lua_float(value: f64) -> String {
+    if value.is_nan() {
+        return if value.is_sign_negative() {
+            "-nan"
+        } else {
+            "nan"
+        }
+        .to_string();
+    }
+    if value.is_infinite() {
+        return if value < 0.0 { "-inf" } else { "inf" }.to_string();
+    }
+    let sign = if value.is_sign_negative() { "-" } else { "" };
+    let body = format_general(value.abs(), 14, false);
+    if body.contains(['.', 'e']) {
+        format!("{}{}", sign, body)
+    } else {
+        format!("{}{}.0", sign, body)
+    }
+}
+
+fn quote_string(s: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(s.len() + 2);
+    out.push(b'"');
+    let mut bytes = s.iter().copied().peekable();
+    while let Some(b) = bytes.next() {
+        match b {
+            b'"' | b'\\' | b'\n' => {
+                out.push(b'\\');
+                out.push(b);
+            }
+            b if b.is_ascii_control() => {
+                let escape = if bytes.peek().is_some_and(u8::is_ascii_digit) {
+                    format!("\\{:03}", b)
+                } else {
+                    format!("\\{}", b)
+                };
+                out.extend_from_slice(escape.as_bytes());
+            }
+            b => out.push(b),
+        }
+    }
+    out.push(b'"');
+    out
+}
+
+fn format_quoted(arg: &FormatArg, position: usize) -> Result<Vec<u8>, StringError> {
+    let literal = match arg {
+        FormatArg::Str(s) => return Ok(quote_string(s)),
+        FormatArg::Int(i) if *i == i64::MIN => "0x8000000000000000".to_string(),
+        FormatArg::Int(i) => i.to_string(),
+        FormatArg::Float(f) if f.is_nan() => "(0/0)".to_string(),
+        FormatArg::Float(f) if f.is_infinite() => {
+            if *f < 0.0 { "-1e9999" } else { "1e9999" }.to_string()
+        }
+        FormatArg::Float(f) => format!("{:?}", f),
+        FormatArg::Other {
+            type_name, display, ..
+        } if type_name == "nil" || type_name == "boolean" => display.clone(),
+        _ => return Err(bad_argument(position, "value has no literal form")),
+    };
+    Ok(literal.into_bytes())
+}
+
+/// printf-style formatting compatible with Lua's `string.format`.
+///
+/// Supports `%d %i %o %x %X %c %e %E %f %F %g %G %q %s` and `%%`, with the
+/// `-+ #0` flags and up to two digits of width and precision. Like Lua, it
+/// works on bytes: the format and `%s` arguments may hold any byte sequence,
+/// and `%c` emits a single byte.
+pub fn format(fmt: &[u8], args: &[FormatArg]) -> Result<Vec<u8>, StringError> {
+    let mut out = Vec::with_capacity(fmt.len());
+    let mut bytes = fmt.iter().copied().peekable();
+    let mut next_arg = 0;
+
+    while let Some(b) = bytes.next() {
+        if b != b'%' {
+            out.push(b);
+            continue;
+        }
+        if bytes.next_if_eq(&b'%').is_some() {
+            out.push(b'%');
+            continue;
+        }
+
+        let mut raw = vec![b'%'];
+        let mut spec = FormatSpec::default();
+        while let Some(&flag) = bytes.peek() {
+            match flag {
+                b'-' => spec.left = true,
+                b'0' => spec.zero = true,
+                b'+' => spec.plus = true,
+                b' ' => spec.space = true,
+                b'#' => spec.alt = true,
+                _ => break,
+            }
+            raw.push(flag);
+            bytes.next();
+        }
+        let (width, width_digits) = read_number(&mut bytes, &mut raw);
+        spec.width = width;
+        let mut precision_digits = 0;
+        if bytes.next_if_eq(&b'.').is_some() {
+            raw.push(b'.');
+            let (precision, digits) = read_number(&mut bytes, &mut raw);
+            spec.precision = Some(precision);
+            precision_digits = digits;
+        }
+        let conversion = bytes.next().ok_or_else(|| invalid_conversion(&raw))?;
+        raw.push(conversion);
+        if width_digits > 2 || precision_digits > 2 || !b"dioxXceEfFgGqs".contains(&conversion) {
+            return Err(invalid_conversion(&raw));
+        }
+        let conversion = conversion as char;
+
+        let position = next_arg + 2;
+        let arg = args
+            .get(next_arg)
+            .ok_or_else(|| bad_argument(position, "no value"))?;
+        next_arg += 1;
+
+        let piece = match conversion {
+            'd' | 'i' => {
+                let value = arg.as_integer(position)?;
+                let digits = apply_integer_precision(
+                    value.unsigned_abs().to_string(),
+                    spec.precision,
+                    value == 0,
+                );
+                spec.pad(
+                    spec.sign(value < 0),
+                    "",
+                    digits.as_bytes(),
+                    spec.precision.is_none(),
+                )
+            }
+            'o' | 'x' | 'X' => {
+                let value = arg.as_integer(position)? as u64;
+                let digits = match conversion {
+                    'o' => format!("{:o}", value),
+                    'x' => format!("{:x}", value),
+                    _ => format!("{:X}", value),
+                };
+                let mut digits = apply_integer_precision(digits, spec.precision, value == 0);
+                let prefix = match conversion {
+                    'x' if spec.alt && value != 0 => "0x",
+                    'X' if spec.alt && value != 0 => "0X",
+                    _ => "",
+                };
+                if conversion == 'o' && spec.alt && !digits.starts_with('0') {
+                    digits.insert(0, '0');
+                }
+                spec.pad("", prefix, digits.as_bytes(), spec.precision.is_none())
+            }
+            // Truncated to one byte, like C's `(unsigned char)` conversion
+            'c' => spec.pad("", "", &[arg.as_integer(position)? as u8], false),
+            'e' | 'E' | 'f' | 'F' | 'g' | 'G' => {
+                let value = arg.as_float(position)?;
+                let precision = spec.precision.unwrap_or(6);
+                let body = if value.is_nan() {
+                    "nan".to_string()
+                } else if value.is_infinite() {
+                    "inf".to_string()
+                } else {
+                    match conversion {
+                        'e' | 'E' => format_exponent(value.abs(), precision, spec.alt),
+                        'f' | 'F' => format_fixed(value.abs(), precision, spec.alt),
+                        _ => format_general(value.abs(), precision, spec.alt),
+                    }
+                };
+                let body = if conversion.is_ascii_uppercase() {
+                    body.to_uppercase()
+                } else {
+                    body
+                };
+                let negative = value.is_sign_negative() && !value.is_nan();
+                spec.pad(spec.sign(negative), "", body.as_bytes(), value.is_finite())
+            }
+            'q' => {
+                if spec.has_modifiers() {
+                    return Err(StringError::InvalidFormat(
+                        "specifier '%q' cannot have modifiers".to_string(),
+                    ));
+                }
+                format_quoted(arg, position)?
+            }
+            _ => {
+                let text = arg.to_display();
+                let len = spec.precision.map_or(text.len(), |p| p.min(text.len()));
+                spec.pad("", "", &text[..len], false)
+            }
+        };
+        out.extend_from_slice(&piece);
+    }
+
+    Ok(out)
+}
+
+/// Replace `${name}` placeholders with entries from `values`.
+///
+/// Unknown placeholders are left as-is, or reported as an error when `strict` is set.
+pub fn template(
+    tmpl: &str,
+    values: &HashMap<String, String>,
+    strict: bool,
+) -> Result<String, StringError> {
+    let mut out = String::with_capacity(tmpl.len());
+    let mut rest = tmpl;
+
+    while let Some(start) = rest.find("${") {
+        out.push_str(&rest[..start]);
+        let placeholder = &rest[start..];
+        let Some(end) = placeholder.find('}') else {
+            rest = placeholder;
+            break;
+        };
+
+        let name = placeholder[2..end].trim();
+        match values.get(name) {
+            Some(value) => out.push_str(value),
+            None if strict => return Err(StringError::MissingTemplateKey(name.to_string())),
+            None => out.push_str(&placeholder[..=end]),
+        }
+        rest = &placeholder[end + 1..];
+    }
+
+    out.push_str(rest);
+    Ok(out)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn int(i: i64) -> FormatArg {
+        FormatArg::Int(i)
+    }
+
+    fn float(f: f64) -> FormatArg {
+        FormatArg::Float(f)
+    }
+
+    fn text(s: &str) -> FormatArg {
+        FormatArg::Str(s.as_bytes().to_vec())
+    }
+
+    #[test]
+    fn test_format_integers_with_padding() {
+        assert_eq!(
+            format(b"%5d|%-5d|%05d", &[int(42), int(42), int(42)]).unwrap(),
+            b"   42|42   |00042"
+        );
+        assert_eq!(
+            format(b"%+d % d %.3d", &[int(7), int(7), int(7)]).unwrap(),
+            b"+7  7 007"
+        );
+        assert_eq!(format(b"%d", &[int(-15)]).unwrap(), b"-15");
+        assert_eq!(format(b"%05d", &[int(-15)]).unwrap(), b"-0015");
+        assert_eq!(format(b"%d", &[float(3.0)]).unwrap(), b"3");
+        assert_eq!(format(b"%d", &[text("12")]).unwrap(), b"12");
+    }
+
+    #[test]
+    fn test_format_hex_and_octal() {
+        assert_eq!(
+            format(b"%x %X %o", &[int(255), int(255), int(8)]).unwrap(),
+            b"ff FF 10"
+        );
+        assert_eq!(
+            format(b"%#x %#o %08x", &[int(255), int(8), int(255)]).unwrap(),
+            b"0xff 010 000000ff"
+        );
+        assert_eq!(format(b"%x", &[int(-1)]).unwrap(), b"ffffffffffffffff");
+    }
+
+    #[test]
+    fn test_format_floats_with_precision() {
+        assert_eq!(format(b"%f", &[float(3.14159)]).unwrap(), b"3.141590");
+        assert_eq!(format(b"%.2f", &[float(3.14159)]).unwrap(), b"3.14");
+        assert_eq!(
+            format(b"%8.3f|%-8.1f|", &[float(2.5), float(2.5)]).unwrap(),
+            b"   2.500|2.5     |"
+        );
+        assert_eq!(format(b"%08.2f", &[float(-1.5)]).unwrap(), b"-0001.50");
+        assert_eq!(
+            format(b"%.0f %#.0f", &[float(2.0), float(2.0)]).unwrap(),
+            b"2 2."
+        );
+        assert_eq!(
+            format(b"%e %.2E", &[float(1234.5), float(0.000123)]).unwrap(),
+            b"1.234500e+03 1.23E-04"
+        );
+        assert_eq!(
+            format(b"%g %g %g", &[float(100000.0), float(1e6), float(0.0001)]).unwrap(),
+            b"100000 1e+06 0.0001"
+        );
+        assert_eq!(
+            format(b"%.3g %g", &[float(3.14159), float(0.5)]).unwrap(),
+            b"3.14 0.5"
+        );
+        assert_eq!(format(b"%f", &[float(f64::INFINITY)]).unwrap(), b"inf");
+    }
+
+    #[test]
+    fn test_format_strings() {
+        assert_eq!(
+            format(
+                b"[%s] [%10s] [%-6s] [%.3s]",
+                &[text("hi"), text("right"), text("left"), text("truncate")]
+            )
+            .unwrap(),
+            b"[hi] [     right] [left  ] [tru]"
+        );
+        assert_eq!(
+            format(b"%s %s %s", &[int(1), float(1.0), float(0.1)]).unwrap(),
+            b"1 1.0 0.1"
+        );
+        let nil = FormatArg::Other {
+            type_name: "nil".to_string(),
+            display: "nil".to_string(),
+        };
+        assert_eq!(format(b"%s", &[nil]).unwrap(), b"nil");
+        assert_eq!(format(b"100%% %c", &[int(65)]).unwrap(), b"100% A");
+        assert_eq!(
+            format(b"%q", &[text("say \"hi\"\n\0")]).unwrap(),
+            b"\"say \\\"hi\\\"\\\n\\0\""
+        );
+    }
+
+    #[test]
+    fn test_format_is_binary_safe() {
+        assert_eq!(
+            format(b"%c%c%c", &[int(200), int(255), int(0)]).unwrap(),
+            [200, 255, 0]
+        );
+        assert_eq!(format(b"%c", &[int(256 + 65)]).unwrap(), b"A");
+        assert_eq!(
+            format(
+                b"\xff%s|%3s|%.1s",
+                &[
+                    FormatArg::Str(vec![0xe9, 0]),
+                    text("\u{e9}"),
+                    text("\u{e9}")
+                ]
+            )
+            .unwrap(),
+            b"\xff\xe9\0| \xc3\xa9|\xc3"
+        );
+        assert_eq!(
+            format(b"%q", &[FormatArg::Str(vec![b'a', 0xe9, 1])]).unwrap(),
+            b"\"a\xe9\\1\""
+        );
+    }
+
+    #[test]
+    fn test_format_errors() {
+        assert!(format(b"%d", &[])
+            .unwrap_err()
+            .to_string()
+            .contains("bad argument #2 to 'format' (no value)"));
+        assert!(format(b"%d", &[text("abc")])
+            .unwrap_err()
+            .to_string()
+            .contains("number expected, got string"));
+        assert!(format(b"%d", &[float(1.5)])
+            .unwrap_err()
+            .to_string()
+            .contains("no integer representation"));
+        assert!(format(b"%y", &[int(1)])
+            .unwrap_err()
+            .to_string()
+            .contains("invalid conversion '%y'"));
+        assert!(format(b"%100d", &[int(1)]).is_err());
+    }
+
+    #[test]
+    fn test_template_substitution() {
+        let mut values = HashMap::new();
+        values.insert("name".to_string(), "Ada".to_string());
+        values.insert("count".to_string(), "3".to_string());
+
+        assert_eq!(
+            template(
+                "Hello ${name}, you have ${ count } messages",
+                &values,
+                false
+            )
+            .unwrap(),
+            "Hello Ada, you have 3 messages"
+        );
+        assert_eq!(
+            template("no placeholders", &values, false).unwrap(),
+            "no placeholders"
+        );
+        assert_eq!(
+            template("unclosed ${name", &values, false).unwrap(),
+            "unclosed ${name"
+        );
+    }
+
+    #[test]
+    fn test_template_missing_key() {
+        let mut values = HashMap::new();
+        values.insert("name".to_string(), "Ada".to_string());
+
+        assert_eq!(
+            template("${name} is ${age}", &values, false).unwrap(),
+            "Ada is ${age}"
+        );
+        let err = template("${name} is ${age}", &values, true).unwrap_err();
+        assert!(matches!(err, StringError::MissingTemplateKey(ref key) if key == "age"));
+    }
+
     #[test]
     fn test_split() {
-        assert_eq!(split("a,b,c", ","), vec!["a", "b", "c"]);
-        assert_eq!(split("hello", ""), vec!["h", "e", "l", "l", "o"]);
-        assert_eq!(split("one", ","), vec!["one"]);
+        assert_eq!(split("a,b,c", ",", None), vec!["a", "b", "c"]);
+        assert_eq!(split("hello", "", None), vec!["h", "e", "l", "l", "o"]);
+        assert_eq!(split("one", ",", None), vec!["one"]);
+    }
+
+    #[test]
+    fn test_split_with_limit() {
+        assert_eq!(split("a,b,c,d", ",", Some(2)), vec!["a", "b,c,d"]);
+        assert_eq!(split("a,b,c,d", ",", Some(1)), vec!["a,b,c,d"]);
+        assert_eq!(split("a,b", ",", Some(5)), vec!["a", "b"]);
+        assert_eq!(split("a,b,c", ",", Some(0)), vec!["a", "b", "c"]);
+        assert_eq!(split("héllo", "", Some(3)), vec!["h", "é", "llo"]);
+    }
+
+    #[test]
+    fn test_split_regex() {
+        assert_eq!(
+            split_regex("one  two\tthree\n four", r"\s+", None).unwrap(),
+            vec!["one", "two", "three", "four"]
+        );
+        assert_eq!(
+            split_regex("k1=v1; k2=v2;k3=v3", r";\s*", Some(2)).unwrap(),
+            vec!["k1=v1", "k2=v2;k3=v3"]
+        );
+        assert!(matches!(
+            split_regex("abc", "(", None),
+            Err(StringError::InvalidPattern(_))
+        ));
     }
 
     #[test]
@@ -221,6 +925,28 @@ mod tests {
         assert_eq!(capitalize("h"), "H");
     }
 
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("Héllo Wörld"), "hello-world");
+        assert_eq!(
+            slugify("  Rust & Lua: 2024 edition!  "),
+            "rust-lua-2024-edition"
+        );
+        assert_eq!(slugify("--already-slugged--"), "already-slugged");
+        assert_eq!(slugify("Ærøskøbing straße"), "aeroskobing-strasse");
+        assert_eq!(slugify("!!!"), "");
+    }
+
+    #[test]
+    fn test_truncate() {
+        assert_eq!(truncate("hello world", 5, None), "hello…");
+        assert_eq!(truncate("hello world", 5, Some("...")), "hello...");
+        assert_eq!(truncate("short", 10, None), "short");
+        assert_eq!(truncate("exact", 5, None), "exact");
+        assert_eq!(truncate("héllo wörld", 2, Some("")), "hé");
+        assert_eq!(truncate("🌍🌎🌏", 1, None), "🌍…");
+    }
+
     #[test]
     fn test_lines() {
         assert_eq!(lines("a\nb\nc"), vec!["a", "b", "c"]);
@@ -0,0 +1,39 @@
+use crate::error::StructuredError;
+use std::fmt;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum TemplateError {
+    Parse { line: usize, message: String },
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TemplateError::Parse { line, message } => {
+                write!(f, "Template error at line {}: {}", line, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+impl TemplateError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            TemplateError::Parse { .. } => "ERR_PARSE",
+        }
+    }
+}
+
+impl From<TemplateError> for StructuredError {
+    fn from(err: TemplateError) -> Self {
+        StructuredError::new("template", err.code(), err.to_string())
+    }
+}
+
+impl From<TemplateError> for crate::error::HypeError {
+    fn from(err: TemplateError) -> Self {
+        crate::error::HypeError::Execution(err.to_string())
+    }
+}
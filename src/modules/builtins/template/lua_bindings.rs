@@ -0,0 +1,46 @@
+use super::operations::{parse, render};
+use crate::lua::error::{structured_error, wrap_module_errors};
+use crate::lua::require::lua_to_json;
+use mlua::{Lua, Result as LuaResult, Table, Value};
+use serde_json::Value as JsonValue;
+
+pub fn create_template_module(lua: &Lua) -> LuaResult<Table<'_>> {
+    let template = lua.create_table()?;
+
+    let render_fn = lua.create_function(|lua, (source, data): (String, Option<Value>)| {
+        let nodes = parse(&source).map_err(structured_error)?;
+        let data = match data {
+            None | Some(Value::Nil) => JsonValue::Object(Default::default()),
+            Some(value) => lua_to_json(lua, value)?,
+        };
+        Ok(render(&nodes, &data))
+    })?;
+    template.set("render", render_fn)?;
+
+    wrap_module_errors(lua, &template)?;
+    Ok(template)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_from_lua_tables() {
+        let lua = Lua::new();
+        let template = create_template_module(&lua).unwrap();
+        lua.globals().set("template", template).unwrap();
+
+        let output: String = lua
+            .load(r#"return template.render("{{#xs}}{{.}},{{/xs}}{{n}}", {xs = {1, 2}, n = 1.5})"#)
+            .eval()
+            .unwrap();
+        assert_eq!(output, "1,2,1.5");
+
+        let code: String = lua
+            .load(r#"local ok, err = pcall(template.render, "{{#a}}") return err.code"#)
+            .eval()
+            .unwrap();
+        assert_eq!(code, "ERR_PARSE");
+    }
+}
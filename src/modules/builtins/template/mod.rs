@@ -0,0 +1,61 @@
+pub mod error;
+pub mod lua_bindings;
+pub mod operations;
+
+use serde_json::{json, Value as JsonValue};
+
+use super::BuiltinModule;
+use crate::error::HypeError;
+
+pub use lua_bindings::create_template_module;
+
+pub struct TemplateModule;
+
+impl TemplateModule {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for TemplateModule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BuiltinModule for TemplateModule {
+    fn name(&self) -> &str {
+        "template"
+    }
+
+    fn exports(&self) -> std::result::Result<JsonValue, HypeError> {
+        Ok(json!({
+            "__id": "template",
+            "__desc": "Mustache-style templates with variables, sections and inverted sections",
+            "render": {
+                "__fn": "render",
+                "__desc": "Render a template against a data table; {{var}} is HTML-escaped, {{{var}}} is raw",
+                "__signature": "render(template: string, data?: table) -> string"
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_template_module_name() {
+        let module = TemplateModule::new();
+        assert_eq!(module.name(), "template");
+    }
+
+    #[test]
+    fn test_template_module_exports() {
+        let module = TemplateModule::new();
+        let exports = module.exports().unwrap();
+        assert_eq!(exports["__id"], "template");
+        assert!(exports.get("render").is_some());
+    }
+}
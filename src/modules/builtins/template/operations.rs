@@ -0,0 +1,372 @@
+use super::error::TemplateError;
+use serde_json::Value as JsonValue;
+
+pub type Result<T> = std::result::Result<T, TemplateError>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Node {
+    Text(String),
+    /// `{{name}}`, or `{{{name}}}` / `{{& name}}` when `escape` is false.
+    Variable {
+        name: String,
+        escape: bool,
+    },
+    /// `{{#name}}...{{/name}}`, or `{{^name}}...{{/name}}` when `inverted`.
+    Section {
+        name: String,
+        inverted: bool,
+        children: Vec<Node>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TagKind {
+    Variable,
+    Raw,
+    Open,
+    Inverted,
+    Close,
+    Comment,
+}
+
+impl TagKind {
+    /// Tags that produce no output and may sit alone on a line, which is
+    /// then removed along with its newline.
+    fn is_standalone_kind(self) -> bool {
+        matches!(
+            self,
+            TagKind::Open | TagKind::Inverted | TagKind::Close | TagKind::Comment
+        )
+    }
+}
+
+#[derive(Debug)]
+enum Token {
+    Text(String),
+    Tag {
+        kind: TagKind,
+        name: String,
+        line: usize,
+    },
+}
+
+/// Parse a mustache template: variables, raw variables, sections, inverted
+/// sections and comments. Partials and delimiter changes are not supported.
+pub fn parse(template: &str) -> Result<Vec<Node>> {
+    let mut tokens = tokenize(template)?;
+    strip_standalone_lines(&mut tokens);
+    build_tree(tokens)
+}
+
+fn tokenize(template: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut rest = template;
+    let mut line = 1;
+
+    while let Some(start) = rest.find("{{") {
+        if start > 0 {
+            tokens.push(Token::Text(rest[..start].to_string()));
+        }
+        line += rest[..start].matches('\n').count();
+        let after = &rest[start + 2..];
+        let error = |message: String| TemplateError::Parse { line, message };
+
+        let (kind, body, consumed) = if let Some(raw) = after.strip_prefix('{') {
+            let end = raw
+                .find("}}}")
+                .ok_or_else(|| error("'{{{' is not closed with '}}}'".to_string()))?;
+            (TagKind::Raw, &raw[..end], 1 + end + 3)
+        } else {
+            let end = after
+                .find("}}")
+                .ok_or_else(|| error("'{{' is not closed with '}}'".to_string()))?;
+            let body = &after[..end];
+            let (kind, body) = match body.chars().next() {
+                Some('#') => (TagKind::Open, &body[1..]),
+                Some('^') => (TagKind::Inverted, &body[1..]),
+                Some('/') => (TagKind::Close, &body[1..]),
+                Some('!') => (TagKind::Comment, &body[1..]),
+                Some('&') => (TagKind::Raw, &body[1..]),
+                Some('>') => return Err(error("partials ({{> name}}) are not supported".into())),
+                Some('=') => {
+                    return Err(error(
+                        "changing delimiters ({{=<% %>=}}) is not supported".into(),
+                    ))
+                }
+                _ => (TagKind::Variable, body),
+            };
+            (kind, body, end + 2)
+        };
+
+        let name = body.trim();
+        if name.is_empty() && kind != TagKind::Comment {
+            return Err(error("tag has no name".to_string()));
+        }
+        tokens.push(Token::Tag {
+            kind,
+            name: name.to_string(),
+            line,
+        });
+        line += body.matches('\n').count();
+        rest = &after[consumed..];
+    }
+    if !rest.is_empty() {
+        tokens.push(Token::Text(rest.to_string()));
+    }
+    Ok(tokens)
+}
+
+/// Drop the line around a section or comment tag that is alone on it, so
+/// block tags do not leave blank lines behind.
+fn strip_standalone_lines(tokens: &mut [Token]) {
+    // Decide every tag against the original text first, since a line break
+    // between two block tags is shared by both of them
+    let standalone: Vec<usize> = (0..tokens.len())
+        .filter(|&i| is_standalone(tokens, i))
+        .collect();
+
+    for i in standalone {
+        if let Some(Token::Text(text)) = i.checked_sub(1).map(|p| &mut tokens[p]) {
+            let keep = text.rfind('\n').map_or(0, |n| n + 1);
+            text.truncate(keep);
+        }
+        if let Some(Token::Text(text)) = tokens.get_mut(i + 1) {
+            let skip = text.find('\n').map_or(text.len(), |n| n + 1);
+            text.drain(..skip);
+        }
+    }
+}
+
+fn is_standalone(tokens: &[Token], i: usize) -> bool {
+    match &tokens[i] {
+        Token::Tag { kind, .. } if kind.is_standalone_kind() => {}
+        _ => return false,
+    }
+    let starts_line = match i.checked_sub(1).map(|p| &tokens[p]) {
+        None => true,
+        Some(Token::Text(text)) => {
+            let tail = text.rsplit('\n').next().unwrap_or_default();
+            tail.trim().is_empty() && (text.contains('\n') || i == 1)
+        }
+        Some(Token::Tag { .. }) => false,
+    };
+    let ends_line = match tokens.get(i + 1) {
+        None => true,
+        Some(Token::Text(text)) => {
+            let head = text.split('\n').next().unwrap_or_default();
+            head.trim().is_empty() && (text.contains('\n') || i + 2 == tokens.len())
+        }
+        Some(Token::Tag { .. }) => false,
+    };
+    starts_line && ends_line
+}
+
+fn build_tree(tokens: Vec<Token>) -> Result<Vec<Node>> {
+    // Each open section: its name, whether inverted, the line it opened on,
+    // and the nodes collected before it
+    let mut open: Vec<(String, bool, usize, Vec<Node>)> = Vec::new();
+    let mut nodes = Vec::new();
+
+    for token in tokens {
+        match token {
+            Token::Text(text) if text.is_empty() => {}
+            Token::Text(text) => nodes.push(Node::Text(text)),
+            Token::Tag { kind, name, line } => match kind {
+                TagKind::Variable | TagKind::Raw => nodes.push(Node::Variable {
+                    name,
+                    escape: kind == TagKind::Variable,
+                }),
+                TagKind::Comment => {}
+                TagKind::Open | TagKind::Inverted => {
+                    let outer = std::mem::take(&mut nodes);
+                    open.push((name, kind == TagKind::Inverted, line, outer));
+                }
+                TagKind::Close => {
+                    let Some((open_name, inverted, _, outer)) = open.pop() else {
+                        return Err(TemplateError::Parse {
+                            line,
+                            message: format!("'{{{{/{}}}}}' has no matching opening tag", name),
+                        });
+                    };
+                    if open_name != name {
+                        return Err(TemplateError::Parse {
+                            line,
+                            message: format!("'{{{{/{}}}}}' closes '{{{{#{}}}}}'", name, open_name),
+                        });
+                    }
+                    let children = std::mem::replace(&mut nodes, outer);
+                    nodes.push(Node::Section {
+                        name,
+                        inverted,
+                        children,
+                    });
+                }
+            },
+        }
+    }
+
+    if let Some((name, _, line, _)) = open.pop() {
+        return Err(TemplateError::Parse {
+            line,
+            message: format!("section '{}' is never closed", name),
+        });
+    }
+    Ok(nodes)
+}
+
+/// Render parsed nodes against `data`. Missing names render as nothing.
+pub fn render(nodes: &[Node], data: &JsonValue) -> String {
+    let mut output = String::new();
+    render_nodes(nodes, &mut vec![data], &mut output);
+    output
+}
+
+fn render_nodes(nodes: &[Node], stack: &mut Vec<&JsonValue>, output: &mut String) {
+    for node in nodes {
+        match node {
+            Node::Text(text) => output.push_str(text),
+            Node::Variable { name, escape } => {
+                let text = lookup(stack, name).map(display).unwrap_or_default();
+                if *escape {
+                    output.push_str(&escape_html(&text));
+                } else {
+                    output.push_str(&text);
+                }
+            }
+            Node::Section {
+                name,
+                inverted,
+                children,
+            } => {
+                let value = lookup(stack, name);
+                let truthy = value.is_some_and(is_truthy);
+                if *inverted {
+                    if !truthy {
+                        render_nodes(children, stack, output);
+                    }
+                    continue;
+                }
+                let Some(value) = value.filter(|v| is_truthy(v)) else {
+                    continue;
+                };
+                let items: Vec<&JsonValue> = match value {
+                    JsonValue::Array(items) => items.iter().collect(),
+                    other => vec![other],
+                };
+                for item in items {
+                    stack.push(item);
+                    render_nodes(children, stack, output);
+                    stack.pop();
+                }
+            }
+        }
+    }
+}
+
+/// Find `name` (`.`, `key` or `dotted.key`) starting from the innermost context.
+fn lookup<'a>(stack: &[&'a JsonValue], name: &str) -> Option<&'a JsonValue> {
+    if name == "." {
+        return stack.last().copied();
+    }
+    let mut parts = name.split('.');
+    let first = parts.next()?;
+    let mut value = stack
+        .iter()
+        .rev()
+        .find_map(|context| context.as_object().and_then(|map| map.get(first)))?;
+    for part in parts {
+        value = value.as_object()?.get(part)?;
+    }
+    Some(value)
+}
+
+/// Lua tables cannot tell an empty array from an empty object, so both are false.
+fn is_truthy(value: &JsonValue) -> bool {
+    match value {
+        JsonValue::Null | JsonValue::Bool(false) => false,
+        JsonValue::Array(items) => !items.is_empty(),
+        JsonValue::Object(map) => !map.is_empty(),
+        _ => true,
+    }
+}
+
+fn display(value: &JsonValue) -> String {
+    match value {
+        JsonValue::String(s) => s.clone(),
+        JsonValue::Number(n) => n.to_string(),
+        JsonValue::Bool(b) => b.to_string(),
+        JsonValue::Null | JsonValue::Array(_) | JsonValue::Object(_) => String::new(),
+    }
+}
+
+pub fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn render_str(template: &str, data: JsonValue) -> String {
+        render(&parse(template).unwrap(), &data)
+    }
+
+    #[test]
+    fn test_variables_escape_and_raw() {
+        let data = json!({"name": "<b>Tom & Co</b>", "user": {"age": 42}});
+        assert_eq!(
+            render_str(
+                "{{name}}|{{{name}}}|{{& name}}|{{user.age}}|{{missing}}",
+                data
+            ),
+            "&lt;b&gt;Tom &amp; Co&lt;/b&gt;|<b>Tom & Co</b>|<b>Tom & Co</b>|42|"
+        );
+    }
+
+    #[test]
+    fn test_sections_lists_and_inverted() {
+        let data = json!({"items": [{"name": "a"}, {"name": "b"}], "title": "List", "empty": {}});
+        assert_eq!(
+            render_str(
+                "{{#items}}{{name}}-{{title}};{{/items}}{{^empty}}none{{/empty}}{{#tags}}x{{/tags}}",
+                data
+            ),
+            "a-List;b-List;none"
+        );
+        assert_eq!(
+            render_str("{{#list}}[{{.}}]{{/list}}", json!({"list": [1, 2]})),
+            "[1][2]"
+        );
+    }
+
+    #[test]
+    fn test_standalone_tags_remove_their_lines() {
+        let template = "servers:\n{{#servers}}\n  - {{host}}\n{{/servers}}\n{{! done }}\nend\n";
+        let data = json!({"servers": [{"host": "a"}, {"host": "b"}]});
+        assert_eq!(render_str(template, data), "servers:\n  - a\n  - b\nend\n");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = parse("ok\n{{#a}}\n{{/b}}").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Template error at line 3: '{{/b}}' closes '{{#a}}'"
+        );
+        assert!(parse("{{#open}}").is_err());
+        assert!(parse("{{name").is_err());
+        assert!(parse("{{> partial}}").is_err());
+    }
+}
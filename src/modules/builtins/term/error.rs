@@ -0,0 +1,54 @@
+use crate::error::StructuredError;
+use std::fmt;
+
+#[derive(Debug)]
+pub enum TermError {
+    InvalidStream(String),
+    /// stdin closed before a prompt was answered.
+    Eof,
+    Io(String),
+}
+
+impl fmt::Display for TermError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TermError::InvalidStream(name) => write!(
+                f,
+                "Invalid stream '{}' (expected stdout, stderr or stdin)",
+                name
+            ),
+            TermError::Eof => write!(f, "No answer: stdin is closed"),
+            TermError::Io(msg) => write!(f, "Terminal I/O error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for TermError {}
+
+impl TermError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            TermError::InvalidStream(_) => "ERR_INVALID_STREAM",
+            TermError::Eof => "ERR_EOF",
+            TermError::Io(_) => "ERR_IO",
+        }
+    }
+}
+
+impl From<std::io::Error> for TermError {
+    fn from(err: std::io::Error) -> Self {
+        TermError::Io(err.to_string())
+    }
+}
+
+impl From<TermError> for StructuredError {
+    fn from(err: TermError) -> Self {
+        StructuredError::new("term", err.code(), err.to_string())
+    }
+}
+
+impl From<TermError> for crate::error::HypeError {
+    fn from(err: TermError) -> Self {
+        crate::error::HypeError::Execution(err.to_string())
+    }
+}
@@ -0,0 +1,202 @@
+use super::operations::*;
+use super::progress::{ProgressBar, Spinner, DEFAULT_WIDTH};
+use super::prompt;
+use crate::lua::error::{structured_error, wrap_module_errors};
+use mlua::{
+    Lua, Result as LuaResult, String as LuaString, Table, UserData, UserDataFields, UserDataMethods,
+};
+use std::cell::Cell;
+use std::io;
+use std::rc::Rc;
+
+pub fn create_term_module(lua: &Lua) -> LuaResult<Table<'_>> {
+    let term = lua.create_table()?;
+    // `None` until the script calls `setColors`; the environment is checked
+    // on every call so `NO_COLOR` set later still applies.
+    let forced = Rc::new(Cell::new(None));
+
+    for style in STYLES {
+        let forced = forced.clone();
+        let style_fn = lua.create_function(move |lua, text: LuaString| {
+            if detect_colors(forced.get()) {
+                lua.create_string(style.apply(text.as_bytes()))
+            } else {
+                Ok(text)
+            }
+        })?;
+        term.set(style.name, style_fn)?;
+    }
+
+    let is_tty_fn = lua.create_function(|_, stream: Option<String>| {
+        let stream =
+            Stream::from_name(stream.as_deref().unwrap_or("stdout")).map_err(structured_error)?;
+        Ok(stream.is_terminal())
+    })?;
+    term.set("isTTY", is_tty_fn)?;
+
+    let enabled = forced.clone();
+    let colors_enabled_fn = lua.create_function(move |_, ()| Ok(detect_colors(enabled.get())))?;
+    term.set("colorsEnabled", colors_enabled_fn)?;
+
+    let set_colors_fn = lua.create_function(move |_, enabled: Option<bool>| {
+        forced.set(enabled);
+        Ok(())
+    })?;
+    term.set("setColors", set_colors_fn)?;
+
+    // Questions go to stderr so they show even when stdout is piped or captured
+    let prompt_fn = lua.create_function(|_, (question, default): (String, Option<String>)| {
+        prompt::prompt(
+            &mut io::stdin().lock(),
+            &mut io::stderr(),
+            &question,
+            default.as_deref(),
+        )
+        .map_err(structured_error)
+    })?;
+    term.set("prompt", prompt_fn)?;
+
+    let confirm_fn = lua.create_function(|_, (question, default): (String, Option<bool>)| {
+        prompt::confirm(
+            &mut io::stdin().lock(),
+            &mut io::stderr(),
+            &question,
+            default,
+        )
+        .map_err(structured_error)
+    })?;
+    term.set("confirm", confirm_fn)?;
+
+    let password_fn = lua.create_function(|_, question: String| {
+        prompt::password(&question).map_err(structured_error)
+    })?;
+    term.set("password", password_fn)?;
+
+    // Progress goes to stderr, and only when it is a terminal, so logs and
+    // piped output are not filled with redraws
+    let progress_bar_fn = lua.create_function(|_, (total, options): (u64, Option<Table>)| {
+        let (width, label) = match options {
+            Some(options) => (
+                options.get::<_, Option<usize>>("width")?,
+                options.get::<_, Option<String>>("label")?,
+            ),
+            None => (None, None),
+        };
+        Ok(ProgressBarHandle(ProgressBar::new(
+            total,
+            width.unwrap_or(DEFAULT_WIDTH),
+            label.unwrap_or_default(),
+            Stream::Stderr.is_terminal(),
+        )))
+    })?;
+    term.set("progressBar", progress_bar_fn)?;
+
+    let spinner_fn = lua.create_function(|_, label: Option<String>| {
+        Ok(SpinnerHandle(Spinner::new(
+            label.unwrap_or_default(),
+            Stream::Stderr.is_terminal(),
+        )))
+    })?;
+    term.set("spinner", spinner_fn)?;
+
+    wrap_module_errors(lua, &term)?;
+    Ok(term)
+}
+
+struct ProgressBarHandle(ProgressBar);
+
+impl UserData for ProgressBarHandle {
+    fn add_fields<'lua, F: UserDataFields<'lua, Self>>(fields: &mut F) {
+        fields.add_field_method_get("total", |_, this| Ok(this.0.total()));
+        fields.add_field_method_get("current", |_, this| Ok(this.0.current()));
+        fields.add_field_method_get("percent", |_, this| Ok(this.0.percent()));
+        fields.add_field_method_get("finished", |_, this| Ok(this.0.is_finished()));
+    }
+
+    fn add_methods<'lua, M: UserDataMethods<'lua, Self>>(methods: &mut M) {
+        methods.add_method_mut("update", |_, this, current: u64| {
+            this.0
+                .update(current, &mut io::stderr())
+                .map_err(mlua::Error::external)
+        });
+
+        methods.add_method_mut("finish", |_, this, ()| {
+            this.0
+                .finish(&mut io::stderr())
+                .map_err(mlua::Error::external)
+        });
+    }
+}
+
+struct SpinnerHandle(Spinner);
+
+impl UserData for SpinnerHandle {
+    fn add_fields<'lua, F: UserDataFields<'lua, Self>>(fields: &mut F) {
+        fields.add_field_method_get("stopped", |_, this| Ok(this.0.is_stopped()));
+    }
+
+    fn add_methods<'lua, M: UserDataMethods<'lua, Self>>(methods: &mut M) {
+        methods.add_method_mut("tick", |_, this, ()| {
+            this.0
+                .tick(&mut io::stderr())
+                .map_err(mlua::Error::external)
+        });
+
+        methods.add_method_mut("stop", |_, this, message: Option<String>| {
+            this.0
+                .stop(message.as_deref(), &mut io::stderr())
+                .map_err(mlua::Error::external)
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_term_module() {
+        let lua = Lua::new();
+        let term = create_term_module(&lua).unwrap();
+        for name in [
+            "red",
+            "green",
+            "yellow",
+            "blue",
+            "bold",
+            "dim",
+            "isTTY",
+            "prompt",
+            "confirm",
+            "password",
+            "progressBar",
+            "spinner",
+        ] {
+            assert!(term.contains_key(name).unwrap(), "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_forced_colors() {
+        let lua = Lua::new();
+        lua.globals()
+            .set("term", create_term_module(&lua).unwrap())
+            .unwrap();
+        lua.load(
+            r#"
+term.setColors(true)
+assert(term.colorsEnabled())
+assert(term.bold(term.red("hi")) == "\27[1m\27[31mhi\27[39m\27[22m")
+assert(term.dim(42) == "\27[2m42\27[22m")
+
+term.setColors(false)
+assert(term.green("plain") == "plain")
+
+local ok, err = pcall(term.isTTY, "printer")
+assert(not ok and err.code == "ERR_INVALID_STREAM", tostring(err))
+"#,
+        )
+        .exec()
+        .unwrap();
+    }
+}
@@ -0,0 +1,107 @@
+pub mod error;
+pub mod lua_bindings;
+pub mod operations;
+pub mod progress;
+pub mod prompt;
+
+use serde_json::{json, Value as JsonValue};
+
+use super::BuiltinModule;
+use crate::error::HypeError;
+
+pub use lua_bindings::create_term_module;
+
+pub struct TermModule;
+
+impl TermModule {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for TermModule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BuiltinModule for TermModule {
+    fn name(&self) -> &str {
+        "term"
+    }
+
+    fn exports(&self) -> std::result::Result<JsonValue, HypeError> {
+        let mut exports = json!({
+            "__id": "term",
+            "__desc": "ANSI text styles that respect NO_COLOR, TTY detection, prompts and progress output",
+            "isTTY": {
+                "__fn": "isTTY",
+                "__desc": "Whether a stream is a terminal",
+                "__signature": "isTTY(stream?: \"stdout\" | \"stderr\" | \"stdin\") -> boolean"
+            },
+            "colorsEnabled": {
+                "__fn": "colorsEnabled",
+                "__desc": "Whether styles currently add escape codes",
+                "__signature": "colorsEnabled() -> boolean"
+            },
+            "setColors": {
+                "__fn": "setColors",
+                "__desc": "Force styles on or off; nil restores detection",
+                "__signature": "setColors(enabled?: boolean)"
+            },
+            "prompt": {
+                "__fn": "prompt",
+                "__desc": "Ask for a line of text on stdin",
+                "__signature": "prompt(question: string, default?: string) -> string"
+            },
+            "confirm": {
+                "__fn": "confirm",
+                "__desc": "Ask a yes/no question",
+                "__signature": "confirm(question: string, default?: boolean) -> boolean"
+            },
+            "password": {
+                "__fn": "password",
+                "__desc": "Ask for a secret without echoing it",
+                "__signature": "password(question: string) -> string"
+            },
+            "progressBar": {
+                "__fn": "progressBar",
+                "__desc": "A [####----] 50% bar on stderr with :update(current) and :finish()",
+                "__signature": "progressBar(total: number, options?: {width?: number, label?: string}) -> ProgressBar"
+            },
+            "spinner": {
+                "__fn": "spinner",
+                "__desc": "A spinner on stderr with :tick() and :stop(message?)",
+                "__signature": "spinner(label?: string) -> Spinner"
+            }
+        });
+        for style in operations::STYLES {
+            exports[style.name] = json!({
+                "__fn": style.name,
+                "__desc": format!("Wrap text in {}", style.name),
+                "__signature": format!("{}(text: string) -> string", style.name)
+            });
+        }
+        Ok(exports)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_term_module_name() {
+        let module = TermModule::new();
+        assert_eq!(module.name(), "term");
+    }
+
+    #[test]
+    fn test_term_module_exports() {
+        let module = TermModule::new();
+        let exports = module.exports().unwrap();
+        for name in ["red", "bold", "dim", "isTTY", "setColors"] {
+            assert!(exports.get(name).is_some(), "{}", name);
+        }
+    }
+}
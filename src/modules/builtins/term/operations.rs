@@ -0,0 +1,150 @@
+use super::error::TermError;
+use std::io::{self, IsTerminal};
+
+pub type Result<T> = std::result::Result<T, TermError>;
+
+/// A text style as its SGR open and close codes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Style {
+    pub name: &'static str,
+    open: u8,
+    close: u8,
+}
+
+const fn style(name: &'static str, open: u8, close: u8) -> Style {
+    Style { name, open, close }
+}
+
+pub const STYLES: [Style; 11] = [
+    style("bold", 1, 22),
+    style("dim", 2, 22),
+    style("italic", 3, 23),
+    style("underline", 4, 24),
+    style("red", 31, 39),
+    style("green", 32, 39),
+    style("yellow", 33, 39),
+    style("blue", 34, 39),
+    style("magenta", 35, 39),
+    style("cyan", 36, 39),
+    style("gray", 90, 39),
+];
+
+impl Style {
+    /// Wrap `text` in this style. Close codes inside `text`, left by nested
+    /// styles of the same kind, reopen this style so it carries on after them.
+    pub fn apply(&self, text: &[u8]) -> Vec<u8> {
+        let open = format!("\x1b[{}m", self.open);
+        let close = format!("\x1b[{}m", self.close);
+        let mut styled = open.clone().into_bytes();
+        let mut rest = text;
+        while let Some(at) = find(rest, close.as_bytes()) {
+            let end = at + close.len();
+            styled.extend_from_slice(&rest[..end]);
+            styled.extend_from_slice(open.as_bytes());
+            rest = &rest[end..];
+        }
+        styled.extend_from_slice(rest);
+        styled.extend_from_slice(close.as_bytes());
+        styled
+    }
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stream {
+    Stdout,
+    Stderr,
+    Stdin,
+}
+
+impl Stream {
+    pub fn from_name(name: &str) -> Result<Self> {
+        match name {
+            "stdout" => Ok(Stream::Stdout),
+            "stderr" => Ok(Stream::Stderr),
+            "stdin" => Ok(Stream::Stdin),
+            other => Err(TermError::InvalidStream(other.to_string())),
+        }
+    }
+
+    pub fn is_terminal(&self) -> bool {
+        match self {
+            Stream::Stdout => io::stdout().is_terminal(),
+            Stream::Stderr => io::stderr().is_terminal(),
+            Stream::Stdin => io::stdin().is_terminal(),
+        }
+    }
+}
+
+/// Whether styles produce escape codes. A script's explicit choice wins;
+/// otherwise a non-empty `NO_COLOR` turns them off (https://no-color.org),
+/// a `FORCE_COLOR` other than `0` turns them on, and failing both they are
+/// on only when stdout is a terminal.
+pub fn colors_enabled(
+    forced: Option<bool>,
+    no_color: Option<&str>,
+    force_color: Option<&str>,
+    stdout_is_tty: bool,
+) -> bool {
+    if let Some(forced) = forced {
+        return forced;
+    }
+    if no_color.is_some_and(|value| !value.is_empty()) {
+        return false;
+    }
+    if let Some(value) = force_color {
+        return value != "0";
+    }
+    stdout_is_tty
+}
+
+/// `colors_enabled` for the current environment.
+pub fn detect_colors(forced: Option<bool>) -> bool {
+    let no_color = std::env::var("NO_COLOR").ok();
+    let force_color = std::env::var("FORCE_COLOR").ok();
+    colors_enabled(
+        forced,
+        no_color.as_deref(),
+        force_color.as_deref(),
+        Stream::Stdout.is_terminal(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn named(name: &str) -> Style {
+        *STYLES.iter().find(|s| s.name == name).unwrap()
+    }
+
+    #[test]
+    fn test_nested_styles_resume_outer() {
+        let green = named("green").apply(b"ok");
+        let mut inner = b"a ".to_vec();
+        inner.extend_from_slice(&green);
+        inner.extend_from_slice(b" b");
+        let styled = named("red").apply(&inner);
+        assert_eq!(
+            String::from_utf8(styled).unwrap(),
+            "\x1b[31ma \x1b[32mok\x1b[39m\x1b[31m b\x1b[39m"
+        );
+        assert_eq!(named("bold").apply(b""), b"\x1b[1m\x1b[22m");
+    }
+
+    #[test]
+    fn test_color_detection_precedence() {
+        assert!(!colors_enabled(None, Some("1"), Some("1"), true));
+        assert!(colors_enabled(None, Some(""), None, true));
+        assert!(colors_enabled(None, None, Some("1"), false));
+        assert!(!colors_enabled(None, None, Some("0"), true));
+        assert!(!colors_enabled(None, None, None, false));
+        assert!(colors_enabled(Some(true), Some("1"), None, false));
+        assert!(!colors_enabled(Some(false), None, Some("1"), true));
+    }
+}
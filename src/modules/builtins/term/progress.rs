@@ -0,0 +1,238 @@
+use std::fmt::Write as _;
+use std::io::{self, Write};
+
+pub const DEFAULT_WIDTH: usize = 20;
+
+const SPINNER_FRAMES: [char; 4] = ['|', '/', '-', '\\'];
+
+/// One status line redrawn in place with `\r`. The buffer is reused between
+/// draws, and nothing is written when `enabled` is false (output is not a
+/// terminal).
+struct StatusLine {
+    enabled: bool,
+    line: String,
+    last_len: usize,
+}
+
+impl StatusLine {
+    fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            line: String::new(),
+            last_len: 0,
+        }
+    }
+
+    /// Write the current `line` over the previous one, blanking any leftover
+    /// characters when it got shorter.
+    fn draw(&mut self, out: &mut impl Write) -> io::Result<()> {
+        let len = self.line.chars().count();
+        let padding = self.last_len.saturating_sub(len);
+        self.last_len = len;
+        write!(out, "\r{}{:padding$}", self.line, "", padding = padding)?;
+        out.flush()
+    }
+
+    fn clear(&mut self, out: &mut impl Write) -> io::Result<()> {
+        write!(out, "\r{:width$}\r", "", width = self.last_len)?;
+        self.last_len = 0;
+        out.flush()
+    }
+}
+
+/// A `[####----] 50%` bar for work with a known size.
+pub struct ProgressBar {
+    status: StatusLine,
+    total: u64,
+    current: u64,
+    width: usize,
+    label: String,
+    /// The filled cells and percentage last drawn, to skip redundant redraws.
+    drawn: Option<(usize, u64)>,
+    finished: bool,
+}
+
+impl ProgressBar {
+    pub fn new(total: u64, width: usize, label: String, enabled: bool) -> Self {
+        Self {
+            status: StatusLine::new(enabled),
+            total,
+            current: 0,
+            width,
+            label,
+            drawn: None,
+            finished: false,
+        }
+    }
+
+    pub fn total(&self) -> u64 {
+        self.total
+    }
+
+    pub fn current(&self) -> u64 {
+        self.current
+    }
+
+    /// Whole percent done. An empty job counts as complete.
+    pub fn percent(&self) -> u64 {
+        (self.current * 100).checked_div(self.total).unwrap_or(100)
+    }
+
+    fn filled(&self) -> usize {
+        (self.current as u128 * self.width as u128)
+            .checked_div(self.total as u128)
+            .map_or(self.width, |filled| filled as usize)
+    }
+
+    fn render_into(&self, line: &mut String) {
+        line.clear();
+        if !self.label.is_empty() {
+            line.push_str(&self.label);
+            line.push(' ');
+        }
+        let filled = self.filled();
+        line.push('[');
+        line.extend(std::iter::repeat('#').take(filled));
+        line.extend(std::iter::repeat('-').take(self.width - filled));
+        let _ = write!(line, "] {}%", self.percent());
+    }
+
+    /// Move to `current` (clamped to the total) and redraw if the bar changed.
+    pub fn update(&mut self, current: u64, out: &mut impl Write) -> io::Result<()> {
+        if self.finished {
+            return Ok(());
+        }
+        self.current = current.min(self.total);
+        let state = (self.filled(), self.percent());
+        if !self.status.enabled || self.drawn == Some(state) {
+            return Ok(());
+        }
+        self.drawn = Some(state);
+        let mut line = std::mem::take(&mut self.status.line);
+        self.render_into(&mut line);
+        self.status.line = line;
+        self.status.draw(out)
+    }
+
+    /// Draw the bar at 100% and end its line.
+    pub fn finish(&mut self, out: &mut impl Write) -> io::Result<()> {
+        self.update(self.total, out)?;
+        if !self.finished && self.status.enabled {
+            writeln!(out)?;
+        }
+        self.finished = true;
+        Ok(())
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+}
+
+/// An indeterminate `| label` spinner advanced by each `tick`.
+pub struct Spinner {
+    status: StatusLine,
+    label: String,
+    frame: usize,
+    stopped: bool,
+}
+
+impl Spinner {
+    pub fn new(label: String, enabled: bool) -> Self {
+        Self {
+            status: StatusLine::new(enabled),
+            label,
+            frame: 0,
+            stopped: false,
+        }
+    }
+
+    pub fn tick(&mut self, out: &mut impl Write) -> io::Result<()> {
+        if self.stopped {
+            return Ok(());
+        }
+        self.frame = (self.frame + 1) % SPINNER_FRAMES.len();
+        if !self.status.enabled {
+            return Ok(());
+        }
+        let line = &mut self.status.line;
+        line.clear();
+        line.push(SPINNER_FRAMES[self.frame]);
+        line.push(' ');
+        line.push_str(&self.label);
+        self.status.draw(out)
+    }
+
+    /// Erase the spinner, leaving `message` on its line when given.
+    pub fn stop(&mut self, message: Option<&str>, out: &mut impl Write) -> io::Result<()> {
+        if self.stopped {
+            return Ok(());
+        }
+        self.stopped = true;
+        if !self.status.enabled {
+            return Ok(());
+        }
+        self.status.clear(out)?;
+        if let Some(message) = message {
+            writeln!(out, "{}", message)?;
+        }
+        out.flush()
+    }
+
+    pub fn is_stopped(&self) -> bool {
+        self.stopped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn drawn(out: Vec<u8>) -> String {
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_bar_renders_percentages() {
+        let mut out = Vec::new();
+        let mut bar = ProgressBar::new(10, 8, String::new(), true);
+        bar.update(0, &mut out).unwrap();
+        bar.update(5, &mut out).unwrap();
+        // Unchanged bars are not redrawn
+        bar.update(5, &mut out).unwrap();
+        bar.finish(&mut out).unwrap();
+        assert_eq!(
+            drawn(out),
+            "\r[--------] 0%\r[####----] 50%\r[########] 100%\n"
+        );
+
+        let mut out = Vec::new();
+        let mut labeled = ProgressBar::new(0, 4, "copy".to_string(), true);
+        labeled.finish(&mut out).unwrap();
+        assert_eq!(drawn(out), "\rcopy [####] 100%\n");
+    }
+
+    #[test]
+    fn test_disabled_output_is_suppressed() {
+        let mut out = Vec::new();
+        let mut bar = ProgressBar::new(4, 8, String::new(), false);
+        bar.update(2, &mut out).unwrap();
+        bar.finish(&mut out).unwrap();
+        assert_eq!(bar.current(), 4);
+
+        let mut spinner = Spinner::new("loading".to_string(), false);
+        spinner.tick(&mut out).unwrap();
+        spinner.stop(Some("done"), &mut out).unwrap();
+        assert!(out.is_empty());
+    }
+
+    #[test]
+    fn test_spinner_cycles_and_clears() {
+        let mut out = Vec::new();
+        let mut spinner = Spinner::new("fetch".to_string(), true);
+        spinner.tick(&mut out).unwrap();
+        spinner.tick(&mut out).unwrap();
+        spinner.stop(Some("fetched"), &mut out).unwrap();
+        assert_eq!(drawn(out), "\r/ fetch\r- fetch\r       \rfetched\n");
+    }
+}
@@ -0,0 +1,213 @@
+use super::error::TermError;
+use super::operations::Result;
+use std::io::{self, BufRead, IsTerminal, Write};
+
+/// Read one line without its `\n` or `\r\n`, or `None` at EOF.
+fn read_answer(input: &mut impl BufRead) -> Result<Option<String>> {
+    let mut line = Vec::new();
+    if input.read_until(b'\n', &mut line)? == 0 {
+        return Ok(None);
+    }
+    if line.ends_with(b"\n") {
+        line.pop();
+        if line.ends_with(b"\r") {
+            line.pop();
+        }
+    }
+    Ok(Some(String::from_utf8_lossy(&line).into_owned()))
+}
+
+/// Write `question` followed by `hint` and a space, ready for the answer.
+fn ask(output: &mut impl Write, question: &str, hint: Option<&str>) -> Result<()> {
+    let question = question.trim_end();
+    match hint {
+        Some(hint) => write!(output, "{} {} ", question, hint)?,
+        None => write!(output, "{} ", question)?,
+    }
+    output.flush()?;
+    Ok(())
+}
+
+/// Ask for a line of text. An empty answer, or EOF, gives `default` when
+/// there is one; EOF without a default is an error.
+pub fn prompt(
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+    question: &str,
+    default: Option<&str>,
+) -> Result<String> {
+    let hint = default.map(|default| format!("({})", default));
+    ask(output, question, hint.as_deref())?;
+    match (read_answer(input)?, default) {
+        (Some(answer), Some(default)) if answer.trim().is_empty() => Ok(default.to_string()),
+        (Some(answer), _) => Ok(answer),
+        (None, Some(default)) => Ok(default.to_string()),
+        (None, None) => Err(TermError::Eof),
+    }
+}
+
+/// Ask a yes/no question, repeating it until the answer is y, yes, n or no
+/// (any case). An empty answer, or EOF, gives `default` when there is one.
+pub fn confirm(
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+    question: &str,
+    default: Option<bool>,
+) -> Result<bool> {
+    let hint = match default {
+        Some(true) => "[Y/n]",
+        Some(false) => "[y/N]",
+        None => "[y/n]",
+    };
+    loop {
+        ask(output, question, Some(hint))?;
+        let Some(answer) = read_answer(input)? else {
+            return default.ok_or(TermError::Eof);
+        };
+        match (answer.trim().to_lowercase().as_str(), default) {
+            ("y" | "yes", _) => return Ok(true),
+            ("n" | "no", _) => return Ok(false),
+            ("", Some(default)) => return Ok(default),
+            _ => writeln!(output, "Please answer y or n.")?,
+        }
+    }
+}
+
+/// Ask for a secret on stdin. When stdin is a terminal its echo is turned off
+/// while typing; piped input is read as a plain line.
+pub fn password(question: &str) -> Result<String> {
+    let mut stderr = io::stderr();
+    ask(&mut stderr, question, None)?;
+    let answer = if io::stdin().is_terminal() {
+        let _echo = EchoGuard::disable()?;
+        let answer = read_answer(&mut io::stdin().lock());
+        // The Enter key was not echoed either
+        writeln!(stderr)?;
+        answer?
+    } else {
+        read_answer(&mut io::stdin().lock())?
+    };
+    answer.ok_or(TermError::Eof)
+}
+
+/// Turns terminal echo off for stdin until dropped.
+struct EchoGuard {
+    #[cfg(unix)]
+    original: libc::termios,
+    #[cfg(windows)]
+    original: u32,
+}
+
+#[cfg(unix)]
+impl EchoGuard {
+    fn disable() -> io::Result<Self> {
+        // SAFETY: tcgetattr fills the zeroed struct, and both calls only read
+        // or write the terminal state of stdin.
+        unsafe {
+            let mut original: libc::termios = std::mem::zeroed();
+            if libc::tcgetattr(libc::STDIN_FILENO, &mut original) != 0 {
+                return Err(io::Error::last_os_error());
+            }
+            let mut hidden = original;
+            hidden.c_lflag &= !libc::ECHO;
+            if libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &hidden) != 0 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(Self { original })
+        }
+    }
+}
+
+#[cfg(unix)]
+impl Drop for EchoGuard {
+    fn drop(&mut self) {
+        // SAFETY: restores the settings read in `disable`.
+        unsafe {
+            libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &self.original);
+        }
+    }
+}
+
+#[cfg(windows)]
+impl EchoGuard {
+    fn disable() -> io::Result<Self> {
+        use windows_sys::Win32::System::Console::{
+            GetConsoleMode, GetStdHandle, SetConsoleMode, ENABLE_ECHO_INPUT, STD_INPUT_HANDLE,
+        };
+        // SAFETY: the handle comes from GetStdHandle and the mode is written
+        // into a local before use.
+        unsafe {
+            let handle = GetStdHandle(STD_INPUT_HANDLE);
+            let mut original = 0;
+            if GetConsoleMode(handle, &mut original) == 0 {
+                return Err(io::Error::last_os_error());
+            }
+            if SetConsoleMode(handle, original & !ENABLE_ECHO_INPUT) == 0 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(Self { original })
+        }
+    }
+}
+
+#[cfg(windows)]
+impl Drop for EchoGuard {
+    fn drop(&mut self) {
+        use windows_sys::Win32::System::Console::{GetStdHandle, SetConsoleMode, STD_INPUT_HANDLE};
+        // SAFETY: restores the mode read in `disable`.
+        unsafe {
+            SetConsoleMode(GetStdHandle(STD_INPUT_HANDLE), self.original);
+        }
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+impl EchoGuard {
+    fn disable() -> io::Result<Self> {
+        Ok(Self {})
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn answer<T>(input: &str, run: impl FnOnce(&mut &[u8], &mut Vec<u8>) -> T) -> (T, String) {
+        let mut output = Vec::new();
+        let result = run(&mut input.as_bytes(), &mut output);
+        (result, String::from_utf8(output).unwrap())
+    }
+
+    #[test]
+    fn test_prompt_defaults_on_empty_and_eof() {
+        let (name, shown) = answer("Ada\r\n", |i, o| prompt(i, o, "Name?", None));
+        assert_eq!(name.unwrap(), "Ada");
+        assert_eq!(shown, "Name? ");
+
+        let (name, shown) = answer("\n", |i, o| prompt(i, o, "Name? ", Some("anon")));
+        assert_eq!(name.unwrap(), "anon");
+        assert_eq!(shown, "Name? (anon) ");
+
+        let (name, _) = answer("", |i, o| prompt(i, o, "Name?", Some("anon")));
+        assert_eq!(name.unwrap(), "anon");
+        let (name, _) = answer("", |i, o| prompt(i, o, "Name?", None));
+        assert!(matches!(name, Err(TermError::Eof)));
+    }
+
+    #[test]
+    fn test_confirm_repeats_until_yes_or_no() {
+        let (ok, shown) = answer("maybe\nYES\n", |i, o| confirm(i, o, "Continue?", None));
+        assert!(ok.unwrap());
+        assert_eq!(
+            shown,
+            "Continue? [y/n] Please answer y or n.\nContinue? [y/n] "
+        );
+
+        let (ok, shown) = answer("\n", |i, o| confirm(i, o, "Delete?", Some(false)));
+        assert!(!ok.unwrap());
+        assert_eq!(shown, "Delete? [y/N] ");
+
+        let (ok, _) = answer("", |i, o| confirm(i, o, "Go?", None));
+        assert!(matches!(ok, Err(TermError::Eof)));
+    }
+}
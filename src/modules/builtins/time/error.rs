@@ -6,6 +6,11 @@ pub enum TimeError {
     FormatError(String),
     InvalidTimestamp(i64),
     InvalidDuration(i64),
+    InvalidUnit(String),
+    UnrecognizedFormat {
+        input: String,
+        attempted: Vec<&'static str>,
+    },
 }
 
 impl fmt::Display for TimeError {
@@ -15,6 +20,17 @@ impl fmt::Display for TimeError {
             TimeError::FormatError(msg) => write!(f, "Failed to format time: {}", msg),
             TimeError::InvalidTimestamp(ts) => write!(f, "Invalid timestamp: {}", ts),
             TimeError::InvalidDuration(dur) => write!(f, "Invalid duration: {}", dur),
+            TimeError::InvalidUnit(unit) => write!(
+                f,
+                "Invalid unit '{}': expected years, months, weeks, days, hours, minutes, seconds or milliseconds",
+                unit
+            ),
+            TimeError::UnrecognizedFormat { input, attempted } => write!(
+                f,
+                "Failed to parse time '{}': no format matched (tried {})",
+                input,
+                attempted.join(", ")
+            ),
         }
     }
 }
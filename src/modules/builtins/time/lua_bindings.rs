@@ -1,5 +1,11 @@
 use super::operations::*;
+use crate::engine::timeout::{Deadline, TimeoutError};
+use crate::modules::builtins::process::{dispatch_signals, signals};
 use mlua::{Lua, Result as LuaResult, Table, Value};
+use std::time::{Duration, Instant};
+
+/// How often a sleep under a timeout checks for signals.
+const SIGNAL_POLL_INTERVAL: Duration = Duration::from_millis(50);
 
 pub fn create_time_module(lua: &Lua) -> LuaResult<Table> {
     let time = lua.create_table()?;
@@ -29,6 +35,11 @@ pub fn create_time_module(lua: &Lua) -> LuaResult<Table> {
     })?;
     time.set("parse", parse_fn)?;
 
+    let parse_auto_fn = lua.create_function(|_, date_str: String| {
+        parse_auto(&date_str).map_err(|e| mlua::Error::RuntimeError(e.to_string()))
+    })?;
+    time.set("parseAuto", parse_auto_fn)?;
+
     let to_iso_fn = lua.create_function(|_, timestamp: i64| match to_iso(timestamp) {
         Ok(s) => Ok(s),
         Err(e) => Err(mlua::Error::RuntimeError(e.to_string())),
@@ -93,9 +104,46 @@ pub fn create_time_module(lua: &Lua) -> LuaResult<Table> {
     })?;
     time.set("second", second_fn)?;
 
-    let sleep_fn = lua.create_function(|_, ms: u64| {
-        sleep(ms);
-        Ok(())
+    let add_fn = lua.create_function(|_, (timestamp, offset): (i64, Table)| {
+        add(timestamp, &parse_offset(offset)?).map_err(|e| mlua::Error::RuntimeError(e.to_string()))
+    })?;
+    time.set("add", add_fn)?;
+
+    let diff_fn = lua.create_function(|_, (a, b, unit): (i64, i64, Option<String>)| {
+        let unit = match unit {
+            Some(unit) => {
+                DiffUnit::parse(&unit).map_err(|e| mlua::Error::RuntimeError(e.to_string()))?
+            }
+            None => DiffUnit::Milliseconds,
+        };
+        diff(a, b, unit).map_err(|e| mlua::Error::RuntimeError(e.to_string()))
+    })?;
+    time.set("diff", diff_fn)?;
+
+    let sleep_fn = lua.create_function(|lua, ms: u64| {
+        if !signals::is_listening() {
+            return sleep_until_deadline(lua, Duration::from_millis(ms));
+        }
+
+        // Wake for signals so `process.on` handlers run promptly, then
+        // finish the sleep
+        let wake_at = Instant::now() + Duration::from_millis(ms);
+        loop {
+            let remaining = wake_at.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Ok(());
+            }
+            let signalled = match Deadline::current(lua) {
+                Some(_) => {
+                    sleep_until_deadline(lua, remaining.min(SIGNAL_POLL_INTERVAL))?;
+                    signals::has_pending()
+                }
+                None => signals::wait_for_signal(remaining),
+            };
+            if signalled {
+                dispatch_signals(lua)?;
+            }
+        }
     })?;
     time.set("sleep", sleep_fn)?;
 
@@ -108,6 +156,45 @@ pub fn create_time_module(lua: &Lua) -> LuaResult<Table> {
     Ok(time)
 }
 
+/// The `{years, months, days, hours, minutes, seconds}` table of `time.add`.
+/// Unknown fields are rejected so a typo such as `day` is not ignored.
+fn parse_offset(offset: Table) -> LuaResult<DateOffset> {
+    let mut parsed = DateOffset::default();
+    for pair in offset.pairs::<String, i64>() {
+        let (field, amount) = pair?;
+        let slot = match field.as_str() {
+            "years" => &mut parsed.years,
+            "months" => &mut parsed.months,
+            "days" => &mut parsed.days,
+            "hours" => &mut parsed.hours,
+            "minutes" => &mut parsed.minutes,
+            "seconds" => &mut parsed.seconds,
+            other => {
+                return Err(mlua::Error::RuntimeError(format!(
+                    "Unknown field '{}' in time.add offset: expected years, months, days, hours, minutes or seconds",
+                    other
+                )))
+            }
+        };
+        *slot = amount;
+    }
+    Ok(parsed)
+}
+
+/// Sleep, stopping early with the timeout error if the script's deadline passes.
+fn sleep_until_deadline(lua: &Lua, duration: Duration) -> LuaResult<()> {
+    match Deadline::current(lua) {
+        Some(deadline) => deadline.sleep(duration).map_err(|e| match e {
+            TimeoutError::TimeoutExpired(_) => mlua::Error::external(deadline.execution_error()),
+            other => mlua::Error::external(other),
+        }),
+        None => {
+            std::thread::sleep(duration);
+            Ok(())
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -125,6 +212,7 @@ mod tests {
         assert!(time.contains_key("nowNanos").unwrap());
         assert!(time.contains_key("format").unwrap());
         assert!(time.contains_key("parse").unwrap());
+        assert!(time.contains_key("parseAuto").unwrap());
         assert!(time.contains_key("toISO").unwrap());
         assert!(time.contains_key("fromISO").unwrap());
         assert!(time.contains_key("date").unwrap());
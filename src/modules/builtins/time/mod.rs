@@ -59,6 +59,11 @@ impl BuiltinModule for TimeModule {
                 "__desc": "Parse date string using custom format",
                 "__signature": "parse(dateString: string, format: string) -> number"
             },
+            "parseAuto": {
+                "__fn": "parseAuto",
+                "__desc": "Parse date string by trying common formats in turn",
+                "__signature": "parseAuto(dateString: string) -> number"
+            },
             "toISO": {
                 "__fn": "toISO",
                 "__desc": "Convert timestamp to ISO 8601 string",
@@ -118,6 +123,16 @@ impl BuiltinModule for TimeModule {
                 "__fn": "duration",
                 "__desc": "Format duration in human-readable form",
                 "__signature": "duration(ms: number) -> string"
+            },
+            "add": {
+                "__fn": "add",
+                "__desc": "Add a calendar offset to a timestamp in local time",
+                "__signature": "add(timestamp: number, offset: {years?, months?, days?, hours?, minutes?, seconds?}) -> number"
+            },
+            "diff": {
+                "__fn": "diff",
+                "__desc": "Whole units between two timestamps (a - b)",
+                "__signature": "diff(a: number, b: number, unit?: string) -> number"
             }
         }))
     }
@@ -143,6 +158,7 @@ mod tests {
         assert!(exports.get("nowNanos").is_some());
         assert!(exports.get("format").is_some());
         assert!(exports.get("parse").is_some());
+        assert!(exports.get("parseAuto").is_some());
         assert!(exports.get("toISO").is_some());
         assert!(exports.get("fromISO").is_some());
         assert!(exports.get("date").is_some());
@@ -155,6 +171,8 @@ mod tests {
         assert!(exports.get("sleep").is_some());
         assert!(exports.get("elapsed").is_some());
         assert!(exports.get("duration").is_some());
+        assert!(exports.get("add").is_some());
+        assert!(exports.get("diff").is_some());
     }
 
     #[test]
@@ -1,5 +1,8 @@
 use super::error::TimeError;
-use chrono::{DateTime, Datelike, Local, TimeZone, Timelike, Utc};
+use chrono::{
+    DateTime, Datelike, Days, Local, LocalResult, Months, NaiveDate, NaiveDateTime, TimeDelta,
+    TimeZone, Timelike, Utc,
+};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 pub fn now() -> i64 {
@@ -34,6 +37,73 @@ pub fn parse_timestamp(date_str: &str, format: &str) -> Result<i64, TimeError> {
     Ok(dt.timestamp_millis())
 }
 
+/// Epoch values at or above this magnitude are read as milliseconds rather
+/// than seconds. In seconds it would be past the year 5000.
+const EPOCH_MILLIS_THRESHOLD: i64 = 100_000_000_000;
+
+type FormatParser = fn(&str) -> Option<i64>;
+
+/// The formats `parse_auto` tries, in order, by name and parser.
+const AUTO_FORMATS: &[(&str, FormatParser)] = &[
+    ("ISO 8601", parse_iso_8601),
+    ("RFC 2822", parse_rfc_2822),
+    ("YYYY-MM-DD HH:MM:SS", parse_sql_datetime),
+    ("Unix epoch seconds/milliseconds", parse_epoch),
+];
+
+/// Parse a timestamp without knowing its format, trying each of
+/// `AUTO_FORMATS` in turn. Times without an offset are read as UTC.
+pub fn parse_auto(date_str: &str) -> Result<i64, TimeError> {
+    let trimmed = date_str.trim();
+    AUTO_FORMATS
+        .iter()
+        .find_map(|(_, parse)| parse(trimmed))
+        .ok_or_else(|| TimeError::UnrecognizedFormat {
+            input: date_str.to_string(),
+            attempted: AUTO_FORMATS.iter().map(|(name, _)| *name).collect(),
+        })
+}
+
+fn parse_iso_8601(s: &str) -> Option<i64> {
+    if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+        return Some(dt.timestamp_millis());
+    }
+    let naive = NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S%.f")
+        .or_else(|_| NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M"))
+        .ok()
+        .or_else(|| {
+            NaiveDate::parse_from_str(s, "%Y-%m-%d")
+                .ok()?
+                .and_hms_opt(0, 0, 0)
+        })?;
+    Some(naive.and_utc().timestamp_millis())
+}
+
+fn parse_rfc_2822(s: &str) -> Option<i64> {
+    DateTime::parse_from_rfc2822(s)
+        .ok()
+        .map(|dt| dt.timestamp_millis())
+}
+
+fn parse_sql_datetime(s: &str) -> Option<i64> {
+    NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S%.f")
+        .ok()
+        .map(|naive| naive.and_utc().timestamp_millis())
+}
+
+fn parse_epoch(s: &str) -> Option<i64> {
+    let digits = s.strip_prefix('-').unwrap_or(s);
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let value: i64 = s.parse().ok()?;
+    if value.abs() >= EPOCH_MILLIS_THRESHOLD {
+        Some(value)
+    } else {
+        value.checked_mul(1000)
+    }
+}
+
 pub fn to_iso(timestamp_ms: i64) -> Result<String, TimeError> {
     let dt = timestamp_to_datetime(timestamp_ms)?;
     Ok(dt.to_rfc3339())
@@ -143,6 +213,161 @@ pub fn format_duration(ms: i64) -> String {
     }
 }
 
+/// An amount of time to add with [`add`]. Calendar fields move the local
+/// date and keep the wall-clock time; clock fields add elapsed time.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DateOffset {
+    pub years: i64,
+    pub months: i64,
+    pub days: i64,
+    pub hours: i64,
+    pub minutes: i64,
+    pub seconds: i64,
+}
+
+/// Add `offset` to a timestamp in the local time zone. Years and months are
+/// added first, clamping the day to the end of a shorter month (Jan 31 + 1
+/// month is Feb 28 or 29), then days, so the wall-clock time survives DST
+/// changes. Hours, minutes and seconds are then added as elapsed time.
+pub fn add(timestamp_ms: i64, offset: &DateOffset) -> Result<i64, TimeError> {
+    add_in(&Local, timestamp_ms, offset)
+}
+
+fn add_in<Tz: TimeZone>(tz: &Tz, timestamp_ms: i64, offset: &DateOffset) -> Result<i64, TimeError> {
+    let out_of_range = || TimeError::InvalidTimestamp(timestamp_ms);
+    let mut local = timestamp_to_datetime(timestamp_ms)?
+        .with_timezone(tz)
+        .naive_local();
+
+    let months = offset
+        .years
+        .checked_mul(12)
+        .and_then(|months| months.checked_add(offset.months))
+        .ok_or_else(out_of_range)?;
+    local = shift_months(local, months).ok_or_else(out_of_range)?;
+    local = shift_days(local, offset.days).ok_or_else(out_of_range)?;
+
+    let elapsed = TimeDelta::try_hours(offset.hours)
+        .zip(TimeDelta::try_minutes(offset.minutes))
+        .zip(TimeDelta::try_seconds(offset.seconds))
+        .and_then(|((hours, minutes), seconds)| hours.checked_add(&minutes)?.checked_add(&seconds))
+        .ok_or_else(out_of_range)?;
+    resolve_local(tz, local)
+        .ok_or_else(out_of_range)?
+        .checked_add_signed(elapsed)
+        .map(|dt| dt.timestamp_millis())
+        .ok_or_else(out_of_range)
+}
+
+fn shift_months(local: NaiveDateTime, months: i64) -> Option<NaiveDateTime> {
+    let count = Months::new(u32::try_from(months.unsigned_abs()).ok()?);
+    if months >= 0 {
+        local.checked_add_months(count)
+    } else {
+        local.checked_sub_months(count)
+    }
+}
+
+fn shift_days(local: NaiveDateTime, days: i64) -> Option<NaiveDateTime> {
+    let count = Days::new(days.unsigned_abs());
+    if days >= 0 {
+        local.checked_add_days(count)
+    } else {
+        local.checked_sub_days(count)
+    }
+}
+
+/// The instant a local wall-clock time refers to. A time repeated when
+/// clocks go back resolves to its first occurrence; a time skipped when they
+/// go forward moves ahead by the length of the gap.
+fn resolve_local<Tz: TimeZone>(tz: &Tz, local: NaiveDateTime) -> Option<DateTime<Tz>> {
+    match tz.from_local_datetime(&local) {
+        LocalResult::Single(dt) => Some(dt),
+        LocalResult::Ambiguous(earliest, _) => Some(earliest),
+        LocalResult::None => {
+            // Gaps are at most a few hours; step past one an hour at a time
+            (1..=4).find_map(|hours| {
+                let later = local.checked_add_signed(TimeDelta::try_hours(hours)?)?;
+                tz.from_local_datetime(&later).earliest()
+            })
+        }
+    }
+}
+
+/// A unit [`diff`] can measure in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffUnit {
+    Years,
+    Months,
+    Weeks,
+    Days,
+    Hours,
+    Minutes,
+    Seconds,
+    Milliseconds,
+}
+
+impl DiffUnit {
+    /// Singular and plural unit names, such as `"day"` and `"days"`.
+    pub fn parse(unit: &str) -> Result<Self, TimeError> {
+        match unit {
+            "year" | "years" => Ok(DiffUnit::Years),
+            "month" | "months" => Ok(DiffUnit::Months),
+            "week" | "weeks" => Ok(DiffUnit::Weeks),
+            "day" | "days" => Ok(DiffUnit::Days),
+            "hour" | "hours" => Ok(DiffUnit::Hours),
+            "minute" | "minutes" => Ok(DiffUnit::Minutes),
+            "second" | "seconds" => Ok(DiffUnit::Seconds),
+            "millisecond" | "milliseconds" => Ok(DiffUnit::Milliseconds),
+            other => Err(TimeError::InvalidUnit(other.to_string())),
+        }
+    }
+}
+
+/// How many whole `unit`s `a` is after `b`, negative when it is before.
+/// Years, months, weeks and days count calendar steps in the local time
+/// zone, so a day across a DST change is still one day; smaller units
+/// measure elapsed time.
+pub fn diff(a_ms: i64, b_ms: i64, unit: DiffUnit) -> Result<i64, TimeError> {
+    diff_in(&Local, a_ms, b_ms, unit)
+}
+
+fn diff_in<Tz: TimeZone>(tz: &Tz, a_ms: i64, b_ms: i64, unit: DiffUnit) -> Result<i64, TimeError> {
+    let elapsed = a_ms
+        .checked_sub(b_ms)
+        .ok_or(TimeError::InvalidTimestamp(a_ms))?;
+    let local = |ms: i64| -> Result<NaiveDateTime, TimeError> {
+        Ok(timestamp_to_datetime(ms)?.with_timezone(tz).naive_local())
+    };
+
+    Ok(match unit {
+        DiffUnit::Years => whole_months(local(a_ms)?, local(b_ms)?) / 12,
+        DiffUnit::Months => whole_months(local(a_ms)?, local(b_ms)?),
+        DiffUnit::Weeks => (local(a_ms)? - local(b_ms)?).num_days() / 7,
+        DiffUnit::Days => (local(a_ms)? - local(b_ms)?).num_days(),
+        DiffUnit::Hours => elapsed / 3_600_000,
+        DiffUnit::Minutes => elapsed / 60_000,
+        DiffUnit::Seconds => elapsed / 1000,
+        DiffUnit::Milliseconds => elapsed,
+    })
+}
+
+/// Whole calendar months from `b` to `a`, counting a month only once the
+/// same day and time is reached (clamped to the end of shorter months).
+fn whole_months(a: NaiveDateTime, b: NaiveDateTime) -> i64 {
+    let mut months =
+        (a.year() as i64 - b.year() as i64) * 12 + (a.month() as i64 - b.month() as i64);
+    let overshoots = |months: i64| match shift_months(b, months) {
+        Some(shifted) if months > 0 => shifted > a,
+        Some(shifted) if months < 0 => shifted < a,
+        _ => false,
+    };
+    if overshoots(months) {
+        months -= months.signum();
+    }
+    months
+}
+
 fn timestamp_to_datetime(timestamp_ms: i64) -> Result<DateTime<Utc>, TimeError> {
     let seconds = timestamp_ms / 1000;
     let nanos = ((timestamp_ms % 1000) * 1_000_000) as u32;
@@ -156,6 +381,36 @@ fn timestamp_to_datetime(timestamp_ms: i64) -> Result<DateTime<Utc>, TimeError>
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_auto_formats() {
+        let expected = 1_709_294_400_000; // 2024-03-01T12:00:00Z
+        assert_eq!(parse_auto("2024-03-01T12:00:00Z").unwrap(), expected);
+        assert_eq!(parse_auto("2024-03-01T07:00:00-05:00").unwrap(), expected);
+        assert_eq!(parse_auto("2024-03-01T12:00:00").unwrap(), expected);
+        assert_eq!(
+            parse_auto("2024-03-01").unwrap(),
+            expected - 12 * 3600 * 1000
+        );
+        assert_eq!(
+            parse_auto("Fri, 01 Mar 2024 12:00:00 +0000").unwrap(),
+            expected
+        );
+        assert_eq!(parse_auto("2024-03-01 12:00:00").unwrap(), expected);
+        assert_eq!(parse_auto(" 1709294400 ").unwrap(), expected);
+        assert_eq!(parse_auto("1709294400000").unwrap(), expected);
+    }
+
+    #[test]
+    fn test_parse_auto_lists_attempted_formats() {
+        let err = parse_auto("yesterday").unwrap_err().to_string();
+        assert!(err.contains("'yesterday'"), "{}", err);
+        for (name, _) in AUTO_FORMATS {
+            assert!(err.contains(name), "{}", err);
+        }
+        assert!(parse_auto("").is_err());
+        assert!(parse_auto("12.5").is_err());
+    }
+
     #[test]
     fn test_now() {
         let t = now();
@@ -255,4 +510,53 @@ mod tests {
         let formatted = format_timestamp(timestamp, "%Y-%m-%d").unwrap();
         assert_eq!(formatted, "2021-01-01");
     }
+
+    #[test]
+    fn test_add_months_clamps_to_month_end() {
+        let jan_31 = from_iso("2023-01-31T10:00:00Z").unwrap();
+        let month = DateOffset {
+            months: 1,
+            ..DateOffset::default()
+        };
+        let feb = add_in(&Utc, jan_31, &month).unwrap();
+        assert_eq!(to_iso(feb).unwrap(), "2023-02-28T10:00:00+00:00");
+
+        let leap = add_in(
+            &Utc,
+            from_iso("2024-02-29T00:00:00Z").unwrap(),
+            &DateOffset {
+                years: 1,
+                ..DateOffset::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(to_iso(leap).unwrap(), "2025-02-28T00:00:00+00:00");
+
+        let back = add_in(
+            &Utc,
+            from_iso("2024-03-01T00:00:00Z").unwrap(),
+            &DateOffset {
+                days: -1,
+                hours: 6,
+                ..DateOffset::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(to_iso(back).unwrap(), "2024-02-29T06:00:00+00:00");
+    }
+
+    #[test]
+    fn test_diff_counts_whole_units() {
+        let a = from_iso("2024-03-15T12:00:00Z").unwrap();
+        let b = from_iso("2024-01-31T18:00:00Z").unwrap();
+        assert_eq!(diff_in(&Utc, a, b, DiffUnit::Days).unwrap(), 43);
+        assert_eq!(diff_in(&Utc, b, a, DiffUnit::Days).unwrap(), -43);
+        assert_eq!(diff_in(&Utc, a, b, DiffUnit::Weeks).unwrap(), 6);
+        assert_eq!(diff_in(&Utc, a, b, DiffUnit::Months).unwrap(), 1);
+        assert_eq!(diff_in(&Utc, b, a, DiffUnit::Months).unwrap(), -1);
+        assert_eq!(diff_in(&Utc, a, b, DiffUnit::Hours).unwrap(), 43 * 24 + 18);
+        assert_eq!(diff_in(&Utc, a, b, DiffUnit::Years).unwrap(), 0);
+        assert_eq!(DiffUnit::parse("day").unwrap(), DiffUnit::Days);
+        assert!(DiffUnit::parse("fortnights").is_err());
+    }
 }
@@ -0,0 +1,26 @@
+use std::fmt;
+
+#[derive(Debug)]
+pub enum TomlError {
+    ParseError(String),
+    SerializationError(String),
+    UnsupportedValue(String),
+}
+
+impl fmt::Display for TomlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TomlError::ParseError(msg) => write!(f, "{}", msg.trim_end()),
+            TomlError::SerializationError(msg) => write!(f, "TOML serialization error: {}", msg),
+            TomlError::UnsupportedValue(msg) => write!(f, "Unsupported TOML value: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for TomlError {}
+
+impl From<TomlError> for crate::error::HypeError {
+    fn from(err: TomlError) -> Self {
+        crate::error::HypeError::Execution(err.to_string())
+    }
+}
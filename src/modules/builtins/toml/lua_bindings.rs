@@ -0,0 +1,54 @@
+use super::operations::*;
+use crate::lua::require::json_to_lua;
+use crate::lua::require::lua_to_json;
+use mlua::{Lua, Table};
+
+pub fn create_toml_module(lua: &Lua) -> mlua::Result<Table<'_>> {
+    let toml_table = lua.create_table()?;
+
+    let parse_fn = lua.create_function(|lua, input: String| {
+        let value = parse(&input).map_err(mlua::Error::external)?;
+        json_to_lua(lua, &value).map_err(mlua::Error::external)
+    })?;
+    toml_table.set("parse", parse_fn)?;
+
+    let stringify_fn = lua.create_function(|lua, table: Table| {
+        let json_value = lua_to_json(lua, mlua::Value::Table(table))?;
+        stringify(&json_value).map_err(mlua::Error::external)
+    })?;
+    toml_table.set("stringify", stringify_fn)?;
+
+    Ok(toml_table)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_toml_module() {
+        let lua = Lua::new();
+        let toml = create_toml_module(&lua).unwrap();
+        assert!(toml.contains_key("parse").unwrap());
+        assert!(toml.contains_key("stringify").unwrap());
+    }
+
+    #[test]
+    fn test_parse_and_stringify_from_lua() {
+        let lua = Lua::new();
+        let toml = create_toml_module(&lua).unwrap();
+        lua.globals().set("toml", toml).unwrap();
+
+        let (name, text): (String, String) = lua
+            .load(
+                r#"
+                local doc = toml.parse('[package]\nname = "demo"\n[package.metadata]\ntags = ["a"]\n')
+                return doc.package.name, toml.stringify(doc)
+            "#,
+            )
+            .eval()
+            .unwrap();
+        assert_eq!(name, "demo");
+        assert!(text.contains("[package.metadata]"), "{}", text);
+    }
+}
@@ -0,0 +1,67 @@
+pub mod error;
+pub mod lua_bindings;
+pub mod operations;
+
+use serde_json::{json, Value as JsonValue};
+
+use super::BuiltinModule;
+use crate::error::HypeError;
+
+pub use lua_bindings::create_toml_module;
+
+pub struct TomlModule;
+
+impl TomlModule {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for TomlModule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BuiltinModule for TomlModule {
+    fn name(&self) -> &str {
+        "toml"
+    }
+
+    fn exports(&self) -> std::result::Result<JsonValue, HypeError> {
+        Ok(json!({
+            "__id": "toml",
+            "__desc": "TOML parsing and serialization",
+            "parse": {
+                "__fn": "parse",
+                "__desc": "Parse a TOML document into a table",
+                "__signature": "parse(str: string) -> table"
+            },
+            "stringify": {
+                "__fn": "stringify",
+                "__desc": "Serialize a table to a TOML document",
+                "__signature": "stringify(table: table) -> string"
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_toml_module_name() {
+        let module = TomlModule::new();
+        assert_eq!(module.name(), "toml");
+    }
+
+    #[test]
+    fn test_toml_module_exports() {
+        let module = TomlModule::new();
+        let exports = module.exports().unwrap();
+        assert!(exports.is_object());
+        assert!(exports.get("parse").is_some());
+        assert!(exports.get("stringify").is_some());
+    }
+}
@@ -0,0 +1,150 @@
+use super::error::TomlError;
+use serde_json::Value as JsonValue;
+use toml::Value as TomlValue;
+
+pub type Result<T> = std::result::Result<T, TomlError>;
+
+/// Parse a TOML document into a JSON object.
+///
+/// Dates and times are returned as their RFC 3339 strings.
+pub fn parse(input: &str) -> Result<JsonValue> {
+    let table: toml::Table = input
+        .parse()
+        .map_err(|e: toml::de::Error| TomlError::ParseError(e.to_string()))?;
+    toml_to_json(TomlValue::Table(table))
+}
+
+/// Serialize a JSON object as a TOML document. Nested objects become
+/// `[section]` headers and arrays of objects become `[[array]]` tables.
+pub fn stringify(value: &JsonValue) -> Result<String> {
+    match json_to_toml(value, "")? {
+        TomlValue::Table(table) => {
+            toml::to_string(&table).map_err(|e| TomlError::SerializationError(e.to_string()))
+        }
+        _ => Err(TomlError::UnsupportedValue(
+            "a TOML document must be a table".to_string(),
+        )),
+    }
+}
+
+fn toml_to_json(value: TomlValue) -> Result<JsonValue> {
+    match value {
+        TomlValue::String(s) => Ok(JsonValue::String(s)),
+        TomlValue::Integer(i) => Ok(JsonValue::from(i)),
+        TomlValue::Float(f) => serde_json::Number::from_f64(f)
+            .map(JsonValue::Number)
+            .ok_or_else(|| TomlError::UnsupportedValue(format!("non-finite number {}", f))),
+        TomlValue::Boolean(b) => Ok(JsonValue::Bool(b)),
+        TomlValue::Datetime(dt) => Ok(JsonValue::String(dt.to_string())),
+        TomlValue::Array(items) => items
+            .into_iter()
+            .map(toml_to_json)
+            .collect::<Result<Vec<_>>>()
+            .map(JsonValue::Array),
+        TomlValue::Table(table) => {
+            let mut map = serde_json::Map::new();
+            for (key, value) in table {
+                map.insert(key, toml_to_json(value)?);
+            }
+            Ok(JsonValue::Object(map))
+        }
+    }
+}
+
+fn json_to_toml(value: &JsonValue, path: &str) -> Result<TomlValue> {
+    match value {
+        JsonValue::Null => Err(TomlError::UnsupportedValue(format!(
+            "'{}' is nil; TOML has no null",
+            if path.is_empty() { "<root>" } else { path }
+        ))),
+        JsonValue::Bool(b) => Ok(TomlValue::Boolean(*b)),
+        JsonValue::Number(n) => match n.as_i64() {
+            Some(i) => Ok(TomlValue::Integer(i)),
+            None => Ok(TomlValue::Float(n.as_f64().unwrap_or(f64::NAN))),
+        },
+        JsonValue::String(s) => Ok(TomlValue::String(s.clone())),
+        JsonValue::Array(items) => items
+            .iter()
+            .enumerate()
+            .map(|(i, item)| json_to_toml(item, &format!("{}[{}]", path, i + 1)))
+            .collect::<Result<Vec<_>>>()
+            .map(TomlValue::Array),
+        JsonValue::Object(map) => {
+            let mut table = toml::Table::new();
+            for (key, value) in map {
+                let child = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", path, key)
+                };
+                table.insert(key.clone(), json_to_toml(value, &child)?);
+            }
+            Ok(TomlValue::Table(table))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const CARGO: &str = r#"
+[package]
+name = "hype-rs"
+version = "0.1.0"
+edition = "2021"
+
+[package.metadata.docs]
+all-features = true
+
+[dependencies]
+mlua = { version = "0.9", features = ["lua54"] }
+
+[[bin]]
+name = "hype"
+path = "src/main.rs"
+"#;
+
+    #[test]
+    fn test_parse_package_section() {
+        let value = parse(CARGO).unwrap();
+        assert_eq!(value["package"]["name"], "hype-rs");
+        assert_eq!(value["package"]["metadata"]["docs"]["all-features"], true);
+        assert_eq!(value["dependencies"]["mlua"]["features"], json!(["lua54"]));
+        assert_eq!(
+            value["bin"],
+            json!([{"name": "hype", "path": "src/main.rs"}])
+        );
+    }
+
+    #[test]
+    fn test_round_trip_preserves_structure() {
+        let value = parse(CARGO).unwrap();
+        let text = stringify(&value).unwrap();
+        assert!(text.contains("[package]"), "{}", text);
+        assert!(text.contains("[[bin]]"), "{}", text);
+        assert_eq!(parse(&text).unwrap(), value);
+    }
+
+    #[test]
+    fn test_parse_datetime_as_string() {
+        let value = parse("released = 2024-05-01T12:30:00Z").unwrap();
+        assert_eq!(value["released"], "2024-05-01T12:30:00Z");
+    }
+
+    #[test]
+    fn test_parse_error_has_position() {
+        let err = parse("[package]\nname = \n").unwrap_err().to_string();
+        assert!(err.contains("line 2, column"), "{}", err);
+    }
+
+    #[test]
+    fn test_stringify_rejects_non_tables_and_nulls() {
+        assert!(stringify(&json!([1, 2])).is_err());
+        let err = stringify(&json!({"a": {"b": null}}))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("a.b"), "{}", err);
+    }
+}
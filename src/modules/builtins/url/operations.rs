@@ -90,6 +90,9 @@ pub fn format(components: UrlComponents) -> Result<String, UrlError> {
     Ok(url_str)
 }
 
+/// Resolve `relative` against `base` following RFC 3986 section 5.2, as
+/// implemented by `Url::join`: dot segments, scheme-relative `//host`,
+/// query-only `?x` and fragment-only `#y` references.
 pub fn resolve(base: &str, relative: &str) -> Result<String, UrlError> {
     let base_url = Url::parse(base).map_err(|e| UrlError::ParseError(e.to_string()))?;
 
@@ -182,6 +185,71 @@ mod tests {
         assert_eq!(result, "https://example.com/baz");
     }
 
+    /// RFC 3986 section 5.4 reference resolution examples.
+    #[test]
+    fn test_resolve_rfc3986_examples() {
+        let base = "http://a/b/c/d;p?q";
+        let cases = [
+            // 5.4.1 normal examples
+            ("g:h", "g:h"),
+            ("g", "http://a/b/c/g"),
+            ("./g", "http://a/b/c/g"),
+            ("g/", "http://a/b/c/g/"),
+            ("/g", "http://a/g"),
+            // Empty paths of http URLs serialize as "/"
+            ("//g", "http://g/"),
+            ("?y", "http://a/b/c/d;p?y"),
+            ("g?y", "http://a/b/c/g?y"),
+            ("#s", "http://a/b/c/d;p?q#s"),
+            ("g#s", "http://a/b/c/g#s"),
+            ("g?y#s", "http://a/b/c/g?y#s"),
+            (";x", "http://a/b/c/;x"),
+            ("g;x", "http://a/b/c/g;x"),
+            ("g;x?y#s", "http://a/b/c/g;x?y#s"),
+            ("", "http://a/b/c/d;p?q"),
+            (".", "http://a/b/c/"),
+            ("./", "http://a/b/c/"),
+            ("..", "http://a/b/"),
+            ("../", "http://a/b/"),
+            ("../g", "http://a/b/g"),
+            ("../..", "http://a/"),
+            ("../../", "http://a/"),
+            ("../../g", "http://a/g"),
+            // 5.4.2 abnormal examples
+            ("../../../g", "http://a/g"),
+            ("../../../../g", "http://a/g"),
+            ("/./g", "http://a/g"),
+            ("/../g", "http://a/g"),
+            ("g.", "http://a/b/c/g."),
+            (".g", "http://a/b/c/.g"),
+            ("g..", "http://a/b/c/g.."),
+            ("..g", "http://a/b/c/..g"),
+            ("./../g", "http://a/b/g"),
+            ("./g/.", "http://a/b/c/g/"),
+            ("g/./h", "http://a/b/c/g/h"),
+            ("g/../h", "http://a/b/c/h"),
+            ("g;x=1/./y", "http://a/b/c/g;x=1/y"),
+            ("g;x=1/../y", "http://a/b/c/y"),
+            ("g?y/./x", "http://a/b/c/g?y/./x"),
+            ("g?y/../x", "http://a/b/c/g?y/../x"),
+            ("g#s/./x", "http://a/b/c/g#s/./x"),
+            ("g#s/../x", "http://a/b/c/g#s/../x"),
+            // The RFC's backward-compatible reading of a same-scheme reference
+            ("http:g", "http://a/b/c/g"),
+        ];
+
+        for (reference, expected) in cases {
+            assert_eq!(
+                resolve(base, reference).unwrap(),
+                expected,
+                "resolve({:?}, {:?})",
+                base,
+                reference
+            );
+        }
+        assert_eq!(resolve("http://a/b/c", "../d").unwrap(), "http://a/d");
+    }
+
     #[test]
     fn test_encode() {
         assert_eq!(encode("hello world"), "hello+world");
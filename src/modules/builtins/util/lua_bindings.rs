@@ -0,0 +1,372 @@
+use super::operations::*;
+use crate::lua::require::lua_to_json;
+use crate::modules::builtins::json::operations::encode;
+use mlua::{Function, Lua, MultiValue, RegistryKey, Result as LuaResult, Table, Value};
+use serde_json::Value as JsonValue;
+use std::cell::RefCell;
+use std::time::{Duration, Instant};
+
+/// `inherits`, `deprecate` and `promisify` wrap Lua functions, so they are
+/// written in Lua. Globals are captured up front in case scripts replace them.
+const UTIL_HELPERS: &str = r#"
+local util, warn = ...
+local setmetatable, rawset, pack, unpack, error = setmetatable, rawset, table.pack, table.unpack, error
+
+function util.inherits(ctor, super)
+    assert(type(ctor) == "table" and type(super) == "table", "inherits expects two tables")
+    rawset(ctor, "super_", super)
+    return setmetatable(ctor, { __index = super })
+end
+
+function util.deprecate(fn, message)
+    local warned = false
+    return function(...)
+        if not warned then
+            warned = true
+            warn(message)
+        end
+        return fn(...)
+    end
+end
+
+function util.promisify(fn)
+    return function(...)
+        local outcome
+        local args = pack(...)
+        args[args.n + 1] = function(...) outcome = pack(...) end
+        fn(unpack(args, 1, args.n + 1))
+        if outcome == nil then
+            error("promisified function did not call its callback", 2)
+        end
+        if outcome[1] ~= nil then
+            error(outcome[1], 2)
+        end
+        return unpack(outcome, 2, outcome.n)
+    end
+end
+"#;
+
+/// Runs of `fn` measured by `util.bench` when no count is given.
+const DEFAULT_BENCH_ITERATIONS: u32 = 100;
+
+pub fn create_util_module(lua: &Lua) -> LuaResult<Table<'_>> {
+    let util = lua.create_table()?;
+
+    let format_fn = lua.create_function(|lua, (template, args): (Value, MultiValue)| {
+        format_values(lua, template, args.into_iter().collect())
+    })?;
+    util.set("format", format_fn)?;
+
+    let inspect_fn = lua.create_function(|_, (value, options): (Value, Option<Table>)| {
+        let mut inspect_options = InspectOptions::default();
+        if let Some(depth) = options
+            .map(|opts| opts.get::<_, Option<usize>>("depth"))
+            .transpose()?
+            .flatten()
+        {
+            inspect_options.depth = depth;
+        }
+        inspect(&value, &inspect_options)
+    })?;
+    util.set("inspect", inspect_fn)?;
+
+    let time_fn = lua.create_function(|lua, (label, func): (String, Function)| {
+        let start = Instant::now();
+        let results: MultiValue = func.call(())?;
+        let print: Function = lua.globals().get("print")?;
+        print.call::<_, ()>(format_elapsed(&label, start.elapsed()))?;
+        Ok(results)
+    })?;
+    util.set("time", time_fn)?;
+
+    let bench_fn = lua.create_function(|lua, (func, iterations): (Function, Option<u32>)| {
+        let iterations = iterations.unwrap_or(DEFAULT_BENCH_ITERATIONS);
+        if iterations == 0 {
+            return Err(mlua::Error::RuntimeError(
+                "bench iterations must be at least 1".to_string(),
+            ));
+        }
+        let mut samples = Vec::with_capacity(iterations as usize);
+        for _ in 0..iterations {
+            let start = Instant::now();
+            func.call::<_, ()>(())?;
+            samples.push(start.elapsed());
+        }
+        let stats = bench_stats(&samples).expect("at least one sample");
+        let result = lua.create_table()?;
+        result.set("min", duration_ms(stats.min))?;
+        result.set("max", duration_ms(stats.max))?;
+        result.set("mean", duration_ms(stats.mean))?;
+        result.set("iterations", iterations)?;
+        Ok(result)
+    })?;
+    util.set("bench", bench_fn)?;
+
+    let memoize_fn = lua.create_function(|lua, (func, options): (Function, Option<Table>)| {
+        let (max_size, ttl) = parse_memoize_options(options)?;
+        let func = lua.create_registry_value(func)?;
+        let cache: RefCell<MemoCache<Vec<RegistryKey>>> =
+            RefCell::new(MemoCache::new(max_size, ttl));
+
+        lua.create_function(move |lua, args: MultiValue| {
+            let key = memo_key(lua, &args)?;
+            if let Some(results) = cache.borrow_mut().get(&key, Instant::now()) {
+                return results
+                    .iter()
+                    .map(|result| lua.registry_value::<Value>(result))
+                    .collect::<LuaResult<MultiValue>>();
+            }
+
+            // The cache is not borrowed during the call, so `func` may call
+            // its memoized self
+            let results: MultiValue = lua.registry_value::<Function>(&func)?.call(args)?;
+            let stored = results
+                .iter()
+                .map(|result| lua.create_registry_value(result.clone()))
+                .collect::<LuaResult<Vec<_>>>()?;
+            cache.borrow_mut().insert(key, stored, Instant::now());
+            Ok(results)
+        })
+    })?;
+    util.set("memoize", memoize_fn)?;
+
+    let warn_fn = lua.create_function(|_, message: String| {
+        eprintln!("DeprecationWarning: {}", message);
+        Ok(())
+    })?;
+    lua.load(UTIL_HELPERS)
+        .set_name("=util")
+        .call::<_, ()>((util.clone(), warn_fn))?;
+
+    Ok(util)
+}
+
+/// `maxSize` and `ttl` (in seconds) from the options of `util.memoize`.
+fn parse_memoize_options(options: Option<Table>) -> LuaResult<(Option<usize>, Option<Duration>)> {
+    let Some(options) = options else {
+        return Ok((None, None));
+    };
+    let max_size = match options.get::<_, Option<i64>>("maxSize")? {
+        Some(size) if size >= 1 => Some(size as usize),
+        Some(_) => {
+            return Err(mlua::Error::RuntimeError(
+                "memoize maxSize must be at least 1".to_string(),
+            ))
+        }
+        None => None,
+    };
+    let ttl = match options.get::<_, Option<f64>>("ttl")? {
+        Some(seconds) if seconds.is_finite() && seconds > 0.0 => {
+            Some(Duration::from_secs_f64(seconds))
+        }
+        Some(_) => {
+            return Err(mlua::Error::RuntimeError(
+                "memoize ttl must be a positive number of seconds".to_string(),
+            ))
+        }
+        None => None,
+    };
+    Ok((max_size, ttl))
+}
+
+/// The cache key for a call: its arguments as a JSON array.
+fn memo_key(lua: &Lua, args: &MultiValue) -> LuaResult<String> {
+    let values = args
+        .iter()
+        .map(|arg| lua_to_json(lua, arg.clone()))
+        .collect::<LuaResult<Vec<_>>>()
+        .map_err(|e| {
+            mlua::Error::RuntimeError(format!(
+                "memoized function arguments must be JSON-serializable: {}",
+                e
+            ))
+        })?;
+    encode(&JsonValue::Array(values)).map_err(mlua::Error::external)
+}
+
+/// `util.format(template, ...)`: substitute directives, then append leftovers.
+pub fn format_values(lua: &Lua, template: Value, args: Vec<Value>) -> LuaResult<String> {
+    let template = match template {
+        Value::String(s) => s.to_str()?.to_string(),
+        other => {
+            // Like Node: with no template every argument is just appended
+            let all = std::iter::once(other).chain(args).collect::<Vec<_>>();
+            return append_rest(lua, String::new(), &all);
+        }
+    };
+
+    let mut output = String::new();
+    let mut next = 0;
+    for piece in parse_template(&template) {
+        match piece {
+            Piece::Text(text) => output.push_str(text),
+            Piece::Directive(directive, text) => match args.get(next) {
+                Some(value) => {
+                    output.push_str(&format_directive(lua, directive, value)?);
+                    next += 1;
+                }
+                None => output.push_str(text),
+            },
+        }
+    }
+    append_rest(lua, output, &args[next.min(args.len())..])
+}
+
+fn format_directive(lua: &Lua, directive: Directive, value: &Value) -> LuaResult<String> {
+    match directive {
+        Directive::String => to_display_string(lua, value),
+        Directive::Integer => Ok(format_integer(to_number(lua, value)?)),
+        Directive::Float => Ok(format_float(to_number(lua, value)?)),
+        Directive::Json => {
+            let json = lua_to_json(lua, value.clone())?;
+            encode(&json).map_err(mlua::Error::external)
+        }
+        Directive::Inspect => inspect(value, &InspectOptions { depth: 4 }),
+    }
+}
+
+/// Arguments without a matching directive are appended, space separated.
+fn append_rest(lua: &Lua, mut output: String, rest: &[Value]) -> LuaResult<String> {
+    for value in rest {
+        if !output.is_empty() {
+            output.push(' ');
+        }
+        output.push_str(&to_display_string(lua, value)?);
+    }
+    Ok(output)
+}
+
+/// Strings as-is, plain tables inspected, anything else through `tostring`.
+fn to_display_string(lua: &Lua, value: &Value) -> LuaResult<String> {
+    match value {
+        Value::String(s) => Ok(s.to_string_lossy().to_string()),
+        Value::Table(table) if !has_tostring(table) => inspect(value, &InspectOptions::default()),
+        other => {
+            let tostring: Function = lua.globals().get("tostring")?;
+            tostring.call(other.clone())
+        }
+    }
+}
+
+fn has_tostring(table: &Table) -> bool {
+    table
+        .get_metatable()
+        .map(|mt| mt.contains_key("__tostring").unwrap_or(false))
+        .unwrap_or(false)
+}
+
+fn to_number(lua: &Lua, value: &Value) -> LuaResult<Option<f64>> {
+    match value {
+        Value::Integer(i) => Ok(Some(*i as f64)),
+        Value::Number(n) => Ok(Some(*n)),
+        Value::String(_) => lua.coerce_number(value.clone()),
+        _ => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lua_with_util() -> Lua {
+        let lua = Lua::new();
+        let util = create_util_module(&lua).unwrap();
+        lua.globals().set("util", util).unwrap();
+        lua
+    }
+
+    #[test]
+    fn test_create_util_module() {
+        let lua = Lua::new();
+        let util = create_util_module(&lua).unwrap();
+        for name in [
+            "format",
+            "inspect",
+            "time",
+            "bench",
+            "inherits",
+            "deprecate",
+            "promisify",
+        ] {
+            assert!(util.contains_key(name).unwrap(), "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_format_json_directive() {
+        let lua = lua_with_util();
+        let output: String = lua
+            .load(r#"return util.format("payload: %j", {id = 7, tags = {"a", "b"}})"#)
+            .eval()
+            .unwrap();
+
+        let json = output.strip_prefix("payload: ").unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(parsed, serde_json::json!({"id": 7, "tags": ["a", "b"]}));
+    }
+
+    #[test]
+    fn test_format_inspect_directive_and_extra_args() {
+        let lua = lua_with_util();
+        let output: String = lua
+            .load(r#"return util.format("%s has %d items: %o", "cart", 2.7, {1, "x", ok = true}, "extra", 5)"#)
+            .eval()
+            .unwrap();
+        assert_eq!(output, r#"cart has 2 items: { 1, "x", ok = true } extra 5"#);
+
+        let output: String = lua
+            .load(r#"return util.format("%s and %s", "one")"#)
+            .eval()
+            .unwrap();
+        assert_eq!(output, "one and %s");
+    }
+
+    #[test]
+    fn test_promisify_and_inherits() {
+        let lua = lua_with_util();
+        let (sum, err, greeting): (i64, String, String) = lua
+            .load(
+                r#"
+                local add = util.promisify(function(a, b, cb) cb(nil, a + b) end)
+                local fail = util.promisify(function(cb) cb("boom") end)
+                local ok, err = pcall(fail)
+
+                local Base = {}
+                function Base.greet() return "hello" end
+                local Child = util.inherits({}, Base)
+                return add(2, 3), err, Child.greet()
+            "#,
+            )
+            .eval()
+            .unwrap();
+        assert_eq!(sum, 5);
+        assert!(err.contains("boom"));
+        assert_eq!(greeting, "hello");
+    }
+
+    #[test]
+    fn test_time_returns_results_and_bench_reports_stats() {
+        let lua = lua_with_util();
+        let printed: String = lua
+            .load(
+                r#"
+                local lines = {}
+                print = function(line) lines[#lines + 1] = line end
+                local a, b = util.time("sum", function() return 1 + 2, "done" end)
+                assert(a == 3 and b == "done")
+
+                local stats = util.bench(function() local x = 0 for i = 1, 10 do x = x + i end end, 20)
+                assert(stats.iterations == 20)
+                assert(stats.min >= 0 and stats.min <= stats.mean and stats.mean <= stats.max)
+                assert(util.bench(function() end).iterations == 100)
+                assert(not pcall(util.bench, function() end, 0))
+                assert(not pcall(util.time, "fails", function() error("boom") end))
+                return lines[1]
+            "#,
+            )
+            .eval()
+            .unwrap();
+        assert!(printed.starts_with("sum: "), "{}", printed);
+        assert!(printed.ends_with("ms"), "{}", printed);
+    }
+}
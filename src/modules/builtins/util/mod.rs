@@ -1,8 +1,13 @@
+pub mod lua_bindings;
+pub mod operations;
+
 use serde_json::{json, Value as JsonValue};
 
 use super::BuiltinModule;
 use crate::error::HypeError;
 
+pub use lua_bindings::create_util_module;
+
 /// Utility functions module
 pub struct UtilModule;
 
@@ -27,23 +32,43 @@ impl BuiltinModule for UtilModule {
         Ok(json!({
             "inspect": {
                 "__fn": "inspect",
-                "__desc": "Convert value to string representation"
+                "__desc": "Convert value to string representation",
+                "__signature": "inspect(value: any, options?: {depth?: number}) -> string"
             },
             "format": {
                 "__fn": "format",
-                "__desc": "Format string with arguments"
+                "__desc": "Format string with %s, %d, %i, %f, %j (JSON) and %o (inspect)",
+                "__signature": "format(template: string, ...any) -> string"
+            },
+            "time": {
+                "__fn": "time",
+                "__desc": "Run a function, print how long it took as 'label: Xms' and return its results",
+                "__signature": "time(label: string, fn: function) -> ...any"
+            },
+            "bench": {
+                "__fn": "bench",
+                "__desc": "Run a function repeatedly and report min, max and mean milliseconds",
+                "__signature": "bench(fn: function, iterations?: number) -> {min: number, max: number, mean: number, iterations: number}"
+            },
+            "memoize": {
+                "__fn": "memoize",
+                "__desc": "Wrap a function so results are cached by its JSON-serialized arguments, with optional LRU size limit and expiry in seconds",
+                "__signature": "memoize(fn: function, options?: {maxSize?: number, ttl?: number}) -> function"
             },
             "promisify": {
                 "__fn": "promisify",
-                "__desc": "Convert callback-based function to promise"
+                "__desc": "Convert a callback-last function into one that returns its result or raises its error",
+                "__signature": "promisify(fn: function) -> function"
             },
             "inherits": {
                 "__fn": "inherits",
-                "__desc": "Set prototype inheritance"
+                "__desc": "Set prototype inheritance",
+                "__signature": "inherits(ctor: table, super: table) -> table"
             },
             "deprecate": {
                 "__fn": "deprecate",
-                "__desc": "Mark function as deprecated"
+                "__desc": "Mark function as deprecated",
+                "__signature": "deprecate(fn: function, message: string) -> function"
             }
         }))
     }
@@ -92,7 +117,16 @@ mod tests {
         let module = UtilModule::new();
         let exports = module.exports().unwrap();
 
-        let functions = vec!["inspect", "format", "promisify", "inherits", "deprecate"];
+        let functions = vec![
+            "inspect",
+            "format",
+            "time",
+            "bench",
+            "memoize",
+            "promisify",
+            "inherits",
+            "deprecate",
+        ];
 
         for func in functions {
             assert!(exports.get(func).is_some(), "Missing function: {}", func);
@@ -0,0 +1,424 @@
+use mlua::{Table, Value};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::ffi::c_void;
+use std::time::{Duration, Instant};
+
+/// A `%` conversion in a `util.format` template.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Directive {
+    /// `%s`
+    String,
+    /// `%d` or `%i`
+    Integer,
+    /// `%f`
+    Float,
+    /// `%j`
+    Json,
+    /// `%o` or `%O`
+    Inspect,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Piece<'a> {
+    Text(&'a str),
+    Directive(Directive, &'a str),
+}
+
+/// Split a format template into literal text and directives. `%%` becomes a
+/// literal `%`; unknown conversions are kept as text.
+pub fn parse_template(template: &str) -> Vec<Piece<'_>> {
+    let mut pieces = Vec::new();
+    let mut literal_start = 0;
+    let mut chars = template.char_indices().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        if c != '%' {
+            continue;
+        }
+        let Some(&(j, spec)) = chars.peek() else {
+            break;
+        };
+        let directive = match spec {
+            's' => Some(Directive::String),
+            'd' | 'i' => Some(Directive::Integer),
+            'f' => Some(Directive::Float),
+            'j' => Some(Directive::Json),
+            'o' | 'O' => Some(Directive::Inspect),
+            '%' => None,
+            _ => continue,
+        };
+        chars.next();
+
+        if literal_start < i {
+            pieces.push(Piece::Text(&template[literal_start..i]));
+        }
+        let end = j + spec.len_utf8();
+        match directive {
+            Some(directive) => pieces.push(Piece::Directive(directive, &template[i..end])),
+            None => pieces.push(Piece::Text("%")),
+        }
+        literal_start = end;
+    }
+
+    if literal_start < template.len() {
+        pieces.push(Piece::Text(&template[literal_start..]));
+    }
+    pieces
+}
+
+/// `%d`: the number truncated toward zero, or `NaN`.
+pub fn format_integer(value: Option<f64>) -> String {
+    match value {
+        Some(n) if n.is_finite() => format!("{}", n.trunc() as i64),
+        Some(n) if n.is_infinite() => if n > 0.0 { "Infinity" } else { "-Infinity" }.to_string(),
+        _ => "NaN".to_string(),
+    }
+}
+
+/// `%f`: the number as a float, or `NaN`.
+pub fn format_float(value: Option<f64>) -> String {
+    match value {
+        Some(n) if n.is_nan() => "NaN".to_string(),
+        Some(n) if n.is_infinite() => if n > 0.0 { "Infinity" } else { "-Infinity" }.to_string(),
+        Some(n) => format!("{}", n),
+        None => "NaN".to_string(),
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct InspectOptions {
+    /// How many levels of nested tables to expand before printing `{...}`.
+    pub depth: usize,
+}
+
+impl Default for InspectOptions {
+    fn default() -> Self {
+        Self { depth: 2 }
+    }
+}
+
+/// Render a Lua value as a single-line, Lua-like literal for debugging.
+///
+/// Array items come first, then the remaining keys in sorted order so the
+/// output is stable. Tables that contain themselves print as `<cycle>`.
+pub fn inspect(value: &Value, options: &InspectOptions) -> mlua::Result<String> {
+    let mut seen = HashSet::new();
+    inspect_value(value, options.depth, &mut seen)
+}
+
+fn inspect_value(
+    value: &Value,
+    depth: usize,
+    seen: &mut HashSet<*const c_void>,
+) -> mlua::Result<String> {
+    Ok(match value {
+        Value::Nil => "nil".to_string(),
+        Value::Boolean(b) => b.to_string(),
+        Value::Integer(i) => i.to_string(),
+        Value::Number(n) => format_number(*n),
+        Value::String(s) => format!("{:?}", s.to_string_lossy()),
+        Value::Table(table) => inspect_table(table, depth, seen)?,
+        Value::Function(_) => "<function>".to_string(),
+        Value::Thread(_) => "<thread>".to_string(),
+        Value::UserData(_) => "<userdata>".to_string(),
+        Value::LightUserData(_) => "<lightuserdata>".to_string(),
+        Value::Error(e) => format!("<error: {}>", e),
+    })
+}
+
+fn inspect_table(
+    table: &Table,
+    depth: usize,
+    seen: &mut HashSet<*const c_void>,
+) -> mlua::Result<String> {
+    let pointer = table.to_pointer();
+    if seen.contains(&pointer) {
+        return Ok("<cycle>".to_string());
+    }
+
+    let length = table.raw_len();
+    let mut keyed = Vec::new();
+    for pair in table.clone().pairs::<Value, Value>() {
+        let (key, value) = pair?;
+        let in_array = match key {
+            Value::Integer(i) => i >= 1 && (i as usize) <= length,
+            _ => false,
+        };
+        if !in_array {
+            keyed.push((key, value));
+        }
+    }
+    if length == 0 && keyed.is_empty() {
+        return Ok("{}".to_string());
+    }
+    if depth == 0 {
+        return Ok("{...}".to_string());
+    }
+
+    seen.insert(pointer);
+    let mut items = Vec::with_capacity(length + keyed.len());
+    for i in 1..=length {
+        let value: Value = table.raw_get(i)?;
+        items.push(inspect_value(&value, depth - 1, seen)?);
+    }
+    let mut fields = keyed
+        .iter()
+        .map(|(key, value)| {
+            Ok((
+                format_key(key, seen)?,
+                inspect_value(value, depth - 1, seen)?,
+            ))
+        })
+        .collect::<mlua::Result<Vec<_>>>()?;
+    fields.sort();
+    items.extend(
+        fields
+            .into_iter()
+            .map(|(key, value)| format!("{} = {}", key, value)),
+    );
+    seen.remove(&pointer);
+
+    Ok(format!("{{ {} }}", items.join(", ")))
+}
+
+fn format_key(key: &Value, seen: &mut HashSet<*const c_void>) -> mlua::Result<String> {
+    if let Value::String(s) = key {
+        let name = s.to_string_lossy();
+        if is_identifier(&name) {
+            return Ok(name.to_string());
+        }
+    }
+    Ok(format!("[{}]", inspect_value(key, 0, seen)?))
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Floats as Lua's `tostring` prints them (`1.0`, `0.5`, `inf`, `nan`).
+fn format_number(n: f64) -> String {
+    if n.is_nan() {
+        "nan".to_string()
+    } else if n.is_infinite() {
+        if n > 0.0 { "inf" } else { "-inf" }.to_string()
+    } else if n.fract() == 0.0 && n.abs() < 1e16 {
+        format!("{:.1}", n)
+    } else {
+        n.to_string()
+    }
+}
+
+/// Fastest, slowest and average run reported by `util.bench`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BenchStats {
+    pub min: Duration,
+    pub max: Duration,
+    pub mean: Duration,
+}
+
+/// Summarise timed runs, or `None` when there are none.
+pub fn bench_stats(samples: &[Duration]) -> Option<BenchStats> {
+    let min = *samples.iter().min()?;
+    let max = *samples.iter().max()?;
+    let total: Duration = samples.iter().sum();
+    Some(BenchStats {
+        min,
+        max,
+        mean: total / samples.len() as u32,
+    })
+}
+
+/// Milliseconds with microsecond precision, as Lua numbers.
+pub fn duration_ms(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+/// The line `util.time` prints: `label: 1.234ms`.
+pub fn format_elapsed(label: &str, elapsed: Duration) -> String {
+    format!("{}: {:.3}ms", label, duration_ms(elapsed))
+}
+
+/// Results cached by `util.memoize`, keyed by the serialized arguments.
+/// When `max_size` is set the least recently used entry is evicted to make
+/// room, and entries older than `ttl` are treated as missing. Callers pass
+/// `now` from a monotonic clock.
+pub struct MemoCache<V> {
+    entries: HashMap<String, MemoEntry<V>>,
+    /// Entry keys by the tick they were last used at, oldest first.
+    recency: BTreeMap<u64, String>,
+    tick: u64,
+    max_size: Option<usize>,
+    ttl: Option<Duration>,
+}
+
+struct MemoEntry<V> {
+    value: V,
+    stored_at: Instant,
+    last_used: u64,
+}
+
+impl<V> MemoCache<V> {
+    pub fn new(max_size: Option<usize>, ttl: Option<Duration>) -> Self {
+        Self {
+            entries: HashMap::new(),
+            recency: BTreeMap::new(),
+            tick: 0,
+            max_size,
+            ttl,
+        }
+    }
+
+    /// The cached value for `key`, marking it as recently used. An expired
+    /// entry is removed and reported as missing.
+    pub fn get(&mut self, key: &str, now: Instant) -> Option<&V> {
+        let expired = {
+            let entry = self.entries.get(key)?;
+            self.ttl
+                .is_some_and(|ttl| now.duration_since(entry.stored_at) >= ttl)
+        };
+        if expired {
+            self.remove(key);
+            return None;
+        }
+
+        self.tick += 1;
+        let entry = self.entries.get_mut(key)?;
+        self.recency.remove(&entry.last_used);
+        self.recency.insert(self.tick, key.to_string());
+        entry.last_used = self.tick;
+        Some(&entry.value)
+    }
+
+    /// Store `value` under `key`, evicting the least recently used entry if
+    /// the cache is full.
+    pub fn insert(&mut self, key: String, value: V, now: Instant) {
+        self.remove(&key);
+        if let Some(max_size) = self.max_size {
+            while self.entries.len() >= max_size {
+                let Some((_, oldest)) = self.recency.pop_first() else {
+                    break;
+                };
+                self.entries.remove(&oldest);
+            }
+        }
+
+        self.tick += 1;
+        self.recency.insert(self.tick, key.clone());
+        self.entries.insert(
+            key,
+            MemoEntry {
+                value,
+                stored_at: now,
+                last_used: self.tick,
+            },
+        );
+    }
+
+    fn remove(&mut self, key: &str) {
+        if let Some(entry) = self.entries.remove(key) {
+            self.recency.remove(&entry.last_used);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mlua::Lua;
+
+    #[test]
+    fn test_parse_template() {
+        assert_eq!(
+            parse_template("%s is %d%% done %q"),
+            vec![
+                Piece::Directive(Directive::String, "%s"),
+                Piece::Text(" is "),
+                Piece::Directive(Directive::Integer, "%d"),
+                Piece::Text("%"),
+                Piece::Text(" done %q"),
+            ]
+        );
+        assert_eq!(
+            parse_template("trailing %"),
+            vec![Piece::Text("trailing %")]
+        );
+    }
+
+    #[test]
+    fn test_format_numbers() {
+        assert_eq!(format_integer(Some(42.9)), "42");
+        assert_eq!(format_integer(Some(-3.5)), "-3");
+        assert_eq!(format_integer(None), "NaN");
+        assert_eq!(format_float(Some(1.5)), "1.5");
+        assert_eq!(format_float(Some(f64::INFINITY)), "Infinity");
+    }
+
+    #[test]
+    fn test_inspect_nested_and_cyclic_tables() {
+        let lua = Lua::new();
+        let value: Value = lua
+            .load(
+                r#"
+                local t = {1, "two", name = "x", ["with space"] = true, nested = {deep = {deeper = {}}}}
+                t.self = t
+                return t
+            "#,
+            )
+            .eval()
+            .unwrap();
+
+        assert_eq!(
+            inspect(&value, &InspectOptions::default()).unwrap(),
+            r#"{ 1, "two", ["with space"] = true, name = "x", nested = { deep = {...} }, self = <cycle> }"#
+        );
+        assert_eq!(
+            inspect(&value, &InspectOptions { depth: 0 }).unwrap(),
+            "{...}"
+        );
+    }
+
+    #[test]
+    fn test_bench_stats_and_elapsed_format() {
+        assert_eq!(bench_stats(&[]), None);
+        let stats = bench_stats(&[
+            Duration::from_millis(3),
+            Duration::from_millis(1),
+            Duration::from_millis(2),
+        ])
+        .unwrap();
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.max, Duration::from_millis(3));
+        assert_eq!(stats.mean, Duration::from_millis(2));
+        assert_eq!(
+            format_elapsed("load", Duration::from_micros(1500)),
+            "load: 1.500ms"
+        );
+    }
+
+    #[test]
+    fn test_memo_cache_evicts_least_recently_used() {
+        let now = Instant::now();
+        let mut cache = MemoCache::new(Some(2), None);
+        cache.insert("a".to_string(), 1, now);
+        cache.insert("b".to_string(), 2, now);
+        assert_eq!(cache.get("a", now), Some(&1));
+
+        cache.insert("c".to_string(), 3, now);
+        assert_eq!(cache.entries.len(), 2);
+        assert_eq!(cache.get("b", now), None);
+        assert_eq!(cache.get("a", now), Some(&1));
+        assert_eq!(cache.get("c", now), Some(&3));
+    }
+
+    #[test]
+    fn test_memo_cache_expires_after_ttl() {
+        let start = Instant::now();
+        let mut cache = MemoCache::new(None, Some(Duration::from_secs(10)));
+        cache.insert("a".to_string(), 1, start);
+        assert_eq!(cache.get("a", start + Duration::from_secs(9)), Some(&1));
+        assert_eq!(cache.get("a", start + Duration::from_secs(10)), None);
+        assert!(cache.entries.is_empty());
+    }
+}
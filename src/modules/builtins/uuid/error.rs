@@ -0,0 +1,42 @@
+use crate::error::StructuredError;
+use std::fmt;
+
+#[derive(Debug)]
+pub enum UuidError {
+    InvalidUuid(String),
+    InvalidNamespace(String),
+}
+
+impl fmt::Display for UuidError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UuidError::InvalidUuid(input) => write!(f, "Invalid UUID: '{}'", input),
+            UuidError::InvalidNamespace(input) => {
+                write!(f, "Invalid namespace: '{}' is not a UUID", input)
+            }
+        }
+    }
+}
+
+impl std::error::Error for UuidError {}
+
+impl UuidError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            UuidError::InvalidUuid(_) => "ERR_INVALID_UUID",
+            UuidError::InvalidNamespace(_) => "ERR_INVALID_NAMESPACE",
+        }
+    }
+}
+
+impl From<UuidError> for StructuredError {
+    fn from(err: UuidError) -> Self {
+        StructuredError::new("uuid", err.code(), err.to_string())
+    }
+}
+
+impl From<UuidError> for crate::error::HypeError {
+    fn from(err: UuidError) -> Self {
+        crate::error::HypeError::Execution(err.to_string())
+    }
+}
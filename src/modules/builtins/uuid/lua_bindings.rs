@@ -0,0 +1,72 @@
+use super::operations::*;
+use crate::lua::error::{structured_error, wrap_module_errors};
+use mlua::{Lua, Result as LuaResult, String as LuaString, Table};
+
+pub fn create_uuid_module(lua: &Lua) -> LuaResult<Table<'_>> {
+    let uuid = lua.create_table()?;
+
+    uuid.set("v4", lua.create_function(|_, ()| Ok(v4()))?)?;
+    uuid.set("v7", lua.create_function(|_, ()| Ok(v7()))?)?;
+    // `nil` is a Lua keyword, so scripts call this as uuid["nil"]() or use NIL
+    uuid.set("nil", lua.create_function(|_, ()| Ok(nil()))?)?;
+    uuid.set("NIL", nil())?;
+
+    let v5_fn = lua.create_function(|_, (namespace, name): (String, LuaString)| {
+        v5(&namespace, name.as_bytes()).map_err(structured_error)
+    })?;
+    uuid.set("v5", v5_fn)?;
+
+    let parse_fn =
+        lua.create_function(|_, input: String| parse(&input).map_err(structured_error))?;
+    uuid.set("parse", parse_fn)?;
+
+    let validate_fn = lua.create_function(|_, input: String| Ok(parse(&input).is_ok()))?;
+    uuid.set("validate", validate_fn)?;
+
+    let version_fn =
+        lua.create_function(|_, input: String| version(&input).map_err(structured_error))?;
+    uuid.set("version", version_fn)?;
+
+    for (name, namespace) in NAMESPACES {
+        uuid.set(format!("NAMESPACE_{}", name), namespace.to_string())?;
+    }
+
+    wrap_module_errors(lua, &uuid)?;
+    Ok(uuid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_uuid_module() {
+        let lua = Lua::new();
+        let uuid = create_uuid_module(&lua).unwrap();
+        for name in ["v4", "v5", "v7", "nil", "parse", "validate", "version"] {
+            assert!(uuid.contains_key(name).unwrap(), "{}", name);
+        }
+        assert!(uuid.contains_key("NAMESPACE_DNS").unwrap());
+        assert!(uuid.contains_key("NIL").unwrap());
+    }
+
+    #[test]
+    fn test_errors_are_structured() {
+        let lua = Lua::new();
+        lua.globals()
+            .set("uuid", create_uuid_module(&lua).unwrap())
+            .unwrap();
+        lua.load(
+            r#"
+local ok, err = pcall(uuid.parse, "nope")
+assert(not ok and err.kind == "uuid" and err.code == "ERR_INVALID_UUID", tostring(err))
+
+ok, err = pcall(uuid.v5, "nope", "name")
+assert(not ok and err.code == "ERR_INVALID_NAMESPACE", tostring(err))
+assert(uuid.validate("nope") == false)
+"#,
+        )
+        .exec()
+        .unwrap();
+    }
+}
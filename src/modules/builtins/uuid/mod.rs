@@ -0,0 +1,115 @@
+pub mod error;
+pub mod lua_bindings;
+pub mod operations;
+
+use serde_json::{json, Value as JsonValue};
+
+use super::BuiltinModule;
+use crate::error::HypeError;
+
+pub use error::UuidError;
+pub use lua_bindings::create_uuid_module;
+
+pub struct UuidModule;
+
+impl UuidModule {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for UuidModule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BuiltinModule for UuidModule {
+    fn name(&self) -> &str {
+        "uuid"
+    }
+
+    fn exports(&self) -> std::result::Result<JsonValue, HypeError> {
+        Ok(json!({
+            "__id": "uuid",
+            "__desc": "UUID generation, parsing and validation",
+            "v4": {
+                "__fn": "v4",
+                "__desc": "Random UUID",
+                "__signature": "v4() -> string"
+            },
+            "v5": {
+                "__fn": "v5",
+                "__desc": "Name-based UUID (SHA-1), deterministic for a namespace and name",
+                "__signature": "v5(namespace: string, name: string) -> string"
+            },
+            "v7": {
+                "__fn": "v7",
+                "__desc": "Time-ordered UUID that sorts by creation time",
+                "__signature": "v7() -> string"
+            },
+            "nil": {
+                "__fn": "nil",
+                "__desc": "The all-zero UUID (call as uuid[\"nil\"]() since nil is a keyword)",
+                "__signature": "nil() -> string"
+            },
+            "NIL": {
+                "__value": "string",
+                "__desc": "The all-zero UUID",
+            },
+            "parse": {
+                "__fn": "parse",
+                "__desc": "Validate a UUID and return its canonical lowercase hyphenated form",
+                "__signature": "parse(input: string) -> string"
+            },
+            "validate": {
+                "__fn": "validate",
+                "__desc": "Check whether a string is a valid UUID",
+                "__signature": "validate(input: string) -> boolean"
+            },
+            "version": {
+                "__fn": "version",
+                "__desc": "Version number of a UUID (0 for the nil UUID)",
+                "__signature": "version(input: string) -> number"
+            },
+            "NAMESPACE_DNS": {
+                "__value": "string",
+                "__desc": "RFC 4122 namespace for domain names",
+            },
+            "NAMESPACE_URL": {
+                "__value": "string",
+                "__desc": "RFC 4122 namespace for URLs",
+            },
+            "NAMESPACE_OID": {
+                "__value": "string",
+                "__desc": "RFC 4122 namespace for ISO OIDs",
+            },
+            "NAMESPACE_X500": {
+                "__value": "string",
+                "__desc": "RFC 4122 namespace for X.500 DNs",
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_uuid_module_name() {
+        let module = UuidModule::new();
+        assert_eq!(module.name(), "uuid");
+    }
+
+    #[test]
+    fn test_uuid_module_exports() {
+        let module = UuidModule::new();
+        let exports = module.exports().unwrap();
+        assert!(exports.get("v4").is_some());
+        assert!(exports.get("v5").is_some());
+        assert!(exports.get("v7").is_some());
+        assert!(exports.get("parse").is_some());
+        assert!(exports.get("nil").is_some());
+    }
+}
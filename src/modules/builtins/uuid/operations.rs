@@ -0,0 +1,100 @@
+use super::UuidError;
+use uuid::Uuid;
+
+/// The RFC 4122 namespaces for `v5`, by the names scripts see them under.
+pub const NAMESPACES: [(&str, Uuid); 4] = [
+    ("DNS", Uuid::NAMESPACE_DNS),
+    ("URL", Uuid::NAMESPACE_URL),
+    ("OID", Uuid::NAMESPACE_OID),
+    ("X500", Uuid::NAMESPACE_X500),
+];
+
+pub fn v4() -> String {
+    Uuid::new_v4().to_string()
+}
+
+/// Name-based UUID: the same namespace and name always give the same id.
+pub fn v5(namespace: &str, name: &[u8]) -> Result<String, UuidError> {
+    let namespace = Uuid::try_parse(namespace)
+        .map_err(|_| UuidError::InvalidNamespace(namespace.to_string()))?;
+    Ok(Uuid::new_v5(&namespace, name).to_string())
+}
+
+/// Time-ordered UUID: ids created later sort after earlier ones.
+pub fn v7() -> String {
+    Uuid::now_v7().to_string()
+}
+
+pub fn nil() -> String {
+    Uuid::nil().to_string()
+}
+
+/// Validate `input` (hyphenated, simple, braced or `urn:uuid:` form) and
+/// return it in lowercase hyphenated form.
+pub fn parse(input: &str) -> Result<String, UuidError> {
+    Uuid::try_parse(input.trim())
+        .map(|uuid| uuid.to_string())
+        .map_err(|_| UuidError::InvalidUuid(input.to_string()))
+}
+
+/// The version number of a valid UUID (0 for the nil UUID).
+pub fn version(input: &str) -> Result<usize, UuidError> {
+    let uuid =
+        Uuid::try_parse(input.trim()).map_err(|_| UuidError::InvalidUuid(input.to_string()))?;
+    Ok(uuid.get_version_num())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_v5_is_deterministic() {
+        let dns = Uuid::NAMESPACE_DNS.to_string();
+        let first = v5(&dns, b"example.com").unwrap();
+        assert_eq!(first, v5(&dns, b"example.com").unwrap());
+        assert_eq!(first, "cfbff0d1-9375-5685-968c-48ce8b15ae17");
+        assert_ne!(first, v5(&dns, b"example.org").unwrap());
+        assert!(matches!(
+            v5("not-a-namespace", b"x"),
+            Err(UuidError::InvalidNamespace(_))
+        ));
+    }
+
+    #[test]
+    fn test_v4_and_v7() {
+        assert_ne!(v4(), v4());
+        assert_eq!(version(&v4()).unwrap(), 4);
+
+        let earlier = v7();
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        let later = v7();
+        assert_eq!(version(&later).unwrap(), 7);
+        assert!(earlier < later, "{} !< {}", earlier, later);
+    }
+
+    #[test]
+    fn test_parse_canonicalizes_and_rejects_malformed() {
+        let canonical = "67e55044-10b1-426f-9247-bb680e5fe0c8";
+        for input in [
+            "67E55044-10B1-426F-9247-BB680E5FE0C8",
+            "67e5504410b1426f9247bb680e5fe0c8",
+            "{67e55044-10b1-426f-9247-bb680e5fe0c8}",
+            "urn:uuid:67e55044-10b1-426f-9247-bb680e5fe0c8",
+        ] {
+            assert_eq!(parse(input).unwrap(), canonical, "{}", input);
+        }
+        for input in [
+            "",
+            "67e55044-10b1-426f-9247",
+            "67e55044-10b1-426f-9247-bb680e5fe0cz",
+        ] {
+            assert!(
+                matches!(parse(input), Err(UuidError::InvalidUuid(_))),
+                "{}",
+                input
+            );
+        }
+        assert_eq!(version(&nil()).unwrap(), 0);
+    }
+}
@@ -0,0 +1,52 @@
+use crate::error::StructuredError;
+use std::fmt;
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum WorkerError {
+    NotFound(String),
+    Spawn(String),
+    /// The worker script raised an error or returned a value that is not JSON.
+    Failed(String),
+    Timeout(Duration),
+}
+
+impl fmt::Display for WorkerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WorkerError::NotFound(path) => write!(f, "Worker script not found: {}", path),
+            WorkerError::Spawn(err) => write!(f, "Failed to start worker: {}", err),
+            WorkerError::Failed(err) => write!(f, "Worker failed: {}", err),
+            WorkerError::Timeout(timeout) => write!(
+                f,
+                "Worker did not finish within {:.3}s",
+                timeout.as_secs_f64()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for WorkerError {}
+
+impl WorkerError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            WorkerError::NotFound(_) => "ENOENT",
+            WorkerError::Spawn(_) => "ERR_SPAWN",
+            WorkerError::Failed(_) => "ERR_WORKER_FAILED",
+            WorkerError::Timeout(_) => "ETIMEDOUT",
+        }
+    }
+}
+
+impl From<WorkerError> for StructuredError {
+    fn from(err: WorkerError) -> Self {
+        StructuredError::new("worker", err.code(), err.to_string())
+    }
+}
+
+impl From<WorkerError> for crate::error::HypeError {
+    fn from(err: WorkerError) -> Self {
+        crate::error::HypeError::Execution(err.to_string())
+    }
+}
@@ -0,0 +1,88 @@
+use super::operations::{spawn_worker, WorkerContext, WorkerHandle};
+use crate::engine::output::StdoutRedirect;
+use crate::lua::error::{structured_error, wrap_module_errors};
+use crate::lua::require::lua_to_json;
+use crate::lua::security::check_file_access;
+use crate::lua::SecurityPolicy;
+use crate::modules::builtins::json::lua_bindings::json_to_lua;
+use mlua::{Lua, Result as LuaResult, Table, UserData, UserDataMethods, Value as LuaValue};
+use std::path::PathBuf;
+use std::time::Duration;
+
+pub fn create_worker_module(lua: &Lua) -> LuaResult<Table<'_>> {
+    let worker = lua.create_table()?;
+
+    let spawn_fn = lua.create_function(|lua, (path, data): (String, LuaValue)| {
+        check_file_access(lua, &path, "read").map_err(mlua::Error::external)?;
+        let data = lua_to_json(lua, data)?;
+        let context = WorkerContext {
+            policy: lua.app_data_ref::<SecurityPolicy>().map(|p| p.clone()),
+            stdout: lua.app_data_ref::<StdoutRedirect>().map(|r| r.clone()),
+        };
+        spawn_worker(PathBuf::from(path), data, context).map_err(structured_error)
+    })?;
+    worker.set("spawn", spawn_fn)?;
+
+    wrap_module_errors(lua, &worker)?;
+    Ok(worker)
+}
+
+impl UserData for WorkerHandle {
+    fn add_methods<'lua, M: UserDataMethods<'lua, Self>>(methods: &mut M) {
+        methods.add_method_mut("join", |lua, this, timeout: Option<f64>| {
+            let timeout = match timeout {
+                Some(seconds) if seconds.is_finite() && seconds >= 0.0 => {
+                    Some(Duration::from_secs_f64(seconds))
+                }
+                Some(_) => {
+                    return Err(mlua::Error::external(
+                        "join timeout must be a non-negative number of seconds",
+                    ))
+                }
+                None => None,
+            };
+            let result = this.join(timeout).map_err(mlua::Error::external)?;
+            json_to_lua(lua, &result)
+        });
+
+        methods.add_method_mut("isDone", |_, this, ()| Ok(this.is_done()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_worker_module_functions() {
+        let lua = Lua::new();
+        let worker = create_worker_module(&lua).unwrap();
+        assert!(worker.contains_key("spawn").unwrap());
+    }
+
+    #[test]
+    fn test_spawn_and_join() {
+        let mut file = tempfile::Builder::new().suffix(".lua").tempfile().unwrap();
+        file.write_all(b"return { greeting = 'hello ' .. workerData.name }")
+            .unwrap();
+
+        let lua = Lua::new();
+        lua.globals()
+            .set("worker", create_worker_module(&lua).unwrap())
+            .unwrap();
+        lua.globals()
+            .set("script", file.path().to_string_lossy().to_string())
+            .unwrap();
+        let greeting: String = lua
+            .load(
+                r#"
+                local handle = worker.spawn(script, { name = "worker" })
+                return handle:join().greeting
+            "#,
+            )
+            .eval()
+            .unwrap();
+        assert_eq!(greeting, "hello worker");
+    }
+}
@@ -0,0 +1,61 @@
+pub mod error;
+pub mod lua_bindings;
+pub mod operations;
+
+use serde_json::{json, Value as JsonValue};
+
+use super::BuiltinModule;
+use crate::error::HypeError;
+
+pub use lua_bindings::create_worker_module;
+
+pub struct WorkerModule;
+
+impl WorkerModule {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for WorkerModule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BuiltinModule for WorkerModule {
+    fn name(&self) -> &str {
+        "worker"
+    }
+
+    fn exports(&self) -> std::result::Result<JsonValue, HypeError> {
+        Ok(json!({
+            "__id": "worker",
+            "__desc": "Run Lua scripts on separate threads and collect their results",
+            "spawn": {
+                "__fn": "spawn",
+                "__desc": "Run a script on a new thread with its own Lua state; data is passed as the chunk argument and the workerData global. The handle's join(timeoutSeconds?) returns the script's JSON-compatible result, and isDone() reports whether it has finished",
+                "__signature": "spawn(path: string, data?: any) -> WorkerHandle"
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_worker_module_name() {
+        let module = WorkerModule::new();
+        assert_eq!(module.name(), "worker");
+    }
+
+    #[test]
+    fn test_worker_module_exports() {
+        let module = WorkerModule::new();
+        let exports = module.exports().unwrap();
+        assert_eq!(exports["__id"], "worker");
+        assert!(exports.get("spawn").is_some());
+    }
+}
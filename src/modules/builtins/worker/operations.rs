@@ -0,0 +1,251 @@
+use super::error::WorkerError;
+use crate::engine::output::StdoutRedirect;
+use crate::lua::require::lua_to_json;
+use crate::lua::{
+    create_cli_config, format_print_line, setup_require_fn, LuaStateLifecycleManager,
+    SecurityPolicy,
+};
+use crate::modules::builtins::json::lua_bindings::json_to_lua;
+use crate::modules::loader::ModuleLoader;
+use mlua::{Lua, MultiValue, Value as LuaValue};
+use serde_json::Value as JsonValue;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// How long a worker's state may sit idle before the lifecycle manager
+/// considers it stale. Workers remove their state as soon as they finish.
+const WORKER_STATE_IDLE: Duration = Duration::from_secs(60);
+
+type WorkerResult = Result<JsonValue, WorkerError>;
+
+/// What a worker thread runs with, copied from the state that spawned it.
+#[derive(Clone, Default)]
+pub struct WorkerContext {
+    pub policy: Option<SecurityPolicy>,
+    pub stdout: Option<StdoutRedirect>,
+}
+
+/// A script running on its own thread. The result is kept once received, so
+/// joining again returns the same value.
+pub struct WorkerHandle {
+    receiver: Receiver<WorkerResult>,
+    result: Option<WorkerResult>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl WorkerHandle {
+    /// Wait for the worker's result, giving up after `timeout` if one is set.
+    /// A worker that times out keeps running and can be joined again.
+    pub fn join(&mut self, timeout: Option<Duration>) -> WorkerResult {
+        if self.result.is_none() {
+            let received = match timeout {
+                Some(timeout) => match self.receiver.recv_timeout(timeout) {
+                    Ok(result) => result,
+                    Err(RecvTimeoutError::Timeout) => return Err(WorkerError::Timeout(timeout)),
+                    Err(RecvTimeoutError::Disconnected) => Err(exited_without_result()),
+                },
+                None => self
+                    .receiver
+                    .recv()
+                    .unwrap_or_else(|_| Err(exited_without_result())),
+            };
+            if let Some(thread) = self.thread.take() {
+                let _ = thread.join();
+            }
+            self.result = Some(received);
+        }
+        self.result.clone().expect("result was just stored")
+    }
+
+    /// Whether the worker has finished, without waiting for it.
+    pub fn is_done(&mut self) -> bool {
+        if self.result.is_some() {
+            return true;
+        }
+        match self.receiver.try_recv() {
+            Ok(result) => {
+                self.result = Some(result);
+                true
+            }
+            Err(mpsc::TryRecvError::Empty) => false,
+            Err(mpsc::TryRecvError::Disconnected) => {
+                self.result = Some(Err(exited_without_result()));
+                true
+            }
+        }
+    }
+}
+
+fn exited_without_result() -> WorkerError {
+    WorkerError::Failed("worker thread exited without a result".to_string())
+}
+
+/// Run the Lua script at `script` on a new thread with `data` as its
+/// argument and `workerData` global. The script's return value is sent back
+/// as JSON.
+pub fn spawn_worker(
+    script: PathBuf,
+    data: JsonValue,
+    context: WorkerContext,
+) -> Result<WorkerHandle, WorkerError> {
+    if !script.is_file() {
+        return Err(WorkerError::NotFound(script.display().to_string()));
+    }
+
+    let (sender, receiver) = mpsc::channel();
+    let name = format!("hype-worker:{}", script.display());
+    let thread = thread::Builder::new()
+        .name(name)
+        .spawn(move || {
+            let _ = sender.send(run_worker(&script, data, context));
+        })
+        .map_err(|e| WorkerError::Spawn(e.to_string()))?;
+
+    Ok(WorkerHandle {
+        receiver,
+        result: None,
+        thread: Some(thread),
+    })
+}
+
+/// The body of a worker thread. Lua states cannot move between threads, so
+/// each worker creates its own through a lifecycle manager and removes it
+/// once the script has finished.
+fn run_worker(script: &Path, data: JsonValue, context: WorkerContext) -> WorkerResult {
+    let source = std::fs::read_to_string(script).map_err(|e| WorkerError::Failed(e.to_string()))?;
+
+    let lifecycle = LuaStateLifecycleManager::new(WORKER_STATE_IDLE, WORKER_STATE_IDLE);
+    let id = lifecycle
+        .create_state(create_cli_config(false, false, None), false)
+        .map_err(|e| WorkerError::Spawn(e.to_string()))?;
+    let state = lifecycle
+        .get_state(id)
+        .map_err(|e| WorkerError::Spawn(e.to_string()))?;
+
+    let result = {
+        let manager = state.lock().unwrap();
+        let lua = manager.lua.lock().unwrap();
+        prepare_state(&lua, script, &context)
+            .and_then(|()| run_script(&lua, script, &source, data))
+            .map_err(|e| WorkerError::Failed(error_message(&e)))
+    };
+
+    drop(state);
+    let _ = lifecycle.remove_state(id);
+    result
+}
+
+fn prepare_state(lua: &Lua, script: &Path, context: &WorkerContext) -> mlua::Result<()> {
+    if let Some(policy) = &context.policy {
+        policy.install(lua);
+    }
+
+    let cwd = std::env::current_dir().map_err(mlua::Error::external)?;
+    let mut loader = ModuleLoader::new(cwd);
+    loader.set_entry(script.display().to_string());
+    setup_require_fn(lua, Arc::new(Mutex::new(loader))).map_err(mlua::Error::external)?;
+
+    // Share the parent's output capture so worker prints stay in order with its own
+    if let Some(redirect) = &context.stdout {
+        let capture = Arc::clone(&redirect.0);
+        let print = lua.create_function(move |lua, values: MultiValue| {
+            let line = format_print_line(lua, values, false)?;
+            if let Ok(capture) = capture.lock() {
+                capture.capture_print(&line);
+            }
+            Ok(())
+        })?;
+        lua.globals().set("print", print)?;
+        lua.set_app_data(redirect.clone());
+    }
+    Ok(())
+}
+
+fn run_script(lua: &Lua, script: &Path, source: &str, data: JsonValue) -> mlua::Result<JsonValue> {
+    let data = json_to_lua(lua, &data)?;
+    lua.globals().set("workerData", data.clone())?;
+    let result: LuaValue = lua
+        .load(source)
+        .set_name(format!("@{}", script.display()))
+        .call(data)?;
+    lua_to_json(lua, result)
+}
+
+/// The first line of a Lua error, without mlua's "runtime error" prefix or
+/// the stack traceback.
+fn error_message(err: &mlua::Error) -> String {
+    let message = match err {
+        mlua::Error::RuntimeError(message) => message.clone(),
+        mlua::Error::CallbackError { cause, .. } => return error_message(cause),
+        other => other.to_string(),
+    };
+    message.lines().next().unwrap_or_default().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::io::Write;
+
+    fn script(source: &str) -> tempfile::NamedTempFile {
+        let mut file = tempfile::Builder::new().suffix(".lua").tempfile().unwrap();
+        file.write_all(source.as_bytes()).unwrap();
+        file
+    }
+
+    #[test]
+    fn test_worker_returns_json_result() {
+        let file = script("local n = ... return { doubled = n * 2, same = workerData == n }");
+        let mut handle = spawn_worker(
+            file.path().to_path_buf(),
+            json!(21),
+            WorkerContext::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            handle.join(None).unwrap(),
+            json!({ "doubled": 42, "same": true })
+        );
+        assert!(handle.is_done());
+        assert_eq!(handle.join(None).unwrap()["doubled"], json!(42));
+    }
+
+    #[test]
+    fn test_worker_errors() {
+        let missing = spawn_worker(
+            PathBuf::from("/nonexistent/worker.lua"),
+            JsonValue::Null,
+            WorkerContext::default(),
+        );
+        assert!(matches!(missing, Err(WorkerError::NotFound(_))));
+
+        let file = script("error('boom')");
+        let mut handle = spawn_worker(
+            file.path().to_path_buf(),
+            JsonValue::Null,
+            WorkerContext::default(),
+        )
+        .unwrap();
+        match handle.join(None) {
+            Err(WorkerError::Failed(message)) => assert!(message.ends_with("boom"), "{}", message),
+            other => panic!("expected a failure, got {:?}", other),
+        }
+
+        let file = script("local t = os.clock() + 0.5 while os.clock() < t do end");
+        let mut handle = spawn_worker(
+            file.path().to_path_buf(),
+            JsonValue::Null,
+            WorkerContext::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            handle.join(Some(Duration::from_millis(10))),
+            Err(WorkerError::Timeout(Duration::from_millis(10)))
+        );
+        assert!(!handle.is_done());
+    }
+}
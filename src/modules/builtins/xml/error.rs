@@ -0,0 +1,54 @@
+use crate::error::StructuredError;
+use std::fmt;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum XmlError {
+    /// The input is not well-formed XML. `line` and `column` are 1-based.
+    Parse {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    /// A table passed to `stringify` is not a valid element tree.
+    InvalidTree(String),
+}
+
+impl fmt::Display for XmlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            XmlError::Parse {
+                line,
+                column,
+                message,
+            } => write!(
+                f,
+                "Invalid XML at line {}, column {}: {}",
+                line, column, message
+            ),
+            XmlError::InvalidTree(message) => write!(f, "Invalid XML tree: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for XmlError {}
+
+impl XmlError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            XmlError::Parse { .. } => "ERR_INVALID_XML",
+            XmlError::InvalidTree(_) => "ERR_INVALID_TREE",
+        }
+    }
+}
+
+impl From<XmlError> for StructuredError {
+    fn from(err: XmlError) -> Self {
+        StructuredError::new("xml", err.code(), err.to_string())
+    }
+}
+
+impl From<XmlError> for crate::error::HypeError {
+    fn from(err: XmlError) -> Self {
+        crate::error::HypeError::Execution(err.to_string())
+    }
+}
@@ -0,0 +1,179 @@
+use super::error::XmlError;
+use super::operations::{parse, stringify, Element};
+use crate::lua::error::{structured_error, wrap_module_errors};
+use mlua::{Lua, Result as LuaResult, Table, Value as LuaValue};
+
+/// Deepest tree `stringify` accepts, so a table that contains itself is an
+/// error rather than a stack overflow.
+const MAX_DEPTH: usize = 256;
+
+pub fn create_xml_module(lua: &Lua) -> LuaResult<Table<'_>> {
+    let xml = lua.create_table()?;
+
+    let parse_fn = lua.create_function(|lua, input: String| {
+        let root = parse(&input).map_err(structured_error)?;
+        element_to_lua(lua, &root)
+    })?;
+    xml.set("parse", parse_fn)?;
+
+    let stringify_fn = lua.create_function(|_, tree: Table| {
+        let root = element_from_lua(&tree, 0).map_err(structured_error)?;
+        stringify(&root).map_err(structured_error)
+    })?;
+    xml.set("stringify", stringify_fn)?;
+
+    wrap_module_errors(lua, &xml)?;
+    Ok(xml)
+}
+
+fn element_to_lua<'lua>(lua: &'lua Lua, element: &Element) -> LuaResult<Table<'lua>> {
+    let table = lua.create_table()?;
+    table.set("tag", element.tag.as_str())?;
+
+    let attributes = lua.create_table()?;
+    for (name, value) in &element.attributes {
+        attributes.set(name.as_str(), value.as_str())?;
+    }
+    table.set("attributes", attributes)?;
+
+    let children = lua.create_table()?;
+    for (i, child) in element.children.iter().enumerate() {
+        children.set(i + 1, element_to_lua(lua, child)?)?;
+    }
+    table.set("children", children)?;
+
+    table.set("text", element.text.as_str())?;
+    Ok(table)
+}
+
+fn element_from_lua(table: &Table, depth: usize) -> Result<Element, XmlError> {
+    if depth > MAX_DEPTH {
+        return Err(XmlError::InvalidTree(format!(
+            "elements are nested more than {} deep",
+            MAX_DEPTH
+        )));
+    }
+    let invalid = |message: String| XmlError::InvalidTree(message);
+
+    let tag = match table.get::<_, LuaValue>("tag") {
+        Ok(LuaValue::String(tag)) => tag.to_string_lossy().into_owned(),
+        Ok(other) => {
+            return Err(invalid(format!(
+                "element tag must be a string, not {}",
+                other.type_name()
+            )))
+        }
+        Err(e) => return Err(invalid(e.to_string())),
+    };
+    let mut element = Element::new(tag);
+
+    match table.get::<_, LuaValue>("attributes") {
+        Ok(LuaValue::Nil) => {}
+        Ok(LuaValue::Table(attributes)) => {
+            for pair in attributes.pairs::<String, LuaValue>() {
+                let (name, value) = pair.map_err(|e| invalid(e.to_string()))?;
+                let value = scalar_text(&value).ok_or_else(|| {
+                    invalid(format!(
+                        "attribute '{}' of <{}> must be a string or number, not {}",
+                        name,
+                        element.tag,
+                        value.type_name()
+                    ))
+                })?;
+                element.attributes.insert(name, value);
+            }
+        }
+        Ok(other) => {
+            return Err(invalid(format!(
+                "attributes of <{}> must be a table, not {}",
+                element.tag,
+                other.type_name()
+            )))
+        }
+        Err(e) => return Err(invalid(e.to_string())),
+    }
+
+    match table.get::<_, LuaValue>("children") {
+        Ok(LuaValue::Nil) => {}
+        Ok(LuaValue::Table(children)) => {
+            for child in children.sequence_values::<LuaValue>() {
+                match child.map_err(|e| invalid(e.to_string()))? {
+                    LuaValue::Table(child) => {
+                        element.children.push(element_from_lua(&child, depth + 1)?)
+                    }
+                    other => {
+                        return Err(invalid(format!(
+                            "children of <{}> must be element tables, not {}",
+                            element.tag,
+                            other.type_name()
+                        )))
+                    }
+                }
+            }
+        }
+        Ok(other) => {
+            return Err(invalid(format!(
+                "children of <{}> must be a table, not {}",
+                element.tag,
+                other.type_name()
+            )))
+        }
+        Err(e) => return Err(invalid(e.to_string())),
+    }
+
+    match table.get::<_, LuaValue>("text") {
+        Ok(LuaValue::Nil) => {}
+        Ok(value) => {
+            element.text = scalar_text(&value).ok_or_else(|| {
+                invalid(format!(
+                    "text of <{}> must be a string or number, not {}",
+                    element.tag,
+                    value.type_name()
+                ))
+            })?
+        }
+        Err(e) => return Err(invalid(e.to_string())),
+    }
+
+    Ok(element)
+}
+
+fn scalar_text(value: &LuaValue) -> Option<String> {
+    match value {
+        LuaValue::String(s) => Some(s.to_string_lossy().into_owned()),
+        LuaValue::Integer(n) => Some(n.to_string()),
+        LuaValue::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_xml_module_functions() {
+        let lua = Lua::new();
+        let xml = create_xml_module(&lua).unwrap();
+        assert!(xml.contains_key("parse").unwrap());
+        assert!(xml.contains_key("stringify").unwrap());
+    }
+
+    #[test]
+    fn test_parse_error_code() {
+        let lua = Lua::new();
+        lua.globals()
+            .set("xml", create_xml_module(&lua).unwrap())
+            .unwrap();
+        let code: String = lua
+            .load(
+                r#"
+                local ok, err = pcall(xml.parse, "<a><b></a>")
+                return err.code
+            "#,
+            )
+            .eval()
+            .unwrap();
+        assert_eq!(code, "ERR_INVALID_XML");
+    }
+}
@@ -0,0 +1,67 @@
+pub mod error;
+pub mod lua_bindings;
+pub mod operations;
+
+use serde_json::{json, Value as JsonValue};
+
+use super::BuiltinModule;
+use crate::error::HypeError;
+
+pub use lua_bindings::create_xml_module;
+
+pub struct XmlModule;
+
+impl XmlModule {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for XmlModule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BuiltinModule for XmlModule {
+    fn name(&self) -> &str {
+        "xml"
+    }
+
+    fn exports(&self) -> std::result::Result<JsonValue, HypeError> {
+        Ok(json!({
+            "__id": "xml",
+            "__desc": "Parse XML documents into element trees and serialize them back",
+            "parse": {
+                "__fn": "parse",
+                "__desc": "Parse an XML document into its root element; text and CDATA are joined into text and whitespace between elements is dropped",
+                "__signature": "parse(xml: string) -> {tag: string, attributes: table, children: table[], text: string}"
+            },
+            "stringify": {
+                "__fn": "stringify",
+                "__desc": "Serialize an element tree to XML, with attributes in name order and empty elements self-closed",
+                "__signature": "stringify(tree: {tag: string, attributes?: table, children?: table[], text?: string}) -> string"
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_xml_module_name() {
+        let module = XmlModule::new();
+        assert_eq!(module.name(), "xml");
+    }
+
+    #[test]
+    fn test_xml_module_exports() {
+        let module = XmlModule::new();
+        let exports = module.exports().unwrap();
+        assert_eq!(exports["__id"], "xml");
+        assert!(exports.get("parse").is_some());
+        assert!(exports.get("stringify").is_some());
+    }
+}
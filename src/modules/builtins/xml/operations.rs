@@ -0,0 +1,227 @@
+use super::error::XmlError;
+use quick_xml::escape::escape;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use std::collections::BTreeMap;
+
+/// An element with its attributes, child elements and the text directly
+/// inside it. Text and CDATA sections are joined into `text`; whitespace
+/// between elements is dropped.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Element {
+    pub tag: String,
+    pub attributes: BTreeMap<String, String>,
+    pub children: Vec<Element>,
+    pub text: String,
+}
+
+impl Element {
+    pub fn new(tag: impl Into<String>) -> Self {
+        Self {
+            tag: tag.into(),
+            ..Self::default()
+        }
+    }
+}
+
+/// Parse a document into its root element. The XML declaration, comments,
+/// processing instructions and DOCTYPE are skipped.
+pub fn parse(input: &str) -> Result<Element, XmlError> {
+    let mut reader = Reader::from_str(input);
+    let mut stack: Vec<Element> = Vec::new();
+    let mut root: Option<Element> = None;
+
+    loop {
+        let start = reader.buffer_position() as usize;
+        let event = reader
+            .read_event()
+            .map_err(|e| parse_error(input, reader.error_position() as usize, &e.to_string()))?;
+        let fail = |message: &str| parse_error(input, start, message);
+
+        match event {
+            Event::Start(e) => {
+                if root.is_some() {
+                    return Err(fail("content after the root element"));
+                }
+                stack.push(element_from_start(&e).map_err(|m| fail(&m))?);
+            }
+            Event::Empty(e) => {
+                if root.is_some() {
+                    return Err(fail("content after the root element"));
+                }
+                let element = element_from_start(&e).map_err(|m| fail(&m))?;
+                match stack.last_mut() {
+                    Some(parent) => parent.children.push(element),
+                    None => root = Some(element),
+                }
+            }
+            Event::End(_) => {
+                let element = stack.pop().ok_or_else(|| fail("unexpected closing tag"))?;
+                match stack.last_mut() {
+                    Some(parent) => parent.children.push(element),
+                    None => root = Some(element),
+                }
+            }
+            Event::Text(e) => {
+                let text = e.unescape().map_err(|e| fail(&e.to_string()))?;
+                if text.trim().is_empty() {
+                    continue;
+                }
+                match stack.last_mut() {
+                    Some(parent) => parent.text.push_str(&text),
+                    None => return Err(fail("text outside the root element")),
+                }
+            }
+            Event::CData(e) => {
+                let text = e.decode().map_err(|e| fail(&e.to_string()))?;
+                match stack.last_mut() {
+                    Some(parent) => parent.text.push_str(&text),
+                    None => return Err(fail("CDATA outside the root element")),
+                }
+            }
+            Event::Eof => {
+                if let Some(open) = stack.last() {
+                    return Err(fail(&format!("unclosed element <{}>", open.tag)));
+                }
+                return root.ok_or_else(|| fail("document has no root element"));
+            }
+            Event::Decl(_) | Event::PI(_) | Event::Comment(_) | Event::DocType(_) => {}
+        }
+    }
+}
+
+fn element_from_start(start: &BytesStart) -> Result<Element, String> {
+    let tag = String::from_utf8_lossy(start.name().as_ref()).into_owned();
+    let mut element = Element::new(tag);
+    for attribute in start.attributes() {
+        let attribute = attribute.map_err(|e| e.to_string())?;
+        let name = String::from_utf8_lossy(attribute.key.as_ref()).into_owned();
+        let value = attribute.unescape_value().map_err(|e| e.to_string())?;
+        element.attributes.insert(name, value.into_owned());
+    }
+    Ok(element)
+}
+
+/// An error at byte `offset` of `input`, reported as a line and column.
+fn parse_error(input: &str, offset: usize, message: &str) -> XmlError {
+    let mut end = offset.min(input.len());
+    while !input.is_char_boundary(end) {
+        end -= 1;
+    }
+    let before = &input[..end];
+    let line = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    XmlError::Parse {
+        line,
+        column: before[line_start..].chars().count() + 1,
+        message: message.to_string(),
+    }
+}
+
+/// Serialize an element tree. Attributes are written in name order, and an
+/// element without text or children is written as a self-closing tag.
+pub fn stringify(element: &Element) -> Result<String, XmlError> {
+    let mut out = String::new();
+    write_element(element, &mut out)?;
+    Ok(out)
+}
+
+fn write_element(element: &Element, out: &mut String) -> Result<(), XmlError> {
+    check_name(&element.tag, "tag")?;
+    out.push('<');
+    out.push_str(&element.tag);
+    for (name, value) in &element.attributes {
+        check_name(name, "attribute name")?;
+        out.push(' ');
+        out.push_str(name);
+        out.push_str("=\"");
+        out.push_str(&escape(value.as_str()));
+        out.push('"');
+    }
+
+    if element.text.is_empty() && element.children.is_empty() {
+        out.push_str("/>");
+        return Ok(());
+    }
+    out.push('>');
+    out.push_str(&escape(element.text.as_str()));
+    for child in &element.children {
+        write_element(child, out)?;
+    }
+    out.push_str("</");
+    out.push_str(&element.tag);
+    out.push('>');
+    Ok(())
+}
+
+fn check_name(name: &str, what: &str) -> Result<(), XmlError> {
+    let valid_start = |c: char| c.is_alphabetic() || c == '_' || c == ':';
+    let valid = name.chars().next().is_some_and(valid_start)
+        && name
+            .chars()
+            .all(|c| valid_start(c) || c.is_alphanumeric() || c == '-' || c == '.');
+    if valid {
+        Ok(())
+    } else {
+        Err(XmlError::InvalidTree(format!(
+            "{} {:?} is not a valid XML name",
+            what, name
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_attributes_children_and_text() {
+        let root = parse(
+            r#"<?xml version="1.0"?>
+<!-- feed -->
+<rss version="2.0">
+  <channel>
+    <title>News &amp; Views</title>
+    <item id="1"><![CDATA[<b>bold</b>]]></item>
+    <empty flag="yes"/>
+  </channel>
+</rss>"#,
+        )
+        .unwrap();
+
+        assert_eq!(root.tag, "rss");
+        assert_eq!(root.attributes["version"], "2.0");
+        assert_eq!(root.text, "");
+        let channel = &root.children[0];
+        let tags: Vec<&str> = channel.children.iter().map(|c| c.tag.as_str()).collect();
+        assert_eq!(tags, vec!["title", "item", "empty"]);
+        assert_eq!(channel.children[0].text, "News & Views");
+        assert_eq!(channel.children[1].text, "<b>bold</b>");
+        assert_eq!(channel.children[2].attributes["flag"], "yes");
+    }
+
+    #[test]
+    fn test_stringify_round_trips() {
+        let source = r#"<a x="1 &lt; 2"><b>t &amp; u</b><c/><d><![CDATA[x]]></d></a>"#;
+        let root = parse(source).unwrap();
+        let xml = stringify(&root).unwrap();
+        assert_eq!(xml, r#"<a x="1 &lt; 2"><b>t &amp; u</b><c/><d>x</d></a>"#);
+        assert_eq!(parse(&xml).unwrap(), root);
+    }
+
+    #[test]
+    fn test_parse_errors_report_position() {
+        let err = parse("<a>\n  <b></c>\n</a>").unwrap_err();
+        match err {
+            XmlError::Parse { line, .. } => assert_eq!(line, 2),
+            other => panic!("expected a parse error, got {:?}", other),
+        }
+        assert!(matches!(
+            parse("<a><b></b>"),
+            Err(XmlError::Parse { message, .. }) if message == "unclosed element <a>"
+        ));
+        assert!(parse("").is_err());
+        assert!(parse("<a/><b/>").is_err());
+        assert!(stringify(&Element::new("bad tag")).is_err());
+    }
+}
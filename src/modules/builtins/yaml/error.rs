@@ -0,0 +1,26 @@
+use std::fmt;
+
+#[derive(Debug)]
+pub enum YamlError {
+    ParseError(String),
+    SerializationError(String),
+    UnsupportedValue(String),
+}
+
+impl fmt::Display for YamlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            YamlError::ParseError(msg) => write!(f, "YAML parse error: {}", msg),
+            YamlError::SerializationError(msg) => write!(f, "YAML serialization error: {}", msg),
+            YamlError::UnsupportedValue(msg) => write!(f, "Unsupported YAML value: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for YamlError {}
+
+impl From<YamlError> for crate::error::HypeError {
+    fn from(err: YamlError) -> Self {
+        crate::error::HypeError::Execution(err.to_string())
+    }
+}
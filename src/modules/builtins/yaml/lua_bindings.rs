@@ -0,0 +1,74 @@
+use super::operations::*;
+use crate::lua::require::json_to_lua;
+use crate::lua::require::lua_to_json;
+use mlua::{Lua, Table, Value as LuaValue};
+
+pub fn create_yaml_module(lua: &Lua) -> mlua::Result<Table<'_>> {
+    let yaml_table = lua.create_table()?;
+
+    let parse_fn = lua.create_function(|lua, input: String| {
+        let value = parse(&input).map_err(mlua::Error::external)?;
+        json_to_lua(lua, &value).map_err(mlua::Error::external)
+    })?;
+    yaml_table.set("parse", parse_fn)?;
+
+    let stringify_fn = lua.create_function(|lua, value: LuaValue| {
+        let json_value = lua_to_json(lua, value)?;
+        stringify(&json_value).map_err(mlua::Error::external)
+    })?;
+    yaml_table.set("stringify", stringify_fn)?;
+
+    Ok(yaml_table)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lua_with_yaml() -> Lua {
+        let lua = Lua::new();
+        let yaml = create_yaml_module(&lua).unwrap();
+        lua.globals().set("yaml", yaml).unwrap();
+        lua
+    }
+
+    #[test]
+    fn test_create_yaml_module() {
+        let lua = Lua::new();
+        let yaml = create_yaml_module(&lua).unwrap();
+        assert!(yaml.contains_key("parse").unwrap());
+        assert!(yaml.contains_key("stringify").unwrap());
+    }
+
+    #[test]
+    fn test_nested_mapping_round_trip() {
+        let lua = lua_with_yaml();
+        let (host, port, tag, again): (String, i64, String, String) = lua
+            .load(
+                r#"
+                local config = {server = {host = "localhost", port = 8080}, tags = {"a", "b"}}
+                local text = yaml.stringify(config)
+                local parsed = yaml.parse(text)
+                return parsed.server.host, parsed.server.port, parsed.tags[2], yaml.stringify(parsed)
+            "#,
+            )
+            .eval()
+            .unwrap();
+        assert_eq!(
+            (host.as_str(), port, tag.as_str()),
+            ("localhost", 8080, "b")
+        );
+        assert!(again.contains("port: 8080"), "{}", again);
+    }
+
+    #[test]
+    fn test_invalid_document_is_lua_error() {
+        let lua = lua_with_yaml();
+        let err = lua
+            .load(r#"return yaml.parse("a: [1, 2")"#)
+            .exec()
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("YAML parse error"), "{}", err);
+    }
+}
@@ -0,0 +1,67 @@
+pub mod error;
+pub mod lua_bindings;
+pub mod operations;
+
+use serde_json::{json, Value as JsonValue};
+
+use super::BuiltinModule;
+use crate::error::HypeError;
+
+pub use lua_bindings::create_yaml_module;
+
+pub struct YamlModule;
+
+impl YamlModule {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for YamlModule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BuiltinModule for YamlModule {
+    fn name(&self) -> &str {
+        "yaml"
+    }
+
+    fn exports(&self) -> std::result::Result<JsonValue, HypeError> {
+        Ok(json!({
+            "__id": "yaml",
+            "__desc": "YAML parsing and serialization",
+            "parse": {
+                "__fn": "parse",
+                "__desc": "Parse a YAML document into a Lua value",
+                "__signature": "parse(str: string) -> any"
+            },
+            "stringify": {
+                "__fn": "stringify",
+                "__desc": "Serialize a Lua value to YAML",
+                "__signature": "stringify(value: any) -> string"
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_yaml_module_name() {
+        let module = YamlModule::new();
+        assert_eq!(module.name(), "yaml");
+    }
+
+    #[test]
+    fn test_yaml_module_exports() {
+        let module = YamlModule::new();
+        let exports = module.exports().unwrap();
+        assert!(exports.is_object());
+        assert!(exports.get("parse").is_some());
+        assert!(exports.get("stringify").is_some());
+    }
+}
@@ -0,0 +1,126 @@
+use super::error::YamlError;
+use serde_json::Value as JsonValue;
+use serde_yaml::Value as YamlValue;
+
+pub type Result<T> = std::result::Result<T, YamlError>;
+
+/// Parse a single YAML document into a JSON value.
+///
+/// Anchors, aliases and `<<` merge keys are resolved, tags are dropped in
+/// favour of the tagged value, and non-string mapping keys become strings.
+pub fn parse(input: &str) -> Result<JsonValue> {
+    let mut value: YamlValue =
+        serde_yaml::from_str(input).map_err(|e| YamlError::ParseError(e.to_string()))?;
+    value
+        .apply_merge()
+        .map_err(|e| YamlError::ParseError(e.to_string()))?;
+    yaml_to_json(value)
+}
+
+pub fn stringify(value: &JsonValue) -> Result<String> {
+    serde_yaml::to_string(value).map_err(|e| YamlError::SerializationError(e.to_string()))
+}
+
+fn yaml_to_json(value: YamlValue) -> Result<JsonValue> {
+    match value {
+        YamlValue::Null => Ok(JsonValue::Null),
+        YamlValue::Bool(b) => Ok(JsonValue::Bool(b)),
+        YamlValue::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                Ok(JsonValue::from(i))
+            } else if let Some(u) = n.as_u64() {
+                Ok(JsonValue::from(u))
+            } else {
+                let f = n.as_f64().unwrap_or(f64::NAN);
+                serde_json::Number::from_f64(f)
+                    .map(JsonValue::Number)
+                    .ok_or_else(|| YamlError::UnsupportedValue(format!("non-finite number {}", n)))
+            }
+        }
+        YamlValue::String(s) => Ok(JsonValue::String(s)),
+        YamlValue::Sequence(items) => items
+            .into_iter()
+            .map(yaml_to_json)
+            .collect::<Result<Vec<_>>>()
+            .map(JsonValue::Array),
+        YamlValue::Mapping(mapping) => {
+            let mut map = serde_json::Map::new();
+            for (key, value) in mapping {
+                map.insert(key_to_string(key)?, yaml_to_json(value)?);
+            }
+            Ok(JsonValue::Object(map))
+        }
+        YamlValue::Tagged(tagged) => yaml_to_json(tagged.value),
+    }
+}
+
+fn key_to_string(key: YamlValue) -> Result<String> {
+    match key {
+        YamlValue::String(s) => Ok(s),
+        YamlValue::Number(n) => Ok(n.to_string()),
+        YamlValue::Bool(b) => Ok(b.to_string()),
+        YamlValue::Null => Ok("null".to_string()),
+        YamlValue::Tagged(tagged) => key_to_string(tagged.value),
+        YamlValue::Sequence(_) | YamlValue::Mapping(_) => Err(YamlError::UnsupportedValue(
+            "mapping keys must be scalars".to_string(),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_nested_mapping() {
+        let value = parse("server:\n  host: localhost\n  port: 8080\n  tls: false\n").unwrap();
+        assert_eq!(
+            value,
+            json!({"server": {"host": "localhost", "port": 8080, "tls": false}})
+        );
+    }
+
+    #[test]
+    fn test_parse_list_of_maps() {
+        let value = parse("- name: api\n  replicas: 2\n- name: worker\n  replicas: 1\n").unwrap();
+        assert_eq!(
+            value,
+            json!([{"name": "api", "replicas": 2}, {"name": "worker", "replicas": 1}])
+        );
+    }
+
+    #[test]
+    fn test_parse_multiline_strings_and_anchors() {
+        let doc = "defaults: &defaults\n  retries: 3\nprod:\n  <<: *defaults\nliteral: |\n  line one\n  line two\nfolded: >\n  a\n  b\nalias: *defaults\n";
+        let value = parse(doc).unwrap();
+        assert_eq!(value["literal"], "line one\nline two\n");
+        assert_eq!(value["folded"], "a b\n");
+        assert_eq!(value["alias"], json!({"retries": 3}));
+        assert_eq!(value["prod"], json!({"retries": 3}));
+    }
+
+    #[test]
+    fn test_parse_scalar_keys_and_tags() {
+        let value = parse("1: one\ntrue: yes\nwhen: !timestamp 2024-01-01\n").unwrap();
+        assert_eq!(value["1"], "one");
+        assert_eq!(value["true"], "yes");
+        assert_eq!(value["when"], "2024-01-01");
+    }
+
+    #[test]
+    fn test_parse_invalid_document() {
+        let err = parse("key: [unclosed\n").unwrap_err().to_string();
+        assert!(err.starts_with("YAML parse error"), "{}", err);
+        assert!(err.contains("line"), "{}", err);
+        assert!(parse("value: .inf").is_err());
+    }
+
+    #[test]
+    fn test_stringify_round_trip() {
+        let value = json!({"name": "hype", "tags": ["lua", "rust"], "nested": {"depth": 2}});
+        let yaml = stringify(&value).unwrap();
+        assert!(yaml.contains("name: hype"));
+        assert_eq!(parse(&yaml).unwrap(), value);
+    }
+}
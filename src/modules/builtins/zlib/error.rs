@@ -0,0 +1,46 @@
+use crate::error::StructuredError;
+use std::fmt;
+
+#[derive(Debug)]
+pub enum ZlibError {
+    CompressError(String),
+    DecompressError(String),
+    InvalidLevel(u32),
+}
+
+impl fmt::Display for ZlibError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ZlibError::CompressError(msg) => write!(f, "Compression failed: {}", msg),
+            ZlibError::DecompressError(msg) => write!(f, "Decompression failed: {}", msg),
+            ZlibError::InvalidLevel(level) => {
+                write!(f, "Invalid compression level {} (expected 0-9)", level)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ZlibError {}
+
+impl ZlibError {
+    /// Node's zlib error codes.
+    pub fn code(&self) -> &'static str {
+        match self {
+            ZlibError::CompressError(_) => "Z_STREAM_ERROR",
+            ZlibError::DecompressError(_) => "Z_DATA_ERROR",
+            ZlibError::InvalidLevel(_) => "ERR_OUT_OF_RANGE",
+        }
+    }
+}
+
+impl From<ZlibError> for StructuredError {
+    fn from(err: ZlibError) -> Self {
+        StructuredError::new("zlib", err.code(), err.to_string())
+    }
+}
+
+impl From<ZlibError> for crate::error::HypeError {
+    fn from(err: ZlibError) -> Self {
+        crate::error::HypeError::Execution(err.to_string())
+    }
+}
@@ -0,0 +1,87 @@
+use super::operations::*;
+use crate::lua::error::{structured_error, wrap_module_errors};
+use mlua::{Lua, Result as LuaResult, String as LuaString, Table};
+
+pub fn create_zlib_module(lua: &Lua) -> LuaResult<Table<'_>> {
+    let zlib = lua.create_table()?;
+
+    let gzip_fn = lua.create_function(|lua, (data, options): (LuaString, Option<Table>)| {
+        let compressed = gzip(data.as_bytes(), parse_level(options)?).map_err(structured_error)?;
+        lua.create_string(&compressed)
+    })?;
+    zlib.set("gzip", gzip_fn)?;
+
+    let gunzip_fn = lua.create_function(|lua, data: LuaString| {
+        let output = gunzip(data.as_bytes()).map_err(structured_error)?;
+        lua.create_string(&output)
+    })?;
+    zlib.set("gunzip", gunzip_fn)?;
+
+    let deflate_fn = lua.create_function(|lua, (data, options): (LuaString, Option<Table>)| {
+        let compressed =
+            deflate(data.as_bytes(), parse_level(options)?).map_err(structured_error)?;
+        lua.create_string(&compressed)
+    })?;
+    zlib.set("deflate", deflate_fn)?;
+
+    let inflate_fn = lua.create_function(|lua, data: LuaString| {
+        let output = inflate(data.as_bytes()).map_err(structured_error)?;
+        lua.create_string(&output)
+    })?;
+    zlib.set("inflate", inflate_fn)?;
+
+    wrap_module_errors(lua, &zlib)?;
+    Ok(zlib)
+}
+
+fn parse_level(options: Option<Table>) -> LuaResult<u32> {
+    match options {
+        Some(opts) => Ok(opts
+            .get::<_, Option<u32>>("level")?
+            .unwrap_or(DEFAULT_LEVEL)),
+        None => Ok(DEFAULT_LEVEL),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lua_with_zlib() -> Lua {
+        let lua = Lua::new();
+        let zlib = create_zlib_module(&lua).unwrap();
+        lua.globals().set("zlib", zlib).unwrap();
+        lua
+    }
+
+    #[test]
+    fn test_create_zlib_module() {
+        let lua = Lua::new();
+        let zlib = create_zlib_module(&lua).unwrap();
+        for name in ["gzip", "gunzip", "deflate", "inflate"] {
+            assert!(zlib.contains_key(name).unwrap(), "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_roundtrip_binary_string() {
+        let lua = lua_with_zlib();
+        lua.load(
+            r#"
+local bytes = {}
+for i = 0, 255 do bytes[#bytes + 1] = string.char(i) end
+local payload = table.concat(bytes):rep(8)
+
+assert(zlib.gunzip(zlib.gzip(payload)) == payload)
+assert(zlib.inflate(zlib.deflate(payload, {level = 9})) == payload)
+assert(zlib.gzip(payload):sub(1, 2) == "\x1f\x8b")
+
+local ok, err = pcall(zlib.gunzip, "plain text")
+assert(not ok and err.code == "Z_DATA_ERROR" and err.kind == "zlib", tostring(err))
+assert(err.message:find("Decompression failed"), err.message)
+"#,
+        )
+        .exec()
+        .unwrap();
+    }
+}
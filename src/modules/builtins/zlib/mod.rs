@@ -0,0 +1,79 @@
+pub mod error;
+pub mod lua_bindings;
+pub mod operations;
+
+use serde_json::{json, Value as JsonValue};
+
+use super::BuiltinModule;
+use crate::error::HypeError;
+
+pub use error::ZlibError;
+pub use lua_bindings::create_zlib_module;
+
+pub struct ZlibModule;
+
+impl ZlibModule {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for ZlibModule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BuiltinModule for ZlibModule {
+    fn name(&self) -> &str {
+        "zlib"
+    }
+
+    fn exports(&self) -> std::result::Result<JsonValue, HypeError> {
+        Ok(json!({
+            "__id": "zlib",
+            "__desc": "gzip and deflate compression of byte strings",
+            "gzip": {
+                "__fn": "gzip",
+                "__desc": "Compress data into the gzip format",
+                "__signature": "gzip(data: string, options?: {level?: number}) -> string"
+            },
+            "gunzip": {
+                "__fn": "gunzip",
+                "__desc": "Decompress gzip data",
+                "__signature": "gunzip(data: string) -> string"
+            },
+            "deflate": {
+                "__fn": "deflate",
+                "__desc": "Compress data into the zlib (deflate) format",
+                "__signature": "deflate(data: string, options?: {level?: number}) -> string"
+            },
+            "inflate": {
+                "__fn": "inflate",
+                "__desc": "Decompress zlib (deflate) data",
+                "__signature": "inflate(data: string) -> string"
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zlib_module_name() {
+        let module = ZlibModule::new();
+        assert_eq!(module.name(), "zlib");
+    }
+
+    #[test]
+    fn test_zlib_module_exports() {
+        let module = ZlibModule::new();
+        let exports = module.exports().unwrap();
+        assert!(exports.get("gzip").is_some());
+        assert!(exports.get("gunzip").is_some());
+        assert!(exports.get("deflate").is_some());
+        assert!(exports.get("inflate").is_some());
+    }
+}
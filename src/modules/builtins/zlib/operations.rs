@@ -0,0 +1,98 @@
+use super::ZlibError;
+use flate2::read::{MultiGzDecoder, ZlibDecoder};
+use flate2::write::{GzEncoder, ZlibEncoder};
+use flate2::Compression;
+use std::io::{Read, Write};
+
+/// flate2's default, the same trade-off as `gzip -6`.
+pub const DEFAULT_LEVEL: u32 = 6;
+
+fn compression(level: u32) -> Result<Compression, ZlibError> {
+    if level > 9 {
+        return Err(ZlibError::InvalidLevel(level));
+    }
+    Ok(Compression::new(level))
+}
+
+/// Compress `data` into the gzip format (RFC 1952).
+pub fn gzip(data: &[u8], level: u32) -> Result<Vec<u8>, ZlibError> {
+    let mut encoder = GzEncoder::new(Vec::new(), compression(level)?);
+    encoder
+        .write_all(data)
+        .map_err(|e| ZlibError::CompressError(e.to_string()))?;
+    encoder
+        .finish()
+        .map_err(|e| ZlibError::CompressError(e.to_string()))
+}
+
+/// Decompress gzip data, including multi-member files such as concatenated `.gz` output.
+pub fn gunzip(data: &[u8]) -> Result<Vec<u8>, ZlibError> {
+    let mut output = Vec::new();
+    MultiGzDecoder::new(data)
+        .read_to_end(&mut output)
+        .map_err(|e| ZlibError::DecompressError(e.to_string()))?;
+    Ok(output)
+}
+
+/// Compress `data` into the zlib format (RFC 1950), as Node's `zlib.deflate` does.
+pub fn deflate(data: &[u8], level: u32) -> Result<Vec<u8>, ZlibError> {
+    let mut encoder = ZlibEncoder::new(Vec::new(), compression(level)?);
+    encoder
+        .write_all(data)
+        .map_err(|e| ZlibError::CompressError(e.to_string()))?;
+    encoder
+        .finish()
+        .map_err(|e| ZlibError::CompressError(e.to_string()))
+}
+
+pub fn inflate(data: &[u8]) -> Result<Vec<u8>, ZlibError> {
+    let mut output = Vec::new();
+    ZlibDecoder::new(data)
+        .read_to_end(&mut output)
+        .map_err(|e| ZlibError::DecompressError(e.to_string()))?;
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `printf 'hello, hype!\n' | gzip -n`
+    const HELLO_GZ: [u8; 33] = [
+        0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x03, 0xcb, 0x48, 0xcd, 0xc9, 0xc9,
+        0xd7, 0x51, 0xc8, 0xa8, 0x2c, 0x48, 0x55, 0xe4, 0x02, 0x00, 0x7b, 0xee, 0xd2, 0xc1, 0x0d,
+        0x00, 0x00, 0x00,
+    ];
+
+    #[test]
+    fn test_gunzip_known_blob() {
+        assert_eq!(gunzip(&HELLO_GZ).unwrap(), b"hello, hype!\n");
+    }
+
+    #[test]
+    fn test_roundtrip_binary_and_repetitive_data() {
+        let binary: Vec<u8> = (0..=255u8).cycle().take(4096).collect();
+        assert_eq!(
+            gunzip(&gzip(&binary, DEFAULT_LEVEL).unwrap()).unwrap(),
+            binary
+        );
+        assert_eq!(inflate(&deflate(&binary, 9).unwrap()).unwrap(), binary);
+
+        let repetitive = r#"{"id":1,"status":"ok"},"#.repeat(500);
+        let compressed = gzip(repetitive.as_bytes(), DEFAULT_LEVEL).unwrap();
+        assert!(compressed.len() < repetitive.len() / 10);
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(matches!(gzip(b"x", 10), Err(ZlibError::InvalidLevel(10))));
+        assert!(matches!(
+            gunzip(b"not gzip"),
+            Err(ZlibError::DecompressError(_))
+        ));
+        assert!(matches!(
+            inflate(&HELLO_GZ),
+            Err(ZlibError::DecompressError(_))
+        ));
+    }
+}
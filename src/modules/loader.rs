@@ -40,6 +40,13 @@ pub struct ModuleLoader {
     detector: CircularDependencyDetector,
     load_stack: Arc<RwLock<Vec<String>>>,
     builtins: BuiltinRegistry,
+    entry: Option<String>,
+    /// The last load failure reported with its require chain, so modules
+    /// that fail because of it pass it on instead of wrapping it again.
+    last_failure: Option<String>,
+    /// Every user module file this loader has started loading, in order,
+    /// including ones that failed.
+    module_paths: Vec<PathBuf>,
 }
 
 impl ModuleLoader {
@@ -59,9 +66,44 @@ impl ModuleLoader {
             detector: CircularDependencyDetector::new(),
             load_stack: Arc::new(RwLock::new(Vec::new())),
             builtins: BuiltinRegistry::new(),
+            entry: None,
+            last_failure: None,
+            module_paths: Vec::new(),
         }
     }
 
+    /// Name the script that started the program, shown first in require chains.
+    pub fn set_entry(&mut self, entry: impl Into<String>) {
+        self.entry = Some(entry.into());
+    }
+
+    /// The chain of requires that leads to `module_id`, from the entry script
+    /// through every module still loading, e.g. `main.lua -> ./a -> ./b`.
+    pub fn require_chain(&self, module_id: &str) -> String {
+        self.entry
+            .iter()
+            .cloned()
+            .chain(self.detector.get_stack())
+            .chain(std::iter::once(module_id.to_string()))
+            .collect::<Vec<_>>()
+            .join(" -> ")
+    }
+
+    /// Describe a failure to load `module_id` with the chain of requires that
+    /// led to it. When `error` came from a module further down the same chain,
+    /// that module's report is returned unchanged.
+    pub fn chain_error(&mut self, module_id: &str, message: String) -> HypeError {
+        if let Some(inner) = self.last_failure.take() {
+            if message.contains(&inner) {
+                self.last_failure = Some(inner.clone());
+                return HypeError::Execution(inner);
+            }
+        }
+        let failure = format!("{}: {}", self.require_chain(module_id), message);
+        self.last_failure = Some(failure.clone());
+        HypeError::Execution(failure)
+    }
+
     /// Require a module by identifier.
     ///
     /// Implements the require() function behavior:
@@ -104,11 +146,12 @@ impl ModuleLoader {
         module_id: &str,
         from_dir: Option<&Path>,
     ) -> Result<JsonValue, HypeError> {
-        let path = if let Some(dir) = from_dir {
-            self.resolver.resolve_from(dir, module_id)?
+        let resolved = if let Some(dir) = from_dir {
+            self.resolver.resolve_from(dir, module_id)
         } else {
-            self.resolver.resolve(module_id)?
+            self.resolver.resolve(module_id)
         };
+        let path = resolved.map_err(|e| self.chain_error(module_id, error_message(e)))?;
 
         let cache_key = path.to_string_lossy().to_string();
 
@@ -214,6 +257,16 @@ impl ModuleLoader {
         &self.resolver
     }
 
+    /// Add a directory whose `hype_modules` folder is searched for packages.
+    pub fn add_search_path(&mut self, path: PathBuf) {
+        self.resolver.add_search_path(path);
+    }
+
+    /// Allow `require` of modules by absolute path, which is refused by default.
+    pub fn set_allow_absolute_paths(&mut self, allow: bool) {
+        self.resolver.set_allow_absolute_paths(allow);
+    }
+
     /// Get detector reference.
     pub fn detector(&self) -> &CircularDependencyDetector {
         &self.detector
@@ -224,6 +277,11 @@ impl ModuleLoader {
         self.builtins.is_builtin(module_id)
     }
 
+    /// Names of the built-in modules compiled into this build
+    pub fn builtin_names(&self) -> Vec<&'static str> {
+        self.builtins.list()
+    }
+
     /// Load a built-in module with Lua bindings
     ///
     /// For modules that need callable Lua functions (like HTTP),
@@ -252,44 +310,61 @@ impl ModuleLoader {
         lua: &'lua mlua::Lua,
         module_id: &str,
     ) -> Result<mlua::Value<'lua>, HypeError> {
-        let path = self.resolver.resolve(module_id)?;
-        let cache_key = path.to_string_lossy().to_string();
-
-        // Check if already cached (this marks it as loaded, so we skip loading)
-        if self.registry.get(&cache_key).map(|opt| opt.is_some()).unwrap_or(false) {
-            // Module has been loaded before, but we can't return cached Lua values
-            // Re-execute it to preserve function references
-            // TODO: Better caching strategy for Lua values
-        }
+        let path = self
+            .resolver
+            .resolve(module_id)
+            .map_err(|e| self.chain_error(module_id, error_message(e)))?;
+        self.begin_user_module(module_id, &path)?;
+        let result = execute_user_module(lua, &path, module_id);
+        self.finish_user_module(module_id, &path, result)
+    }
 
-        // Check for circular dependencies
+    /// Mark `module_id`, resolved to `path`, as loading so it joins the
+    /// require chain and circular requires are caught. Run its code with
+    /// [`execute_user_module`] and then call `finish_user_module`.
+    ///
+    /// The loader does not need to stay locked while the code runs, which lets
+    /// the module require others in turn.
+    pub fn begin_user_module(&mut self, module_id: &str, path: &Path) -> Result<(), HypeError> {
+        let cache_key = path.to_string_lossy().to_string();
         let mut stack = self
             .load_stack
             .write()
             .map_err(|_| HypeError::Execution("Failed to acquire load stack lock".to_string()))?;
 
         if stack.contains(&cache_key) {
-            return Err(HypeError::Execution(format!(
+            let failure = format!(
                 "Circular dependency detected: {}",
-                cache_key
-            )));
+                self.require_chain(module_id)
+            );
+            self.last_failure = Some(failure.clone());
+            return Err(HypeError::Execution(failure));
         }
 
-        stack.push(cache_key.clone());
-        drop(stack);
+        stack.push(cache_key);
+        self.detector.push(module_id.to_string());
+        if !self.module_paths.iter().any(|known| known == path) {
+            self.module_paths.push(path.to_path_buf());
+        }
+        Ok(())
+    }
 
-        // Read the Lua file
-        let content = std::fs::read_to_string(&path).map_err(|e| {
-            HypeError::Execution(format!("Failed to read module file '{}': {}", path.display(), e))
-        })?;
+    /// Take `module_id` off the require chain and record the outcome of
+    /// running it. Failures are reported with the chain that led to them.
+    pub fn finish_user_module<'lua>(
+        &mut self,
+        module_id: &str,
+        path: &Path,
+        result: mlua::Result<mlua::Value<'lua>>,
+    ) -> Result<mlua::Value<'lua>, HypeError> {
+        let cache_key = path.to_string_lossy().to_string();
+        self.load_stack
+            .write()
+            .map_err(|_| HypeError::Execution("Failed to acquire load stack lock".to_string()))?
+            .pop();
+        self.detector.pop();
 
-        // Execute the module in the Lua runtime
-        let result = lua.load(&content)
-            .set_name(module_id.to_string())
-            .eval::<mlua::Value>()
-            .map_err(|e| {
-                HypeError::Execution(format!("Failed to execute module '{}': {}", module_id, e))
-            })?;
+        let result = result.map_err(|e| self.chain_error(module_id, e.to_string()))?;
 
         // Add module metadata to the result if it's a table
         if let mlua::Value::Table(table) = &result {
@@ -307,25 +382,51 @@ impl ModuleLoader {
             "__id": module_id,
             "__path": cache_key.clone(),
         });
-        self.registry
-            .set(cache_key.clone(), metadata, info)?;
-
-        // Clean up load stack
-        let mut stack = self
-            .load_stack
-            .write()
-            .map_err(|_| HypeError::Execution("Failed to acquire load stack lock".to_string()))?;
-        stack.pop();
+        self.registry.set(cache_key, metadata, info)?;
 
         Ok(result)
     }
 
+    /// Files of the user modules required so far, whether or not they loaded.
+    pub fn module_paths(&self) -> &[PathBuf] {
+        &self.module_paths
+    }
+
     /// Get all cached module keys.
     pub fn cached_modules(&self) -> Result<Vec<String>, HypeError> {
         self.registry.list_modules()
     }
 }
 
+/// The text of a loader error without the "Execution error:" prefix, which
+/// would otherwise repeat at every level of a require chain.
+pub fn error_message(err: HypeError) -> String {
+    match err {
+        HypeError::Execution(message) => message,
+        other => other.to_string(),
+    }
+}
+
+/// Read and run the module file at `path`, returning what it returns.
+pub fn execute_user_module<'lua>(
+    lua: &'lua mlua::Lua,
+    path: &Path,
+    module_id: &str,
+) -> mlua::Result<mlua::Value<'lua>> {
+    let content = std::fs::read_to_string(path)
+        .map(crate::file_io::strip_shebang)
+        .map_err(|e| {
+            mlua::Error::RuntimeError(format!(
+                "Failed to read module file '{}': {}",
+                path.display(),
+                e
+            ))
+        })?;
+    lua.load(&content)
+        .set_name(format!("@{}", module_id))
+        .eval::<mlua::Value>()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
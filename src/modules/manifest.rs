@@ -2,7 +2,7 @@ use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use super::error::ModuleError;
 use crate::error::HypeError;
@@ -30,8 +30,9 @@ pub struct HypeManifest {
     pub description: Option<String>,
     /// Optional main entry point (file or function)
     pub main: Option<String>,
-    /// Optional list of dependencies
-    pub dependencies: Option<Vec<String>>,
+    /// Optional dependencies (name -> version requirement or local path)
+    #[serde(default, deserialize_with = "deserialize_dependencies")]
+    pub dependencies: Option<HashMap<String, String>>,
     /// Optional binary/command mappings (command_name -> script_path)
     #[serde(default)]
     pub bin: Option<HashMap<String, String>>,
@@ -63,7 +64,7 @@ impl HypeManifest {
     }
 
     /// Set module dependencies.
-    pub fn with_dependencies(mut self, deps: Vec<String>) -> Self {
+    pub fn with_dependencies(mut self, deps: HashMap<String, String>) -> Self {
         self.dependencies = Some(deps);
         self
     }
@@ -77,8 +78,20 @@ impl HypeManifest {
     /// Load a manifest from a file path.
     ///
     /// Reads the file and parses it as JSON. Returns an error if the file
-    /// cannot be read or if the JSON is invalid.
+    /// cannot be read or if the JSON is invalid. `${VAR}` in string values is
+    /// replaced with the environment variable; unset variables are left as
+    /// written, and `$${VAR}` keeps a literal `${VAR}`.
     pub fn load(path: &Path) -> Result<Self, HypeError> {
+        Self::load_with_env(path, false)
+    }
+
+    /// Load a manifest like [`HypeManifest::load`], but fail when a `${VAR}`
+    /// names an environment variable that is not set.
+    pub fn load_strict(path: &Path) -> Result<Self, HypeError> {
+        Self::load_with_env(path, true)
+    }
+
+    fn load_with_env(path: &Path, strict: bool) -> Result<Self, HypeError> {
         if !path.exists() {
             return Err(HypeError::Execution(
                 ModuleError::ManifestNotFound(path.to_path_buf()).to_string(),
@@ -91,15 +104,21 @@ impl HypeManifest {
             )
         })?;
 
-        let manifest: HypeManifest = serde_json::from_str(&content).map_err(|e| {
+        let parse_error = |reason: String| {
             HypeError::Execution(
                 ModuleError::ManifestParseError {
                     path: path.to_path_buf(),
-                    reason: e.to_string(),
+                    reason,
                 }
                 .to_string(),
             )
-        })?;
+        };
+        let mut value: serde_json::Value =
+            serde_json::from_str(&content).map_err(|e| parse_error(e.to_string()))?;
+        interpolate_env(&mut value, &|name| std::env::var(name).ok(), strict)
+            .map_err(parse_error)?;
+        let manifest: HypeManifest =
+            serde_json::from_value(value).map_err(|e| parse_error(e.to_string()))?;
 
         Ok(manifest)
     }
@@ -126,6 +145,7 @@ impl HypeManifest {
     pub fn validate_with_package_dir(&self, package_dir: &Path) -> Result<(), HypeError> {
         self.validate()?;
         self.validate_bin(package_dir)?;
+        self.validate_dependency_paths(package_dir)?;
 
         Ok(())
     }
@@ -173,11 +193,14 @@ impl HypeManifest {
     }
 
     /// Validate dependencies if present.
+    ///
+    /// Names follow the module name rules. Values are either a local path
+    /// (see [`is_path_dependency`]) or a semver requirement such as `^1.2.0` or `*`.
     fn validate_dependencies(&self) -> Result<(), HypeError> {
         if let Some(deps) = &self.dependencies {
             let name_re = Regex::new(NAME_PATTERN).unwrap();
 
-            for dep in deps {
+            for (dep, spec) in deps {
                 if !name_re.is_match(dep) {
                     return Err(HypeError::Execution(
                         ModuleError::InvalidManifest {
@@ -186,6 +209,40 @@ impl HypeManifest {
                         .to_string(),
                     ));
                 }
+
+                if !is_path_dependency(spec) && semver::VersionReq::parse(spec).is_err() {
+                    return Err(HypeError::Execution(
+                        ModuleError::InvalidManifest {
+                            reason: format!(
+                                "invalid version requirement for dependency '{}': '{}'",
+                                dep, spec
+                            ),
+                        }
+                        .to_string(),
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check that every local-path dependency exists relative to the package.
+    fn validate_dependency_paths(&self, package_dir: &Path) -> Result<(), HypeError> {
+        for (dep, spec) in self.dependencies.iter().flatten() {
+            if let Some(path) = dependency_path(package_dir, spec) {
+                if !path.exists() {
+                    return Err(HypeError::Execution(
+                        ModuleError::InvalidManifest {
+                            reason: format!(
+                                "dependency '{}' does not exist at path: {}",
+                                dep,
+                                path.display()
+                            ),
+                        }
+                        .to_string(),
+                    ));
+                }
             }
         }
 
@@ -323,12 +380,138 @@ impl HypeManifest {
     }
 }
 
+/// Whether a dependency value names a local path (`./lib`, `../shared`,
+/// `/abs/path` or `file:lib`) rather than a version requirement.
+pub fn is_path_dependency(spec: &str) -> bool {
+    spec.starts_with("./")
+        || spec.starts_with("../")
+        || spec.starts_with("file:")
+        || Path::new(spec).is_absolute()
+}
+
+/// Resolve a local-path dependency against the package directory.
+pub fn dependency_path(package_dir: &Path, spec: &str) -> Option<PathBuf> {
+    if !is_path_dependency(spec) {
+        return None;
+    }
+    Some(package_dir.join(spec.strip_prefix("file:").unwrap_or(spec)))
+}
+
+/// Expand `${VAR}` in every string value of `value` (object keys are left
+/// alone) using `lookup`.
+fn interpolate_env(
+    value: &mut serde_json::Value,
+    lookup: &dyn Fn(&str) -> Option<String>,
+    strict: bool,
+) -> Result<(), String> {
+    match value {
+        serde_json::Value::String(text) => *text = expand_env_vars(text, lookup, strict)?,
+        serde_json::Value::Array(items) => {
+            for item in items {
+                interpolate_env(item, lookup, strict)?;
+            }
+        }
+        serde_json::Value::Object(map) => {
+            for item in map.values_mut() {
+                interpolate_env(item, lookup, strict)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn expand_env_vars(
+    text: &str,
+    lookup: &dyn Fn(&str) -> Option<String>,
+    strict: bool,
+) -> Result<String, String> {
+    let mut expanded = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find('$') {
+        expanded.push_str(&rest[..start]);
+        let after = &rest[start..];
+        if after.starts_with("$${") {
+            expanded.push('$');
+            rest = &after[2..];
+            continue;
+        }
+        let reference = after
+            .strip_prefix("${")
+            .and_then(|body| body.find('}').map(|end| &body[..end]))
+            .filter(|name| is_env_var_name(name));
+        match reference {
+            Some(name) => {
+                match lookup(name) {
+                    Some(value) => expanded.push_str(&value),
+                    None if strict => {
+                        return Err(format!(
+                            "environment variable {} is not set (referenced as ${{{}}})",
+                            name, name
+                        ))
+                    }
+                    None => expanded.push_str(&after[..name.len() + 3]),
+                }
+                rest = &after[name.len() + 3..];
+            }
+            None => {
+                expanded.push('$');
+                rest = &after[1..];
+            }
+        }
+    }
+    expanded.push_str(rest);
+    Ok(expanded)
+}
+
+fn is_env_var_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Accept both `{"name": "^1.0.0"}` and the older `["name"]` form, where every
+/// listed dependency matches any version.
+fn deserialize_dependencies<'de, D>(
+    deserializer: D,
+) -> Result<Option<HashMap<String, String>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Dependencies {
+        Map(HashMap<String, String>),
+        List(Vec<String>),
+    }
+
+    Ok(
+        Option::<Dependencies>::deserialize(deserializer)?.map(|deps| match deps {
+            Dependencies::Map(map) => map,
+            Dependencies::List(names) => names
+                .into_iter()
+                .map(|name| (name, "*".to_string()))
+                .collect(),
+        }),
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::io::Write;
     use tempfile::NamedTempFile;
 
+    fn deps(entries: &[(&str, &str)]) -> HashMap<String, String> {
+        entries
+            .iter()
+            .map(|(name, spec)| (name.to_string(), spec.to_string()))
+            .collect()
+    }
+
     #[test]
     fn test_manifest_new() {
         let manifest = HypeManifest::new("my_module".to_string(), "1.0.0".to_string());
@@ -343,7 +526,7 @@ mod tests {
         let manifest = HypeManifest::new("test".to_string(), "1.0.0".to_string())
             .with_description("A test module".to_string())
             .with_main("main.lua".to_string())
-            .with_dependencies(vec!["dep1".to_string(), "dep2".to_string()]);
+            .with_dependencies(deps(&[("dep1", "^1.0.0"), ("dep2", "./lib/dep2")]));
 
         assert_eq!(manifest.name, "test");
         assert_eq!(manifest.version, "1.0.0");
@@ -351,7 +534,7 @@ mod tests {
         assert_eq!(manifest.main, Some("main.lua".to_string()));
         assert_eq!(
             manifest.dependencies,
-            Some(vec!["dep1".to_string(), "dep2".to_string()])
+            Some(deps(&[("dep1", "^1.0.0"), ("dep2", "./lib/dep2")]))
         );
     }
 
@@ -430,12 +613,49 @@ mod tests {
     #[test]
     fn test_validate_dependencies() {
         let manifest = HypeManifest::new("test".to_string(), "1.0.0".to_string())
-            .with_dependencies(vec!["dep1".to_string(), "dep2".to_string()]);
+            .with_dependencies(deps(&[("dep1", "*"), ("dep2", "file:vendor/dep2")]));
         assert!(manifest.validate_dependencies().is_ok());
 
         let manifest2 = HypeManifest::new("test".to_string(), "1.0.0".to_string())
-            .with_dependencies(vec!["invalid!dep".to_string()]);
+            .with_dependencies(deps(&[("invalid!dep", "*")]));
         assert!(manifest2.validate_dependencies().is_err());
+
+        let manifest3 = HypeManifest::new("test".to_string(), "1.0.0".to_string())
+            .with_dependencies(deps(&[("dep1", "not a version")]));
+        assert!(manifest3.validate_dependencies().is_err());
+    }
+
+    #[test]
+    fn test_dependencies_accept_map_and_legacy_list() {
+        let manifest: HypeManifest = serde_json::from_str(
+            r#"{"name": "app", "version": "1.0.0", "dependencies": {"utils": "./lib/utils", "log": "^2.1.0"}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            manifest.dependencies,
+            Some(deps(&[("utils", "./lib/utils"), ("log", "^2.1.0")]))
+        );
+
+        let legacy: HypeManifest = serde_json::from_str(
+            r#"{"name": "app", "version": "1.0.0", "dependencies": ["utils"]}"#,
+        )
+        .unwrap();
+        assert_eq!(legacy.dependencies, Some(deps(&[("utils", "*")])));
+    }
+
+    #[test]
+    fn test_validate_dependency_paths_exist() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("lib/utils")).unwrap();
+
+        let manifest = HypeManifest::new("app".to_string(), "1.0.0".to_string())
+            .with_dependencies(deps(&[("utils", "./lib/utils"), ("log", "^1.0.0")]));
+        assert!(manifest.validate_with_package_dir(dir.path()).is_ok());
+
+        let missing = HypeManifest::new("app".to_string(), "1.0.0".to_string())
+            .with_dependencies(deps(&[("gone", "./lib/gone")]));
+        let err = missing.validate_with_package_dir(dir.path()).unwrap_err();
+        assert!(err.to_string().contains("dependency 'gone' does not exist"));
     }
 
     #[test]
@@ -443,7 +663,7 @@ mod tests {
         let manifest = HypeManifest::new("valid_module".to_string(), "1.0.0".to_string())
             .with_description("A valid module".to_string())
             .with_main("main.lua".to_string())
-            .with_dependencies(vec!["dep1".to_string()]);
+            .with_dependencies(deps(&[("dep1", "1.0.0")]));
 
         assert!(manifest.validate().is_ok());
     }
@@ -493,11 +713,50 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_load_expands_env_vars() {
+        std::env::set_var("HYPE_MANIFEST_TEST_FOO", "/opt/hype");
+        std::env::remove_var("HYPE_MANIFEST_TEST_UNSET");
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file
+            .write_all(
+                br#"{
+                    "name": "tool",
+                    "version": "1.0.0",
+                    "description": "Installed in ${HYPE_MANIFEST_TEST_FOO}, $${HOME} and ${HYPE_MANIFEST_TEST_UNSET}",
+                    "bin": {"tool": "${HYPE_MANIFEST_TEST_FOO}/bin/tool.lua"}
+                }"#,
+            )
+            .unwrap();
+        temp_file.flush().unwrap();
+
+        let manifest = HypeManifest::load(temp_file.path()).unwrap();
+        assert_eq!(
+            manifest.description.as_deref(),
+            Some("Installed in /opt/hype, ${HOME} and ${HYPE_MANIFEST_TEST_UNSET}")
+        );
+        assert_eq!(manifest.bin.unwrap()["tool"], "/opt/hype/bin/tool.lua");
+
+        let err = HypeManifest::load_strict(temp_file.path()).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("environment variable HYPE_MANIFEST_TEST_UNSET is not set"));
+    }
+
+    #[test]
+    fn test_expand_env_vars_edge_cases() {
+        let lookup = |name: &str| (name == "A").then(|| "x".to_string());
+        let expand = |text| expand_env_vars(text, &lookup, false).unwrap();
+        assert_eq!(expand("${A}${A}-$A-${}-${1A}-${A"), "xx-$A-${}-${1A}-${A");
+        assert_eq!(expand("cost: $5"), "cost: $5");
+        assert!(expand_env_vars("${B}", &lookup, true).is_err());
+    }
+
     #[test]
     fn test_serialization() {
         let manifest = HypeManifest::new("test".to_string(), "1.0.0".to_string())
             .with_description("Test".to_string())
-            .with_dependencies(vec!["dep1".to_string()]);
+            .with_dependencies(deps(&[("dep1", "1.0.0")]));
 
         let json = serde_json::to_string(&manifest).unwrap();
         assert!(json.contains("test"));
@@ -1,10 +1,11 @@
 use crate::modules::error::ModuleError;
+use crate::modules::manifest::HypeManifest;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::env;
 use std::fs;
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tempfile::NamedTempFile;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -14,6 +15,12 @@ pub struct InstalledPackage {
     pub install_date: String,
     pub location: PathBuf,
     pub bin: HashMap<String, String>,
+    /// SHA-256 of each installed bin script, keyed by its path in the package.
+    #[serde(default)]
+    pub checksums: HashMap<String, String>,
+    /// Local directory the package was installed from; `None` for downloads.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<PathBuf>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -75,9 +82,10 @@ impl GlobalPackageRegistry {
             ModuleError::RegistryError(format!("Failed to read registry file: {}", e))
         })?;
 
-        let data: RegistryData = serde_json::from_str(&content).map_err(|e| {
-            ModuleError::RegistryError(format!("Failed to parse registry JSON: {}", e))
-        })?;
+        let data: RegistryData = match serde_json::from_str(&content) {
+            Ok(data) => data,
+            Err(e) => return Self::recover(&registry_file, e),
+        };
 
         Ok(Self {
             root_dir,
@@ -89,6 +97,80 @@ impl GlobalPackageRegistry {
         })
     }
 
+    /// Move an unreadable registry aside and rebuild it from the installed
+    /// package directories, so one interrupted write does not break every command.
+    fn recover(registry_file: &Path, error: serde_json::Error) -> Result<Self, ModuleError> {
+        let backup = registry_file.with_extension(format!(
+            "json.corrupt-{}",
+            chrono::Utc::now().format("%Y%m%d%H%M%S")
+        ));
+        fs::rename(registry_file, &backup).map_err(|e| {
+            ModuleError::RegistryError(format!(
+                "Registry file is corrupted ({}) and could not be backed up: {}",
+                error, e
+            ))
+        })?;
+
+        eprintln!(
+            "Warning: package registry was corrupted ({}); moved it to {} and rebuilt it from installed packages",
+            error,
+            backup.display()
+        );
+
+        let mut registry = Self::new()?;
+        registry.rebuild_from_packages();
+        registry.save()?;
+        Ok(registry)
+    }
+
+    fn rebuild_from_packages(&mut self) {
+        let Ok(entries) = fs::read_dir(&self.packages_dir) else {
+            return;
+        };
+        let mut locations: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.is_dir())
+            .collect();
+        locations.sort();
+
+        for location in locations {
+            // Skip staging and backup directories left by an interrupted upgrade
+            let hidden = location
+                .file_name()
+                .and_then(|name| name.to_str())
+                .map_or(true, |name| name.starts_with('.'));
+            if hidden {
+                continue;
+            }
+            let Ok(manifest) = HypeManifest::load(&location.join("hype.json")) else {
+                continue;
+            };
+            let bin = manifest.bin.unwrap_or_default();
+            if self.packages.contains_key(&manifest.name)
+                || bin.keys().any(|cmd| self.bin_commands.contains_key(cmd))
+            {
+                continue;
+            }
+
+            let pkg_version = format!("{}@{}", manifest.name, manifest.version);
+            for cmd in bin.keys() {
+                self.bin_commands.insert(cmd.clone(), pkg_version.clone());
+            }
+            self.packages.insert(
+                manifest.name.clone(),
+                InstalledPackage {
+                    name: manifest.name,
+                    version: manifest.version,
+                    install_date: chrono::Utc::now().to_rfc3339(),
+                    location,
+                    bin,
+                    checksums: HashMap::new(),
+                    source: None,
+                },
+            );
+        }
+    }
+
     pub fn save(&self) -> Result<(), ModuleError> {
         let data = RegistryData {
             packages: self.packages.clone(),
@@ -112,6 +194,12 @@ impl GlobalPackageRegistry {
             ModuleError::RegistryError(format!("Failed to write to temp file: {}", e))
         })?;
 
+        // Flush before the rename so a crash cannot leave a renamed but empty file
+        temp_file
+            .as_file()
+            .sync_all()
+            .map_err(|e| ModuleError::RegistryError(format!("Failed to sync temp file: {}", e)))?;
+
         temp_file.persist(&self.registry_file).map_err(|e| {
             ModuleError::RegistryError(format!("Failed to persist registry file: {}", e))
         })?;
@@ -162,6 +250,46 @@ impl GlobalPackageRegistry {
         Ok(())
     }
 
+    /// Swap in a new version of an installed package with a single registry
+    /// write. Commands the package already owns do not count as conflicts.
+    pub fn replace_package(
+        &mut self,
+        pkg: InstalledPackage,
+    ) -> Result<Option<InstalledPackage>, ModuleError> {
+        let conflicts: Vec<String> = pkg
+            .bin
+            .keys()
+            .filter(|cmd| {
+                self.bin_commands
+                    .get(*cmd)
+                    .is_some_and(|owner| !owner.starts_with(&format!("{}@", pkg.name)))
+            })
+            .cloned()
+            .collect();
+        if !conflicts.is_empty() {
+            return Err(ModuleError::RegistryError(format!(
+                "Binary command conflicts: {}",
+                conflicts.join(", ")
+            )));
+        }
+
+        let previous = self.packages.remove(&pkg.name);
+        if let Some(old) = &previous {
+            for cmd in old.bin.keys() {
+                self.bin_commands.remove(cmd);
+            }
+        }
+
+        let pkg_version = format!("{}@{}", pkg.name, pkg.version);
+        for cmd in pkg.bin.keys() {
+            self.bin_commands.insert(cmd.clone(), pkg_version.clone());
+        }
+        self.packages.insert(pkg.name.clone(), pkg);
+        self.save()?;
+
+        Ok(previous)
+    }
+
     pub fn remove_package(&mut self, name: &str) -> Result<InstalledPackage, ModuleError> {
         let pkg = self
             .packages
@@ -258,6 +386,8 @@ mod tests {
             install_date: "2025-10-26T12:00:00Z".to_string(),
             location: PathBuf::from("/test/path"),
             bin: bin_map,
+            checksums: HashMap::new(),
+            source: None,
         };
 
         registry.add_package(pkg).unwrap();
@@ -287,6 +417,8 @@ mod tests {
             install_date: "2025-10-26T12:00:00Z".to_string(),
             location: PathBuf::from("/some/path"),
             bin: bin_map,
+            checksums: HashMap::new(),
+            source: None,
         };
 
         registry.add_package(pkg).unwrap();
@@ -314,6 +446,8 @@ mod tests {
             install_date: "2025-10-26T12:00:00Z".to_string(),
             location: PathBuf::from("/rm/path"),
             bin: bin_map,
+            checksums: HashMap::new(),
+            source: None,
         };
 
         registry.add_package(pkg).unwrap();
@@ -352,6 +486,8 @@ mod tests {
             install_date: "2025-10-26T12:00:00Z".to_string(),
             location: PathBuf::from("/path1"),
             bin: bin_map1,
+            checksums: HashMap::new(),
+            source: None,
         };
 
         registry.add_package(pkg1).unwrap();
@@ -379,6 +515,8 @@ mod tests {
             install_date: "2025-10-26T12:00:00Z".to_string(),
             location: PathBuf::from("/first"),
             bin: bin_map1,
+            checksums: HashMap::new(),
+            source: None,
         };
 
         registry.add_package(pkg1).unwrap();
@@ -392,6 +530,8 @@ mod tests {
             install_date: "2025-10-26T12:00:00Z".to_string(),
             location: PathBuf::from("/second"),
             bin: bin_map2,
+            checksums: HashMap::new(),
+            source: None,
         };
 
         let result = registry.add_package(pkg2);
@@ -411,6 +551,8 @@ mod tests {
             install_date: "2025-10-26T12:00:00Z".to_string(),
             location: PathBuf::from("/a"),
             bin: HashMap::new(),
+            checksums: HashMap::new(),
+            source: None,
         };
 
         let pkg2 = InstalledPackage {
@@ -419,6 +561,8 @@ mod tests {
             install_date: "2025-10-26T12:00:00Z".to_string(),
             location: PathBuf::from("/b"),
             bin: HashMap::new(),
+            checksums: HashMap::new(),
+            source: None,
         };
 
         registry.add_package(pkg1).unwrap();
@@ -443,6 +587,8 @@ mod tests {
             install_date: "2025-10-26T12:00:00Z".to_string(),
             location: PathBuf::from("/special"),
             bin: bin_map,
+            checksums: HashMap::new(),
+            source: None,
         };
 
         registry.add_package(pkg).unwrap();
@@ -466,6 +612,8 @@ mod tests {
             install_date: "2025-10-26T12:00:00Z".to_string(),
             location: PathBuf::from("/atomic"),
             bin: HashMap::new(),
+            checksums: HashMap::new(),
+            source: None,
         };
 
         registry.add_package(pkg).unwrap();
@@ -476,6 +624,75 @@ mod tests {
         assert!(loaded.get("atomic-pkg").is_some());
     }
 
+    #[test]
+    fn test_save_replaces_registry_without_leaving_temp_files() {
+        let _lock = TEST_MUTEX.lock().unwrap();
+        let temp = setup_test_env();
+        let mut registry = GlobalPackageRegistry::new().unwrap();
+
+        for i in 0..5 {
+            let mut bin = HashMap::new();
+            bin.insert(format!("cmd{}", i), "bin/cli.lua".to_string());
+            registry
+                .add_package(InstalledPackage {
+                    name: format!("pkg{}", i),
+                    version: "1.0.0".to_string(),
+                    install_date: "2025-10-26T12:00:00Z".to_string(),
+                    location: PathBuf::from("/pkg"),
+                    bin,
+                    checksums: HashMap::new(),
+                    source: None,
+                })
+                .unwrap();
+        }
+
+        let mut entries: Vec<String> = fs::read_dir(temp.path())
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        entries.sort();
+        assert_eq!(entries, vec!["bin", "packages", "registry.json"]);
+        assert_eq!(GlobalPackageRegistry::load().unwrap().list().len(), 5);
+    }
+
+    #[test]
+    fn test_load_recovers_from_corrupted_registry() {
+        let _lock = TEST_MUTEX.lock().unwrap();
+        let temp = setup_test_env();
+        let registry = GlobalPackageRegistry::new().unwrap();
+
+        let pkg_dir = registry.packages_dir().join("tool@1.2.0");
+        fs::create_dir_all(pkg_dir.join("bin")).unwrap();
+        fs::write(pkg_dir.join("bin/cli.lua"), "print('tool')").unwrap();
+        fs::write(
+            pkg_dir.join("hype.json"),
+            r#"{"name": "tool", "version": "1.2.0", "bin": {"tool": "bin/cli.lua"}}"#,
+        )
+        .unwrap();
+        let truncated = r#"{"packages": {"tool": {"name": "tool", "vers"#;
+        fs::write(&registry.registry_file, truncated).unwrap();
+
+        let recovered = GlobalPackageRegistry::load().unwrap();
+        let tool = recovered.get("tool").unwrap();
+        assert_eq!(tool.version, "1.2.0");
+        assert_eq!(tool.location, pkg_dir);
+        assert_eq!(
+            recovered.which_command("tool"),
+            Some("tool@1.2.0".to_string())
+        );
+
+        let backups: Vec<PathBuf> = fs::read_dir(temp.path())
+            .unwrap()
+            .map(|e| e.unwrap().path())
+            .filter(|p| p.to_string_lossy().contains("registry.json.corrupt-"))
+            .collect();
+        assert_eq!(backups.len(), 1);
+        assert_eq!(fs::read_to_string(&backups[0]).unwrap(), truncated);
+
+        let reloaded = GlobalPackageRegistry::load().unwrap();
+        assert!(reloaded.get("tool").is_some());
+    }
+
     #[test]
     fn test_multiple_bin_commands() {
         let _lock = TEST_MUTEX.lock().unwrap();
@@ -493,6 +710,8 @@ mod tests {
             install_date: "2025-10-26T12:00:00Z".to_string(),
             location: PathBuf::from("/multi"),
             bin: bin_map,
+            checksums: HashMap::new(),
+            source: None,
         };
 
         registry.add_package(pkg).unwrap();
@@ -21,6 +21,9 @@ const BUILTIN_MODULES: &[&str] = &["fs", "path", "events", "util", "table"];
 /// 5. Home directory modules (~/.hype/modules)
 /// 6. Returns error if not found
 ///
+/// Scoped package names such as `@myorg/utils` are looked up as
+/// `hype_modules/@myorg/utils/` and never treated as paths.
+///
 /// Handles cross-platform paths, tilde expansion, and relative vs absolute paths.
 pub struct ModuleResolver {
     root_dir: PathBuf,
@@ -81,6 +84,11 @@ impl ModuleResolver {
             return Ok(self.get_builtin_path(module_id));
         }
 
+        if module_id.starts_with('@') {
+            validate_scoped_name(module_id)?;
+            return self.resolve_module_paths(module_id);
+        }
+
         if module_id.starts_with("./") || module_id.starts_with("../") {
             return self.resolve_relative(module_id);
         }
@@ -364,6 +372,10 @@ impl ModuleResolver {
             return Ok(self.get_builtin_path(module_id));
         }
 
+        if module_id.starts_with('@') {
+            validate_scoped_name(module_id)?;
+        }
+
         let mut current = from.to_path_buf();
 
         loop {
@@ -484,6 +496,28 @@ impl ModuleResolver {
     }
 }
 
+/// Check a scoped package name: `@scope/name`, optionally followed by a path
+/// inside the package (`@scope/name/lib/helpers`). Empty, `.` and `..`
+/// segments are rejected so a scoped name cannot climb out of hype_modules.
+fn validate_scoped_name(module_id: &str) -> Result<(), HypeError> {
+    let segments: Vec<&str> = module_id[1..].split('/').collect();
+    let valid = segments.len() >= 2
+        && segments
+            .iter()
+            .all(|segment| !matches!(*segment, "" | "." | "..") && !segment.contains('\\'));
+    if valid {
+        Ok(())
+    } else {
+        Err(HypeError::Execution(
+            ModuleError::InvalidModuleName(format!(
+                "{} (scoped names look like @scope/name)",
+                module_id
+            ))
+            .to_string(),
+        ))
+    }
+}
+
 /// Get the user's home directory in a cross-platform way.
 fn dirs_home() -> Result<PathBuf, HypeError> {
     #[cfg(target_os = "windows")]
@@ -947,4 +981,43 @@ mod tests {
         let result = resolver.resolve("nonexistent");
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_resolve_scoped_package() {
+        let temp_dir = TempDir::new().unwrap();
+        let temp_path = temp_dir.path();
+        let package = temp_path.join("hype_modules").join("@myorg").join("utils");
+        fs::create_dir_all(&package).unwrap();
+        fs::write(package.join("index.lua"), "return {}").unwrap();
+        fs::write(package.join("extra.lua"), "return {}").unwrap();
+
+        let nested_dir = temp_path.join("src");
+        fs::create_dir_all(&nested_dir).unwrap();
+        let resolver = ModuleResolver::new(nested_dir.clone());
+
+        let path = resolver.resolve("@myorg/utils").unwrap();
+        assert_eq!(path, package.join("index.lua"));
+        let path = resolver.resolve("@myorg/utils/extra").unwrap();
+        assert_eq!(path, package.join("extra.lua"));
+        let path = resolver.resolve_from(&nested_dir, "@myorg/utils").unwrap();
+        assert_eq!(path, package);
+    }
+
+    #[test]
+    fn test_resolve_malformed_scoped_names() {
+        let temp_dir = TempDir::new().unwrap();
+        let resolver = ModuleResolver::new(temp_dir.path().to_path_buf());
+        for name in [
+            "@myorg",
+            "@/utils",
+            "@myorg/",
+            "@myorg/../secret",
+            "@myorg/./utils",
+        ] {
+            let err = resolver.resolve(name).unwrap_err().to_string();
+            assert!(err.contains("Invalid module name"), "{}: {}", name, err);
+        }
+        let err = resolver.resolve("@myorg/missing").unwrap_err().to_string();
+        assert!(err.contains("@myorg/missing"), "{}", err);
+    }
 }
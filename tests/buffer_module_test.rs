@@ -0,0 +1,69 @@
+use hype_rs::lua::require::setup_require_fn;
+use hype_rs::modules::loader::ModuleLoader;
+use mlua::Lua;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+fn setup_lua() -> Lua {
+    let lua = Lua::new();
+    let loader = Arc::new(Mutex::new(ModuleLoader::new(PathBuf::from("."))));
+    setup_require_fn(&lua, loader).unwrap();
+    lua
+}
+
+#[test]
+fn test_buffer_uint32_round_trip_both_endians() {
+    let lua = setup_lua();
+    lua.load(
+        r#"
+local buffer = require("buffer")
+local buf = buffer.alloc(8)
+assert(buf:writeUInt32(0xDEADBEEF, 0) == 4)
+assert(buf:writeUInt32(0xDEADBEEF, 4, true) == 8)
+assert(buf:toString("hex") == "deadbeefefbeadde")
+assert(buf:readUInt32(0) == 0xDEADBEEF)
+assert(buf:readUInt32(4, true) == 0xDEADBEEF)
+assert(buf:readUInt32(4) == 0xEFBEADDE)
+"#,
+    )
+    .exec()
+    .unwrap();
+}
+
+#[test]
+fn test_buffer_slice_copies_range() {
+    let lua = setup_lua();
+    lua.load(
+        r#"
+local buffer = require("buffer")
+local buf = buffer.from("hello world")
+local world = buf:slice(6)
+assert(world:toString() == "world")
+assert(#buf:slice(0, 5) == 5)
+world:writeUInt8(0x57, 0)
+assert(world:toString() == "World")
+assert(buf:toString() == "hello world")
+"#,
+    )
+    .exec()
+    .unwrap();
+}
+
+#[test]
+fn test_buffer_out_of_bounds_raises_error() {
+    let lua = setup_lua();
+    lua.load(
+        r#"
+local buffer = require("buffer")
+local buf = buffer.alloc(3)
+local ok, err = pcall(function() return buf:readUInt32(0) end)
+assert(not ok)
+assert(string.find(tostring(err), "out of range", 1, true))
+assert(not pcall(function() return buf:writeUInt16(1, 2) end))
+assert(not pcall(function() return buf:slice(2, 10) end))
+assert(buffer.isBuffer(buf) and not buffer.isBuffer("abc"))
+"#,
+    )
+    .exec()
+    .unwrap();
+}
@@ -0,0 +1,83 @@
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn run_check_in(dir: &Path, args: &[&str]) -> std::process::Output {
+    let manifest = concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml");
+    let mut cmd_args = vec!["run", "--quiet", "--manifest-path", manifest, "--", "check"];
+    cmd_args.extend_from_slice(args);
+    std::process::Command::new("cargo")
+        .args(&cmd_args)
+        .current_dir(dir)
+        .output()
+        .expect("Failed to execute command")
+}
+
+#[test]
+fn test_check_passes_valid_script_without_running_it() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.path().join("main.lua"),
+        "local fs = require('fs')\nerror('should not run')\n",
+    )
+    .unwrap();
+
+    let output = run_check_in(temp_dir.path(), &["main.lua", "--requires"]);
+
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(String::from_utf8_lossy(&output.stdout).contains("no problems found"));
+}
+
+#[test]
+fn test_check_reports_syntax_error_with_line() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.path().join("broken.lua"),
+        "local x = 1\nif x then\n  print(x)\n",
+    )
+    .unwrap();
+
+    let output = run_check_in(temp_dir.path(), &["broken.lua"]);
+
+    assert!(
+        !output.status.success(),
+        "Syntax error should fail the check"
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("broken.lua:"), "{}", stderr);
+    assert!(stderr.contains("'end' expected"), "{}", stderr);
+}
+
+#[test]
+fn test_check_reports_unresolvable_require() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.path().join("main.lua"),
+        "local helpers = require('./lib/missing')\nreturn helpers\n",
+    )
+    .unwrap();
+
+    let output = run_check_in(temp_dir.path(), &["main.lua", "--requires"]);
+
+    assert!(
+        !output.status.success(),
+        "Unresolvable require should fail the check"
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("cannot resolve require('./lib/missing')"),
+        "{}",
+        stderr
+    );
+
+    let output = run_check_in(temp_dir.path(), &["main.lua"]);
+    assert!(
+        output.status.success(),
+        "Require targets are only checked with --requires: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
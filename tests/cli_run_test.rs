@@ -0,0 +1,1119 @@
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, Instant};
+use tempfile::TempDir;
+
+fn serve_script(body: &'static str) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    thread::spawn(move || {
+        for mut stream in listener.incoming().flatten() {
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut line = String::new();
+            while reader.read_line(&mut line).unwrap_or(0) > 0 && line != "\r\n" {
+                line.clear();
+            }
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            let _ = stream.write_all(response.as_bytes());
+        }
+    });
+
+    format!("http://{}/hello.lua", addr)
+}
+
+fn run_hype(args: &[&str]) -> std::process::Output {
+    run_hype_in(Path::new(env!("CARGO_MANIFEST_DIR")), args)
+}
+
+fn run_hype_in(dir: &Path, args: &[&str]) -> std::process::Output {
+    run_hype_with_env(dir, args, &[])
+}
+
+fn run_hype_with_env(dir: &Path, args: &[&str], envs: &[(&str, &str)]) -> std::process::Output {
+    std::process::Command::new(env!("CARGO_BIN_EXE_hype"))
+        .arg("run")
+        .args(args)
+        .envs(envs.iter().copied())
+        .current_dir(dir)
+        .output()
+        .expect("Failed to execute command")
+}
+
+#[test]
+fn test_run_timeout_terminates_infinite_loop() {
+    let temp_dir = TempDir::new().unwrap();
+    let script_path = temp_dir.path().join("loop.lua");
+    fs::write(&script_path, "while true do end").unwrap();
+
+    let start = Instant::now();
+    let output = run_hype(&[script_path.to_str().unwrap(), "--timeout", "500ms"]);
+    let elapsed = start.elapsed();
+
+    assert!(!output.status.success(), "Timed out script should fail");
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("timed out"),
+        "stderr should report the timeout: {}",
+        stderr
+    );
+    assert!(
+        elapsed < Duration::from_secs(60),
+        "Script should be stopped by the timeout"
+    );
+}
+
+#[test]
+fn test_run_timeout_allows_fast_script() {
+    let temp_dir = TempDir::new().unwrap();
+    let script_path = temp_dir.path().join("fast.lua");
+    fs::write(&script_path, "print('finished')").unwrap();
+
+    let output = run_hype(&[script_path.to_str().unwrap(), "--timeout", "5s"]);
+
+    assert!(output.status.success(), "Fast script should succeed");
+    assert!(String::from_utf8_lossy(&output.stdout).contains("finished"));
+}
+
+#[test]
+fn test_run_rejects_invalid_timeout() {
+    let temp_dir = TempDir::new().unwrap();
+    let script_path = temp_dir.path().join("fast.lua");
+    fs::write(&script_path, "print('finished')").unwrap();
+
+    let output = run_hype(&[script_path.to_str().unwrap(), "--timeout", "soon"]);
+
+    assert!(
+        !output.status.success(),
+        "Invalid timeout should be rejected"
+    );
+}
+
+#[test]
+fn test_run_instruction_limit_stops_tight_loop() {
+    let temp_dir = TempDir::new().unwrap();
+    let script_path = temp_dir.path().join("spin.lua");
+    fs::write(&script_path, "local n = 0\nwhile true do n = n + 1 end").unwrap();
+
+    let output = run_hype(&[
+        script_path.to_str().unwrap(),
+        "--instruction-limit",
+        "100000",
+    ]);
+
+    assert!(
+        !output.status.success(),
+        "Script over the instruction limit should fail"
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Instruction limit exceeded") && stderr.contains("> 100000"),
+        "stderr should report the instruction limit: {}",
+        stderr
+    );
+}
+
+#[test]
+fn test_run_instruction_limit_ignored_in_debug_mode() {
+    let temp_dir = TempDir::new().unwrap();
+    let script_path = temp_dir.path().join("count.lua");
+    fs::write(
+        &script_path,
+        "local n = 0\nfor i = 1, 200000 do n = n + i end\nprint(n)",
+    )
+    .unwrap();
+
+    let output = run_hype(&[
+        script_path.to_str().unwrap(),
+        "--instruction-limit",
+        "1000",
+        "--debug",
+    ]);
+
+    assert!(
+        output.status.success(),
+        "--debug should lift the instruction limit: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(String::from_utf8_lossy(&output.stdout).contains("20000100000"));
+}
+
+#[test]
+fn test_run_stats_json_to_file() {
+    let temp_dir = TempDir::new().unwrap();
+    let script_path = temp_dir.path().join("sum.lua");
+    let stats_path = temp_dir.path().join("stats.json");
+    fs::write(
+        &script_path,
+        "local n = 0\nfor i = 1, 10000 do n = n + i end\nprint(n)",
+    )
+    .unwrap();
+
+    let stats_arg = format!("--stats-json={}", stats_path.display());
+    let output = run_hype(&[script_path.to_str().unwrap(), &stats_arg]);
+
+    assert!(output.status.success(), "Script should succeed");
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "50005000");
+
+    let stats: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&stats_path).unwrap()).unwrap();
+    assert_eq!(stats["success"], true);
+    assert_eq!(stats["exit_code"], 0);
+    assert!(stats["execution_time_ms"].as_f64().unwrap() >= 0.0);
+    assert!(stats["instructions_executed"].as_u64().unwrap() > 0);
+    assert!(stats["memory_usage"].as_u64().unwrap() > 0);
+}
+
+#[test]
+fn test_run_stats_json_to_stderr_keeps_stdout_clean() {
+    let temp_dir = TempDir::new().unwrap();
+    let script_path = temp_dir.path().join("hello.lua");
+    fs::write(&script_path, "print('hello')").unwrap();
+
+    let output = run_hype(&["--stats-json", script_path.to_str().unwrap()]);
+
+    assert!(output.status.success(), "Script should succeed");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("hello"));
+    assert!(
+        !stdout.contains("memory_usage"),
+        "stats must not go to stdout"
+    );
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let stats_line = stderr
+        .lines()
+        .rev()
+        .find(|line| line.starts_with('{'))
+        .expect("stderr should contain a JSON stats line");
+    let stats: serde_json::Value = serde_json::from_str(stats_line).unwrap();
+    assert_eq!(stats["success"], true);
+    assert!(stats.get("memory_usage").is_some());
+}
+
+const REMOTE_SCRIPT: &str = "print('remote ok')";
+const REMOTE_SCRIPT_SHA256: &str = "sha256-Xe1TqAWW6ng7Lzn+T1tsIEzMVPKpQqQnFA6cxwsT7EM=";
+
+#[test]
+fn test_run_remote_script_requires_allow_remote() {
+    let url = serve_script(REMOTE_SCRIPT);
+
+    let output = run_hype(&[&url]);
+
+    assert!(!output.status.success(), "Remote script should be refused");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("--allow-remote"), "{}", stderr);
+    assert!(!String::from_utf8_lossy(&output.stdout).contains("remote ok"));
+}
+
+#[test]
+fn test_run_remote_script_with_allow_remote_and_integrity() {
+    let url = serve_script(REMOTE_SCRIPT);
+
+    let output = run_hype(&["--allow-remote", "--integrity", REMOTE_SCRIPT_SHA256, &url]);
+
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(String::from_utf8_lossy(&output.stdout).contains("remote ok"));
+}
+
+#[test]
+fn test_run_remote_script_rejects_integrity_mismatch() {
+    let url = serve_script("print('tampered')");
+
+    let output = run_hype(&["--allow-remote", "--integrity", REMOTE_SCRIPT_SHA256, &url]);
+
+    assert!(!output.status.success(), "Mismatched script should fail");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Integrity check failed"), "{}", stderr);
+    assert!(!String::from_utf8_lossy(&output.stdout).contains("tampered"));
+}
+
+#[test]
+fn test_run_script_with_shebang() {
+    let temp_dir = TempDir::new().unwrap();
+    let script_path = temp_dir.path().join("greet.lua");
+    fs::write(
+        &script_path,
+        "#!/usr/bin/env hype\nprint('hello ' .. args[1])\n",
+    )
+    .unwrap();
+
+    let output = run_hype(&[script_path.to_str().unwrap(), "world"]);
+
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(String::from_utf8_lossy(&output.stdout).contains("hello world"));
+}
+
+#[test]
+fn test_run_shebang_keeps_error_line_numbers() {
+    let temp_dir = TempDir::new().unwrap();
+    let script_path = temp_dir.path().join("fails.lua");
+    fs::write(
+        &script_path,
+        "#!/usr/bin/env hype\nlocal x = 1\nerror('boom')\n",
+    )
+    .unwrap();
+
+    let output = run_hype(&[script_path.to_str().unwrap()]);
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains(":3: boom"), "{}", stderr);
+}
+
+#[test]
+fn test_run_check_accepts_valid_script_without_running_it() {
+    let temp_dir = TempDir::new().unwrap();
+    let script_path = temp_dir.path().join("valid.lua");
+    fs::write(
+        &script_path,
+        "print('side effect')\nerror('should not run')",
+    )
+    .unwrap();
+
+    let output = run_hype(&["--check", script_path.to_str().unwrap()]);
+
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(!String::from_utf8_lossy(&output.stdout).contains("side effect"));
+}
+
+#[test]
+fn test_run_check_reports_syntax_error_location() {
+    let temp_dir = TempDir::new().unwrap();
+    let script_path = temp_dir.path().join("broken.lua");
+    fs::write(&script_path, "local x = 1\nlocal = 2\n").unwrap();
+
+    let output = run_hype(&["--check", script_path.to_str().unwrap()]);
+
+    assert!(
+        !output.status.success(),
+        "Broken script should fail the check"
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("broken.lua:2:"), "{}", stderr);
+}
+
+#[test]
+fn test_run_check_follows_local_requires() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::create_dir(temp_dir.path().join("lib")).unwrap();
+    fs::write(
+        temp_dir.path().join("main.lua"),
+        "local util = require('./lib/util')\nprint(util.x)",
+    )
+    .unwrap();
+    fs::write(temp_dir.path().join("lib/util.lua"), "return {\n  x =\n}\n").unwrap();
+
+    let output = run_hype_in(temp_dir.path(), &["--check", "main.lua"]);
+
+    assert!(
+        !output.status.success(),
+        "Broken dependency should fail the check"
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("util.lua:3:"), "{}", stderr);
+}
+
+#[test]
+fn test_on_exit_hook_runs_before_process_exit() {
+    let temp_dir = TempDir::new().unwrap();
+    let sentinel = temp_dir.path().join("sentinel.txt");
+    let script_path = temp_dir.path().join("cleanup.lua");
+    fs::write(
+        &script_path,
+        format!(
+            r#"
+local process = require("process")
+local fs = require("fs")
+process.onExit(function(code)
+    fs.writeFileSync({:?}, "cleaned up " .. code)
+end)
+process.exit(3)
+"#,
+            sentinel.to_str().unwrap()
+        ),
+    )
+    .unwrap();
+
+    let output = run_hype(&[script_path.to_str().unwrap()]);
+
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(fs::read_to_string(&sentinel).unwrap(), "cleaned up 3");
+}
+
+#[test]
+fn test_on_exit_hook_runs_after_script_error() {
+    let temp_dir = TempDir::new().unwrap();
+    let sentinel = temp_dir.path().join("sentinel.txt");
+    let script_path = temp_dir.path().join("failing.lua");
+    fs::write(
+        &script_path,
+        format!(
+            r#"
+local process = require("process")
+local fs = require("fs")
+process.onExit(function(code)
+    fs.writeFileSync({:?}, "cleaned up " .. code)
+end)
+error("boom")
+"#,
+            sentinel.to_str().unwrap()
+        ),
+    )
+    .unwrap();
+
+    let output = run_hype(&[script_path.to_str().unwrap()]);
+
+    assert!(!output.status.success());
+    assert_eq!(fs::read_to_string(&sentinel).unwrap(), "cleaned up 1");
+}
+
+#[test]
+fn test_run_output_events_stream() {
+    let temp_dir = TempDir::new().unwrap();
+    let script_path = temp_dir.path().join("events.lua");
+    fs::write(
+        &script_path,
+        "print('starting')\nio.write('partial')\nerror('went wrong')\n",
+    )
+    .unwrap();
+
+    let output = run_hype(&["--output-events", script_path.to_str().unwrap()]);
+
+    assert!(!output.status.success());
+    let events: Vec<serde_json::Value> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("each line should be a JSON event"))
+        .collect();
+    let types: Vec<&str> = events
+        .iter()
+        .map(|event| event["type"].as_str().unwrap())
+        .collect();
+    assert_eq!(types, ["print", "write", "metrics", "error"]);
+
+    assert!(events[0]["text"].as_str().unwrap().contains("starting"));
+    assert_eq!(events[1]["text"], "partial");
+    assert_eq!(events[2]["success"], false);
+    assert!(events[2]["execution_time_ms"].is_number());
+    assert_eq!(events[3]["message"], "went wrong");
+    assert_eq!(events[3]["line"], 3);
+    assert!(events[3]["file"].as_str().unwrap().ends_with("events.lua"));
+}
+
+#[test]
+fn test_run_output_format_json_records() {
+    let temp_dir = TempDir::new().unwrap();
+    let script_path = temp_dir.path().join("log.lua");
+    fs::write(&script_path, "print('first')\nprint('second', 2)\n").unwrap();
+
+    let output = run_hype(&["--output-format", "json", script_path.to_str().unwrap()]);
+
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let records: Vec<serde_json::Value> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("each line should be a JSON record"))
+        .collect();
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0]["stream"], "stdout");
+    assert!(records[0]["line"].as_str().unwrap().contains("first"));
+    assert!(records[1]["line"].as_str().unwrap().ends_with("\t2"));
+
+    let timestamps: Vec<&str> = records
+        .iter()
+        .map(|record| record["timestamp"].as_str().unwrap())
+        .collect();
+    assert!(timestamps[0] < timestamps[1], "{:?}", timestamps);
+}
+
+#[test]
+fn test_run_timeout_interrupts_blocking_sleep() {
+    let temp_dir = TempDir::new().unwrap();
+    let script_path = temp_dir.path().join("sleepy.lua");
+    fs::write(
+        &script_path,
+        "local time = require('time')\ntime.sleep(10000)\nprint('woke up')\n",
+    )
+    .unwrap();
+
+    // Build first so compilation time does not count against the timeout
+    let _ = run_hype(&["--version"]);
+    let start = Instant::now();
+    let output = run_hype(&["--timeout", "1", script_path.to_str().unwrap()]);
+    let elapsed = start.elapsed();
+
+    assert!(!output.status.success(), "Script should be killed");
+    assert!(elapsed < Duration::from_secs(5), "took {:?}", elapsed);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("timed out"), "{}", stderr);
+    assert!(!String::from_utf8_lossy(&output.stdout).contains("woke up"));
+}
+
+#[test]
+fn test_console_honors_hype_log_level() {
+    let temp_dir = TempDir::new().unwrap();
+    let script_path = temp_dir.path().join("log.lua");
+    fs::write(
+        &script_path,
+        r#"
+local console = require("console")
+console.info("starting")
+console.warn("low disk")
+console.error("failed")
+console.json({event = "done"}, "error")
+"#,
+    )
+    .unwrap();
+
+    let output = run_hype_with_env(
+        temp_dir.path(),
+        &[script_path.to_str().unwrap()],
+        &[("HYPE_LOG", "warn")],
+    );
+
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty(), "console must not write to stdout");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!stderr.contains("starting"), "{}", stderr);
+    assert!(stderr.contains("Z [WARN] low disk\n"), "{}", stderr);
+    assert!(stderr.contains("Z [ERROR] failed\n"), "{}", stderr);
+    let record = stderr
+        .lines()
+        .find(|line| line.starts_with("{\""))
+        .expect("console.json line");
+    let record: serde_json::Value = serde_json::from_str(record).unwrap();
+    assert_eq!(record["event"], "done");
+    assert_eq!(record["level"], "error");
+}
+
+#[test]
+fn test_process_stdin_lines_piped_through_script() {
+    let temp_dir = TempDir::new().unwrap();
+    let script_path = temp_dir.path().join("upper.lua");
+    fs::write(
+        &script_path,
+        r#"
+local process = require("process")
+assert(process.stdin.isTTY == false)
+local count = 0
+for line in process.stdin.lines() do
+    process.stdout.write(line:upper() .. "\n")
+    count = count + 1
+end
+process.stderr.write("lines: " .. count)
+"#,
+    )
+    .unwrap();
+
+    let mut child = std::process::Command::new(env!("CARGO_BIN_EXE_hype"))
+        .arg("run")
+        .arg(&script_path)
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .expect("Failed to execute command");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"hello\r\nworld\nno newline")
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{}", stderr);
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "HELLO\nWORLD\nNO NEWLINE\n"
+    );
+    assert!(stderr.contains("lines: 3"), "{}", stderr);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_sigint_runs_process_on_handler_before_exit() {
+    let temp_dir = TempDir::new().unwrap();
+    let script_path = temp_dir.path().join("server.lua");
+    let ready = temp_dir.path().join("ready");
+    let cleaned = temp_dir.path().join("cleaned");
+    fs::write(
+        &script_path,
+        format!(
+            r#"
+local fs = require("fs")
+local process = require("process")
+local time = require("time")
+
+process.on("SIGINT", function(signal)
+    fs.writeFileSync("{cleaned}", "cleaned up after " .. signal)
+    process.exit(130)
+end)
+fs.writeFileSync("{ready}", "")
+time.sleep(30000)
+"#,
+            cleaned = cleaned.display(),
+            ready = ready.display()
+        ),
+    )
+    .unwrap();
+
+    let mut child = std::process::Command::new(env!("CARGO_BIN_EXE_hype"))
+        .args(["run", script_path.to_str().unwrap()])
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .expect("Failed to execute command");
+
+    let start = Instant::now();
+    while !ready.exists() {
+        assert!(
+            start.elapsed() < Duration::from_secs(30),
+            "script never started"
+        );
+        thread::sleep(Duration::from_millis(20));
+    }
+    let status = std::process::Command::new("kill")
+        .args(["-INT", &child.id().to_string()])
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    let status = child.wait().unwrap();
+    assert!(
+        start.elapsed() < Duration::from_secs(20),
+        "sleep was not interrupted"
+    );
+    assert_eq!(status.code(), Some(130));
+    assert_eq!(
+        fs::read_to_string(&cleaned).unwrap(),
+        "cleaned up after SIGINT"
+    );
+}
+
+#[test]
+fn test_compiled_bytecode_matches_source_output() {
+    let temp_dir = TempDir::new().unwrap();
+    let script_path = temp_dir.path().join("report.lua");
+    fs::write(
+        &script_path,
+        r#"
+local json = require("json")
+local squares = {}
+for i = 1, 5 do
+    squares[#squares + 1] = i * i
+end
+print("sum", squares[1] + squares[5])
+print(json.encode(squares))
+print(string.format("%.2f", math.pi))
+"#,
+    )
+    .unwrap();
+    let bytecode_path = temp_dir.path().join("out.luac");
+
+    let compiled = std::process::Command::new(env!("CARGO_BIN_EXE_hype"))
+        .args(["compile", script_path.to_str().unwrap(), "-o"])
+        .arg(&bytecode_path)
+        .output()
+        .unwrap();
+    assert!(
+        compiled.status.success(),
+        "{}",
+        String::from_utf8_lossy(&compiled.stderr)
+    );
+    assert!(fs::read(&bytecode_path).unwrap().starts_with(b"\x1bLua"));
+
+    let from_source = run_hype(&[script_path.to_str().unwrap()]);
+    let from_bytecode = run_hype(&[bytecode_path.to_str().unwrap()]);
+    assert!(
+        from_bytecode.status.success(),
+        "{}",
+        String::from_utf8_lossy(&from_bytecode.stderr)
+    );
+    assert!(String::from_utf8_lossy(&from_source.stdout).contains("26"));
+    assert_eq!(from_bytecode.stdout, from_source.stdout);
+}
+
+#[test]
+fn test_compiled_bytecode_keeps_error_line_numbers() {
+    let temp_dir = TempDir::new().unwrap();
+    let script_path = temp_dir.path().join("fails.lua");
+    fs::write(&script_path, "local x = 1\n\nerror('boom')\n").unwrap();
+
+    let compiled = std::process::Command::new(env!("CARGO_BIN_EXE_hype"))
+        .args(["compile", script_path.to_str().unwrap()])
+        .output()
+        .unwrap();
+    assert!(compiled.status.success());
+
+    let output = run_hype(&[temp_dir.path().join("fails.luac").to_str().unwrap()]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains(":3: boom"), "{}", stderr);
+}
+
+#[test]
+fn test_strict_sandbox_refuses_bytecode() {
+    let temp_dir = TempDir::new().unwrap();
+    let script_path = temp_dir.path().join("s.lua");
+    fs::write(&script_path, "print('from-source')\n").unwrap();
+    let other_path = temp_dir.path().join("other.lua");
+    fs::write(&other_path, "print('src-changed')\n").unwrap();
+    let bytecode_path = temp_dir.path().join("s.luac");
+
+    let compiled = std::process::Command::new(env!("CARGO_BIN_EXE_hype"))
+        .args(["compile", other_path.to_str().unwrap(), "-o"])
+        .arg(&bytecode_path)
+        .output()
+        .unwrap();
+    assert!(compiled.status.success());
+
+    // A .luac beside the script is never run in its place
+    let output = run_hype(&["--sandbox", "strict", script_path.to_str().unwrap()]);
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout).trim(),
+        "from-source"
+    );
+
+    let output = run_hype(&["--sandbox", "strict", bytecode_path.to_str().unwrap()]);
+    assert!(!output.status.success());
+    assert!(!String::from_utf8_lossy(&output.stdout).contains("src-changed"));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("bytecode"), "{}", stderr);
+}
+
+#[test]
+fn test_run_args_as_table_collects_opts() {
+    let temp_dir = TempDir::new().unwrap();
+    let script_path = temp_dir.path().join("s.lua");
+    fs::write(
+        &script_path,
+        r#"
+assert(_opts.name == "bob", tostring(_opts.name))
+assert(_opts.verbose == true, tostring(_opts.verbose))
+assert(args[1] == "pos1", tostring(args[1]))
+assert(args[2] == nil)
+assert(args.has_flag("verbose") and args.get_named("name") == "bob")
+print("opts ok")
+"#,
+    )
+    .unwrap();
+
+    let output = run_hype(&[
+        "--args-as-table=verbose",
+        script_path.to_str().unwrap(),
+        "--name",
+        "bob",
+        "--verbose",
+        "pos1",
+    ]);
+
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(String::from_utf8_lossy(&output.stdout).contains("opts ok"));
+
+    // Without the option scripts see no _opts and values stay positional
+    fs::write(
+        &script_path,
+        "assert(_opts == nil)\nassert(args[1] == 'bob')",
+    )
+    .unwrap();
+    let output = run_hype(&[script_path.to_str().unwrap(), "--name", "bob"]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+#[test]
+fn test_run_require_preloads_modules_in_order() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.path().join("polyfill.lua"),
+        "_G.greeting = 'hello'\n_G.loaded = (_G.loaded or '') .. 'polyfill,'\nreturn {}",
+    )
+    .unwrap();
+    fs::write(
+        temp_dir.path().join("setup.lua"),
+        "_G.loaded = (_G.loaded or '') .. 'setup'",
+    )
+    .unwrap();
+    fs::write(
+        temp_dir.path().join("main.lua"),
+        "io.write(greeting .. ' ' .. loaded .. '\\n')",
+    )
+    .unwrap();
+
+    let output = run_hype_in(
+        temp_dir.path(),
+        &[
+            "-r",
+            "./polyfill.lua",
+            "--require",
+            "./setup.lua",
+            "main.lua",
+        ],
+    );
+
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "hello polyfill,setup\n"
+    );
+}
+
+#[test]
+fn test_run_require_error_aborts_before_script() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(temp_dir.path().join("broken.lua"), "error('bad setup')").unwrap();
+    fs::write(temp_dir.path().join("main.lua"), "io.write('main ran')").unwrap();
+
+    let output = run_hype_in(temp_dir.path(), &["-r", "./broken.lua", "main.lua"]);
+
+    assert!(!output.status.success());
+    assert!(!String::from_utf8_lossy(&output.stdout).contains("main ran"));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Failed to preload module './broken.lua'") && stderr.contains("bad setup"),
+        "{}",
+        stderr
+    );
+}
+
+#[test]
+fn test_run_uncaught_error_reports_line_and_exit_code() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.path().join("fails.lua"),
+        "io.write('before\\n')\nlocal function explode()\n    local x = 1\n    error('kaboom')\nend\nexplode()\nio.write('after\\n')\n",
+    )
+    .unwrap();
+
+    let output = run_hype_in(temp_dir.path(), &["fails.lua"]);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "before\n");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("fails.lua:4: kaboom\n"), "{}", stderr);
+    assert!(stderr.contains("stack traceback:"), "{}", stderr);
+    assert!(stderr.contains("fails.lua:6: in main chunk"), "{}", stderr);
+    // Reported once, without the Rust error wrapper
+    assert_eq!(stderr.matches("kaboom").count(), 1, "{}", stderr);
+}
+
+#[test]
+fn test_run_missing_script_exits_with_io_code() {
+    let temp_dir = TempDir::new().unwrap();
+
+    let output = run_hype_in(temp_dir.path(), &["missing.lua"]);
+
+    assert_eq!(output.status.code(), Some(2));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("missing.lua"), "{}", stderr);
+}
+
+#[test]
+fn test_run_pretty_print_inspects_tables() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.path().join("show.lua"),
+        "print('config', {a = 1})\n",
+    )
+    .unwrap();
+
+    let output = run_hype_in(temp_dir.path(), &["--pretty-print", "show.lua"]);
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "config\t{ a = 1 }\n"
+    );
+
+    // Opt-in: by default print shows the table's address like plain Lua
+    let output = run_hype_in(temp_dir.path(), &["show.lua"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.starts_with("config\ttable: 0x"), "{}", stdout);
+}
+
+#[test]
+fn test_run_watch_reruns_when_script_or_module_changes() {
+    use std::process::{Command, Stdio};
+    use std::sync::mpsc::channel;
+
+    let temp_dir = TempDir::new().unwrap();
+    let dir = temp_dir.path();
+    fs::write(dir.join("greet.lua"), "return {msg = 'hello'}\n").unwrap();
+    fs::write(
+        dir.join("app.lua"),
+        "print(require('./greet').msg .. ' from app')\n",
+    )
+    .unwrap();
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_hype"))
+        .args(["run", "--watch", "app.lua"])
+        .current_dir(dir)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    // Lines from both streams; the two may interleave in any order
+    let (lines_tx, lines) = channel();
+    let stderr = BufReader::new(child.stderr.take().unwrap());
+    let stderr_tx = lines_tx.clone();
+    thread::spawn(move || {
+        for line in stderr.lines().map_while(Result::ok) {
+            let _ = stderr_tx.send(line);
+        }
+    });
+    let stdout = BufReader::new(child.stdout.take().unwrap());
+    thread::spawn(move || {
+        for line in stdout.lines().map_while(Result::ok) {
+            let _ = lines_tx.send(line);
+        }
+    });
+
+    // Wait for a run to print `expected` and for the watcher to be ready again
+    let mut seen = Vec::new();
+    let mut wait_for_run = |expected: &str| {
+        let deadline = Instant::now() + Duration::from_secs(60);
+        let (mut printed, mut waiting) = (false, false);
+        while let Some(remaining) = deadline.checked_duration_since(Instant::now()) {
+            let Ok(line) = lines.recv_timeout(remaining) else {
+                break;
+            };
+            printed |= line == expected;
+            waiting |= line.starts_with("[watch] Waiting");
+            seen.push(line);
+            if printed && waiting {
+                return;
+            }
+        }
+        panic!("no run printed {:?}: {:?}", expected, seen);
+    };
+
+    wait_for_run("hello from app");
+    fs::write(dir.join("greet.lua"), "return {msg = 'goodbye'}\n").unwrap();
+    wait_for_run("goodbye from app");
+    fs::write(dir.join("app.lua"), "print('edited app')\n").unwrap();
+    wait_for_run("edited app");
+
+    child.kill().unwrap();
+    child.wait().unwrap();
+}
+
+#[test]
+fn test_run_process_argv_and_exec_path() {
+    let temp_dir = TempDir::new().unwrap();
+    let script_path = temp_dir.path().join("argv.lua");
+    fs::write(
+        &script_path,
+        r#"
+local process = require("process")
+local os = require("os")
+local fs = require("fs")
+assert(process.execPath == os.execPath())
+assert(fs.existsSync(process.execPath), process.execPath)
+print("argv0 " .. process.argv[0])
+for i = 1, #process.argv do
+    print("arg " .. i .. " " .. process.argv[i])
+end
+"#,
+    )
+    .unwrap();
+    let script = script_path.to_str().unwrap();
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_hype"))
+        .args(["run", script, "alpha", "beta", "gamma"])
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let expected = format!("argv0 {}\narg 1 alpha\narg 2 beta\narg 3 gamma\n", script);
+    assert!(stdout.contains(&expected), "{}", stdout);
+}
+
+#[test]
+fn test_run_line_buffered_stdout_interleaves_with_stderr() {
+    let temp_dir = TempDir::new().unwrap();
+    let script_path = temp_dir.path().join("interleave.lua");
+    fs::write(
+        &script_path,
+        r#"
+local process = require("process")
+print("out 1")
+process.flush()
+process.stderr.write("err 1\n")
+process.setStdoutLineBuffered(true)
+for i = 2, 4 do
+    print("out " .. i)
+    process.stderr.write("err " .. i .. "\n")
+end
+"#,
+    )
+    .unwrap();
+
+    // Send both streams to one file so the order they were written in is kept
+    let log_path = temp_dir.path().join("combined.log");
+    let log = fs::File::create(&log_path).unwrap();
+    let status = std::process::Command::new(env!("CARGO_BIN_EXE_hype"))
+        .args(["run", script_path.to_str().unwrap()])
+        .stdout(log.try_clone().unwrap())
+        .stderr(log)
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    let combined = fs::read_to_string(&log_path).unwrap();
+    assert_eq!(
+        combined,
+        "out 1\nerr 1\nout 2\nerr 2\nout 3\nerr 3\nout 4\nerr 4\n"
+    );
+}
+
+#[test]
+fn test_run_absolute_require_needs_flag() {
+    let temp_dir = TempDir::new().unwrap();
+    let module_path = temp_dir.path().join("shared.lua");
+    fs::write(&module_path, "return { answer = 42 }").unwrap();
+    let script_path = temp_dir.path().join("main.lua");
+    fs::write(
+        &script_path,
+        format!(
+            "local shared = require({:?})\nprint(\"answer \" .. shared.answer)\n",
+            module_path.canonicalize().unwrap().to_str().unwrap()
+        ),
+    )
+    .unwrap();
+    let script = script_path.to_str().unwrap();
+
+    let output = run_hype(&[script]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Absolute paths"), "{}", stderr);
+
+    let output = run_hype(&["--allow-absolute-requires", script]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(String::from_utf8_lossy(&output.stdout).contains("answer 42"));
+}
+
+#[test]
+fn test_run_sandbox_strict_blocks_file_writes() {
+    let temp_dir = TempDir::new().unwrap();
+    let script_path = temp_dir.path().join("write.lua");
+    fs::write(
+        &script_path,
+        r#"
+local fs = require("fs")
+print("read " .. fs.readFileSync("in.txt"))
+fs.writeFileSync("out.txt", "hello")
+print("wrote")
+"#,
+    )
+    .unwrap();
+    fs::write(temp_dir.path().join("in.txt"), "input").unwrap();
+    let script = script_path.to_str().unwrap();
+
+    let output = run_hype_in(temp_dir.path(), &["--sandbox", "strict", script]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("read input"));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("disabled by the security policy"),
+        "{}",
+        stderr
+    );
+    assert!(!String::from_utf8_lossy(&output.stdout).contains("wrote"));
+    assert!(!temp_dir.path().join("out.txt").exists());
+
+    for args in [
+        &["--sandbox", "unsafe", script][..],
+        &["--unsafe", script][..],
+    ] {
+        let output = run_hype_in(temp_dir.path(), args);
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        assert!(String::from_utf8_lossy(&output.stdout).contains("wrote"));
+        assert_eq!(
+            fs::read_to_string(temp_dir.path().join("out.txt")).unwrap(),
+            "hello"
+        );
+        fs::remove_file(temp_dir.path().join("out.txt")).unwrap();
+    }
+}
+
+#[test]
+fn test_run_sandbox_strict_blocks_network_and_subprocess() {
+    let url = serve_script("print('hi')");
+    let temp_dir = TempDir::new().unwrap();
+    let script_path = temp_dir.path().join("net.lua");
+    fs::write(
+        &script_path,
+        format!(
+            r#"
+local http = require("http")
+local process = require("process")
+local ok, err = pcall(http.get, {:?})
+print("http", ok, not ok and err.code or "")
+ok = pcall(process.exec, "echo", {{"hi"}})
+print("exec", ok)
+"#,
+            url
+        ),
+    )
+    .unwrap();
+    let script = script_path.to_str().unwrap();
+
+    let output = run_hype(&["--sandbox", "strict", script]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("http\tfalse\tEACCES"), "{}", stdout);
+    assert!(stdout.contains("exec\tfalse"), "{}", stdout);
+
+    let output = run_hype(&["--sandbox", "unsafe", script]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("http\ttrue"), "{}", stdout);
+    assert!(stdout.contains("exec\ttrue"), "{}", stdout);
+}
@@ -0,0 +1,43 @@
+#[cfg(feature = "clipboard")]
+mod tests {
+    use hype_rs::lua::require::setup_require_fn;
+    use hype_rs::modules::loader::ModuleLoader;
+    use mlua::Lua;
+    use std::path::PathBuf;
+    use std::sync::{Arc, Mutex};
+
+    fn setup_lua() -> Lua {
+        let lua = Lua::new();
+        let loader = Arc::new(Mutex::new(ModuleLoader::new(PathBuf::from("."))));
+        setup_require_fn(&lua, loader).unwrap();
+        lua
+    }
+
+    /// Round-trips text where a display is available; on headless machines
+    /// both calls must fail with a structured ERR_UNAVAILABLE error.
+    #[test]
+    fn test_write_then_read_or_unavailable() {
+        let lua = setup_lua();
+        let outcome: String = lua
+            .load(
+                r#"
+local clipboard = require("clipboard")
+local text = "hype clipboard test ✓"
+local ok, err = pcall(clipboard.write, text)
+if ok then
+    assert(clipboard.read() == text)
+    return "roundtrip"
+end
+
+assert(err.code == "ERR_UNAVAILABLE" and err.kind == "clipboard", tostring(err))
+assert(err.message:find("Clipboard is unavailable", 1, true), err.message)
+local ok, read_err = pcall(clipboard.read)
+assert(not ok and read_err.code == "ERR_UNAVAILABLE")
+return "unavailable"
+"#,
+            )
+            .eval()
+            .unwrap();
+        assert!(outcome == "roundtrip" || outcome == "unavailable");
+    }
+}
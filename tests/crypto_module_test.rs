@@ -70,6 +70,31 @@ assert(count == 16)
     .unwrap();
 }
 
+#[test]
+fn test_crypto_random_bytes_encoded() {
+    let lua = setup_lua();
+    lua.load(
+        r#"
+local crypto = require("crypto")
+local token = crypto.randomBytes(16, "hex")
+assert(type(token) == "string")
+assert(#token == 32, #token)
+assert(token:match("^[0-9a-f]+$"))
+
+local encoded = crypto.randomBytes(24, "base64")
+local raw = crypto.base64Decode(encoded)
+assert(#raw == 24)
+assert(crypto.base64Encode(raw) == encoded)
+assert(crypto.hexEncode(crypto.hexDecode(token)) == token)
+
+local ok, err = pcall(crypto.randomBytes, 16, "latin1")
+assert(not ok and tostring(err):find("Unknown encoding"))
+"#,
+    )
+    .exec()
+    .unwrap();
+}
+
 #[test]
 fn test_crypto_random_int() {
     let lua = setup_lua();
@@ -244,6 +269,7 @@ local crypto = require("crypto")
 assert(type(crypto) == "table")
 assert(type(crypto.hash) == "function")
 assert(type(crypto.hashFile) == "function")
+assert(type(crypto.hashDir) == "function")
 assert(type(crypto.hmac) == "function")
 assert(type(crypto.randomBytes) == "function")
 assert(type(crypto.randomInt) == "function")
@@ -294,3 +320,79 @@ assert(uuid1 ~= uuid2)
     .exec()
     .unwrap();
 }
+
+#[test]
+fn test_crypto_seeded_sequences_are_reproducible() {
+    let lua = setup_lua();
+    lua.load(
+        r#"
+local crypto = require("crypto")
+local function sequence(seed)
+    crypto.seedInsecure(seed)
+    local values = {}
+    for i = 1, 5 do
+        values[i] = crypto.randomIntInsecure(1, 1000000)
+    end
+    values[6] = crypto.randomFloat()
+    return table.concat(values, ",")
+end
+
+local first = sequence(1234)
+assert(sequence(1234) == first)
+assert(sequence(5678) ~= first)
+
+local f = crypto.randomFloat()
+assert(f >= 0 and f < 1)
+assert(not pcall(crypto.randomIntInsecure, 3, 3))
+"#,
+    )
+    .exec()
+    .unwrap();
+}
+
+#[test]
+fn test_crypto_hash_dir() {
+    let first = tempfile::tempdir().unwrap();
+    let second = tempfile::tempdir().unwrap();
+    for (root, order) in [
+        (first.path(), ["a.txt", "nested/b.txt"]),
+        (second.path(), ["nested/b.txt", "a.txt"]),
+    ] {
+        for name in order {
+            let path = root.join(name);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, name).unwrap();
+        }
+    }
+    std::fs::write(first.path().join("build.log"), "noise").unwrap();
+
+    let lua = setup_lua();
+    lua.globals()
+        .set("first", first.path().to_string_lossy().to_string())
+        .unwrap();
+    lua.globals()
+        .set("second", second.path().to_string_lossy().to_string())
+        .unwrap();
+    lua.globals()
+        .set(
+            "changed",
+            second.path().join("a.txt").to_string_lossy().to_string(),
+        )
+        .unwrap();
+    lua.load(
+        r#"
+local crypto = require("crypto")
+local a = crypto.hashDir("sha256", first, { ignore = { "*.log" } })
+local b = crypto.hashDir("sha256", second)
+assert(a == b, "identical trees should hash equal")
+assert(crypto.hashDir("sha256", first) ~= b)
+
+local fs = require("fs")
+fs.writeFileSync(changed, "a.txT")
+assert(crypto.hashDir("sha256", second) ~= a, "a one-byte change should alter the hash")
+assert(not pcall(crypto.hashDir, "sha256", changed))
+"#,
+    )
+    .exec()
+    .unwrap();
+}
@@ -0,0 +1,68 @@
+use hype_rs::lua::require::setup_require_fn;
+use hype_rs::modules::loader::ModuleLoader;
+use mlua::Lua;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+fn setup_lua() -> Lua {
+    let lua = Lua::new();
+    let loader = Arc::new(Mutex::new(ModuleLoader::new(PathBuf::from("."))));
+    setup_require_fn(&lua, loader).unwrap();
+    lua
+}
+
+#[test]
+fn test_csv_quoted_fields_containing_delimiters() {
+    let lua = setup_lua();
+    lua.load(
+        r#"
+local csv = require("csv")
+local rows = csv.parse('city,motto\n"Paris, FR","Fluctuat ""nec"" mergitur"\n"Multi\nLine",x\n')
+assert(#rows == 3)
+assert(rows[2][1] == "Paris, FR")
+assert(rows[2][2] == 'Fluctuat "nec" mergitur')
+assert(rows[3][1] == "Multi\nLine")
+
+local text = csv.stringify(rows)
+local again = csv.parse(text)
+assert(again[2][1] == "Paris, FR" and again[3][1] == "Multi\nLine")
+"#,
+    )
+    .exec()
+    .unwrap();
+}
+
+#[test]
+fn test_csv_header_mapped_parse() {
+    let lua = setup_lua();
+    lua.load(
+        r#"
+local csv = require("csv")
+local people = csv.parse("id\tname\temail\n1\tAda\tada@example.com\n2\tGrace\tgrace@example.com\n",
+    {headers = true, delimiter = "\t"})
+assert(#people == 2)
+assert(people[1].id == "1" and people[1].name == "Ada")
+assert(people[2].email == "grace@example.com")
+
+local out = csv.stringify(people, {headers = {"name", "id"}})
+assert(out == "name,id\nAda,1\nGrace,2\n", out)
+"#,
+    )
+    .exec()
+    .unwrap();
+}
+
+#[test]
+fn test_csv_invalid_options_error() {
+    let lua = setup_lua();
+    lua.load(
+        r#"
+local csv = require("csv")
+local ok, err = pcall(csv.parse, "a,b", {delimiter = "::"})
+assert(not ok)
+assert(string.find(tostring(err), "delimiter must be a single", 1, true))
+"#,
+    )
+    .exec()
+    .unwrap();
+}
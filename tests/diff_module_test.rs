@@ -0,0 +1,72 @@
+use hype_rs::lua::require::setup_require_fn;
+use hype_rs::modules::loader::ModuleLoader;
+use mlua::Lua;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+fn setup_lua() -> Lua {
+    let lua = Lua::new();
+    let loader = Arc::new(Mutex::new(ModuleLoader::new(PathBuf::from("."))));
+    setup_require_fn(&lua, loader).unwrap();
+    lua
+}
+
+#[test]
+fn test_lines_marks_two_line_change() {
+    let lua = setup_lua();
+    let patch: String = lua
+        .load(
+            r#"
+local diff = require("diff")
+return diff.lines("one\ntwo\nthree\n", "one\n2\nthree\nfour\n", {fromFile = "a/x.txt", toFile = "b/x.txt"})
+"#,
+        )
+        .eval()
+        .unwrap();
+    assert_eq!(
+        patch,
+        "--- a/x.txt\n+++ b/x.txt\n@@ -1,3 +1,4 @@\n one\n-two\n+2\n three\n+four\n"
+    );
+}
+
+#[test]
+fn test_apply_reconstructs_modified() {
+    let lua = setup_lua();
+    lua.load(
+        r#"
+local diff = require("diff")
+local original = "local x = 1\nlocal y = 2\nprint(x + y)\n\n-- end"
+local modified = "local x = 10\nlocal y = 2\nlocal z = 3\nprint(x + y + z)\n\n-- end\n"
+local patch = diff.lines(original, modified, {context = 1})
+assert(diff.apply(original, patch) == modified, patch)
+assert(diff.lines(modified, modified) == "")
+assert(diff.apply(modified, "") == modified)
+
+local ok, err = pcall(diff.apply, "something else\n", patch)
+assert(not ok and err.code == "ERR_PATCH_MISMATCH" and err.kind == "diff", tostring(err))
+ok, err = pcall(diff.apply, original, "@@ -1,5 +1,5 @@\n local x = 1\n")
+assert(not ok and err.code == "ERR_INVALID_PATCH", tostring(err))
+"#,
+    )
+    .exec()
+    .unwrap();
+}
+
+#[test]
+fn test_chars_returns_change_runs() {
+    let lua = setup_lua();
+    let summary: String = lua
+        .load(
+            r#"
+local diff = require("diff")
+local parts = {}
+for _, change in ipairs(diff.chars("color", "colour")) do
+    parts[#parts + 1] = change.op .. ":" .. change.value
+end
+return table.concat(parts, " ")
+"#,
+        )
+        .eval()
+        .unwrap();
+    assert_eq!(summary, "equal:colo insert:u equal:r");
+}
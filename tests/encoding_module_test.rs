@@ -0,0 +1,85 @@
+use hype_rs::lua::require::setup_require_fn;
+use hype_rs::modules::loader::ModuleLoader;
+use mlua::Lua;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+fn setup_lua() -> Lua {
+    let lua = Lua::new();
+    let loader = Arc::new(Mutex::new(ModuleLoader::new(PathBuf::from("."))));
+    setup_require_fn(&lua, loader).unwrap();
+    lua
+}
+
+#[test]
+fn test_base32_roundtrip_is_binary_safe() {
+    let lua = setup_lua();
+    lua.load(
+        r#"
+local encoding = require("encoding")
+
+assert(encoding.base32Encode("foobar") == "MZXW6YTBOI======")
+assert(encoding.base32Decode("MZXW6YTBOI======") == "foobar")
+assert(encoding.base32Decode("mzxw6ytboi") == "foobar")
+
+local bytes = {}
+for i = 0, 255 do bytes[#bytes + 1] = string.char(i) end
+local binary = table.concat(bytes)
+for _, codec in ipairs({"base32", "base64", "base64Url", "hex", "percent"}) do
+    local encoded = encoding[codec .. "Encode"](binary)
+    assert(encoding[codec .. "Decode"](encoded) == binary, codec)
+end
+"#,
+    )
+    .exec()
+    .unwrap();
+}
+
+#[test]
+fn test_base64_url_uses_url_safe_alphabet() {
+    let lua = setup_lua();
+    lua.load(
+        r#"
+local encoding = require("encoding")
+local data = "\251\255\191"
+
+assert(encoding.base64Encode(data) == "+/+/")
+local url = encoding.base64UrlEncode(data)
+assert(url == "-_-_", url)
+assert(not url:find("[+/=]"))
+assert(encoding.base64UrlEncode("hi") == "aGk")
+assert(encoding.base64UrlDecode("aGk") == "hi")
+assert(encoding.base64UrlDecode("aGk=") == "hi")
+assert(encoding.hexEncode("\0\255") == "00ff")
+assert(encoding.percentEncode("a b&c") == "a%20b%26c")
+"#,
+    )
+    .exec()
+    .unwrap();
+}
+
+#[test]
+fn test_invalid_input_is_rejected() {
+    let lua = setup_lua();
+    lua.load(
+        r#"
+local encoding = require("encoding")
+local cases = {
+    {"base32Decode", "MZXW1==="},
+    {"base32Decode", "MZX"},
+    {"base64Decode", "not base64!"},
+    {"base64UrlDecode", "+/+/"},
+    {"hexDecode", "abc"},
+    {"hexDecode", "zz"},
+    {"percentDecode", "100%"},
+}
+for _, case in ipairs(cases) do
+    local ok, err = pcall(encoding[case[1]], case[2])
+    assert(not ok, case[1] .. " accepted " .. case[2])
+    assert(err.code == "ERR_INVALID_INPUT" and err.kind == "encoding", tostring(err))
+end
+"#,
+    )
+    .exec()
+    .unwrap();
+}
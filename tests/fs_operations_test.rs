@@ -116,6 +116,140 @@ fn test_fs_module_readdir() {
     assert_eq!(file3, "subdir");
 }
 
+#[cfg(unix)]
+#[test]
+fn test_fs_module_readdir_with_file_types() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(temp_dir.path().join("file.txt"), "content").unwrap();
+    fs::create_dir(temp_dir.path().join("subdir")).unwrap();
+    std::os::unix::fs::symlink("file.txt", temp_dir.path().join("link")).unwrap();
+
+    let lua_ctx = Lua::new();
+    let loader = Arc::new(Mutex::new(ModuleLoader::new(PathBuf::from("."))));
+    setup_require_fn(&lua_ctx, loader).unwrap();
+    lua_ctx
+        .globals()
+        .set("dir", temp_dir.path().to_str().unwrap())
+        .unwrap();
+
+    lua_ctx
+        .load(
+            r#"
+        local fs = require("fs")
+        local names = fs.readdirSync(dir)
+        assert(#names == 3 and names[1] == "file.txt" and names[2] == "link" and names[3] == "subdir")
+        assert(#fs.readdirSync(dir, {withFileTypes = false}) == 3)
+        assert(type(fs.readdirSync(dir, {withFileTypes = false})[1]) == "string")
+
+        local entries = fs.readdirSync(dir, {withFileTypes = true})
+        assert(#entries == 3)
+        local file, link, subdir = entries[1], entries[2], entries[3]
+        assert(file.name == "file.txt" and file.isFile and not file.isDirectory and not file.isSymlink)
+        assert(link.name == "link" and link.isSymlink and not link.isFile and not link.isDirectory)
+        assert(subdir.name == "subdir" and subdir.isDirectory and not subdir.isFile and not subdir.isSymlink)
+        for i, entry in ipairs(entries) do
+            assert(entry.name == names[i])
+        end
+
+        local ok, err = pcall(fs.readdirSync, dir .. "/missing", {withFileTypes = true})
+        assert(not ok and err.code == "ENOENT", tostring(err))
+        "#,
+        )
+        .exec()
+        .unwrap();
+}
+
+fn nested_fixture() -> TempDir {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    fs::create_dir_all(root.join("docs/guides")).unwrap();
+    fs::create_dir_all(root.join("node_modules/dep")).unwrap();
+    fs::write(root.join("README.md"), "").unwrap();
+    fs::write(root.join("docs/index.md"), "").unwrap();
+    fs::write(root.join("docs/guides/start.md"), "").unwrap();
+    fs::write(root.join("node_modules/dep/index.lua"), "").unwrap();
+    temp_dir
+}
+
+#[test]
+fn test_fs_module_walk_visits_all_entries_and_prunes() {
+    let temp_dir = nested_fixture();
+
+    let lua_ctx = Lua::new();
+    let loader = Arc::new(Mutex::new(ModuleLoader::new(PathBuf::from("."))));
+    setup_require_fn(&lua_ctx, loader).unwrap();
+    lua_ctx
+        .globals()
+        .set("dir", temp_dir.path().to_str().unwrap())
+        .unwrap();
+
+    lua_ctx
+        .load(
+            r#"
+        local fs = require("fs")
+        local visited = {}
+        fs.walk(dir, function(entry)
+            table.insert(visited, entry.relativePath .. (entry.isDir and "/" or ""))
+            assert(entry.path == dir .. "/" .. entry.relativePath, entry.path)
+        end)
+        local all = table.concat(visited, " ")
+        assert(all == "README.md docs/ docs/guides/ docs/guides/start.md docs/index.md "
+            .. "node_modules/ node_modules/dep/ node_modules/dep/index.lua", all)
+
+        local files = {}
+        fs.walk(dir, function(entry)
+            if entry.isDir and entry.relativePath == "node_modules" then
+                return false
+            end
+            if not entry.isDir then
+                table.insert(files, entry.relativePath)
+            end
+        end)
+        local pruned = table.concat(files, " ")
+        assert(pruned == "README.md docs/guides/start.md docs/index.md", pruned)
+
+        assert(table.concat(fs.readdirSync(dir .. "/docs", {recursive = true}), " ")
+            == "guides guides/start.md index.md")
+        local typed = fs.readdirSync(dir .. "/docs", {recursive = true, withFileTypes = true})
+        assert(typed[1].name == "guides" and typed[1].isDirectory)
+        assert(typed[2].name == "guides/start.md" and typed[2].isFile)
+
+        local ok, err = pcall(fs.walk, dir .. "/missing", function() end)
+        assert(not ok and err.code == "ENOENT", tostring(err))
+        ok, err = pcall(fs.walk, dir, function() error("stop here") end)
+        assert(not ok and tostring(err):find("stop here"), tostring(err))
+        "#,
+        )
+        .exec()
+        .unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn test_fs_module_walk_stops_at_symlink_cycles() {
+    let temp_dir = nested_fixture();
+    std::os::unix::fs::symlink("..", temp_dir.path().join("docs/guides/loop")).unwrap();
+
+    let lua_ctx = Lua::new();
+    let loader = Arc::new(Mutex::new(ModuleLoader::new(PathBuf::from("."))));
+    setup_require_fn(&lua_ctx, loader).unwrap();
+    lua_ctx
+        .globals()
+        .set("dir", temp_dir.path().join("docs").to_str().unwrap())
+        .unwrap();
+
+    let listed: String = lua_ctx
+        .load(
+            r#"
+        local fs = require("fs")
+        return table.concat(fs.readdirSync(dir, {recursive = true}), " ")
+        "#,
+        )
+        .eval()
+        .unwrap();
+    assert_eq!(listed, "guides guides/loop guides/start.md index.md");
+}
+
 #[test]
 fn test_fs_module_mkdir_rmdir() {
     let temp_dir = TempDir::new().unwrap();
@@ -258,3 +392,148 @@ fn test_fs_module_error_handling_rmdir_nonempty() {
     let result = lua_ctx.load(&code).exec();
     assert!(result.is_err());
 }
+
+#[test]
+fn test_temp_helpers_return_distinct_existing_paths() {
+    let lua_ctx = Lua::new();
+    let loader = Arc::new(Mutex::new(ModuleLoader::new(PathBuf::from("."))));
+    setup_require_fn(&lua_ctx, loader).unwrap();
+
+    let (dir_a, dir_b, file_a, file_b): (String, String, String, String) = lua_ctx
+        .load(
+            r#"
+            local fs = require("fs")
+            local os = require("os")
+            return os.mkdtemp("hype-it-"), os.mkdtemp("hype-it-"),
+                fs.mkstemp("hype-it-"), fs.mkstemp()
+        "#,
+        )
+        .eval()
+        .unwrap();
+
+    assert_ne!(dir_a, dir_b);
+    assert_ne!(file_a, file_b);
+    for dir in [&dir_a, &dir_b] {
+        assert!(PathBuf::from(dir).is_dir(), "{}", dir);
+        fs::remove_dir(dir).unwrap();
+    }
+    for file in [&file_a, &file_b] {
+        assert!(PathBuf::from(file).is_file(), "{}", file);
+        fs::remove_file(file).unwrap();
+    }
+    assert!(file_b.contains("hype-"));
+}
+
+#[test]
+fn test_fs_write_stream_writes_many_lines() {
+    let temp_dir = TempDir::new().unwrap();
+    let report = temp_dir.path().join("report.csv");
+
+    let lua_ctx = Lua::new();
+    let loader = Arc::new(Mutex::new(ModuleLoader::new(PathBuf::from("."))));
+    setup_require_fn(&lua_ctx, loader).unwrap();
+
+    let code = format!(
+        r#"
+        local fs = require("fs")
+        local stream = fs.createWriteStream("{}")
+        stream:write("id,square\n")
+        for i = 1, 10000 do
+            stream:write(i, ",", i * i, "\n")
+        end
+        stream:flush()
+        local bytes = stream.bytesWritten
+        stream:close()
+        assert(stream.closed)
+        local ok, err = pcall(stream.write, stream, "late")
+        assert(not ok and tostring(err):find("write stream is closed"), tostring(err))
+        return bytes
+        "#,
+        report.display()
+    );
+
+    let bytes: u64 = lua_ctx.load(&code).eval().unwrap();
+    let mut expected = String::from("id,square\n");
+    for i in 1..=10000u64 {
+        expected.push_str(&format!("{},{}\n", i, i * i));
+    }
+    assert_eq!(fs::read_to_string(&report).unwrap(), expected);
+    assert_eq!(bytes, expected.len() as u64);
+}
+
+#[test]
+fn test_fs_write_stream_append_and_truncate() {
+    let temp_dir = TempDir::new().unwrap();
+    let log = temp_dir.path().join("app.log");
+    fs::write(&log, "existing\n").unwrap();
+
+    let lua_ctx = Lua::new();
+    let loader = Arc::new(Mutex::new(ModuleLoader::new(PathBuf::from("."))));
+    setup_require_fn(&lua_ctx, loader).unwrap();
+
+    let code = format!(
+        r#"
+        local fs = require("fs")
+        local path = "{}"
+        local log = fs.createWriteStream(path, {{append = true}})
+        log:write("first\n"):write("second\n")
+        log:close()
+        assert(fs.readFileSync(path) == "existing\nfirst\nsecond\n")
+
+        -- A stream that is never closed is flushed when collected
+        fs.createWriteStream(path, {{append = true}}):write("third\n")
+        collectgarbage()
+        collectgarbage()
+        assert(fs.readFileSync(path) == "existing\nfirst\nsecond\nthird\n")
+
+        local fresh = fs.createWriteStream(path)
+        fresh:write("replaced\n")
+        fresh:close()
+        return fs.readFileSync(path)
+        "#,
+        log.display()
+    );
+
+    let content: String = lua_ctx.load(&code).eval().unwrap();
+    assert_eq!(content, "replaced\n");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_fs_access_read_only_file() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("readonly.txt");
+    fs::write(&file_path, "data").unwrap();
+    fs::set_permissions(&file_path, fs::Permissions::from_mode(0o444)).unwrap();
+
+    let lua_ctx = Lua::new();
+    let loader = Arc::new(Mutex::new(ModuleLoader::new(PathBuf::from("."))));
+    setup_require_fn(&lua_ctx, loader).unwrap();
+    lua_ctx
+        .globals()
+        .set("path", file_path.to_str().unwrap())
+        .unwrap();
+    // root may write to any file regardless of its mode
+    let is_root = unsafe { libc::geteuid() } == 0;
+    lua_ctx.globals().set("is_root", is_root).unwrap();
+
+    lua_ctx
+        .load(
+            r#"
+        local fs = require("fs")
+        local c = fs.constants
+        assert(c.F_OK == 0 and c.R_OK == 4 and c.W_OK == 2 and c.X_OK == 1)
+        assert(fs.access(path) == true)
+        assert(fs.access(path, c.R_OK) == true)
+        assert(fs.access(path, c.R_OK | c.X_OK) == false)
+        assert(is_root or fs.access(path, c.W_OK) == false)
+
+        local ok, err = pcall(fs.access, path .. ".missing", c.R_OK)
+        assert(not ok and err.code == "ENOENT", tostring(err))
+        "#,
+        )
+        .exec()
+        .unwrap();
+}
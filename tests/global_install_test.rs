@@ -762,3 +762,66 @@ fn test_registry_persistence_across_operations() {
         "Remaining package should still appear"
     );
 }
+
+#[test]
+fn test_installed_package_requires_vendored_dependency() {
+    let _lock = get_lock();
+    let (temp, hype_home) = setup_test_env();
+
+    let mut bins = HashMap::new();
+    bins.insert("depcli".to_string(), "bin/cli.lua".to_string());
+    let pkg_dir = create_test_package(temp.path(), "dep-cli", "1.0.0", bins);
+    fs::write(
+        pkg_dir.join("bin/cli.lua"),
+        "local utils = require('utils')\nprint('answer=' .. utils.answer)\n",
+    )
+    .unwrap();
+
+    let utils_dir = temp.path().join("shared/utils");
+    fs::create_dir_all(&utils_dir).unwrap();
+    fs::write(utils_dir.join("init.lua"), "return { answer = 42 }").unwrap();
+
+    let manifest_path = pkg_dir.join("hype.json");
+    let mut manifest: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&manifest_path).unwrap()).unwrap();
+    manifest["dependencies"] = serde_json::json!({ "utils": "../shared/utils" });
+    fs::write(&manifest_path, manifest.to_string()).unwrap();
+
+    let manifest = concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml");
+    let output = std::process::Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--manifest-path",
+            manifest,
+            "--",
+            "install",
+        ])
+        .arg(&pkg_dir)
+        .env("HYPE_HOME", &hype_home)
+        .output()
+        .expect("Failed to execute install command");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    // Run the installed script from an unrelated directory
+    let elsewhere = TempDir::new().unwrap();
+    let script = hype_home.join("packages/dep-cli@1.0.0/bin/cli.lua");
+    let output = std::process::Command::new("cargo")
+        .args(["run", "--quiet", "--manifest-path", manifest, "--"])
+        .arg(&script)
+        .current_dir(elsewhere.path())
+        .env("HYPE_HOME", &hype_home)
+        .output()
+        .expect("Failed to run installed script");
+
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(String::from_utf8_lossy(&output.stdout).contains("answer=42"));
+}
@@ -93,7 +93,7 @@ mod tests {
     #[ignore]
     fn test_get_with_options_no_options() {
         let client = HttpClient::new().unwrap();
-        let res = client.get_with_options("https://httpbin.org/get", None, None);
+        let res = client.get_with_options("https://httpbin.org/get", None, None, None);
         assert!(res.is_ok());
         if let Ok(response) = res {
             assert_eq!(response.status, 200);
@@ -109,8 +109,12 @@ mod tests {
             password: "pass".to_string(),
         };
 
-        let res =
-            client.get_with_options("https://httpbin.org/basic-auth/user/pass", None, Some(auth));
+        let res = client.get_with_options(
+            "https://httpbin.org/basic-auth/user/pass",
+            None,
+            Some(auth),
+            None,
+        );
         assert!(res.is_ok());
         if let Ok(response) = res {
             assert_eq!(response.status, 200);
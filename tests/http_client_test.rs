@@ -0,0 +1,779 @@
+#[cfg(feature = "http")]
+mod tests {
+    use hype_rs::modules::builtins::http::client::DEFAULT_USER_AGENT;
+    use hype_rs::modules::builtins::http::{create_http_module, HttpClient};
+    use mlua::Lua;
+    use std::collections::HashMap;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::{TcpListener, TcpStream};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+    use std::time::Duration;
+
+    struct MockRequest {
+        method: String,
+        path: String,
+        headers: HashMap<String, String>,
+        body: Vec<u8>,
+    }
+
+    struct MockResponse {
+        status: u16,
+        headers: Vec<(String, String)>,
+        body: Vec<u8>,
+    }
+
+    impl MockResponse {
+        fn ok(body: impl Into<Vec<u8>>) -> Self {
+            Self {
+                status: 200,
+                headers: Vec::new(),
+                body: body.into(),
+            }
+        }
+    }
+
+    type Handler = Arc<dyn Fn(&MockRequest) -> MockResponse + Send + Sync>;
+
+    fn spawn_server(handler: Handler) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let handler = handler.clone();
+                thread::spawn(move || handle_connection(stream, handler));
+            }
+        });
+
+        format!("http://{}", addr)
+    }
+
+    fn handle_connection(stream: TcpStream, handler: Handler) {
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+        let mut request_line = String::new();
+        if reader.read_line(&mut request_line).unwrap_or(0) == 0 {
+            return;
+        }
+        let mut parts = request_line.split_whitespace();
+        let method = parts.next().unwrap_or_default().to_string();
+        let path = parts.next().unwrap_or_default().to_string();
+
+        let mut headers = HashMap::new();
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            let line = line.trim_end();
+            if line.is_empty() {
+                break;
+            }
+            if let Some((key, value)) = line.split_once(':') {
+                headers.insert(key.trim().to_ascii_lowercase(), value.trim().to_string());
+            }
+        }
+
+        let length = headers
+            .get("content-length")
+            .and_then(|v| v.parse::<usize>().ok())
+            .unwrap_or(0);
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body).unwrap();
+
+        let request = MockRequest {
+            method,
+            path,
+            headers,
+            body,
+        };
+        let response = handler(&request);
+
+        let mut out = stream;
+        let mut head = format!(
+            "HTTP/1.1 {} OK\r\nContent-Length: {}\r\nConnection: close\r\n",
+            response.status,
+            response.body.len()
+        );
+        for (key, value) in &response.headers {
+            head.push_str(&format!("{}: {}\r\n", key, value));
+        }
+        head.push_str("\r\n");
+        out.write_all(head.as_bytes()).unwrap();
+        out.write_all(&response.body).unwrap();
+        out.flush().unwrap();
+    }
+
+    fn spawn_header_echo_server() -> String {
+        spawn_server(Arc::new(|req: &MockRequest| {
+            let body = serde_json::to_string(&req.headers).unwrap();
+            MockResponse::ok(body)
+        }))
+    }
+
+    fn spawn_request_echo_server() -> String {
+        spawn_server(Arc::new(|req: &MockRequest| {
+            let body = serde_json::json!({
+                "method": req.method,
+                "headers": req.headers,
+                "body": String::from_utf8_lossy(&req.body),
+            });
+            MockResponse::ok(body.to_string())
+        }))
+    }
+
+    fn lua_with_http(base_url: &str) -> Lua {
+        let lua = Lua::new();
+        let http = create_http_module(&lua).unwrap();
+        lua.globals().set("http", http).unwrap();
+        lua.globals().set("BASE_URL", base_url).unwrap();
+        lua
+    }
+
+    fn echoed_headers(lua: &Lua, script: &str) -> HashMap<String, String> {
+        let body: String = lua.load(script).eval().unwrap();
+        serde_json::from_str(&body).unwrap()
+    }
+
+    #[test]
+    fn test_default_user_agent_is_sent() {
+        let base_url = spawn_header_echo_server();
+        let client = HttpClient::new().unwrap();
+
+        let response = client.get(&format!("{}/ua", base_url)).unwrap();
+        let headers: HashMap<String, String> = serde_json::from_str(&response.body).unwrap();
+
+        assert_eq!(headers["user-agent"], DEFAULT_USER_AGENT);
+        assert!(DEFAULT_USER_AGENT.starts_with("hype/"));
+    }
+
+    #[test]
+    fn test_set_user_agent_and_default_headers() {
+        let base_url = spawn_header_echo_server();
+        let lua = lua_with_http(&base_url);
+
+        let headers = echoed_headers(
+            &lua,
+            r#"
+            http.setUserAgent("my-tool/2.0")
+            http.setDefaultHeaders({["X-Api-Key"] = "secret"})
+            return http.get(BASE_URL .. "/defaults").body
+        "#,
+        );
+
+        assert_eq!(headers["user-agent"], "my-tool/2.0");
+        assert_eq!(headers["x-api-key"], "secret");
+    }
+
+    #[test]
+    fn test_per_request_header_overrides_default() {
+        let base_url = spawn_header_echo_server();
+        let lua = lua_with_http(&base_url);
+
+        let headers = echoed_headers(
+            &lua,
+            r#"
+            http.setDefaultHeaders({["X-Api-Key"] = "default", ["Accept"] = "text/plain"})
+            return http.fetch(BASE_URL .. "/override", {
+                headers = {["x-api-key"] = "per-request", ["User-Agent"] = "custom-agent"}
+            }).body
+        "#,
+        );
+
+        assert_eq!(headers["x-api-key"], "per-request");
+        assert_eq!(headers["user-agent"], "custom-agent");
+        assert_eq!(headers["accept"], "text/plain");
+    }
+
+    #[test]
+    fn test_new_client_sends_default_headers() {
+        let base_url = spawn_header_echo_server();
+        let lua = lua_with_http(&base_url);
+
+        let headers = echoed_headers(
+            &lua,
+            r#"
+            local api = http.newClient({
+                headers = {Authorization = "Bearer token", ["User-Agent"] = "api-tool/1.0"}
+            })
+            return api.get(BASE_URL .. "/me").body
+        "#,
+        );
+        assert_eq!(headers["authorization"], "Bearer token");
+        assert_eq!(headers["user-agent"], "api-tool/1.0");
+
+        // The module-level client is not affected
+        let headers = echoed_headers(&lua, r#"return http.get(BASE_URL).body"#);
+        assert!(!headers.contains_key("authorization"));
+    }
+
+    #[test]
+    fn test_new_client_per_request_headers_override_and_remove_defaults() {
+        let base_url = spawn_header_echo_server();
+        let lua = lua_with_http(&base_url);
+
+        let headers = echoed_headers(
+            &lua,
+            r#"
+            api = http.newClient({headers = {Authorization = "Bearer token", ["X-Team"] = "core"}})
+            return api.fetch(BASE_URL, {headers = {authorization = "Bearer other"}}).body
+        "#,
+        );
+        assert_eq!(headers["authorization"], "Bearer other");
+        assert_eq!(headers["x-team"], "core");
+
+        let headers = echoed_headers(
+            &lua,
+            r#"return api.post(BASE_URL, {body = "x", headers = {Authorization = false}}).body"#,
+        );
+        assert!(!headers.contains_key("authorization"), "{:?}", headers);
+        assert_eq!(headers["x-team"], "core");
+
+        let headers = echoed_headers(
+            &lua,
+            r#"return api.get(BASE_URL, {headers = {["X-Team"] = "web"}}).body"#,
+        );
+        assert_eq!(headers["x-team"], "web");
+        assert_eq!(headers["authorization"], "Bearer token");
+    }
+
+    #[test]
+    fn test_request_hook_injects_header() {
+        let base_url = spawn_header_echo_server();
+        let lua = lua_with_http(&base_url);
+
+        let headers = echoed_headers(
+            &lua,
+            r#"
+            local api = http.newClient()
+            api:onRequest(function(req)
+                req.headers["X-Request-Id"] = "req-1"
+            end)
+            api:onRequest(function(req)
+                req.headers["X-Seen"] = req.headers["X-Request-Id"] .. " " .. req.method
+            end)
+            return api.get(BASE_URL .. "/traced").body
+        "#,
+        );
+        assert_eq!(headers["x-request-id"], "req-1");
+        assert_eq!(headers["x-seen"], "req-1 GET");
+
+        // Hooks belong to the client they were added to
+        let headers = echoed_headers(&lua, r#"return http.get(BASE_URL).body"#);
+        assert!(!headers.contains_key("x-request-id"));
+    }
+
+    #[test]
+    fn test_request_hook_can_rewrite_method_and_body() {
+        let base_url = spawn_request_echo_server();
+        let lua = lua_with_http(&base_url);
+
+        let echoed: serde_json::Value = serde_json::from_str(
+            &lua.load(
+                r#"
+                local api = http.newClient()
+                api:onRequest(function(req)
+                    req.method = "put"
+                    req.body = req.body .. "!"
+                end)
+                return api.post(BASE_URL, {body = "hello"}).body
+            "#,
+            )
+            .eval::<String>()
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(echoed["method"], "PUT");
+        assert_eq!(echoed["body"], "hello!");
+    }
+
+    #[test]
+    fn test_response_hook_observes_status() {
+        let base_url = spawn_server(Arc::new(|req: &MockRequest| {
+            if req.path == "/missing" {
+                MockResponse {
+                    status: 404,
+                    headers: Vec::new(),
+                    body: Vec::new(),
+                }
+            } else {
+                MockResponse::ok("ok")
+            }
+        }));
+        let lua = lua_with_http(&base_url);
+
+        let seen: String = lua
+            .load(
+                r#"
+                local seen = {}
+                local api = http.newClient()
+                api:onResponse(function(res) table.insert(seen, "a" .. res.status) end)
+                api:onResponse(function(res) table.insert(seen, "b" .. res.status) end)
+                api.get(BASE_URL .. "/found")
+                api.fetch(BASE_URL .. "/missing")
+                return table.concat(seen, ",")
+            "#,
+            )
+            .eval()
+            .unwrap();
+        assert_eq!(seen, "a200,b200,a404,b404");
+    }
+
+    #[test]
+    fn test_mock_server_records_method_and_body() {
+        let base_url = spawn_server(Arc::new(|req: &MockRequest| {
+            MockResponse::ok(format!(
+                "{} {} {}",
+                req.method,
+                req.path,
+                String::from_utf8_lossy(&req.body)
+            ))
+        }));
+        let client = HttpClient::new().unwrap();
+
+        let response = client
+            .post(
+                &format!("{}/echo", base_url),
+                Some("hello".to_string()),
+                None,
+            )
+            .unwrap();
+
+        assert_eq!(response.body, "POST /echo hello");
+    }
+
+    #[test]
+    fn test_fetch_json_option_serializes_body() {
+        let base_url = spawn_request_echo_server();
+        let lua = lua_with_http(&base_url);
+
+        let echoed: String = lua
+            .load(
+                r#"
+            return http.fetch(BASE_URL .. "/json", {
+                method = "POST",
+                json = {name = "Alice", tags = {"a", "b"}}
+            }).body
+        "#,
+            )
+            .eval()
+            .unwrap();
+        let echoed: serde_json::Value = serde_json::from_str(&echoed).unwrap();
+
+        assert_eq!(echoed["method"], "POST");
+        assert_eq!(echoed["headers"]["content-type"], "application/json");
+        let sent: serde_json::Value =
+            serde_json::from_str(echoed["body"].as_str().unwrap()).unwrap();
+        assert_eq!(
+            sent,
+            serde_json::json!({"name": "Alice", "tags": ["a", "b"]})
+        );
+    }
+
+    #[test]
+    fn test_fetch_response_type_json_decodes_body() {
+        let base_url = spawn_request_echo_server();
+        let lua = lua_with_http(&base_url);
+
+        lua.load(
+            r#"
+            local response = http.fetch(BASE_URL .. "/users", {
+                method = "POST",
+                json = {name = "Alice", admin = true},
+                responseType = "json"
+            })
+            assert(response.status == 200)
+            assert(type(response.body) == "table", type(response.body))
+            assert(response.body.method == "POST")
+            assert(response.body.headers["content-type"] == "application/json")
+            assert(type(response:text()) == "string")
+
+            local sent = response:json()
+            assert(sent.body == '{"admin":true,"name":"Alice"}', sent.body)
+        "#,
+        )
+        .exec()
+        .unwrap();
+    }
+
+    #[test]
+    fn test_fetch_response_type_errors() {
+        let base_url = spawn_server(Arc::new(|_: &MockRequest| MockResponse::ok("not json")));
+        let lua = lua_with_http(&base_url);
+
+        lua.load(
+            r#"
+            local ok, err = pcall(http.fetch, BASE_URL, {responseType = "json"})
+            assert(not ok and err.code == "EJSON", tostring(err))
+
+            local ok, err = pcall(http.fetch, BASE_URL, {responseType = "blob"})
+            assert(not ok and tostring(err):find("responseType", 1, true), tostring(err))
+
+            assert(http.fetch(BASE_URL, {responseType = "text"}).body == "not json")
+        "#,
+        )
+        .exec()
+        .unwrap();
+    }
+
+    #[test]
+    fn test_post_json_option_keeps_explicit_content_type() {
+        let base_url = spawn_request_echo_server();
+        let lua = lua_with_http(&base_url);
+
+        let echoed: String = lua
+            .load(
+                r#"
+            return http.post(BASE_URL .. "/json", {
+                json = {ok = true},
+                headers = {["content-type"] = "application/vnd.api+json"}
+            }).body
+        "#,
+            )
+            .eval()
+            .unwrap();
+        let echoed: serde_json::Value = serde_json::from_str(&echoed).unwrap();
+
+        assert_eq!(
+            echoed["headers"]["content-type"],
+            "application/vnd.api+json"
+        );
+        assert_eq!(echoed["body"], r#"{"ok":true}"#);
+    }
+
+    #[test]
+    fn test_json_and_body_options_are_mutually_exclusive() {
+        let base_url = spawn_request_echo_server();
+        let lua = lua_with_http(&base_url);
+
+        let result = lua
+            .load(r#"return http.post(BASE_URL, {body = "raw", json = {a = 1}})"#)
+            .exec();
+
+        let err = result.unwrap_err().to_string();
+        assert!(err.contains("mutually exclusive"), "{}", err);
+    }
+
+    #[test]
+    fn test_set_host_resolver_routes_hostname_to_local_server() {
+        let base_url = spawn_request_echo_server();
+        let addr = base_url.trim_start_matches("http://").to_string();
+        let lua = lua_with_http(&base_url);
+        lua.globals().set("SERVER_ADDR", addr).unwrap();
+
+        let echoed: String = lua
+            .load(
+                r#"
+            http.setHostResolver({["api.example.test"] = SERVER_ADDR})
+            return http.get("http://api.example.test/users").body
+        "#,
+            )
+            .eval()
+            .unwrap();
+        let echoed: serde_json::Value = serde_json::from_str(&echoed).unwrap();
+
+        assert_eq!(echoed["method"], "GET");
+        assert_eq!(echoed["headers"]["host"], "api.example.test");
+    }
+
+    #[test]
+    fn test_set_host_resolver_rejects_invalid_address() {
+        let client = HttpClient::new().unwrap();
+        let mut overrides = HashMap::new();
+        overrides.insert("api.example.test".to_string(), "not-an-ip".to_string());
+
+        let err = client.set_host_resolver(overrides).unwrap_err().to_string();
+        assert!(err.contains("not-an-ip"), "{}", err);
+    }
+
+    #[test]
+    fn test_gzip_response_is_decoded() {
+        use hype_rs::modules::builtins::zlib::operations::gzip;
+
+        let base_url = spawn_server(Arc::new(|req: &MockRequest| {
+            let body = serde_json::json!({
+                "accept_encoding": req.headers.get("accept-encoding"),
+                "items": [1, 2, 3],
+            });
+            MockResponse {
+                status: 200,
+                headers: vec![
+                    ("Content-Type".to_string(), "application/json".to_string()),
+                    ("Content-Encoding".to_string(), "gzip".to_string()),
+                ],
+                body: gzip(body.to_string().as_bytes(), 6).unwrap(),
+            }
+        }));
+        let lua = lua_with_http(&base_url);
+
+        lua.load(
+            r#"
+            local response = http.get(BASE_URL .. "/compressed")
+            local data = response:json()
+            assert(data.accept_encoding == "gzip, deflate, br", tostring(data.accept_encoding))
+            assert(#data.items == 3 and data.items[3] == 3)
+            assert(response:text() == response.body)
+
+            local raw = response:rawBody()
+            assert(raw:sub(1, 2) == "\x1f\x8b", "raw body should be the gzip bytes")
+            assert(#raw ~= #response.body)
+        "#,
+        )
+        .exec()
+        .unwrap();
+    }
+
+    #[test]
+    fn test_bodyless_responses_with_content_encoding() {
+        let base_url = spawn_server(Arc::new(|req: &MockRequest| MockResponse {
+            status: if req.path == "/empty" { 204 } else { 200 },
+            headers: vec![("Content-Encoding".to_string(), "gzip".to_string())],
+            body: Vec::new(),
+        }));
+        let lua = lua_with_http(&base_url);
+
+        lua.load(
+            r#"
+            local response = http.fetch(BASE_URL .. "/empty")
+            assert(response.status == 204 and response.body == "")
+
+            response = http.head(BASE_URL .. "/resource")
+            assert(response.status == 200 and response.body == "")
+        "#,
+        )
+        .exec()
+        .unwrap();
+    }
+
+    /// A server that answers `/<n>` after `n` * 50ms with body `n`, counting
+    /// the most requests it was handling at once.
+    fn spawn_slow_server() -> (String, Arc<AtomicUsize>) {
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let peak_seen = peak.clone();
+        let base_url = spawn_server(Arc::new(move |req: &MockRequest| {
+            let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            peak.fetch_max(now, Ordering::SeqCst);
+            let n: u64 = req.path.trim_start_matches('/').parse().unwrap();
+            thread::sleep(Duration::from_millis(n * 50));
+            in_flight.fetch_sub(1, Ordering::SeqCst);
+            MockResponse::ok(n.to_string())
+        }));
+        (base_url, peak_seen)
+    }
+
+    #[test]
+    fn test_fetch_all_runs_concurrently_in_input_order() {
+        let (base_url, peak) = spawn_slow_server();
+        let lua = lua_with_http(&base_url);
+
+        let bodies: String = lua
+            .load(
+                r#"
+                local urls = {}
+                for _, n in ipairs({6, 5, 4, 3, 2, 1}) do
+                    table.insert(urls, BASE_URL .. "/" .. n)
+                end
+                local results = http.fetchAll(urls)
+                assert(#results == 6)
+                local bodies = {}
+                for _, res in ipairs(results) do
+                    assert(res.status == 200 and res.ok())
+                    table.insert(bodies, res.body)
+                end
+                return table.concat(bodies, ",")
+            "#,
+            )
+            .eval()
+            .unwrap();
+        assert_eq!(bodies, "6,5,4,3,2,1");
+        assert!(peak.load(Ordering::SeqCst) > 1);
+    }
+
+    #[test]
+    fn test_fetch_all_concurrency_limit() {
+        let (base_url, peak) = spawn_slow_server();
+        let lua = lua_with_http(&base_url);
+
+        lua.load(
+            r#"
+            local urls = {}
+            for n = 1, 6 do
+                table.insert(urls, BASE_URL .. "/" .. (n % 2 + 1))
+            end
+            local results = http.fetchAll(urls, { concurrency = 2 })
+            for n, res in ipairs(results) do
+                assert(res.body == tostring(n % 2 + 1))
+            end
+            assert(not pcall(http.fetchAll, urls, { concurrency = 0 }))
+        "#,
+        )
+        .exec()
+        .unwrap();
+        assert_eq!(peak.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_fetch_all_reports_failures_in_place() {
+        let base_url = spawn_request_echo_server();
+        let lua = lua_with_http(&base_url);
+
+        lua.load(
+            r#"
+            local seen = {}
+            local api = http.newClient()
+            api:onRequest(function(req) req.headers["X-Batch"] = "yes" end)
+            api:onResponse(function(res) table.insert(seen, res.status) end)
+            local results = api.fetchAll(
+                { BASE_URL .. "/a", "not a url", BASE_URL .. "/b" },
+                { method = "POST", json = { n = 1 }, responseType = "json" }
+            )
+            assert(#results == 3 and #seen == 2)
+            assert(results[1].body.method == "POST")
+            assert(results[1].body.body == '{"n":1}')
+            assert(results[3].body.headers["x-batch"] == "yes")
+            assert(results[2].error.code == "EREQUEST", results[2].error.code)
+            assert(results[2].error.message:find("Invalid URL"))
+        "#,
+        )
+        .exec()
+        .unwrap();
+    }
+
+    fn artifact() -> Vec<u8> {
+        (0..20_000u32).map(|i| (i % 251) as u8).collect()
+    }
+
+    /// Serves `artifact()`, answering `Range: bytes=N-` requests with the
+    /// rest of the file. `/norange` ignores ranges, `/badrange` always starts
+    /// its partial content at byte 0 and `/missing` is a 404.
+    fn spawn_artifact_server() -> (String, Arc<Mutex<Vec<Option<String>>>>) {
+        let ranges = Arc::new(Mutex::new(Vec::new()));
+        let seen = ranges.clone();
+        let base_url = spawn_server(Arc::new(move |req: &MockRequest| {
+            let content = artifact();
+            let total = content.len();
+            let range = req.headers.get("range").cloned();
+            ranges.lock().unwrap().push(range.clone());
+            let start = range
+                .as_deref()
+                .and_then(|r| r.strip_prefix("bytes="))
+                .and_then(|r| r.trim_end_matches('-').parse::<usize>().ok());
+
+            match (req.path.as_str(), start) {
+                ("/missing", _) => MockResponse {
+                    status: 404,
+                    headers: Vec::new(),
+                    body: Vec::new(),
+                },
+                ("/norange", _) | (_, None) => MockResponse::ok(content),
+                (_, Some(start)) if start >= total => MockResponse {
+                    status: 416,
+                    headers: vec![("Content-Range".into(), format!("bytes */{}", total))],
+                    body: Vec::new(),
+                },
+                (path, Some(start)) => {
+                    let start = if path == "/badrange" { 0 } else { start };
+                    MockResponse {
+                        status: 206,
+                        headers: vec![(
+                            "Content-Range".into(),
+                            format!("bytes {}-{}/{}", start, total - 1, total),
+                        )],
+                        body: content[start..].to_vec(),
+                    }
+                }
+            }
+        }));
+        (base_url, seen)
+    }
+
+    fn download(lua: &Lua, path: &str, dest: &std::path::Path) -> (bool, i64, i64, i64) {
+        lua.globals()
+            .set("DEST", dest.to_string_lossy().to_string())
+            .unwrap();
+        lua.load(format!(
+            r#"
+            local result = http.download(BASE_URL .. "{}", DEST, {{ resume = true }})
+            return result.resumed, result.size, result.downloaded, result.status
+            "#,
+            path
+        ))
+        .eval()
+        .unwrap()
+    }
+
+    #[test]
+    fn test_download_resumes_partial_file() {
+        let (base_url, ranges) = spawn_artifact_server();
+        let lua = lua_with_http(&base_url);
+        let dir = tempfile::tempdir().unwrap();
+        let dest = dir.path().join("artifact.bin");
+        std::fs::write(&dest, &artifact()[..7_000]).unwrap();
+
+        assert_eq!(download(&lua, "/file", &dest), (true, 20_000, 13_000, 206));
+        assert_eq!(std::fs::read(&dest).unwrap(), artifact());
+        assert_eq!(
+            ranges.lock().unwrap().as_slice(),
+            [Some("bytes=7000-".to_string())]
+        );
+
+        // A complete file is left alone
+        assert_eq!(download(&lua, "/file", &dest), (true, 20_000, 0, 416));
+        assert_eq!(std::fs::read(&dest).unwrap(), artifact());
+    }
+
+    #[test]
+    fn test_download_falls_back_to_full_download() {
+        let (base_url, ranges) = spawn_artifact_server();
+        let lua = lua_with_http(&base_url);
+        let dir = tempfile::tempdir().unwrap();
+
+        let dest = dir.path().join("norange.bin");
+        std::fs::write(&dest, &artifact()[..7_000]).unwrap();
+        assert_eq!(
+            download(&lua, "/norange", &dest),
+            (false, 20_000, 20_000, 200)
+        );
+        assert_eq!(std::fs::read(&dest).unwrap(), artifact());
+
+        // A Content-Range that does not continue the file is not trusted
+        let dest = dir.path().join("badrange.bin");
+        std::fs::write(&dest, &artifact()[..7_000]).unwrap();
+        ranges.lock().unwrap().clear();
+        assert_eq!(
+            download(&lua, "/badrange", &dest),
+            (false, 20_000, 20_000, 200)
+        );
+        assert_eq!(std::fs::read(&dest).unwrap(), artifact());
+        assert_eq!(
+            ranges.lock().unwrap().as_slice(),
+            [Some("bytes=7000-".to_string()), None]
+        );
+    }
+
+    #[test]
+    fn test_download_error_keeps_existing_file() {
+        let (base_url, _) = spawn_artifact_server();
+        let lua = lua_with_http(&base_url);
+        let dir = tempfile::tempdir().unwrap();
+        let dest = dir.path().join("partial.bin");
+        std::fs::write(&dest, b"partial").unwrap();
+        lua.globals()
+            .set("DEST", dest.to_string_lossy().to_string())
+            .unwrap();
+
+        let code: String = lua
+            .load(
+                r#"
+                local ok, err = pcall(http.download, BASE_URL .. "/missing", DEST, { resume = true })
+                assert(not ok)
+                return err.code
+            "#,
+            )
+            .eval()
+            .unwrap();
+        assert_eq!(code, "ERESPONSE");
+        assert_eq!(std::fs::read(&dest).unwrap(), b"partial");
+    }
+}
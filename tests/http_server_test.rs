@@ -0,0 +1,92 @@
+#[cfg(feature = "http")]
+mod tests {
+    use hype_rs::modules::builtins::http::{create_http_module, HttpClient};
+    use mlua::Lua;
+    use std::collections::HashMap;
+    use std::thread;
+
+    fn lua_with_server(handler: &str) -> (Lua, u16) {
+        let lua = Lua::new();
+        lua.globals()
+            .set("http", create_http_module(&lua).unwrap())
+            .unwrap();
+        let port: u16 = lua
+            .load(format!(
+                "server = http.createServer({})\nreturn server:listen(0)",
+                handler
+            ))
+            .eval()
+            .unwrap();
+        (lua, port)
+    }
+
+    #[test]
+    fn test_server_echoes_request_body() {
+        let (lua, port) = lua_with_server(
+            r#"function(req)
+                server:close()
+                return {
+                    status = 201,
+                    headers = {["x-method"] = req.method, ["x-name"] = req.query.name},
+                    body = req.path .. ":" .. req.headers["x-token"] .. ":" .. req.body,
+                }
+            end"#,
+        );
+        assert_ne!(port, 0);
+
+        let client = thread::spawn(move || {
+            let headers = HashMap::from([("X-Token".to_string(), Some("abc".to_string()))]);
+            HttpClient::new()
+                .unwrap()
+                .post(
+                    &format!("http://127.0.0.1:{}/hook?name=ada", port),
+                    Some("ping".to_string()),
+                    Some(headers),
+                )
+                .unwrap()
+        });
+
+        lua.load("server:serve()").exec().unwrap();
+        let response = client.join().unwrap();
+        assert_eq!(response.status, 201);
+        assert_eq!(response.body, "/hook:abc:ping");
+        assert_eq!(response.headers.get("x-method").unwrap(), "POST");
+        assert_eq!(response.headers.get("x-name").unwrap(), "ada");
+
+        let closed: bool = lua.load("return server.closed").eval().unwrap();
+        assert!(closed);
+    }
+
+    #[test]
+    fn test_handler_errors_become_500_and_json_responses() {
+        let (lua, port) = lua_with_server(
+            r#"function(req)
+                if req.path == "/fail" then error("boom") end
+                return {json = {ok = true, path = req.path}}
+            end"#,
+        );
+
+        let client = thread::spawn(move || {
+            let client = HttpClient::new().unwrap();
+            let failed = client
+                .get(&format!("http://127.0.0.1:{}/fail", port))
+                .unwrap();
+            let ok = client
+                .get(&format!("http://127.0.0.1:{}/ok", port))
+                .unwrap();
+            (failed, ok)
+        });
+
+        let handled: u32 = lua
+            .load("local n = server:poll(5000) n = n + server:poll(5000) server:close() return n")
+            .eval()
+            .unwrap();
+        let (failed, ok) = client.join().unwrap();
+        assert_eq!(handled, 2);
+        assert_eq!(failed.status, 500);
+        assert_eq!(ok.status, 200);
+        assert_eq!(ok.headers.get("content-type").unwrap(), "application/json");
+        let body: serde_json::Value = serde_json::from_str(&ok.body).unwrap();
+        assert_eq!(body, serde_json::json!({"ok": true, "path": "/ok"}));
+    }
+}
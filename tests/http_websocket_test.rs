@@ -0,0 +1,95 @@
+#[cfg(feature = "http")]
+mod tests {
+    use hype_rs::modules::builtins::http::create_http_module;
+    use mlua::Lua;
+    use std::net::TcpListener;
+    use std::sync::mpsc::{channel, Receiver};
+    use std::thread;
+    use tokio_tungstenite::tungstenite::{accept, Message};
+
+    /// Echo text and binary messages back. A ping is sent first; received
+    /// pongs and the handshake's user agent are reported on the channel.
+    fn spawn_echo_server() -> (String, Receiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (events, received) = channel();
+
+        thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut socket = accept(stream).unwrap();
+            socket
+                .send(Message::Ping(b"heartbeat".to_vec().into()))
+                .unwrap();
+            loop {
+                match socket.read() {
+                    Ok(Message::Pong(payload)) => {
+                        let payload = String::from_utf8_lossy(&payload).into_owned();
+                        events.send(format!("pong:{}", payload)).unwrap();
+                    }
+                    Ok(message @ (Message::Text(_) | Message::Binary(_))) => {
+                        socket.send(message).unwrap();
+                    }
+                    Ok(_) => {}
+                    Err(_) => break,
+                }
+            }
+        });
+
+        (format!("ws://{}/echo", addr), received)
+    }
+
+    fn lua_with_http() -> Lua {
+        let lua = Lua::new();
+        lua.globals()
+            .set("http", create_http_module(&lua).unwrap())
+            .unwrap();
+        lua
+    }
+
+    #[test]
+    fn test_websocket_echo_roundtrip() {
+        let (url, events) = spawn_echo_server();
+        let lua = lua_with_http();
+        lua.globals().set("URL", url).unwrap();
+
+        lua.load(
+            r#"
+local ws = http.connectWebSocket(URL)
+assert(not ws.closed)
+
+ws:send("hello")
+local message, kind = ws:receive(5000)
+assert(message == "hello", tostring(message))
+assert(kind == "text", kind)
+
+ws:send("\0\1\255", {binary = true})
+message, kind = ws:receive(5000)
+assert(message == "\0\1\255" and kind == "binary", kind)
+
+local none, reason = ws:receive(50)
+assert(none == nil and reason == "timeout", reason)
+
+ws:close()
+assert(ws.closed)
+local after, state = ws:receive()
+assert(after == nil and state == "closed")
+"#,
+        )
+        .exec()
+        .unwrap();
+
+        // The ping sent before the first echo was answered automatically
+        assert_eq!(events.recv().unwrap(), "pong:heartbeat");
+    }
+
+    #[test]
+    fn test_websocket_rejects_http_urls() {
+        let lua = lua_with_http();
+        let err = lua
+            .load(r#"http.connectWebSocket("http://127.0.0.1:9/")"#)
+            .exec()
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("ws://"), "{}", err);
+    }
+}
@@ -0,0 +1,82 @@
+use hype_rs::lua::require::setup_require_fn;
+use hype_rs::modules::loader::ModuleLoader;
+use mlua::Lua;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+fn setup_lua() -> Lua {
+    let lua = Lua::new();
+    let loader = Arc::new(Mutex::new(ModuleLoader::new(PathBuf::from("."))));
+    setup_require_fn(&lua, loader).unwrap();
+    lua
+}
+
+#[test]
+fn test_ini_parse_sections_and_comments() {
+    let lua = setup_lua();
+    lua.load(
+        r#"
+local ini = require("ini")
+local config = ini.parse([==[
+; global settings
+editor = vim
+
+[core]
+# comments use ; or #
+repositoryformatversion = 0
+bare = false
+pager = "less -R"
+
+[remote "origin"]
+url = https://example.com/repo.git
+fetch = +refs/heads/*:refs/remotes/origin/*
+]==])
+
+assert(config.editor == "vim")
+assert(config.core.repositoryformatversion == "0")
+assert(config.core.bare == "false")
+assert(config.core.pager == "less -R")
+assert(config['remote "origin"'].url == "https://example.com/repo.git")
+local keys = 0
+for _ in pairs(config.core) do keys = keys + 1 end
+assert(keys == 3, keys)
+"#,
+    )
+    .exec()
+    .unwrap();
+}
+
+#[test]
+fn test_ini_round_trip_and_duplicate_keys() {
+    let lua = setup_lua();
+    lua.load(
+        r#"
+local ini = require("ini")
+local original = {
+    name = "demo",
+    Unit = {Description = "Demo service", After = "network.target"},
+    Service = {ExecStart = "/usr/bin/demo --port 8080", Restart = "always"},
+}
+local text = ini.stringify(original)
+assert(text:find("^name = demo\n"), text)
+local parsed = ini.parse(text)
+assert(parsed.name == "demo")
+assert(parsed.Unit.Description == "Demo service")
+assert(parsed.Service.ExecStart == "/usr/bin/demo --port 8080")
+assert(ini.stringify(parsed) == text)
+
+-- The last value of a repeated key wins
+local dup = ini.parse("[server]\nport = 80\nport = 8080\n")
+assert(dup.server.port == "8080")
+
+local ok, err = pcall(ini.parse, "[server\nport = 80")
+assert(not ok and err.code == "ERR_PARSE" and err.kind == "ini")
+assert(err.message:find("line 1", 1, true), err.message)
+
+ok, err = pcall(ini.stringify, {server = {nested = {}}})
+assert(not ok and err.code == "ERR_INVALID_VALUE", tostring(err))
+"#,
+    )
+    .exec()
+    .unwrap();
+}
@@ -228,3 +228,125 @@ fn test_json_error_invalid_syntax() {
     let result = lua.load(code).exec();
     assert!(result.is_err());
 }
+
+#[test]
+fn test_json_encode_array_detection() {
+    let lua = Lua::new();
+    let loader = Arc::new(Mutex::new(ModuleLoader::new(PathBuf::from("."))));
+    setup_require_fn(&lua, loader).unwrap();
+
+    let code = r#"
+        local json = require("json")
+        local sparse = {}
+        sparse[1] = "a"
+        sparse[3] = "c"
+        return json.encode({1, 2, 3}), json.encode(sparse), json.encode({"x", n = 1}), json.encode({})
+    "#;
+
+    let (array, sparse, mixed, empty): (String, String, String, String) =
+        lua.load(code).eval().unwrap();
+    assert_eq!(array, "[1,2,3]");
+    let sparse: serde_json::Value = serde_json::from_str(&sparse).unwrap();
+    assert_eq!(sparse, serde_json::json!({"1": "a", "3": "c"}));
+    let mixed: serde_json::Value = serde_json::from_str(&mixed).unwrap();
+    assert_eq!(mixed, serde_json::json!({"1": "x", "n": 1}));
+    assert_eq!(empty, "{}");
+}
+
+#[test]
+fn test_json_encode_uses_tojson_metamethod() {
+    let lua = Lua::new();
+    let loader = Arc::new(Mutex::new(ModuleLoader::new(PathBuf::from("."))));
+    setup_require_fn(&lua, loader).unwrap();
+
+    let code = r#"
+        local json = require("json")
+        local Point = {}
+        Point.__index = Point
+        Point.__tojson = function(p) return {p.x, p.y} end
+        local function point(x, y) return setmetatable({x = x, y = y, cache = print}, Point) end
+
+        local Money = {__tojson = function(m) return string.format("%.2f %s", m.amount, m.currency) end}
+        local price = setmetatable({amount = 9.5, currency = "EUR"}, Money)
+
+        return json.encode({path = {point(0, 0), point(3, 4)}, price = price})
+    "#;
+
+    let encoded: String = lua.load(code).eval().unwrap();
+    let value: serde_json::Value = serde_json::from_str(&encoded).unwrap();
+    assert_eq!(
+        value,
+        serde_json::json!({"path": [[0, 0], [3, 4]], "price": "9.50 EUR"})
+    );
+}
+
+#[test]
+fn test_json_encode_rejects_unsupported_values() {
+    let lua = Lua::new();
+    let loader = Arc::new(Mutex::new(ModuleLoader::new(PathBuf::from("."))));
+    setup_require_fn(&lua, loader).unwrap();
+
+    let code = r#"
+        local json = require("json")
+        local cyclic = {}
+        cyclic.self = cyclic
+        local errors = {}
+        for _, value in ipairs({{handler = print}, {[true] = 1}, cyclic}) do
+            local ok, err = pcall(json.encode, value)
+            assert(not ok)
+            errors[#errors + 1] = tostring(err)
+        end
+        -- The same table twice is fine as long as it does not contain itself
+        local shared = {1}
+        assert(json.encode({shared, shared}) == "[[1],[1]]")
+        return table.concat(errors, "\n")
+    "#;
+
+    let errors: String = lua.load(code).eval().unwrap();
+    assert!(
+        errors.contains("cannot convert a function to JSON"),
+        "{}",
+        errors
+    );
+    assert!(
+        errors.contains("object keys must be strings or numbers, not boolean"),
+        "{}",
+        errors
+    );
+    assert!(errors.contains("table that contains itself"), "{}", errors);
+}
+
+#[test]
+fn test_json_encode_non_finite_numbers() {
+    let lua = Lua::new();
+    let loader = Arc::new(Mutex::new(ModuleLoader::new(PathBuf::from("."))));
+    setup_require_fn(&lua, loader).unwrap();
+
+    let code = r#"
+        local json = require("json")
+        local ok, huge_err = pcall(json.encode, {limit = math.huge})
+        assert(not ok)
+        local ok, nan_err = pcall(json.encode, {ratio = 0/0})
+        assert(not ok)
+        local lenient = json.encode({limit = math.huge, low = -math.huge, ratio = 0/0, n = 1}, {allowNonFinite = true})
+        local pretty = json.stringify({math.huge}, {pretty = true, allowNonFinite = true})
+        return tostring(huge_err), tostring(nan_err), lenient, pretty
+    "#;
+
+    let (huge_err, nan_err, lenient, pretty): (String, String, String, String) =
+        lua.load(code).eval().unwrap();
+    assert!(
+        huge_err.contains(
+            "Infinity is not valid JSON (encode with {allowNonFinite = true} to write null)"
+        ),
+        "{}",
+        huge_err
+    );
+    assert!(nan_err.contains("NaN is not valid JSON"), "{}", nan_err);
+    let lenient: serde_json::Value = serde_json::from_str(&lenient).unwrap();
+    assert_eq!(
+        lenient,
+        serde_json::json!({"limit": null, "low": null, "ratio": null, "n": 1})
+    );
+    assert_eq!(pretty, "[\n  null\n]");
+}
@@ -19,7 +19,7 @@ print("   Architecture:", process.arch)
 
 print("\n5. Testing process.argv...")
 print("   Arguments count:", #process.argv)
-print("   First argument:", process.argv[1])
+print("   Script path:", process.argv[0])
 
 print("\n6. Testing process.env (read)...")
 print("   PATH exists:", process.env.PATH ~= nil)
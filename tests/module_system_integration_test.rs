@@ -324,6 +324,45 @@ mod require_cache_and_resolve {
 
         assert!(result.is_err(), "resolve should error on missing module");
     }
+
+    #[test]
+    fn test_require_has_checks_builtins() {
+        let lua = Lua::new();
+        let loader = Arc::new(Mutex::new(ModuleLoader::new(PathBuf::from("."))));
+
+        setup_require_fn(&lua, loader).unwrap();
+
+        let (has_fs, has_missing, has_http): (bool, bool, bool) = lua
+            .load(
+                r#"
+            return require.has("fs"), require.has("nonexistent"), require.has("http")
+        "#,
+            )
+            .eval()
+            .unwrap();
+
+        assert!(has_fs);
+        assert!(!has_missing);
+        assert_eq!(has_http, cfg!(feature = "http"));
+    }
+
+    #[test]
+    fn test_require_builtins_matches_registry() {
+        let lua = Lua::new();
+        let loader = Arc::new(Mutex::new(ModuleLoader::new(PathBuf::from("."))));
+
+        setup_require_fn(&lua, loader).unwrap();
+
+        let names: Vec<String> = lua.load("return require.builtins()").eval().unwrap();
+
+        let mut expected: Vec<String> = hype_rs::modules::builtins::BuiltinRegistry::new()
+            .list()
+            .into_iter()
+            .map(String::from)
+            .collect();
+        expected.sort();
+        assert_eq!(names, expected);
+    }
 }
 
 mod cli_module_integration {
@@ -436,6 +475,97 @@ mod error_handling {
 
         assert!(result.is_err(), "Should error on invalid path");
     }
+
+    fn setup_project(files: &[(&str, &str)]) -> (tempfile::TempDir, Lua) {
+        let dir = tempfile::tempdir().unwrap();
+        for (name, source) in files {
+            std::fs::write(dir.path().join(name), source).unwrap();
+        }
+        let mut loader = ModuleLoader::new(dir.path().to_path_buf());
+        loader.set_entry("main.lua");
+        let lua = Lua::new();
+        setup_require_fn(&lua, Arc::new(Mutex::new(loader))).unwrap();
+        (dir, lua)
+    }
+
+    #[test]
+    fn test_nested_requires_load() {
+        let (_dir, lua) = setup_project(&[
+            (
+                "a.lua",
+                "local b = require('./b')\nreturn {value = b.value + 1}",
+            ),
+            ("b.lua", "local path = require('path')\nreturn {value = 41}"),
+        ]);
+
+        let value: i64 = lua.load("return require('./a').value").eval().unwrap();
+        assert_eq!(value, 42);
+    }
+
+    #[test]
+    fn test_transitive_runtime_error_reports_require_chain() {
+        let (_dir, lua) = setup_project(&[
+            ("a.lua", "local b = require('./b')\nreturn b"),
+            ("b.lua", "local c = require('./c')\nreturn c"),
+            ("c.lua", "local config = nil\nreturn {port = config.port}"),
+        ]);
+
+        let message: String = lua
+            .load(
+                r#"
+            local ok, err = pcall(require, "./a")
+            assert(not ok and err.code == "ERR_MODULE_LOAD", tostring(err))
+            return err.message
+        "#,
+            )
+            .eval()
+            .unwrap();
+
+        assert!(
+            message.contains("main.lua -> ./a -> ./b -> ./c: "),
+            "{}",
+            message
+        );
+        assert!(
+            message.contains("attempt to index a nil value"),
+            "{}",
+            message
+        );
+        // The chain is reported once, not again by every module above it
+        assert_eq!(message.matches(" -> ./c").count(), 1, "{}", message);
+    }
+
+    #[test]
+    fn test_missing_and_circular_requires_report_chain() {
+        let (_dir, lua) = setup_project(&[
+            ("a.lua", "return require('./missing')"),
+            ("loop.lua", "return require('./back')"),
+            ("back.lua", "return require('./loop')"),
+        ]);
+
+        let (missing, circular): (String, String) = lua
+            .load(
+                r#"
+            local _, missing = pcall(require, "./a")
+            local _, circular = pcall(require, "./loop")
+            return missing.message, circular.message
+        "#,
+            )
+            .eval()
+            .unwrap();
+
+        assert!(
+            missing.contains("main.lua -> ./a -> ./missing: "),
+            "{}",
+            missing
+        );
+        assert!(
+            circular
+                .contains("Circular dependency detected: main.lua -> ./loop -> ./back -> ./loop"),
+            "{}",
+            circular
+        );
+    }
 }
 
 mod loader_operations {
@@ -693,4 +823,43 @@ mod module_features {
             "Module state should be preserved across requires"
         );
     }
+
+    #[test]
+    fn test_require_scoped_package() {
+        let dir = tempfile::tempdir().unwrap();
+        let package = dir.path().join("hype_modules").join("@myorg").join("utils");
+        std::fs::create_dir_all(package.join("lib")).unwrap();
+        std::fs::write(
+            package.join("index.lua"),
+            "local strings = require('@myorg/utils/lib/strings')\nreturn {shout = strings.upper}",
+        )
+        .unwrap();
+        std::fs::write(
+            package.join("lib").join("strings.lua"),
+            "return {upper = string.upper}",
+        )
+        .unwrap();
+
+        let lua = Lua::new();
+        let loader = ModuleLoader::new(dir.path().to_path_buf());
+        setup_require_fn(&lua, Arc::new(Mutex::new(loader))).unwrap();
+
+        let (shouted, bad_name): (String, String) = lua
+            .load(
+                r#"
+            local utils = require("@myorg/utils")
+            local ok, err = pcall(require, "@myorg")
+            assert(not ok)
+            return utils.shout("hi"), tostring(err.message or err)
+        "#,
+            )
+            .eval()
+            .unwrap();
+        assert_eq!(shouted, "HI");
+        assert!(
+            bad_name.contains("scoped names look like @scope/name"),
+            "{}",
+            bad_name
+        );
+    }
 }
@@ -133,7 +133,7 @@ fn test_process_argv() {
     let lua = setup_lua();
     let code = r#"
         local process = require("process")
-        return type(process.argv) == "table" and #process.argv > 0
+        return type(process.argv) == "table" and type(process.argv[0]) == "string"
     "#;
     let result: bool = lua.load(code).eval().unwrap();
     assert!(result);
@@ -168,3 +168,100 @@ fn test_process_integration_with_fs() {
     let result: bool = lua.load(code).eval().unwrap();
     assert!(result);
 }
+
+#[test]
+fn test_process_load_env_preserves_existing_vars() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join(".env");
+    std::fs::write(
+        &path,
+        "# fixture\nexport HYPE_DOTENV_NAME=\"hype app\" # trailing\nHYPE_DOTENV_KEEP=from-file\nHYPE_DOTENV_RAW='a#b'\n",
+    )
+    .unwrap();
+    std::env::set_var("HYPE_DOTENV_KEEP", "from-shell");
+
+    let lua = setup_lua();
+    lua.globals()
+        .set("env_path", path.to_str().unwrap())
+        .unwrap();
+    let (parsed_keep, raw): (String, String) = lua
+        .load(
+            r#"
+            local process = require("process")
+            local parsed = process.loadEnv(env_path)
+            return parsed.HYPE_DOTENV_KEEP, parsed.HYPE_DOTENV_RAW
+        "#,
+        )
+        .eval()
+        .unwrap();
+
+    assert_eq!(parsed_keep, "from-file");
+    assert_eq!(raw, "a#b");
+    assert_eq!(std::env::var("HYPE_DOTENV_NAME").unwrap(), "hype app");
+    assert_eq!(std::env::var("HYPE_DOTENV_KEEP").unwrap(), "from-shell");
+
+    lua.load(r#"require("process").loadEnv(env_path, {override = true})"#)
+        .exec()
+        .unwrap();
+    assert_eq!(std::env::var("HYPE_DOTENV_KEEP").unwrap(), "from-file");
+
+    let missing = lua
+        .load(r#"require("process").loadEnv(env_path .. ".missing")"#)
+        .exec();
+    assert!(missing.is_err());
+}
+
+#[test]
+fn test_process_stdout_buffering() {
+    let dir = tempfile::tempdir().unwrap();
+    let log_path = dir.path().join("stdout.log");
+    let script_path = dir.path().join("buffering.lua");
+    // The script reads its own redirected stdout to see what has been written so far
+    std::fs::write(
+        &script_path,
+        format!(
+            r#"
+local fs = require("fs")
+local process = require("process")
+local function written() return fs.readFileSync({log:?}) end
+
+process.setStdoutLineBuffered(false)
+print("held 1")
+process.stdout.write("held 2\n")
+assert(written() == "", "flushed early: " .. written())
+
+process.flush()
+assert(written() == "held 1\nheld 2\n", written())
+
+process.setStdoutLineBuffered(true)
+print("line 3")
+assert(written() == "held 1\nheld 2\nline 3\n", written())
+process.stdout.write("line 4\npartial")
+assert(written() == "held 1\nheld 2\nline 3\nline 4\n", written())
+
+process.setStdoutLineBuffered(false)
+print(" kept")
+assert(written() == "held 1\nheld 2\nline 3\nline 4\n", written())
+"#,
+            log = log_path.to_str().unwrap()
+        ),
+    )
+    .unwrap();
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_hype"))
+        .args(["run", script_path.to_str().unwrap()])
+        .stdout(std::fs::File::create(&log_path).unwrap())
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    // Whatever was still buffered is written when the script exits
+    assert_eq!(
+        std::fs::read_to_string(&log_path).unwrap(),
+        "held 1\nheld 2\nline 3\nline 4\npartial kept\n"
+    );
+}
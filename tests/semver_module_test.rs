@@ -0,0 +1,68 @@
+use hype_rs::lua::require::setup_require_fn;
+use hype_rs::modules::loader::ModuleLoader;
+use mlua::Lua;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+fn setup_lua() -> Lua {
+    let lua = Lua::new();
+    let loader = Arc::new(Mutex::new(ModuleLoader::new(PathBuf::from("."))));
+    setup_require_fn(&lua, loader).unwrap();
+    lua
+}
+
+#[test]
+fn test_semver_comparison_ordering() {
+    let lua = setup_lua();
+    lua.load(
+        r#"
+local semver = require("semver")
+
+local versions = {"1.10.0", "1.0.0", "1.0.0-alpha", "0.9.9", "1.0.0-beta.2", "1.2.0", "1.0.0-beta.11"}
+table.sort(versions, semver.lt)
+local expected = {"0.9.9", "1.0.0-alpha", "1.0.0-beta.2", "1.0.0-beta.11", "1.0.0", "1.2.0", "1.10.0"}
+for i, v in ipairs(expected) do
+    assert(versions[i] == v, i .. ": " .. versions[i] .. " ~= " .. v)
+end
+
+assert(semver.compare("1.0.0-alpha", "1.0.0") == -1)
+assert(semver.compare("2.0.0", "1.9.9") == 1)
+assert(semver.compare("1.0.0+linux", "1.0.0+mac") == 0)
+assert(semver.lt("1.0.0-alpha", "1.0.0"))
+assert(semver.gt("1.0.1", "1.0.0") and semver.gte("1.0.0", "1.0.0"))
+assert(semver.lte("1.0.0", "1.0.0") and semver.eq("v1.0.0", "1.0.0"))
+
+local v = semver.parse("3.4.5-rc.1+build.7")
+assert(v.major == 3 and v.minor == 4 and v.patch == 5)
+assert(v.prerelease == "rc.1" and v.build == "build.7")
+assert(semver.major("3.4.5") == 3 and semver.minor("3.4.5") == 4 and semver.patch("3.4.5") == 5)
+"#,
+    )
+    .exec()
+    .unwrap();
+}
+
+#[test]
+fn test_semver_satisfies_ranges() {
+    let lua = setup_lua();
+    lua.load(
+        r#"
+local semver = require("semver")
+
+assert(semver.satisfies("1.5.0", "^1.2.3"))
+assert(not semver.satisfies("2.0.0", "^1.2.3"))
+assert(not semver.satisfies("1.2.2", "^1.2.3"))
+assert(semver.satisfies("1.2.9", "~1.2.3"))
+assert(not semver.satisfies("1.3.0", "~1.2.3"))
+assert(semver.satisfies("1.4.0", ">=1.2.0 <2.0.0"))
+assert(semver.satisfies("1.5.0", "1.0.0 - 2.0.0"))
+assert(semver.satisfies("4.0.0", "^2 || ^4"))
+assert(not semver.satisfies("1.5.0-beta", "^1.2.3"))
+
+local ok, err = pcall(semver.satisfies, "1.0.0", "^^1")
+assert(not ok and err.code == "ERR_INVALID_RANGE", tostring(err))
+"#,
+    )
+    .exec()
+    .unwrap();
+}
@@ -0,0 +1,174 @@
+#[cfg(feature = "sqlite")]
+mod tests {
+    use hype_rs::lua::create_cli_security_policy;
+    use hype_rs::lua::require::setup_require_fn;
+    use hype_rs::lua::security::SandboxLevel;
+    use hype_rs::modules::loader::ModuleLoader;
+    use mlua::Lua;
+    use std::path::PathBuf;
+    use std::sync::{Arc, Mutex};
+
+    fn setup_lua() -> Lua {
+        let lua = Lua::new();
+        let loader = Arc::new(Mutex::new(ModuleLoader::new(PathBuf::from("."))));
+        setup_require_fn(&lua, loader).unwrap();
+        lua
+    }
+
+    #[test]
+    fn test_insert_with_bound_params_and_query_back() {
+        let lua = setup_lua();
+        lua.load(
+            r#"
+local sqlite = require("sqlite")
+local db = sqlite.open(":memory:")
+db:exec([[
+    CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT NOT NULL, score REAL, avatar BLOB);
+    CREATE INDEX users_name ON users (name);
+]])
+
+assert(db:exec("INSERT INTO users (name, score) VALUES (?, ?)", {"ada", 9.5}) == 1)
+assert(db:lastInsertRowId() == 1)
+db:exec("INSERT INTO users (name, score, avatar) VALUES (:name, :score, :avatar)",
+    {name = "grace", score = 8, avatar = "\0\1\2"})
+
+-- Bound values are data, never SQL
+local evil = "x'); DROP TABLE users; --"
+db:exec("INSERT INTO users (name) VALUES (?)", {evil})
+
+local rows = db:query("SELECT id, name, score, avatar FROM users ORDER BY id")
+assert(#rows == 3)
+assert(rows[1].id == 1 and math.type(rows[1].id) == "integer")
+assert(rows[1].name == "ada" and rows[1].score == 9.5)
+assert(rows[1].avatar == nil)
+assert(rows[2].avatar == "\0\1\2")
+assert(rows[3].name == evil and rows[3].score == nil)
+
+local found = db:query("SELECT name FROM users WHERE score > ?", {8.5})
+assert(#found == 1 and found[1].name == "ada")
+assert(#db:query("SELECT * FROM users WHERE name = ?", {"nobody"}) == 0)
+
+db:close()
+assert(db:isClosed())
+local ok, err = pcall(db.query, db, "SELECT 1")
+assert(not ok and err.code == "ERR_CLOSED")
+"#,
+        )
+        .exec()
+        .unwrap();
+    }
+
+    #[test]
+    fn test_failed_transaction_rolls_back() {
+        let lua = setup_lua();
+        lua.load(
+            r#"
+local sqlite = require("sqlite")
+local db = sqlite.open(":memory:")
+db:exec("CREATE TABLE ledger (account TEXT, amount INTEGER)")
+
+local total = db:transaction(function(tx)
+    tx:exec("INSERT INTO ledger VALUES (?, ?)", {"alice", 100})
+    tx:exec("INSERT INTO ledger VALUES (?, ?)", {"bob", -100})
+    return 2
+end)
+assert(total == 2)
+
+local ok, err = pcall(db.transaction, db, function(tx)
+    tx:exec("INSERT INTO ledger VALUES (?, ?)", {"carol", 50})
+    error("insufficient funds")
+end)
+assert(not ok and tostring(err):find("insufficient funds"))
+
+-- A failed inner transaction only undoes its own work
+db:transaction(function(tx)
+    tx:exec("INSERT INTO ledger VALUES (?, ?)", {"dave", 1})
+    pcall(tx.transaction, tx, function()
+        tx:exec("INSERT INTO ledger VALUES (?, ?)", {"erin", 2})
+        error("nested failure")
+    end)
+end)
+
+local rows = db:query("SELECT account FROM ledger ORDER BY rowid")
+assert(#rows == 3, #rows)
+assert(rows[1].account == "alice" and rows[2].account == "bob")
+assert(rows[3].account == "dave")
+"#,
+        )
+        .exec()
+        .unwrap();
+    }
+
+    #[test]
+    fn test_parameter_errors() {
+        let lua = setup_lua();
+        lua.load(
+            r#"
+local sqlite = require("sqlite")
+local db = sqlite.open(":memory:")
+db:exec("CREATE TABLE t (a, b)")
+
+local ok, err = pcall(db.exec, db, "INSERT INTO t VALUES (?, ?)", {1})
+assert(not ok and err.code == "ERR_INVALID_PARAMETER", tostring(err))
+
+ok, err = pcall(db.query, db, "SELECT * FROM t WHERE a = ?", {1, b = 2})
+assert(not ok and err.code == "ERR_INVALID_PARAMETER")
+
+ok, err = pcall(db.prepare, db, "SELEC nonsense")
+assert(not ok and err.code == "ERR_QUERY" and err.kind == "sqlite")
+"#,
+        )
+        .exec()
+        .unwrap();
+    }
+
+    #[test]
+    fn test_attach_is_denied_under_strict_and_allowlisted_policies() {
+        let dir = tempfile::tempdir().unwrap();
+        let allowed = dir.path().join("allowed");
+        std::fs::create_dir(&allowed).unwrap();
+        let outside = dir.path().join("outside.db");
+
+        let lua = setup_lua();
+        create_cli_security_policy(false, SandboxLevel::Strict).install(&lua);
+        lua.globals()
+            .set("outside", outside.to_str().unwrap())
+            .unwrap();
+        lua.load(
+            r#"
+local sqlite = require("sqlite")
+local db = sqlite.open(":memory:")
+local ok = pcall(db.exec, db, "ATTACH DATABASE '" .. outside .. "' AS a; CREATE TABLE a.t (x)")
+assert(not ok, "ATTACH should be denied")
+ok = pcall(db.exec, db, "VACUUM INTO '" .. outside .. "'")
+assert(not ok, "VACUUM INTO should be denied")
+"#,
+        )
+        .exec()
+        .unwrap();
+        assert!(!outside.exists());
+
+        let lua = setup_lua();
+        let mut policy = create_cli_security_policy(false, SandboxLevel::Default);
+        policy.allowed_file_operations.allowed_paths = vec![allowed.clone()];
+        policy.install(&lua);
+        lua.globals()
+            .set("outside", outside.to_str().unwrap())
+            .unwrap();
+        lua.globals()
+            .set("inside", allowed.join("main.db").to_str().unwrap())
+            .unwrap();
+        lua.load(
+            r#"
+local sqlite = require("sqlite")
+assert(not pcall(sqlite.open, outside))
+local db = sqlite.open(inside)
+local ok = pcall(db.exec, db, "ATTACH DATABASE '" .. outside .. "' AS a; CREATE TABLE a.t (x)")
+assert(not ok, "ATTACH should be denied")
+"#,
+        )
+        .exec()
+        .unwrap();
+        assert!(!outside.exists());
+    }
+}
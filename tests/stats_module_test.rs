@@ -0,0 +1,68 @@
+use hype_rs::lua::require::setup_require_fn;
+use hype_rs::modules::loader::ModuleLoader;
+use mlua::Lua;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+fn setup_lua() -> Lua {
+    let lua = Lua::new();
+    let loader = Arc::new(Mutex::new(ModuleLoader::new(PathBuf::from("."))));
+    setup_require_fn(&lua, loader).unwrap();
+    lua
+}
+
+#[test]
+fn test_stats_known_dataset() {
+    let lua = setup_lua();
+    lua.load(
+        r#"
+local stats = require("stats")
+local data = {9, 2, 5, 4, 4, 7, 4, 5}
+
+local function close(a, b) return math.abs(a - b) < 1e-9 end
+
+assert(stats.sum(data) == 40)
+assert(stats.mean(data) == 5)
+assert(stats.median(data) == 4.5)
+assert(stats.mode(data) == 4)
+assert(stats.min(data) == 2 and stats.max(data) == 9)
+
+assert(stats.variance(data) == 4)
+assert(stats.stddev(data) == 2)
+assert(close(stats.variance(data, {sample = true}), 32 / 7))
+assert(close(stats.stddev(data, {sample = true}), math.sqrt(32 / 7)))
+
+assert(stats.percentile(data, 0) == 2)
+assert(stats.percentile(data, 50) == 4.5)
+assert(close(stats.percentile(data, 25), 4))
+assert(close(stats.percentile(data, 90), 7.6))
+assert(stats.percentile(data, 100) == 9)
+"#,
+    )
+    .exec()
+    .unwrap();
+}
+
+#[test]
+fn test_stats_empty_and_invalid_input() {
+    let lua = setup_lua();
+    lua.load(
+        r#"
+local stats = require("stats")
+
+assert(stats.sum({}) == 0)
+assert(stats.mean({}) == nil)
+assert(stats.median({}) == nil)
+assert(stats.percentile({}, 50) == nil)
+assert(stats.stddev({42}, {sample = true}) == nil)
+assert(stats.stddev({42}) == 0)
+
+local ok, err = pcall(stats.median, {1, 2, true})
+assert(not ok and err.code == "ERR_NOT_A_NUMBER", tostring(err))
+ok, err = pcall(stats.percentile, {1, 2}, -1)
+assert(not ok and err.code == "ERR_INVALID_PERCENTILE", tostring(err))
+"#,
+    )
+    .exec()
+    .unwrap();
+}
@@ -0,0 +1,82 @@
+use hype_rs::lua::require::setup_require_fn;
+use hype_rs::modules::loader::ModuleLoader;
+use mlua::Lua;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+fn setup_lua() -> Lua {
+    let lua = Lua::new();
+    let loader = Arc::new(Mutex::new(ModuleLoader::new(PathBuf::from("."))));
+    setup_require_fn(&lua, loader).unwrap();
+    lua
+}
+
+#[test]
+fn test_render_list_section() {
+    let lua = setup_lua();
+    lua.load(
+        r#"
+local template = require("template")
+local source = [[
+<ul>
+{{#items}}
+  <li>{{name}} ({{price}}) {{currency}}</li>
+{{/items}}
+{{^items}}
+  <li>No items</li>
+{{/items}}
+</ul>
+]]
+local out = template.render(source, {
+    currency = "EUR",
+    items = {{name = "tea", price = 3}, {name = "cake", price = 4.5}},
+})
+assert(out == "<ul>\n  <li>tea (3) EUR</li>\n  <li>cake (4.5) EUR</li>\n</ul>\n", out)
+
+out = template.render(source, {items = {}})
+assert(out == "<ul>\n  <li>No items</li>\n</ul>\n", out)
+
+assert(template.render("{{#tags}}[{{.}}]{{/tags}}", {tags = {"a", "b"}}) == "[a][b]")
+assert(template.render("{{#user}}{{name}}{{/user}}", {user = {name = "ada"}}) == "ada")
+assert(template.render("{{#on}}yes{{/on}}{{^off}}no{{/off}}", {on = true, off = false}) == "yesno")
+"#,
+    )
+    .exec()
+    .unwrap();
+}
+
+#[test]
+fn test_missing_variables_render_empty() {
+    let lua = setup_lua();
+    lua.load(
+        r#"
+local template = require("template")
+assert(template.render("Hello, {{name}}!", {}) == "Hello, !")
+assert(template.render("{{user.address.city}}|{{{raw}}}", {user = {}}) == "|")
+assert(template.render("static text") == "static text")
+"#,
+    )
+    .exec()
+    .unwrap();
+}
+
+#[test]
+fn test_escaped_and_raw_output() {
+    let lua = setup_lua();
+    lua.load(
+        r#"
+local template = require("template")
+local data = {html = [[<a href="/x?a=1&b=2">it's</a>]]}
+local escaped = template.render("{{html}}", data)
+assert(escaped == "&lt;a href=&quot;/x?a=1&amp;b=2&quot;&gt;it&#39;s&lt;/a&gt;", escaped)
+assert(template.render("{{{html}}}", data) == data.html)
+assert(template.render("{{& html}}", data) == data.html)
+
+local ok, err = pcall(template.render, "{{#open}}never closed", {})
+assert(not ok and err.code == "ERR_PARSE" and err.kind == "template", tostring(err))
+assert(tostring(err.message):find("never closed"))
+"#,
+    )
+    .exec()
+    .unwrap();
+}
@@ -0,0 +1,180 @@
+use hype_rs::lua::require::setup_require_fn;
+use hype_rs::modules::loader::ModuleLoader;
+use mlua::Lua;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Output, Stdio};
+use std::sync::{Arc, Mutex};
+use tempfile::TempDir;
+
+fn setup_lua() -> Lua {
+    let lua = Lua::new();
+    let loader = Arc::new(Mutex::new(ModuleLoader::new(PathBuf::from("."))));
+    setup_require_fn(&lua, loader).unwrap();
+    lua
+}
+
+/// Run `script` with `hype run`, writing `input` to its stdin.
+fn run_with_stdin(script: &str, input: &str) -> Output {
+    let temp_dir = TempDir::new().unwrap();
+    let script_path = temp_dir.path().join("ask.lua");
+    std::fs::write(&script_path, script).unwrap();
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_hype"))
+        .arg("run")
+        .arg(&script_path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Failed to execute command");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn test_forced_colors_wrap_text_in_ansi_codes() {
+    let lua = setup_lua();
+    lua.load(
+        r#"
+local term = require("term")
+term.setColors(true)
+
+local codes = {
+    red = 31, green = 32, yellow = 33, blue = 34,
+    magenta = 35, cyan = 36, gray = 90,
+}
+for name, code in pairs(codes) do
+    assert(term[name]("x") == "\27[" .. code .. "mx\27[39m", name)
+end
+assert(term.bold("x") == "\27[1mx\27[22m")
+assert(term.dim("x") == "\27[2mx\27[22m")
+
+-- An inner color does not end the outer one early
+local line = term.red("error: " .. term.yellow("file.lua") .. " missing")
+assert(line == "\27[31merror: \27[33mfile.lua\27[39m\27[31m missing\27[39m", line)
+"#,
+    )
+    .exec()
+    .unwrap();
+}
+
+#[test]
+fn test_environment_controls_colors() {
+    let lua = setup_lua();
+    std::env::remove_var("FORCE_COLOR");
+    std::env::set_var("NO_COLOR", "1");
+    lua.load(
+        r#"
+local term = require("term")
+assert(not term.colorsEnabled())
+local plain = term.red("warning") .. term.bold("!")
+assert(plain == "warning!", plain)
+assert(not plain:find("\27", 1, true))
+"#,
+    )
+    .exec()
+    .unwrap();
+
+    // Without NO_COLOR, FORCE_COLOR turns colors on even when piped
+    std::env::remove_var("NO_COLOR");
+    std::env::set_var("FORCE_COLOR", "1");
+    lua.load(
+        r#"
+local term = require("term")
+assert(term.colorsEnabled())
+assert(term.green("ok") == "\27[32mok\27[39m")
+
+term.setColors(false)
+assert(term.green("ok") == "ok")
+term.setColors(nil)
+assert(term.colorsEnabled())
+assert(type(term.isTTY()) == "boolean" and type(term.isTTY("stderr")) == "boolean")
+"#,
+    )
+    .exec()
+    .unwrap();
+    std::env::remove_var("FORCE_COLOR");
+}
+
+#[test]
+fn test_prompt_reads_answers_from_stdin() {
+    let output = run_with_stdin(
+        r#"
+local term = require("term")
+local name = term.prompt("Project name?")
+local license = term.prompt("License?", "MIT")
+local secret = term.password("Token:")
+io.write(name .. "|" .. license .. "|" .. secret .. "\n")
+local ok, err = pcall(term.prompt, "Anything else?")
+io.write(err.code .. "\n")
+"#,
+        "demo app\n\ns3cret\n",
+    );
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{}", stderr);
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "demo app|MIT|s3cret\nERR_EOF\n"
+    );
+    // Questions are written to stderr, leaving stdout for the script's output
+    assert!(
+        stderr.contains("Project name? License? (MIT) Token: "),
+        "{}",
+        stderr
+    );
+}
+
+#[test]
+fn test_confirm_yes_and_default_on_empty() {
+    let output = run_with_stdin(
+        r#"
+local term = require("term")
+print(term.confirm("Install?"))
+print(term.confirm("Overwrite?", false))
+print(term.confirm("Continue?", true))
+print(term.confirm("At EOF?", true))
+"#,
+        "y\n\nwhat\n\n",
+    );
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{}", stderr);
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "true\nfalse\ntrue\ntrue\n"
+    );
+    assert!(stderr.contains("Overwrite? [y/N]"), "{}", stderr);
+    assert!(stderr.contains("Please answer y or n."), "{}", stderr);
+}
+
+#[test]
+fn test_progress_is_silent_when_not_a_terminal() {
+    let output = run_with_stdin(
+        r#"
+local term = require("term")
+local bar = term.progressBar(4, {width = 8, label = "copy"})
+for i = 1, 4 do bar:update(i) end
+assert(bar.percent == 100 and bar.current == 4)
+bar:finish()
+assert(bar.finished)
+
+local spinner = term.spinner("waiting")
+spinner:tick()
+spinner:stop("done")
+assert(spinner.stopped)
+io.write("ok\n")
+"#,
+        "",
+    );
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "ok\n");
+    assert!(output.stderr.is_empty(), "{:?}", output.stderr);
+}
@@ -293,3 +293,108 @@ assert(components.month >= 1 and components.month <= 12)
     .exec()
     .unwrap();
 }
+
+/// US Eastern time as a POSIX TZ rule, so it works without a zoneinfo
+/// database. DST began on 2024-03-10 at 02:00.
+const EASTERN: &str = "EST5EDT,M3.2.0,M11.1.0";
+
+#[test]
+fn test_time_add_months_clamps_at_month_end() {
+    std::env::set_var("TZ", EASTERN);
+    let lua = setup_lua();
+    lua.load(
+        r#"
+local time = require("time")
+local jan31 = time.fromISO("2024-01-31T15:00:00Z")   -- 10:00 EST
+assert(time.toISO(time.add(jan31, {months = 1})) == "2024-02-29T15:00:00+00:00")
+assert(time.toISO(time.add(jan31, {years = 1, months = 1})) == "2025-02-28T15:00:00+00:00")
+assert(time.toISO(time.add(jan31, {months = -2})) == "2023-11-30T15:00:00+00:00")
+assert(time.toISO(time.add(jan31, {hours = 1, minutes = 30, seconds = 15})) == "2024-01-31T16:30:15+00:00")
+assert(time.add(jan31, {}) == jan31)
+
+local ok, err = pcall(time.add, jan31, {day = 1})
+assert(not ok and tostring(err):find("Unknown field 'day'"), tostring(err))
+"#,
+    )
+    .exec()
+    .unwrap();
+}
+
+#[test]
+fn test_time_add_days_keeps_wall_clock_across_dst() {
+    std::env::set_var("TZ", EASTERN);
+    let lua = setup_lua();
+    lua.load(
+        r#"
+local time = require("time")
+local after = time.fromISO("2024-03-11T16:00:00Z")   -- noon EDT
+local before = time.add(after, {days = -2})
+assert(time.toISO(before) == "2024-03-09T17:00:00+00:00", time.toISO(before))   -- noon EST
+assert(after - before == 47 * 3600 * 1000)
+assert(time.add(before, {days = 2}) == after)
+-- Hours are elapsed time, so 48 of them land an hour later on the clock
+assert(time.toISO(time.add(before, {hours = 48})) == "2024-03-11T17:00:00+00:00")
+"#,
+    )
+    .exec()
+    .unwrap();
+}
+
+#[test]
+fn test_time_diff_in_days() {
+    std::env::set_var("TZ", EASTERN);
+    let lua = setup_lua();
+    lua.load(
+        r#"
+local time = require("time")
+local march1 = time.fromISO("2024-03-01T05:00:00Z")
+local march15 = time.fromISO("2024-03-15T04:00:00Z")
+assert(time.diff(march15, march1, "days") == 14)
+assert(time.diff(march1, march15, "days") == -14)
+assert(time.diff(march15, march1, "weeks") == 2)
+assert(time.diff(march15, march1, "hours") == 14 * 24 - 1)
+assert(time.diff(march15, march1) == (14 * 24 - 1) * 3600 * 1000)
+assert(time.diff(time.fromISO("2024-03-31T12:00:00Z"), time.fromISO("2024-01-31T12:00:00Z"), "months") == 2)
+assert(time.diff(time.fromISO("2025-01-30T12:00:00Z"), time.fromISO("2024-01-31T12:00:00Z"), "years") == 0)
+
+local ok, err = pcall(time.diff, march15, march1, "fortnights")
+assert(not ok and tostring(err):find("Invalid unit"), tostring(err))
+"#,
+    )
+    .exec()
+    .unwrap();
+}
+
+#[test]
+fn test_time_parse_auto() {
+    let lua = setup_lua();
+    lua.load(
+        r#"
+local time = require("time")
+local expected = time.fromISO("2024-03-01T12:00:00Z")
+local inputs = {
+    "2024-03-01T12:00:00Z",
+    "2024-03-01T13:00:00+01:00",
+    "2024-03-01T12:00:00",
+    "Fri, 01 Mar 2024 12:00:00 GMT",
+    "2024-03-01 12:00:00",
+    "1709294400",
+    "1709294400000",
+}
+for _, input in ipairs(inputs) do
+    assert(time.parseAuto(input) == expected, input)
+end
+assert(time.parseAuto("2024-03-01") == expected - 12 * 3600 * 1000)
+
+local ok, err = pcall(time.parseAuto, "next tuesday")
+assert(not ok)
+err = tostring(err)
+assert(err:find("'next tuesday'", 1, true), err)
+for _, name in ipairs({"ISO 8601", "RFC 2822", "YYYY-MM-DD HH:MM:SS", "Unix epoch"}) do
+    assert(err:find(name, 1, true), err)
+end
+"#,
+    )
+    .exec()
+    .unwrap();
+}
@@ -0,0 +1,79 @@
+use hype_rs::lua::require::setup_require_fn;
+use hype_rs::modules::loader::ModuleLoader;
+use mlua::Lua;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+fn setup_lua() -> Lua {
+    let lua = Lua::new();
+    let loader = Arc::new(Mutex::new(ModuleLoader::new(PathBuf::from("."))));
+    setup_require_fn(&lua, loader).unwrap();
+    lua
+}
+
+#[test]
+fn test_toml_parse_package_section() {
+    let lua = setup_lua();
+    lua.load(
+        r#"
+local toml = require("toml")
+local manifest = toml.parse([==[
+[package]
+name = "demo"
+version = "1.2.3"
+authors = ["Ada", "Grace"]
+
+[package.metadata.release]
+publish = false
+
+[[bin]]
+name = "demo"
+path = "src/main.rs"
+]==])
+assert(manifest.package.name == "demo")
+assert(manifest.package.authors[2] == "Grace")
+assert(manifest.package.metadata.release.publish == false)
+assert(manifest.bin[1].path == "src/main.rs")
+"#,
+    )
+    .exec()
+    .unwrap();
+}
+
+#[test]
+fn test_toml_round_trip() {
+    let lua = setup_lua();
+    lua.load(
+        r#"
+local toml = require("toml")
+local original = {
+    package = {name = "demo", version = "1.2.3", metadata = {docs = {all_features = true}}},
+    bin = {{name = "demo", path = "src/main.rs"}}
+}
+local text = toml.stringify(original)
+assert(string.find(text, "[package]", 1, true))
+assert(string.find(text, "[[bin]]", 1, true))
+local parsed = toml.parse(text)
+assert(parsed.package.name == "demo")
+assert(parsed.package.metadata.docs.all_features == true)
+assert(parsed.bin[1].name == "demo")
+"#,
+    )
+    .exec()
+    .unwrap();
+}
+
+#[test]
+fn test_toml_parse_error_reports_position() {
+    let lua = setup_lua();
+    lua.load(
+        r#"
+local toml = require("toml")
+local ok, err = pcall(toml.parse, "[package]\nname = ")
+assert(not ok)
+assert(string.find(tostring(err), "line 2", 1, true), tostring(err))
+"#,
+    )
+    .exec()
+    .unwrap();
+}
@@ -0,0 +1,127 @@
+use hype_rs::lua::require::setup_require_fn;
+use hype_rs::modules::loader::ModuleLoader;
+use mlua::Lua;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+fn setup_lua() -> Lua {
+    let lua = Lua::new();
+    let loader = Arc::new(Mutex::new(ModuleLoader::new(PathBuf::from("."))));
+    setup_require_fn(&lua, loader).unwrap();
+    lua
+}
+
+#[test]
+fn test_util_format_json_round_trips() {
+    let lua = setup_lua();
+    lua.load(
+        r#"
+local util = require("util")
+local json = require("json")
+local out = util.format("%j", {name = "hype", tags = {"lua", "rust"}})
+local decoded = json.decode(out)
+assert(decoded.name == "hype", out)
+assert(decoded.tags[2] == "rust", out)
+assert(util.format("100%% %s", "done") == "100% done")
+"#,
+    )
+    .exec()
+    .unwrap();
+}
+
+#[test]
+fn test_util_format_inspect_directive() {
+    let lua = setup_lua();
+    lua.load(
+        r#"
+local util = require("util")
+local out = util.format("state: %o", {1, 2, mode = "fast", nested = {on = true}})
+assert(out == 'state: { 1, 2, mode = "fast", nested = { on = true } }', out)
+assert(util.inspect({a = {b = {c = {}}}}, {depth = 1}) == "{ a = {...} }")
+"#,
+    )
+    .exec()
+    .unwrap();
+}
+
+#[test]
+fn test_util_memoize_runs_once_per_arguments() {
+    let lua = setup_lua();
+    lua.load(
+        r#"
+local util = require("util")
+local calls = 0
+local square = util.memoize(function(n, opts)
+    calls = calls + 1
+    return n * n, opts and opts.tag
+end)
+
+assert(square(4) == 16)
+assert(square(4) == 16)
+assert(calls == 1, calls)
+local value, tag = square(3, {tag = "x"})
+assert(value == 9 and tag == "x")
+square(3, {tag = "x"})
+assert(calls == 2, calls)
+
+local fib
+fib = util.memoize(function(n)
+    if n < 2 then return n end
+    return fib(n - 1) + fib(n - 2)
+end)
+assert(fib(80) == 23416728348467685)
+
+local ok, err = pcall(square, print)
+assert(not ok and tostring(err):find("JSON%-serializable"), tostring(err))
+"#,
+    )
+    .exec()
+    .unwrap();
+}
+
+#[test]
+fn test_util_memoize_evicts_least_recently_used() {
+    let lua = setup_lua();
+    lua.load(
+        r#"
+local util = require("util")
+local calls = {}
+local id = util.memoize(function(key)
+    calls[key] = (calls[key] or 0) + 1
+    return key
+end, {maxSize = 2})
+
+id("a")
+id("b")
+id("a")      -- "b" is now the least recently used
+id("c")      -- evicts "b"
+id("a")
+id("b")      -- recomputed
+assert(calls.a == 1 and calls.b == 2 and calls.c == 1)
+"#,
+    )
+    .exec()
+    .unwrap();
+}
+
+#[test]
+fn test_util_memoize_reruns_after_ttl() {
+    let lua = setup_lua();
+    lua.load(
+        r#"
+local util = require("util")
+calls = 0
+cached = util.memoize(function()
+    calls = calls + 1
+    return calls
+end, {ttl = 0.05})
+assert(cached() == 1 and cached() == 1)
+"#,
+    )
+    .exec()
+    .unwrap();
+
+    std::thread::sleep(std::time::Duration::from_millis(80));
+    let value: i64 = lua.load("return cached()").eval().unwrap();
+    assert_eq!(value, 2);
+}
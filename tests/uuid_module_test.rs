@@ -0,0 +1,62 @@
+use hype_rs::lua::require::setup_require_fn;
+use hype_rs::modules::loader::ModuleLoader;
+use mlua::Lua;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+fn setup_lua() -> Lua {
+    let lua = Lua::new();
+    let loader = Arc::new(Mutex::new(ModuleLoader::new(PathBuf::from("."))));
+    setup_require_fn(&lua, loader).unwrap();
+    lua
+}
+
+#[test]
+fn test_uuid_generators() {
+    let lua = setup_lua();
+    lua.load(
+        r#"
+local uuid = require("uuid")
+
+local a, b = uuid.v5(uuid.NAMESPACE_URL, "https://example.com"), uuid.v5(uuid.NAMESPACE_URL, "https://example.com")
+assert(a == b, a .. " ~= " .. b)
+assert(uuid.version(a) == 5)
+assert(a ~= uuid.v5(uuid.NAMESPACE_DNS, "https://example.com"))
+
+local ids = {}
+for i = 1, 100 do
+    local id = uuid.v4()
+    assert(not ids[id], "duplicate v4 " .. id)
+    ids[id] = true
+end
+
+assert(uuid.version(uuid.v7()) == 7)
+assert(uuid["nil"]() == "00000000-0000-0000-0000-000000000000")
+assert(uuid.NIL == uuid["nil"]())
+"#,
+    )
+    .exec()
+    .unwrap();
+}
+
+#[test]
+fn test_uuid_parse_rejects_malformed_input() {
+    let lua = setup_lua();
+    lua.load(
+        r#"
+local uuid = require("uuid")
+
+assert(uuid.parse("67E5504410B1426F9247BB680E5FE0C8") == "67e55044-10b1-426f-9247-bb680e5fe0c8")
+for _, bad in ipairs({"", "not-a-uuid", "67e55044-10b1-426f-9247-bb680e5fe0c", "67e55044-10b1-426f-9247-bb680e5fe0c8x"}) do
+    local ok, err = pcall(uuid.parse, bad)
+    assert(not ok and err.code == "ERR_INVALID_UUID", bad)
+    assert(uuid.validate(bad) == false, bad)
+end
+
+local ok, err = pcall(uuid.v5, "example.com", "name")
+assert(not ok and err.code == "ERR_INVALID_NAMESPACE", tostring(err))
+"#,
+    )
+    .exec()
+    .unwrap();
+}
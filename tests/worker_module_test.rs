@@ -0,0 +1,99 @@
+use hype_rs::lua::require::setup_require_fn;
+use hype_rs::modules::loader::ModuleLoader;
+use mlua::Lua;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tempfile::TempDir;
+
+fn setup_lua() -> Lua {
+    let lua = Lua::new();
+    let loader = Arc::new(Mutex::new(ModuleLoader::new(PathBuf::from("."))));
+    setup_require_fn(&lua, loader).unwrap();
+    lua
+}
+
+fn write_script(dir: &TempDir, name: &str, source: &str) -> String {
+    let path = dir.path().join(name);
+    std::fs::write(&path, source).unwrap();
+    path.to_string_lossy().into_owned()
+}
+
+#[test]
+fn test_two_workers_compute_and_join() {
+    let dir = TempDir::new().unwrap();
+    let script = write_script(
+        &dir,
+        "sum.lua",
+        r#"
+local range = ...
+local total = 0
+for i = range.from, range.to do
+    total = total + i
+end
+return { from = range.from, total = total, json = require("json") ~= nil }
+"#,
+    );
+
+    let lua = setup_lua();
+    lua.globals().set("script", script).unwrap();
+    lua.load(
+        r#"
+local worker = require("worker")
+local low = worker.spawn(script, { from = 1, to = 1000 })
+local high = worker.spawn(script, { from = 1001, to = 2000 })
+
+local a = low:join()
+local b = high:join()
+assert(a.from == 1 and b.from == 1001)
+assert(a.total == 500500, tostring(a.total))
+assert(b.total == 1500500, tostring(b.total))
+assert(a.json and b.json, "workers can require builtins")
+assert(low:isDone() and high:isDone())
+assert(low:join().total == 500500, "join returns the same result again")
+"#,
+    )
+    .exec()
+    .unwrap();
+}
+
+#[test]
+fn test_worker_errors_and_timeouts() {
+    let dir = TempDir::new().unwrap();
+    let failing = write_script(&dir, "fail.lua", "error('worker exploded')");
+    let slow = write_script(
+        &dir,
+        "slow.lua",
+        "local t = os.clock() + 0.3 while os.clock() < t do end return workerData",
+    );
+
+    let lua = setup_lua();
+    lua.globals().set("failing", failing).unwrap();
+    lua.globals().set("slow", slow).unwrap();
+    lua.globals()
+        .set(
+            "missing",
+            dir.path()
+                .join("missing.lua")
+                .to_string_lossy()
+                .into_owned(),
+        )
+        .unwrap();
+    lua.load(
+        r#"
+local worker = require("worker")
+
+local ok, err = pcall(worker.spawn, missing)
+assert(not ok and err.code == "ENOENT", tostring(err))
+
+local ok, err = pcall(function() return worker.spawn(failing):join() end)
+assert(not ok and tostring(err):find("worker exploded", 1, true), tostring(err))
+
+local handle = worker.spawn(slow, "done")
+local ok, err = pcall(function() return handle:join(0.01) end)
+assert(not ok and tostring(err):find("did not finish", 1, true), tostring(err))
+assert(handle:join() == "done")
+"#,
+    )
+    .exec()
+    .unwrap();
+}
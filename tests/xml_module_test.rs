@@ -0,0 +1,117 @@
+use hype_rs::lua::require::setup_require_fn;
+use hype_rs::modules::loader::ModuleLoader;
+use mlua::Lua;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+fn setup_lua() -> Lua {
+    let lua = Lua::new();
+    let loader = Arc::new(Mutex::new(ModuleLoader::new(PathBuf::from("."))));
+    setup_require_fn(&lua, loader).unwrap();
+    lua
+}
+
+#[test]
+fn test_parse_attributes_and_nested_elements() {
+    let lua = setup_lua();
+    lua.load(
+        r#"
+local xml = require("xml")
+local doc = xml.parse([==[
+<?xml version="1.0" encoding="UTF-8"?>
+<soap:Envelope xmlns:soap="http://schemas.xmlsoap.org/soap/envelope/">
+  <soap:Body>
+    <order id="42" status="open">
+      <item sku="A-1" qty="2">Widget</item>
+      <note><![CDATA[Leave at <door>]]></note>
+      <gift/>
+    </order>
+  </soap:Body>
+</soap:Envelope>
+]==])
+
+assert(doc.tag == "soap:Envelope")
+assert(doc.attributes["xmlns:soap"] == "http://schemas.xmlsoap.org/soap/envelope/")
+local order = doc.children[1].children[1]
+assert(order.tag == "order" and order.attributes.id == "42" and order.attributes.status == "open")
+assert(#order.children == 3)
+assert(order.children[1].text == "Widget" and order.children[1].attributes.qty == "2")
+assert(order.children[2].text == "Leave at <door>")
+assert(order.children[3].tag == "gift" and #order.children[3].children == 0)
+assert(order.children[3].text == "")
+"#,
+    )
+    .exec()
+    .unwrap();
+}
+
+#[test]
+fn test_stringify_round_trips_to_equivalent_xml() {
+    let lua = setup_lua();
+    let xml: String = lua
+        .load(
+            r#"
+local xml = require("xml")
+local source = '<feed lang="en"><entry id="1"><title>A &amp; B</title><link href="/a?x=1&amp;y=2"/></entry></feed>'
+local tree = xml.parse(source)
+local out = xml.stringify(tree)
+
+local again = xml.parse(out)
+assert(again.tag == "feed" and again.attributes.lang == "en")
+assert(again.children[1].children[1].text == "A & B")
+assert(again.children[1].children[2].attributes.href == "/a?x=1&y=2")
+return out
+"#,
+        )
+        .eval()
+        .unwrap();
+    assert_eq!(
+        xml,
+        r#"<feed lang="en"><entry id="1"><title>A &amp; B</title><link href="/a?x=1&amp;y=2"/></entry></feed>"#
+    );
+}
+
+#[test]
+fn test_stringify_builds_from_tables() {
+    let lua = setup_lua();
+    let xml: String = lua
+        .load(
+            r#"
+local xml = require("xml")
+return xml.stringify({
+    tag = "user",
+    attributes = {name = "Ada", age = 36},
+    children = {{tag = "role", text = "admin"}, {tag = "active"}},
+})
+"#,
+        )
+        .eval()
+        .unwrap();
+    assert_eq!(
+        xml,
+        r#"<user age="36" name="Ada"><role>admin</role><active/></user>"#
+    );
+}
+
+#[test]
+fn test_malformed_xml_reports_position() {
+    let lua = setup_lua();
+    lua.load(
+        r#"
+local xml = require("xml")
+local ok, err = pcall(xml.parse, "<root>\n  <item></items>\n</root>")
+assert(not ok and err.code == "ERR_INVALID_XML" and err.kind == "xml", tostring(err))
+assert(err.message:find("line 2, column", 1, true), err.message)
+
+ok, err = pcall(xml.parse, "<root><open></root")
+assert(not ok and err.code == "ERR_INVALID_XML", tostring(err))
+
+ok, err = pcall(xml.stringify, {tag = "bad name"})
+assert(not ok and err.code == "ERR_INVALID_TREE", tostring(err))
+ok, err = pcall(xml.stringify, {tag = "a", children = {"text"}})
+assert(not ok and err.code == "ERR_INVALID_TREE", tostring(err))
+"#,
+    )
+    .exec()
+    .unwrap();
+}
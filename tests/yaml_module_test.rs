@@ -0,0 +1,73 @@
+use hype_rs::lua::require::setup_require_fn;
+use hype_rs::modules::loader::ModuleLoader;
+use mlua::Lua;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+fn setup_lua() -> Lua {
+    let lua = Lua::new();
+    let loader = Arc::new(Mutex::new(ModuleLoader::new(PathBuf::from("."))));
+    setup_require_fn(&lua, loader).unwrap();
+    lua
+}
+
+#[test]
+fn test_yaml_round_trip_nested_mapping() {
+    let lua = setup_lua();
+    lua.load(
+        r#"
+local yaml = require("yaml")
+local config = {
+    database = {host = "db.local", port = 5432, options = {ssl = true}},
+    features = {"auth", "billing"}
+}
+local parsed = yaml.parse(yaml.stringify(config))
+assert(parsed.database.host == "db.local")
+assert(parsed.database.port == 5432)
+assert(parsed.database.options.ssl == true)
+assert(parsed.features[1] == "auth" and parsed.features[2] == "billing")
+"#,
+    )
+    .exec()
+    .unwrap();
+}
+
+#[test]
+fn test_yaml_parse_list_of_maps() {
+    let lua = setup_lua();
+    lua.load(
+        r#"
+local yaml = require("yaml")
+local services = yaml.parse([[
+- name: api
+  port: 8080
+  description: |
+    Public API
+    server
+- name: worker
+  port: 9090
+]])
+assert(#services == 2)
+assert(services[1].name == "api" and services[1].port == 8080)
+assert(services[1].description == "Public API\nserver\n")
+assert(services[2].name == "worker")
+"#,
+    )
+    .exec()
+    .unwrap();
+}
+
+#[test]
+fn test_yaml_parse_invalid_document_errors() {
+    let lua = setup_lua();
+    lua.load(
+        r#"
+local yaml = require("yaml")
+local ok, err = pcall(yaml.parse, "key: [unclosed")
+assert(not ok)
+assert(string.find(tostring(err), "YAML parse error", 1, true))
+"#,
+    )
+    .exec()
+    .unwrap();
+}
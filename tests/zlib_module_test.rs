@@ -0,0 +1,51 @@
+use hype_rs::lua::require::setup_require_fn;
+use hype_rs::modules::loader::ModuleLoader;
+use mlua::Lua;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+fn setup_lua() -> Lua {
+    let lua = Lua::new();
+    let loader = Arc::new(Mutex::new(ModuleLoader::new(PathBuf::from("."))));
+    setup_require_fn(&lua, loader).unwrap();
+    lua
+}
+
+#[test]
+fn test_zlib_gzip_shrinks_repetitive_json() {
+    let lua = setup_lua();
+    lua.load(
+        r#"
+local zlib = require("zlib")
+local json = require("json")
+
+local records = {}
+for i = 1, 500 do records[i] = {id = i, status = "ok"} end
+local text = json.encode(records)
+
+local packed = zlib.gzip(text)
+assert(#packed < #text / 5, #packed .. " vs " .. #text)
+assert(zlib.gunzip(packed) == text)
+
+local deflated = zlib.deflate(text, {level = 1})
+assert(#deflated < #text and zlib.inflate(deflated) == text)
+"#,
+    )
+    .exec()
+    .unwrap();
+}
+
+#[test]
+fn test_zlib_gunzip_known_blob() {
+    let lua = setup_lua();
+    lua.load(
+        r#"
+local zlib = require("zlib")
+-- printf 'hello, hype!\n' | gzip -n
+local blob = "\x1f\x8b\x08\x00\x00\x00\x00\x00\x02\x03\xcb\x48\xcd\xc9\xc9\xd7\x51\xc8\xa8\x2c\x48\x55\xe4\x02\x00\x7b\xee\xd2\xc1\x0d\x00\x00\x00"
+assert(zlib.gunzip(blob) == "hello, hype!\n")
+"#,
+    )
+    .exec()
+    .unwrap();
+}